@@ -0,0 +1,198 @@
+//! Default arguments of `cargo fuzzcheck`, read from the `fuzzcheck.toml` file of the current directory.
+//!
+//! The file contains a `[default]` table, whose arguments apply to every fuzz test, and a `[target."<name>"]`
+//! table for each fuzz test with specific arguments, which take precedence over the default ones. The keys
+//! are the names of the command line options, without the leading `--`:
+//! ```toml
+//! [default]
+//! profile = "fuzz"
+//! address-sanitizer = true
+//! stop-after-duration = 3600
+//! coverage-include = ["src/parser/**", "src/lexer/**"]
+//!
+//! [target."tests::fuzz_parser"]
+//! in-corpus = "corpora/parser"
+//! stop-after-first-failure = true
+//! ```
+//! A boolean gives the flag if it is `true` and nothing if it is `false`, and an array repeats the option for
+//! each of its elements. The options given on the command line replace those of the file, and a flag set to
+//! `true` in the file is turned off by giving `--no-<flag>` on the command line, such as `--no-address-sanitizer`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use fuzzcheck_common::arg::ArgumentsError;
+use serde::Deserialize;
+use toml::{Table, Value};
+
+/// The name of the configuration file, which is read from the current directory
+pub const CONFIG_FILE: &str = "fuzzcheck.toml";
+
+/// The tables of the configuration file, whose keys are in the order in which they are written
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    #[serde(default)]
+    default: Table,
+    /// The table of each fuzz test with specific arguments, by name
+    #[serde(default)]
+    target: HashMap<String, Table>,
+}
+
+/// The arguments given by the configuration file to the fuzz test of the given name, grouped by option.
+///
+/// Each element is the name of an option and the command line arguments it corresponds to.
+/// Returns an empty list if the file does not exist.
+pub fn config_arguments(path: &Path, target_name: &str) -> Result<Vec<(String, Vec<String>)>, ArgumentsError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => {
+            return Err(ArgumentsError::Validation(format!(
+                "The configuration file {} could not be read: {}",
+                path.display(),
+                e
+            )))
+        }
+    };
+    let config: Config =
+        toml::from_str(&content).map_err(|e| ArgumentsError::Validation(format!("{}: {}", path.display(), e)))?;
+
+    let mut arguments: Vec<(String, Vec<String>)> = vec![];
+    let target_table = config.target.get(target_name);
+    for (key, value) in config.default.iter().chain(target_table.into_iter().flatten()) {
+        let args = option_arguments(key, value)
+            .ok_or_else(|| ArgumentsError::Validation(format!("{}: invalid value for {}", path.display(), key)))?;
+        if let Some(entry) = arguments.iter_mut().find(|(k, _)| k == key) {
+            entry.1 = args;
+        } else {
+            arguments.push((key.clone(), args));
+        }
+    }
+    Ok(arguments)
+}
+
+/// The command line arguments corresponding to the value of an option in the configuration file
+fn option_arguments(key: &str, value: &Value) -> Option<Vec<String>> {
+    let option = format!("--{}", key);
+    match value {
+        Value::Boolean(true) => Some(vec![option]),
+        Value::Boolean(false) => Some(vec![]),
+        Value::Array(elements) => elements
+            .iter()
+            .map(|element| Some([option.clone(), argument(element)?]))
+            .collect::<Option<Vec<_>>>()
+            .map(|args| args.concat()),
+        value => Some(vec![option, argument(value)?]),
+    }
+}
+
+/// A string or a number, which is given to the option as is
+fn argument(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.clone()),
+        Value::Integer(integer) => Some(integer.to_string()),
+        Value::Float(float) => Some(float.to_string()),
+        _ => None,
+    }
+}
+
+/// Remove the `--no-<flag>` arguments, which turn off a flag set to `true` in the configuration file, from the
+/// command line arguments, and return the names of these flags.
+///
+/// The options whose name starts with `no-`, such as `--no-stats`, are kept.
+pub fn take_negated_flags(parser: &getopts::Options, args: &mut Vec<&str>) -> Vec<String> {
+    let mut negated_flags = vec![];
+    args.retain(|arg| {
+        let Some(flag) = arg.strip_prefix("--no-") else {
+            return true;
+        };
+        let is_negated_flag =
+            parser.parse([arg]).is_err() && parser.parse([format!("--{}", flag)]).is_ok_and(|m| m.free.is_empty());
+        if is_negated_flag {
+            negated_flags.push(flag.to_owned());
+        }
+        !is_negated_flag
+    });
+    negated_flags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_arguments() {
+        let folder = std::env::temp_dir().join(format!("cargo_fuzzcheck_config_{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let path = folder.join(CONFIG_FILE);
+        std::fs::write(
+            &path,
+            r#"
+[default]
+profile = "fuzz"
+address-sanitizer = true
+coverage-include = ["src/**", "tests/**"]
+
+[target."tests::fuzz_parser"]
+profile = "release"
+address-sanitizer = false
+stop-after-duration = 60
+
+[target."tests::fuzz_lexer"]
+in-corpus = "corpora/lexer"
+"#,
+        )
+        .unwrap();
+
+        let arguments = config_arguments(&path, "tests::fuzz_parser").unwrap();
+        let expected = [
+            ("profile", vec!["--profile", "release"]),
+            ("address-sanitizer", vec![]),
+            (
+                "coverage-include",
+                vec!["--coverage-include", "src/**", "--coverage-include", "tests/**"],
+            ),
+            ("stop-after-duration", vec!["--stop-after-duration", "60"]),
+        ];
+        assert_eq!(
+            arguments,
+            expected.map(|(key, args)| (key.to_owned(), args.into_iter().map(str::to_owned).collect()))
+        );
+        let arguments = config_arguments(&path, "tests::fuzz_other").unwrap();
+        assert_eq!(
+            arguments.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(),
+            ["profile", "address-sanitizer", "coverage-include"]
+        );
+        assert_eq!(arguments[1].1, ["--address-sanitizer"]);
+
+        std::fs::write(&path, "[default]\nprofile = release").unwrap();
+        assert!(config_arguments(&path, "tests::fuzz_parser").is_err());
+        std::fs::write(&path, "[targets.\"tests::fuzz_parser\"]\nprofile = \"fuzz\"").unwrap();
+        assert!(config_arguments(&path, "tests::fuzz_parser").is_err());
+        std::fs::write(&path, "[default]\ncoverage-include = [true]").unwrap();
+        assert!(config_arguments(&path, "tests::fuzz_parser").is_err());
+
+        std::fs::remove_dir_all(&folder).unwrap();
+        assert!(config_arguments(&path, "tests::fuzz_parser").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_take_negated_flags() {
+        let mut parser = fuzzcheck_common::arg::options_parser();
+        parser.optflag("", "address-sanitizer", "");
+        parser.optopt("", "profile", "", "");
+        let mut args = vec![
+            "tests::fuzz",
+            "--no-address-sanitizer",
+            "--no-stats",
+            "--no-profile",
+            "--no-unknown",
+            "--no-stop-after-first-failure",
+        ];
+        let negated_flags = take_negated_flags(&parser, &mut args);
+        assert_eq!(negated_flags, ["address-sanitizer", "stop-after-first-failure"]);
+        // the options that are not negated flags are left to the parser
+        assert_eq!(args, ["tests::fuzz", "--no-stats", "--no-profile", "--no-unknown"]);
+    }
+}
@@ -2,14 +2,34 @@
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::format_push_string)]
 
+mod config;
+mod plot;
+
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::process;
 use std::process::{Command, Stdio};
 
 use fuzzcheck_common::arg::*;
+use fuzzcheck_common::{TerminationStatus, TERMINATION_STATUS_FILE_ENV};
+use proc_macro2::{Delimiter, TokenTree};
+
+pub use crate::config::{config_arguments, take_negated_flags, CONFIG_FILE};
+pub use crate::plot::{plot_stats, PLOT_SUBCOMMAND};
 const TARGET: &str = env!("TARGET");
 const BUILD_FOLDER: &str = "target/fuzzcheck";
+const TERMINATION_STATUS_FILE: &str = "termination_status";
+
+/// The argument given to `cargo fuzzcheck`, instead of the name of a fuzz test, to list the fuzz tests
+pub const LIST_SUBCOMMAND: &str = "list";
+/// The argument given to `cargo fuzzcheck`, followed by the name of a fuzz test, to measure its speed
+/// with the `bench` command
+pub const BENCH_SUBCOMMAND: &str = "bench";
+/// The subcommand comparing the code coverage of two test cases, followed by the fuzz test and the two input files
+pub const COVDIFF_SUBCOMMAND: &str = "covdiff";
+/// The subcommand merging several corpora into one, followed by the fuzz test and the folders of the corpora
+pub const MERGE_SUBCOMMAND: &str = "merge";
 
 pub enum CompiledTarget {
     Lib,
@@ -26,37 +46,197 @@ impl CompiledTarget {
     }
 }
 
-pub fn launch_executable(
-    target_name: &str,
-    args: &Arguments,
+impl CompiledTarget {
+    /// Whether the `target` object of a cargo `compiler-artifact` message describes this target
+    fn matches_artifact_target(&self, target: &serde_json::Value) -> bool {
+        let kinds = target["kind"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let has_kind = |kind: &str| kinds.iter().any(|k| k == kind);
+        match self {
+            CompiledTarget::Lib => {
+                !kinds.is_empty()
+                    && !["bin", "test", "example", "bench", "custom-build"]
+                        .into_iter()
+                        .any(has_kind)
+            }
+            CompiledTarget::Bin(name) => has_kind("bin") && target["name"] == name.as_str(),
+            CompiledTarget::Test(name) => has_kind("test") && target["name"] == name.as_str(),
+        }
+    }
+}
+
+/// The instrumentation used to record the code coverage of the fuzz test
+#[derive(Clone, Copy)]
+pub enum CoverageBackend {
+    /// The LLVM coverage instrumentation, given by `-C instrument-coverage` (default)
+    Llvm,
+    /// The 8-bit counters of SanitizerCoverage, which do not rely on the format of the LLVM
+    /// coverage map and can therefore be used when it cannot be read
+    SanitizerCoverage,
+}
+impl CoverageBackend {
+    /// Parse the name of a backend given to the `--coverage-backend` option
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "llvm" => Some(CoverageBackend::Llvm),
+            "sancov" => Some(CoverageBackend::SanitizerCoverage),
+            _ => None,
+        }
+    }
+    fn rustflags(self) -> &'static str {
+        match self {
+            CoverageBackend::Llvm => " -C instrument-coverage",
+            CoverageBackend::SanitizerCoverage => {
+                // the table of PCs and the line tables give the function and file of each counter, and the
+                // instrumented functions record the depth of the stack for the StackDepthSensor
+                " -C passes=sancov-module -C llvm-args=-sanitizer-coverage-level=3 -C llvm-args=-sanitizer-coverage-inline-8bit-counters \
+                 -C llvm-args=-sanitizer-coverage-pc-table -C llvm-args=-sanitizer-coverage-stack-depth \
+                 -C debuginfo=line-tables-only -C strip=none"
+            }
+        }
+    }
+}
+
+/// A test executable compiled by `cargo test`
+pub struct TestExecutable {
+    /// The location of the executable
+    pub path: PathBuf,
+    /// The folder of the package containing the tests, which is the working directory of the tests
+    /// when they are run by `cargo test`
+    pub package_folder: PathBuf,
+    /// The environment variables given to the fuzz test, in addition to those of `cargo fuzzcheck`
+    pub envs: Vec<(String, String)>,
+    /// The working directory of the fuzz test, relative to the package folder, if it is not the package
+    /// folder itself
+    pub working_dir: Option<PathBuf>,
+}
+
+impl TestExecutable {
+    /// The folder in which the fuzz test is launched
+    fn current_dir(&self) -> PathBuf {
+        match &self.working_dir {
+            Some(working_dir) => self.package_folder.join(working_dir),
+            None => self.package_folder.clone(),
+        }
+    }
+
+    /// Make the relative paths of the arguments relative to the package folder instead of the working directory,
+    /// such that they designate the same files and folders whichever working directory is chosen
+    fn resolve_paths(&self, args: &Arguments) -> Arguments {
+        let mut args = args.clone();
+        let resolve = |path: &mut PathBuf| *path = self.package_folder.join(&path);
+        for folder in [
+            &mut args.corpus_in,
+            &mut args.corpus_out,
+            &mut args.incoming_corpus,
+            &mut args.artifacts_folder,
+            &mut args.stats_folder,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            resolve(folder);
+        }
+        if let FuzzerCommand::Read { input_file } | FuzzerCommand::MinifyInput { input_file } = &mut args.command {
+            resolve(input_file);
+        }
+        if let FuzzerCommand::CoverageDiff { input_files } = &mut args.command {
+            for input_file in input_files.iter_mut() {
+                resolve(input_file);
+            }
+        }
+        if let FuzzerCommand::Merge { corpora } = &mut args.command {
+            for corpus in corpora.iter_mut() {
+                resolve(corpus);
+            }
+        }
+        args
+    }
+}
+
+/// Compile the tests of the given target with the fuzzing configuration and return the test executable.
+///
+/// The location of the executable is given by the `compiler-artifact` messages that cargo prints
+/// when `--message-format=json` is used.
+pub fn compile_test_executable(
     compiled_target: &CompiledTarget,
     cargo_args: &[String],
     address_sanitizer: bool,
     profile: &str,
-    instrument_coverage: bool,
-    stdio: impl Fn() -> Stdio,
-) -> std::io::Result<process::Child> {
-    let args = string_from_args(args);
+    coverage_backend: Option<CoverageBackend>,
+) -> std::io::Result<TestExecutable> {
     let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_else(|_| "".to_owned());
-    if instrument_coverage {
-        rustflags.push_str(" -C instrument-coverage");
+    if let Some(coverage_backend) = coverage_backend {
+        rustflags.push_str(coverage_backend.rustflags());
     }
     rustflags.push_str(" --cfg fuzzing");
 
     if address_sanitizer {
         rustflags.push_str(" -Zsanitizer=address");
     }
-    let child = Command::new("cargo")
-        .env("FUZZCHECK_ARGS", args)
+    let output = Command::new("cargo")
         .env("RUSTFLAGS", &rustflags)
         .arg("test")
+        .arg("--no-run")
+        .arg("--message-format=json-render-diagnostics")
         .args(compiled_target.to_args())
         .args(cargo_args)
         .args(["--target", TARGET])
         .arg("--profile")
         .arg(profile)
         .args(["--target-dir", BUILD_FOLDER])
-        .arg("--")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other("the fuzz test could not be compiled"));
+    }
+
+    let mut executables = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact")
+        .filter(|message| compiled_target.matches_artifact_target(&message["target"]))
+        .filter_map(|message| {
+            let path = PathBuf::from(message["executable"].as_str()?);
+            let package_folder = Path::new(message["manifest_path"].as_str()?).parent()?.to_path_buf();
+            Some(TestExecutable {
+                path,
+                package_folder,
+                envs: vec![],
+                working_dir: None,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    match executables.len() {
+        0 => Err(std::io::Error::other("cargo did not compile any test executable for the target")),
+        1 => Ok(executables.remove(0)),
+        _ => Err(std::io::Error::other(
+            "cargo compiled several test executables for the target, use --cargo-args \"--package <NAME>\" to select one",
+        )),
+    }
+}
+
+/// Launch the fuzz test of the given name, in a test executable returned by [`compile_test_executable`]
+pub fn launch_executable(
+    executable: &TestExecutable,
+    target_name: &str,
+    args: &Arguments,
+    stdio: impl Fn() -> Stdio,
+) -> std::io::Result<process::Child> {
+    let args = if executable.working_dir.is_some() {
+        string_from_args(&executable.resolve_paths(args))
+    } else {
+        string_from_args(args)
+    };
+    let termination_status_file = termination_status_file()?;
+    let _ = std::fs::remove_file(&termination_status_file);
+    let child = Command::new(&executable.path)
+        .current_dir(executable.current_dir())
+        .envs(executable.envs.iter().map(|(key, value)| (key, value)))
+        .env("CARGO_MANIFEST_DIR", &executable.package_folder)
+        .env("FUZZCHECK_ARGS", args)
+        .env(TERMINATION_STATUS_FILE_ENV, &termination_status_file)
         .arg("--nocapture")
         .arg("--exact")
         .arg(target_name)
@@ -68,14 +248,170 @@ pub fn launch_executable(
     Ok(child)
 }
 
+/// The names of the fuzz tests of the test executable, that is, the `#[test]` functions which call one of the
+/// [`FUZZ_TEST_ENTRY_POINTS`], and the functions annotated with `#[fuzz_target]`.
+///
+/// The tests are listed by the test executable, and only those whose function contains a call to an entry point,
+/// or is a fuzz target, in the `src` or `tests` folders of the package are kept.
+pub fn list_fuzz_targets(executable: &TestExecutable) -> std::io::Result<Vec<String>> {
+    let output = Command::new(&executable.path)
+        .current_dir(&executable.package_folder)
+        .arg("--list")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other("the tests could not be listed"));
+    }
+    let mut fuzz_test_functions = HashSet::new();
+    for folder in ["src", "tests"] {
+        collect_fuzz_test_functions(&executable.package_folder.join(folder), &mut fuzz_test_functions)?;
+    }
+    let tests = String::from_utf8_lossy(&output.stdout);
+    let fuzz_targets = tests
+        .lines()
+        .filter_map(|line| line.strip_suffix(": test"))
+        .filter(|test| {
+            let function_name = test.rsplit("::").next().unwrap_or(test);
+            fuzz_test_functions.contains(function_name)
+        })
+        .map(|test| test.to_owned())
+        .collect();
+    Ok(fuzz_targets)
+}
+
+/// The functions of fuzzcheck that start a fuzz test
+const FUZZ_TEST_ENTRY_POINTS: [&str; 3] = ["fuzz_test", "fuzz_differential", "fuzz_test_async"];
+
+/// Insert the names of the functions which call one of the [`FUZZ_TEST_ENTRY_POINTS`] or are fuzz targets, found in the Rust files of the folder
+/// and its subfolders, into `names`
+fn collect_fuzz_test_functions(folder: &Path, names: &mut HashSet<String>) -> std::io::Result<()> {
+    if !folder.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_fuzz_test_functions(&path, names)?;
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            let source = std::fs::read_to_string(&path)?;
+            names.extend(functions_calling_fuzz_test(&source));
+        }
+    }
+    Ok(())
+}
+
+/// The names of the functions of the source file whose body contains one of the [`FUZZ_TEST_ENTRY_POINTS`], or
+/// which are annotated with `#[fuzz_target]`, including the functions
+/// nested inside modules, `impl` blocks, or other functions.
+///
+/// The file is only split into tokens, so that comments and literals are ignored, and the function is found
+/// even if it is generated by a macro whose arguments are written as Rust code. Returns an empty list if the
+/// file cannot be tokenized.
+fn functions_calling_fuzz_test(source: &str) -> Vec<String> {
+    let Ok(tokens) = source.parse::<proc_macro2::TokenStream>() else {
+        return vec![];
+    };
+    let mut names = vec![];
+    collect_functions_calling_fuzz_test(tokens, &mut names);
+    names
+}
+
+fn collect_functions_calling_fuzz_test(tokens: proc_macro2::TokenStream, names: &mut Vec<String>) {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    // whether the item being read is annotated with `#[fuzz_target]`
+    let mut is_fuzz_target = false;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if ident == "fn" => {
+                let Some(TokenTree::Ident(name)) = tokens.get(i + 1) else {
+                    continue;
+                };
+                if std::mem::take(&mut is_fuzz_target) {
+                    names.push(name.to_string());
+                    continue;
+                }
+                // the body is the first block after the signature, unless the function has no body
+                let body = tokens[i + 2..].iter().find_map(|token| match token {
+                    TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => Some(Some(group)),
+                    TokenTree::Punct(punct) if punct.as_char() == ';' => Some(None),
+                    _ => None,
+                });
+                if let Some(Some(body)) = body {
+                    if contains_fuzz_test(body.stream()) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Bracket && i > 0 => {
+                if matches!(&tokens[i - 1], TokenTree::Punct(punct) if punct.as_char() == '#') {
+                    is_fuzz_target |= is_fuzz_target_attribute(group.stream());
+                } else {
+                    collect_functions_calling_fuzz_test(group.stream(), names);
+                }
+            }
+            TokenTree::Group(group) => {
+                is_fuzz_target &= group.delimiter() == Delimiter::Parenthesis;
+                collect_functions_calling_fuzz_test(group.stream(), names)
+            }
+            TokenTree::Punct(punct) if punct.as_char() == ';' => is_fuzz_target = false,
+            _ => {}
+        }
+    }
+}
+
+/// Whether the content of an attribute is `fuzz_target`, or a path ending with `fuzz_target`
+fn is_fuzz_target_attribute(tokens: proc_macro2::TokenStream) -> bool {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    matches!(tokens.last(), Some(TokenTree::Ident(ident)) if ident == "fuzz_target")
+        && tokens.iter().all(|token| match token {
+            TokenTree::Ident(_) => true,
+            TokenTree::Punct(punct) => punct.as_char() == ':',
+            _ => false,
+        })
+}
+
+/// Whether the tokens contain the identifier of one of the [`FUZZ_TEST_ENTRY_POINTS`]
+fn contains_fuzz_test(tokens: proc_macro2::TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => FUZZ_TEST_ENTRY_POINTS.iter().any(|entry_point| ident == entry_point),
+        TokenTree::Group(group) => contains_fuzz_test(group.stream()),
+        _ => false,
+    })
+}
+
+/// The file where the fuzzer writes its termination status before exiting
+///
+/// Its name contains the id of the `cargo fuzzcheck` process, such that several `cargo fuzzcheck` commands
+/// launched in the same directory do not read each other's termination status.
+fn termination_status_file() -> std::io::Result<PathBuf> {
+    Ok(std::env::current_dir()?
+        .join(BUILD_FOLDER)
+        .join(format!("{}_{}", TERMINATION_STATUS_FILE, process::id())))
+}
+
+/// The exit code of `cargo fuzzcheck` after the fuzz test launched by [`launch_executable`] exited
+/// with the given status, according to the exit code policy in `args`.
+pub fn exit_code(args: &Arguments, status: process::ExitStatus) -> i32 {
+    let termination_status = termination_status_file().ok().and_then(|file| {
+        let termination_status = std::fs::read_to_string(&file)
+            .ok()
+            .and_then(|s| s.trim().parse::<i32>().ok());
+        let _ = std::fs::remove_file(&file);
+        termination_status
+    });
+    match args.exit_code_policy {
+        ExitCodePolicy::Return => status.code().unwrap_or(TerminationStatus::Unknown as i32),
+        // If the fuzzer did not write its termination status, then it was never launched.
+        // Either the fuzz test could not be compiled, or no test has the given name.
+        ExitCodePolicy::Exit => termination_status.unwrap_or(TerminationStatus::ConfigurationError as i32),
+    }
+}
+
 pub fn input_minify_command(
+    executable: &TestExecutable,
     target_name: &str,
     args: &Arguments,
-    compiled_target: &CompiledTarget,
-    cargo_args: &[String],
-    address_sanitizer: bool,
-    profile: &str,
-    instrument_coverage: bool,
     stdio: &impl Fn() -> Stdio,
 ) -> std::io::Result<()> {
     let mut config = args.clone();
@@ -122,16 +458,7 @@ pub fn input_minify_command(
 
     println!("launch with config: {:?}", string_from_args(&config));
 
-    let child = launch_executable(
-        target_name,
-        &config,
-        compiled_target,
-        cargo_args,
-        address_sanitizer,
-        profile,
-        instrument_coverage,
-        stdio,
-    )?;
+    let child = launch_executable(executable, target_name, &config, stdio)?;
     let o = child.wait_with_output()?;
 
     assert!(
@@ -145,16 +472,7 @@ pub fn input_minify_command(
             input_file: simplest.clone(),
         };
         println!("launch with config: {:?}", string_from_args(&config));
-        let mut c = launch_executable(
-            target_name,
-            &config,
-            compiled_target,
-            cargo_args,
-            address_sanitizer,
-            profile,
-            instrument_coverage,
-            Stdio::inherit,
-        )?;
+        let mut c = launch_executable(executable, target_name, &config, Stdio::inherit)?;
         c.wait()?;
     }
 }
@@ -181,6 +499,40 @@ pub fn string_from_args(args: &Arguments) -> String {
             s.push(' ');
             Some(input_file.clone())
         }
+        FuzzerCommand::Analyze => {
+            s.push_str("--command ");
+            s.push_str(COMMAND_ANALYZE);
+            s.push(' ');
+            None
+        }
+        FuzzerCommand::Repool => {
+            s.push_str("--command ");
+            s.push_str(COMMAND_REPOOL);
+            s.push(' ');
+            None
+        }
+        FuzzerCommand::Bench => {
+            s.push_str("--command ");
+            s.push_str(COMMAND_BENCH);
+            s.push(' ');
+            None
+        }
+        FuzzerCommand::CoverageDiff { input_files } => {
+            s.push_str("--command ");
+            s.push_str(COMMAND_COVDIFF);
+            s.push(' ');
+            s.push_str(&format!("--{} {} ", INPUT_FILE_FLAG, input_files[0].display()));
+            Some(input_files[1].clone())
+        }
+        FuzzerCommand::Merge { corpora } => {
+            s.push_str("--command ");
+            s.push_str(COMMAND_MERGE);
+            s.push(' ');
+            for corpus in corpora {
+                s.push_str(&format!("--{} {} ", MERGE_CORPUS_FLAG, corpus.display()));
+            }
+            None
+        }
     };
     if let Some(input_file) = input_file {
         s.push_str(&format!("--{} {} ", INPUT_FILE_FLAG, input_file.display()));
@@ -220,14 +572,169 @@ pub fn string_from_args(args: &Arguments) -> String {
     s.push_str(&stats_args);
     s.push(' ');
 
-    s.push_str(&format!("--{} {} ", MAX_INPUT_CPLX_FLAG, args.max_input_cplx as usize));
+    if let Some(incoming_corpus) = &args.incoming_corpus {
+        s.push_str(&format!("--{} {} ", INCOMING_CORPUS_FLAG, incoming_corpus.display()));
+    }
+    if let Some(stats_socket) = &args.stats_socket {
+        s.push_str(&format!("--{} {} ", STATS_SOCKET_FLAG, stats_socket));
+    }
+    for pattern in args.coverage_include.iter() {
+        s.push_str(&format!("--{} {} ", COVERAGE_INCLUDE_FLAG, pattern));
+    }
+    for pattern in args.coverage_exclude.iter() {
+        s.push_str(&format!("--{} {} ", COVERAGE_EXCLUDE_FLAG, pattern));
+    }
+    if args.print_coverage_functions {
+        s.push_str(&format!("--{} ", PRINT_COVERAGE_FUNCTIONS_FLAG));
+    }
+    for tag in args.corpus_with_tags.iter() {
+        s.push_str(&format!("--{} {} ", CORPUS_WITH_TAG_FLAG, tag));
+    }
+    for tag in args.corpus_without_tags.iter() {
+        s.push_str(&format!("--{} {} ", CORPUS_WITHOUT_TAG_FLAG, tag));
+    }
+
+    s.push_str(&format!("--{} {} ", MAX_MUTATE_CPLX_FLAG, args.max_input_cplx as usize));
+    s.push_str(&format!(
+        "--{} {} ",
+        MAX_ARBITRARY_CPLX_FLAG, args.max_arbitrary_cplx as usize
+    ));
     s.push_str(&format!("--{} {} ", MAX_DURATION_FLAG, args.maximum_duration.as_secs()));
     s.push_str(&format!("--{} {} ", MAX_ITERATIONS_FLAG, args.maximum_iterations));
     if args.stop_after_first_failure {
         s.push_str(&format!("--{} ", STOP_AFTER_FIRST_FAILURE_FLAG));
     }
+    if args.preserve_failure {
+        s.push_str(&format!("--{} ", PRESERVE_FAILURE_FLAG));
+    }
+    if let Some(budget) = args.stop_after_no_progress {
+        s.push_str(&format!("--{} {} ", STOP_AFTER_NO_PROGRESS_FLAG, budget));
+    }
     if args.detect_infinite_loop {
         s.push_str(&format!("--{} ", DETECT_INFINITE_LOOP_FLAG));
     }
+    if args.resume {
+        s.push_str(&format!("--{} ", RESUME_FLAG));
+    }
+    if args.deduplicate_inputs {
+        s.push_str(&format!("--{} ", DEDUP_INPUTS_FLAG));
+    }
+    if !args.adaptive_mutations {
+        s.push_str(&format!("--{} ", NO_ADAPTIVE_MUTATIONS_FLAG));
+    }
+    if args.fork {
+        s.push_str(&format!("--{} ", FORK_FLAG));
+    }
+    if args.track_provenance {
+        s.push_str(&format!("--{} ", TRACK_PROVENANCE_FLAG));
+    }
+    if args.record_observations {
+        s.push_str(&format!("--{} ", RECORD_OBSERVATIONS_FLAG));
+    }
+    if args.check_stability {
+        s.push_str(&format!("--{} ", CHECK_STABILITY_FLAG));
+    }
+    if args.observation_sampling > 1 {
+        s.push_str(&format!(
+            "--{} {} ",
+            OBSERVATION_SAMPLING_FLAG, args.observation_sampling
+        ));
+    }
+    let exit_code_policy = match args.exit_code_policy {
+        ExitCodePolicy::Return => EXIT_CODE_POLICY_RETURN,
+        ExitCodePolicy::Exit => EXIT_CODE_POLICY_EXIT,
+    };
+    s.push_str(&format!("--{} {} ", EXIT_CODE_POLICY_FLAG, exit_code_policy));
+    let corpus_naming = match args.corpus_naming {
+        CorpusNamingPolicy::ContentHash => CORPUS_NAMING_HASH,
+        CorpusNamingPolicy::ComplexityAndHash => CORPUS_NAMING_CPLX_HASH,
+    };
+    s.push_str(&format!("--{} {} ", CORPUS_NAMING_FLAG, corpus_naming));
     s
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_functions_calling_fuzz_test() {
+        let source = r##"
+            // fn commented() { fuzz_test(test) }
+            /* fn block_commented() {
+                fuzz_test(test)
+            } */
+            fn not_fuzz_test() {
+                let _ = "fn in_string() { fuzz_test(test) }";
+                let _ = r#"}" fn in_raw_string() { fuzz_test(test) }"#;
+                let _ = ['{', '"'];
+            }
+            #[cfg(test)]
+            mod tests {
+                trait Declared {
+                    fn declared(&self);
+                }
+                impl Declared for () {
+                    fn declared(&self) {
+                        let _ = "}";
+                    }
+                }
+                fn outer<'a>(_: &'a str) {
+                    fn inner() {
+                        fuzzcheck::fuzz_test(test).default_options().launch();
+                    }
+                }
+                #[test]
+                fn fuzz_test_1() -> Result<(), ()> {
+                    let result = fuzz_test(|x: &[u8]| { let _ = x; }).default_options().launch();
+                    Ok(())
+                }
+                fn calls_other_fuzz_test() {
+                    fuzz_test_2();
+                }
+                #[test]
+                fn differential() {
+                    fuzzcheck::fuzz_differential(f1, f2, |x, y| x == y).default_options().launch();
+                }
+                #[test]
+                fn asynchronous() {
+                    fuzz_test_async(|x: &u8| async move { let _ = x; }).default_options().launch();
+                }
+                #[fuzzcheck::fuzz_target]
+                #[allow(unused)]
+                pub(crate) fn target(x: &[u8]) {}
+                #[fuzz_target]
+                fn other_target(x: &[u8]) {}
+                #[fuzz_target]
+                struct NotAFunction;
+                fn not_a_target() {}
+                #[not_fuzz_target]
+                fn not_a_target_either() {}
+            }
+        "##;
+        assert_eq!(
+            functions_calling_fuzz_test(source),
+            [
+                "outer",
+                "inner",
+                "fuzz_test_1",
+                "differential",
+                "asynchronous",
+                "target",
+                "other_target"
+            ]
+        );
+        // the file cannot be tokenized
+        assert!(functions_calling_fuzz_test("fn unclosed() { fuzz_test(test)").is_empty());
+    }
+
+    #[test]
+    fn test_basic_crate_fuzz_test_functions() {
+        let basic_crate = Path::new(env!("CARGO_MANIFEST_DIR")).join("../usage_tests/basic_crate");
+        let mut names = HashSet::new();
+        for folder in ["src", "tests"] {
+            collect_fuzz_test_functions(&basic_crate.join(folder), &mut names).unwrap();
+        }
+        assert_eq!(names, HashSet::from(["fuzz".to_owned()]));
+    }
+}
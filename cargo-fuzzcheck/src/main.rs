@@ -1,16 +1,31 @@
 #![allow(clippy::bool_comparison)]
 extern crate cargo_fuzzcheck;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::string::String;
 
 use cargo_fuzzcheck::*;
 use fuzzcheck_common::arg::*;
+use fuzzcheck_common::TerminationStatus;
 
 const CARGO_ARGS_FLAG: &str = "cargo-args";
+const COVERAGE_BACKEND_FLAG: &str = "coverage-backend";
+const ENV_FLAG: &str = "env";
+const WORKING_DIR_FLAG: &str = "working-dir";
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() {
+    match run() {
+        Ok(exit_code) => process::exit(exit_code),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            process::exit(TerminationStatus::ConfigurationError as i32);
+        }
+    }
+}
+
+/// Run the command and return the exit code of `cargo fuzzcheck`
+fn run() -> Result<i32, Box<dyn Error>> {
     let mut parser = options_parser();
 
     parser.optflag("", "lib", "Test only this package's library unit tests (default)");
@@ -24,6 +39,25 @@ fn main() -> Result<(), Box<dyn Error>> {
         "",
     );
     parser.optflag("", "no-instrument-coverage", "Turn off coverage instrumentation");
+    parser.optopt(
+        "",
+        COVERAGE_BACKEND_FLAG,
+        "The instrumentation used to record code coverage: llvm (default) or sancov, which can be used when the LLVM coverage map cannot be read",
+        "<llvm|sancov>",
+    );
+
+    parser.optmulti(
+        "",
+        ENV_FLAG,
+        "An environment variable given to the fuzz test, which can be repeated",
+        "<KEY=VALUE>",
+    );
+    parser.optopt(
+        "",
+        WORKING_DIR_FLAG,
+        "The working directory of the fuzz test, relative to the folder of its package (default: the package folder)",
+        "<PATH>",
+    );
 
     parser.opt(
         "",
@@ -46,26 +80,141 @@ fn main() -> Result<(), Box<dyn Error>> {
         return Err(Box::new(ArgumentsError::NoArgumentsGiven(help(&parser))));
     }
 
-    let string_args = env_args[start_idx..].iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    let mut string_args = env_args[start_idx..].iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    // the flags set to true in the configuration file that are turned off on the command line
+    let negated_flags = take_negated_flags(&parser, &mut string_args);
+
+    let mut matches = parser.parse(string_args.clone()).map_err(ArgumentsError::Parsing)?;
+    if let Some(flag) = negated_flags.iter().find(|flag| matches.opt_present(flag)) {
+        return Err(Box::new(ArgumentsError::Validation(format!(
+            "Only one of --{flag} and --no-{flag} must be given."
+        ))));
+    }
+
+    // the `plot`, `bench`, `covdiff`, and `merge` subcommands are followed by the name of the fuzz test
+    let subcommand = matches
+        .free
+        .first()
+        .filter(|first| {
+            [PLOT_SUBCOMMAND, BENCH_SUBCOMMAND, COVDIFF_SUBCOMMAND, MERGE_SUBCOMMAND].contains(&first.as_str())
+        })
+        .cloned();
 
-    let matches = parser.parse(string_args.clone()).map_err(ArgumentsError::Parsing)?;
+    // the configuration file gives the default arguments of the fuzz test, which are replaced by those
+    // given on the command line
+    let config_target_name = if subcommand.is_some() {
+        matches.free.get(1)
+    } else {
+        matches.free.first()
+    };
+    if let Some(target_name) = config_target_name {
+        let config_args = config_arguments(Path::new(CONFIG_FILE), target_name)?
+            .into_iter()
+            .filter(|(_, args)| !args.is_empty())
+            .collect::<Vec<_>>();
+        if !config_args.is_empty() {
+            let all_config_args = config_args.iter().flat_map(|(_, args)| args).collect::<Vec<_>>();
+            parser
+                .parse(all_config_args)
+                .map_err(|e| ArgumentsError::Validation(format!("{} is not valid: {}", CONFIG_FILE, e)))?;
+            let mut string_args = string_args.clone();
+            for (key, args) in &config_args {
+                if !matches.opt_present(key) && !negated_flags.contains(key) {
+                    string_args.extend(args.iter().map(String::as_str));
+                }
+            }
+            matches = parser.parse(string_args).map_err(ArgumentsError::Parsing)?;
+        }
+    }
 
     let mut args = match Arguments::from_matches(&matches, true) {
         Ok(r) => r,
         Err(ArgumentsError::WantsHelp) => {
             println!("{}", help(&parser));
-            return Ok(());
+            return Ok(TerminationStatus::Success as i32);
         }
         Err(e) => {
             return Err(Box::new(e));
         }
     };
+
+    // this won't crash because we `Arguments::from_matches` would have returned an error otherwise
+    let target_name = if let Some(subcommand) = &subcommand {
+        matches.free.get(1).ok_or_else(|| {
+            ArgumentsError::Validation(format!("The name of a fuzz test must be given after {}.", subcommand))
+        })?
+    } else {
+        &matches.free[0]
+    };
+
+    if subcommand.as_deref() == Some(PLOT_SUBCOMMAND) {
+        let stats_folder = args
+            .stats_folder
+            .unwrap_or_else(|| PathBuf::new().join(format!("fuzz/{}/stats", target_name)));
+        let path = plot_stats(&stats_folder)?;
+        println!("The statistics of the pool were plotted in {}", path.display());
+        return Ok(TerminationStatus::Success as i32);
+    }
+    if subcommand.as_deref() == Some(BENCH_SUBCOMMAND) {
+        args.command = FuzzerCommand::Bench;
+    }
+    if subcommand.as_deref() == Some(COVDIFF_SUBCOMMAND) {
+        let [first, second] = [2, 3].map(|i| matches.free.get(i).map(PathBuf::from));
+        let (Some(first), Some(second)) = (first, second) else {
+            return Err(Box::new(ArgumentsError::Validation(format!(
+                "Two input files must be given after {} {}.",
+                COVDIFF_SUBCOMMAND, target_name
+            ))));
+        };
+        args.command = FuzzerCommand::CoverageDiff {
+            input_files: [first, second],
+        };
+    }
+    if subcommand.as_deref() == Some(MERGE_SUBCOMMAND) {
+        let corpora = matches.free[2..].iter().map(PathBuf::from).collect::<Vec<_>>();
+        if corpora.is_empty() {
+            return Err(Box::new(ArgumentsError::Validation(format!(
+                "The folders of the corpora to merge must be given after {} {}.",
+                MERGE_SUBCOMMAND, target_name
+            ))));
+        }
+        args.command = FuzzerCommand::Merge { corpora };
+    }
+
     let address_sanitizer = matches.opt_present("address-sanitizer");
     let profile = matches.opt_str("profile").unwrap_or_else(|| "release".to_owned());
     let no_instrument_coverage = matches.opt_present("no-instrument-coverage");
+    let coverage_backend = match matches.opt_str(COVERAGE_BACKEND_FLAG) {
+        Some(name) => CoverageBackend::from_name(&name).ok_or_else(|| {
+            ArgumentsError::Validation(format!(
+                "The coverage backend must be llvm or sancov, but {} was given.",
+                name
+            ))
+        })?,
+        None => CoverageBackend::Llvm,
+    };
+    if no_instrument_coverage && matches.opt_present(COVERAGE_BACKEND_FLAG) {
+        return Err(Box::new(ArgumentsError::Validation(format!(
+            "Only one of --no-instrument-coverage and --{} must be given.",
+            COVERAGE_BACKEND_FLAG
+        ))));
+    }
 
-    // this won't crash because we `Arguments::from_matches` would have returned an error otherwise
-    let target_name = &matches.free[0];
+    let envs = matches
+        .opt_strs(ENV_FLAG)
+        .into_iter()
+        .map(|env| {
+            env.split_once('=')
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .ok_or_else(|| {
+                    ArgumentsError::Validation(format!(
+                        "The environment variables must be given as KEY=VALUE, but {} was given.",
+                        env
+                    ))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let working_dir = matches.opt_str(WORKING_DIR_FLAG).map(PathBuf::from);
 
     let cargo_args: Option<String> = matches.opt_get(CARGO_ARGS_FLAG)?;
 
@@ -98,6 +247,28 @@ fn main() -> Result<(), Box<dyn Error>> {
         unreachable!();
     };
 
+    let mut executable = compile_test_executable(
+        &compiled_target,
+        &cargo_args,
+        address_sanitizer,
+        &profile,
+        if no_instrument_coverage {
+            None
+        } else {
+            Some(coverage_backend)
+        },
+    )?;
+    executable.envs = envs;
+    executable.working_dir = working_dir;
+
+    if target_name == LIST_SUBCOMMAND {
+        let fuzz_targets = list_fuzz_targets(&executable)?;
+        for fuzz_target in fuzz_targets {
+            println!("{}", fuzz_target);
+        }
+        return Ok(TerminationStatus::Success as i32);
+    }
+
     match args.command {
         FuzzerCommand::Fuzz => {
             if args.corpus_in.is_none() && matches.opt_present(NO_IN_CORPUS_FLAG) == false {
@@ -112,43 +283,78 @@ fn main() -> Result<(), Box<dyn Error>> {
             if args.stats_folder.is_none() && matches.opt_present(NO_STATS_FLAG) == false {
                 args.stats_folder = Some(PathBuf::new().join(format!("fuzz/{}/stats", target_name)));
             }
-            let exec = launch_executable(
-                target_name,
-                &args,
-                &compiled_target,
-                &cargo_args,
-                address_sanitizer,
-                &profile,
-                !no_instrument_coverage,
-                &process::Stdio::inherit,
-            )?;
-            exec.wait_with_output()?;
+            let mut exec = launch_executable(&executable, target_name, &args, &process::Stdio::inherit)?;
+            let status = exec.wait()?;
+            Ok(exit_code(&args, status))
         }
         FuzzerCommand::MinifyInput { .. } => {
-            input_minify_command(
-                target_name,
-                &args,
-                &compiled_target,
-                &cargo_args,
-                address_sanitizer,
-                &profile,
-                !no_instrument_coverage,
-                &process::Stdio::inherit,
-            )?;
+            input_minify_command(&executable, target_name, &args, &process::Stdio::inherit)?;
+            Ok(TerminationStatus::Success as i32)
         }
         FuzzerCommand::Read { .. } => {
-            let exec = launch_executable(
-                target_name,
-                &args,
-                &compiled_target,
-                &cargo_args,
-                address_sanitizer,
-                &profile,
-                !no_instrument_coverage,
-                &process::Stdio::inherit,
-            )?;
-            exec.wait_with_output()?;
+            let mut exec = launch_executable(&executable, target_name, &args, &process::Stdio::inherit)?;
+            let status = exec.wait()?;
+            Ok(exit_code(&args, status))
+        }
+        FuzzerCommand::Analyze => {
+            if args.corpus_in.is_none() && matches.opt_present(NO_IN_CORPUS_FLAG) == false {
+                args.corpus_in = Some(PathBuf::new().join(format!("fuzz/{}/corpus", target_name)));
+            }
+            if args.stats_folder.is_none() && matches.opt_present(NO_STATS_FLAG) == false {
+                args.stats_folder = Some(PathBuf::new().join(format!("fuzz/{}/stats", target_name)));
+            }
+            // the corpus is only read, and no artifact is saved
+            args.corpus_out = None;
+            args.artifacts_folder = None;
+            let mut exec = launch_executable(&executable, target_name, &args, &process::Stdio::inherit)?;
+            let status = exec.wait()?;
+            Ok(exit_code(&args, status))
+        }
+        FuzzerCommand::Bench => {
+            if args.corpus_in.is_none() && matches.opt_present(NO_IN_CORPUS_FLAG) == false {
+                args.corpus_in = Some(PathBuf::new().join(format!("fuzz/{}/corpus", target_name)));
+            }
+            // the corpus is only read, and nothing is saved
+            args.corpus_out = None;
+            args.artifacts_folder = None;
+            args.stats_folder = None;
+            let mut exec = launch_executable(&executable, target_name, &args, &process::Stdio::inherit)?;
+            let status = exec.wait()?;
+            Ok(exit_code(&args, status))
+        }
+        FuzzerCommand::CoverageDiff { .. } => {
+            // only the two input files are read, and nothing is saved
+            args.corpus_in = None;
+            args.corpus_out = None;
+            args.artifacts_folder = None;
+            args.stats_folder = None;
+            let mut exec = launch_executable(&executable, target_name, &args, &process::Stdio::inherit)?;
+            let status = exec.wait()?;
+            Ok(exit_code(&args, status))
+        }
+        FuzzerCommand::Merge { .. } => {
+            if args.corpus_out.is_none() && matches.opt_present(NO_OUT_CORPUS_FLAG) == false {
+                args.corpus_out = Some(PathBuf::new().join(format!("fuzz/{}/merged_corpus", target_name)));
+            }
+            // only the merged corpora are read, and only the output corpus is written
+            args.corpus_in = None;
+            args.artifacts_folder = None;
+            args.stats_folder = None;
+            let mut exec = launch_executable(&executable, target_name, &args, &process::Stdio::inherit)?;
+            let status = exec.wait()?;
+            Ok(exit_code(&args, status))
+        }
+        FuzzerCommand::Repool => {
+            if args.stats_folder.is_none() && matches.opt_present(NO_STATS_FLAG) == false {
+                args.stats_folder = Some(PathBuf::new().join(format!("fuzz/{}/stats", target_name)));
+            }
+            // only the recorded observations are read from the stats folder
+            args.corpus_in = None;
+            args.corpus_out = None;
+            args.artifacts_folder = None;
+            let mut exec = launch_executable(&executable, target_name, &args, &process::Stdio::inherit)?;
+            let status = exec.wait()?;
+            Ok(exit_code(&args, status))
         }
     }
-    Ok(())
 }
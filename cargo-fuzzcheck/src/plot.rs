@@ -0,0 +1,244 @@
+//! The `plot` subcommand, which draws the statistics of the pool of the most recent fuzzing session over time.
+//!
+//! The statistics are read from the `events.jsonl` file that the fuzzer saves in the folder of each session.
+//! Every numeric field of the pool statistics, such as the code coverage, is drawn as a line relative to its
+//! maximum, and the resulting SVG image is saved next to the statistics, as `pool_stats.svg`.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use fuzzcheck_common::STATS_JSONL_FILE;
+
+/// The argument given to `cargo fuzzcheck`, followed by the name of a fuzz test, to plot the statistics of its pool
+pub const PLOT_SUBCOMMAND: &str = "plot";
+
+const PLOT_FILE: &str = "pool_stats.svg";
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 400.0;
+const MARGIN: f64 = 60.0;
+const COLORS: [&str; 6] = ["#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b"];
+
+/// The values of a statistic over time, given in seconds since the start of the fuzzer
+struct Series {
+    name: String,
+    points: Vec<(f64, f64)>,
+}
+
+/// Plot the statistics of the pool of the most recent fuzzing session saved in the stats folder.
+///
+/// Returns the path of the SVG file.
+pub fn plot_stats(stats_folder: &Path) -> std::io::Result<PathBuf> {
+    let session = latest_session(stats_folder)?.ok_or_else(|| {
+        std::io::Error::other(format!(
+            "no statistics of a fuzzing session were found in {}",
+            stats_folder.display()
+        ))
+    })?;
+    let content = std::fs::read_to_string(session.join(STATS_JSONL_FILE))?;
+    let series = pool_series(&content);
+    if series.is_empty() {
+        return Err(std::io::Error::other(format!(
+            "the statistics in {} do not contain any numeric statistic of the pool",
+            session.display()
+        )));
+    }
+    let path = session.join(PLOT_FILE);
+    std::fs::write(&path, render_svg(&series))?;
+    Ok(path)
+}
+
+/// The folder of the most recent fuzzing session containing JSON lines statistics.
+///
+/// Each fuzzing session has its own subfolder of the stats folder, named after the time at which it started.
+fn latest_session(stats_folder: &Path) -> std::io::Result<Option<PathBuf>> {
+    let mut latest_session: Option<(u128, PathBuf)> = None;
+    for entry in std::fs::read_dir(stats_folder)? {
+        let path = entry?.path();
+        let time = path
+            .file_name()
+            .and_then(std::ffi::OsStr::to_str)
+            .and_then(|name| name.parse::<u128>().ok());
+        if let Some(time) = time {
+            if path.join(STATS_JSONL_FILE).is_file()
+                && latest_session
+                    .as_ref()
+                    .is_none_or(|(latest_time, _)| time > *latest_time)
+            {
+                latest_session = Some((time, path));
+            }
+        }
+    }
+    Ok(latest_session.map(|(_, path)| path))
+}
+
+/// The numeric statistics of the pool, in the order in which they first appear
+fn pool_series(content: &str) -> Vec<Series> {
+    let mut series: Vec<Series> = vec![];
+    for event in content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+    {
+        let (Some(time_ms), Some(pool)) = (event["time_ms"].as_f64(), event["pool"].as_object()) else {
+            continue;
+        };
+        for (name, value) in pool {
+            let Some(value) = value.as_f64() else {
+                continue;
+            };
+            let point = (time_ms / 1000.0, value);
+            if let Some(series) = series.iter_mut().find(|series| series.name == *name) {
+                series.points.push(point);
+            } else {
+                series.push(Series {
+                    name: name.clone(),
+                    points: vec![point],
+                });
+            }
+        }
+    }
+    series
+}
+
+/// The largest value of the series, or `f64::EPSILON` if none is positive
+fn max_value(series: &Series) -> f64 {
+    series
+        .points
+        .iter()
+        .map(|(_, value)| *value)
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON)
+}
+
+/// Draw the series as lines sharing the same time axis, with a legend.
+///
+/// The statistics of the pool have unrelated units, so each series is drawn relative to its own maximum,
+/// which is given in the legend.
+fn render_svg(series: &[Series]) -> String {
+    let max_time = series
+        .iter()
+        .flat_map(|series| series.points.iter())
+        .map(|(time, _)| *time)
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON);
+    let x = |time: f64| MARGIN + time / max_time * (WIDTH - 2.0 * MARGIN);
+    let y = |fraction: f64| HEIGHT - MARGIN - fraction * (HEIGHT - 2.0 * MARGIN);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+        w = WIDTH,
+        h = HEIGHT
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+    // axes, with the maximum time and value at their ends
+    let _ = writeln!(
+        svg,
+        r#"<polyline points="{left},{top} {left},{bottom} {right},{bottom}" fill="none" stroke="black"/>"#,
+        left = MARGIN,
+        top = MARGIN,
+        bottom = HEIGHT - MARGIN,
+        right = WIDTH - MARGIN
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="end">max</text>"#,
+        MARGIN - 5.0,
+        MARGIN + 4.0
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="end">0</text>"#,
+        MARGIN - 5.0,
+        HEIGHT - MARGIN + 4.0
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="end">{:.1} s</text>"#,
+        WIDTH - MARGIN,
+        HEIGHT - MARGIN + 20.0,
+        max_time
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="middle">time since the start of the fuzzer</text>"#,
+        WIDTH / 2.0,
+        HEIGHT - MARGIN + 40.0
+    );
+    for (i, series) in series.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        let max_value = max_value(series);
+        let points = series
+            .points
+            .iter()
+            .map(|&(time, value)| format!("{:.1},{:.1}", x(time), y(value / max_value)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+            points, color
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" fill="{}">{} (max: {})</text>"#,
+            MARGIN + 10.0,
+            MARGIN - 40.0 + 15.0 * i as f64,
+            color,
+            escape_xml(&series.name),
+            max_value
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_series() {
+        let content = r#"{"event":"START","time_ms":0}
+{"event":"PULSE","time_ms":1000,"iterations":10,"pool":{"cov-size":2,"cov-percent-coverage":12.5,"max-count":3,"best":"Some(4)"}}
+not a JSON line
+{"event":"NEW","time_ms":2500,"iterations":30,"pool":{"cov-size":5,"cov-percent-coverage":25.0,"max-count":1}}
+"#;
+        let series = pool_series(content);
+        let names = series.iter().map(|series| series.name.as_str()).collect::<Vec<_>>();
+        // the fields are ordered by name in the JSON objects, and the string field is not drawn
+        assert_eq!(names, ["cov-percent-coverage", "cov-size", "max-count"]);
+        assert_eq!(series[1].points, [(1.0, 2.0), (2.5, 5.0)]);
+        assert_eq!(series[2].points, [(1.0, 3.0), (2.5, 1.0)]);
+        assert_eq!(max_value(&series[2]), 3.0);
+
+        let svg = render_svg(&series);
+        assert_eq!(svg.matches("stroke-width=\"2\"").count(), 3);
+        assert!(svg.contains("max-count (max: 3)"));
+        // each series is drawn relative to its own maximum
+        let top = format!("{:.1},{:.1}", WIDTH - MARGIN, MARGIN);
+        assert_eq!(svg.matches(&top).count(), 2);
+
+        assert!(pool_series(r#"{"event":"START","time_ms":0}"#).is_empty());
+    }
+
+    #[test]
+    fn test_latest_session() {
+        let folder = std::env::temp_dir().join(format!("cargo_fuzzcheck_plot_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        for (session, has_stats) in [("100", true), ("300", false), ("200", true), ("events", true)] {
+            let session = folder.join(session);
+            std::fs::create_dir_all(&session).unwrap();
+            if has_stats {
+                std::fs::write(session.join(STATS_JSONL_FILE), "").unwrap();
+            }
+        }
+        assert_eq!(latest_session(&folder).unwrap(), Some(folder.join("200")));
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -3,11 +3,22 @@ fn main() {
     let file_to_compile = match target_os.as_str() {
         "macos" | "ios" => "src/code_coverage_sensor/instrumentation_pointers_mac.c",
         "linux" => "src/code_coverage_sensor/instrumentation_pointers_linux.c",
-        _ => panic!("fuzzcheck only work on macOS and Linux"),
+        "windows" => "src/code_coverage_sensor/instrumentation_pointers_windows.c",
+        _ => panic!("fuzzcheck only work on macOS, Linux and Windows"),
     };
 
-    cc::Build::new()
-        .file(file_to_compile)
-        .compile("instrumentation_pointers");
+    let sanitizer_coverage = "src/code_coverage_sensor/sanitizer_coverage.c";
+
+    let mut build = cc::Build::new();
+    build.file(file_to_compile).file(sanitizer_coverage);
     println!("cargo:rerun-if-changed={}", file_to_compile);
+    println!("cargo:rerun-if-changed={}", sanitizer_coverage);
+
+    // relies on weak undefined symbols, which are not supported by the linkers of macOS and Windows
+    if target_os == "linux" {
+        let sanitizer_death_callback = "src/sanitizer_death_callback_linux.c";
+        build.file(sanitizer_death_callback);
+        println!("cargo:rerun-if-changed={}", sanitizer_death_callback);
+    }
+    build.compile("instrumentation_pointers");
 }
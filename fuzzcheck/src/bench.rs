@@ -0,0 +1,196 @@
+//! The `bench` command, which measures how fast the fuzz test can be executed.
+//!
+//! The test cases of the input corpus, or arbitrary ones if it is empty, are repeatedly mutated and executed
+//! for a fixed duration. Each execution is observed by the sensor of the fuzz test and processed by its pools, as
+//! while fuzzing, but no test case is added to the corpus. The time spent in the mutator, in the sensor and pools,
+//! and in the test function are measured separately, such that the cause of a low number of iterations per second
+//! can be found.
+
+use std::ffi::OsStr;
+use std::fmt::Display;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use fuzzcheck_common::arg::Arguments;
+
+use crate::fuzzer::TestHooks;
+use crate::signals_handler::set_infinite_loop_alarm;
+use crate::traits::SensorAndPool;
+use crate::world::World;
+use crate::{Mutator, PoolStorageIndex, Serializer};
+
+/// The duration of the benchmark if none is given with `--stop-after-duration`
+const DEFAULT_BENCH_DURATION: Duration = Duration::from_secs(10);
+/// The number of arbitrary test cases that are generated when the input corpus is empty
+const NBR_ARBITRARY_INPUTS: usize = 100;
+
+/// The result of the `bench` command
+pub(crate) struct BenchReport {
+    /// The number of test cases that were mutated, either read from the corpus or generated
+    pub nbr_inputs: usize,
+    pub nbr_executions: usize,
+    pub nbr_test_failures: usize,
+    pub total_time: Duration,
+    /// The time spent mutating and unmutating the test cases
+    pub mutator_time: Duration,
+    /// The time spent starting and stopping the recording of the sensor, and processing its observations in the pools
+    pub sensor_time: Duration,
+    pub test_time: Duration,
+}
+
+/// Mutate and run the test cases of the input corpus until the duration given by `--stop-after-duration` has
+/// elapsed, or [`DEFAULT_BENCH_DURATION`] if there is none.
+///
+/// The files of the corpus are decoded by the first serializer that can read their extension. If none of them
+/// can be decoded, arbitrary test cases are generated instead.
+#[coverage(off)]
+pub(crate) fn bench<T, M>(
+    test: &dyn Fn(&T) -> bool,
+    hooks: &TestHooks<T>,
+    mutator: &M,
+    serializers: &[&dyn Serializer<Value = T>],
+    sensor_and_pool: &mut dyn SensorAndPool,
+    world: &World,
+    args: &Arguments,
+) -> std::io::Result<BenchReport>
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    let max_cplx = args.max_input_cplx;
+    let duration = if args.maximum_duration == Duration::MAX {
+        DEFAULT_BENCH_DURATION
+    } else {
+        args.maximum_duration
+    };
+    let mut inputs = vec![];
+    for (path, data) in world.read_input_corpus()? {
+        let Ok(data) = data else {
+            continue;
+        };
+        let extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+        let value = serializers
+            .iter()
+            .find(
+                #[coverage(off)]
+                |serializer| serializer.reads_extension(extension),
+            )
+            .and_then(
+                #[coverage(off)]
+                |serializer| serializer.from_data_with_extension(&data, extension),
+            );
+        if let Some(value) = value
+            && let Some(cache) = mutator.validate_value(&value)
+        {
+            inputs.push((value, cache));
+        }
+    }
+    if inputs.is_empty() {
+        for _ in 0..NBR_ARBITRARY_INPUTS {
+            let (value, _) = mutator.random_arbitrary(max_cplx);
+            if let Some(cache) = mutator.validate_value(&value) {
+                inputs.push((value, cache));
+            }
+        }
+    }
+    let mut report = BenchReport {
+        nbr_inputs: inputs.len(),
+        nbr_executions: 0,
+        nbr_test_failures: 0,
+        total_time: Duration::ZERO,
+        mutator_time: Duration::ZERO,
+        sensor_time: Duration::ZERO,
+        test_time: Duration::ZERO,
+    };
+    if inputs.is_empty() {
+        return Ok(report);
+    }
+
+    // the test failures are counted, but their panic messages would slow down the benchmark
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(
+        #[coverage(off)]
+        |_| {},
+    ));
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        let (value, cache) = &mut inputs[report.nbr_executions % report.nbr_inputs];
+
+        let before_mutation = Instant::now();
+        let (token, cplx) = mutator.random_mutate(value, cache, max_cplx);
+        let after_mutation = Instant::now();
+        // the hooks are not part of any of the measured times
+        hooks.before(value);
+        let before_start_recording = Instant::now();
+        sensor_and_pool.start_recording();
+        if args.detect_infinite_loop {
+            set_infinite_loop_alarm();
+        }
+        let before_test = Instant::now();
+        let result = catch_unwind(AssertUnwindSafe(
+            #[coverage(off)]
+            || test(value),
+        ));
+        let after_test = Instant::now();
+        sensor_and_pool.stop_recording();
+        // the changes to the corpus are not applied, each execution is given a different index so that the pools
+        // do not confuse them
+        let _ = sensor_and_pool.process(PoolStorageIndex::mock(report.nbr_executions), cplx);
+        let after_process = Instant::now();
+        let success = matches!(result, Ok(true));
+        hooks.after(value, success);
+        let before_unmutation = Instant::now();
+        mutator.unmutate(value, cache, token);
+        let after_unmutation = Instant::now();
+
+        report.mutator_time += (after_mutation - before_mutation) + (after_unmutation - before_unmutation);
+        report.sensor_time += (before_test - before_start_recording) + (after_process - after_test);
+        report.test_time += after_test - before_test;
+        report.nbr_executions += 1;
+        if !success {
+            report.nbr_test_failures += 1;
+        }
+    }
+    report.total_time = start.elapsed();
+    std::panic::set_hook(previous_hook);
+    Ok(report)
+}
+
+impl BenchReport {
+    #[coverage(off)]
+    pub fn executions_per_second(&self) -> f64 {
+        self.nbr_executions as f64 / self.total_time.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// The percentage of the total time that was spent on the given task
+    #[coverage(off)]
+    fn percentage(&self, time: Duration) -> f64 {
+        100.0 * time.as_secs_f64() / self.total_time.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+impl Display for BenchReport {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let other_time = self
+            .total_time
+            .saturating_sub(self.mutator_time + self.sensor_time + self.test_time);
+        writeln!(
+            f,
+            "{} executions of {} test cases in {:.2?}, iter/s: {:.0}, test failures: {}",
+            self.nbr_executions,
+            self.nbr_inputs,
+            self.total_time,
+            self.executions_per_second(),
+            self.nbr_test_failures
+        )?;
+        write!(
+            f,
+            "time spent in the test function: {:.1}%, in the sensor and pools: {:.1}%, in the mutator: {:.1}%, elsewhere: {:.1}%",
+            self.percentage(self.test_time),
+            self.percentage(self.sensor_time),
+            self.percentage(self.mutator_time),
+            self.percentage(other_time)
+        )
+    }
+}
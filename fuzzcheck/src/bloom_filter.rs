@@ -39,6 +39,7 @@ const LN_2_SQR: f64 = LN_2 * LN_2;
 pub struct BloomFilter<T: ?Sized> {
     k: u64,
     m: usize,
+    keys: [(u128, u128); 2],
     hashers: [AHasher; 2],
     pub bitmap: BitVec,
     _phantom: PhantomData<T>,
@@ -56,19 +57,77 @@ impl<T: ?Sized> BloomFilter<T> {
         let k = Self::optimal_k(false_pos_rate);
         let m = Self::optimal_m(false_pos_rate, size);
         let bitmap = BitVec::from_elem(m, false);
+        let keys = [
+            (fastrand::u128(..), fastrand::u128(..)),
+            (fastrand::u128(..), fastrand::u128(..)),
+        ];
+        Self::with_keys_and_bitmap(k, m, keys, bitmap)
+    }
+
+    #[coverage(off)]
+    fn with_keys_and_bitmap(k: u64, m: usize, keys: [(u128, u128); 2], bitmap: BitVec) -> Self {
         let hashers = [
-            AHasher::new_with_keys(fastrand::u128(..), fastrand::u128(..)),
-            AHasher::new_with_keys(fastrand::u128(..), fastrand::u128(..)),
+            AHasher::new_with_keys(keys[0].0, keys[0].1),
+            AHasher::new_with_keys(keys[1].0, keys[1].1),
         ];
         BloomFilter {
             k,
             m,
+            keys,
             hashers,
             bitmap,
             _phantom: PhantomData,
         }
     }
 
+    /// Encode the bloom filter, including the keys of its hashers, such that it
+    /// can be decoded by [`BloomFilter::from_bytes`] in a later process
+    #[coverage(off)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend(self.k.to_le_bytes());
+        bytes.extend((self.m as u64).to_le_bytes());
+        for (key1, key2) in self.keys {
+            bytes.extend(key1.to_le_bytes());
+            bytes.extend(key2.to_le_bytes());
+        }
+        bytes.extend(self.bitmap.to_bytes());
+        bytes
+    }
+
+    /// Decode a bloom filter encoded by [`BloomFilter::to_bytes`]
+    ///
+    /// Returns `None` if the bytes are malformed or if the decoded filter does not
+    /// have the size and false positive rate given as arguments.
+    #[coverage(off)]
+    pub fn from_bytes(bytes: &[u8], size: usize, false_pos_rate: f64) -> Option<Self> {
+        #[coverage(off)]
+        fn take<const N: usize>(bytes: &mut &[u8]) -> Option<[u8; N]> {
+            let (head, tail) = bytes.split_first_chunk::<N>()?;
+            *bytes = tail;
+            Some(*head)
+        }
+        let mut bytes = bytes;
+        let k = u64::from_le_bytes(take(&mut bytes)?);
+        let m = u64::from_le_bytes(take(&mut bytes)?) as usize;
+        if k != Self::optimal_k(false_pos_rate) || m != Self::optimal_m(false_pos_rate, size) {
+            return None;
+        }
+        let mut keys = [(0, 0); 2];
+        for key in keys.iter_mut() {
+            *key = (
+                u128::from_le_bytes(take(&mut bytes)?),
+                u128::from_le_bytes(take(&mut bytes)?),
+            );
+        }
+        if bytes.len() != m.div_ceil(8) {
+            return None;
+        }
+        let mut bitmap = BitVec::from_bytes(bytes);
+        bitmap.truncate(m);
+        Some(Self::with_keys_and_bitmap(k, m, keys, bitmap))
+    }
+
     /// Calculate optimal m value for the filter
     /// where m is the optimal number of bits in the bit array
     /// while preventing overfill
@@ -115,7 +174,8 @@ impl<T: ?Sized> BloomFilter<T> {
     /// wrapping_mul: wrapping mult around at the boundary type
     #[coverage(off)]
     fn find_index(&self, i: u64, hash1: u64, hash2: u64) -> usize {
-        hash1.wrapping_add((i).wrapping_mul(hash2)) as usize % self.m
+        // the modulo is computed on 64 bits such that the index is the same on every platform
+        (hash1.wrapping_add((i).wrapping_mul(hash2)) % self.m as u64) as usize
     }
 
     /// Insert T into the BloomFilter index
@@ -125,11 +185,7 @@ impl<T: ?Sized> BloomFilter<T> {
         T: Hash,
     {
         let (hash1, hash2) = self.hash(t);
-
-        for i in 0..self.k {
-            let index = self.find_index(i, hash1, hash2);
-            self.bitmap.set(index, true);
-        }
+        self.insert_hashes(hash1, hash2);
     }
 
     /// Check if t of type T is in the BloomFilter index
@@ -139,7 +195,21 @@ impl<T: ?Sized> BloomFilter<T> {
         T: Hash,
     {
         let (hash1, hash2) = self.hash(t);
+        self.contains_hashes(hash1, hash2)
+    }
 
+    /// Insert the value whose two hashes were computed by the caller instead of by the hashers of the filter
+    #[coverage(off)]
+    pub fn insert_hashes(&mut self, hash1: u64, hash2: u64) {
+        for i in 0..self.k {
+            let index = self.find_index(i, hash1, hash2);
+            self.bitmap.set(index, true);
+        }
+    }
+
+    /// Check if the value whose two hashes were computed by the caller is in the BloomFilter index
+    #[coverage(off)]
+    pub fn contains_hashes(&self, hash1: u64, hash2: u64) -> bool {
         for i in 0..self.k {
             let index = self.find_index(i, hash1, hash2);
             if !self.bitmap.get(index).unwrap() {
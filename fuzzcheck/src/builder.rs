@@ -53,27 +53,52 @@ let _ = fuzzcheck::fuzz_test(test_function)
 # }
 ```
 
+To quickly run the test function on random values, without code coverage instrumentation, for example in
+a regular `cargo test`, use [`.random_generation_only(..)`](FuzzerBuilder2::random_generation_only) after
+specifying the mutator:
+```
+# use fuzzcheck::DefaultMutator;
+fn test_function(xs: &[u8]) -> bool { xs.len() < 100_000 }
+
+let failure = fuzzcheck::fuzz_test(test_function)
+    .default_mutator()
+    .random_generation_only(1000)
+    .launch();
+assert!(failure.is_none());
+```
+
 To build a custom sensor and pool, you may want to look at the [`Sensor`], [`Pool`](crate::Pool), and [`CompatibleWithObservations`] traits.
 You can also look at the types provided in the [`sensors_and_pools`](crate::sensors_and_pools) module. But the easiest way to customize them
 is to use the [`SensorAndPoolBuilder`], although it only offers a couple limited options.
 */
 
 use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{Debug, Display};
+use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::Path;
+use std::pin::Pin;
 use std::result::Result;
 use std::time::Duration;
 
 use fuzzcheck_common::arg::{
-    options_parser, Arguments, ArgumentsError, FuzzerCommand, COMMAND_FUZZ, COMMAND_MINIFY_INPUT, INPUT_FILE_FLAG,
+    options_parser, Arguments, ArgumentsError, CorpusNamingPolicy, DefaultArguments, ExitCodePolicy, FuzzerCommand,
+    NoProgressBudget, COMMAND_FUZZ, COMMAND_MINIFY_INPUT, INPUT_FILE_FLAG,
 };
+use fuzzcheck_common::TerminationStatus;
 
-use crate::code_coverage_sensor::CodeCoverageSensor;
-use crate::fuzzer::{Fuzzer, FuzzingResult};
+use crate::code_coverage_sensor::{CodeCoverageSensor, CoverageFilter};
+use crate::fuzzer::{terminate, Fuzzer, FuzzingResult, TestHooks};
 use crate::sensors_and_pools::{
-    AndPool, DifferentObservations, MaximiseEachCounterPool, MaximiseObservationPool, MostNDiversePool,
-    SameObservations, SimplestToActivateCounterPool, WrapperSensor,
+    record_output, AllocationSensor, AndPool, DifferentObservations, EvictionPolicy, MaximiseAllocationsPool,
+    MaximiseEachCounterPool, MaximiseObservationPool, MostNDiversePool, SameObservations,
+    SimplestToActivateCounterPool, TestFailure, WrapperSensor, TEST_FAILURE,
 };
+#[cfg(feature = "serde_bincode_serializer")]
+use crate::SerdeBincodeSerializer;
 #[cfg(feature = "serde_ron_serializer")]
 use crate::SerdeRonSerializer;
 #[cfg(feature = "serde_json_serializer")]
@@ -101,6 +126,10 @@ pub enum ReturnBool {}
 pub enum ReturnVoid {}
 /// Marker type for a function of type `Fn(&T) -> Result<V, E>`
 pub enum ReturnResult {}
+/// Marker type for a function of type `Fn(&T) -> O` whose output is checked by a failure predicate
+pub enum ReturnCheckedOutput {}
+/// Marker type for a function of type `Fn(&T) -> O` whose output is observed by an [`OutputSensor`](crate::sensors_and_pools::OutputSensor)
+pub enum ReturnObservedOutput {}
 
 impl<T, FT: ?Sized, F> FuzzTestFunction<T, FT, ReturnBool> for F
 where
@@ -130,15 +159,205 @@ where
     }
 }
 
-impl<T, FT: ?Sized, F, S, E> FuzzTestFunction<T, FT, ReturnResult> for F
+/// The error of the test function is displayed in the description of the test failure, which
+/// is saved to the statistics folder with the artifacts.
+impl<T, FT: ?Sized, F, V, E> FuzzTestFunction<T, FT, ReturnResult> for F
+where
+    T: Borrow<FT>,
+    F: Fn(&FT) -> Result<V, E>,
+    E: Display,
+{
+    type NormalizedFunction = impl Fn(&T) -> bool;
+    #[coverage(off)]
+    fn test_function(self) -> Self::NormalizedFunction {
+        #[coverage(off)]
+        move |x| match self(x.borrow()) {
+            Ok(_) => true,
+            Err(error) => {
+                let display = error.to_string();
+                let mut hasher = DefaultHasher::new();
+                display.hash(&mut hasher);
+                unsafe {
+                    TEST_FAILURE = Some(TestFailure {
+                        display,
+                        id: hasher.finish(),
+                    });
+                }
+                false
+            }
+        }
+    }
+}
+
+/// A test function whose output is checked by a failure predicate.
+///
+/// It is created by [`TestFunctionExt::failure_predicate`].
+pub struct WithFailurePredicate<F, P> {
+    test_function: F,
+    failure_predicate: P,
+}
+
+/// A test function whose output is recorded by [`record_output`].
+///
+/// It is created by [`TestFunctionExt::observe_output`].
+pub struct ObservingOutput<F> {
+    test_function: F,
+}
+
+/// Extension trait for the functions that can be given to [`fuzz_test`].
+pub trait TestFunctionExt<FT: ?Sized, O>: Fn(&FT) -> O + Sized {
+    /// Fuzz-test the function, reporting a test failure when `failure_predicate` returns `true` for its output.
+    ///
+    /// This is useful when the output of the function can be checked without panicking:
+    /// ```
+    /// use fuzzcheck::builder::TestFunctionExt;
+    ///
+    /// fn encode(xs: &[u8]) -> Vec<u8> {
+    ///     xs.iter().flat_map(|&x| [x, x]).collect()
+    /// }
+    /// // each byte must be repeated twice
+    /// let is_invalid = |output: &Vec<u8>| output.chunks(2).any(|pair| pair.len() != 2 || pair[0] != pair[1]);
+    /// let builder = fuzzcheck::fuzz_test(encode.failure_predicate(is_invalid));
+    /// ```
+    #[coverage(off)]
+    fn failure_predicate<P>(self, failure_predicate: P) -> WithFailurePredicate<Self, P>
+    where
+        P: Fn(&O) -> bool,
+    {
+        WithFailurePredicate {
+            test_function: self,
+            failure_predicate,
+        }
+    }
+
+    /// Fuzz-test the function, passing each of its outputs to [`record_output`].
+    ///
+    /// The outputs can then be observed by an [`OutputSensor`](crate::sensors_and_pools::OutputSensor),
+    /// for example to find test cases producing as many different outputs as possible with an
+    /// [`OutputDiversityPool`](crate::sensors_and_pools::OutputDiversityPool). The function never fails,
+    /// unless it panics.
+    #[coverage(off)]
+    fn observe_output(self) -> ObservingOutput<Self>
+    where
+        O: Hash,
+    {
+        ObservingOutput { test_function: self }
+    }
+}
+impl<FT: ?Sized, O, F> TestFunctionExt<FT, O> for F where F: Fn(&FT) -> O {}
+
+impl<T, FT: ?Sized, F, O, P> FuzzTestFunction<T, FT, ReturnCheckedOutput> for WithFailurePredicate<F, P>
+where
+    T: Borrow<FT>,
+    F: Fn(&FT) -> O,
+    P: Fn(&O) -> bool,
+{
+    type NormalizedFunction = impl Fn(&T) -> bool;
+    #[coverage(off)]
+    fn test_function(self) -> Self::NormalizedFunction {
+        let WithFailurePredicate {
+            test_function,
+            failure_predicate,
+        } = self;
+        #[coverage(off)]
+        move |x| {
+            let output = test_function(x.borrow());
+            if failure_predicate(&output) {
+                unsafe {
+                    TEST_FAILURE = Some(TestFailure {
+                        display: "the failure predicate rejected the output of the test function".to_string(),
+                        id: 0,
+                    });
+                }
+                false
+            } else {
+                true
+            }
+        }
+    }
+}
+
+impl<T, FT: ?Sized, F, O> FuzzTestFunction<T, FT, ReturnObservedOutput> for ObservingOutput<F>
+where
+    T: Borrow<FT>,
+    F: Fn(&FT) -> O,
+    O: Hash,
+{
+    type NormalizedFunction = impl Fn(&T) -> bool;
+    #[coverage(off)]
+    fn test_function(self) -> Self::NormalizedFunction {
+        let ObservingOutput { test_function } = self;
+        #[coverage(off)]
+        move |x| {
+            record_output(&test_function(x.borrow()));
+            true
+        }
+    }
+}
+
+/// The future returned by an async test function, given to the executor of [`fuzz_test_async`]
+pub type TestFuture<'a, O> = Pin<Box<dyn Future<Output = O> + 'a>>;
+
+/// An async test function along with the executor that drives its futures to completion.
+///
+/// It is created by [`fuzz_test_async`].
+pub struct AsyncTestFunction<F, B> {
+    test_function: F,
+    block_on: B,
+}
+
+impl<F, B> AsyncTestFunction<F, B> {
+    /// Convert the async test function to a synchronous one, which waits for the completion of its future
+    #[coverage(off)]
+    fn blocking<FT: ?Sized, O>(self) -> impl Fn(&FT) -> O
+    where
+        F: AsyncFn(&FT) -> O,
+        B: for<'a> Fn(TestFuture<'a, O>) -> O,
+    {
+        let AsyncTestFunction {
+            test_function,
+            block_on,
+        } = self;
+        #[coverage(off)]
+        move |x| block_on(Box::pin(test_function(x)))
+    }
+}
+
+impl<T, FT: ?Sized, F, B> FuzzTestFunction<T, FT, ReturnBool> for AsyncTestFunction<F, B>
 where
     T: Borrow<FT>,
-    F: Fn(&FT) -> Result<E, S>,
+    F: AsyncFn(&FT) -> bool,
+    B: for<'a> Fn(TestFuture<'a, bool>) -> bool,
 {
     type NormalizedFunction = impl Fn(&T) -> bool;
     #[coverage(off)]
     fn test_function(self) -> Self::NormalizedFunction {
-        move |x| self(x.borrow()).is_ok()
+        FuzzTestFunction::<T, FT, ReturnBool>::test_function(self.blocking())
+    }
+}
+impl<T, FT: ?Sized, F, B> FuzzTestFunction<T, FT, ReturnVoid> for AsyncTestFunction<F, B>
+where
+    T: Borrow<FT>,
+    F: AsyncFn(&FT),
+    B: for<'a> Fn(TestFuture<'a, ()>),
+{
+    type NormalizedFunction = impl Fn(&T) -> bool;
+    #[coverage(off)]
+    fn test_function(self) -> Self::NormalizedFunction {
+        FuzzTestFunction::<T, FT, ReturnVoid>::test_function(self.blocking())
+    }
+}
+impl<T, FT: ?Sized, F, B, V, E> FuzzTestFunction<T, FT, ReturnResult> for AsyncTestFunction<F, B>
+where
+    T: Borrow<FT>,
+    F: AsyncFn(&FT) -> Result<V, E>,
+    B: for<'a> Fn(TestFuture<'a, Result<V, E>>) -> Result<V, E>,
+    E: Display,
+{
+    type NormalizedFunction = impl Fn(&T) -> bool;
+    #[coverage(off)]
+    fn test_function(self) -> Self::NormalizedFunction {
+        FuzzTestFunction::<T, FT, ReturnResult>::test_function(self.blocking())
     }
 }
 
@@ -226,9 +445,20 @@ where
 /// * [`self.out_corpus(..)`](FuzzerBuilder5::out_corpus)
 /// * [`self.artifacts_folder(..)`](FuzzerBuilder5::artifacts_folder)
 /// * [`self.maximum_complexity(..)`](FuzzerBuilder5::maximum_complexity)
+/// * [`self.maximum_arbitrary_complexity(..)`](FuzzerBuilder5::maximum_arbitrary_complexity)
 /// * [`self.stop_after_iterations(..)`](FuzzerBuilder5::stop_after_iterations)
 /// * [`self.stop_after_duration(..)`](FuzzerBuilder5::stop_after_duration)
 /// * [`self.stop_after_first_test_failure(..)`](FuzzerBuilder5::stop_after_first_test_failure)
+///
+/// And register additional serializers to read the input corpus with:
+/// * [`self.also_read_corpus_with(..)`](FuzzerBuilder5::also_read_corpus_with)
+///
+/// Or give seed values to test before fuzzing starts with:
+/// * [`self.initial_inputs(..)`](FuzzerBuilder5::initial_inputs)
+///
+/// Or run some code around every execution of the test function with:
+/// * [`self.before_each(..)`](FuzzerBuilder5::before_each)
+/// * [`self.after_each(..)`](FuzzerBuilder5::after_each)
 pub struct FuzzerBuilder5<F, M, V, Sens, P>
 where
     F: Fn(&V) -> bool + 'static,
@@ -240,9 +470,15 @@ where
     test_function: F,
     mutator: M,
     serializer: Box<dyn Serializer<Value = V>>,
+    corpus_readers: Vec<Box<dyn Serializer<Value = V>>>,
+    initial_inputs: Vec<V>,
+    hooks: TestHooks<V>,
     sensor: Sens,
     pool: P,
     arguments: Arguments,
+    /// The limit given by [`maximum_arbitrary_complexity`](FuzzerBuilder5::maximum_arbitrary_complexity), which
+    /// replaces the one of the arguments when the fuzz test is launched
+    max_arbitrary_cplx: Option<f64>,
     _phantom: PhantomData<*const V>,
 }
 
@@ -252,11 +488,14 @@ where
     The returned value is a [`FuzzerBuilder1`]. See the [module/crate documentation](crate::builder)
     for a full example of how to build a fuzz test.
 
-    There are currently three kinds of functions that can be passed as arguments:
+    There are currently four kinds of functions that can be passed as arguments:
 
     1. `Fn(&T)` : the fuzzer will only report a failure when the given function crashes
     2. `Fn(&T) -> Bool` : the fuzzer will report a failure when the output is `false`
-    3. `Fn(&T) -> Result<_,_>` : the fuzzer will report a failure when the output is `Err(..)`
+    3. `Fn(&T) -> Result<_, E>` : the fuzzer will report a failure when the output is `Err(..)`, and the error,
+       which must implement `Display`, describes the test failure
+    4. `Fn(&T) -> O` with a [failure predicate](TestFunctionExt::failure_predicate) : the fuzzer will report a
+       failure when the predicate returns `true` for the output
 */
 #[coverage(off)]
 pub fn fuzz_test<T, F, TestFunctionKind>(test_function: F) -> FuzzerBuilder1<T::Owned, F::NormalizedFunction>
@@ -271,6 +510,107 @@ where
     }
 }
 
+/**
+    Build a differential fuzz test, which compares two implementations of the same function.
+
+    Both functions are run on each test case, and their outputs are given to `compare`, which returns `true`
+    if they agree. Otherwise, the fuzzer reports a test failure, described by the two outputs. The returned
+    value is a [`FuzzerBuilder1`], which is used in the same way as the one returned by [`fuzz_test`].
+
+    Note that the default sensor observes the code coverage of both implementations.
+
+    ```no_run
+    fn sum(xs: &[u8]) -> u64 {
+        xs.iter().map(|&x| x as u64).sum()
+    }
+    fn fast_sum(xs: &[u8]) -> u64 {
+        xs.iter().fold(0u8, |acc, &x| acc.wrapping_add(x)) as u64
+    }
+    let result = fuzzcheck::fuzz_differential(sum, fast_sum, |a, b| a == b)
+        .default_options()
+        .launch();
+    ```
+*/
+#[coverage(off)]
+pub fn fuzz_differential<T, O1, O2, F1, F2, C>(
+    first: F1,
+    second: F2,
+    compare: C,
+) -> FuzzerBuilder1<T::Owned, impl Fn(&T::Owned) -> bool>
+where
+    T: ?Sized + ToOwned + 'static,
+    T::Owned: Clone,
+    F1: Fn(&T) -> O1 + 'static,
+    F2: Fn(&T) -> O2 + 'static,
+    C: Fn(&O1, &O2) -> bool + 'static,
+    O1: Debug,
+    O2: Debug,
+{
+    fuzz_test(
+        #[coverage(off)]
+        move |x: &T| {
+            let first_output = first(x);
+            let second_output = second(x);
+            if compare(&first_output, &second_output) {
+                true
+            } else {
+                unsafe {
+                    TEST_FAILURE = Some(TestFailure {
+                        display: format!(
+                            "the implementations disagree\nfirst output: {:?}\nsecond output: {:?}",
+                            first_output, second_output
+                        ),
+                        id: 0,
+                    });
+                }
+                false
+            }
+        },
+    )
+}
+
+/**
+    Build a fuzz test for the given async function, whose futures are run to completion by `block_on`.
+
+    The returned value is a [`FuzzerBuilder1`], which is used in the same way as the one returned by
+    [`fuzz_test`]. The async function can return `()`, `bool`, or `Result<_, E>`, with the same meaning
+    as for [`fuzz_test`].
+
+    `block_on` is given the future of each test case, boxed and pinned, and must return its output. It
+    is typically the `block_on` function of an async runtime. The future is driven within the execution of
+    the test case, so [`detect_infinite_loop`](crate::Arguments::detect_infinite_loop) also applies to the
+    futures that never complete, and the code coverage of the runtime is observed together with the one of
+    the test function. Use a [coverage filter](crate::Arguments::coverage_exclude) to ignore it.
+
+    ```no_run
+    async fn parse(bytes: &[u8]) -> Result<String, std::str::Utf8Error> {
+        std::str::from_utf8(bytes).map(str::to_owned)
+    }
+    # fn block_on<O>(future: fuzzcheck::builder::TestFuture<O>) -> O { todo!() }
+    let result = fuzzcheck::fuzz_test_async(parse, |future| block_on(future))
+        .default_options()
+        .launch();
+    ```
+*/
+#[coverage(off)]
+pub fn fuzz_test_async<T, F, B, TestFunctionKind>(
+    test_function: F,
+    block_on: B,
+) -> FuzzerBuilder1<
+    T::Owned,
+    <AsyncTestFunction<F, B> as FuzzTestFunction<T::Owned, T, TestFunctionKind>>::NormalizedFunction,
+>
+where
+    T: ?Sized + ToOwned + 'static,
+    T::Owned: Clone,
+    AsyncTestFunction<F, B>: FuzzTestFunction<T::Owned, T, TestFunctionKind>,
+{
+    fuzz_test(AsyncTestFunction {
+        test_function,
+        block_on,
+    })
+}
+
 #[cfg(feature = "serde_json_serializer")]
 impl<T, F> FuzzerBuilder1<T, F>
 where
@@ -428,6 +768,18 @@ where
             _phantom: PhantomData,
         }
     }
+
+    /// Specify [`SerdeSerializer::pretty`] as the serializer to use when saving the interesting test cases
+    /// to the file system. The test cases are written as pretty-printed json files, with sorted keys.
+    #[coverage(off)]
+    pub fn serde_serializer_pretty(self) -> FuzzerBuilder3<F, M, V> {
+        FuzzerBuilder3 {
+            test_function: self.test_function,
+            mutator: self.mutator,
+            serializer: Box::new(SerdeSerializer::<V>::pretty()),
+            _phantom: PhantomData,
+        }
+    }
 }
 
 #[cfg(feature = "serde_ron_serializer")]
@@ -450,6 +802,110 @@ where
     }
 }
 
+#[cfg(feature = "serde_bincode_serializer")]
+impl<F, M, V> FuzzerBuilder2<F, M, V>
+where
+    F: Fn(&V) -> bool,
+    V: Clone + serde::Serialize + for<'e> serde::Deserialize<'e> + 'static,
+    M: Mutator<V>,
+{
+    /// Specify [`SerdeBincodeSerializer`] as the serializer to use when saving the
+    /// interesting test cases to the file system.
+    #[coverage(off)]
+    pub fn serde_bincode_serializer(self) -> FuzzerBuilder3<F, M, V> {
+        FuzzerBuilder3 {
+            test_function: self.test_function,
+            mutator: self.mutator,
+            serializer: Box::new(SerdeBincodeSerializer::<V>::default()),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, M, V> FuzzerBuilder2<F, M, V>
+where
+    F: Fn(&V) -> bool,
+    V: Clone + 'static,
+    M: Mutator<V>,
+{
+    /// Only run the test function on `number_of_values` random values generated by the mutator, without any
+    /// code coverage feedback, and obtain a [`RandomGenerationTest`].
+    ///
+    /// No sensor or pool is used, and nothing is written to the file system. Therefore, the test does not need
+    /// to be compiled with `-C instrument-coverage` and can be run by a plain `cargo test`, for example as a
+    /// quick smoke test on CI. The values are generated with an increasing maximum complexity, such that
+    /// the simplest ones are tested first.
+    #[coverage(off)]
+    pub fn random_generation_only(self, number_of_values: usize) -> RandomGenerationTest<F, M, V> {
+        RandomGenerationTest {
+            test_function: self.test_function,
+            mutator: self.mutator,
+            number_of_values,
+            max_cplx: DefaultArguments::default().max_input_cplx,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// A test which runs the test function on random values, created by
+/// [`random_generation_only(..)`](FuzzerBuilder2::random_generation_only).
+///
+/// Use [`self.launch()`](RandomGenerationTest::launch) to run it.
+pub struct RandomGenerationTest<F, M, V>
+where
+    F: Fn(&V) -> bool + 'static,
+    V: Clone + 'static,
+    M: Mutator<V>,
+{
+    test_function: F,
+    mutator: M,
+    number_of_values: usize,
+    max_cplx: f64,
+    _phantom: PhantomData<*const V>,
+}
+
+impl<F, M, V> RandomGenerationTest<F, M, V>
+where
+    F: Fn(&V) -> bool + 'static,
+    V: Clone + 'static,
+    M: Mutator<V>,
+{
+    /// The maximum complexity of the generated values
+    #[must_use]
+    #[coverage(off)]
+    pub fn maximum_complexity(self, max_cplx: f64) -> Self {
+        Self { max_cplx, ..self }
+    }
+
+    /// Run the test function on the random values and return the first one that fails the test, if any.
+    ///
+    /// The values are not minified. If the test function panics, the panic is caught after its message is printed.
+    #[coverage(off)]
+    pub fn launch(self) -> Option<V> {
+        let RandomGenerationTest {
+            test_function,
+            mutator,
+            number_of_values,
+            max_cplx,
+            _phantom,
+        } = self;
+        mutator.initialize();
+
+        for i in 0..number_of_values {
+            let cplx = max_cplx * (i + 1) as f64 / number_of_values as f64;
+            let (value, _) = mutator.random_arbitrary(cplx);
+            let result = catch_unwind(AssertUnwindSafe(
+                #[coverage(off)]
+                || test_function(&value),
+            ));
+            if !matches!(result, Ok(true)) {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
 impl<F, M, V> FuzzerBuilder3<F, M, V>
 where
     F: Fn(&V) -> bool,
@@ -491,6 +947,31 @@ where
             _phantom: PhantomData,
         }
     }
+    /// Uses an [`AllocationSensor`] and a [`MaximiseAllocationsPool`], such that the fuzzer
+    /// looks for the test cases performing the most allocations and allocating the most bytes.
+    ///
+    /// The global allocator of the test binary must be a [`CountingAllocator`](crate::sensors_and_pools::CountingAllocator).
+    /// Otherwise, the sensor does not observe anything. For example:
+    /// ```
+    /// use std::alloc::System;
+    /// use fuzzcheck::sensors_and_pools::CountingAllocator;
+    ///
+    /// #[global_allocator]
+    /// static ALLOC: CountingAllocator<System> = CountingAllocator(System);
+    /// ```
+    /// See the [`AllocationSensor`] documentation for a complete example.
+    #[coverage(off)]
+    pub fn allocation_sensor_and_pool(self) -> FuzzerBuilder4<F, M, V, AllocationSensor, MaximiseAllocationsPool> {
+        let (sensor, pool) = allocation_sensor_and_pool().finish();
+        FuzzerBuilder4 {
+            test_function: self.test_function,
+            mutator: self.mutator,
+            serializer: self.serializer,
+            sensor,
+            pool,
+            _phantom: PhantomData,
+        }
+    }
     #[coverage(off)]
     pub fn sensor_and_pool<Sens: Sensor, P: CompatibleWithObservations<Sens::Observations>>(
         self,
@@ -522,9 +1003,13 @@ where
             test_function: self.test_function,
             mutator: self.mutator,
             serializer: self.serializer,
+            corpus_readers: vec![],
+            initial_inputs: vec![],
+            hooks: TestHooks::default(),
             sensor: self.sensor,
             pool: self.pool,
             arguments,
+            max_arbitrary_cplx: None,
             _phantom: self._phantom,
         }
     }
@@ -575,16 +1060,20 @@ fuzzcheck {minify} --{input_file} "artifacts/crash.json"
             Ok(r) => r,
             Err(e) => {
                 println!("{}\n\n{}", e, help);
-                std::process::exit(1);
+                terminate(TerminationStatus::ConfigurationError);
             }
         };
         FuzzerBuilder5 {
             test_function: self.test_function,
             mutator: self.mutator,
             serializer: self.serializer,
+            corpus_readers: vec![],
+            initial_inputs: vec![],
+            hooks: TestHooks::default(),
             sensor: self.sensor,
             pool: self.pool,
             arguments,
+            max_arbitrary_cplx: None,
             _phantom: PhantomData,
         }
     }
@@ -620,6 +1109,14 @@ where
         x.arguments.corpus_out = path.map(Path::to_path_buf);
         x
     }
+    /// Scan the given folder for new test cases while fuzzing. See [`Arguments::incoming_corpus`].
+    #[must_use]
+    #[coverage(off)]
+    pub fn incoming_corpus(self, path: Option<&Path>) -> Self {
+        let mut x = self;
+        x.arguments.incoming_corpus = path.map(Path::to_path_buf);
+        x
+    }
     #[must_use]
     #[coverage(off)]
     pub fn artifacts_folder(self, path: Option<&Path>) -> Self {
@@ -627,11 +1124,28 @@ where
         x.arguments.artifacts_folder = path.map(Path::to_path_buf);
         x
     }
+    /// Set the maximum complexity of the tested inputs, whether they are generated from scratch or mutated.
+    ///
+    /// The inputs generated from scratch are also limited to this complexity, unless a lower
+    /// [`maximum_arbitrary_complexity`](FuzzerBuilder5::maximum_arbitrary_complexity) is given.
     #[must_use]
     #[coverage(off)]
     pub fn maximum_complexity(self, max_input_cplx: f64) -> Self {
         let mut x = self;
         x.arguments.max_input_cplx = max_input_cplx;
+        x.arguments.max_arbitrary_cplx = max_input_cplx;
+        x
+    }
+    /// Set the maximum complexity of the inputs generated from scratch.
+    ///
+    /// Mutated inputs can still grow up to the [`maximum_complexity`](FuzzerBuilder5::maximum_complexity).
+    /// It can be called before or after `maximum_complexity`, and is reduced to the maximum complexity if it
+    /// is higher when the fuzz test is launched.
+    #[must_use]
+    #[coverage(off)]
+    pub fn maximum_arbitrary_complexity(self, max_arbitrary_cplx: f64) -> Self {
+        let mut x = self;
+        x.max_arbitrary_cplx = Some(max_arbitrary_cplx);
         x
     }
     #[must_use]
@@ -648,6 +1162,15 @@ where
         x.arguments.maximum_duration = duration;
         x
     }
+    /// Stop the fuzzer once no test case was added to or removed from the corpus for the given number
+    /// of iterations or duration. See [`Arguments::stop_after_no_progress`].
+    #[must_use]
+    #[coverage(off)]
+    pub fn stop_after_no_progress(self, budget: NoProgressBudget) -> Self {
+        let mut x = self;
+        x.arguments.stop_after_no_progress = Some(budget);
+        x
+    }
     #[must_use]
     #[coverage(off)]
     pub fn stop_after_first_test_failure(self, stop_after_first_test_failure: bool) -> Self {
@@ -655,6 +1178,111 @@ where
         x.arguments.stop_after_first_failure = stop_after_first_test_failure;
         x
     }
+    /// Choose what happens after the fuzzer stops.
+    ///
+    /// With [`ExitCodePolicy::Exit`], [`launch`](FuzzerBuilder5::launch) never returns. Instead, the process
+    /// exits with code 0 if no test failure was found and 2 otherwise.
+    #[must_use]
+    #[coverage(off)]
+    pub fn exit_code_policy(self, exit_code_policy: ExitCodePolicy) -> Self {
+        let mut x = self;
+        x.arguments.exit_code_policy = exit_code_policy;
+        x
+    }
+    /// Choose how the files of the output corpus and the artifacts are named.
+    ///
+    /// By default, they are named after the hash of their content, see [`CorpusNamingPolicy`].
+    #[must_use]
+    #[coverage(off)]
+    pub fn corpus_naming_policy(self, corpus_naming: CorpusNamingPolicy) -> Self {
+        let mut x = self;
+        x.arguments.corpus_naming = corpus_naming;
+        x
+    }
+    /// Only fully analyze the observations of the sensor every `n` iterations, and skip the observations that
+    /// were seen before in between. See [`Arguments::observation_sampling`].
+    ///
+    /// The number of skipped observations is reported in the statistics of the fuzzer.
+    #[must_use]
+    #[coverage(off)]
+    pub fn observation_sampling(self, n: usize) -> Self {
+        assert!(n > 0, "the observation sampling rate must be strictly positive");
+        let mut x = self;
+        x.arguments.observation_sampling = n;
+        x
+    }
+    /// Run each test case in a child process created by `fork` (Unix only).
+    ///
+    /// This is useful when the test function modifies global state that cannot be reset, since
+    /// each test case then starts from the state of the fuzzer. Crashes and timeouts are reported
+    /// as test failures instead of stopping the fuzzer. However, running a test case becomes much
+    /// slower, and only the code coverage counters are sent back from the child process: custom
+    /// sensors must keep their state in memory shared with the child, for example with
+    /// [`anonymous_shared_counters`](crate::subprocess::anonymous_shared_counters).
+    #[must_use]
+    #[coverage(off)]
+    pub fn fork(self, fork: bool) -> Self {
+        let mut x = self;
+        x.arguments.fork = fork;
+        x
+    }
+    /// Also read the files of the input corpus with the extension `serializer.extension()` using the given serializer.
+    ///
+    /// By default, the fuzzer only reads the files whose extension matches the one of the main serializer
+    /// and skips the other ones. The main serializer is still used to save test cases to the file system.
+    /// This is useful to keep using an input corpus written in a different format. Alternatively, a
+    /// [`MultiSerializer`](crate::serializers::MultiSerializer) can be used as the main serializer.
+    #[must_use]
+    #[coverage(off)]
+    pub fn also_read_corpus_with<S>(self, serializer: S) -> Self
+    where
+        S: Serializer<Value = V> + 'static,
+    {
+        let mut x = self;
+        x.corpus_readers.push(Box::new(serializer));
+        x
+    }
+    /// Test the given values before fuzzing starts, in addition to the test cases of the input corpus.
+    ///
+    /// This is a way to provide known-interesting seeds directly as Rust values instead of saving
+    /// them to an input corpus folder first. The values that are rejected by
+    /// [`mutator.validate_value(..)`](crate::Mutator::validate_value) are skipped. The other ones are
+    /// processed by the pool like the test cases of the input corpus, and they are saved to the
+    /// output corpus using the serializer if the pool keeps them.
+    ///
+    /// Calling this method several times replaces the previous initial inputs.
+    #[must_use]
+    #[coverage(off)]
+    pub fn initial_inputs(self, inputs: Vec<V>) -> Self {
+        let mut x = self;
+        x.initial_inputs = inputs;
+        x
+    }
+    /// Call the given function before every execution of the test function.
+    ///
+    /// It is called outside of the region whose code coverage is observed by the sensors, so it can be used to
+    /// reset some global state or clear a cache without influencing the pool. Calling this method several
+    /// times replaces the previous function.
+    #[must_use]
+    #[coverage(off)]
+    pub fn before_each(self, f: impl Fn(&V) + 'static) -> Self {
+        let mut x = self;
+        x.hooks.before_each = Some(Box::new(f));
+        x
+    }
+    /// Call the given function after every execution of the test function, with the test case and
+    /// whether the test succeeded.
+    ///
+    /// Like [`before_each`](FuzzerBuilder5::before_each), it is called outside of the region whose code
+    /// coverage is observed by the sensors, which makes it suitable to gather domain-specific metrics.
+    /// Calling this method several times replaces the previous function.
+    #[must_use]
+    #[coverage(off)]
+    pub fn after_each(self, f: impl Fn(&V, bool) + 'static) -> Self {
+        let mut x = self;
+        x.hooks.after_each = Some(Box::new(f));
+        x
+    }
     /// Launch the fuzz test!
     #[coverage(off)]
     pub fn launch(self) -> FuzzingResult<V> {
@@ -662,24 +1290,179 @@ where
             test_function,
             mutator,
             serializer,
+            corpus_readers,
+            initial_inputs,
+            hooks,
             pool,
             sensor,
             arguments,
+            max_arbitrary_cplx,
             _phantom,
         } = self;
+        let arguments = resolve_complexity_limits(arguments, max_arbitrary_cplx);
 
         mutator.initialize();
 
         crate::fuzzer::launch(
             Box::new(test_function),
+            hooks,
+            mutator,
+            serializer,
+            corpus_readers,
+            initial_inputs,
+            Box::new((sensor, pool)),
+            arguments,
+        )
+    }
+
+    /// Build a [`Fuzzer`] that is driven one test case at a time, instead of launching the fuzz test.
+    ///
+    /// This is useful to embed fuzzcheck into another program, for example to interleave the fuzzer
+    /// with other work or to inspect its corpus and statistics while it runs. The fuzzer behaves
+    /// like the `fuzz` command of [`launch`](FuzzerBuilder5::launch), and its signal handlers are
+    /// installed until it is dropped.
+    /// ```no_run
+    /// # fn test_function(x: &u8) {}
+    /// let mut fuzzer = fuzzcheck::fuzz_test(test_function)
+    ///     .default_options()
+    ///     .build();
+    /// while let Ok(step) = fuzzer.step() {
+    ///     if step.nbr_added > 0 {
+    ///         println!("{} test cases were run", fuzzer.stats().total_number_of_runs);
+    ///     }
+    /// }
+    /// ```
+    #[coverage(off)]
+    pub fn build(self) -> Fuzzer<V, M> {
+        let FuzzerBuilder5 {
+            test_function,
+            mutator,
+            serializer,
+            corpus_readers,
+            initial_inputs,
+            hooks,
+            pool,
+            sensor,
+            arguments,
+            max_arbitrary_cplx,
+            _phantom,
+        } = self;
+        let arguments = resolve_complexity_limits(arguments, max_arbitrary_cplx);
+
+        mutator.initialize();
+
+        Fuzzer::for_fuzz_command(
+            Box::new(test_function),
+            hooks,
+            mutator,
+            serializer,
+            corpus_readers,
+            initial_inputs,
+            Box::new((sensor, pool)),
+            arguments,
+        )
+    }
+
+    /// Launch a bounded fuzz test and return the simplest failing test case that could be found, if any.
+    ///
+    /// This works like a property-based test with shrinking: fuzzcheck first looks for a failing test
+    /// case for at most [`stop_after_iterations(..)`](FuzzerBuilder5::stop_after_iterations) iterations.
+    /// If it finds one, it then tries to minify it, using the same iteration budget for each minifying round.
+    ///
+    /// Unlike [`launch`](FuzzerBuilder5::launch), the fuzzer always stops after the first test failure and
+    /// the result is the counterexample itself. Combined with [`Arguments::for_property_test`], nothing is
+    /// written to the file system, which makes it suitable for use inside a regular `#[test]`.
+    #[coverage(off)]
+    pub fn launch_property_test(self) -> Option<V> {
+        let FuzzerBuilder5 {
+            test_function,
+            mutator,
+            serializer,
+            corpus_readers,
+            initial_inputs,
+            hooks,
+            pool,
+            sensor,
+            arguments,
+            max_arbitrary_cplx,
+            _phantom,
+        } = self;
+        let arguments = resolve_complexity_limits(arguments, max_arbitrary_cplx);
+
+        mutator.initialize();
+
+        crate::fuzzer::launch_property_test(
+            Box::new(test_function),
+            hooks,
             mutator,
             serializer,
+            corpus_readers,
+            initial_inputs,
             Box::new((sensor, pool)),
             arguments,
         )
     }
 }
 
+/// Replace the maximum complexity of the arbitrary inputs by the one given to the builder, if any, and reduce it
+/// to the maximum complexity of all inputs
+#[coverage(off)]
+fn resolve_complexity_limits(mut arguments: Arguments, max_arbitrary_cplx: Option<f64>) -> Arguments {
+    if let Some(max_arbitrary_cplx) = max_arbitrary_cplx {
+        arguments.max_arbitrary_cplx = max_arbitrary_cplx;
+    }
+    arguments.max_arbitrary_cplx = arguments.max_arbitrary_cplx.min(arguments.max_input_cplx);
+    arguments
+}
+
+/**
+    Run a bounded fuzz test on the given function and return its simplest counterexample, if any.
+
+    This is a shortcut for:
+    ```no_run
+    # use fuzzcheck::Arguments;
+    # fn test_function(x: &bool) {}
+    # let maximum_iterations = 10_000;
+    let counterexample = fuzzcheck::fuzz_test(test_function)
+        .default_mutator()
+        .serde_serializer()
+        .default_sensor_and_pool()
+        .arguments(Arguments::for_property_test(maximum_iterations))
+        .launch_property_test();
+    ```
+    See [`FuzzerBuilder5::launch_property_test`] for more details. No folder is created or read from the file system.
+
+    Note that the default sensor observes the code coverage of the test function, so the test must still
+    be compiled with `-C instrument-coverage`, which `cargo fuzzcheck` does automatically. To run
+    a property test without code coverage instrumentation, use the builder with a different sensor.
+*/
+#[cfg(feature = "serde_json_serializer")]
+#[doc(cfg(feature = "serde_json_serializer"))]
+#[coverage(off)]
+pub fn test_once<T, F, TestFunctionKind>(test_function: F, maximum_iterations: usize) -> Option<T::Owned>
+where
+    T: ?Sized + ToOwned + 'static,
+    T::Owned: Clone + serde::Serialize + for<'e> serde::Deserialize<'e> + DefaultMutator,
+    <T::Owned as DefaultMutator>::Mutator: 'static,
+    F: FuzzTestFunction<T::Owned, T, TestFunctionKind>,
+    F::NormalizedFunction: 'static,
+{
+    let mutator = <T::Owned as DefaultMutator>::default_mutator();
+    mutator.initialize();
+    let (sensor, pool) = default_sensor_and_pool().finish();
+
+    crate::fuzzer::launch_property_test(
+        Box::new(test_function.test_function()),
+        TestHooks::default(),
+        mutator,
+        Box::new(SerdeSerializer::<T::Owned>::default()),
+        vec![],
+        vec![],
+        Box::new((sensor, pool)),
+        Arguments::for_property_test(maximum_iterations),
+    )
+}
+
 pub type BasicSensor = CodeCoverageSensor;
 pub type DiverseSensor = impl WrapperSensor<
     Wrapped = CodeCoverageSensor,
@@ -753,7 +1536,10 @@ pub fn max_cov_hits_sensor_and_pool() -> SensorAndPoolBuilder<MaxHitsSensor, Max
 /// on the result to augment the pool. Or use [`.finish()`](SensorAndPoolBuilder::finish) to obtain the concrete sensor and pool.
 #[coverage(off)]
 pub fn basic_sensor_and_pool() -> SensorAndPoolBuilder<BasicSensor, BasicPool> {
-    let sensor = CodeCoverageSensor::observing_only_files_from_current_dir();
+    let sensor = code_coverage_sensor_from_cargo_fuzzcheck(
+        #[coverage(off)]
+        |file, _function| file.is_relative(),
+    );
     let nbr_counters = sensor.count_instrumented;
     SensorAndPoolBuilder {
         sensor,
@@ -767,7 +1553,7 @@ pub fn basic_sensor_and_pool() -> SensorAndPoolBuilder<BasicSensor, BasicPool> {
 pub fn basic_sensor_and_pool_with_custom_filter(
     keep: impl Fn(&Path, &str) -> bool,
 ) -> SensorAndPoolBuilder<BasicSensor, BasicPool> {
-    let sensor = CodeCoverageSensor::new(keep);
+    let sensor = code_coverage_sensor_from_cargo_fuzzcheck(keep);
     let nbr_counters = sensor.count_instrumented;
     SensorAndPoolBuilder {
         sensor,
@@ -775,6 +1561,44 @@ pub fn basic_sensor_and_pool_with_custom_filter(
     }
 }
 
+/// The arguments given by `cargo fuzzcheck` to the fuzz test, if it was launched by it and they are valid.
+///
+/// Invalid arguments are reported by [`arguments_from_cargo_fuzzcheck`](FuzzerBuilder4::arguments_from_cargo_fuzzcheck).
+#[coverage(off)]
+fn cargo_fuzzcheck_arguments() -> Option<Arguments> {
+    let arguments = std::env::var("FUZZCHECK_ARGS").ok()?;
+    let arguments = split_string_by_whitespace(&arguments);
+    let matches = options_parser().parse(arguments).ok()?;
+    Arguments::from_matches(&matches, false).ok()
+}
+
+/// Create a code coverage sensor observing the functions selected by `keep`, as further restricted by the
+/// `--coverage-include` and `--coverage-exclude` arguments of `cargo fuzzcheck`.
+///
+/// This is done when the sensor is created because the fuzzer’s arguments are given to the builder
+/// only after the sensor. If `--print-coverage-functions` was given, the observed files and functions are
+/// printed and the process exits.
+#[coverage(off)]
+fn code_coverage_sensor_from_cargo_fuzzcheck(keep: impl Fn(&Path, &str) -> bool) -> CodeCoverageSensor {
+    let arguments = cargo_fuzzcheck_arguments();
+    let filter = arguments
+        .as_ref()
+        .map(CoverageFilter::from_arguments)
+        .unwrap_or_default();
+    let sensor = CodeCoverageSensor::new(filter.apply(keep));
+    if arguments.is_some_and(
+        #[coverage(off)]
+        |arguments| arguments.print_coverage_functions,
+    ) {
+        println!("Observed files:");
+        sensor.print_observed_files();
+        println!("\nObserved functions:");
+        sensor.print_observed_functions();
+        terminate(TerminationStatus::Success);
+    }
+    sensor
+}
+
 /// Create the [sensor and pool builder](SensorAndPoolBuilder) that is used by default by fuzzcheck
 ///
 /// Currently, the result cannot be augmented any further. Thus, the only action you can take on the result is to
@@ -797,6 +1621,17 @@ pub fn default_sensor_and_pool_with_custom_filter(
         .find_test_cases_repeatedly_hitting_coverage_counters()
 }
 
+/// Create a [sensor and pool builder](SensorAndPoolBuilder) made of an [`AllocationSensor`] and a [`MaximiseAllocationsPool`]
+///
+/// See [`FuzzerBuilder3::allocation_sensor_and_pool`] for more details.
+#[coverage(off)]
+pub fn allocation_sensor_and_pool() -> SensorAndPoolBuilder<AllocationSensor, MaximiseAllocationsPool> {
+    SensorAndPoolBuilder {
+        sensor: AllocationSensor::default(),
+        pool: MaximiseAllocationsPool::new("max_allocations"),
+    }
+}
+
 /// A builder to create a [sensor](Sensor) and [pool](crate::Pool) that can be given as argument to
 /// [`FuzzerBuilder3::sensor_and_pool`].
 ///
@@ -831,6 +1666,45 @@ where
 }
 
 impl SensorAndPoolBuilder<BasicSensor, BasicPool> {
+    /// Weight the code coverage counters of each function by the given closure, such that the pool favours
+    /// the test cases making progress in the code that matters most.
+    ///
+    /// The closure is given the path of the file and the demangled name of each observed function, and returns
+    /// the weight of its counters, which is `1.0` by default. For example, the following gives ten times more
+    /// importance to the parser of the crate:
+    /// ```no_run
+    /// use fuzzcheck::builder::basic_sensor_and_pool;
+    /// let (sensor, pool) = basic_sensor_and_pool()
+    ///     .weight_coverage(|file, _function| if file.starts_with("src/parser") { 10.0 } else { 1.0 })
+    ///     .finish();
+    /// ```
+    /// See [`SimplestToActivateCounterPool::with_counter_weights`].
+    #[must_use]
+    #[coverage(off)]
+    pub fn weight_coverage(self, weight: impl Fn(&Path, &str) -> f64) -> Self {
+        let weights = self.sensor.counter_weights(weight);
+        let mut x = self;
+        x.pool = x.pool.with_counter_weights(weights);
+        x
+    }
+    /// Keep at most `max_size` test cases in the main pool, which otherwise grows with the number of code coverage
+    /// counters activated during the fuzzing session. The test cases exceeding that size are evicted from the pool,
+    /// and from the output corpus, according to the given policy:
+    /// ```no_run
+    /// use fuzzcheck::builder::basic_sensor_and_pool;
+    /// use fuzzcheck::sensors_and_pools::EvictionPolicy;
+    /// let (sensor, pool) = basic_sensor_and_pool()
+    ///     .max_pool_size(1000, EvictionPolicy::LowestScore)
+    ///     .finish();
+    /// ```
+    /// See [`SimplestToActivateCounterPool::with_max_size`].
+    #[must_use]
+    #[coverage(off)]
+    pub fn max_pool_size(self, max_size: usize, policy: EvictionPolicy) -> Self {
+        let mut x = self;
+        x.pool = x.pool.with_max_size(max_size, policy);
+        x
+    }
     /// Augment the current pool such that it also tries to find a fixed-length set of test cases which, together,
     /// trigger the most code coverage.
     ///
@@ -939,3 +1813,66 @@ impl SensorAndPoolBuilder<DiverseSensor, BasicAndDiversePool> {
         SensorAndPoolBuilder { sensor, pool }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+
+    /// The maximum complexities of all inputs and of the arbitrary inputs given by the builder to the fuzzer
+    #[coverage(off)]
+    fn complexity_limits<F, M, V, Sens, P>(builder: FuzzerBuilder5<F, M, V, Sens, P>) -> (f64, f64)
+    where
+        F: Fn(&V) -> bool + 'static,
+        V: Clone + 'static,
+        M: Mutator<V>,
+        Sens: Sensor,
+        P: CompatibleWithObservations<Sens::Observations>,
+    {
+        let arguments = resolve_complexity_limits(builder.arguments, builder.max_arbitrary_cplx);
+        (arguments.max_input_cplx, arguments.max_arbitrary_cplx)
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_complexity_limits() {
+        let builder = |arguments: Arguments| {
+            fuzz_test(
+                #[coverage(off)]
+                |_: &Vec<u8>| true,
+            )
+            .default_mutator()
+            .serde_serializer()
+            .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+            .arguments(arguments)
+        };
+        let default_arguments = || Arguments::for_property_test(1000);
+        // the order in which the limits are given does not matter
+        let limits = complexity_limits(
+            builder(default_arguments())
+                .maximum_arbitrary_complexity(50.)
+                .maximum_complexity(1000.),
+        );
+        assert_eq!(limits, (1000., 50.));
+        let limits = complexity_limits(
+            builder(default_arguments())
+                .maximum_complexity(1000.)
+                .maximum_arbitrary_complexity(50.),
+        );
+        assert_eq!(limits, (1000., 50.));
+        // without an arbitrary limit, the maximum complexity is used
+        let limits = complexity_limits(builder(default_arguments()).maximum_complexity(1000.));
+        assert_eq!(limits, (1000., 1000.));
+        // the arbitrary limit cannot be higher than the maximum complexity
+        let limits = complexity_limits(
+            builder(default_arguments())
+                .maximum_arbitrary_complexity(1000.)
+                .maximum_complexity(50.),
+        );
+        assert_eq!(limits, (50., 50.));
+        let mut arguments = default_arguments();
+        arguments.max_input_cplx = 50.;
+        arguments.max_arbitrary_cplx = 1000.;
+        assert_eq!(complexity_limits(builder(arguments)), (50., 50.));
+    }
+}
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use fuzzcheck_common::arg::Arguments;
+
+/// Glob patterns selecting the functions observed by a [`CodeCoverageSensor`](super::CodeCoverageSensor).
+///
+/// They are typically given by the `--coverage-include` and `--coverage-exclude` flags of `cargo fuzzcheck`,
+/// which the default sensors read automatically. A pattern matches either the path of a file or the demangled
+/// name of a function. In a pattern, `?` matches any character except `/`, `*` matches any sequence of characters
+/// except `/`, and `**` matches any sequence of characters.
+///
+/// ```no_run
+/// use fuzzcheck::sensors_and_pools::{CodeCoverageSensor, CoverageFilter};
+/// let filter = CoverageFilter {
+///     include: vec!["src/parser/**".to_owned(), "my_crate::lexer::*".to_owned()],
+///     exclude: vec!["**/tests.rs".to_owned()],
+/// };
+/// let sensor = CodeCoverageSensor::new(filter.apply(|file, _function| file.is_relative()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CoverageFilter {
+    /// If it is not empty, only the functions that match one of these patterns are observed
+    pub include: Vec<String>,
+    /// The functions that match one of these patterns are never observed
+    pub exclude: Vec<String>,
+}
+
+impl CoverageFilter {
+    /// The filter given by the `coverage_include` and `coverage_exclude` arguments
+    #[coverage(off)]
+    pub fn from_arguments(arguments: &Arguments) -> Self {
+        Self {
+            include: arguments.coverage_include.clone(),
+            exclude: arguments.coverage_exclude.clone(),
+        }
+    }
+
+    /// Combine the filter with the `keep` function, which decides which functions are observed when
+    /// there are no `include` patterns
+    #[coverage(off)]
+    pub fn apply<K>(self, keep: K) -> impl Fn(&Path, &str) -> bool
+    where
+        K: Fn(&Path, &str) -> bool,
+    {
+        #[coverage(off)]
+        move |file, function| {
+            let matches = #[coverage(off)]
+            |patterns: &[String]| {
+                let file = file.to_string_lossy();
+                patterns.iter().any(
+                    #[coverage(off)]
+                    |pattern| glob_match(pattern, &file) || glob_match(pattern, function),
+                )
+            };
+            let included = if self.include.is_empty() {
+                keep(file, function)
+            } else {
+                matches(&self.include)
+            };
+            included && !matches(&self.exclude)
+        }
+    }
+}
+
+/// Whether the text is matched by the glob pattern, where `?` matches any character except `/`,
+/// `*` any sequence of characters except `/`, and `**` any sequence of characters
+#[coverage(off)]
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    glob_match_chars(&pattern, &text)
+}
+
+#[coverage(off)]
+fn glob_match_chars(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => (0..=text.len()).any(
+            #[coverage(off)]
+            |i| glob_match_chars(rest, &text[i..]),
+        ),
+        ['*', rest @ ..] => {
+            let len_segment = text
+                .iter()
+                .position(
+                    #[coverage(off)]
+                    |&c| c == '/',
+                )
+                .unwrap_or(text.len());
+            (0..=len_segment).any(
+                #[coverage(off)]
+                |i| glob_match_chars(rest, &text[i..]),
+            )
+        }
+        ['?', rest @ ..] => matches!(text, [c, ..] if *c != '/') && glob_match_chars(rest, &text[1..]),
+        [p, rest @ ..] => matches!(text, [c, ..] if c == p) && glob_match_chars(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_glob_match() {
+        assert!(glob_match("src/parser/**", "src/parser/mod.rs"));
+        assert!(glob_match("src/parser/**", "src/parser/expr/binary.rs"));
+        assert!(!glob_match("src/parser/**", "src/lexer.rs"));
+        assert!(glob_match("src/*.rs", "src/lib.rs"));
+        assert!(!glob_match("src/*.rs", "src/parser/mod.rs"));
+        assert!(glob_match("**/tests.rs", "src/parser/tests.rs"));
+        assert!(glob_match("src/l?b.rs", "src/lib.rs"));
+        assert!(!glob_match("src/l?b.rs", "src/lb.rs"));
+        assert!(glob_match("my_crate::parser::*", "my_crate::parser::parse_expr"));
+        assert!(glob_match("my_crate::parser::*", "my_crate::parser::Parser::new"));
+        assert!(!glob_match("my_crate::parser::*", "my_crate::lexer::lex"));
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_coverage_filter() {
+        let keep_relative = |file: &Path, _function: &str| file.is_relative();
+
+        let filter = CoverageFilter::default().apply(keep_relative);
+        assert!(filter(Path::new("src/lib.rs"), "my_crate::f"));
+        assert!(!filter(
+            Path::new("/rustc/library/core/src/option.rs"),
+            "core::option::f"
+        ));
+
+        let filter = CoverageFilter {
+            include: vec!["src/parser/**".to_owned(), "core::option::*".to_owned()],
+            exclude: vec!["**/tests.rs".to_owned()],
+        }
+        .apply(keep_relative);
+        assert!(filter(Path::new("src/parser/mod.rs"), "my_crate::parser::parse"));
+        assert!(!filter(Path::new("src/lexer.rs"), "my_crate::lexer::lex"));
+        assert!(!filter(Path::new("src/parser/tests.rs"), "my_crate::parser::tests::t"));
+        // the include patterns replace the default `keep` function
+        assert!(filter(
+            Path::new("/rustc/library/core/src/option.rs"),
+            "core::option::f"
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+//! A reader of the line tables of the DWARF debugging information, which give the source file of each instruction
+//! of the program.
+//!
+//! They are used to know the files of the functions instrumented by SanitizerCoverage, which, unlike the LLVM
+//! coverage instrumentation, does not record them. The line tables are read with `gimli`, and only the files of
+//! the instructions are kept, not their lines or columns.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use gimli::{AttributeValue, Dwarf, LineProgramHeader, Reader, Unit};
+
+/// The file of each range of addresses of the program
+#[derive(Debug, Default)]
+pub struct LineTable {
+    files: Vec<PathBuf>,
+    /// The address at which each row starts, sorted, and the index of its file in `files`, or `None` for the end
+    /// of a sequence of instructions or a file that is not in the file table of the unit
+    rows: Vec<(u64, Option<usize>)>,
+}
+
+impl LineTable {
+    /// Read the line tables of all the compilation units of the program.
+    ///
+    /// Returns `None` if they are not valid.
+    #[coverage(off)]
+    pub fn parse<R: Reader>(dwarf: &Dwarf<R>) -> Option<Self> {
+        let mut table = LineTable::default();
+        let mut units = dwarf.units();
+        while let Some(header) = units.next().ok()? {
+            let unit = dwarf.unit(header).ok()?;
+            table.parse_unit(dwarf, &unit)?;
+        }
+        table.rows.sort_by_key(
+            #[coverage(off)]
+            |&(address, _)| address,
+        );
+        Some(table)
+    }
+
+    #[coverage(off)]
+    fn parse_unit<R: Reader>(&mut self, dwarf: &Dwarf<R>, unit: &Unit<R>) -> Option<()> {
+        let Some(program) = unit.line_program.clone() else {
+            return Some(());
+        };
+        // The compiler writes the paths relative to the compilation directory when they are inside it, so they
+        // are resolved against it only if the main file of the unit was given as an absolute path. The paths
+        // are then the same as those given to the compiler.
+        let compilation_dir = unit
+            .name
+            .as_ref()
+            .and_then(
+                #[coverage(off)]
+                |name| name.to_string_lossy().ok(),
+            )
+            .filter(
+                #[coverage(off)]
+                |name| Path::new(name.as_ref()).is_absolute(),
+            )
+            .and_then(
+                #[coverage(off)]
+                |_| {
+                    let compilation_dir = unit.comp_dir.as_ref()?.to_string_lossy().ok()?;
+                    Some(PathBuf::from(compilation_dir.as_ref()))
+                },
+            );
+
+        // the index in `self.files` of each file index of the unit
+        let mut file_indices = HashMap::new();
+        // whether the current sequence is ignored, which is the case of the sequences starting at address 0, that
+        // belong to functions removed by the linker
+        let mut ignored_sequence = None;
+        // the address of the previous row of the current sequence
+        let mut previous_address = None;
+        let mut rows = program.rows();
+        while let Some((header, row)) = rows.next_row().ok()? {
+            let ignored = *ignored_sequence.get_or_insert(row.address() == 0);
+            if row.end_sequence() {
+                if !ignored {
+                    self.rows.push((row.address(), None));
+                }
+                ignored_sequence = None;
+                previous_address = None;
+                continue;
+            }
+            // the following rows at the same address are the instructions inlined at the start of the
+            // first one, which gives the file of the address
+            if ignored || previous_address.replace(row.address()) == Some(row.address()) {
+                continue;
+            }
+            let file = match file_indices.get(&row.file_index()) {
+                Some(&file) => file,
+                None => {
+                    let file = file_path(dwarf, unit, header, row.file_index(), compilation_dir.as_deref()).map(
+                        #[coverage(off)]
+                        |path| {
+                            self.files.push(path);
+                            self.files.len() - 1
+                        },
+                    );
+                    file_indices.insert(row.file_index(), file);
+                    file
+                }
+            };
+            self.rows.push((row.address(), file));
+        }
+        Some(())
+    }
+
+    /// The file of the instruction at the given address
+    #[coverage(off)]
+    pub fn file(&self, address: u64) -> Option<&Path> {
+        let index = self.rows.partition_point(
+            #[coverage(off)]
+            |&(row_address, _)| row_address <= address,
+        );
+        let (_, file) = self.rows.get(index.checked_sub(1)?)?;
+        self.files.get((*file)?).map(PathBuf::as_path)
+    }
+}
+
+/// The path of the file of the given index in the file table of the unit
+///
+/// The compilation directory (the directory 0) is left implicit, and the path is resolved against
+/// `compilation_dir` only if it is relative.
+#[coverage(off)]
+fn file_path<R: Reader>(
+    dwarf: &Dwarf<R>,
+    unit: &Unit<R>,
+    header: &LineProgramHeader<R>,
+    file_index: u64,
+    compilation_dir: Option<&Path>,
+) -> Option<PathBuf> {
+    let attr_string = #[coverage(off)]
+    |attr: AttributeValue<R>| -> Option<String> {
+        let string = dwarf.attr_string(unit, attr).ok()?;
+        Some(string.to_string_lossy().ok()?.into_owned())
+    };
+    let file = header.file(file_index)?;
+    let name = attr_string(file.path_name())?;
+    let directory = if file.directory_index() == 0 {
+        None
+    } else {
+        file.directory(header).and_then(attr_string)
+    };
+    let path = Path::new(&directory.unwrap_or_default()).join(name);
+    match compilation_dir {
+        Some(compilation_dir) if path.is_relative() => Some(compilation_dir.join(path)),
+        _ => Some(path),
+    }
+}
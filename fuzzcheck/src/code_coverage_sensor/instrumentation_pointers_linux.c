@@ -1,13 +1,15 @@
 int __llvm_profile_runtime = 0;
 
-extern unsigned long int __start___llvm_prf_cnts;
-extern unsigned long int __stop___llvm_prf_cnts;
+// The sections are missing when the program is not compiled with -C instrument-coverage,
+// for example when SanitizerCoverage is used instead, in which case these symbols are null.
+extern unsigned long int __start___llvm_prf_cnts __attribute__((weak));
+extern unsigned long int __stop___llvm_prf_cnts __attribute__((weak));
 
-extern char __start___llvm_prf_data;
-extern char __stop___llvm_prf_data;
+extern char __start___llvm_prf_data __attribute__((weak));
+extern char __stop___llvm_prf_data __attribute__((weak));
 
-extern char __start___llvm_prf_names;
-extern char __stop___llvm_prf_names;
+extern char __start___llvm_prf_names __attribute__((weak));
+extern char __stop___llvm_prf_names __attribute__((weak));
 
 char *get_start_prf_data()
 {
@@ -0,0 +1,53 @@
+// On Windows, there are no linker-defined symbols marking the start and end of a section.
+// Instead, the linker sorts the sections named `.name$suffix` alphabetically by suffix and
+// merges them. LLVM places its profile data in the `$M` sections, so variables placed in the
+// `$A` and `$Z` sections delimit it.
+
+#ifdef _MSC_VER
+#pragma section(".lprfc$A", read, write)
+#pragma section(".lprfc$Z", read, write)
+#pragma section(".lprfd$A", read, write)
+#pragma section(".lprfd$Z", read, write)
+#pragma section(".lprfn$A", read)
+#pragma section(".lprfn$Z", read)
+#define FUZZCHECK_SECTION(name) __declspec(allocate(name))
+#else
+#define FUZZCHECK_SECTION(name) __attribute__((section(name)))
+#endif
+
+int __llvm_profile_runtime = 0;
+
+FUZZCHECK_SECTION(".lprfc$A") unsigned long long __start___llvm_prf_cnts = 0;
+FUZZCHECK_SECTION(".lprfc$Z") unsigned long long __stop___llvm_prf_cnts = 0;
+
+FUZZCHECK_SECTION(".lprfd$A") char __start___llvm_prf_data = 0;
+FUZZCHECK_SECTION(".lprfd$Z") char __stop___llvm_prf_data = 0;
+
+FUZZCHECK_SECTION(".lprfn$A") const char __start___llvm_prf_names = 0;
+FUZZCHECK_SECTION(".lprfn$Z") const char __stop___llvm_prf_names = 0;
+
+char *get_start_prf_data()
+{
+    return &__start___llvm_prf_data + 1;
+}
+char *get_end_prf_data()
+{
+    return &__stop___llvm_prf_data;
+}
+char *get_start_prf_names()
+{
+    return (char *)&__start___llvm_prf_names + 1;
+}
+char *get_end_prf_names()
+{
+    return (char *)&__stop___llvm_prf_names;
+}
+
+unsigned long long *get_start_instrumentation_counters()
+{
+    return &__start___llvm_prf_cnts + 1;
+}
+unsigned long long *get_end_instrumentation_counters()
+{
+    return &__stop___llvm_prf_cnts;
+}
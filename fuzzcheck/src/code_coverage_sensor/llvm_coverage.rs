@@ -22,6 +22,10 @@ extern "C" {
 pub unsafe fn get_counters() -> &'static mut [u64] {
     let start = get_start_instrumentation_counters();
     let end = get_end_instrumentation_counters();
+    // the symbols are weak, and thus null if the program was not instrumented
+    if start.is_null() || end.is_null() {
+        return &mut [];
+    }
     let len = end.offset_from(start) as usize;
     std::slice::from_raw_parts_mut(start, len)
 }
@@ -40,6 +44,16 @@ pub unsafe fn get_prf_names() -> &'static [u8] {
     std::slice::from_raw_parts(start, len)
 }
 
+// The names of the sections containing the coverage map, which differ in COFF binaries
+#[cfg(windows)]
+const COVMAP_SECTION_NAME: &str = ".lcovmap$M";
+#[cfg(windows)]
+const COVFUN_SECTION_NAME: &str = ".lcovfun$M";
+#[cfg(not(windows))]
+const COVMAP_SECTION_NAME: &str = "__llvm_covmap";
+#[cfg(not(windows))]
+const COVFUN_SECTION_NAME: &str = "__llvm_covfun";
+
 pub struct LLVMCovSections {
     pub covfun: Vec<u8>,
     pub covmap: Vec<u8>,
@@ -61,7 +75,7 @@ pub fn get_llvm_cov_sections(path: &Path) -> Result<LLVMCovSections, ReadCovMapE
         },
     )?;
     let covmap = obj_file
-        .section_by_name("__llvm_covmap")
+        .section_by_name(COVMAP_SECTION_NAME)
         .ok_or(ReadCovMapError::CannotFindSection {
             section: CovMapSection::CovMap,
         })?
@@ -69,7 +83,7 @@ pub fn get_llvm_cov_sections(path: &Path) -> Result<LLVMCovSections, ReadCovMapE
         .unwrap()
         .to_vec();
     let covfun = obj_file
-        .section_by_name("__llvm_covfun")
+        .section_by_name(COVFUN_SECTION_NAME)
         .ok_or(ReadCovMapError::CannotFindSection {
             section: CovMapSection::CovFun,
         })?
@@ -929,6 +943,29 @@ pub struct Coverage {
     pub expression_counters: Vec<OptimisedExpandedExpression>,
 }
 
+/// Returns true if any of the counters is not zero.
+///
+/// The counters are read by chunks of 8, which are OR-ed together before being compared to zero.
+/// This lets the compiler vectorise the loop, which runs on every function of the program after
+/// each test case, even though most of them were not executed.
+#[coverage(off)]
+pub fn any_counter_is_set(counters: &[u64]) -> bool {
+    let mut chunks = counters.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut any = 0;
+        for &c in chunk {
+            any |= c;
+        }
+        if any != 0 {
+            return true;
+        }
+    }
+    chunks.remainder().iter().any(
+        #[coverage(off)]
+        |&c| c != 0,
+    )
+}
+
 impl Coverage {
     #[coverage(off)]
     pub fn new(
@@ -982,4 +1019,161 @@ impl Coverage {
             )
             .collect()
     }
+
+    /// Returns true if any counter of the function is not zero.
+    ///
+    /// When it is false, all the single counters and expression counters of the function are zero.
+    #[coverage(off)]
+    pub fn is_executed(&self) -> bool {
+        let counters = unsafe { std::slice::from_raw_parts(self.start_counters, self.counters_len) };
+        any_counter_is_set(counters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use test::Bencher;
+
+    use super::*;
+
+    const NBR_FUNCTIONS: usize = 20_000;
+
+    /// A program of `NBR_FUNCTIONS` functions of 10 to 50 counters each, where one function out of
+    /// `executed_every` has all its counters set
+    ///
+    /// Each counter of a function is a single counter, and one counter out of three is also part of
+    /// an expression.
+    #[coverage(off)]
+    fn program(executed_every: usize) -> Vec<Coverage> {
+        let mut function_records = vec![];
+        let mut prf_datas = vec![];
+        let mut all_counters = vec![];
+        for i in 0..NBR_FUNCTIONS {
+            let function_id = FunctionIdentifier {
+                name_md5: i as i64,
+                structural_hash: i as u64 + 1,
+            };
+            let number_of_counters = 10 + (i * 7) % 41;
+            let mut expressions = vec![];
+            for j in 0..number_of_counters {
+                let single = ExpandedExpression {
+                    add_terms: vec![j],
+                    sub_terms: vec![],
+                };
+                expressions.push((single, vec![]));
+                if j % 3 == 1 {
+                    let expression = ExpandedExpression {
+                        add_terms: vec![j - 1, j],
+                        sub_terms: vec![j - 1],
+                    };
+                    expressions.push((expression, vec![]));
+                }
+            }
+            function_records.push(FunctionRecord {
+                header: FunctionRecordHeader {
+                    id: function_id.clone(),
+                    hash_translation_unit: [0; 8],
+                    length_encoded_data: 0,
+                },
+                file_id_mapping: FileIDMapping {
+                    filename_indices: vec![],
+                },
+                expressions,
+                inferred_expressions: vec![],
+                name_function: format!("f{i}"),
+                filenames: vec![],
+            });
+            prf_datas.push(PrfData {
+                function_id,
+                number_of_counters,
+            });
+            let executed = (i + 1) % executed_every == 0;
+            all_counters.resize(all_counters.len() + number_of_counters, executed as u64);
+        }
+        Coverage::new(function_records, prf_datas, Box::leak(all_counters.into_boxed_slice())).unwrap()
+    }
+
+    /// The observations of the program, collected as `CodeCoverageSensor::get_observations` does
+    ///
+    /// When `skip_unexecuted` is false, every single counter and expression is read, as it was
+    /// before the functions whose counters are all zero were skipped.
+    #[coverage(off)]
+    fn collect_observations(coverage: &[Coverage], skip_unexecuted: bool) -> Vec<(usize, u64)> {
+        let mut observations = vec![];
+        let mut index = 0;
+        for coverage in coverage {
+            if skip_unexecuted && !coverage.is_executed() {
+                index += coverage.single_counters.len() + coverage.expression_counters.len();
+                continue;
+            }
+            for &single in coverage.single_counters.iter() {
+                let single = unsafe { *single };
+                if single != 0 {
+                    observations.push((index, single));
+                }
+                index += 1;
+            }
+            for expr in &coverage.expression_counters {
+                let computed = expr.compute();
+                if computed != 0 {
+                    observations.push((index, computed));
+                }
+                index += 1;
+            }
+        }
+        observations
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_skipping_unexecuted_functions_keeps_the_observations() {
+        for executed_every in [1, 100, NBR_FUNCTIONS + 1] {
+            let coverage = program(executed_every);
+            let observations = collect_observations(&coverage, true);
+            assert_eq!(observations, collect_observations(&coverage, false));
+            assert_eq!(observations.is_empty(), executed_every > NBR_FUNCTIONS);
+        }
+    }
+
+    #[bench]
+    #[coverage(off)]
+    fn bench_collect_few_executed_baseline(b: &mut Bencher) {
+        let coverage = program(100);
+        b.iter(
+            #[coverage(off)]
+            || collect_observations(test::black_box(&coverage), false),
+        );
+    }
+
+    #[bench]
+    #[coverage(off)]
+    fn bench_collect_few_executed_skipping(b: &mut Bencher) {
+        let coverage = program(100);
+        b.iter(
+            #[coverage(off)]
+            || collect_observations(test::black_box(&coverage), true),
+        );
+    }
+
+    #[bench]
+    #[coverage(off)]
+    fn bench_collect_all_executed_baseline(b: &mut Bencher) {
+        let coverage = program(1);
+        b.iter(
+            #[coverage(off)]
+            || collect_observations(test::black_box(&coverage), false),
+        );
+    }
+
+    #[bench]
+    #[coverage(off)]
+    fn bench_collect_all_executed_skipping(b: &mut Bencher) {
+        let coverage = program(1);
+        b.iter(
+            #[coverage(off)]
+            || collect_observations(test::black_box(&coverage), true),
+        );
+    }
 }
@@ -1,17 +1,61 @@
 //! Code coverage analysis
 
+mod coverage_filter;
+mod debug_line;
 mod leb128;
 mod llvm_coverage;
+mod sanitizer_coverage;
 #[cfg(feature = "serde_json_serializer")]
 mod serialized;
 
 use std::collections::{BTreeSet, HashMap};
 use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
+use ahash::AHasher;
+
+pub use self::coverage_filter::CoverageFilter;
 use self::llvm_coverage::{get_counters, get_prf_data, read_covmap, Coverage, LLVMCovSections};
+use self::sanitizer_coverage::{
+    get_sancov_counters, get_sancov_functions, is_fuzzcheck_file, push_nonzero_counters, SancovFunction,
+};
+use crate::observations_record::{decode_counters, encode_counters};
 use crate::traits::{SaveToStatsFolder, Sensor};
 
+/// The counters that are ignored by every `CodeCoverageSensor`, because they were found to be nondeterministic.
+///
+/// Each counter is identified by the address of the first counter of its function and by its position within
+/// them. Unlike the indices of the observations, these do not depend on the files and functions observed by
+/// each sensor.
+static IGNORED_COUNTERS: Mutex<BTreeSet<(usize, usize)>> = Mutex::new(BTreeSet::new());
+/// Incremented whenever `IGNORED_COUNTERS` changes, such that each sensor knows when to update its own list
+static IGNORED_COUNTERS_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// The part of the source code whose execution is counted by a code coverage counter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CounterLocation {
+    /// The path of the file containing the function, as given to the compiler
+    pub file: PathBuf,
+    /// The demangled name of the function
+    pub function: String,
+    /// The code regions counted by the counter. A counter often covers several disjoint regions of
+    /// the function, and it may also cover none of them.
+    pub regions: Vec<CodeRegion>,
+}
+
+/// A region of source code, from `(line_start, col_start)` to `(line_end, col_end)`. Lines and columns
+/// start at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CodeRegion {
+    pub line_start: usize,
+    pub col_start: usize,
+    pub line_end: usize,
+    pub col_end: usize,
+}
+
 /// A sensor that automatically records the code coverage of the program through an array of counters.
 ///
 /// This is the default sensor used by fuzzcheck. It can filter the recorded code coverage so that
@@ -26,9 +70,56 @@ use crate::traits::{SaveToStatsFolder, Sensor};
 /// use fuzzcheck::sensors_and_pools::CodeCoverageSensor;
 /// let sensor = CodeCoverageSensor::new(|file, _function| file.is_relative());
 /// ```
+///
+/// ### SanitizerCoverage
+///
+/// The code coverage is normally given by the LLVM coverage instrumentation (`-C instrument-coverage`),
+/// whose format can change between LLVM versions. If the program was instead compiled with the 8-bit counters
+/// of SanitizerCoverage, which `cargo fuzzcheck --coverage-backend sancov` does, they are used automatically.
+/// The files and names of the functions are then read from the debugging information of the program, so it
+/// must also be compiled with `-C llvm-args=-sanitizer-coverage-pc-table` and `-C debuginfo=line-tables-only`
+/// (or more), and without stripping its symbols. SanitizerCoverage does not know about `#[coverage(off)]`: the
+/// functions of fuzzcheck are always excluded, but the other functions with that attribute are not, so they must
+/// be excluded by the `keep` function. The counters are observed at the granularity of the edges of the control
+/// flow graph, and their code regions are not known.
+///
+/// ### Observations
+///
+/// The observations of the sensor are a list of `(index, value)` tuples, sorted by index, for each counter whose
+/// value is not zero. The indices are stable for the lifetime of the sensor, and they are the same for all sensors
+/// of the same program created with the same `keep` function. They range from `0` to
+/// [`count_instrumented`](Self::count_instrumented) (exclusive), and
+/// [`counter_locations`](CodeCoverageSensor::counter_locations) gives the part of the source code counted by each
+/// of them. The value of a counter is the number of times its code regions were executed, or an approximation of
+/// it with SanitizerCoverage, whose counters are only 8 bits wide.
+///
+/// Custom pools that handle these observations can implement [`CounterPool`](crate::sensors_and_pools::CounterPool).
+///
+/// ### Dead regions
+///
+/// With the `serde_json_serializer` feature, the sensor saves a `dead_regions.json` file to the stats folder,
+/// listing the code regions whose counters were never activated by any test case executed by the fuzzer,
+/// grouped by file and function. Targeted test cases can then be added to the input corpus to reach them.
+/// It is not available with SanitizerCoverage.
 pub struct CodeCoverageSensor {
     pub(crate) coverage: Vec<Coverage>,
+    // The functions instrumented by SanitizerCoverage, which are used instead of `coverage` when
+    // they are not empty
+    sancov_functions: Vec<SancovFunction>,
+    // The indices of the functions whose counters were not zero when the observations were last
+    // collected. Only their counters are reset before the next test case is run.
+    //
+    // Note that the counters cannot be compared against a baseline or an epoch instead of being reset,
+    // because the instrumented code increments them in place: computing the observations of a test case
+    // would then require reading and copying every counter, which is at least as costly as resetting the
+    // few that changed.
     needs_clearing: Vec<usize>,
+    // Whether each observed counter is ignored, or an empty vector if none of them are
+    ignored_counters: Vec<bool>,
+    ignored_counters_generation: usize,
+    // Whether each observed counter was activated by any test case since the sensor was created, including
+    // the ignored counters
+    hit_counters: Vec<bool>,
     /// The number of code regions observed by the sensor
     pub count_instrumented: usize,
 }
@@ -46,6 +137,39 @@ impl CodeCoverageSensor {
     where
         K: Fn(&Path, &str) -> bool,
     {
+        if !unsafe { get_sancov_counters() }.is_empty() {
+            let mut sancov_functions = unsafe { get_sancov_functions() }.unwrap_or_else(
+                #[coverage(off)]
+                |error| {
+                    panic!(
+                        "could not find the functions instrumented by SanitizerCoverage ({}), the program must be \
+                         compiled with `-C llvm-args=-sanitizer-coverage-pc-table` and `-C debuginfo=line-tables-only`",
+                        error
+                    )
+                },
+            );
+            sancov_functions.retain(
+                #[coverage(off)]
+                |function| !is_fuzzcheck_file(&function.file) && keep(&function.file, &function.name),
+            );
+            let count_instrumented = sancov_functions
+                .iter()
+                .map(
+                    #[coverage(off)]
+                    |function| function.counters.len(),
+                )
+                .sum();
+            let needs_clearing = (0..sancov_functions.len()).collect();
+            return CodeCoverageSensor {
+                coverage: vec![],
+                sancov_functions,
+                needs_clearing,
+                ignored_counters: vec![],
+                ignored_counters_generation: 0,
+                hit_counters: vec![false; count_instrumented],
+                count_instrumented,
+            };
+        }
         let exec = std::env::current_exe().expect("could not read current executable");
         let LLVMCovSections {
             covfun,
@@ -85,19 +209,105 @@ impl CodeCoverageSensor {
         let needs_clearing = (0..coverage.len()).collect();
         CodeCoverageSensor {
             coverage,
+            sancov_functions: vec![],
             needs_clearing,
+            ignored_counters: vec![],
+            ignored_counters_generation: 0,
+            hit_counters: vec![false; count_instrumented],
             count_instrumented,
         }
     }
 
+    /// The address of the first counter and the number of observed counters of each function, in the order of
+    /// the indices of the observations
+    #[coverage(off)]
+    fn counter_groups(&self) -> Vec<(usize, usize)> {
+        if !self.sancov_functions.is_empty() {
+            return self
+                .sancov_functions
+                .iter()
+                .map(
+                    #[coverage(off)]
+                    |function| (function.counters.as_ptr() as usize, function.counters.len()),
+                )
+                .collect();
+        }
+        self.coverage
+            .iter()
+            .map(
+                #[coverage(off)]
+                |coverage| {
+                    (
+                        coverage.start_counters as usize,
+                        coverage.single_counters.len() + coverage.expression_counters.len(),
+                    )
+                },
+            )
+            .collect()
+    }
+
+    /// Ignore the counters at the given indices of the observations, in this sensor and in every other
+    /// `CodeCoverageSensor` of the program.
+    ///
+    /// It is used by the fuzzer to exclude the counters whose value is not determined by the test case.
+    #[coverage(off)]
+    pub(crate) fn ignore_counters(&self, indices: impl IntoIterator<Item = usize>) {
+        let mut indices = indices.into_iter().collect::<Vec<_>>();
+        if indices.is_empty() {
+            return;
+        }
+        indices.sort_unstable();
+        let mut ignored = IGNORED_COUNTERS.lock().unwrap();
+        let mut first_index = 0;
+        let mut indices = indices.into_iter().peekable();
+        for (start, len) in self.counter_groups() {
+            while let Some(index) = indices.next_if(
+                #[coverage(off)]
+                |&index| index < first_index + len,
+            ) {
+                ignored.insert((start, index - first_index));
+            }
+            first_index += len;
+        }
+        IGNORED_COUNTERS_GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Update the list of ignored counters of the sensor if other counters were ignored since it was last updated
+    #[coverage(off)]
+    fn update_ignored_counters(&mut self) {
+        let generation = IGNORED_COUNTERS_GENERATION.load(Ordering::Relaxed);
+        if generation == self.ignored_counters_generation {
+            return;
+        }
+        self.ignored_counters_generation = generation;
+        let ignored = IGNORED_COUNTERS.lock().unwrap();
+        let mut ignored_counters = Vec::with_capacity(self.count_instrumented);
+        for (start, len) in self.counter_groups() {
+            ignored_counters.extend((0..len).map(
+                #[coverage(off)]
+                |i| ignored.contains(&(start, i)),
+            ));
+        }
+        self.ignored_counters = ignored_counters;
+    }
+
+    /// Reset the counters of the functions that were executed by the last test case.
+    ///
+    /// Its cost is proportional to the number of counters of these functions, not to the total
+    /// number of counters, except before the first test case, when all counters are reset.
     #[coverage(off)]
     unsafe fn clear(&mut self) {
+        if !self.sancov_functions.is_empty() {
+            for &function_idx in &self.needs_clearing {
+                self.sancov_functions[function_idx].counters.fill(0);
+            }
+            self.needs_clearing.clear();
+            return;
+        }
         for &coverage_idx in &self.needs_clearing {
             let coverage = &self.coverage[coverage_idx];
             let slice = std::slice::from_raw_parts_mut(coverage.start_counters, coverage.counters_len);
-            for c in slice.iter_mut() {
-                *c = 0;
-            }
+            slice.fill(0);
         }
         self.needs_clearing.clear();
     }
@@ -117,13 +327,76 @@ impl Sensor for CodeCoverageSensor {
 
     #[coverage(off)]
     fn get_observations(&mut self) -> Self::Observations {
+        self.update_ignored_counters();
+        let mut observations = self.nonzero_counters();
+        for &(index, _) in &observations {
+            self.hit_counters[index] = true;
+        }
+        if !self.ignored_counters.is_empty() {
+            let ignored_counters = &self.ignored_counters;
+            observations.retain(
+                #[coverage(off)]
+                |&(index, _)| !ignored_counters[index],
+            );
+        }
+        observations
+    }
+
+    #[coverage(off)]
+    fn hash_observations(&self, observations: &Self::Observations) -> Option<u64> {
+        let mut hasher = AHasher::default();
+        observations.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    #[coverage(off)]
+    fn get_and_record_observations(&mut self, record: &mut Vec<u8>) -> Self::Observations {
+        let observations = self.get_observations();
+        encode_counters(&observations, record);
+        observations
+    }
+
+    #[coverage(off)]
+    fn replay_observations(&self, record: &mut &[u8]) -> Option<Self::Observations> {
+        decode_counters(record, self.count_instrumented)
+    }
+
+    #[coverage(off)]
+    fn code_coverage_sensor(&mut self) -> Option<&mut CodeCoverageSensor> {
+        Some(self)
+    }
+}
+
+impl CodeCoverageSensor {
+    /// The index and value of each counter that is not zero
+    #[coverage(off)]
+    fn nonzero_counters(&mut self) -> Vec<(usize, u64)> {
         self.needs_clearing.clear();
         let mut observations = Vec::with_capacity(self.count_instrumented);
+        if !self.sancov_functions.is_empty() {
+            let mut index = 0;
+            for (i, function) in self.sancov_functions.iter().enumerate() {
+                let old_observations_len = observations.len();
+                push_nonzero_counters(function.counters, index, &mut observations);
+                if observations.len() != old_observations_len {
+                    self.needs_clearing.push(i);
+                }
+                index += function.counters.len();
+            }
+            return observations;
+        }
         unsafe {
             let CodeCoverageSensor { coverage, .. } = self;
             let mut index = 0;
-            let mut old_observations_len = 0;
             for (i, coverage) in coverage.iter().enumerate() {
+                if !coverage.is_executed() {
+                    index += coverage.single_counters.len() + coverage.expression_counters.len();
+                    continue;
+                }
+                // even if none of its observations are different from zero, for example because an
+                // expression is the difference between two equal counters, the function's counters
+                // must be reset
+                self.needs_clearing.push(i);
                 for &single in coverage.single_counters.iter() {
                     if *single != 0 {
                         observations.push((index, *single));
@@ -137,15 +410,33 @@ impl Sensor for CodeCoverageSensor {
                     }
                     index += 1;
                 }
-                if observations.len() != old_observations_len {
-                    self.needs_clearing.push(i);
-                    old_observations_len = observations.len();
-                }
             }
         }
         observations
     }
 }
+
+/// Returns the regions of memory holding all the code coverage counters of the program, as the
+/// address of their first byte and their size in bytes.
+///
+/// It is used by the fork mode, which copies the counters updated by the forked process back to
+/// the fuzzer.
+#[coverage(off)]
+pub(crate) fn coverage_counters_regions() -> Vec<(*mut u8, usize)> {
+    let mut regions = vec![];
+    for region in unsafe { get_sancov_counters() } {
+        regions.push((region.as_mut_ptr(), region.len()));
+    }
+    let llvm_counters = unsafe { get_counters() };
+    if !llvm_counters.is_empty() {
+        regions.push((
+            llvm_counters.as_mut_ptr() as *mut u8,
+            std::mem::size_of_val(llvm_counters),
+        ));
+    }
+    regions
+}
+
 impl SaveToStatsFolder for CodeCoverageSensor {
     #[coverage(off)]
     fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
@@ -153,7 +444,13 @@ impl SaveToStatsFolder for CodeCoverageSensor {
             if #[cfg(feature = "serde_json_serializer")] {
                 let coverage_map = self.coverage_map();
                 let content = serde_json::to_vec(&coverage_map).unwrap();
-                vec![(PathBuf::new().join("coverage_sensor.json"), content)]
+                let mut files = vec![(PathBuf::new().join("coverage_sensor.json"), content)];
+                if !self.coverage.is_empty() {
+                    let report = serialized::dead_regions_report(&self.counter_locations(), &self.hit_counters);
+                    let content = serde_json::to_vec_pretty(&report).unwrap();
+                    files.push((PathBuf::new().join("dead_regions.json"), content));
+                }
+                files
             } else {
                 vec![]
             }
@@ -162,6 +459,118 @@ impl SaveToStatsFolder for CodeCoverageSensor {
 }
 
 impl CodeCoverageSensor {
+    /// The weight of each counter observed by the sensor, in the order of the indices of its observations.
+    ///
+    /// The `weight` function is given the path of the file and the demangled name of each observed function.
+    /// All the counters of a function have the same weight, which is the greatest weight of the files of
+    /// the function. The result can be passed to
+    /// [`SimplestToActivateCounterPool::with_counter_weights`](crate::sensors_and_pools::SimplestToActivateCounterPool::with_counter_weights).
+    #[coverage(off)]
+    pub fn counter_weights<W>(&self, weight: W) -> Vec<f64>
+    where
+        W: Fn(&Path, &str) -> f64,
+    {
+        if !self.sancov_functions.is_empty() {
+            let mut weights = Vec::with_capacity(self.count_instrumented);
+            for function in self.sancov_functions.iter() {
+                let function_weight = weight(&function.file, &function.name);
+                weights.extend(std::iter::repeat_n(function_weight, function.counters.len()));
+            }
+            return weights;
+        }
+        let mut weights = Vec::with_capacity(self.count_instrumented);
+        for c in self.coverage.iter() {
+            let name = &c.function_record.name_function;
+            let function_weight = c
+                .function_record
+                .filenames
+                .iter()
+                .map(
+                    #[coverage(off)]
+                    |file| weight(file, name),
+                )
+                .reduce(f64::max)
+                .unwrap_or(1.0);
+            let nbr_counters = c.single_counters.len() + c.expression_counters.len();
+            weights.extend(std::iter::repeat_n(function_weight, nbr_counters));
+        }
+        weights
+    }
+    /// The location in the source code of each counter observed by the sensor, in the order of the indices of
+    /// its observations. That is, the counter at index `i` of the observations counts the execution of the code
+    /// regions given by `counter_locations()[i]`.
+    ///
+    /// With SanitizerCoverage, the code regions of the counters are not known, so they are all empty.
+    #[coverage(off)]
+    pub fn counter_locations(&self) -> Vec<CounterLocation> {
+        if !self.sancov_functions.is_empty() {
+            let mut locations = Vec::with_capacity(self.count_instrumented);
+            for function in self.sancov_functions.iter() {
+                locations.extend(std::iter::repeat_n(
+                    CounterLocation {
+                        file: function.file.clone(),
+                        function: function.name.clone(),
+                        regions: vec![],
+                    },
+                    function.counters.len(),
+                ));
+            }
+            return locations;
+        }
+        let mut locations = Vec::with_capacity(self.count_instrumented);
+        for c in self.coverage.iter() {
+            let record = &c.function_record;
+            let function = rustc_demangle::demangle(&record.name_function).to_string();
+            // the single counters come first, then the expression counters, as in `nonzero_counters`
+            let single_counters = record.expressions.iter().filter(
+                #[coverage(off)]
+                |(e, _)| e.add_terms.len() == 1 && e.sub_terms.is_empty(),
+            );
+            let expression_counters = record.expressions.iter().filter(
+                #[coverage(off)]
+                |(e, _)| !e.add_terms.is_empty() && !(e.add_terms.len() == 1 && e.sub_terms.is_empty()),
+            );
+            for (_, regions) in single_counters.chain(expression_counters) {
+                let file = regions
+                    .first()
+                    .and_then(
+                        #[coverage(off)]
+                        |region| {
+                            record.file_id_mapping.filename_indices.iter().position(
+                                #[coverage(off)]
+                                |&idx| idx == region.filename_index,
+                            )
+                        },
+                    )
+                    .and_then(
+                        #[coverage(off)]
+                        |file_idx| record.filenames.get(file_idx),
+                    )
+                    .or(record.filenames.first())
+                    .cloned()
+                    .unwrap_or_default();
+                let regions = regions
+                    .iter()
+                    .map(
+                        #[coverage(off)]
+                        |region| CodeRegion {
+                            line_start: region.line_start,
+                            col_start: region.col_start,
+                            line_end: region.line_end,
+                            col_end: region.col_end,
+                        },
+                    )
+                    .collect();
+                locations.push(CounterLocation {
+                    file,
+                    function: function.clone(),
+                    regions,
+                });
+            }
+        }
+        debug_assert_eq!(locations.len(), self.count_instrumented);
+        locations
+    }
     #[coverage(off)]
     pub fn print_observed_functions(&self) {
         let mut all = BTreeSet::new();
@@ -170,6 +579,9 @@ impl CodeCoverageSensor {
             let name = rustc_demangle::demangle(name).to_string();
             all.insert(name.clone());
         }
+        for function in self.sancov_functions.iter() {
+            all.insert(function.name.clone());
+        }
         for name in all {
             println!("{name}");
         }
@@ -182,8 +594,31 @@ impl CodeCoverageSensor {
                 all.insert(name.display().to_string());
             }
         }
+        for function in self.sancov_functions.iter() {
+            all.insert(function.file.display().to_string());
+        }
         for name in all {
             println!("{name}");
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::llvm_coverage::any_counter_is_set;
+
+    #[test]
+    #[coverage(off)]
+    fn test_any_counter_is_set() {
+        assert!(!any_counter_is_set(&[]));
+        for len in 0..20 {
+            let mut counters = vec![0; len];
+            assert!(!any_counter_is_set(&counters));
+            for i in 0..len {
+                counters[i] = 1;
+                assert!(any_counter_is_set(&counters));
+                counters[i] = 0;
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+#include <stddef.h>
+#include <stdint.h>
+
+// The regions of 8-bit counters inserted by SanitizerCoverage (-sanitizer-coverage-inline-8bit-counters).
+// Each instrumented module registers its own region before the program starts.
+#define MAX_NBR_SANCOV_REGIONS (1 << 14)
+
+static uint8_t *sancov_regions_start[MAX_NBR_SANCOV_REGIONS];
+static uint8_t *sancov_regions_end[MAX_NBR_SANCOV_REGIONS];
+// the number of regions registered, which may be larger than MAX_NBR_SANCOV_REGIONS
+static size_t nbr_sancov_regions = 0;
+
+void __sanitizer_cov_8bit_counters_init(uint8_t *start, uint8_t *stop)
+{
+    if (start == stop)
+    {
+        return;
+    }
+    if (nbr_sancov_regions < MAX_NBR_SANCOV_REGIONS)
+    {
+        sancov_regions_start[nbr_sancov_regions] = start;
+        sancov_regions_end[nbr_sancov_regions] = stop;
+    }
+    nbr_sancov_regions += 1;
+}
+
+size_t get_max_nbr_sancov_regions()
+{
+    return MAX_NBR_SANCOV_REGIONS;
+}
+size_t get_nbr_sancov_regions()
+{
+    return nbr_sancov_regions;
+}
+uint8_t *get_start_sancov_region(size_t i)
+{
+    return sancov_regions_start[i];
+}
+uint8_t *get_end_sancov_region(size_t i)
+{
+    return sancov_regions_end[i];
+}
+
+// The tables of the instrumented PCs (-sanitizer-coverage-pc-table), registered by each module along with its
+// region of counters. Each table contains a (PC, flags) pair for each counter of the region, where the flags
+// are 1 if the PC is the entry of a function.
+static const uintptr_t *sancov_pcs_regions_start[MAX_NBR_SANCOV_REGIONS];
+static const uintptr_t *sancov_pcs_regions_end[MAX_NBR_SANCOV_REGIONS];
+static size_t nbr_sancov_pcs_regions = 0;
+
+void __sanitizer_cov_pcs_init(const uintptr_t *pcs_beg, const uintptr_t *pcs_end)
+{
+    if (pcs_beg == pcs_end)
+    {
+        return;
+    }
+    if (nbr_sancov_pcs_regions < MAX_NBR_SANCOV_REGIONS)
+    {
+        sancov_pcs_regions_start[nbr_sancov_pcs_regions] = pcs_beg;
+        sancov_pcs_regions_end[nbr_sancov_pcs_regions] = pcs_end;
+    }
+    nbr_sancov_pcs_regions += 1;
+}
+
+size_t get_nbr_sancov_pcs_regions()
+{
+    return nbr_sancov_pcs_regions;
+}
+const uintptr_t *get_start_sancov_pcs_region(size_t i)
+{
+    return sancov_pcs_regions_start[i];
+}
+const uintptr_t *get_end_sancov_pcs_region(size_t i)
+{
+    return sancov_pcs_regions_end[i];
+}
+
+// The lowest address of the stack reached by the functions instrumented by SanitizerCoverage with
+// -sanitizer-coverage-stack-depth, which compare it to their frame address when they are called.
+// It is read by the StackDepthSensor.
+#if defined(_MSC_VER)
+__declspec(thread) uintptr_t __sancov_lowest_stack;
+#else
+__thread uintptr_t __sancov_lowest_stack __attribute__((tls_model("initial-exec")));
+#endif
+
+uintptr_t get_sancov_lowest_stack()
+{
+    return __sancov_lowest_stack;
+}
+void reset_sancov_lowest_stack()
+{
+    __sancov_lowest_stack = UINTPTR_MAX;
+}
@@ -0,0 +1,389 @@
+//! Support for the 8-bit counters inserted by SanitizerCoverage, which can be used
+//! instead of the LLVM coverage instrumentation.
+//!
+//! SanitizerCoverage does not record the files and functions of its counters. Instead, the table of
+//! the instrumented PCs (`-sanitizer-coverage-pc-table`) gives the function of each counter, whose name
+//! is found in the symbol table of the program and whose file is found in its line tables.
+
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use object::{CompressionFormat, Object, ObjectSection, ObjectSymbol, SymbolKind};
+
+use super::debug_line::LineTable;
+
+extern "C" {
+    fn get_max_nbr_sancov_regions() -> usize;
+    fn get_nbr_sancov_regions() -> usize;
+    fn get_start_sancov_region(i: usize) -> *mut u8;
+    fn get_end_sancov_region(i: usize) -> *mut u8;
+    fn get_nbr_sancov_pcs_regions() -> usize;
+    fn get_start_sancov_pcs_region(i: usize) -> *const usize;
+    fn get_end_sancov_pcs_region(i: usize) -> *const usize;
+}
+
+/// The flag of the entries of the table of PCs that are the entry of a function
+const PC_FLAG_FUNCTION_ENTRY: usize = 1;
+
+/// The 8-bit counters of a function instrumented by SanitizerCoverage
+pub struct SancovFunction {
+    /// The path of the file containing the function, as given to the compiler
+    pub file: PathBuf,
+    /// The demangled name of the function
+    pub name: String,
+    pub counters: &'static mut [u8],
+}
+
+#[derive(Debug)]
+pub enum ReadSancovError {
+    CannotReadObjectFile {
+        path: PathBuf,
+    },
+    /// The program was not compiled with `-sanitizer-coverage-pc-table`
+    MissingPcTable,
+    /// The program was stripped of its symbol table or of its line tables
+    CannotFindSection {
+        section: &'static str,
+    },
+    InvalidLineTable,
+}
+impl Display for ReadSancovError {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadSancovError::CannotReadObjectFile { path } => {
+                write!(f, "cannot read the object file {}", path.display())
+            }
+            ReadSancovError::MissingPcTable => write!(f, "the table of PCs is missing"),
+            ReadSancovError::CannotFindSection { section } => write!(f, "cannot find the {} section", section),
+            ReadSancovError::InvalidLineTable => write!(f, "the line tables are invalid"),
+        }
+    }
+}
+
+/// Returns the regions of 8-bit counters registered by the modules instrumented by SanitizerCoverage.
+///
+/// The list is empty if the program was not compiled with SanitizerCoverage.
+#[coverage(off)]
+pub unsafe fn get_sancov_counters() -> Vec<&'static mut [u8]> {
+    let nbr_regions = get_nbr_sancov_regions();
+    assert!(
+        nbr_regions <= get_max_nbr_sancov_regions(),
+        "too many modules are instrumented by SanitizerCoverage: {} (maximum {})",
+        nbr_regions,
+        get_max_nbr_sancov_regions()
+    );
+    (0..nbr_regions)
+        .map(
+            #[coverage(off)]
+            |i| {
+                let start = get_start_sancov_region(i);
+                let end = get_end_sancov_region(i);
+                std::slice::from_raw_parts_mut(start, end.offset_from(start) as usize)
+            },
+        )
+        .collect()
+}
+
+/// Split the regions of 8-bit counters into the counters of each instrumented function, with the
+/// PC of its entry.
+///
+/// The table of PCs of each region has an entry for each of its counters, which begins a new function
+/// if it is flagged as a function entry.
+#[coverage(off)]
+pub fn split_into_functions(
+    regions: Vec<&'static mut [u8]>,
+    pcs_regions: &[&[usize]],
+) -> Result<Vec<(usize, &'static mut [u8])>, ReadSancovError> {
+    if regions.len() != pcs_regions.len() {
+        return Err(ReadSancovError::MissingPcTable);
+    }
+    let mut functions = vec![];
+    for (mut counters, pcs) in regions.into_iter().zip(pcs_regions) {
+        if pcs.len() != 2 * counters.len() {
+            return Err(ReadSancovError::MissingPcTable);
+        }
+        let mut entries = pcs.chunks_exact(2).enumerate().filter(
+            #[coverage(off)]
+            |(_, entry)| entry[1] & PC_FLAG_FUNCTION_ENTRY != 0,
+        );
+        let Some((0, first_entry)) = entries.next() else {
+            return Err(ReadSancovError::MissingPcTable);
+        };
+        let mut entry_pc = first_entry[0];
+        let mut start = 0;
+        for (next_start, entry) in entries {
+            let (function, rest) = counters.split_at_mut(next_start - start);
+            functions.push((entry_pc, function));
+            counters = rest;
+            entry_pc = entry[0];
+            start = next_start;
+        }
+        functions.push((entry_pc, counters));
+    }
+    Ok(functions)
+}
+
+/// The data of a section of the object file, decompressed if needed
+#[coverage(off)]
+fn section_data<'data>(object: &object::File<'data>, name: &'static str) -> Result<Cow<'data, [u8]>, ReadSancovError> {
+    let missing_section = ReadSancovError::CannotFindSection { section: name };
+    let Some(section) = object.section_by_name(name) else {
+        return Err(missing_section);
+    };
+    let compressed = section.compressed_data().map_err(
+        #[coverage(off)]
+        |_| ReadSancovError::InvalidLineTable,
+    )?;
+    match compressed.format {
+        CompressionFormat::None => Ok(Cow::Borrowed(compressed.data)),
+        CompressionFormat::Zlib => {
+            let mut data = Vec::with_capacity(compressed.uncompressed_size as usize);
+            flate2::read::ZlibDecoder::new(compressed.data)
+                .read_to_end(&mut data)
+                .map_err(
+                    #[coverage(off)]
+                    |_| ReadSancovError::InvalidLineTable,
+                )?;
+            Ok(Cow::Owned(data))
+        }
+        _ => Err(ReadSancovError::InvalidLineTable),
+    }
+}
+
+/// Read the line tables of the object file
+///
+/// The `.debug_info` and `.debug_line` sections are required, while the other DWARF sections, such as those
+/// containing the strings, are considered empty if they are missing.
+#[coverage(off)]
+fn line_table(object: &object::File) -> Result<LineTable, ReadSancovError> {
+    let dwarf = gimli::Dwarf::load(
+        #[coverage(off)]
+        |section| match section {
+            gimli::SectionId::DebugInfo | gimli::SectionId::DebugLine => section_data(object, section.name()),
+            _ => Ok(section_data(object, section.name()).unwrap_or_default()),
+        },
+    )?;
+    let endian = if object.is_little_endian() {
+        gimli::RunTimeEndian::Little
+    } else {
+        gimli::RunTimeEndian::Big
+    };
+    let dwarf = dwarf.borrow(
+        #[coverage(off)]
+        |section| gimli::EndianSlice::new(section, endian),
+    );
+    LineTable::parse(&dwarf).ok_or(ReadSancovError::InvalidLineTable)
+}
+
+/// Returns the counters of each function instrumented by SanitizerCoverage, with its file and name.
+///
+/// The functions whose file is not given by the line tables are not returned.
+#[coverage(off)]
+pub unsafe fn get_sancov_functions() -> Result<Vec<SancovFunction>, ReadSancovError> {
+    let pcs_regions = (0..get_nbr_sancov_pcs_regions().min(get_max_nbr_sancov_regions()))
+        .map(
+            #[coverage(off)]
+            |i| {
+                let start = get_start_sancov_pcs_region(i);
+                let end = get_end_sancov_pcs_region(i);
+                std::slice::from_raw_parts(start, end.offset_from(start) as usize)
+            },
+        )
+        .collect::<Vec<_>>();
+    let functions = split_into_functions(get_sancov_counters(), &pcs_regions)?;
+
+    let path = std::env::current_exe().map_err(
+        #[coverage(off)]
+        |_| ReadSancovError::CannotReadObjectFile { path: PathBuf::new() },
+    )?;
+    let cannot_read_object_file = #[coverage(off)]
+    || ReadSancovError::CannotReadObjectFile { path: path.clone() };
+    let bin_data = std::fs::read(&path).map_err(
+        #[coverage(off)]
+        |_| cannot_read_object_file(),
+    )?;
+    let object = object::File::parse(&*bin_data).map_err(
+        #[coverage(off)]
+        |_| cannot_read_object_file(),
+    )?;
+
+    let mut symbols = object
+        .symbols()
+        .filter(
+            #[coverage(off)]
+            |symbol| symbol.kind() == SymbolKind::Text && symbol.address() != 0,
+        )
+        .filter_map(
+            #[coverage(off)]
+            |symbol| Some((symbol.address(), symbol.name().ok()?)),
+        )
+        .collect::<Vec<_>>();
+    symbols.sort_unstable();
+    // the difference between the addresses of the running program and those of the object file, which
+    // is found from the address of a function of this module
+    let Some(&(address, _)) = symbols.iter().find(
+        #[coverage(off)]
+        |(_, name)| *name == "get_nbr_sancov_regions",
+    ) else {
+        return Err(ReadSancovError::CannotFindSection { section: ".symtab" });
+    };
+    let load_bias = (get_nbr_sancov_regions as *const () as u64).wrapping_sub(address);
+
+    let line_table = line_table(&object)?;
+
+    Ok(functions
+        .into_iter()
+        .filter_map(
+            #[coverage(off)]
+            |(entry_pc, counters)| {
+                let address = (entry_pc as u64).wrapping_sub(load_bias);
+                let file = line_table.file(address)?.to_path_buf();
+                let symbol = symbols.partition_point(
+                    #[coverage(off)]
+                    |&(symbol_address, _)| symbol_address <= address,
+                );
+                let name = symbol
+                    .checked_sub(1)
+                    .map(
+                        #[coverage(off)]
+                        |symbol| rustc_demangle::demangle(symbols[symbol].1).to_string(),
+                    )
+                    .unwrap_or_default();
+                Some(SancovFunction { file, name, counters })
+            },
+        )
+        .collect())
+}
+
+/// Whether the file is part of fuzzcheck.
+///
+/// Fuzzcheck’s own functions opt out of the coverage instrumentation with `#[coverage(off)]`, which
+/// SanitizerCoverage ignores, so they are excluded from the observations by their file instead. This includes
+/// the generic functions of fuzzcheck, such as its mutators, that are instantiated in the crate being tested.
+#[coverage(off)]
+pub fn is_fuzzcheck_file(file: &Path) -> bool {
+    let this_file = Path::new(file!());
+    let src_folder = this_file.parent().and_then(
+        #[coverage(off)]
+        |folder| folder.parent(),
+    );
+    src_folder.is_some_and(
+        #[coverage(off)]
+        |src_folder| file.starts_with(src_folder),
+    )
+}
+
+/// Push the index and value of the counters that are not zero to the observations.
+///
+/// The index of the first counter is `first_index`. The counters are read 8 at a time, such that
+/// the (typically large) regions of zeros are skipped quickly.
+#[coverage(off)]
+pub fn push_nonzero_counters(counters: &[u8], first_index: usize, observations: &mut Vec<(usize, u64)>) {
+    let mut chunks = counters.chunks_exact(8);
+    let mut index = first_index;
+    for chunk in &mut chunks {
+        if u64::from_ne_bytes(chunk.try_into().unwrap()) != 0 {
+            for (i, &c) in chunk.iter().enumerate() {
+                if c != 0 {
+                    observations.push((index + i, c as u64));
+                }
+            }
+        }
+        index += 8;
+    }
+    for (i, &c) in chunks.remainder().iter().enumerate() {
+        if c != 0 {
+            observations.push((index + i, c as u64));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use object::{Object, ObjectSymbol};
+
+    use super::{is_fuzzcheck_file, line_table, push_nonzero_counters, split_into_functions};
+
+    #[test]
+    #[coverage(off)]
+    fn test_push_nonzero_counters() {
+        let mut counters = vec![0u8; 21];
+        counters[0] = 1;
+        counters[9] = 2;
+        counters[20] = 255;
+        let mut observations = vec![(0, 1)];
+        push_nonzero_counters(&counters, 10, &mut observations);
+        assert_eq!(observations, vec![(0, 1), (10, 1), (19, 2), (30, 255)]);
+
+        let mut observations = vec![];
+        push_nonzero_counters(&counters[1..9], 0, &mut observations);
+        assert!(observations.is_empty());
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_split_into_functions() {
+        let first_region: &'static mut [u8] = Box::leak(Box::new([1, 2, 3, 4]));
+        let second_region: &'static mut [u8] = Box::leak(Box::new([5]));
+        let first_pcs = [0x10, 1, 0x14, 0, 0x20, 1, 0x24, 0];
+        let second_pcs = [0x30, 1];
+        let functions = split_into_functions(vec![first_region, second_region], &[&first_pcs, &second_pcs]).unwrap();
+        let functions = functions
+            .into_iter()
+            .map(
+                #[coverage(off)]
+                |(pc, counters)| (pc, counters.to_vec()),
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(functions, vec![(0x10, vec![1, 2]), (0x20, vec![3, 4]), (0x30, vec![5])]);
+
+        // the tables of PCs are missing or do not match the counters
+        let region: &'static mut [u8] = Box::leak(Box::new([1, 2]));
+        assert!(split_into_functions(vec![region], &[]).is_err());
+        let region: &'static mut [u8] = Box::leak(Box::new([1, 2]));
+        assert!(split_into_functions(vec![region], &[&[0x10, 1]]).is_err());
+        let region: &'static mut [u8] = Box::leak(Box::new([1]));
+        assert!(split_into_functions(vec![region], &[&[0x14, 0]]).is_err());
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_line_table() {
+        let bin_data = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let object = object::File::parse(&*bin_data).unwrap();
+        let line_table = line_table(&object).unwrap();
+        // the function is found in the symbol table, and its file is the path of this file given to the compiler
+        let symbol = object
+            .symbols()
+            .find(
+                #[coverage(off)]
+                |symbol| {
+                    symbol.name().is_ok_and(
+                        #[coverage(off)]
+                        |name| {
+                            format!("{:#}", rustc_demangle::demangle(name))
+                                == "fuzzcheck::code_coverage_sensor::sanitizer_coverage::split_into_functions"
+                        },
+                    )
+                },
+            )
+            .unwrap();
+        assert_eq!(line_table.file(symbol.address()), Some(Path::new(file!())));
+        assert_eq!(line_table.file(0), None);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_is_fuzzcheck_file() {
+        assert!(is_fuzzcheck_file(Path::new(file!())));
+        let src_folder = Path::new(file!()).parent().unwrap().parent().unwrap();
+        assert!(is_fuzzcheck_file(&src_folder.join("mutators/vector/mod.rs")));
+        assert!(!is_fuzzcheck_file(Path::new("other_crate/src/lib.rs")));
+        assert!(!is_fuzzcheck_file(&src_folder.parent().unwrap().join("tests/lib.rs")));
+    }
+}
@@ -1,9 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
-use super::CodeCoverageSensor;
+use super::{CodeCoverageSensor, CounterLocation};
 
 #[derive(Serialize, Deserialize)]
 pub struct CoverageMap {
@@ -136,3 +136,173 @@ impl CodeCoverageSensor {
         CoverageMap { functions }
     }
 }
+
+/// The code regions that were never executed, saved to `dead_regions.json`
+#[derive(Serialize, Deserialize)]
+pub struct DeadRegionsReport {
+    nbr_counters: usize,
+    nbr_dead_counters: usize,
+    files: Vec<DeadRegionsFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeadRegionsFile {
+    file: String,
+    functions: Vec<DeadRegionsFunction>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeadRegionsFunction {
+    name: String,
+    /// The total number of counters of the function, such that a function that was never executed can be
+    /// distinguished from one that was partially executed
+    nbr_counters: usize,
+    dead_counters: Vec<Counter>,
+}
+
+/// Group the counters that were never hit by file and function, in alphabetical order.
+///
+/// `hit_counters[i]` tells whether the counter at index `i`, located at `locations[i]`, was hit. The counters
+/// without any code region are not reported.
+#[coverage(off)]
+pub(crate) fn dead_regions_report(locations: &[CounterLocation], hit_counters: &[bool]) -> DeadRegionsReport {
+    let mut files = BTreeMap::<&PathBuf, BTreeMap<&str, DeadRegionsFunction>>::new();
+    let mut nbr_dead_counters = 0;
+    for (index, (location, &is_hit)) in locations.iter().zip(hit_counters).enumerate() {
+        let function = files
+            .entry(&location.file)
+            .or_default()
+            .entry(&location.function)
+            .or_insert_with(
+                #[coverage(off)]
+                || DeadRegionsFunction {
+                    name: location.function.clone(),
+                    nbr_counters: 0,
+                    dead_counters: vec![],
+                },
+            );
+        function.nbr_counters += 1;
+        if is_hit || location.regions.is_empty() {
+            continue;
+        }
+        nbr_dead_counters += 1;
+        function.dead_counters.push(Counter {
+            id: index,
+            regions: location
+                .regions
+                .iter()
+                .map(
+                    #[coverage(off)]
+                    |region| Region {
+                        lines: (region.line_start, region.line_end),
+                        cols: (region.col_start, region.col_end),
+                    },
+                )
+                .collect(),
+        });
+    }
+    let files = files
+        .into_iter()
+        .filter_map(
+            #[coverage(off)]
+            |(file, functions)| {
+                let functions = functions
+                    .into_values()
+                    .filter(
+                        #[coverage(off)]
+                        |function| !function.dead_counters.is_empty(),
+                    )
+                    .collect::<Vec<_>>();
+                if functions.is_empty() {
+                    None
+                } else {
+                    Some(DeadRegionsFile {
+                        file: file.display().to_string(),
+                        functions,
+                    })
+                }
+            },
+        )
+        .collect();
+    DeadRegionsReport {
+        nbr_counters: locations.len(),
+        nbr_dead_counters,
+        files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_coverage_sensor::CodeRegion;
+
+    #[coverage(off)]
+    fn location(file: &str, function: &str, line: usize) -> CounterLocation {
+        CounterLocation {
+            file: PathBuf::from(file),
+            function: function.to_owned(),
+            regions: vec![CodeRegion {
+                line_start: line,
+                col_start: 1,
+                line_end: line,
+                col_end: 10,
+            }],
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_dead_regions_report() {
+        let locations = vec![
+            location("src/b.rs", "b::f", 1),
+            location("src/b.rs", "b::f", 2),
+            location("src/a.rs", "a::g", 3),
+            location("src/a.rs", "a::h", 4),
+            CounterLocation {
+                regions: vec![],
+                ..location("src/a.rs", "a::h", 0)
+            },
+        ];
+        let report = dead_regions_report(&locations, &[true, false, true, false, false]);
+        assert_eq!(report.nbr_counters, 5);
+        assert_eq!(report.nbr_dead_counters, 2);
+        let summary = report
+            .files
+            .iter()
+            .map(
+                #[coverage(off)]
+                |file| {
+                    (
+                        file.file.as_str(),
+                        file.functions
+                            .iter()
+                            .map(
+                                #[coverage(off)]
+                                |f| {
+                                    (
+                                        f.name.as_str(),
+                                        f.nbr_counters,
+                                        f.dead_counters
+                                            .iter()
+                                            .map(
+                                                #[coverage(off)]
+                                                |c| (c.id, c.regions[0].lines.0),
+                                            )
+                                            .collect::<Vec<_>>(),
+                                    )
+                                },
+                            )
+                            .collect::<Vec<_>>(),
+                    )
+                },
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("src/a.rs", vec![("a::h", 2, vec![(3, 4)])]),
+                ("src/b.rs", vec![("b::f", 2, vec![(1, 2)])]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! The `analyze` command, which reports the code coverage contributed by each test case of the input corpus.
+//!
+//! Each test case is run once. Its coverage features are the counters that it activates, as observed by the
+//! sensor of the fuzz test, and its unique features are those that no other test case of the corpus activates.
+//! A test case without unique features can be removed from the corpus without losing any code coverage, while a
+//! test case with many unique features is a good seed to keep.
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::observations_record::decode_counters;
+use crate::traits::SensorAndPool;
+use crate::world::World;
+use crate::{CSVField, Mutator, PoolStorageIndex, Serializer};
+
+/// The name of the report saved in the stats folder, without its extension
+pub(crate) const CORPUS_ANALYSIS_FILE: &str = "corpus_analysis";
+
+/// The contribution of a test case to the code coverage of the corpus
+#[cfg_attr(feature = "serde_json_serializer", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CorpusEntryAnalysis {
+    pub path: PathBuf,
+    pub complexity: f64,
+    /// The number of seconds since the file was last modified, if it is known
+    pub age: Option<f64>,
+    pub test_failure: bool,
+    /// The number of counters activated by the test case
+    pub nbr_features: usize,
+    /// The number of counters activated by the test case and by no other test case of the corpus
+    pub nbr_unique_features: usize,
+}
+
+/// The report of the `analyze` command
+pub(crate) struct CorpusAnalysis {
+    /// The analysis of each valid test case, sorted by decreasing number of unique features and increasing complexity
+    pub entries: Vec<CorpusEntryAnalysis>,
+    /// The number of files that could not be decoded into a valid test case
+    pub nbr_skipped_files: usize,
+}
+
+/// The counters activated by a test case, as `(sensor, counter index)` pairs, from the observations [recorded by
+/// the sensor](crate::Sensor::get_and_record_observations).
+///
+/// A sensor made of several sensors of counters records the counters of each of them in turn. Returns `None` if
+/// the observations are not counters.
+#[coverage(off)]
+fn recorded_features(mut record: &[u8]) -> Option<Vec<(usize, usize)>> {
+    if record.is_empty() {
+        return None;
+    }
+    let mut features = vec![];
+    let mut sensor = 0;
+    while !record.is_empty() {
+        let counters = decode_counters(&mut record, usize::MAX)?;
+        features.extend(counters.into_iter().map(
+            #[coverage(off)]
+            |(index, _)| (sensor, index),
+        ));
+        sensor += 1;
+    }
+    Some(features)
+}
+
+/// Run each test case of the input corpus once and compute the coverage features it contributes, as
+/// observed by the sensor of the fuzz test.
+///
+/// The files of the corpus are decoded by the first serializer that can read their extension. Returns
+/// `None` if the observations of the sensor are not counters, such as those of the code coverage sensor.
+#[coverage(off)]
+pub(crate) fn analyze_corpus<T, M>(
+    test: &dyn Fn(&T) -> bool,
+    mutator: &M,
+    serializers: &[&dyn Serializer<Value = T>],
+    sensor_and_pool: &mut dyn SensorAndPool,
+    world: &World,
+) -> std::io::Result<Option<CorpusAnalysis>>
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    let now = SystemTime::now();
+    let mut nbr_skipped_files = 0;
+    let mut entries = vec![];
+    let mut all_features = vec![];
+    let mut record = vec![];
+    for (id, (path, data)) in world.read_input_corpus()?.into_iter().enumerate() {
+        let Ok(data) = data else {
+            nbr_skipped_files += 1;
+            continue;
+        };
+        let extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+        let value = serializers
+            .iter()
+            .find(
+                #[coverage(off)]
+                |serializer| serializer.reads_extension(extension),
+            )
+            .and_then(
+                #[coverage(off)]
+                |serializer| serializer.from_data_with_extension(&data, extension),
+            );
+        let Some((value, cache)) = value.and_then(
+            #[coverage(off)]
+            |value| {
+                mutator.validate_value(&value).map(
+                    #[coverage(off)]
+                    |cache| (value, cache),
+                )
+            },
+        ) else {
+            nbr_skipped_files += 1;
+            continue;
+        };
+        let complexity = mutator.complexity(&value, &cache);
+        let age = std::fs::metadata(&path)
+            .and_then(
+                #[coverage(off)]
+                |metadata| metadata.modified(),
+            )
+            .ok()
+            .and_then(
+                #[coverage(off)]
+                |modified| now.duration_since(modified).ok(),
+            )
+            .map(
+                #[coverage(off)]
+                |age| age.as_secs_f64(),
+            );
+
+        sensor_and_pool.start_recording();
+        let result = catch_unwind(AssertUnwindSafe(
+            #[coverage(off)]
+            || test(&value),
+        ));
+        sensor_and_pool.stop_recording();
+        // the pool also processes the observations, but it is not used afterwards
+        record.clear();
+        let _ = sensor_and_pool.process_and_record(PoolStorageIndex::mock(id), complexity, &mut record);
+        let Some(features) = recorded_features(&record) else {
+            return Ok(None);
+        };
+
+        entries.push(CorpusEntryAnalysis {
+            path,
+            complexity,
+            age,
+            test_failure: !matches!(result, Ok(true)),
+            nbr_features: features.len(),
+            nbr_unique_features: 0,
+        });
+        all_features.push(features);
+    }
+
+    let mut nbr_test_cases_with_feature = HashMap::<(usize, usize), usize>::new();
+    for features in all_features.iter() {
+        for &feature in features {
+            *nbr_test_cases_with_feature.entry(feature).or_default() += 1;
+        }
+    }
+    for (entry, features) in entries.iter_mut().zip(all_features.iter()) {
+        entry.nbr_unique_features = features
+            .iter()
+            .filter(
+                #[coverage(off)]
+                |feature| nbr_test_cases_with_feature[feature] == 1,
+            )
+            .count();
+    }
+    entries.sort_by(
+        #[coverage(off)]
+        |x, y| {
+            y.nbr_unique_features
+                .cmp(&x.nbr_unique_features)
+                .then(x.complexity.total_cmp(&y.complexity))
+                .then_with(
+                    #[coverage(off)]
+                    || x.path.cmp(&y.path),
+                )
+        },
+    );
+    Ok(Some(CorpusAnalysis {
+        entries,
+        nbr_skipped_files,
+    }))
+}
+
+impl CorpusAnalysis {
+    /// The report as a CSV file
+    #[coverage(off)]
+    fn to_csv(&self) -> Vec<u8> {
+        let headers = [
+            "path",
+            "complexity",
+            "age",
+            "features",
+            "unique_features",
+            "test_failure",
+        ]
+        .map(
+            #[coverage(off)]
+            |header| CSVField::String(header.to_string()),
+        );
+        let mut csv = CSVField::to_bytes(&headers);
+        for entry in self.entries.iter() {
+            csv.extend(CSVField::to_bytes(&[
+                CSVField::String(entry.path.display().to_string()),
+                CSVField::Float(entry.complexity),
+                entry.age.map_or(CSVField::String(String::new()), CSVField::Float),
+                CSVField::Integer(entry.nbr_features as isize),
+                CSVField::Integer(entry.nbr_unique_features as isize),
+                CSVField::Integer(entry.test_failure as isize),
+            ]));
+        }
+        csv
+    }
+
+    /// The files of the report to be saved in the stats folder
+    #[coverage(off)]
+    pub(crate) fn files(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        #[allow(unused_mut)]
+        let mut files = vec![(
+            PathBuf::new().join(CORPUS_ANALYSIS_FILE).with_extension("csv"),
+            self.to_csv(),
+        )];
+        #[cfg(feature = "serde_json_serializer")]
+        files.push((
+            PathBuf::new().join(CORPUS_ANALYSIS_FILE).with_extension("json"),
+            serde_json::to_vec_pretty(&self.entries).unwrap(),
+        ));
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use fuzzcheck_common::arg::Arguments;
+
+    use super::*;
+    use crate::sensors_and_pools::{DynamicValueSensor, NoopSensor, SimplestToActivateCounterPool, UnitPool};
+    use crate::{ByteSerializer, DefaultMutator};
+
+    #[test]
+    #[coverage(off)]
+    fn test_analyze_corpus() {
+        let folder = std::env::temp_dir().join(format!("fuzzcheck_corpus_analysis_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        let corpus = folder.join("corpus");
+        std::fs::create_dir_all(&corpus).unwrap();
+        std::fs::write(corpus.join("a.bytes"), [0, 1]).unwrap();
+        std::fs::write(corpus.join("b.bytes"), [0, 2, 3]).unwrap();
+        std::fs::write(corpus.join("c.bytes"), [1]).unwrap();
+        std::fs::write(corpus.join("d.json"), [1]).unwrap();
+
+        let mut arguments = Arguments::for_property_test(0);
+        arguments.corpus_in = Some(corpus.clone());
+        let world = World::new(arguments).unwrap();
+
+        let counters: &'static [AtomicU64] = Box::leak((0..256).map(|_| AtomicU64::new(0)).collect());
+        let test = |xs: &Vec<u8>| {
+            for &x in xs {
+                counters[x as usize].fetch_add(1, Ordering::Relaxed);
+            }
+            !xs.contains(&3)
+        };
+        let serializer = ByteSerializer::new("bytes");
+        let mut sensor_and_pool = (
+            DynamicValueSensor::new(counters),
+            SimplestToActivateCounterPool::new("cov", counters.len()),
+        );
+        let analysis = analyze_corpus(
+            &test,
+            &Vec::<u8>::default_mutator(),
+            &[&serializer],
+            &mut sensor_and_pool,
+            &world,
+        )
+        .unwrap()
+        .unwrap();
+
+        // the observations of the sensor are not counters
+        let mut sensor_and_pool = (NoopSensor, UnitPool::new(PoolStorageIndex::mock(0)));
+        let no_analysis = analyze_corpus(
+            &test,
+            &Vec::<u8>::default_mutator(),
+            &[&serializer],
+            &mut sensor_and_pool,
+            &world,
+        )
+        .unwrap();
+        assert!(no_analysis.is_none());
+        let _ = std::fs::remove_dir_all(&folder);
+
+        assert_eq!(analysis.nbr_skipped_files, 1);
+        let summary = analysis
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.path.file_name().unwrap().to_str().unwrap(),
+                    entry.nbr_features,
+                    entry.nbr_unique_features,
+                    entry.test_failure,
+                )
+            })
+            .collect::<Vec<_>>();
+        // the counter 0 is activated by a and b, and the counter 1 by a and c
+        // the test cases without unique features are sorted by complexity
+        assert_eq!(
+            summary,
+            vec![
+                ("b.bytes", 3, 2, true),
+                ("c.bytes", 1, 0, false),
+                ("a.bytes", 2, 0, false)
+            ]
+        );
+        assert!(analysis.entries[0].age.is_some());
+        assert_eq!(
+            analysis.files().len(),
+            if cfg!(feature = "serde_json_serializer") { 2 } else { 1 }
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! The `covdiff` command, which compares the code coverage of two test cases.
+//!
+//! Each test case is run once, and the counters activated by only one of them are printed along with the code
+//! regions they count. This explains why a pool keeps one of two test cases that look alike: they may reach
+//! different branches, or execute the same code a different number of times.
+
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fmt::Display;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use crate::code_coverage_sensor::CounterLocation;
+use crate::world::World;
+use crate::{Mutator, Sensor, Serializer};
+
+/// The code coverage of one of the two compared test cases
+pub(crate) struct InputCoverage {
+    pub path: PathBuf,
+    pub complexity: f64,
+    pub test_failure: bool,
+    /// The counters activated by the test case, sorted by index
+    pub counters: Vec<(usize, u64)>,
+}
+
+/// The report of the `covdiff` command
+pub(crate) struct CoverageDiff {
+    pub first: InputCoverage,
+    pub second: InputCoverage,
+    /// The location of each counter, or an empty vector if it is not known
+    pub locations: Vec<CounterLocation>,
+}
+
+/// Run the test cases saved in the two files once each and record the counters they activate.
+///
+/// The files are decoded by the first serializer that can read their extension.
+#[coverage(off)]
+pub(crate) fn coverage_diff<T, M, S>(
+    test: &dyn Fn(&T) -> bool,
+    mutator: &M,
+    serializers: &[&dyn Serializer<Value = T>],
+    sensor: &mut S,
+    locations: Vec<CounterLocation>,
+    world: &World,
+    input_files: [&Path; 2],
+) -> std::io::Result<CoverageDiff>
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+    S: Sensor<Observations = Vec<(usize, u64)>>,
+{
+    let mut coverages = vec![];
+    for path in input_files {
+        let data = world.read_input_file(path)?;
+        let extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+        let value = serializers
+            .iter()
+            .find(
+                #[coverage(off)]
+                |serializer| serializer.reads_extension(extension),
+            )
+            .and_then(
+                #[coverage(off)]
+                |serializer| serializer.from_data_with_extension(&data, extension),
+            )
+            .ok_or_else(
+                #[coverage(off)]
+                || {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("{} could not be decoded into a test case", path.display()),
+                    )
+                },
+            )?;
+        let cache = mutator.validate_value(&value).ok_or_else(
+            #[coverage(off)]
+            || {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{} is not a valid test case for the mutator", path.display()),
+                )
+            },
+        )?;
+        let complexity = mutator.complexity(&value, &cache);
+
+        sensor.start_recording();
+        let result = catch_unwind(AssertUnwindSafe(
+            #[coverage(off)]
+            || test(&value),
+        ));
+        sensor.stop_recording();
+        let mut counters = sensor.get_observations();
+        counters.sort_unstable();
+
+        coverages.push(InputCoverage {
+            path: path.to_path_buf(),
+            complexity,
+            test_failure: !matches!(result, Ok(true)),
+            counters,
+        });
+    }
+    let second = coverages.pop().unwrap();
+    let first = coverages.pop().unwrap();
+    Ok(CoverageDiff {
+        first,
+        second,
+        locations,
+    })
+}
+
+impl CoverageDiff {
+    /// The indices of the counters activated by `a` but not by `b`
+    #[coverage(off)]
+    fn only_in(a: &InputCoverage, b: &InputCoverage) -> Vec<usize> {
+        a.counters
+            .iter()
+            .filter(
+                #[coverage(off)]
+                |(index, _)| {
+                    b.counters
+                        .binary_search_by_key(
+                            index,
+                            #[coverage(off)]
+                            |(index, _)| *index,
+                        )
+                        .is_err()
+                },
+            )
+            .map(
+                #[coverage(off)]
+                |(index, _)| *index,
+            )
+            .collect()
+    }
+
+    /// The number of counters activated by both test cases, but with different values
+    #[coverage(off)]
+    fn nbr_different_values(&self) -> usize {
+        self.first
+            .counters
+            .iter()
+            .filter(
+                #[coverage(off)]
+                |(index, value)| {
+                    self.second
+                        .counters
+                        .binary_search_by_key(
+                            index,
+                            #[coverage(off)]
+                            |(index, _)| *index,
+                        )
+                        .is_ok_and(
+                            #[coverage(off)]
+                            |i| self.second.counters[i].1 != *value,
+                        )
+                },
+            )
+            .count()
+    }
+
+    /// Write the given counters, grouped by file and function
+    #[coverage(off)]
+    fn fmt_counters(&self, f: &mut std::fmt::Formatter<'_>, counters: &[usize]) -> std::fmt::Result {
+        if counters.is_empty() {
+            return writeln!(f, "    (none)");
+        }
+        if self.locations.is_empty() {
+            // the locations of the counters are not known
+            for index in counters {
+                writeln!(f, "    counter #{}", index)?;
+            }
+            return Ok(());
+        }
+        let mut files = BTreeMap::<&Path, BTreeMap<&str, Vec<&CounterLocation>>>::new();
+        for &index in counters {
+            let location = &self.locations[index];
+            files
+                .entry(&location.file)
+                .or_default()
+                .entry(&location.function)
+                .or_default()
+                .push(location);
+        }
+        for (file, functions) in files {
+            writeln!(f, "    {}", file.display())?;
+            for (function, locations) in functions {
+                writeln!(f, "        {}", function)?;
+                for location in locations {
+                    for region in &location.regions {
+                        writeln!(
+                            f,
+                            "            {}:{}-{}:{}",
+                            region.line_start, region.col_start, region.line_end, region.col_end
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for CoverageDiff {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for input in [&self.first, &self.second] {
+            writeln!(
+                f,
+                "{}: complexity {:.2}, {} counters{}",
+                input.path.display(),
+                input.complexity,
+                input.counters.len(),
+                if input.test_failure { ", test failure" } else { "" }
+            )?;
+        }
+        for (a, b) in [(&self.first, &self.second), (&self.second, &self.first)] {
+            writeln!(f, "\nOnly covered by {}:", a.path.display())?;
+            self.fmt_counters(f, &Self::only_in(a, b))?;
+        }
+        write!(
+            f,
+            "\n{} counters are covered by both test cases, but reached a different number of times",
+            self.nbr_different_values()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use fuzzcheck_common::arg::Arguments;
+
+    use super::*;
+    use crate::code_coverage_sensor::CodeRegion;
+    use crate::sensors_and_pools::DynamicValueSensor;
+    use crate::{ByteSerializer, DefaultMutator};
+
+    #[test]
+    #[coverage(off)]
+    fn test_coverage_diff() {
+        let folder = std::env::temp_dir().join(format!("fuzzcheck_coverage_diff_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        let first = folder.join("a.bytes");
+        let second = folder.join("b.bytes");
+        std::fs::write(&first, [0, 1, 1]).unwrap();
+        std::fs::write(&second, [0, 1, 2]).unwrap();
+
+        let world = World::new(Arguments::for_property_test(0)).unwrap();
+        let counters: &'static [AtomicU64] = Box::leak(
+            (0..4)
+                .map(
+                    #[coverage(off)]
+                    |_| AtomicU64::new(0),
+                )
+                .collect(),
+        );
+        let test = #[coverage(off)]
+        |xs: &Vec<u8>| {
+            for &x in xs {
+                counters[x as usize].fetch_add(1, Ordering::Relaxed);
+            }
+            true
+        };
+        let locations = (0..4)
+            .map(
+                #[coverage(off)]
+                |i| CounterLocation {
+                    file: PathBuf::from("src/lib.rs"),
+                    function: if i < 2 { "f".to_owned() } else { "g".to_owned() },
+                    regions: vec![CodeRegion {
+                        line_start: i + 1,
+                        col_start: 1,
+                        line_end: i + 1,
+                        col_end: 5,
+                    }],
+                },
+            )
+            .collect();
+        let serializer = ByteSerializer::new("bytes");
+        let diff = coverage_diff(
+            &test,
+            &Vec::<u8>::default_mutator(),
+            &[&serializer],
+            &mut DynamicValueSensor::new(counters),
+            locations,
+            &world,
+            [&first, &second],
+        )
+        .unwrap();
+        assert_eq!(CoverageDiff::only_in(&diff.first, &diff.second), Vec::<usize>::new());
+        assert_eq!(CoverageDiff::only_in(&diff.second, &diff.first), vec![2]);
+        assert_eq!(diff.nbr_different_values(), 1);
+
+        let report = diff.to_string();
+        assert!(report.contains(&format!(
+            "Only covered by {}:\n    src/lib.rs\n        g\n            3:1-3:5\n",
+            second.display()
+        )));
+        assert!(report.contains(&format!("Only covered by {}:\n    (none)\n", first.display())));
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -140,6 +140,12 @@ impl<T> Slab<T> {
         self.available_slots.push(key.key);
         self.available_slots.sort_unstable();
     }
+    /// Whether the key refers to an element of the slab, as opposed to an available slot
+    /// or a slot past the end of the slab
+    #[coverage(off)]
+    pub fn contains(&self, key: SlabKey<T>) -> bool {
+        key.key < self.storage.len() && self.available_slots.binary_search(&key.key).is_err()
+    }
     // #[coverage(off)]
     // pub fn next_key(&self) -> SlabKey<T> {
     //     if let Some(&slot) = self.available_slots.last() {
@@ -282,6 +288,11 @@ impl<T> RcSlab<T> {
             self.storage.len()
         }
     }
+    /// The number of references to the entry at `key`, which is removed once it reaches 0
+    #[coverage(off)]
+    pub fn ref_count(&self, key: usize) -> usize {
+        self.storage[key].ref_count
+    }
     #[coverage(off)]
     pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
         // O(n) but in practice very fast because there will be almost no available slots
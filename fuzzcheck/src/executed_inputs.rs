@@ -0,0 +1,170 @@
+//! A record of the test cases that were already executed by the fuzzer, persisted in the stats folder
+//! such that restarted fuzzing sessions do not re-execute them.
+
+use std::hash::Hasher;
+use std::path::PathBuf;
+
+use crate::bloom_filter::BloomFilter;
+use crate::traits::SaveToStatsFolder;
+
+/// The name of the file, in the stats folder, containing the executed inputs of a fuzzing session
+pub(crate) const EXECUTED_INPUTS_FILE: &str = "executed_inputs.bloom";
+
+/// The bytes at the start of the file, which identify its format and its version
+const FORMAT_TAG: &[u8; 8] = b"fzckexe1";
+
+const SIZE_BLOOM: usize = 1 << 22;
+const FALSE_POSITIVE_RATE: f64 = 0.000_1;
+
+/// The keys of the two hashers of the serialized test cases. They are fixed, such that a test case has the same
+/// hashes in every process, on every platform, and with every version of fuzzcheck.
+const HASH_KEYS: [(u64, u64); 2] = [
+    (0x6675_7a7a_6368_6563, 0x6b5f_6578_6563_7574),
+    (0x6564_5f69_6e70_7574, 0x735f_626c_6f6f_6d21),
+];
+
+/// The set of the serialized test cases that were executed, stored in a bloom filter.
+///
+/// The test cases are hashed with SipHash-2-4, whose output is specified, instead of the hashers of the filter,
+/// such that a filter saved by a previous session can be used by the next one.
+///
+/// Since a bloom filter can give false positives, a small fraction of the new test cases is
+/// wrongly considered to have been already executed. The filter is cleared once it contains
+/// too many test cases for its false positive rate to stay low.
+///
+/// The filter is large, so it is only saved to the stats folder if it changed since it was last saved.
+pub(crate) struct ExecutedInputs {
+    filter: BloomFilter<[u8]>,
+    nbr_inserted: usize,
+    changed_since_save: bool,
+}
+
+impl ExecutedInputs {
+    #[coverage(off)]
+    pub fn new() -> Self {
+        Self {
+            filter: BloomFilter::new(SIZE_BLOOM, FALSE_POSITIVE_RATE),
+            nbr_inserted: 0,
+            changed_since_save: false,
+        }
+    }
+
+    /// Decode the executed inputs saved by a previous fuzzing session.
+    ///
+    /// Returns `None` if the content of the file is not valid.
+    #[coverage(off)]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.strip_prefix(FORMAT_TAG)?;
+        let (nbr_inserted, filter) = bytes.split_first_chunk::<8>()?;
+        let nbr_inserted = u64::from_le_bytes(*nbr_inserted) as usize;
+        let filter = BloomFilter::from_bytes(filter, SIZE_BLOOM, FALSE_POSITIVE_RATE)?;
+        Some(Self {
+            filter,
+            nbr_inserted,
+            changed_since_save: false,
+        })
+    }
+
+    #[coverage(off)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = FORMAT_TAG.to_vec();
+        bytes.extend((self.nbr_inserted as u64).to_le_bytes());
+        bytes.extend(self.filter.to_bytes());
+        bytes
+    }
+
+    /// Record that the serialized test case was executed.
+    ///
+    /// Returns `false` if it was (probably) already executed before.
+    #[coverage(off)]
+    pub fn insert(&mut self, data: &[u8]) -> bool {
+        let (hash1, hash2) = hashes(data);
+        if self.filter.contains_hashes(hash1, hash2) {
+            return false;
+        }
+        if self.nbr_inserted >= SIZE_BLOOM {
+            self.filter.bitmap.clear();
+            self.nbr_inserted = 0;
+        }
+        self.filter.insert_hashes(hash1, hash2);
+        self.nbr_inserted += 1;
+        self.changed_since_save = true;
+        true
+    }
+
+    /// Record that the content returned by [`save_to_stats_folder`](SaveToStatsFolder::save_to_stats_folder)
+    /// was written to the stats folder
+    #[coverage(off)]
+    pub fn did_save(&mut self) {
+        self.changed_since_save = false;
+    }
+}
+
+/// The two hashes of the serialized test case, which are the same in every process
+#[coverage(off)]
+fn hashes(data: &[u8]) -> (u64, u64) {
+    let hash = #[coverage(off)]
+    |(key0, key1): (u64, u64)| {
+        // `SipHasher` is deprecated in favour of `DefaultHasher`, whose algorithm may change, but it is
+        // still SipHash-2-4
+        #[allow(deprecated)]
+        let mut hasher = std::hash::SipHasher::new_with_keys(key0, key1);
+        hasher.write(data);
+        hasher.finish()
+    };
+    (hash(HASH_KEYS[0]), hash(HASH_KEYS[1]))
+}
+
+impl SaveToStatsFolder for ExecutedInputs {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        if self.changed_since_save {
+            vec![(PathBuf::new().join(EXECUTED_INPUTS_FILE), self.to_bytes())]
+        } else {
+            vec![]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_executed_inputs() {
+        let mut executed_inputs = ExecutedInputs::new();
+        assert!(executed_inputs.insert(b"hello"));
+        assert!(executed_inputs.insert(b"world"));
+        assert!(!executed_inputs.insert(b"hello"));
+
+        let mut decoded = ExecutedInputs::from_bytes(&executed_inputs.to_bytes()).unwrap();
+        assert_eq!(decoded.nbr_inserted, 2);
+        assert!(!decoded.insert(b"hello"));
+        assert!(!decoded.insert(b"world"));
+        assert!(decoded.insert(b"fuzz"));
+
+        // it is only saved again once it changes
+        assert!(!executed_inputs.save_to_stats_folder().is_empty());
+        executed_inputs.did_save();
+        assert!(executed_inputs.save_to_stats_folder().is_empty());
+        assert!(!executed_inputs.insert(b"hello"));
+        assert!(executed_inputs.save_to_stats_folder().is_empty());
+        assert!(executed_inputs.insert(b"fuzz"));
+        assert!(!executed_inputs.save_to_stats_folder().is_empty());
+
+        assert!(ExecutedInputs::from_bytes(&[]).is_none());
+        assert!(ExecutedInputs::from_bytes(&executed_inputs.to_bytes()[..100]).is_none());
+        // the files of another format or version are ignored
+        let mut bytes = executed_inputs.to_bytes();
+        bytes[7] = b'0';
+        assert!(ExecutedInputs::from_bytes(&bytes).is_none());
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_hashes_are_fixed() {
+        // the SipHash-2-4 hashes of the test case, which must not change between versions of fuzzcheck
+        assert_eq!(hashes(b"fuzzcheck"), (14279496468186004804, 7436613173364310580));
+    }
+}
@@ -0,0 +1,186 @@
+//! The fork mode, where each test case is run in a child process created by `fork`.
+//!
+//! The child process starts with a copy of the state of the fuzzer, so that the test function can modify
+//! global state without affecting the test cases that are run after it. Once the test function returns,
+//! the child writes to shared memory the result of the test and the code coverage counters,
+//! which are then copied back to the fuzzer's own counters. The observations of the other sensors are **not**
+//! transmitted, unless their state is itself located in shared memory, for example with
+//! [`anonymous_shared_counters`](crate::subprocess::anonymous_shared_counters).
+//!
+//! A test case that crashes, exits the process, or times out is reported as a test failure instead of
+//! stopping the fuzzer.
+
+cfg_if::cfg_if! {
+    if #[cfg(unix)] {
+        use crate::code_coverage_sensor::coverage_counters_regions;
+        use crate::sensors_and_pools::{TestFailure, TEST_FAILURE, TIMEOUT_DISPLAY};
+        use crate::signals_handler::{
+            reset_sanitizer_death_handler, reset_signal_handlers, set_infinite_loop_alarm, was_interrupted,
+            INTERRUPT_SIGNALS,
+        };
+
+        /// The maximum number of bytes of the description of a test failure that are sent back by the child process
+        const MAX_DISPLAY_LEN: usize = 4096;
+
+        /// The data written by the child process at the beginning of the shared memory, followed by a copy of
+        /// the code coverage counters
+        #[repr(C)]
+        struct Header {
+            test_failure: bool,
+            failure_id: u64,
+            display_len: usize,
+            display: [u8; MAX_DISPLAY_LEN],
+        }
+
+        pub(crate) struct ForkExecutor {
+            shared: *mut u8,
+            shared_len: usize,
+            regions: Vec<(*mut u8, usize)>,
+        }
+
+        impl ForkExecutor {
+            #[coverage(off)]
+            pub fn new() -> Self {
+                let regions = coverage_counters_regions();
+                let counters_len = regions.iter().map(
+                    #[coverage(off)]
+                    |(_, len)| len
+                ).sum::<usize>();
+                let shared_len = std::mem::size_of::<Header>() + counters_len;
+                let shared = unsafe {
+                    libc::mmap(
+                        std::ptr::null_mut(),
+                        shared_len,
+                        libc::PROT_READ | libc::PROT_WRITE,
+                        libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+                        -1,
+                        0,
+                    )
+                };
+                assert!(
+                    shared != libc::MAP_FAILED,
+                    "could not allocate the shared memory of the fork mode: {}",
+                    std::io::Error::last_os_error()
+                );
+                Self { shared: shared as *mut u8, shared_len, regions }
+            }
+
+            /// Run the test in a child process and return true if it failed.
+            ///
+            /// `run_test` is called in the child process. It must return true if the test failed, and
+            /// set [`TEST_FAILURE`] accordingly. The code coverage counters must have been reset
+            /// beforehand, and [`TEST_FAILURE`] is set by this function.
+            #[coverage(off)]
+            pub fn run(&mut self, detect_infinite_loop: bool, run_test: impl FnOnce() -> bool) -> bool {
+                unsafe {
+                    let header = &mut *(self.shared as *mut Header);
+                    let pid = libc::fork();
+                    assert!(pid >= 0, "could not fork the fuzzer: {}", std::io::Error::last_os_error());
+                    if pid == 0 {
+                        // the child must not handle the signals and sanitizer reports as if it were the fuzzer; a crash
+                        // will be noticed by the parent instead
+                        reset_signal_handlers();
+                        reset_sanitizer_death_handler();
+                        if detect_infinite_loop {
+                            set_infinite_loop_alarm();
+                        }
+                        let test_failure = run_test();
+                        header.test_failure = test_failure;
+                        if let Some(failure) = &*std::ptr::addr_of!(TEST_FAILURE) {
+                            let display = failure.display.as_bytes();
+                            let display_len = std::cmp::min(display.len(), MAX_DISPLAY_LEN);
+                            header.display[..display_len].copy_from_slice(&display[..display_len]);
+                            header.display_len = display_len;
+                            header.failure_id = failure.id;
+                        } else {
+                            header.display_len = 0;
+                        }
+                        let mut counters = self.shared.add(std::mem::size_of::<Header>());
+                        for &(start, len) in self.regions.iter() {
+                            std::ptr::copy_nonoverlapping(start, counters, len);
+                            counters = counters.add(len);
+                        }
+                        // do not run the destructors and exit handlers of the fuzzer
+                        libc::_exit(0);
+                    }
+
+                    let mut status = 0;
+                    while libc::waitpid(pid, &mut status, 0) < 0 {
+                        let error = std::io::Error::last_os_error();
+                        assert!(
+                            error.kind() == std::io::ErrorKind::Interrupted,
+                            "could not wait for the forked child process: {}",
+                            error
+                        );
+                    }
+
+                    if libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0 {
+                        let mut counters = self.shared.add(std::mem::size_of::<Header>()) as *const u8;
+                        for &(start, len) in self.regions.iter() {
+                            std::ptr::copy_nonoverlapping(counters, start, len);
+                            counters = counters.add(len);
+                        }
+                        let test_failure = header.test_failure;
+                        if test_failure {
+                            let display = &header.display[..header.display_len];
+                            TEST_FAILURE = Some(TestFailure {
+                                display: String::from_utf8_lossy(display).into_owned(),
+                                id: header.failure_id,
+                            });
+                        }
+                        test_failure
+                    } else if libc::WIFEXITED(status) {
+                        let code = libc::WEXITSTATUS(status);
+                        TEST_FAILURE = Some(TestFailure {
+                            display: format!("the test function exited the process with code {}", code),
+                            id: code as u64,
+                        });
+                        true
+                    } else if libc::WIFSIGNALED(status) {
+                        let signal = libc::WTERMSIG(status);
+                        if INTERRUPT_SIGNALS.contains(&signal) && was_interrupted() {
+                            // the child received the same interrupt signal as the fuzzer, e.g. from CTRL+C,
+                            // which does not mean that the test failed
+                            return false;
+                        }
+                        let display = if signal == libc::SIGALRM && detect_infinite_loop {
+                            TIMEOUT_DISPLAY.to_string()
+                        } else {
+                            format!("the test function crashed with signal {}", signal)
+                        };
+                        TEST_FAILURE = Some(TestFailure {
+                            display,
+                            id: signal as u64,
+                        });
+                        true
+                    } else {
+                        unreachable!()
+                    }
+                }
+            }
+        }
+
+        impl Drop for ForkExecutor {
+            #[coverage(off)]
+            fn drop(&mut self) {
+                unsafe {
+                    libc::munmap(self.shared as *mut libc::c_void, self.shared_len);
+                }
+            }
+        }
+    } else {
+        pub(crate) struct ForkExecutor;
+
+        impl ForkExecutor {
+            #[coverage(off)]
+            pub fn new() -> Self {
+                panic!("the fork mode is only supported on unix")
+            }
+
+            #[coverage(off)]
+            pub fn run(&mut self, _detect_infinite_loop: bool, _run_test: impl FnOnce() -> bool) -> bool {
+                unreachable!()
+            }
+        }
+    }
+}
@@ -1,35 +1,75 @@
 use std::any::{Any, TypeId};
 use std::borrow::Borrow;
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
 use std::hash::{Hash, Hasher};
 use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
 use std::process::exit;
 use std::result::Result;
+use std::time::{Duration, SystemTime};
 
-use fuzzcheck_common::arg::{Arguments, FuzzerCommand};
-use fuzzcheck_common::{FuzzerEvent, FuzzerStats};
-use libc::{SIGABRT, SIGALRM, SIGBUS, SIGFPE, SIGINT, SIGSEGV, SIGTERM, SIGTRAP};
+use fuzzcheck_common::arg::{Arguments, ExitCodePolicy, FuzzerCommand, NoProgressBudget};
+use fuzzcheck_common::{FuzzerEvent, FuzzerStats, TerminationStatus, TERMINATION_STATUS_FILE_ENV};
+use libc::{SIGINT, SIGTERM};
 
+use crate::bench::bench;
+use crate::code_coverage_sensor::{CodeCoverageSensor, CoverageFilter};
+use crate::corpus_analysis::analyze_corpus;
+use crate::coverage_diff::coverage_diff;
 use crate::data_structures::RcSlab;
+use crate::executed_inputs::{ExecutedInputs, EXECUTED_INPUTS_FILE};
+use crate::fork_executor::ForkExecutor;
+use crate::mutators::filter::FilterStats;
+use crate::mutators::operators::OperatorsSession;
+use crate::mutators::validate::{check_mutator_by_key, CheckOptions};
+use crate::observation_cache::ObservationCache;
+use crate::observations_record::{repool, ObservationsRecord, OBSERVATIONS_RECORD_FILE, REPOOL_STATS_FILE};
+use crate::provenance::{ArtifactOrigin, Derivation, Lineage, Provenance};
 use crate::sensors_and_pools::{
     AndSensorAndPool, NoopSensor, TestFailure, TestFailurePool, TestFailureSensor, UnitPool, TEST_FAILURE,
 };
-use crate::signals_handler::set_signal_handlers;
-use crate::subvalue_provider::{CrossoverSubValueProvider, Generation, SubValueProviderId};
-use crate::traits::{CorpusDelta, Mutator, SaveToStatsFolder, SensorAndPool, Serializer};
-use crate::world::World;
+use crate::signals_handler::{
+    reset_sanitizer_death_handler, set_infinite_loop_alarm, set_sanitizer_death_handler, set_signal_handlers,
+    was_interrupted, CRASH_SIGNALS,
+};
+use crate::stability::{CounterStability, NBR_STABILITY_RERUNS};
+use crate::subvalue_provider::{
+    CorpusSubValueProvider, CrossoverSubValueProvider, EmptySubValueProvider, Generation, SubValueProviderId,
+};
+use crate::traits::{CorpusDelta, Mutator, SaveToStatsFolder, Sensor, SensorAndPool, Serializer, Stats};
+use crate::world::{ResourceUsage, World};
 use crate::{CSVField, SubValueProvider, ToCSV};
 
 static WRITE_STATS_ERROR: &str = "the stats could not be written to the file system";
 static WORLD_NEW_ERROR: &str = "an IO operation failed when setting up the fuzzer";
 static SERIALIZER_FROM_DATA_ERROR: &str = "the file could not be decoded into a valid input";
 static READ_INPUT_FILE_ERROR: &str = "the input file could not be read";
+static INCOMING_CORPUS_ERROR: &str = "the incoming corpus could not be read or moved to the input corpus";
 static SAVE_ARTIFACTS_ERROR: &str = "the artifact could not be saved";
 static UPDATE_CORPUS_ERROR: &str = "the corpus could not be updated on the file system";
+#[cfg(feature = "serde_json_serializer")]
+static READ_RESUME_STATE_ERROR: &str = "the state of the previous fuzzing session could not be read";
+static READ_EXECUTED_INPUTS_ERROR: &str = "the inputs executed by the previous fuzzing session could not be read";
+static READ_OBSERVATIONS_RECORD_ERROR: &str =
+    "the observations recorded by the previous fuzzing session could not be read";
+static WRITE_OBSERVATIONS_RECORD_ERROR: &str = "the observations of the test cases could not be recorded";
 
 static mut DID_FIND_ANY_TEST_FAILURE: bool = false;
 
+/// The fuzzer warns when the filter mutators reject a larger proportion of the values they generate
+const FILTER_REJECTION_RATE_WARNING_THRESHOLD: f64 = 0.9;
+/// The minimum number of values that must be filtered before warning about the rejection rate of the filter mutators
+const MIN_NBR_FILTERED_VALUES_FOR_WARNING: u64 = 1000;
+
+/// How often the fuzzer looks for new test cases in the [incoming corpus](Arguments::incoming_corpus)
+const INCOMING_CORPUS_SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum number of hashes of observations remembered by the [observation cache](ObservationCache), after
+/// which the least recently seen ones are forgotten
+const OBSERVATION_CACHE_CAPACITY: usize = 1 << 16;
+
 /// The result of a fuzz test, if it ends.
 ///
 /// It contains two fields:
@@ -51,6 +91,55 @@ pub enum ReasonForStopping<T> {
     ExhaustedAllPossibleMutations,
     MaxIterationsReached,
     MaxDurationReached,
+    /// No pool reported a change to the corpus within the budget given by
+    /// [`stop_after_no_progress`](crate::Arguments::stop_after_no_progress)
+    NoProgress,
+    /// The fuzzer received an interrupt signal, for example because CTRL+C was pressed
+    UserInterrupt,
+}
+
+/// Functions called before and after every execution of the test function, outside of the region
+/// observed by the sensors
+pub(crate) struct TestHooks<T> {
+    pub before_each: Option<Box<dyn Fn(&T)>>,
+    /// The second argument is `true` if the test function succeeded
+    pub after_each: Option<Box<dyn Fn(&T, bool)>>,
+}
+
+impl<T> Default for TestHooks<T> {
+    #[coverage(off)]
+    fn default() -> Self {
+        Self {
+            before_each: None,
+            after_each: None,
+        }
+    }
+}
+
+impl<T> TestHooks<T> {
+    #[coverage(off)]
+    pub(crate) fn before(&self, value: &T) {
+        if let Some(before_each) = &self.before_each {
+            before_each(value);
+        }
+    }
+    #[coverage(off)]
+    pub(crate) fn after(&self, value: &T, success: bool) {
+        if let Some(after_each) = &self.after_each {
+            after_each(value, success);
+        }
+    }
+}
+
+/// What happened during a call to [`Fuzzer::step`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FuzzerStep {
+    /// The number of test cases that were run
+    pub nbr_runs: usize,
+    /// The number of test cases that were added to the corpus
+    pub nbr_added: usize,
+    /// The number of test cases that were removed from the corpus
+    pub nbr_removed: usize,
 }
 
 /// The index to a test case in the fuzzer’s storage.
@@ -90,6 +179,10 @@ struct FuzzedInput<T: Clone + 'static, Mut: Mutator<T>> {
     cache: Mut::Cache,
     mutation_step: Mut::MutationStep,
     generation: Generation,
+    /// The number of times the input was mutated, used to resume a fuzzing session
+    nbr_mutations: usize,
+    /// The identity of the input in the provenance records, if they are kept
+    lineage: Lineage,
 }
 impl<T: Clone + 'static, Mut: Mutator<T>> Clone for FuzzedInput<T, Mut> {
     fn clone(&self) -> Self {
@@ -98,6 +191,8 @@ impl<T: Clone + 'static, Mut: Mutator<T>> Clone for FuzzedInput<T, Mut> {
             cache: self.cache.clone(),
             mutation_step: self.mutation_step.clone(),
             generation: self.generation,
+            nbr_mutations: self.nbr_mutations,
+            lineage: self.lineage.clone(),
         }
     }
 }
@@ -110,6 +205,8 @@ impl<T: Clone + 'static, Mut: Mutator<T>> FuzzedInput<T, Mut> {
             cache,
             mutation_step,
             generation,
+            nbr_mutations: 0,
+            lineage: Lineage::default(),
         }
     }
 
@@ -132,6 +229,7 @@ impl<T: Clone + 'static, Mut: Mutator<T>> FuzzedInput<T, Mut> {
         subvalue_provider: &dyn SubValueProvider,
         max_cplx: f64,
     ) -> Option<(Mut::UnmutateToken, f64)> {
+        self.nbr_mutations += 1;
         m.ordered_mutate(
             &mut self.value,
             &mut self.cache,
@@ -145,24 +243,86 @@ impl<T: Clone + 'static, Mut: Mutator<T>> FuzzedInput<T, Mut> {
     fn unmutate(&mut self, m: &Mut, t: Mut::UnmutateToken) {
         m.unmutate(&mut self.value, &mut self.cache, t);
     }
+
+    /// Advance the mutation step as if the input had been mutated `nbr_mutations` times, in order to
+    /// resume a fuzzing session from where it was stopped.
+    ///
+    /// This is only an approximation of the mutation step of the previous session. The mutations are replayed
+    /// without the subvalues of the corpus, and with the random number generator of the current session, so
+    /// they can differ from those that were performed. And since replaying them takes a time proportional to
+    /// their number, at most [`MAX_FAST_FORWARDED_MUTATIONS`] are replayed.
+    #[coverage(off)]
+    fn fast_forward(&mut self, m: &Mut, nbr_mutations: usize, max_cplx: f64) {
+        for _ in 0..nbr_mutations.min(MAX_FAST_FORWARDED_MUTATIONS) {
+            if let Some((t, _)) = self.mutate(m, &EmptySubValueProvider, max_cplx) {
+                self.unmutate(m, t);
+            } else {
+                break;
+            }
+        }
+        self.nbr_mutations = nbr_mutations;
+    }
 }
 
 struct FuzzerState<T: Clone + 'static, M: Mutator<T>> {
     mutator: M,
     sensor_and_pool: Box<dyn SensorAndPool>,
     pool_storage: RcSlab<FuzzedInputAndSubValueProvider<T, M>>,
+    /// A sample of the subvalues of all the test cases in `pool_storage`, used for crossover mutations
+    corpus_subvalues: CorpusSubValueProvider,
     /// The step given to the mutator when the fuzzer wants to create a new arbitrary test case
     arbitrary_step: M::ArbitraryStep,
     /// The index of the test case that is being tested
     input_idx: FuzzerInputIndex<FuzzedInput<T, M>>,
+    /// How the test case that is being tested was obtained
+    derivation: Derivation,
+    /// The provenance of the test cases added to the corpus, if it is tracked
+    provenance: Option<Provenance>,
     /// Various statistics about the fuzzer run
     fuzzer_stats: FuzzerStats,
+    /// The number of test cases added to and removed from the corpus since the fuzzer was created
+    nbr_corpus_additions: usize,
+    nbr_corpus_removals: usize,
+    /// The number of runs and the time elapsed since the start of the fuzzer when a pool last reported
+    /// a change to the corpus
+    last_corpus_delta: (usize, Duration),
+    /// The time elapsed since the start of the fuzzer when the incoming corpus was last scanned
+    last_incoming_corpus_scan: Duration,
+    /// The files of the incoming corpus that were already read but are still there, along with their
+    /// modification time, such that they are only read again if they are modified
+    incoming_corpus_files: HashMap<PathBuf, SystemTime>,
+    /// The identifier of the failure of the test case being minified, if only the simpler test cases that fail
+    /// in the same way are kept, as requested by [`Arguments::preserve_failure`]
+    failure_to_preserve: Option<u64>,
+    /// The hashes of the observations already analyzed by the pools, which are not analyzed again if they cannot
+    /// improve the pools, or if they are [sampled](Arguments::observation_sampling)
+    observation_cache: ObservationCache,
+    /// The statistics of the mutation operators during the fuzzing session
+    operators: OperatorsSession,
 
     settings: Arguments,
     serializer: Box<dyn Serializer<Value = T>>,
+    /// Additional serializers used to read the files of the input corpus whose extension
+    /// is different from `serializer.extension()`
+    corpus_readers: Vec<Box<dyn Serializer<Value = T>>>,
+    /// The values given to the fuzzer builder to be tested before fuzzing starts, along with the input corpus
+    initial_inputs: Vec<T>,
     /// The world handles effects
     world: World,
     rng: fastrand::Rng,
+    /// The number of mutations performed on each input of the corpus, indexed by the hash of its content,
+    /// during the fuzzing session being resumed
+    resumed_mutations: HashMap<String, usize>,
+    /// The test cases that were already executed, which are skipped if they are generated again
+    ///
+    /// It is `None` if the deduplication of inputs is disabled
+    executed_inputs: Option<ExecutedInputs>,
+    /// Runs the test cases in forked child processes, if the fork mode is enabled
+    fork_executor: Option<ForkExecutor>,
+    /// The observations of the sensor for every test case, if they are recorded for the `repool` command
+    observations_record: Option<ObservationsRecord>,
+    /// Checks that the new code coverage of each test case is reproducible, if enabled
+    stability_check: Option<CounterStability>,
 
     signal_handler_alt_stack: Option<(*mut u8, std::alloc::Layout)>,
 }
@@ -172,6 +332,7 @@ impl<T: Clone + 'static, M: Mutator<T>> Drop for FuzzerState<T, M> {
     fn drop(&mut self) {
         unsafe {
             crate::signals_handler::reset_signal_handlers();
+            reset_sanitizer_death_handler();
             if let Some((stack_ptr, stack_layout)) = self.signal_handler_alt_stack {
                 std::alloc::dealloc(stack_ptr, stack_layout);
             }
@@ -193,6 +354,32 @@ impl<T: Clone + 'static, M: Mutator<T>> FuzzerState<T, M> {
     }
 }
 
+/// The index of the identifier of the subvalue provider of the whole corpus, which is different
+/// from the indices of the test cases in the fuzzer’s storage
+const CORPUS_SUBVALUE_PROVIDER_IDX: usize = usize::MAX;
+/// The maximum number of subvalues of each type sampled from the whole corpus for crossover mutations
+const MAX_CORPUS_SUBVALUES_PER_TYPE: usize = 256;
+
+/// The name of the file, in the stats folder, from which a fuzzing session can be resumed
+pub(crate) const RESUME_STATE_FILE: &str = "resume.json";
+/// The maximum number of mutations replayed on each test case of the corpus when a fuzzing session is resumed
+const MAX_FAST_FORWARDED_MUTATIONS: usize = 10_000;
+
+/// The state of the fuzzer that is saved to the stats folder, such that a fuzzing session can be resumed later
+///
+/// The resumed session is only an approximation of the previous one. The pools are not saved: their content is
+/// recomputed from the observations of the test cases of the corpus when it is read again, but their other
+/// state, such as the scores of the test cases, starts over. And the progress of the mutations of each test
+/// case cannot be recomputed, so it is [fast-forwarded](FuzzedInput::fast_forward) from the number of mutations
+/// that were performed on it.
+#[cfg(feature = "serde_json_serializer")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ResumeState {
+    total_number_of_runs: usize,
+    /// The number of mutations performed on each test case, identified by the hash of its content
+    mutations: Vec<(String, usize)>,
+}
+
 #[coverage(off)]
 fn update_fuzzer_stats(stats: &mut FuzzerStats, world: &mut World) {
     let microseconds = world.elapsed_time_since_last_checkpoint();
@@ -215,6 +402,29 @@ where
     fn save_to_stats_folder(&self) -> Vec<(std::path::PathBuf, Vec<u8>)> {
         let mut contents = self.sensor_and_pool.save_to_stats_folder();
         contents.extend(self.world.save_to_stats_folder());
+        if let Some(executed_inputs) = &self.executed_inputs {
+            contents.extend(executed_inputs.save_to_stats_folder());
+        }
+        if let Some(provenance) = &self.provenance {
+            contents.extend(provenance.files());
+        }
+        contents.extend(self.operators.files());
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "serde_json_serializer")] {
+                // the same test case can be saved in the corpus of multiple pools
+                let mut mutations = HashMap::<String, usize>::new();
+                for ((_, idx), (name, _)) in self.world.corpus.iter() {
+                    let nbr_mutations = self.pool_storage[idx.0].input.nbr_mutations;
+                    mutations.insert(World::hash_from_file_name(name).to_owned(), nbr_mutations);
+                }
+                let resume_state = ResumeState {
+                    total_number_of_runs: self.fuzzer_stats.total_number_of_runs,
+                    mutations: mutations.into_iter().collect(),
+                };
+                let content = serde_json::to_vec(&resume_state).unwrap();
+                contents.push((std::path::PathBuf::new().join(RESUME_STATE_FILE), content));
+            }
+        }
         contents
     }
 }
@@ -225,7 +435,14 @@ where
 {
     #[coverage(off)]
     fn write_stats(&mut self) -> Result<(), std::io::Error> {
-        self.world.write_stats_content(self.save_to_stats_folder())
+        self.world.write_stats_content(self.save_to_stats_folder())?;
+        if let Some(executed_inputs) = &mut self.executed_inputs {
+            executed_inputs.did_save();
+        }
+        if let Some(observations_record) = &mut self.observations_record {
+            observations_record.flush()?;
+        }
+        Ok(())
     }
 
     #[coverage(off)]
@@ -236,34 +453,64 @@ where
         );
 
         match signal {
-            SIGABRT | SIGBUS | SIGSEGV | SIGFPE | SIGALRM | SIGTRAP => {
+            signal if CRASH_SIGNALS.contains(&signal) => {
                 if let Some(input) = Self::get_input(&self.input_idx, &self.pool_storage) {
+                    let origin =
+                        ArtifactOrigin::new(self.derivation, &input.lineage, self.operators.applied_operators());
                     let input = input.new_source(&self.mutator, Generation(0));
                     let cplx = input.complexity(&self.mutator);
-                    let content = self.serializer.to_data(&input.value);
-                    let _ = self.world.save_artifact(content, cplx, self.serializer.extension());
+                    let _ = self
+                        .world
+                        .save_artifact(&input.value, self.serializer.as_ref(), cplx, Some(&origin));
                     self.write_stats().expect(WRITE_STATS_ERROR);
-                    exit(TerminationStatus::Crash as i32);
+                    terminate(TerminationStatus::Crash);
                 } else {
                     self.world.report_event(
                         FuzzerEvent::CrashNoInput,
                         Some((&self.fuzzer_stats, self.sensor_and_pool.stats().as_ref())),
                     );
-                    exit(TerminationStatus::Crash as i32);
+                    terminate(TerminationStatus::Crash);
                 }
             }
             SIGINT | SIGTERM => {
                 self.write_stats().expect(WRITE_STATS_ERROR);
-                self.world.stop()
+                self.world
+                    .stop(&self.fuzzer_stats, self.sensor_and_pool.stats().as_ref())
             }
-            _ => exit(TerminationStatus::Unknown as i32),
+            _ => terminate(TerminationStatus::Unknown),
         }
     }
+    /// Save the test case being executed as an artifact, because a sanitizer detected an error.
+    ///
+    /// The sanitizer terminates the process once this function returns.
+    #[coverage(off)]
+    fn receive_sanitizer_report(&mut self) {
+        self.world.report_event(
+            FuzzerEvent::SanitizerReport,
+            Some((&self.fuzzer_stats, self.sensor_and_pool.stats().as_ref())),
+        );
+        if let Some(input) = Self::get_input(&self.input_idx, &self.pool_storage) {
+            let origin = ArtifactOrigin::new(self.derivation, &input.lineage, self.operators.applied_operators());
+            let input = input.new_source(&self.mutator, Generation(0));
+            let cplx = input.complexity(&self.mutator);
+            let _ = self
+                .world
+                .save_artifact(&input.value, self.serializer.as_ref(), cplx, Some(&origin));
+            let _ = self.write_stats();
+        } else {
+            self.world.report_event(
+                FuzzerEvent::CrashNoInput,
+                Some((&self.fuzzer_stats, self.sensor_and_pool.stats().as_ref())),
+            );
+        }
+        // calling `exit` here is not allowed, since LeakSanitizer reports leaks from an exit handler
+        write_termination_status(TerminationStatus::Crash);
+    }
     #[coverage(off)]
     fn arbitrary_input(&mut self) -> Option<(FuzzedInput<T, M>, f64)> {
         if let Some((v, cplx)) = self
             .mutator
-            .ordered_arbitrary(&mut self.arbitrary_step, self.settings.max_input_cplx)
+            .ordered_arbitrary(&mut self.arbitrary_step, self.settings.max_arbitrary_cplx)
         {
             let cache = self.mutator.validate_value(&v).unwrap();
             let step = self.mutator.default_mutation_step(&v, &cache);
@@ -272,25 +519,92 @@ where
             None
         }
     }
+    /// Record that the current input is about to be executed.
+    ///
+    /// Returns `false` if it was already executed, in this fuzzing session or in a previous one. The skipped
+    /// input still counts as a run of the fuzzer, such that the maximum number of iterations is respected.
+    #[coverage(off)]
+    fn record_executed_input(&mut self) -> bool {
+        let Some(executed_inputs) = &mut self.executed_inputs else {
+            return true;
+        };
+        let Some(input) = Self::get_input(&self.input_idx, &self.pool_storage) else {
+            return true;
+        };
+        let is_new = executed_inputs.insert(&self.serializer.to_data(&input.value));
+        if !is_new {
+            self.fuzzer_stats.total_number_of_runs += 1;
+        }
+        is_new
+    }
+
+    /// Decode and validate the test case read from the given corpus file, with the first serializer that
+    /// reads its extension.
+    ///
+    /// It returns `None` if no serializer reads the extension of the file, and the reason why the file is
+    /// invalid if it cannot be read, decoded, or validated.
+    #[coverage(off)]
+    fn decode_corpus_file(
+        &self,
+        path: &Path,
+        data: std::io::Result<Vec<u8>>,
+    ) -> Option<Result<FuzzedInput<T, M>, String>> {
+        let extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+        let serializer = std::iter::once(&self.serializer)
+            .chain(self.corpus_readers.iter())
+            .find(
+                #[coverage(off)]
+                |serializer| serializer.reads_extension(extension),
+            )?;
+        let data = match data {
+            Ok(data) => data,
+            Err(error) => return Some(Err(error.to_string())),
+        };
+        let Some(value) = serializer.from_data_with_extension(&data, extension) else {
+            return Some(Err("it cannot be decoded by the serializer".to_string()));
+        };
+        let Some(cache) = self.mutator.validate_value(&value) else {
+            return Some(Err("it is rejected by the mutator".to_string()));
+        };
+        let mutation_step = self.mutator.default_mutation_step(&value, &cache);
+        Some(Ok(FuzzedInput::new(value, cache, mutation_step, Generation(0))))
+    }
     #[coverage(off)]
     unsafe fn set_up_signal_handler(&mut self) {
         let ptr = self as *mut Self;
-        let (stack_ptr, stack_size) = set_signal_handlers(
+        self.signal_handler_alt_stack = set_signal_handlers(
             #[coverage(off)]
             move |sig| (*ptr).receive_signal(sig),
         );
-        self.signal_handler_alt_stack = Some((stack_ptr, stack_size));
+        set_sanitizer_death_handler(
+            #[coverage(off)]
+            move || (*ptr).receive_sanitizer_report(),
+        );
     }
 }
 
+/// A fuzzer that can be driven one test case at a time.
+///
+/// It is created by [`FuzzerBuilder5::build`](crate::builder::FuzzerBuilder5::build) and is useful to embed
+/// fuzzcheck inside another program, which decides when to run the next test case through [`Fuzzer::step`]
+/// and can inspect the state of the fuzzer in between.
 pub struct Fuzzer<T, M>
 where
     T: Clone + 'static,
     M: Mutator<T>,
     Self: 'static,
 {
-    state: FuzzerState<T, M>,
+    // the state is boxed because the signal handlers keep a pointer to it, which must stay valid
+    // when the fuzzer is moved
+    state: Box<FuzzerState<T, M>>,
     test: Box<dyn Fn(&T) -> bool>,
+    hooks: TestHooks<T>,
+    /// Whether the initial inputs were processed by [`Fuzzer::step`]
+    did_start: bool,
+    /// The number of runs after which the next `Pulse` event is reported
+    next_milestone: usize,
+    initial_filter_stats: FilterStats,
+    did_warn_about_filters: bool,
 }
 
 impl<T, M> Fuzzer<T, M>
@@ -302,114 +616,343 @@ where
     #[coverage(off)]
     fn new(
         test: Box<dyn Fn(&T) -> bool>,
+        hooks: TestHooks<T>,
         mutator: M,
         serializer: Box<dyn Serializer<Value = T>>,
-        sensor_and_pool: Box<dyn SensorAndPool>,
+        corpus_readers: Vec<Box<dyn Serializer<Value = T>>>,
+        initial_inputs: Vec<T>,
+        mut sensor_and_pool: Box<dyn SensorAndPool>,
         settings: Arguments,
         world: World,
     ) -> Self {
         let arbitrary_step = mutator.default_arbitrary_step();
+        let mut fuzzer_stats = FuzzerStats::default();
+        let mut resumed_mutations = HashMap::new();
+        if settings.resume {
+            cfg_if::cfg_if! {
+                if #[cfg(feature = "serde_json_serializer")] {
+                    let resume_state = world
+                        .read_resume_state()
+                        .expect(READ_RESUME_STATE_ERROR)
+                        .and_then(
+                            #[coverage(off)]
+                            |content| serde_json::from_slice::<ResumeState>(&content).ok(),
+                        );
+                    if let Some(resume_state) = resume_state {
+                        fuzzer_stats.total_number_of_runs = resume_state.total_number_of_runs;
+                        fuzzer_stats.number_of_runs_since_last_reset_time = resume_state.total_number_of_runs;
+                        resumed_mutations = resume_state.mutations.into_iter().collect();
+                    } else {
+                        println!("No previous fuzzing session was found in the stats folder. Starting a new one.");
+                    }
+                } else {
+                    println!("Fuzzing sessions can only be resumed with the `serde_json_serializer` feature. Starting a new one.");
+                }
+            }
+        }
+        let executed_inputs = if settings.deduplicate_inputs
+            && matches!(settings.command, FuzzerCommand::Fuzz)
+            && settings.stats_folder.is_some()
+        {
+            let executed_inputs = world
+                .read_from_latest_session(EXECUTED_INPUTS_FILE)
+                .expect(READ_EXECUTED_INPUTS_ERROR)
+                .and_then(
+                    #[coverage(off)]
+                    |(_, content)| ExecutedInputs::from_bytes(&content),
+                );
+            Some(executed_inputs.unwrap_or_else(ExecutedInputs::new))
+        } else {
+            None
+        };
+        let fork_executor = if settings.fork { Some(ForkExecutor::new()) } else { None };
+        let operators = OperatorsSession::start(settings.adaptive_mutations, mutator.mutation_operators());
+        let provenance = if settings.track_provenance {
+            Some(Provenance::default())
+        } else {
+            None
+        };
+        let observations_record = if settings.record_observations
+            && matches!(settings.command, FuzzerCommand::Fuzz)
+            && let Some(stats_folder) = &world.stats_folder
+        {
+            Some(ObservationsRecord::new(stats_folder).expect(WRITE_OBSERVATIONS_RECORD_ERROR))
+        } else {
+            None
+        };
+        if matches!(settings.command, FuzzerCommand::Fuzz) {
+            fuzzer_stats.skipped_observations = Some(0);
+        }
+        // the counters are compared with the code coverage sensor of the fuzz test, such that its filters apply
+        // and the counters found to be nondeterministic are ignored by the pools observing it
+        let stability_check = if settings.check_stability && matches!(settings.command, FuzzerCommand::Fuzz) {
+            if let Some(sensor) = sensor_and_pool.code_coverage_sensor() {
+                let stability = CounterStability::new(sensor.count_instrumented);
+                fuzzer_stats.stability = Some(stability.stability());
+                Some(stability)
+            } else {
+                println!("The stability is not checked because the fuzz test does not observe the code coverage.");
+                None
+            }
+        } else {
+            None
+        };
         Fuzzer {
-            state: FuzzerState {
+            state: Box::new(FuzzerState {
                 sensor_and_pool,
                 pool_storage: RcSlab::new(),
+                corpus_subvalues: CorpusSubValueProvider::new(
+                    CORPUS_SUBVALUE_PROVIDER_IDX,
+                    MAX_CORPUS_SUBVALUES_PER_TYPE,
+                ),
                 mutator,
                 arbitrary_step,
                 input_idx: FuzzerInputIndex::None,
-                fuzzer_stats: FuzzerStats::default(),
+                derivation: Derivation::Corpus,
+                provenance,
+                fuzzer_stats,
+                nbr_corpus_additions: 0,
+                nbr_corpus_removals: 0,
+                last_corpus_delta: (0, Duration::ZERO),
+                last_incoming_corpus_scan: Duration::ZERO,
+                incoming_corpus_files: HashMap::new(),
+                failure_to_preserve: None,
+                observation_cache: ObservationCache::new(OBSERVATION_CACHE_CAPACITY),
+                operators,
                 settings,
                 serializer,
+                corpus_readers,
+                initial_inputs,
                 world,
                 rng: fastrand::Rng::new(),
+                resumed_mutations,
+                executed_inputs,
+                fork_executor,
+                observations_record,
+                stability_check,
                 signal_handler_alt_stack: None,
-            },
+            }),
             test,
+            hooks,
+            did_start: false,
+            next_milestone: 0,
+            initial_filter_stats: FilterStats::default(),
+            did_warn_about_filters: false,
         }
     }
 
+    /// Create the fuzzer used by the `fuzz` command, with its signal handlers installed.
+    ///
+    /// Unless the fuzzer stops after the first test failure, a pool that keeps the failing test cases is
+    /// added to the given one.
     #[coverage(off)]
-    fn test_and_process_input(&mut self, cplx: f64) -> Result<(), ReasonForStopping<T>> {
-        let Fuzzer {
-            state:
-                FuzzerState {
-                    mutator,
-                    sensor_and_pool,
-                    pool_storage,
-                    input_idx,
-                    fuzzer_stats,
-                    serializer,
-                    world,
-                    settings,
-                    ..
-                },
+    pub(crate) fn for_fuzz_command(
+        test: Box<dyn Fn(&T) -> bool>,
+        hooks: TestHooks<T>,
+        mutator: M,
+        serializer: Box<dyn Serializer<Value = T>>,
+        corpus_readers: Vec<Box<dyn Serializer<Value = T>>>,
+        initial_inputs: Vec<T>,
+        sensor_and_pool: Box<dyn SensorAndPool>,
+        args: Arguments,
+    ) -> Self {
+        if cfg!(debug_assertions) {
+            debug_check_mutator(&mutator, serializer.as_ref(), args.max_input_cplx);
+        }
+        let sensor_and_pool: Box<dyn SensorAndPool> = if !args.stop_after_first_failure {
+            let test_failure_sensor = TestFailureSensor::default();
+            let test_failure_pool = TestFailurePool::new("test_failures");
+            Box::new(AndSensorAndPool::new(
+                sensor_and_pool,
+                Box::new((test_failure_sensor, test_failure_pool)),
+                10.0,
+                1.0,
+            ))
+        } else {
+            sensor_and_pool
+        };
+        let mut fuzzer = Fuzzer::new(
             test,
-            ..
-        } = self;
+            hooks,
+            mutator,
+            serializer,
+            corpus_readers,
+            initial_inputs,
+            sensor_and_pool,
+            args.clone(),
+            World::new(args).expect(WORLD_NEW_ERROR),
+        );
 
-        // we have verified in the caller function that there is an input
-        let input = FuzzerState::<T, M>::get_input(input_idx, pool_storage).unwrap();
+        let mut stats_headers = vec![CSVField::String("time".to_string())];
+        stats_headers.extend(fuzzer.state.fuzzer_stats.csv_headers());
+        stats_headers.extend(ResourceUsage::default().csv_headers());
+        stats_headers.extend(fuzzer.state.sensor_and_pool.stats().csv_headers());
+        fuzzer
+            .state
+            .world
+            .append_stats_file(&stats_headers)
+            .expect(WRITE_STATS_ERROR);
+        unsafe { fuzzer.state.set_up_signal_handler() };
 
-        std::panic::set_hook(Box::new(
+        fuzzer
+    }
+
+    /// Run the fuzzer for one step.
+    ///
+    /// The first call processes the input corpus and the initial inputs of the fuzzer. Each subsequent call
+    /// mutates one test case of the pool, or generates a new one if the pool is empty, and runs the test
+    /// function on it. The events of the fuzzer, such as the addition of a test case to the corpus, are
+    /// reported and the corpus is updated as with [`launch`](crate::builder::FuzzerBuilder5::launch).
+    ///
+    /// An error is returned when the fuzzer stops, for example because a test failure was found while
+    /// [`stop_after_first_failure`](crate::builder::FuzzerBuilder5::stop_after_first_failure) is enabled,
+    /// or because the maximum number of iterations was reached. The end of the fuzzing session is then
+    /// reported and the fuzzer should not be stepped again.
+    #[coverage(off)]
+    pub fn step(&mut self) -> Result<FuzzerStep, ReasonForStopping<T>> {
+        let nbr_runs = self.state.fuzzer_stats.total_number_of_runs;
+        let nbr_added = self.state.nbr_corpus_additions;
+        let nbr_removed = self.state.nbr_corpus_removals;
+
+        let result = if self.did_start {
+            self.next_iteration()
+        } else {
+            self.did_start = true;
+            self.start(false)
+        };
+        if let Err(reason_for_stopping) = &result {
+            self.report_end_of_fuzzing(reason_for_stopping);
+            self.state.write_stats().expect(WRITE_STATS_ERROR);
+        }
+        result.map(
             #[coverage(off)]
-            move |panic_info| {
-                let mut hasher = DefaultHasher::new();
-                panic_info.location().hash(&mut hasher);
-                unsafe {
-                    TEST_FAILURE = Some(TestFailure {
-                        display: format!("{}", panic_info),
-                        id: hasher.finish(),
-                    });
-                }
+            |()| FuzzerStep {
+                nbr_runs: self.state.fuzzer_stats.total_number_of_runs - nbr_runs,
+                nbr_added: self.state.nbr_corpus_additions - nbr_added,
+                nbr_removed: self.state.nbr_corpus_removals - nbr_removed,
             },
-        ));
-        if settings.detect_infinite_loop {
-            let _old_time_left = unsafe { libc::alarm(1) };
-            // TODO: I think setitimer should be prefered, but libc
-            // doesn't support it on linux, see:
-            // https://github.com/rust-lang/libc/issues/1347#event-3879031340
-
-            // let success = unsafe {
-            // let t = itimerval {
-            //     it_interval: libc::timeval { tv_sec: 0, tv_usec: 0 },
-            //     it_value: libc::timeval { tv_sec: 1, tv_usec: 0 },
-            // };
-            // libc::setitimer(ITIMER_REAL, &t, std::ptr::null_mut())
-            // };
-            // assert!(success == 0);
-        }
-        sensor_and_pool.start_recording();
-        let result = catch_unwind(AssertUnwindSafe(
+        )
+    }
+
+    /// Various statistics about the fuzzer, such as the total number of runs of the test function
+    #[coverage(off)]
+    pub fn stats(&self) -> &FuzzerStats {
+        &self.state.fuzzer_stats
+    }
+
+    /// The statistics of the pool, as they are displayed in the terminal
+    #[coverage(off)]
+    pub fn pool_stats(&self) -> Box<dyn Stats> {
+        self.state.sensor_and_pool.stats()
+    }
+
+    /// Iterate over the test cases of the corpus, in no particular order
+    #[coverage(off)]
+    pub fn corpus(&self) -> impl Iterator<Item = &T> {
+        self.state.pool_storage.keys().map(
             #[coverage(off)]
-            || (test)(input.value.borrow()),
-        ));
+            |key| &self.state.pool_storage[key].input.value,
+        )
+    }
 
-        let _ = std::panic::take_hook();
-        let test_failure = match result {
-            Ok(false) => unsafe {
-                TEST_FAILURE = Some(TestFailure {
-                    display: "test function returned false".to_string(),
-                    id: 0,
-                });
-                true
-            },
-            Err(_) => {
-                // the panic handler already changed the value of TEST_FAILURE
-                // so we don't need to do anything
-                true
+    #[coverage(off)]
+    fn test_and_process_input(&mut self, cplx: f64) -> Result<(), ReasonForStopping<T>> {
+        let Fuzzer { state, test, hooks, .. } = self;
+        let FuzzerState {
+            mutator,
+            sensor_and_pool,
+            pool_storage,
+            corpus_subvalues,
+            input_idx,
+            derivation,
+            provenance,
+            fuzzer_stats,
+            nbr_corpus_additions,
+            nbr_corpus_removals,
+            last_corpus_delta,
+            serializer,
+            world,
+            settings,
+            resumed_mutations,
+            fork_executor,
+            observations_record,
+            stability_check,
+            failure_to_preserve,
+            observation_cache,
+            operators,
+            ..
+        } = state.as_mut();
+
+        // we have verified in the caller function that there is an input
+        let input = FuzzerState::<T, M>::get_input(input_idx, pool_storage).unwrap();
+
+        // the sensors start recording after the hook, so that its code coverage is not observed
+        hooks.before(&input.value);
+        set_test_failure_panic_hook();
+        sensor_and_pool.start_recording();
+        let run_test = #[coverage(off)]
+        || run_test_function(test.as_ref(), input.value.borrow());
+        let mut execute = #[coverage(off)]
+        || {
+            if let Some(fork_executor) = fork_executor {
+                fork_executor.run(settings.detect_infinite_loop, run_test)
+            } else {
+                // in the fork mode, the alarm is set by the child process instead
+                if settings.detect_infinite_loop {
+                    set_infinite_loop_alarm();
+                }
+                run_test()
             }
-            Ok(true) => false,
         };
+        let mut test_failure = execute();
+        sensor_and_pool.stop_recording();
+        if !test_failure
+            && let Some(stability) = stability_check
+            && let Some(first) = sensor_and_pool.code_coverage_sensor().and_then(
+                #[coverage(off)]
+                |sensor| stability.new_coverage(&sensor.get_observations()),
+            )
+        {
+            // the pools process the observations of the last execution, from which the counters found to be
+            // nondeterministic are removed
+            for _ in 0..NBR_STABILITY_RERUNS {
+                hooks.after(&input.value, true);
+                hooks.before(&input.value);
+                sensor_and_pool.start_recording();
+                test_failure = execute();
+                sensor_and_pool.stop_recording();
+                if test_failure {
+                    break;
+                }
+                // the stability check is only enabled if the fuzz test has a code coverage sensor
+                let sensor = sensor_and_pool.code_coverage_sensor().unwrap();
+                let observations = sensor.get_observations();
+                sensor.ignore_counters(stability.compare(&first, &observations));
+            }
+            stability.finish(&first);
+            fuzzer_stats.stability = Some(stability.stability());
+        }
+        if test_failure
+            && let Some(failure_to_preserve) = failure_to_preserve
+            && unsafe { &*std::ptr::addr_of!(TEST_FAILURE) }.as_ref().is_none_or(
+                #[coverage(off)]
+                |failure| failure.id != *failure_to_preserve,
+            )
+        {
+            // the test case fails in a different way, so it is not a simpler version of the one being minified
+            test_failure = false;
+        }
+        let _ = std::panic::take_hook();
         if test_failure {
             unsafe {
                 DID_FIND_ANY_TEST_FAILURE = true;
             }
         }
-        sensor_and_pool.stop_recording();
-        if test_failure && self.state.settings.stop_after_first_failure {
-            let serialized_input = serializer.to_data(&input.value);
-            self.state
-                .world
-                .save_artifact(serialized_input, cplx, serializer.extension())
+        if test_failure && settings.stop_after_first_failure {
+            hooks.after(&input.value, false);
+            let origin = ArtifactOrigin::new(*derivation, &input.lineage, operators.applied_operators());
+            world
+                .save_artifact(&input.value, serializer.as_ref(), cplx, Some(&origin))
                 .expect(SAVE_ARTIFACTS_ERROR);
             return Err(ReasonForStopping::TestFailure(input.value.clone()));
         }
@@ -418,9 +961,49 @@ where
 
         let input_id = PoolStorageIndex(pool_storage.next_slot());
 
-        let deltas = sensor_and_pool.process(input_id, cplx);
+        // The observations of the test cases created by the fuzzer are not analyzed if they were already seen with
+        // an equal or lower complexity, since they cannot improve the pools. Except every `observation_sampling`
+        // iterations, they are not analyzed either if they were seen with a higher complexity.
+        let may_skip_observations = fuzzer_stats.skipped_observations.is_some()
+            && matches!(
+                derivation,
+                Derivation::Arbitrary | Derivation::Mutation | Derivation::Crossover
+            );
+        // the recorded observations must not be skipped, so that the `repool` command sees all of them
+        let deltas = if let Some(record) = observations_record {
+            let deltas = sensor_and_pool.process_and_record(input_id, cplx, record.buffer());
+            record
+                .push(fuzzer_stats.total_number_of_runs, cplx)
+                .expect(WRITE_OBSERVATIONS_RECORD_ERROR);
+            deltas
+        } else if may_skip_observations {
+            let ignore_complexity = fuzzer_stats.total_number_of_runs % settings.observation_sampling != 0;
+            let deltas = sensor_and_pool.process_unless_known(
+                input_id,
+                cplx,
+                &mut #[coverage(off)]
+                |hash| observation_cache.record(hash, cplx, ignore_complexity),
+            );
+            deltas.unwrap_or_else(
+                #[coverage(off)]
+                || {
+                    *fuzzer_stats.skipped_observations.as_mut().unwrap() += 1;
+                    vec![]
+                },
+            )
+        } else {
+            sensor_and_pool.process(input_id, cplx)
+        };
+        // the observations of the sensors are read from the global code coverage counters, so the hook
+        // is only called once they were processed
+        hooks.after(&input.value, !test_failure);
+        operators.report_applied_operators(deltas.iter().any(
+            #[coverage(off)]
+            |delta| delta.add,
+        ));
 
         if !deltas.is_empty() {
+            *last_corpus_delta = (fuzzer_stats.total_number_of_runs, world.elapsed_time_since_start());
             let add_ref_count = deltas.iter().fold(
                 0,
                 #[coverage(off)]
@@ -428,20 +1011,56 @@ where
             );
             update_fuzzer_stats(fuzzer_stats, world);
             let event = CorpusDelta::fuzzer_event(&deltas);
-            let content = if add_ref_count > 0 {
-                serializer.to_data(&input.value)
+            let hash = if add_ref_count > 0 {
+                world.hash_value(&input.value, serializer.as_ref())
             } else {
-                vec![]
+                String::new()
             };
+            let nbr_resumed_mutations = if add_ref_count > 0 && !resumed_mutations.is_empty() {
+                resumed_mutations.remove(&hash)
+            } else {
+                None
+            };
+            let lineage = if add_ref_count > 0
+                && let Some(provenance) = provenance
+            {
+                // only the test cases of the pool have a parent, since the temporary ones are not mutated
+                let parent = if let FuzzerInputIndex::Pool(_) = input_idx {
+                    Some(&input.lineage)
+                } else {
+                    None
+                };
+                Some(provenance.record(
+                    hash.clone(),
+                    parent,
+                    *derivation,
+                    fuzzer_stats.total_number_of_runs,
+                    cplx,
+                ))
+            } else {
+                None
+            };
+            if add_ref_count > 0
+                && let FuzzerInputIndex::Pool(parent) = &*input_idx
+            {
+                sensor_and_pool.record_interesting_child(*parent);
+            }
             world
-                .update_corpus(input_id, content, &deltas, serializer.extension())
+                .update_corpus(input_id, &input.value, serializer.as_ref(), &hash, cplx, &deltas)
                 .expect(UPDATE_CORPUS_ERROR);
             world.report_event(event, Some((fuzzer_stats, sensor_and_pool.stats().as_ref())));
             if add_ref_count > 0 {
                 let generation = Generation(fuzzer_stats.total_number_of_runs);
-                let input = input.new_source(mutator, generation);
+                let mut input = input.new_source(mutator, generation);
+                if let Some(nbr_mutations) = nbr_resumed_mutations {
+                    input.fast_forward(mutator, nbr_mutations, settings.max_input_cplx);
+                }
+                if let Some(lineage) = lineage {
+                    input.lineage = lineage;
+                }
                 // check that the mutator's handling of the complexity is correct
-                let serialised = String::from_utf8(serializer.to_data(&input.value)).unwrap();
+                // the serialized value may not be valid utf8, e.g. with binary serializers
+                let serialised = String::from_utf8_lossy(&serializer.to_data(&input.value)).into_owned();
                 assert!(
                     (input.complexity(mutator) - cplx).abs() < 0.01,
                     "The mutator used by the fuzz test does not evaluate the complexity of the test cases consistently.
@@ -479,9 +1098,17 @@ where
                 let stored_input = FuzzedInputAndSubValueProvider { input, subvalues };
                 let storage_idx_2 = pool_storage.insert(stored_input, add_ref_count);
                 assert_eq!(storage_idx_1, storage_idx_2);
+                *nbr_corpus_additions += 1;
+                // the subvalues are removed from `corpus_subvalues` before the test case is dropped
+                unsafe { corpus_subvalues.add_test_case(storage_idx_2, &pool_storage[storage_idx_2].subvalues) };
             }
             for delta in deltas {
                 for r in delta.remove {
+                    // the subvalues are removed from `corpus_subvalues` before the test case is dropped
+                    if pool_storage.ref_count(r.0) == 1 {
+                        corpus_subvalues.remove_test_case(r.0);
+                        *nbr_corpus_removals += 1;
+                    }
                     pool_storage.remove(r.0);
                 }
             }
@@ -493,20 +1120,24 @@ where
     #[coverage(off)]
     fn get_input_and_subvalue_provider<'a>(
         pool_storage: &'a mut RcSlab<FuzzedInputAndSubValueProvider<T, M>>,
+        corpus_subvalues: &'a CorpusSubValueProvider,
         sensor_and_pool: &mut dyn SensorAndPool,
         rng: &fastrand::Rng,
         idx: PoolStorageIndex,
-    ) -> (&'a mut FuzzedInput<T, M>, &'a (impl SubValueProvider + 'a)) {
+    ) -> (&'a mut FuzzedInput<T, M>, &'a dyn SubValueProvider, Derivation) {
         let idx_cross = sensor_and_pool.get_random_index().unwrap();
 
         if idx == idx_cross || rng.u8(..5) == 0 {
             let FuzzedInputAndSubValueProvider { input, subvalues } = &mut pool_storage[idx.0];
-            (input, subvalues)
+            (input, subvalues, Derivation::Mutation)
+        } else if rng.bool() {
+            // crossover with the subvalues of the whole corpus
+            (&mut pool_storage[idx.0].input, corpus_subvalues, Derivation::Crossover)
         } else {
             // crossover of two different test cases
             let (input, FuzzedInputAndSubValueProvider { subvalues, .. }) =
                 pool_storage.get_mut_and_ref(idx.0, idx_cross.0).unwrap();
-            (&mut input.input, subvalues)
+            (&mut input.input, subvalues, Derivation::Crossover)
         }
     }
 
@@ -514,6 +1145,7 @@ where
     fn process_next_input(&mut self) -> Result<(), ReasonForStopping<T>> {
         let FuzzerState {
             pool_storage,
+            corpus_subvalues,
             sensor_and_pool,
             input_idx,
             mutator,
@@ -521,19 +1153,27 @@ where
             rng,
             fuzzer_stats,
             world,
+            operators,
             ..
-        } = &mut self.state;
+        } = self.state.as_mut();
 
+        operators.clear_applied_operators();
         if let Some(idx) = sensor_and_pool.get_random_index() {
             *input_idx = FuzzerInputIndex::Pool(idx);
-            let (input, subvalue_provider) =
-                Self::get_input_and_subvalue_provider(pool_storage, sensor_and_pool.as_mut(), rng, idx);
+            let (input, subvalue_provider, derivation) = Self::get_input_and_subvalue_provider(
+                pool_storage,
+                corpus_subvalues,
+                sensor_and_pool.as_mut(),
+                rng,
+                idx,
+            );
             let generation = input.generation;
             if let Some((unmutate_token, complexity)) =
                 input.mutate(mutator, subvalue_provider, settings.max_input_cplx)
             {
                 //drop(subvalue_provider);
-                if complexity < self.state.settings.max_input_cplx {
+                self.state.derivation = derivation;
+                if complexity < self.state.settings.max_input_cplx && self.state.record_executed_input() {
                     self.test_and_process_input(complexity)?;
                 }
 
@@ -553,8 +1193,9 @@ where
             }
         } else if let Some((input, cplx)) = self.state.arbitrary_input() {
             self.state.input_idx = FuzzerInputIndex::Temporary(input);
+            self.state.derivation = Derivation::Arbitrary;
 
-            if cplx < self.state.settings.max_input_cplx {
+            if cplx < self.state.settings.max_input_cplx && self.state.record_executed_input() {
                 self.test_and_process_input(cplx)?;
             }
 
@@ -568,9 +1209,12 @@ where
         }
     }
 
+    /// Decode the test cases of the input corpus, and report the files that could not be decoded
     #[coverage(off)]
-    fn process_initial_inputs(&mut self) -> Result<(), ReasonForStopping<T>> {
-        let mut inputs: Vec<FuzzedInput<T, M>> = self
+    fn read_input_corpus(&mut self) -> Vec<(FuzzedInput<T, M>, Derivation)> {
+        let mut nbr_skipped_files = 0;
+        let mut nbr_invalid_files = 0;
+        let inputs: Vec<(FuzzedInput<T, M>, Derivation)> = self
             .state
             .world
             .read_input_corpus()
@@ -578,40 +1222,255 @@ where
             .into_iter()
             .filter_map(
                 #[coverage(off)]
-                |value| {
-                    let value = self.state.serializer.from_data(&value)?;
-                    let cache = self.state.mutator.validate_value(&value)?;
-                    let mutation_step = self.state.mutator.default_mutation_step(&value, &cache);
-                    Some(FuzzedInput::new(value, cache, mutation_step, Generation(0)))
+                |(path, data)| match self.state.decode_corpus_file(&path, data) {
+                    None => {
+                        nbr_skipped_files += 1;
+                        None
+                    }
+                    Some(Err(reason)) => {
+                        self.state.world.report_invalid_corpus_file(&path, &reason);
+                        nbr_invalid_files += 1;
+                        None
+                    }
+                    Some(Ok(input)) => Some((input, Derivation::Corpus)),
                 },
             )
             .collect();
+        if nbr_skipped_files > 0 {
+            self.state
+                .world
+                .report_event(FuzzerEvent::SkippedCorpusFiles(nbr_skipped_files), None);
+        }
+        if nbr_invalid_files > 0 {
+            self.state
+                .world
+                .report_event(FuzzerEvent::SkippedInvalidCorpusFiles(nbr_invalid_files), None);
+        }
+        inputs
+    }
+
+    #[coverage(off)]
+    fn process_initial_inputs(&mut self) -> Result<(), ReasonForStopping<T>> {
+        let mut inputs = self.read_input_corpus();
+
+        let mut nbr_skipped_initial_inputs = 0;
+        let initial_inputs = std::mem::take(&mut self.state.initial_inputs);
+        inputs.extend(initial_inputs.into_iter().filter_map(
+            #[coverage(off)]
+            |value| {
+                let Some(cache) = self.state.mutator.validate_value(&value) else {
+                    nbr_skipped_initial_inputs += 1;
+                    return None;
+                };
+                let mutation_step = self.state.mutator.default_mutation_step(&value, &cache);
+                Some((
+                    FuzzedInput::new(value, cache, mutation_step, Generation(0)),
+                    Derivation::InitialInput,
+                ))
+            },
+        ));
+        if nbr_skipped_initial_inputs > 0 {
+            self.state
+                .world
+                .report_event(FuzzerEvent::SkippedInitialInputs(nbr_skipped_initial_inputs), None);
+        }
 
         for _ in 0..100 {
             if let Some((input, _)) = self.state.arbitrary_input() {
-                inputs.push(input);
+                inputs.push((input, Derivation::Arbitrary));
             } else {
                 break;
             }
         }
+        self.run_initial_inputs(inputs)
+    }
+
+    /// Run each test case once, in order, and give its observations to the pool
+    #[coverage(off)]
+    fn run_initial_inputs(
+        &mut self,
+        mut inputs: Vec<(FuzzedInput<T, M>, Derivation)>,
+    ) -> Result<(), ReasonForStopping<T>> {
         inputs.retain(
             #[coverage(off)]
-            |i| i.complexity(&self.state.mutator) <= self.state.settings.max_input_cplx,
+            |(i, _)| i.complexity(&self.state.mutator) <= self.state.settings.max_input_cplx,
         );
         // assert!(!inputs.is_empty());
 
         self.state.world.set_checkpoint_instant();
-        for input in inputs {
+        for (input, derivation) in inputs {
+            if was_interrupted() {
+                return Err(ReasonForStopping::UserInterrupt);
+            }
             let cplx = input.complexity(&self.state.mutator);
             self.state.input_idx = FuzzerInputIndex::Temporary(input);
+            self.state.derivation = derivation;
+            // the initial inputs are always executed, since they are needed to recompute the state of the pools
+            self.state.record_executed_input();
             self.test_and_process_input(cplx)?;
         }
 
         Ok(())
     }
 
+    /// Run each test case of the merged corpora once, such that the output corpus only contains the
+    /// test cases kept by the pool
     #[coverage(off)]
-    fn main_loop(&mut self, minify: bool) -> Result<!, ReasonForStopping<T>> {
+    fn merge_corpora(&mut self) -> Result<(), ReasonForStopping<T>> {
+        self.state.world.report_event(
+            FuzzerEvent::Start,
+            Some((&self.state.fuzzer_stats, self.state.sensor_and_pool.stats().as_ref())),
+        );
+        let inputs = self.read_input_corpus();
+        let nbr_inputs = inputs.len();
+        self.run_initial_inputs(inputs)?;
+        self.state.world.report_event(
+            FuzzerEvent::Done,
+            Some((&self.state.fuzzer_stats, self.state.sensor_and_pool.stats().as_ref())),
+        );
+        let nbr_kept_inputs = self
+            .state
+            .world
+            .corpus
+            .keys()
+            .map(
+                #[coverage(off)]
+                |(_, idx)| *idx,
+            )
+            .collect::<HashSet<_>>()
+            .len();
+        println!(
+            "Merged {} test cases into {} test cases of the output corpus",
+            nbr_inputs, nbr_kept_inputs
+        );
+        Ok(())
+    }
+
+    /// Run the test case being minified, which is the only one of the fuzzer’s storage, and record how it fails,
+    /// such that only the simpler test cases that fail in the same way are kept
+    #[coverage(off)]
+    fn record_failure_to_preserve(&mut self) {
+        let input = &self.state.pool_storage[0].input;
+        self.hooks.before(&input.value);
+        set_test_failure_panic_hook();
+        let test_failure = run_test_function(self.test.as_ref(), &input.value);
+        let _ = std::panic::take_hook();
+        self.hooks.after(&input.value, !test_failure);
+        let failure = if test_failure {
+            unsafe { (*std::ptr::addr_of!(TEST_FAILURE)).clone() }
+        } else {
+            None
+        };
+        if let Some(failure) = &failure {
+            println!("Only keeping the test cases that fail in the same way as the one being minified:");
+            println!("{}", failure.display);
+        } else {
+            println!("The test case being minified does not fail, so every test failure is kept.");
+        }
+        self.state.failure_to_preserve = failure.map(
+            #[coverage(off)]
+            |failure| failure.id,
+        );
+    }
+
+    /// Run the test cases saved in the incoming corpus since it was last scanned, then move them to the
+    /// input corpus
+    #[coverage(off)]
+    fn process_incoming_corpus(&mut self) -> Result<(), ReasonForStopping<T>> {
+        let files = self.state.world.incoming_corpus_files().expect(INCOMING_CORPUS_ERROR);
+        let mut nbr_read_files = 0;
+        for (path, modified) in files {
+            if self.state.incoming_corpus_files.get(&path) == Some(&modified) {
+                continue;
+            }
+            let data = self.state.world.read_input_file(&path);
+            let input = match self.state.decode_corpus_file(&path, data) {
+                // the file may still be written by another program, under a temporary name
+                None => continue,
+                Some(Err(reason)) => {
+                    self.state.world.report_invalid_corpus_file(&path, &reason);
+                    self.state.incoming_corpus_files.insert(path, modified);
+                    continue;
+                }
+                Some(Ok(input)) => input,
+            };
+            nbr_read_files += 1;
+            let cplx = input.complexity(&self.state.mutator);
+            if cplx <= self.state.settings.max_input_cplx {
+                self.state.input_idx = FuzzerInputIndex::Temporary(input);
+                self.state.derivation = Derivation::Incoming;
+                self.state.record_executed_input();
+                self.test_and_process_input(cplx)?;
+            }
+            if !self
+                .state
+                .world
+                .move_to_input_corpus(&path)
+                .expect(INCOMING_CORPUS_ERROR)
+            {
+                self.state.incoming_corpus_files.insert(path, modified);
+            }
+        }
+        if nbr_read_files > 0 {
+            self.state.world.report_event(
+                FuzzerEvent::ReadIncomingCorpus(nbr_read_files),
+                Some((&self.state.fuzzer_stats, self.state.sensor_and_pool.stats().as_ref())),
+            );
+        }
+        Ok(())
+    }
+
+    /// Empty the fuzzer’s storage and replace its content with the single given test case,
+    /// located at `PoolStorageIndex(0)`.
+    #[coverage(off)]
+    fn reset_pool_storage_with_single_input(&mut self, value: T, cache: M::Cache) {
+        let mutation_step = self.state.mutator.default_mutation_step(&value, &cache);
+        self.state.corpus_subvalues.clear();
+        self.state.pool_storage = RcSlab::new();
+        self.state.input_idx = FuzzerInputIndex::None;
+
+        let storage_idx_1 = self.state.pool_storage.next_slot();
+        let generation = Generation(0);
+        let subvalues = CrossoverSubValueProvider::new(
+            SubValueProviderId {
+                idx: storage_idx_1,
+                generation,
+            },
+            &value,
+            &cache,
+            &self.state.mutator,
+        );
+        let stored_input = FuzzedInputAndSubValueProvider {
+            input: FuzzedInput::new(value, cache, mutation_step, generation),
+            subvalues,
+        };
+        let storage_idx_2 = self.state.pool_storage.insert(stored_input, 1);
+
+        assert_eq!(storage_idx_1, storage_idx_2);
+        unsafe {
+            self.state
+                .corpus_subvalues
+                .add_test_case(storage_idx_2, &self.state.pool_storage[storage_idx_2].subvalues)
+        };
+    }
+
+    /// Report that the fuzzer stopped, with a summary of the fuzzing session
+    #[coverage(off)]
+    fn report_end_of_fuzzing(&mut self, reason_for_stopping: &ReasonForStopping<T>) {
+        let event = if let ReasonForStopping::UserInterrupt = reason_for_stopping {
+            FuzzerEvent::Stop
+        } else {
+            FuzzerEvent::Done
+        };
+        self.state.world.report_event(
+            event,
+            Some((&self.state.fuzzer_stats, self.state.sensor_and_pool.stats().as_ref())),
+        );
+    }
+
+    /// Report the start of the fuzzing session and, unless minifying, process the initial inputs
+    #[coverage(off)]
+    fn start(&mut self, minify: bool) -> Result<(), ReasonForStopping<T>> {
         self.state.world.report_event(
             FuzzerEvent::Start,
             Some((&self.state.fuzzer_stats, self.state.sensor_and_pool.stats().as_ref())),
@@ -625,40 +1484,160 @@ where
         }
 
         self.state.world.set_checkpoint_instant();
-        let mut next_milestone = (self.state.fuzzer_stats.total_number_of_runs + 10) * 2;
-        loop {
-            let duration_since_beginning = self.state.world.elapsed_time_since_start();
-            if duration_since_beginning > self.state.settings.maximum_duration {
-                return Err(ReasonForStopping::MaxDurationReached);
+        // the budget of `stop_after_no_progress` starts once the initial inputs are processed
+        self.state.last_corpus_delta = (
+            self.state.fuzzer_stats.total_number_of_runs,
+            self.state.world.elapsed_time_since_start(),
+        );
+        self.next_milestone = (self.state.fuzzer_stats.total_number_of_runs + 10) * 2;
+        self.initial_filter_stats = FilterStats::global();
+        self.did_warn_about_filters = false;
+        Ok(())
+    }
+
+    /// Check whether the fuzzer should stop, then process the next input and report the progress of the fuzzer
+    #[coverage(off)]
+    fn next_iteration(&mut self) -> Result<(), ReasonForStopping<T>> {
+        if was_interrupted() {
+            return Err(ReasonForStopping::UserInterrupt);
+        }
+        let duration_since_beginning = self.state.world.elapsed_time_since_start();
+        if duration_since_beginning > self.state.settings.maximum_duration {
+            return Err(ReasonForStopping::MaxDurationReached);
+        }
+        if self.state.fuzzer_stats.total_number_of_runs >= self.state.settings.maximum_iterations {
+            return Err(ReasonForStopping::MaxIterationsReached);
+        }
+        if let Some(budget) = self.state.settings.stop_after_no_progress {
+            let (runs, elapsed_time) = self.state.last_corpus_delta;
+            let is_exhausted = match budget {
+                NoProgressBudget::Iterations(iterations) => {
+                    self.state.fuzzer_stats.total_number_of_runs.saturating_sub(runs) >= iterations
+                }
+                NoProgressBudget::Duration(duration) => {
+                    duration_since_beginning.saturating_sub(elapsed_time) >= duration
+                }
+            };
+            if is_exhausted {
+                return Err(ReasonForStopping::NoProgress);
             }
-            if self.state.fuzzer_stats.total_number_of_runs >= self.state.settings.maximum_iterations {
-                return Err(ReasonForStopping::MaxIterationsReached);
+        }
+        if self.state.settings.incoming_corpus.is_some()
+            && matches!(self.state.settings.command, FuzzerCommand::Fuzz)
+            && duration_since_beginning >= self.state.last_incoming_corpus_scan + INCOMING_CORPUS_SCAN_INTERVAL
+        {
+            self.state.last_incoming_corpus_scan = duration_since_beginning;
+            self.process_incoming_corpus()?;
+        }
+        self.process_next_input()?;
+        if self.state.fuzzer_stats.total_number_of_runs >= self.next_milestone {
+            update_fuzzer_stats(&mut self.state.fuzzer_stats, &mut self.state.world);
+            self.state.world.report_event(
+                FuzzerEvent::Pulse,
+                Some((&self.state.fuzzer_stats, self.state.sensor_and_pool.stats().as_ref())),
+            );
+            self.next_milestone = self.state.fuzzer_stats.total_number_of_runs * 2;
+
+            let filter_stats = FilterStats::global().since(&self.initial_filter_stats);
+            let total = filter_stats.accepted + filter_stats.rejected;
+            if !self.did_warn_about_filters
+                && total >= MIN_NBR_FILTERED_VALUES_FOR_WARNING
+                && filter_stats.rejection_rate() > FILTER_REJECTION_RATE_WARNING_THRESHOLD
+            {
+                self.did_warn_about_filters = true;
+                self.state
+                    .world
+                    .report_event(FuzzerEvent::HighFilterRejectionRate(filter_stats.rejected, total), None);
             }
-            self.process_next_input()?;
-            if self.state.fuzzer_stats.total_number_of_runs >= next_milestone {
-                update_fuzzer_stats(&mut self.state.fuzzer_stats, &mut self.state.world);
-                self.state.world.report_event(
-                    FuzzerEvent::Pulse,
-                    Some((&self.state.fuzzer_stats, self.state.sensor_and_pool.stats().as_ref())),
-                );
-                next_milestone = self.state.fuzzer_stats.total_number_of_runs * 2;
+        }
+        Ok(())
+    }
+
+    #[coverage(off)]
+    fn main_loop(&mut self, minify: bool) -> Result<!, ReasonForStopping<T>> {
+        self.start(minify)?;
+        loop {
+            self.next_iteration()?;
+        }
+    }
+}
+
+/// Replace the panic hook with one that describes the panic in [`TEST_FAILURE`], identified by its location
+#[coverage(off)]
+fn set_test_failure_panic_hook() {
+    std::panic::set_hook(Box::new(
+        #[coverage(off)]
+        move |panic_info| {
+            let mut hasher = DefaultHasher::new();
+            panic_info.location().hash(&mut hasher);
+            unsafe {
+                TEST_FAILURE = Some(TestFailure {
+                    display: format!("{}", panic_info),
+                    id: hasher.finish(),
+                });
             }
+        },
+    ));
+}
+
+/// Run the test function and return whether it failed, in which case the failure is described in [`TEST_FAILURE`].
+///
+/// The panic hook must have been set by [`set_test_failure_panic_hook`].
+#[coverage(off)]
+fn run_test_function<T: ?Sized>(test: &dyn Fn(&T) -> bool, value: &T) -> bool {
+    // the test function may describe its own failure
+    unsafe {
+        TEST_FAILURE = None;
+    }
+    let result = catch_unwind(AssertUnwindSafe(
+        #[coverage(off)]
+        || test(value),
+    ));
+    match result {
+        Ok(false) => unsafe {
+            if TEST_FAILURE.is_none() {
+                TEST_FAILURE = Some(TestFailure {
+                    display: "test function returned false".to_string(),
+                    id: 0,
+                });
+            }
+            true
+        },
+        Err(_) => {
+            // the panic handler already changed the value of TEST_FAILURE
+            // so we don't need to do anything
+            true
         }
+        Ok(true) => false,
     }
 }
 
-pub enum TerminationStatus {
-    Success = 0,
-    Crash = 1,
-    TestFailure = 2,
-    Unknown = 3,
+/// Write the status to the file given by the [`TERMINATION_STATUS_FILE_ENV`] environment variable, if it exists.
+#[coverage(off)]
+fn write_termination_status(status: TerminationStatus) {
+    if let Ok(path) = std::env::var(TERMINATION_STATUS_FILE_ENV) {
+        let _ = std::fs::write(path, (status as i32).to_string());
+    }
+}
+
+/// Exit the process with the given status, after writing it to the file given by the
+/// [`TERMINATION_STATUS_FILE_ENV`] environment variable, if it exists.
+#[coverage(off)]
+pub(crate) fn terminate(status: TerminationStatus) -> ! {
+    // no test case is running anymore, but LeakSanitizer may still report leaks while the process exits
+    unsafe { reset_sanitizer_death_handler() };
+    write_termination_status(status);
+    exit(status as i32)
 }
 
 #[coverage(off)]
 pub fn launch<T, M>(
     test: Box<dyn Fn(&T) -> bool>,
+    hooks: TestHooks<T>,
     mutator: M,
     serializer: Box<dyn Serializer<Value = T>>,
+    corpus_readers: Vec<Box<dyn Serializer<Value = T>>>,
+    initial_inputs: Vec<T>,
     sensor_and_pool: Box<dyn SensorAndPool>,
     mut args: Arguments,
 ) -> FuzzingResult<T>
@@ -670,70 +1649,31 @@ where
     let command = &args.command;
     let reason_for_stopping = match command {
         FuzzerCommand::Fuzz => {
-            if !args.stop_after_first_failure {
-                let test_failure_sensor = TestFailureSensor::default();
-                let test_failure_pool = TestFailurePool::new("test_failures");
-                let sensor_and_pool = AndSensorAndPool::new(
-                    sensor_and_pool,
-                    Box::new((test_failure_sensor, test_failure_pool)),
-                    10.0,
-                    1.0,
-                );
-                let mut fuzzer = Fuzzer::new(
-                    test,
-                    mutator,
-                    serializer,
-                    Box::new(sensor_and_pool),
-                    args.clone(),
-                    World::new(args.clone()).expect(WORLD_NEW_ERROR),
-                );
-
-                let mut stats_headers = vec![CSVField::String("time".to_string())];
-                stats_headers.extend(fuzzer.state.fuzzer_stats.csv_headers());
-                stats_headers.extend(fuzzer.state.sensor_and_pool.stats().csv_headers());
-                fuzzer
-                    .state
-                    .world
-                    .append_stats_file(&stats_headers)
-                    .expect(WRITE_STATS_ERROR);
-                unsafe { fuzzer.state.set_up_signal_handler() };
-
-                let reason_for_stopping = fuzzer.main_loop(false).unwrap_err();
-                fuzzer.state.write_stats().expect(WRITE_STATS_ERROR);
-
-                reason_for_stopping
-            } else {
-                let mut fuzzer = Fuzzer::new(
-                    test,
-                    mutator,
-                    serializer,
-                    sensor_and_pool,
-                    args.clone(),
-                    World::new(args.clone()).expect(WORLD_NEW_ERROR),
-                );
-                unsafe { fuzzer.state.set_up_signal_handler() };
-
-                let mut stats_headers = vec![CSVField::String("time".to_string())];
-                stats_headers.extend(fuzzer.state.fuzzer_stats.csv_headers());
-                stats_headers.extend(fuzzer.state.sensor_and_pool.stats().csv_headers());
-                fuzzer
-                    .state
-                    .world
-                    .append_stats_file(&stats_headers)
-                    .expect(WRITE_STATS_ERROR);
-                let reason_for_stopping = fuzzer.main_loop(false).unwrap_err();
-                fuzzer.state.write_stats().expect(WRITE_STATS_ERROR);
+            let mut fuzzer = Fuzzer::for_fuzz_command(
+                test,
+                hooks,
+                mutator,
+                serializer,
+                corpus_readers,
+                initial_inputs,
+                sensor_and_pool,
+                args.clone(),
+            );
+            let reason_for_stopping = fuzzer.main_loop(false).unwrap_err();
+            fuzzer.report_end_of_fuzzing(&reason_for_stopping);
+            fuzzer.state.write_stats().expect(WRITE_STATS_ERROR);
 
-                reason_for_stopping
-            }
+            reason_for_stopping
         }
         FuzzerCommand::MinifyInput { input_file } => {
             let world = World::new(args.clone()).expect(WORLD_NEW_ERROR);
-            let value = world.read_input_file(input_file).expect(READ_INPUT_FILE_ERROR);
-            let value = serializer.from_data(&value).expect(SERIALIZER_FROM_DATA_ERROR);
+            let value = world
+                .read_input_value(input_file, serializer.as_ref())
+                .expect(READ_INPUT_FILE_ERROR)
+                .expect(SERIALIZER_FROM_DATA_ERROR);
             if let Some(cache) = mutator.validate_value(&value) {
-                let mutation_step = mutator.default_mutation_step(&value, &cache);
                 args.max_input_cplx = mutator.complexity(&value, &cache) - 0.01;
+                args.max_arbitrary_cplx = args.max_arbitrary_cplx.min(args.max_input_cplx);
 
                 let noop_sensor = NoopSensor;
                 let unit_pool = UnitPool::new(PoolStorageIndex(0));
@@ -745,45 +1685,20 @@ where
                     AndSensorAndPool::new(sensor_and_pool, Box::new((noop_sensor, unit_pool)), 1.0, 100.0);
                 let mut fuzzer = Fuzzer::new(
                     test,
+                    hooks,
                     mutator,
                     serializer,
+                    corpus_readers,
+                    initial_inputs,
                     Box::new(sensor_and_pool),
                     args.clone(),
                     world,
                 );
 
-                let mut subvalues: HashMap<TypeId, Vec<(*const dyn Any, f64)>> = HashMap::default();
-
-                let mut act_on_subvalue = #[coverage(off)]
-                |subvalue: &dyn Any, complexity| {
-                    subvalues
-                        .entry(subvalue.type_id())
-                        .or_default()
-                        .push((subvalue as *const _, complexity));
-                };
-
-                fuzzer
-                    .state
-                    .mutator
-                    .visit_subvalues(&value, &cache, &mut act_on_subvalue);
-                let storage_idx_1 = fuzzer.state.pool_storage.next_slot();
-                let generation = Generation(0);
-                let subvalues = CrossoverSubValueProvider::new(
-                    SubValueProviderId {
-                        idx: storage_idx_1,
-                        generation,
-                    },
-                    &value,
-                    &cache,
-                    &fuzzer.state.mutator,
-                );
-                let stored_input = FuzzedInputAndSubValueProvider {
-                    input: FuzzedInput::new(value, cache, mutation_step, generation),
-                    subvalues,
-                };
-                let storage_idx_2 = fuzzer.state.pool_storage.insert(stored_input, 1);
-
-                assert_eq!(storage_idx_1, storage_idx_2);
+                fuzzer.reset_pool_storage_with_single_input(value, cache);
+                if args.preserve_failure {
+                    fuzzer.record_failure_to_preserve();
+                }
 
                 unsafe { fuzzer.state.set_up_signal_handler() };
 
@@ -797,61 +1712,325 @@ where
         FuzzerCommand::Read { input_file } => {
             // no signal handlers are installed, but that should be ok as the exit code won't be 0
             let mut world = World::new(args.clone()).expect(WORLD_NEW_ERROR);
-            let value = world.read_input_file(input_file).expect(READ_INPUT_FILE_ERROR);
-            let value = serializer.from_data(&value).expect(SERIALIZER_FROM_DATA_ERROR);
+            let value = world
+                .read_input_value(input_file, serializer.as_ref())
+                .expect(READ_INPUT_FILE_ERROR)
+                .expect(SERIALIZER_FROM_DATA_ERROR);
             if let Some(cache) = mutator.validate_value(&value) {
                 let mutation_step = mutator.default_mutation_step(&value, &cache);
                 let input = FuzzedInput::new(value, cache, mutation_step, Generation(0));
                 let cplx = input.complexity(&mutator);
 
+                hooks.before(&input.value);
                 if args.detect_infinite_loop {
-                    let _old_time_left = unsafe { libc::alarm(1) };
-                    // TODO: I think setitimer should be prefered, but libc
-                    // doesn't support it on linux, see:
-                    // https://github.com/rust-lang/libc/issues/1347#event-3879031340
-
-                    // let success = unsafe {
-                    // let t = itimerval {
-                    //     it_interval: libc::timeval { tv_sec: 0, tv_usec: 0 },
-                    //     it_value: libc::timeval { tv_sec: 1, tv_usec: 0 },
-                    // };
-                    // libc::setitimer(ITIMER_REAL, &t, std::ptr::null_mut())
-                    // };
-                    // assert!(success == 0);
+                    set_infinite_loop_alarm();
                 }
 
                 let result = catch_unwind(AssertUnwindSafe(
                     #[coverage(off)]
                     || (test)(input.value.borrow()),
                 ));
+                let success = matches!(result, Ok(true));
+                hooks.after(&input.value, success);
 
-                if result.is_err() || !result.unwrap() {
+                if !success {
                     world.report_event(FuzzerEvent::TestFailure, None);
-                    let content = serializer.to_data(&input.value);
                     world
-                        .save_artifact(content, cplx, serializer.extension())
+                        .save_artifact(&input.value, serializer.as_ref(), cplx, None)
                         .expect(SAVE_ARTIFACTS_ERROR);
                     // in this case we really want to exit with a non-zero termination status here
                     // because the Read command is only used by the input minify command from cargo-fuzzcheck
                     // which checks that a crash happens by looking at the exit code
                     // so we don't want to handle any error
-                    exit(TerminationStatus::TestFailure as i32);
+                    terminate(TerminationStatus::TestFailure);
                 } else {
-                    exit(TerminationStatus::Success as i32);
+                    terminate(TerminationStatus::Success);
                 }
             } else {
                 // TODO: send a better error message saying some inputs in the corpus cannot be read
                 panic!("A value in the input corpus is invalid.");
             }
         }
+        FuzzerCommand::Analyze => {
+            let world = World::new(args.clone()).expect(WORLD_NEW_ERROR);
+            let serializers = std::iter::once(&serializer)
+                .chain(corpus_readers.iter())
+                .map(
+                    #[coverage(off)]
+                    |serializer| serializer.as_ref(),
+                )
+                .collect::<Vec<_>>();
+            let mut sensor_and_pool = sensor_and_pool;
+            let analysis = analyze_corpus(test.as_ref(), &mutator, &serializers, sensor_and_pool.as_mut(), &world)
+                .expect(READ_INPUT_FILE_ERROR);
+            let Some(analysis) = analysis else {
+                println!(
+                    "The corpus cannot be analyzed with the sensor of the fuzz test. Its observations must be \
+                    counters, such as those of the code coverage sensor."
+                );
+                terminate(TerminationStatus::ConfigurationError);
+            };
+            world.write_stats_content(analysis.files()).expect(WRITE_STATS_ERROR);
+            println!(
+                "Analyzed {} test cases of the input corpus, {} of which contribute unique code coverage",
+                analysis.entries.len(),
+                analysis
+                    .entries
+                    .iter()
+                    .filter(
+                        #[coverage(off)]
+                        |entry| entry.nbr_unique_features > 0
+                    )
+                    .count()
+            );
+            if analysis.nbr_skipped_files > 0 {
+                world.report_event(FuzzerEvent::SkippedCorpusFiles(analysis.nbr_skipped_files), None);
+            }
+            terminate(TerminationStatus::Success);
+        }
+        FuzzerCommand::Repool => {
+            let world = World::new(args.clone()).expect(WORLD_NEW_ERROR);
+            let record = world
+                .read_from_latest_session(OBSERVATIONS_RECORD_FILE)
+                .expect(READ_OBSERVATIONS_RECORD_ERROR);
+            let Some((path, content)) = record else {
+                println!(
+                    "No recorded observations were found in the stats folder. Launch the fuzzer with --{} first.",
+                    fuzzcheck_common::arg::RECORD_OBSERVATIONS_FLAG
+                );
+                terminate(TerminationStatus::ConfigurationError);
+            };
+            let entries = ObservationsRecord::decode(&content).expect(READ_OBSERVATIONS_RECORD_ERROR);
+            println!(
+                "Replaying the observations of {} test cases from {}",
+                entries.len(),
+                path.display()
+            );
+            let mut sensor_and_pool = sensor_and_pool;
+            let Some(csv) = repool(&entries, sensor_and_pool.as_mut()) else {
+                println!(
+                    "The recorded observations cannot be replayed by the sensor of the fuzz test. They must be recorded \
+                    by the same sensor, whose observations can be encoded, such as the code coverage sensor."
+                );
+                terminate(TerminationStatus::ConfigurationError);
+            };
+            println!("{}", sensor_and_pool.stats());
+            world
+                .write_stats_content(vec![(PathBuf::new().join(REPOOL_STATS_FILE), csv)])
+                .expect(WRITE_STATS_ERROR);
+            terminate(TerminationStatus::Success);
+        }
+        FuzzerCommand::Bench => {
+            let world = World::new(args.clone()).expect(WORLD_NEW_ERROR);
+            let serializers = std::iter::once(&serializer)
+                .chain(corpus_readers.iter())
+                .map(
+                    #[coverage(off)]
+                    |serializer| serializer.as_ref(),
+                )
+                .collect::<Vec<_>>();
+            let mut sensor_and_pool = sensor_and_pool;
+            let report = bench(
+                test.as_ref(),
+                &hooks,
+                &mutator,
+                &serializers,
+                sensor_and_pool.as_mut(),
+                &world,
+                &args,
+            )
+            .expect(READ_INPUT_FILE_ERROR);
+            println!("{}", report);
+            terminate(TerminationStatus::Success);
+        }
+        FuzzerCommand::Merge { .. } => {
+            let out_corpus_is_empty = args.corpus_out.as_ref().is_none_or(
+                #[coverage(off)]
+                |corpus| {
+                    std::fs::read_dir(corpus).map_or(
+                        true,
+                        #[coverage(off)]
+                        |mut entries| entries.next().is_none(),
+                    )
+                },
+            );
+            if !out_corpus_is_empty {
+                println!(
+                    "The output corpus {} is not empty. Choose a different folder with --{}.",
+                    args.corpus_out.as_ref().unwrap().display(),
+                    fuzzcheck_common::arg::OUT_CORPUS_FLAG
+                );
+                terminate(TerminationStatus::ConfigurationError);
+            }
+            // the failing test cases of the merged corpora are kept, instead of stopping the fuzzer
+            args.stop_after_first_failure = false;
+            let mut fuzzer = Fuzzer::for_fuzz_command(
+                test,
+                hooks,
+                mutator,
+                serializer,
+                corpus_readers,
+                initial_inputs,
+                sensor_and_pool,
+                args.clone(),
+            );
+            match fuzzer.merge_corpora() {
+                Ok(()) => terminate(TerminationStatus::Success),
+                Err(reason_for_stopping) => {
+                    fuzzer.report_end_of_fuzzing(&reason_for_stopping);
+                    reason_for_stopping
+                }
+            }
+        }
+        FuzzerCommand::CoverageDiff { input_files } => {
+            let world = World::new(args.clone()).expect(WORLD_NEW_ERROR);
+            // the code coverage sensor of the fuzz test is used if it has one, such that its filters are applied
+            let mut sensor_and_pool = sensor_and_pool;
+            let mut default_sensor = None;
+            let sensor = match sensor_and_pool.code_coverage_sensor() {
+                Some(sensor) => sensor,
+                None => default_sensor.insert(CodeCoverageSensor::new(CoverageFilter::from_arguments(&args).apply(
+                    #[coverage(off)]
+                    |file, _function| file.is_relative(),
+                ))),
+            };
+            let locations = sensor.counter_locations();
+            let serializers = std::iter::once(&serializer)
+                .chain(corpus_readers.iter())
+                .map(
+                    #[coverage(off)]
+                    |serializer| serializer.as_ref(),
+                )
+                .collect::<Vec<_>>();
+            let [first, second] = input_files;
+            let diff = coverage_diff(
+                test.as_ref(),
+                &mutator,
+                &serializers,
+                sensor,
+                locations,
+                &world,
+                [first, second],
+            )
+            .expect(READ_INPUT_FILE_ERROR);
+            println!("{}", diff);
+            terminate(TerminationStatus::Success);
+        }
     };
     let _ = std::panic::take_hook();
 
     let found_test_failure =
         unsafe { matches!(reason_for_stopping, ReasonForStopping::TestFailure(_)) || DID_FIND_ANY_TEST_FAILURE };
 
+    if args.exit_code_policy == ExitCodePolicy::Exit {
+        if found_test_failure {
+            terminate(TerminationStatus::TestFailure);
+        } else {
+            terminate(TerminationStatus::Success);
+        }
+    }
+
     FuzzingResult {
         found_test_failure,
         reason_for_stopping,
     }
 }
+
+/// Check that the mutator follows the rules of the [`Mutator`] trait, and print the ones it breaks.
+///
+/// The values are compared and described through their serialization.
+#[coverage(off)]
+fn debug_check_mutator<T, M>(mutator: &M, serializer: &dyn Serializer<Value = T>, max_cplx: f64)
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    let options = CheckOptions::default()
+        .max_cplx(max_cplx)
+        .nbr_arbitraries(20)
+        .nbr_mutations(50);
+    let result = check_mutator_by_key(
+        mutator,
+        &options,
+        #[coverage(off)]
+        |value| String::from_utf8_lossy(&serializer.to_data(value)).into_owned(),
+    );
+    if let Err(error) = result {
+        println!("WARNING: {}", error);
+        println!("The fuzzer may not work as well as it should until the mutator is fixed.");
+    }
+}
+
+/// Run a bounded fuzz test and, if a failing test case is found, repeatedly try to simplify it.
+///
+/// The fuzzer first runs for at most `args.maximum_iterations` iterations. If it finds a test failure,
+/// it then switches to a minifying mode, similar to the `minify` command, where it only mutates the current
+/// counterexample and looks for a failing test case of lower complexity. Each minifying round is also bounded
+/// by `args.maximum_iterations`. The simplest failing test case is returned once a round fails to
+/// make progress.
+///
+/// The signal handlers are installed for the duration of the function, but no file is read from or written
+/// to the file system, unless `args` says otherwise.
+#[coverage(off)]
+pub fn launch_property_test<T, M>(
+    test: Box<dyn Fn(&T) -> bool>,
+    hooks: TestHooks<T>,
+    mutator: M,
+    serializer: Box<dyn Serializer<Value = T>>,
+    corpus_readers: Vec<Box<dyn Serializer<Value = T>>>,
+    initial_inputs: Vec<T>,
+    sensor_and_pool: Box<dyn SensorAndPool>,
+    mut args: Arguments,
+) -> Option<T>
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+    Fuzzer<T, M>: 'static,
+{
+    args.command = FuzzerCommand::Fuzz;
+    args.stop_after_first_failure = true;
+    let iterations_per_round = args.maximum_iterations;
+
+    let mut fuzzer = Fuzzer::new(
+        test,
+        hooks,
+        mutator,
+        serializer,
+        corpus_readers,
+        initial_inputs,
+        sensor_and_pool,
+        args.clone(),
+        World::new(args).expect(WORLD_NEW_ERROR),
+    );
+    unsafe { fuzzer.state.set_up_signal_handler() };
+
+    let mut counterexample = match fuzzer.main_loop(false).unwrap_err() {
+        ReasonForStopping::TestFailure(value) => value,
+        _ => {
+            let _ = std::panic::take_hook();
+            return None;
+        }
+    };
+
+    // From now on, we only care about the current counterexample, which is always stored at index 0
+    fuzzer.state.sensor_and_pool = Box::new((NoopSensor, UnitPool::new(PoolStorageIndex(0))));
+    while let Some(cache) = fuzzer.state.mutator.validate_value(&counterexample) {
+        let settings = &mut fuzzer.state.settings;
+        settings.max_input_cplx = fuzzer.state.mutator.complexity(&counterexample, &cache) - 0.01;
+        settings.max_arbitrary_cplx = settings.max_arbitrary_cplx.min(settings.max_input_cplx);
+        fuzzer.state.settings.maximum_iterations = fuzzer
+            .state
+            .fuzzer_stats
+            .total_number_of_runs
+            .saturating_add(iterations_per_round);
+        fuzzer.reset_pool_storage_with_single_input(counterexample.clone(), cache);
+
+        match fuzzer.main_loop(true).unwrap_err() {
+            ReasonForStopping::TestFailure(simpler) => counterexample = simpler,
+            _ => break,
+        }
+    }
+    let _ = std::panic::take_hook();
+
+    Some(counterexample)
+}
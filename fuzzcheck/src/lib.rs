@@ -30,6 +30,8 @@
 #![feature(type_alias_impl_trait, impl_trait_in_assoc_type)]
 // should be stabilised very soon
 #![feature(let_chains)]
+// benchmarks of the hottest loops, not essential
+#![cfg_attr(test, feature(test))]
 //
 // end nightly features
 //
@@ -50,25 +52,82 @@
 #[doc(hidden)]
 pub extern crate fastrand;
 
+mod bench;
 mod bitset;
 mod bloom_filter;
 pub mod builder;
 mod code_coverage_sensor;
+mod corpus_analysis;
+mod coverage_diff;
 mod data_structures;
+mod executed_inputs;
 mod fenwick_tree;
+mod fork_executor;
 mod fuzzer;
 pub mod mutators;
+mod observation_cache;
+mod observations_record;
+mod provenance;
 pub mod sensors_and_pools;
 pub mod serializers;
 mod signals_handler;
 mod split_string;
+mod stability;
+mod stats_socket;
+#[cfg(unix)]
+#[doc(cfg(unix))]
+pub mod subprocess;
 pub mod subvalue_provider;
 mod traits;
 mod world;
 
+#[doc(inline)]
+pub use builder::fuzz_differential;
 #[doc(inline)]
 pub use builder::fuzz_test;
-pub use fuzzcheck_common::arg::Arguments;
+#[doc(inline)]
+pub use builder::fuzz_test_async;
+#[cfg(feature = "serde_json_serializer")]
+#[doc(inline)]
+pub use builder::test_once;
+pub use fuzzcheck_common::arg::{Arguments, CorpusNamingPolicy, ExitCodePolicy, NoProgressBudget};
+#[doc(inline)]
+pub use fuzzcheck_common::FuzzerStats;
+#[doc(inline)]
+pub use fuzzcheck_core::legacy;
+/// Turn a test function into a fuzz test with the default options.
+///
+/// The annotated function is replaced by a `#[test]` of the same name, which is only compiled with
+/// `cfg(all(fuzzing, test))`. It fuzzes the original function with its
+/// [default mutator](crate::DefaultMutator), saves the test cases with [`SerdeSerializer`], uses
+/// the [default sensor and pool](crate::builder::default_sensor_and_pool), and takes its arguments
+/// from `cargo fuzzcheck`. It is a shorthand for:
+/// ```ignore
+/// #[cfg(all(fuzzing, test))]
+/// #[test]
+/// fn fuzz() {
+///     fn fuzz(xs: &[u8]) { /* ... */ }
+///     let result = fuzzcheck::fuzz_test(fuzz)
+///         .default_mutator()
+///         .serde_serializer()
+///         .default_sensor_and_pool()
+///         .arguments_from_cargo_fuzzcheck()
+///         .launch();
+///     assert!(!result.found_test_failure);
+/// }
+/// ```
+/// The test fails if the fuzzer finds a failing test case. The function can take its argument by
+/// reference to any type whose owned version implements `DefaultMutator`, `Serialize`, and `Deserialize`,
+/// and must not be generic.
+/// ```
+/// #[fuzzcheck::fuzz_target]
+/// fn fuzz(xs: &[u8]) {
+///     assert!(xs.len() < 1000);
+/// }
+/// ```
+/// It can be launched with `cargo fuzzcheck fuzz`, and is listed by `cargo fuzzcheck list`.
+#[cfg(feature = "serde_json_serializer")]
+pub use fuzzcheck_mutators_derive::fuzz_target;
 /**
     Make a mutator for a custom type, optionally making it the type’s default mutator.
 
@@ -180,6 +239,32 @@ pub use fuzzcheck_common::arg::Arguments;
             }
     }
     ```
+    Create a mutator for a type defined in another crate, whose fields are public:
+    ```
+    # #![feature(coverage_attribute)]
+    use fuzzcheck::make_mutator;
+    use fuzzcheck::Mutator;
+    # mod other_crate {
+    #     #[derive(Clone)]
+    #     pub struct Point { pub x: u8, pub y: u8 }
+    # }
+    make_mutator! {
+        name: PointMutator,
+        // the mutator produces values of type `other_crate::Point`
+        remote: other_crate::Point,
+        // since `DefaultMutator` cannot be implemented for a foreign type, `default: true`
+        // implements `Default` for `PointMutator` instead
+        default: true,
+        type: // repeat the declaration of the remote type, under a local name
+            pub struct PointShape { pub x: u8, pub y: u8 }
+    }
+    let mutator = PointMutator::default();
+    let (point, _) = mutator.random_arbitrary(10.0);
+    let _: other_crate::Point = point;
+    ```
+    The local type, here `PointShape`, is declared by the macro. The values of the remote type are converted
+    to and from it field by field, so all fields must be public and implement `Clone`. The `remote` setting
+    cannot be used for recursive types.
 */
 pub use fuzzcheck_mutators_derive::make_mutator;
 /// Implement a mutator for the type and make it the type’s `DefaultMutator`.
@@ -213,8 +298,106 @@ pub use fuzzcheck_mutators_derive::make_mutator;
 /// ```
 /// Similarly to [`make_mutator!`](crate::make_mutator), you can use the attributes `#[field_mutator]` and `#[ignore_variant]`
 /// to customise the generated mutator.
+///
+/// A `String` field can also be generated from a grammar with `#[field_mutator(grammar = "<regex>")]`, or
+/// `#[field_mutator(grammar = { <expression of type Rc<Grammar>> })]`, which uses a
+/// [`grammar_based_string_mutator`](crate::mutators::grammar::grammar_based_string_mutator) for that field.
+///
+/// An integer or `char` field can be restricted to a range of values with `#[range(<range expression>)]`, which
+/// uses the [`WithinRange`](crate::mutators::integer_within_range::WithinRange) implementation of the type of
+/// the field to pick its mutator, such as [`U8WithinRangeMutator`](crate::mutators::integer_within_range::U8WithinRangeMutator):
+/// ```
+/// # #![feature(coverage_attribute)]
+/// use fuzzcheck::DefaultMutator;
+///
+/// #[derive(Clone, DefaultMutator)]
+/// struct Rgb {
+///     #[range(0..=10)]
+///     red: u8,
+///     #[range(-5..5)]
+///     offset: i32,
+///     #[range('a'..='z')]
+///     name: char,
+/// }
+/// ```
+///
+/// A field that must not vary during fuzzing, such as a bookkeeping field, can be kept constant with `#[frozen]`,
+/// which always sets it to `Default::default()`, or with `#[frozen(<expression>)]`, which always sets it to the
+/// value of the expression. The field is never mutated and does not contribute to the complexity of the value:
+/// ```
+/// # #![feature(coverage_attribute)]
+/// use fuzzcheck::DefaultMutator;
+///
+/// #[derive(Clone, DefaultMutator)]
+/// struct Packet {
+///     payload: Vec<u8>,
+///     #[frozen]
+///     nbr_retries: usize,
+///     #[frozen(String::from("v1"))]
+///     protocol: String,
+/// }
+/// ```
+///
+/// The `#[weight(<number>)]` attribute multiplies the complexity of a field by the given factor, using a
+/// [`ScaledComplexityMutator`](crate::mutators::scaled_complexity::ScaledComplexityMutator). Since the field to
+/// mutate is chosen with a probability that increases with its complexity, fields with a higher weight are
+/// mutated more often:
+/// ```
+/// # #![feature(coverage_attribute)]
+/// use fuzzcheck::DefaultMutator;
+///
+/// #[derive(Clone, DefaultMutator)]
+/// struct Config {
+///     #[weight(4.0)]
+///     query: Vec<u8>,
+///     verbose: bool,
+///     #[weight(0.5)]
+///     name: String,
+/// }
+/// let mutator = Config::default_mutator();
+/// ```
+///
+/// Crossover mutations reuse the subvalues of the test cases in the corpus. The generated mutator gives every
+/// field to crossover as a whole, and then asks the field's mutator for the parts of the field. A field whose
+/// hand-written mutator does not implement [`visit_subvalues`](crate::Mutator::visit_subvalues), or implements it
+/// with `unimplemented!()`, can be marked with `#[opaque]`: the field is still given to crossover as a whole, but
+/// its mutator is not asked for its parts, using an [`OpaqueMutator`](crate::mutators::opaque::OpaqueMutator):
+/// ```
+/// # #![feature(coverage_attribute)]
+/// use fuzzcheck::DefaultMutator;
+///
+/// #[derive(Clone, DefaultMutator)]
+/// struct Request {
+///     #[opaque]
+///     body: Vec<u8>,
+///     id: u16,
+/// }
+/// let mutator = Request::default_mutator();
+/// ```
+///
+/// The variants of an enum with associated data are initially equally likely to be chosen when a new value is
+/// generated. The `#[variant_weight(<number>)]` attribute changes the relative probability of a variant, using the
+/// weights of an [`AlternationMutator`](crate::mutators::alternation::AlternationMutator), which are then adapted
+/// during the fuzzing session. The variants without the attribute have a weight of 1.0:
+/// ```
+/// # #![feature(coverage_attribute)]
+/// use fuzzcheck::DefaultMutator;
+///
+/// #[derive(Clone, DefaultMutator)]
+/// enum Command {
+///     Read(u8),
+///     Write(u8, Vec<u8>),
+///     #[variant_weight(0.1)] // rarely generate `Reset` values
+///     Reset { hard: bool },
+/// }
+/// let mutator = Command::default_mutator();
+/// ```
 pub use fuzzcheck_mutators_derive::DefaultMutator;
 #[doc(inline)]
+pub use fuzzer::Fuzzer;
+#[doc(inline)]
+pub use fuzzer::FuzzerStep;
+#[doc(inline)]
 pub use fuzzer::FuzzingResult;
 #[doc(inline)]
 pub use fuzzer::PoolStorageIndex;
@@ -226,11 +409,16 @@ pub use mutators::DefaultMutator;
 pub use mutators::MutatorExt;
 pub(crate) use mutators::CROSSOVER_RATE;
 #[doc(inline)]
+pub use sensors_and_pools::record_event;
+#[doc(inline)]
 pub use sensors_and_pools::PoolExt;
 #[doc(inline)]
 pub use sensors_and_pools::SensorExt;
 #[doc(inline)]
 pub use serializers::ByteSerializer;
+#[cfg(feature = "serde_bincode_serializer")]
+#[doc(inline)]
+pub use serializers::SerdeBincodeSerializer;
 #[cfg(feature = "serde_ron_serializer")]
 #[doc(inline)]
 pub use serializers::SerdeRonSerializer;
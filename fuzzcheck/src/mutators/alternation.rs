@@ -1,8 +1,10 @@
 use std::any::Any;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
 use std::marker::PhantomData;
+use std::sync::Mutex;
 
+use crate::mutators::operators::{choose_operator, record_operator_use, register_mutator_operator, OperatorId};
 use crate::Mutator;
 
 /**
@@ -19,6 +21,27 @@ let m = AlternationMutator::new(vec![m1, m2], 0.0);
 
 // m will produce values either in 3..=10 or in 78..=200
 ```
+
+By default, each sub-mutator is equally likely to be chosen to generate a new value. Different weights can be
+given to the sub-mutators with [`AlternationMutator::with_weights`], and updated later with
+[`AlternationMutator::set_weights`]:
+```
+use fuzzcheck::mutators::alternation::AlternationMutator;
+use fuzzcheck::mutators::integer_within_range::U8WithinRangeMutator;
+
+let m1 = U8WithinRangeMutator::new(3 ..= 10);
+let m2 = U8WithinRangeMutator::new(78 ..= 200);
+
+// m1 is chosen 3 times as often as m2
+let m = AlternationMutator::with_weights(vec![m1, m2], vec![3.0, 1.0], 0.0);
+
+// now m2 is chosen twice as often as m1
+m.set_weights(vec![1.0, 2.0]);
+assert_eq!(m.weights(), vec![1.0, 2.0]);
+```
+
+Each alternative is a [mutation operator](crate::mutators::operators) called `alternation::Alternative<index>`,
+and its weight is adapted to how often it leads to interesting test cases during a fuzzing session.
 */
 pub struct AlternationMutator<T, M>
 where
@@ -27,6 +50,8 @@ where
 {
     mutators: Vec<M>,
     rng: fastrand::Rng,
+    weights: RefCell<Vec<f64>>,
+    operators: Vec<OperatorId>,
     added_complexity: f64,
     initialized: Cell<bool>,
     min_complexity: Cell<f64>,
@@ -44,7 +69,15 @@ where
     pub fn new(mutators: Vec<M>, added_complexity: f64) -> Self {
         assert!(!mutators.is_empty());
 
+        let operators = (0..mutators.len())
+            .map(
+                #[coverage(off)]
+                |idx| register_mutator_operator(alternative_operator_name(idx)),
+            )
+            .collect();
         Self {
+            weights: RefCell::new(vec![1.0; mutators.len()]),
+            operators,
             mutators,
             rng: fastrand::Rng::default(),
             added_complexity,
@@ -55,6 +88,81 @@ where
             _phantom: PhantomData,
         }
     }
+
+    /// Create an `AlternationMutator` whose sub-mutators are chosen with a probability proportional to the given weights.
+    ///
+    /// There must be exactly one weight per sub-mutator. See [`set_weights`](AlternationMutator::set_weights)
+    /// for the requirements on the weights.
+    #[coverage(off)]
+    pub fn with_weights(mutators: Vec<M>, weights: Vec<f64>, added_complexity: f64) -> Self {
+        let mutator = Self::new(mutators, added_complexity);
+        mutator.set_weights(weights);
+        mutator
+    }
+
+    /// Change the weights of the sub-mutators.
+    ///
+    /// The weights determine the probability that each sub-mutator is chosen to generate a new value,
+    /// which happens in [`random_arbitrary`](Mutator::random_arbitrary) and when a value is replaced by a new
+    /// one during a mutation. They don't affect the order in which [`ordered_arbitrary`](Mutator::ordered_arbitrary)
+    /// goes through the sub-mutators.
+    ///
+    /// During a fuzzing session, the weights are also adapted to how often each alternative leads to
+    /// interesting test cases, unless the adaptation of the mutations is disabled. They can be updated at any
+    /// time. There must be exactly one weight per sub-mutator, and the weights must be non-negative, finite,
+    /// and not all zero.
+    #[coverage(off)]
+    pub fn set_weights(&self, weights: Vec<f64>) {
+        assert_eq!(
+            weights.len(),
+            self.mutators.len(),
+            "an AlternationMutator needs exactly one weight per sub-mutator"
+        );
+        assert!(
+            weights.iter().all(
+                #[coverage(off)]
+                |w| *w >= 0.0 && w.is_finite()
+            ) && weights.iter().any(
+                #[coverage(off)]
+                |w| *w > 0.0
+            ),
+            "the weights of an AlternationMutator must be non-negative, finite, and not all zero"
+        );
+        *self.weights.borrow_mut() = weights;
+    }
+
+    /// The weights of the sub-mutators, which are all equal to 1.0 unless they were set by
+    /// [`with_weights`](AlternationMutator::with_weights) or [`set_weights`](AlternationMutator::set_weights)
+    ///
+    /// They are the weights given to the mutator, before their adaptation during a fuzzing session.
+    #[coverage(off)]
+    pub fn weights(&self) -> Vec<f64> {
+        self.weights.borrow().clone()
+    }
+
+    /// Choose the index of the sub-mutator used to generate a new value, and record the use of its operator
+    #[coverage(off)]
+    fn sample_mutator_idx(&self) -> usize {
+        let idx = choose_operator(&self.rng, &self.operators, &self.weights.borrow()).unwrap_or_else(
+            #[coverage(off)]
+            || self.rng.usize(..self.mutators.len()),
+        );
+        record_operator_use(self.operators[idx]);
+        idx
+    }
+}
+
+/// The name of the operator of the alternative of the given index, such as `alternation::Alternative0`
+#[coverage(off)]
+fn alternative_operator_name(idx: usize) -> &'static str {
+    // the names are shared by all the alternation mutators, and each one is allocated only once
+    static NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+    let mut names = NAMES.lock().unwrap();
+    while names.len() <= idx {
+        let name = format!("alternation::Alternative{}", names.len());
+        names.push(Box::leak(name.into_boxed_str()));
+    }
+    names[idx]
 }
 
 #[doc(hidden)]
@@ -287,6 +395,7 @@ where
         let inner_step = &mut step.inner[idx];
         if let Some((v, c)) = mutator.ordered_arbitrary(inner_step, max_cplx) {
             step.idx += 1;
+            record_operator_use(self.operators[idx]);
             Some((v, self.complexity_from_inner(c)))
         } else {
             step.indices.remove(step.idx % step.indices.len());
@@ -297,7 +406,7 @@ where
     #[doc(hidden)]
     #[coverage(off)]
     fn random_arbitrary(&self, max_cplx: f64) -> (T, f64) {
-        let idx = self.rng.usize(..self.mutators.len());
+        let idx = self.sample_mutator_idx();
         let mutator = &self.mutators[idx];
 
         let (v, c) = mutator.random_arbitrary(max_cplx);
@@ -360,6 +469,7 @@ where
             subvalue_provider,
             max_cplx,
         ) {
+            record_operator_use(self.operators[idx]);
             Some((UnmutateToken::Inner(idx, t), self.complexity_from_inner(cplx)))
         } else {
             if let Some((mut v, cplx)) = self.ordered_arbitrary(&mut chosen_step.arbitrary, max_cplx) {
@@ -396,6 +506,7 @@ where
         }
 
         let (t, cplx) = mutator.random_mutate(value, &mut cache.inner, max_cplx);
+        record_operator_use(self.operators[idx]);
         (UnmutateToken::Inner(idx, t), self.complexity_from_inner(cplx))
     }
 
@@ -429,4 +540,18 @@ where
             mutator.visit_subvalues(value, &cache.inner, visit);
         }
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        let mut operators: Vec<&'static str> = (0..self.mutators.len()).map(alternative_operator_name).collect();
+        for mutator in self.mutators.iter() {
+            for operator in mutator.mutation_operators() {
+                if !operators.contains(&operator) {
+                    operators.push(operator);
+                }
+            }
+        }
+        operators
+    }
 }
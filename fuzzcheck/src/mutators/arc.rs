@@ -1,3 +1,10 @@
+//! Mutator for `Arc<T>`.
+//!
+//! Like the [`RcMutator`](crate::mutators::rc::RcMutator), the [`ArcMutator`] mutates a copy of the
+//! value behind the `Arc`, which means that a node shared by several `Arc`s stops being shared once
+//! it is mutated. See the [documentation of the `rc` module](crate::mutators::rc) to preserve the
+//! aliasing of shared nodes.
+
 use std::any::Any;
 use std::sync::Arc;
 
@@ -175,6 +182,12 @@ impl<T: Clone + 'static, M: Mutator<T>> Mutator<Arc<T>> for ArcMutator<M> {
     fn visit_subvalues<'a>(&self, value: &'a Arc<T>, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
         self.mutator.visit_subvalues(value, cache, visit)
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
 }
 
 impl<T> DefaultMutator for Arc<T>
@@ -4,9 +4,13 @@ use std::marker::PhantomData;
 
 use fastrand::Rng;
 
-use crate::{DefaultMutator, Mutator};
+use super::CrossoverStep;
+use crate::{DefaultMutator, Mutator, CROSSOVER_RATE};
 
 /// A mutator for fixed-size arrays `[T; N]`.
+///
+/// The same mutator is used for each element of the array. Use [`FixedLenArrayMutator`]
+/// to use a different mutator for each element instead.
 pub struct ArrayMutator<M, T, const N: usize>
 where
     T: Clone + 'static,
@@ -377,12 +381,442 @@ impl<M: Mutator<T>, T: Clone + 'static, const N: usize> Mutator<[T; N]> for Arra
             }
         }
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
+}
+
+/// A mutator for fixed-size arrays `[T; N]`
+///
+/// A different mutator is used for each element of the array, similarly to the
+/// [`FixedLenVecMutator`](crate::mutators::fixed_len_vector::FixedLenVecMutator).
+/// The complexity of an array is the sum of the complexities of its elements, as
+/// with the [`ArrayMutator`].
+///
+/// ```
+/// use fuzzcheck::mutators::array::FixedLenArrayMutator;
+/// use fuzzcheck::mutators::integer_within_range::U8WithinRangeMutator;
+///
+/// // the first byte is a tag between 0 and 3, and the others can be anything
+/// let m = FixedLenArrayMutator::<u8, _, 3>::new([
+///     U8WithinRangeMutator::new(0..=3),
+///     U8WithinRangeMutator::new(..),
+///     U8WithinRangeMutator::new(..),
+/// ]);
+/// ```
+pub struct FixedLenArrayMutator<T, M, const N: usize>
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    pub rng: Rng,
+    mutators: [M; N],
+    initialized: Cell<bool>,
+    min_complexity: Cell<f64>,
+    max_complexity: Cell<f64>,
+    search_space_complexity: Cell<f64>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, M, const N: usize> FixedLenArrayMutator<T, M, N>
+where
+    T: Clone + 'static,
+    M: Mutator<T> + Clone,
+{
+    #[coverage(off)]
+    pub fn new_with_repeated_mutator(mutator: M) -> Self {
+        Self::new(std::array::from_fn(
+            #[coverage(off)]
+            |_| mutator.clone(),
+        ))
+    }
+}
+
+impl<T, M, const N: usize> FixedLenArrayMutator<T, M, N>
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    /// Create a mutator using `mutators[i]` for the element at index `i`
+    #[coverage(off)]
+    pub fn new(mutators: [M; N]) -> Self {
+        Self {
+            rng: Rng::default(),
+            mutators,
+            initialized: Cell::new(false),
+            min_complexity: Cell::new(f64::INFINITY),
+            max_complexity: Cell::default(),
+            search_space_complexity: Cell::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FixedLenArrayMutationStep<T, S> {
+    inner: Vec<S>,
+    element_step: usize,
+    crossover_steps: Vec<CrossoverStep<T>>,
+}
+
+pub enum UnmutateFixedLenArrayToken<T: Clone + 'static, M: Mutator<T>, const N: usize> {
+    ReplaceElement(usize, T),
+    Element(usize, M::UnmutateToken),
+    Elements(Vec<(usize, M::UnmutateToken)>),
+    Replace([T; N]),
+}
+
+impl<T: Clone + 'static, M: Mutator<T>, const N: usize> FixedLenArrayMutator<T, M, N> {
+    #[coverage(off)]
+    fn mutate_elements(
+        &self,
+        value: &mut [T; N],
+        cache: &mut ArrayMutatorCache<M::Cache>,
+        idcs: &[usize],
+        current_cplx: f64,
+        max_cplx: f64,
+    ) -> (UnmutateFixedLenArrayToken<T, M, N>, f64) {
+        let mut cplx = current_cplx;
+        let mut tokens = vec![];
+        for &idx in idcs {
+            let spare_cplx = max_cplx - cplx;
+            let mutator = &self.mutators[idx];
+            let el = &mut value[idx];
+            let el_cache = &mut cache.inner[idx];
+
+            let old_cplx = mutator.complexity(el, el_cache);
+
+            let (token, new_cplx) = mutator.random_mutate(el, el_cache, spare_cplx + old_cplx);
+            tokens.push((idx, token));
+            cplx = cplx - old_cplx + new_cplx;
+        }
+        (UnmutateFixedLenArrayToken::Elements(tokens), cplx)
+    }
+    #[coverage(off)]
+    fn mutate_element(
+        &self,
+        value: &mut [T; N],
+        cache: &mut ArrayMutatorCache<M::Cache>,
+        step: &mut FixedLenArrayMutationStep<T, M::MutationStep>,
+        subvalue_provider: &dyn crate::SubValueProvider,
+        idx: usize,
+        current_cplx: f64,
+        spare_cplx: f64,
+    ) -> Option<(UnmutateFixedLenArrayToken<T, M, N>, f64)> {
+        let mutator = &self.mutators[idx];
+        let el = &mut value[idx];
+        let el_cache = &mut cache.inner[idx];
+        let el_step = &mut step.inner[idx];
+
+        let old_cplx = mutator.complexity(el, el_cache);
+
+        mutator
+            .ordered_mutate(el, el_cache, el_step, subvalue_provider, spare_cplx + old_cplx)
+            .map(
+                #[coverage(off)]
+                |(token, new_cplx)| {
+                    (
+                        UnmutateFixedLenArrayToken::Element(idx, token),
+                        current_cplx - old_cplx + new_cplx,
+                    )
+                },
+            )
+    }
+
+    #[coverage(off)]
+    fn new_input_with_complexity(&self, target_cplx: f64) -> ([T; N], f64) {
+        let mut v = Vec::with_capacity(N);
+        let mut sum_cplx = 0.0;
+        let mut remaining_cplx = target_cplx;
+        let mut remaining_min_complexity = self.min_complexity();
+        for (i, mutator) in self.mutators.iter().enumerate() {
+            let mut max_cplx_element = (remaining_cplx / ((N - i) as f64)) - remaining_min_complexity;
+            let min_cplx_el = mutator.min_complexity();
+            if min_cplx_el >= max_cplx_element {
+                max_cplx_element = min_cplx_el;
+            }
+            let (x, x_cplx) = mutator.random_arbitrary(max_cplx_element);
+            v.push(x);
+            sum_cplx += x_cplx;
+            remaining_cplx -= x_cplx;
+            remaining_min_complexity -= mutator.min_complexity();
+        }
+        (v.try_into().ok().unwrap(), sum_cplx)
+    }
+}
+
+impl<T: Clone + 'static, M: Mutator<T>, const N: usize> Mutator<[T; N]> for FixedLenArrayMutator<T, M, N> {
+    #[doc(hidden)]
+    type Cache = ArrayMutatorCache<M::Cache>;
+    #[doc(hidden)]
+    type MutationStep = FixedLenArrayMutationStep<T, M::MutationStep>;
+    #[doc(hidden)]
+    type ArbitraryStep = ();
+    #[doc(hidden)]
+    type UnmutateToken = UnmutateFixedLenArrayToken<T, M, N>;
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn initialize(&self) {
+        for mutator in self.mutators.iter() {
+            mutator.initialize();
+        }
+        let max_complexity = self.mutators.iter().fold(
+            0.0,
+            #[coverage(off)]
+            |cplx, m| cplx + m.max_complexity(),
+        );
+        let min_complexity = self.mutators.iter().fold(
+            0.0,
+            #[coverage(off)]
+            |cplx, m| cplx + m.min_complexity(),
+        );
+        let search_space_complexity = self.mutators.iter().fold(
+            0.0,
+            #[coverage(off)]
+            |cplx, m| cplx + m.global_search_space_complexity(),
+        );
+        self.min_complexity.set(min_complexity);
+        self.max_complexity.set(max_complexity);
+        self.search_space_complexity.set(search_space_complexity);
+        self.initialized.set(true);
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {}
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn is_valid(&self, value: &[T; N]) -> bool {
+        self.mutators.iter().zip(value.iter()).all(
+            #[coverage(off)]
+            |(m, v)| m.is_valid(v),
+        )
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn validate_value(&self, value: &[T; N]) -> Option<Self::Cache> {
+        let inner_caches: Vec<_> = value
+            .iter()
+            .zip(self.mutators.iter())
+            .map(
+                #[coverage(off)]
+                |(x, mutator)| mutator.validate_value(x),
+            )
+            .collect::<Option<_>>()?;
+
+        let sum_cplx = value.iter().zip(self.mutators.iter()).zip(inner_caches.iter()).fold(
+            0.0,
+            #[coverage(off)]
+            |cplx, ((v, mutator), cache)| cplx + mutator.complexity(v, cache),
+        );
+
+        Some(ArrayMutatorCache {
+            inner: inner_caches,
+            sum_cplx,
+        })
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_mutation_step(&self, value: &[T; N], cache: &Self::Cache) -> Self::MutationStep {
+        let inner = value
+            .iter()
+            .zip(cache.inner.iter())
+            .zip(self.mutators.iter())
+            .map(
+                #[coverage(off)]
+                |((v, c), m)| m.default_mutation_step(v, c),
+            )
+            .collect::<Vec<_>>();
+        FixedLenArrayMutationStep {
+            inner,
+            element_step: 0,
+            crossover_steps: vec![CrossoverStep::default(); N],
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.search_space_complexity.get()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn max_complexity(&self) -> f64 {
+        self.max_complexity.get()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn min_complexity(&self) -> f64 {
+        self.min_complexity.get()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn complexity(&self, _value: &[T; N], cache: &Self::Cache) -> f64 {
+        cache.sum_cplx
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_arbitrary(&self, _step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<([T; N], f64)> {
+        if max_cplx < self.min_complexity() {
+            return None;
+        }
+        Some(self.random_arbitrary(max_cplx))
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_arbitrary(&self, max_cplx: f64) -> ([T; N], f64) {
+        assert!(self.initialized.get());
+        let target_cplx = crate::mutators::gen_f64(&self.rng, 1.0..max_cplx);
+        self.new_input_with_complexity(target_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_mutate(
+        &self,
+        value: &mut [T; N],
+        cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        subvalue_provider: &dyn crate::SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)> {
+        if max_cplx < self.min_complexity() {
+            return None;
+        }
+        if N == 0 || self.rng.usize(0..100) == 0 {
+            let (mut v, cplx) = self.random_arbitrary(max_cplx);
+            std::mem::swap(value, &mut v);
+            return Some((UnmutateFixedLenArrayToken::Replace(v), cplx));
+        }
+        let current_cplx = self.complexity(value, cache);
+        if self.rng.u8(..CROSSOVER_RATE) == 0 {
+            let choice = self.rng.usize(..N);
+            let step = &mut step.crossover_steps[choice];
+            let old_el_cplx = self.mutators[choice].complexity(&value[choice], &cache.inner[choice]);
+            let max_el_cplx = max_cplx - current_cplx + old_el_cplx;
+            if let Some((el, new_el_cplx)) = step.get_next_subvalue(subvalue_provider, max_el_cplx)
+                && self.mutators[choice].is_valid(el)
+            {
+                let mut el = el.clone();
+                std::mem::swap(&mut value[choice], &mut el);
+                let cplx = current_cplx - old_el_cplx + new_el_cplx;
+                return Some((UnmutateFixedLenArrayToken::ReplaceElement(choice, el), cplx));
+            }
+        }
+        if N > 1 && self.rng.usize(..20) == 0 {
+            let mut idcs = (0..N).collect::<Vec<_>>();
+            self.rng.shuffle(&mut idcs);
+            let count = self.rng.usize(2..=N);
+            let idcs = &idcs[..count];
+            Some(self.mutate_elements(value, cache, idcs, current_cplx, max_cplx))
+        } else {
+            let spare_cplx = max_cplx - current_cplx;
+            let idx = step.element_step % N;
+            step.element_step += 1;
+            self.mutate_element(value, cache, step, subvalue_provider, idx, current_cplx, spare_cplx)
+                .or_else(
+                    #[coverage(off)]
+                    || Some(self.random_mutate(value, cache, max_cplx)),
+                )
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_mutate(&self, value: &mut [T; N], cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        if N == 0 || self.rng.usize(0..100) == 0 {
+            let (mut v, cplx) = self.random_arbitrary(max_cplx);
+            std::mem::swap(value, &mut v);
+            return (UnmutateFixedLenArrayToken::Replace(v), cplx);
+        }
+        let current_cplx = self.complexity(value, cache);
+        if N > 1 && self.rng.usize(..20) == 0 {
+            let mut idcs = (0..N).collect::<Vec<_>>();
+            self.rng.shuffle(&mut idcs);
+            let count = self.rng.usize(2..=N);
+            let idcs = &idcs[..count];
+            return self.mutate_elements(value, cache, idcs, current_cplx, max_cplx);
+        }
+        let spare_cplx = max_cplx - current_cplx;
+
+        let idx = self.rng.usize(0..N);
+        let el = &mut value[idx];
+        let el_cache = &mut cache.inner[idx];
+
+        let old_el_cplx = self.mutators[idx].complexity(el, el_cache);
+        let (token, new_el_cplx) = self.mutators[idx].random_mutate(el, el_cache, spare_cplx + old_el_cplx);
+
+        (
+            UnmutateFixedLenArrayToken::Element(idx, token),
+            current_cplx - old_el_cplx + new_el_cplx,
+        )
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn unmutate(&self, value: &mut [T; N], cache: &mut Self::Cache, t: Self::UnmutateToken) {
+        match t {
+            UnmutateFixedLenArrayToken::Element(idx, inner_t) => {
+                self.mutators[idx].unmutate(&mut value[idx], &mut cache.inner[idx], inner_t);
+            }
+            UnmutateFixedLenArrayToken::Elements(tokens) => {
+                for (idx, token) in tokens {
+                    self.mutators[idx].unmutate(&mut value[idx], &mut cache.inner[idx], token);
+                }
+            }
+            UnmutateFixedLenArrayToken::Replace(new_value) => {
+                let _ = std::mem::replace(value, new_value);
+            }
+            UnmutateFixedLenArrayToken::ReplaceElement(idx, el) => {
+                let _ = std::mem::replace(&mut value[idx], el);
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn visit_subvalues<'a>(&self, value: &'a [T; N], cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
+        for idx in 0..N {
+            let cplx = self.mutators[idx].complexity(&value[idx], &cache.inner[idx]);
+            visit(&value[idx], cplx);
+        }
+        for ((el, el_cache), mutator) in value.iter().zip(cache.inner.iter()).zip(self.mutators.iter()) {
+            mutator.visit_subvalues(el, el_cache, visit);
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        let mut operators = vec![];
+        for mutator in self.mutators.iter() {
+            for operator in mutator.mutation_operators() {
+                if !operators.contains(&operator) {
+                    operators.push(operator);
+                }
+            }
+        }
+        operators
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ArrayMutator;
+    use super::{ArrayMutator, FixedLenArrayMutator};
     use crate::mutators::integer::U8Mutator;
+    use crate::mutators::integer_within_range::U8WithinRangeMutator;
     use crate::Mutator;
     #[test]
     #[coverage(off)]
@@ -394,4 +828,38 @@ mod tests {
             eprintln!("{:?}", x);
         }
     }
+
+    #[test]
+    #[coverage(off)]
+    fn test_fixed_len_array_mutator() {
+        let m = FixedLenArrayMutator::<u8, _, 3>::new([
+            U8WithinRangeMutator::new(0..=3),
+            U8WithinRangeMutator::new(10..=20),
+            U8WithinRangeMutator::new(100..=100),
+        ]);
+        m.initialize();
+        assert!(m.is_valid(&[0, 10, 100]));
+        assert!(!m.is_valid(&[4, 10, 100]));
+        for _ in 0..100 {
+            let (mut x, _) = m.ordered_arbitrary(&mut (), 800.0).unwrap();
+            assert!(m.is_valid(&x));
+            let mut cache = m.validate_value(&x).unwrap();
+            let mut step = m.default_mutation_step(&x, &cache);
+            for _ in 0..10 {
+                let original = x;
+                let (token, _) = m
+                    .ordered_mutate(
+                        &mut x,
+                        &mut cache,
+                        &mut step,
+                        &crate::subvalue_provider::EmptySubValueProvider,
+                        800.0,
+                    )
+                    .unwrap();
+                assert!(m.is_valid(&x));
+                m.unmutate(&mut x, &mut cache, token);
+                assert_eq!(x, original);
+            }
+        }
+    }
 }
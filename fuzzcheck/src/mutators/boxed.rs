@@ -159,6 +159,12 @@ impl<T: Clone + 'static, M: Mutator<T>> Mutator<Box<T>> for BoxMutator<M> {
     fn visit_subvalues<'a>(&self, value: &'a Box<T>, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
         self.mutator.visit_subvalues(value, cache, visit)
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
 }
 
 impl<T> DefaultMutator for Box<T>
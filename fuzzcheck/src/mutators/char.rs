@@ -2,29 +2,46 @@ use std::any::Any;
 use std::ops::{Bound, RangeBounds};
 
 use crate::mutators::integer::binary_search_arbitrary_u32;
-use crate::{DefaultMutator, Mutator, MutatorExt};
+use crate::mutators::integer_within_range::WithinRange;
+use crate::{DefaultMutator, Mutator};
 
 const INITIAL_MUTATION_STEP: u64 = 0;
 
-// quickly written but inefficient implementation of a general mutator for char
-//
-// does not lean towards any particular char. Use CharWithinRangeMutator or CharacterMutator
-// for more focused mutators
-impl DefaultMutator for char {
-    type Mutator = impl Mutator<char>;
+cfg_if::cfg_if! {
+    if #[cfg(feature = "unicode_char_mutator")] {
+        impl DefaultMutator for char {
+            type Mutator = crate::mutators::unicode_char::UnicodeCharMutator;
 
-    #[coverage(off)]
-    fn default_mutator() -> Self::Mutator {
-        u32::default_mutator()
-            .filter(
-                #[coverage(off)]
-                |x| char::from_u32(*x).is_some(),
-            )
-            .map(
-                #[coverage(off)]
-                |x| char::from_u32(*x).unwrap(),
-                |c| Some(*c as u32),
-            )
+            #[coverage(off)]
+            fn default_mutator() -> Self::Mutator {
+                crate::mutators::unicode_char::UnicodeCharMutator::default()
+            }
+        }
+    } else {
+        use crate::MutatorExt;
+
+        // quickly written but inefficient implementation of a general mutator for char
+        //
+        // does not lean towards any particular char. Use CharWithinRangeMutator or CharacterMutator
+        // for more focused mutators, or enable the `unicode_char_mutator` feature to use the
+        // UnicodeCharMutator by default
+        impl DefaultMutator for char {
+            type Mutator = impl Mutator<char>;
+
+            #[coverage(off)]
+            fn default_mutator() -> Self::Mutator {
+                u32::default_mutator()
+                    .filter(
+                        #[coverage(off)]
+                        |x| char::from_u32(*x).is_some(),
+                    )
+                    .map(
+                        #[coverage(off)]
+                        |x| char::from_u32(*x).unwrap(),
+                        |c| Some(*c as u32),
+                    )
+            }
+        }
     }
 }
 
@@ -73,6 +90,15 @@ impl CharWithinRangeMutator {
     }
 }
 
+impl WithinRange for char {
+    type Mutator = CharWithinRangeMutator;
+
+    #[coverage(off)]
+    fn within_range_mutator<RB: RangeBounds<char>>(range: RB) -> Self::Mutator {
+        CharWithinRangeMutator::new(range)
+    }
+}
+
 impl Mutator<char> for CharWithinRangeMutator {
     #[doc(hidden)]
     type Cache = f64; // complexity of the character
@@ -22,3 +22,40 @@ where
         )
     }
 }
+
+impl DefaultMutator for Cow<'static, str> {
+    type Mutator = impl Mutator<Cow<'static, str>>;
+
+    #[coverage(off)]
+    fn default_mutator() -> Self::Mutator {
+        MapMutator::new(
+            String::default_mutator(),
+            #[coverage(off)]
+            |s: &Cow<str>| Some(s.clone().into_owned()),
+            #[coverage(off)]
+            |s: &String| Cow::Owned(s.clone()),
+            #[coverage(off)]
+            |s, _cplx| (s.len() * 8) as f64,
+        )
+    }
+}
+
+impl<T> DefaultMutator for Cow<'static, [T]>
+where
+    T: DefaultMutator + Clone + 'static,
+{
+    type Mutator = impl Mutator<Cow<'static, [T]>>;
+
+    #[coverage(off)]
+    fn default_mutator() -> Self::Mutator {
+        MapMutator::new(
+            Vec::<T>::default_mutator(),
+            #[coverage(off)]
+            |xs: &Cow<[T]>| Some(xs.clone().into_owned()),
+            #[coverage(off)]
+            |xs: &Vec<T>| Cow::Owned(xs.clone()),
+            #[coverage(off)]
+            |_, cplx| cplx,
+        )
+    }
+}
@@ -0,0 +1,590 @@
+/*!
+Mutators of trait objects, such as `Box<dyn Trait>`, built from the mutators of the concrete types implementing the trait.
+
+An [`AnyMutator`] is created by registering a mutator for each concrete type with an [`AnyMutatorBuilder`],
+along with a function converting the concrete values to trait objects. Each generated trait object is
+produced by one of the registered mutators, and it is mutated in place by the same mutator. Occasionally,
+a trait object is replaced by one of another concrete type.
+
+The trait objects must be [`Clone`], and they must be downcastable to their concrete types through the
+[`AsAny`] trait. For a `Box<dyn Trait>`, this is usually done by giving `Trait` a method that clones the
+object into a new box, and requiring that `Trait: Any`:
+```
+use std::any::Any;
+use fuzzcheck::DefaultMutator;
+use fuzzcheck::mutators::MutatorExt;
+use fuzzcheck::mutators::dynamic::{AnyMutator, AnyMutatorBuilder, AsAny};
+
+trait Shape: Any {
+    fn area(&self) -> f64;
+    fn clone_box(&self) -> Box<dyn Shape>;
+}
+impl Clone for Box<dyn Shape> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+impl AsAny for Box<dyn Shape> {
+    fn as_any(&self) -> &dyn Any {
+        &**self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        &mut **self
+    }
+}
+
+#[derive(Clone)]
+struct Circle(u8);
+impl Shape for Circle {
+    fn area(&self) -> f64 { 3.14 * (self.0 as f64).powi(2) }
+    fn clone_box(&self) -> Box<dyn Shape> { Box::new(self.clone()) }
+}
+#[derive(Clone)]
+struct Rectangle((u8, u8));
+impl Shape for Rectangle {
+    fn area(&self) -> f64 { self.0.0 as f64 * self.0.1 as f64 }
+    fn clone_box(&self) -> Box<dyn Shape> { Box::new(self.clone()) }
+}
+
+let mutator: AnyMutator<Box<dyn Shape>> = AnyMutatorBuilder::new()
+    .register(u8::default_mutator().map(|x| Circle(*x), |c: &Circle| Some(c.0)), |c| Box::new(c) as Box<dyn Shape>)
+    .register(<(u8, u8)>::default_mutator().map(|x| Rectangle(*x), |r: &Rectangle| Some(r.0)), |r| Box::new(r) as Box<dyn Shape>)
+    .build();
+```
+*/
+
+use std::any::Any;
+use std::marker::PhantomData;
+
+use crate::mutators::alternation::AlternationMutator;
+use crate::{Mutator, SubValueProvider};
+
+/// Gives access to the concrete value behind a trait object, so that it can be downcast to its type.
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// A mutator of trait objects, created by an [`AnyMutatorBuilder`]
+///
+/// It is an [`AlternationMutator`], such that the probability of choosing each concrete type can be changed
+/// with [`set_weights`](AlternationMutator::set_weights).
+pub type AnyMutator<B> = AlternationMutator<B, VariantMutator<B>>;
+
+/// Registers the mutators of the concrete types of the trait objects generated by an [`AnyMutator`]
+pub struct AnyMutatorBuilder<B>
+where
+    B: Clone + AsAny + 'static,
+{
+    variants: Vec<VariantMutator<B>>,
+    added_complexity: f64,
+}
+
+impl<B> Default for AnyMutatorBuilder<B>
+where
+    B: Clone + AsAny + 'static,
+{
+    #[coverage(off)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B> AnyMutatorBuilder<B>
+where
+    B: Clone + AsAny + 'static,
+{
+    #[coverage(off)]
+    pub fn new() -> Self {
+        Self {
+            variants: vec![],
+            added_complexity: 0.0,
+        }
+    }
+
+    /// Register the mutator of a concrete type `T`, and the function converting values of type `T` to trait objects.
+    ///
+    /// The trait objects given to the [`AnyMutator`] are handled by the mutator of the first registered type
+    /// to which they can be downcast. Therefore, each concrete type should be registered only once.
+    #[must_use]
+    #[coverage(off)]
+    pub fn register<T, M>(mut self, mutator: M, wrap: impl Fn(T) -> B + 'static) -> Self
+    where
+        T: Clone + 'static,
+        M: Mutator<T>,
+        M::Cache: 'static,
+        M::MutationStep: 'static,
+        M::ArbitraryStep: 'static,
+        M::UnmutateToken: 'static,
+    {
+        self.variants.push(VariantMutator {
+            inner: Box::new(ConcreteMutator {
+                mutator,
+                wrap,
+                _phantom: PhantomData,
+            }),
+        });
+        self
+    }
+
+    /// The complexity added to the one of the concrete values, see [`AlternationMutator::new`]
+    #[must_use]
+    #[coverage(off)]
+    pub fn added_complexity(mut self, added_complexity: f64) -> Self {
+        self.added_complexity = added_complexity;
+        self
+    }
+
+    /// Create the [`AnyMutator`].
+    ///
+    /// ## Panics
+    /// Panics if no mutator was registered.
+    #[coverage(off)]
+    pub fn build(self) -> AnyMutator<B> {
+        assert!(
+            !self.variants.is_empty(),
+            "an AnyMutator needs at least one registered mutator"
+        );
+        AlternationMutator::new(self.variants, self.added_complexity)
+    }
+}
+
+/// The caches, steps, and unmutate tokens of the registered mutators, whose types are erased
+#[doc(hidden)]
+pub struct ErasedValue(Box<dyn CloneAny>);
+
+impl Clone for ErasedValue {
+    #[coverage(off)]
+    fn clone(&self) -> Self {
+        Self(self.0.clone_erased())
+    }
+}
+
+impl ErasedValue {
+    #[coverage(off)]
+    fn new<T: Clone + 'static>(value: T) -> Self {
+        Self(Box::new(value))
+    }
+    #[coverage(off)]
+    fn get<T: 'static>(&self) -> &T {
+        self.0
+            .erased_ref()
+            .downcast_ref()
+            .expect("an AnyMutator was given the cache or step of another mutator")
+    }
+    #[coverage(off)]
+    fn get_mut<T: 'static>(&mut self) -> &mut T {
+        self.0
+            .erased_mut()
+            .downcast_mut()
+            .expect("an AnyMutator was given the cache or step of another mutator")
+    }
+}
+
+trait CloneAny: Any {
+    fn clone_erased(&self) -> Box<dyn CloneAny>;
+    fn erased_ref(&self) -> &dyn Any;
+    fn erased_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Clone + 'static> CloneAny for T {
+    #[coverage(off)]
+    fn clone_erased(&self) -> Box<dyn CloneAny> {
+        Box::new(self.clone())
+    }
+    #[coverage(off)]
+    fn erased_ref(&self) -> &dyn Any {
+        self
+    }
+    #[coverage(off)]
+    fn erased_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The mutator of one concrete type registered in an [`AnyMutatorBuilder`]
+///
+/// It only accepts the trait objects which can be downcast to its concrete type.
+pub struct VariantMutator<B> {
+    inner: Box<dyn ErasedMutator<B>>,
+}
+
+/// The methods of [`Mutator`], with the concrete type hidden behind the trait object `B`, and the associated
+/// types erased
+trait ErasedMutator<B>: 'static {
+    fn initialize(&self);
+    fn default_arbitrary_step(&self) -> ErasedValue;
+    fn is_valid(&self, value: &B) -> bool;
+    fn validate_value(&self, value: &B) -> Option<ErasedValue>;
+    fn default_mutation_step(&self, value: &B, cache: &ErasedValue) -> ErasedValue;
+    fn global_search_space_complexity(&self) -> f64;
+    fn max_complexity(&self) -> f64;
+    fn min_complexity(&self) -> f64;
+    fn complexity(&self, value: &B, cache: &ErasedValue) -> f64;
+    fn ordered_arbitrary(&self, step: &mut ErasedValue, max_cplx: f64) -> Option<(B, f64)>;
+    fn random_arbitrary(&self, max_cplx: f64) -> (B, f64);
+    fn ordered_mutate(
+        &self,
+        value: &mut B,
+        cache: &mut ErasedValue,
+        step: &mut ErasedValue,
+        subvalue_provider: &dyn SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Box<dyn Any>, f64)>;
+    fn random_mutate(&self, value: &mut B, cache: &mut ErasedValue, max_cplx: f64) -> (Box<dyn Any>, f64);
+    fn unmutate(&self, value: &mut B, cache: &mut ErasedValue, t: Box<dyn Any>);
+    fn visit_subvalues<'a>(&self, value: &'a B, cache: &'a ErasedValue, visit: &mut dyn FnMut(&'a dyn Any, f64));
+    fn mutation_operators(&self) -> Vec<&'static str>;
+}
+
+struct ConcreteMutator<T, M, Wrap> {
+    mutator: M,
+    wrap: Wrap,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, M, Wrap> ConcreteMutator<T, M, Wrap>
+where
+    T: 'static,
+{
+    #[coverage(off)]
+    fn downcast<B: AsAny>(value: &B) -> &T {
+        value
+            .as_any()
+            .downcast_ref()
+            .expect("an AnyMutator was given a value of another concrete type than the one of its cache")
+    }
+    #[coverage(off)]
+    fn downcast_mut<B: AsAny>(value: &mut B) -> &mut T {
+        value
+            .as_any_mut()
+            .downcast_mut()
+            .expect("an AnyMutator was given a value of another concrete type than the one of its cache")
+    }
+}
+
+impl<B, T, M, Wrap> ErasedMutator<B> for ConcreteMutator<T, M, Wrap>
+where
+    B: Clone + AsAny + 'static,
+    T: Clone + 'static,
+    M: Mutator<T>,
+    M::Cache: 'static,
+    M::MutationStep: 'static,
+    M::ArbitraryStep: 'static,
+    M::UnmutateToken: 'static,
+    Wrap: Fn(T) -> B + 'static,
+{
+    #[coverage(off)]
+    fn initialize(&self) {
+        self.mutator.initialize()
+    }
+    #[coverage(off)]
+    fn default_arbitrary_step(&self) -> ErasedValue {
+        ErasedValue::new(self.mutator.default_arbitrary_step())
+    }
+    #[coverage(off)]
+    fn is_valid(&self, value: &B) -> bool {
+        value.as_any().downcast_ref().is_some_and(
+            #[coverage(off)]
+            |value| self.mutator.is_valid(value),
+        )
+    }
+    #[coverage(off)]
+    fn validate_value(&self, value: &B) -> Option<ErasedValue> {
+        let value = value.as_any().downcast_ref()?;
+        self.mutator.validate_value(value).map(ErasedValue::new)
+    }
+    #[coverage(off)]
+    fn default_mutation_step(&self, value: &B, cache: &ErasedValue) -> ErasedValue {
+        ErasedValue::new(
+            self.mutator
+                .default_mutation_step(Self::downcast(value), cache.get::<M::Cache>()),
+        )
+    }
+    #[coverage(off)]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.mutator.global_search_space_complexity()
+    }
+    #[coverage(off)]
+    fn max_complexity(&self) -> f64 {
+        self.mutator.max_complexity()
+    }
+    #[coverage(off)]
+    fn min_complexity(&self) -> f64 {
+        self.mutator.min_complexity()
+    }
+    #[coverage(off)]
+    fn complexity(&self, value: &B, cache: &ErasedValue) -> f64 {
+        self.mutator.complexity(Self::downcast(value), cache.get::<M::Cache>())
+    }
+    #[coverage(off)]
+    fn ordered_arbitrary(&self, step: &mut ErasedValue, max_cplx: f64) -> Option<(B, f64)> {
+        let (value, cplx) = self
+            .mutator
+            .ordered_arbitrary(step.get_mut::<M::ArbitraryStep>(), max_cplx)?;
+        Some(((self.wrap)(value), cplx))
+    }
+    #[coverage(off)]
+    fn random_arbitrary(&self, max_cplx: f64) -> (B, f64) {
+        let (value, cplx) = self.mutator.random_arbitrary(max_cplx);
+        ((self.wrap)(value), cplx)
+    }
+    #[coverage(off)]
+    fn ordered_mutate(
+        &self,
+        value: &mut B,
+        cache: &mut ErasedValue,
+        step: &mut ErasedValue,
+        subvalue_provider: &dyn SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Box<dyn Any>, f64)> {
+        let (token, cplx) = self.mutator.ordered_mutate(
+            Self::downcast_mut(value),
+            cache.get_mut::<M::Cache>(),
+            step.get_mut::<M::MutationStep>(),
+            subvalue_provider,
+            max_cplx,
+        )?;
+        Some((Box::new(token), cplx))
+    }
+    #[coverage(off)]
+    fn random_mutate(&self, value: &mut B, cache: &mut ErasedValue, max_cplx: f64) -> (Box<dyn Any>, f64) {
+        let (token, cplx) =
+            self.mutator
+                .random_mutate(Self::downcast_mut(value), cache.get_mut::<M::Cache>(), max_cplx);
+        (Box::new(token), cplx)
+    }
+    #[coverage(off)]
+    fn unmutate(&self, value: &mut B, cache: &mut ErasedValue, t: Box<dyn Any>) {
+        let t = t
+            .downcast::<M::UnmutateToken>()
+            .expect("an AnyMutator was given the unmutate token of another mutator");
+        self.mutator
+            .unmutate(Self::downcast_mut(value), cache.get_mut::<M::Cache>(), *t)
+    }
+    #[coverage(off)]
+    fn visit_subvalues<'a>(&self, value: &'a B, cache: &'a ErasedValue, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
+        self.mutator
+            .visit_subvalues(Self::downcast(value), cache.get::<M::Cache>(), visit)
+    }
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
+}
+
+impl<B> Mutator<B> for VariantMutator<B>
+where
+    B: Clone + AsAny + 'static,
+{
+    #[doc(hidden)]
+    type Cache = ErasedValue;
+    #[doc(hidden)]
+    type MutationStep = ErasedValue;
+    #[doc(hidden)]
+    type ArbitraryStep = ErasedValue;
+    #[doc(hidden)]
+    type UnmutateToken = Box<dyn Any>;
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn initialize(&self) {
+        self.inner.initialize()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
+        self.inner.default_arbitrary_step()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn is_valid(&self, value: &B) -> bool {
+        self.inner.is_valid(value)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn validate_value(&self, value: &B) -> Option<Self::Cache> {
+        self.inner.validate_value(value)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_mutation_step(&self, value: &B, cache: &Self::Cache) -> Self::MutationStep {
+        self.inner.default_mutation_step(value, cache)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.inner.global_search_space_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn max_complexity(&self) -> f64 {
+        self.inner.max_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn min_complexity(&self) -> f64 {
+        self.inner.min_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn complexity(&self, value: &B, cache: &Self::Cache) -> f64 {
+        self.inner.complexity(value, cache)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(B, f64)> {
+        self.inner.ordered_arbitrary(step, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_arbitrary(&self, max_cplx: f64) -> (B, f64) {
+        self.inner.random_arbitrary(max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_mutate(
+        &self,
+        value: &mut B,
+        cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        subvalue_provider: &dyn SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)> {
+        self.inner
+            .ordered_mutate(value, cache, step, subvalue_provider, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_mutate(&self, value: &mut B, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        self.inner.random_mutate(value, cache, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn unmutate(&self, value: &mut B, cache: &mut Self::Cache, t: Self::UnmutateToken) {
+        self.inner.unmutate(value, cache, t)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn visit_subvalues<'a>(&self, value: &'a B, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
+        self.inner.visit_subvalues(value, cache, visit)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.inner.mutation_operators()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use super::*;
+    use crate::mutators::validate::{check_mutator_by_key, CheckOptions};
+    use crate::{DefaultMutator, MutatorExt};
+
+    trait Shape: Any + Debug {
+        fn clone_box(&self) -> Box<dyn Shape>;
+    }
+    impl Clone for Box<dyn Shape> {
+        #[coverage(off)]
+        fn clone(&self) -> Self {
+            self.clone_box()
+        }
+    }
+    impl AsAny for Box<dyn Shape> {
+        #[coverage(off)]
+        fn as_any(&self) -> &dyn Any {
+            &**self
+        }
+        #[coverage(off)]
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            &mut **self
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct Circle(u8);
+    impl Shape for Circle {
+        #[coverage(off)]
+        fn clone_box(&self) -> Box<dyn Shape> {
+            Box::new(self.clone())
+        }
+    }
+    #[derive(Clone, Debug)]
+    struct Rectangle(Vec<u16>);
+    impl Shape for Rectangle {
+        #[coverage(off)]
+        fn clone_box(&self) -> Box<dyn Shape> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[coverage(off)]
+    fn shape_mutator() -> AnyMutator<Box<dyn Shape>> {
+        AnyMutatorBuilder::new()
+            .register(
+                u8::default_mutator().map(
+                    #[coverage(off)]
+                    |x| Circle(*x),
+                    #[coverage(off)]
+                    |c: &Circle| Some(c.0),
+                ),
+                #[coverage(off)]
+                |c| Box::new(c) as Box<dyn Shape>,
+            )
+            .register(
+                <Vec<u16>>::default_mutator().map(
+                    #[coverage(off)]
+                    |x| Rectangle(x.clone()),
+                    #[coverage(off)]
+                    |r: &Rectangle| Some(r.0.clone()),
+                ),
+                #[coverage(off)]
+                |r| Box::new(r) as Box<dyn Shape>,
+            )
+            .build()
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_any_mutator() {
+        let m = shape_mutator();
+        m.initialize();
+        let options = CheckOptions::default().check_duplicates(false);
+        check_mutator_by_key(
+            &m,
+            &options,
+            #[coverage(off)]
+            |shape| format!("{:?}", shape),
+        )
+        .unwrap();
+
+        let (mut circles, mut rectangles) = (0, 0);
+        for _ in 0..100 {
+            let (shape, _) = m.random_arbitrary(100.0);
+            if shape.as_any().is::<Circle>() {
+                circles += 1;
+            } else if shape.as_any().is::<Rectangle>() {
+                rectangles += 1;
+            }
+        }
+        assert!(circles > 0 && rectangles > 0);
+    }
+}
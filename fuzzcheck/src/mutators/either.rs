@@ -210,6 +210,15 @@ where
             _ => unreachable!(),
         }
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        match self {
+            Either::Left(m) => m.mutation_operators(),
+            Either::Right(m) => m.mutation_operators(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -450,4 +459,14 @@ where
             _ => unreachable!(),
         }
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        match self {
+            Either3::A(m) => m.mutation_operators(),
+            Either3::B(m) => m.mutation_operators(),
+            Either3::C(m) => m.mutation_operators(),
+        }
+    }
 }
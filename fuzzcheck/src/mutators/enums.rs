@@ -4,6 +4,9 @@ use crate::Mutator;
 
 /// Trait used by the [DefaultMutator derive macro](fuzzcheck_mutators_derive::DefaultMutator)
 /// for enums without associated data
+///
+/// The variant indices range from `0` to the number of variants, regardless of the explicit discriminants
+/// of the enum, if any.
 pub trait BasicEnumStructure {
     fn from_variant_index(item_index: usize) -> Self;
     fn get_variant_index(&self) -> usize;
@@ -1,14 +1,67 @@
 use std::any::Any;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::Mutator;
 
+/// The number of values accepted and rejected by all the filter mutators of the process
+static TOTAL_ACCEPTED: AtomicU64 = AtomicU64::new(0);
+static TOTAL_REJECTED: AtomicU64 = AtomicU64::new(0);
+
+/// The number of values generated by the inner mutator of a [`FilterMutator`] that were
+/// accepted and rejected by its filtering function.
+///
+/// Only the values produced by the `arbitrary` and `mutate` methods are counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FilterStats {
+    pub accepted: u64,
+    pub rejected: u64,
+}
+
+impl FilterStats {
+    /// The proportion of values that were rejected, between 0.0 and 1.0
+    #[coverage(off)]
+    pub fn rejection_rate(&self) -> f64 {
+        let total = self.accepted + self.rejected;
+        if total == 0 {
+            0.0
+        } else {
+            self.rejected as f64 / total as f64
+        }
+    }
+
+    /// The statistics of all the filter mutators of the process, which are used by the fuzzer to
+    /// warn about filters that reject most values
+    #[coverage(off)]
+    pub(crate) fn global() -> Self {
+        Self {
+            accepted: TOTAL_ACCEPTED.load(Ordering::Relaxed),
+            rejected: TOTAL_REJECTED.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The values that were accepted and rejected since the `earlier` statistics were taken
+    #[coverage(off)]
+    pub(crate) fn since(&self, earlier: &Self) -> Self {
+        Self {
+            accepted: self.accepted - earlier.accepted,
+            rejected: self.rejected - earlier.rejected,
+        }
+    }
+}
+
 /// A [`FilterMutator`] provides a way to filter values outputted by a mutator.
 /// Given any [`Mutator<Value=T>`] and a function [`Fn(&T) -> bool`] it creates
 /// a new mutator which can generate all the values of `T` the underlying
 /// mutator can, except those for which the filtering function returns false.
+///
+/// The values rejected by the filter are retried silently, which can consume most of the mutation budget
+/// if the filter is too strict. The number of accepted and rejected values is given by [`FilterMutator::stats`],
+/// and the fuzzer prints a warning when the filters reject most of the generated values.
 pub struct FilterMutator<M, F> {
     mutator: M,
     filter: F,
+    stats: Cell<FilterStats>,
 }
 
 impl<M, F> FilterMutator<M, F> {
@@ -23,7 +76,36 @@ impl<M, F> FilterMutator<M, F> {
         F: Fn(&T) -> bool,
         Self: 'static,
     {
-        FilterMutator { mutator, filter }
+        FilterMutator {
+            mutator,
+            filter,
+            stats: Cell::new(FilterStats::default()),
+        }
+    }
+
+    /// The number of values accepted and rejected by the filtering function so far
+    #[coverage(off)]
+    pub fn stats(&self) -> FilterStats {
+        self.stats.get()
+    }
+
+    /// Call the filtering function on the value and record whether it was accepted
+    #[coverage(off)]
+    fn filter_and_record<T>(&self, value: &T) -> bool
+    where
+        F: Fn(&T) -> bool,
+    {
+        let accepted = (self.filter)(value);
+        let mut stats = self.stats.get();
+        if accepted {
+            stats.accepted += 1;
+            TOTAL_ACCEPTED.fetch_add(1, Ordering::Relaxed);
+        } else {
+            stats.rejected += 1;
+            TOTAL_REJECTED.fetch_add(1, Ordering::Relaxed);
+        }
+        self.stats.set(stats);
+        accepted
     }
 }
 
@@ -108,7 +190,7 @@ where
         loop {
             let x = self.mutator.ordered_arbitrary(step, max_cplx);
             if let Some(x) = x {
-                if (self.filter)(&x.0) {
+                if self.filter_and_record(&x.0) {
                     return Some(x);
                 }
             } else {
@@ -122,7 +204,7 @@ where
     fn random_arbitrary(&self, max_cplx: f64) -> (T, f64) {
         loop {
             let x = self.mutator.random_arbitrary(max_cplx);
-            if (self.filter)(&x.0) {
+            if self.filter_and_record(&x.0) {
                 return x;
             }
         }
@@ -143,7 +225,7 @@ where
                 .mutator
                 .ordered_mutate(value, cache, step, subvalue_provider, max_cplx)
             {
-                if (self.filter)(value) {
+                if self.filter_and_record(value) {
                     return Some((t, cplx));
                 } else {
                     self.mutator.unmutate(value, cache, t);
@@ -159,7 +241,7 @@ where
     fn random_mutate(&self, value: &mut T, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
         loop {
             let (t, cplx) = self.mutator.random_mutate(value, cache, max_cplx);
-            if (self.filter)(value) {
+            if self.filter_and_record(value) {
                 return (t, cplx);
             } else {
                 self.mutator.unmutate(value, cache, t);
@@ -178,4 +260,39 @@ where
     fn visit_subvalues<'a>(&self, value: &'a T, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
         self.mutator.visit_subvalues(value, cache, visit)
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mutators::integer::U8Mutator;
+    use crate::{DefaultMutator, Mutator, MutatorExt};
+
+    #[test]
+    #[coverage(off)]
+    fn test_filter_stats() {
+        let mutator = U8Mutator::default().filter(
+            #[coverage(off)]
+            |x| x % 4 == 0,
+        );
+        for _ in 0..1000 {
+            let (x, _) = mutator.random_arbitrary(8.0);
+            assert_eq!(x % 4, 0);
+        }
+        let stats = mutator.stats();
+        assert_eq!(stats.accepted, 1000);
+        assert!(stats.rejected > 2000);
+        assert!((0.6..0.9).contains(&stats.rejection_rate()));
+
+        let mutator = bool::default_mutator().filter(
+            #[coverage(off)]
+            |_| true,
+        );
+        assert_eq!(mutator.stats().rejection_rate(), 0.0);
+    }
 }
@@ -490,6 +490,20 @@ impl<T: Clone + 'static, M: Mutator<T>> Mutator<Vec<T>> for FixedLenVecMutator<T
             }
         }
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        let mut operators = vec![];
+        for mutator in self.mutators.iter() {
+            for operator in mutator.mutation_operators() {
+                if !operators.contains(&operator) {
+                    operators.push(operator);
+                }
+            }
+        }
+        operators
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,170 @@
+use std::any::Any;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use crate::mutators::grammar::ast::AST;
+use crate::mutators::grammar::grammar::Grammar;
+
+/// A function which builds a typed value from the part of a syntax tree matched by a grammar rule.
+///
+/// It is attached to a grammar rule by the [`action`](crate::mutators::grammar::action) function.
+#[derive(Clone)]
+pub struct SemanticAction {
+    f: Rc<dyn Fn(&str, &ActionValues) -> Rc<dyn Any>>,
+}
+
+impl SemanticAction {
+    #[coverage(off)]
+    pub(crate) fn new<T: 'static>(f: impl Fn(&str, &ActionValues) -> T + 'static) -> Self {
+        Self {
+            f: Rc::new(
+                #[coverage(off)]
+                move |text, values| Rc::new(f(text, values)),
+            ),
+        }
+    }
+}
+
+impl Debug for SemanticAction {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SemanticAction")
+    }
+}
+
+/// The values built by the semantic actions of the grammar rules nested inside another one, in the order
+/// in which they appear in the string.
+///
+/// Only the outermost actions are taken into account: an action nested inside another nested action
+/// contributes to the value of the latter instead.
+pub struct ActionValues<'a> {
+    values: &'a [Rc<dyn Any>],
+}
+
+impl<'a> ActionValues<'a> {
+    #[coverage(off)]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[coverage(off)]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the value at the given index, or `None` if there is no value of type `T` at this index
+    #[coverage(off)]
+    pub fn try_get<T: 'static>(&self, idx: usize) -> Option<&'a T> {
+        self.values.get(idx)?.downcast_ref()
+    }
+
+    /// Returns the value at the given index.
+    ///
+    /// ## Panics
+    /// Panics if there is no value of type `T` at this index.
+    #[coverage(off)]
+    pub fn get<T: 'static>(&self, idx: usize) -> &'a T {
+        self.try_get(idx).unwrap_or_else(
+            #[coverage(off)]
+            || {
+                panic!(
+                    "no value of type {} at index {} of the values of the semantic actions",
+                    std::any::type_name::<T>(),
+                    idx
+                )
+            },
+        )
+    }
+
+    /// Iterates over the values of type `T`, ignoring those of any other type
+    #[coverage(off)]
+    pub fn iter<T: 'static>(&self) -> impl Iterator<Item = &'a T> {
+        self.values.iter().filter_map(
+            #[coverage(off)]
+            |value| value.downcast_ref(),
+        )
+    }
+}
+
+/// Run the semantic actions of the grammar on the syntax tree and return the value built by the outermost one.
+///
+/// When the syntax tree can be matched by several alternatives of the grammar, the semantic actions of the
+/// first one are used.
+///
+/// ## Panics
+/// Panics if the syntax tree does not match the grammar, or if the grammar does not produce a single value
+/// of type `T`.
+#[coverage(off)]
+pub(crate) fn evaluate_actions<T: Clone + 'static>(grammar: &Grammar, ast: &AST) -> T {
+    let mut values = vec![];
+    assert!(
+        evaluate_rec(grammar, ast, &mut values),
+        "the syntax tree does not match the grammar"
+    );
+    match values.as_slice() {
+        [value] => value
+            .downcast_ref::<T>()
+            .unwrap_or_else(
+                #[coverage(off)]
+                || {
+                    panic!(
+                        "the outermost semantic action of the grammar does not build a value of type {}",
+                        std::any::type_name::<T>()
+                    )
+                },
+            )
+            .clone(),
+        _ => panic!(
+            "the grammar must build a single value through its semantic actions, but it built {} values",
+            values.len()
+        ),
+    }
+}
+
+/// Match the syntax tree against the grammar and push the values built by its outermost semantic actions.
+///
+/// Returns `false` if the tree does not match the grammar, in which case `values` is left unchanged.
+#[coverage(off)]
+fn evaluate_rec(grammar: &Grammar, ast: &AST, values: &mut Vec<Rc<dyn Any>>) -> bool {
+    let start = values.len();
+    let matches = match (grammar, ast) {
+        (Grammar::Literal(ranges), AST::Token(c)) => ranges.iter().any(
+            #[coverage(off)]
+            |range| range.contains(c),
+        ),
+        (Grammar::Alternation(gs), _) => gs.iter().any(
+            #[coverage(off)]
+            |g| evaluate_rec(g, ast, values),
+        ),
+        (Grammar::Concatenation(gs), AST::Sequence(asts)) => {
+            gs.len() == asts.len()
+                && gs.iter().zip(asts.iter()).all(
+                    #[coverage(off)]
+                    |(g, ast)| evaluate_rec(g, ast, values),
+                )
+        }
+        (Grammar::Repetition(g, range), AST::Sequence(asts)) => {
+            range.contains(&asts.len())
+                && asts.iter().all(
+                    #[coverage(off)]
+                    |ast| evaluate_rec(g, ast, values),
+                )
+        }
+        (Grammar::Recurse(g), AST::Sequence(asts)) => {
+            let g = g.upgrade().expect("the recursive grammar rule was dropped");
+            matches!(asts.as_slice(), [ast] if evaluate_rec(&g, ast, values))
+        }
+        (Grammar::Recursive(g), _) => evaluate_rec(g, ast, values),
+        (Grammar::Action(g, action), _) if evaluate_rec(g, ast, values) => {
+            let inner_values = values.split_off(start);
+            let value = (action.f)(&ast.to_string(), &ActionValues { values: &inner_values });
+            values.push(value);
+            true
+        }
+        _ => false,
+    };
+    if !matches {
+        values.truncate(start);
+    }
+    matches
+}
@@ -1,8 +1,9 @@
 use std::ops::{Range, RangeBounds, RangeInclusive};
 use std::rc::{Rc, Weak};
 
+use crate::mutators::grammar::actions::{ActionValues, SemanticAction};
 #[cfg(feature = "regex_grammar")]
-use crate::mutators::grammar::regex::grammar_from_regex;
+use crate::mutators::grammar::regex::{grammar_from_regex, RegexGrammarError};
 
 #[derive(Clone, Debug)]
 /// A grammar which can be used for fuzzing.
@@ -15,12 +16,38 @@ pub enum Grammar {
     Repetition(Rc<Grammar>, Range<usize>),
     Recurse(Weak<Grammar>),
     Recursive(Rc<Grammar>),
+    Action(Rc<Grammar>, SemanticAction),
 }
 
 #[cfg(feature = "regex_grammar")]
 #[doc(cfg(feature = "regex_grammar"))]
 #[coverage(off)]
+/// Creates an [`Rc<Grammar>`] which matches the given regular expression.
+///
+/// All the syntax accepted by the `regex` crate is supported, except for:
+/// * word boundaries (`\b` and `\B`)
+/// * anchors (`^`, `$`, `\A`, `\z`) which are not at the start or the end of the regular expression
+/// * classes containing no character at all
+///
+/// Anchors match the empty string. Therefore, they are only meaningful if the grammar is not part of a larger one.
+///
+/// ## Panics
+/// Panics if the regular expression is invalid or unsupported. Use [`try_regex`] to handle these errors instead.
 pub fn regex(s: &str) -> Rc<Grammar> {
+    match grammar_from_regex(s) {
+        Ok(grammar) => grammar,
+        Err(e) => panic!("{} (in the regular expression {:?})", e, s),
+    }
+}
+
+#[cfg(feature = "regex_grammar")]
+#[doc(cfg(feature = "regex_grammar"))]
+#[coverage(off)]
+/// Creates an [`Rc<Grammar>`] which matches the given regular expression, or returns an error explaining
+/// why it cannot be done.
+///
+/// See [`regex`] for the supported syntax.
+pub fn try_regex(s: &str) -> Result<Rc<Grammar>, RegexGrammarError> {
     grammar_from_regex(s)
 }
 
@@ -128,3 +155,22 @@ pub fn recursive(data_fn: impl Fn(&Weak<Grammar>) -> Rc<Grammar>) -> Rc<Grammar>
         Rc::try_unwrap(data_fn(g)).unwrap()
     })))
 }
+
+#[coverage(off)]
+/// Attaches a semantic action to the grammar rule, which builds a typed value from the strings it matches.
+///
+/// The action is given the string matched by the rule and the values built by the actions nested inside it.
+/// It is used by [`grammar_based_typed_mutator`](crate::mutators::grammar::grammar_based_typed_mutator)
+/// and has no effect on the other grammar-based mutators.
+///
+/// ```
+/// # use fuzzcheck::mutators::grammar::{action, concatenation, literal, literal_range, repetition};
+/// let number = action(repetition(literal_range('0'..='9'), 1..=9), |text, _| text.parse::<u64>().unwrap());
+/// let sum = action(
+///     concatenation([number.clone(), literal('+'), number]),
+///     |_, values| values.get::<u64>(0) + values.get::<u64>(1),
+/// );
+/// ```
+pub fn action<T: 'static>(g: Rc<Grammar>, f: impl Fn(&str, &ActionValues) -> T + 'static) -> Rc<Grammar> {
+    Rc::new(Grammar::Action(g, SemanticAction::new(f)))
+}
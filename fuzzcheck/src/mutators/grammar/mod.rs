@@ -3,7 +3,10 @@
 //! This module provides a grammar-based `impl Mutator<AST>` which generates an abstract syntax
 //! tree satisfying a grammar, created through [`grammar_based_ast_mutator`]. The resulting mutator can be
 //! transformed into a `Mutator<(AST, String)>`, where the second element of the tuple is the string corresponding
-//! to the abstract syntax tree, by calling [`.with_string()`](ASTMutator::with_string).
+//! to the abstract syntax tree, by calling [`.with_string()`](ASTMutator::with_string). Alternatively,
+//! [`grammar_based_string_mutator`] creates a `Mutator<String>` which hides the abstract syntax tree.
+//! Finally, [`grammar_based_typed_mutator`] creates a `Mutator<T>`, where `T` is a type built from the
+//! abstract syntax tree by semantic actions attached to the grammar rules with [`action`].
 //!
 //! To specify a grammar, you should use the following functions:
 #![cfg_attr(
@@ -17,6 +20,7 @@
 //! * [`concatenation`] matching multiple grammar rules one after the other
 //! * [`repetition`] matching a grammar rule multiple times
 //! * [`recursive`] and [`recurse`] to create recursive grammar rules
+//! * [`action`] to build a typed value from the strings matched by a grammar rule
 #![cfg_attr(
     feature = "regex_grammar",
     doc = r###"
@@ -62,24 +66,40 @@ let rule = recursive(|rule| {
 #![allow(clippy::module_inception)]
 #![allow(clippy::nonstandard_macro_braces)]
 
+mod actions;
 mod ast;
 mod grammar;
 mod mutators;
+mod parser;
 
 #[cfg(feature = "regex_grammar")]
 mod regex;
 
+#[doc(inline)]
+pub use actions::{ActionValues, SemanticAction};
 #[doc(inline)]
 pub use ast::AST;
+#[doc(inline)]
+pub use grammar::Grammar;
+#[doc(inline)]
+pub use grammar::{
+    action, alternation, concatenation, literal, literal_range, literal_ranges, recurse, recursive, repetition,
+};
 #[cfg(feature = "regex_grammar")]
 #[doc(inline)]
 #[doc(cfg(feature = "regex_grammar"))]
-pub use grammar::regex;
+pub use grammar::{regex, try_regex};
 #[doc(inline)]
-pub use grammar::Grammar;
+pub use mutators::grammar_based_ast_mutator;
 #[doc(inline)]
-pub use grammar::{alternation, concatenation, literal, literal_range, literal_ranges, recurse, recursive, repetition};
+pub use mutators::grammar_based_string_mutator;
 #[doc(inline)]
-pub use mutators::grammar_based_ast_mutator;
+pub use mutators::grammar_based_typed_mutator;
 #[doc(inline)]
 pub use mutators::ASTMutator;
+#[doc(inline)]
+pub use mutators::GrammarBasedStringMutator;
+#[cfg(feature = "regex_grammar")]
+#[doc(inline)]
+#[doc(cfg(feature = "regex_grammar"))]
+pub use regex::RegexGrammarError;
@@ -11,8 +11,10 @@ use crate::mutators::alternation::AlternationMutator;
 use crate::mutators::character_classes::CharacterMutator;
 use crate::mutators::either::Either3;
 use crate::mutators::fixed_len_vector::FixedLenVecMutator;
+use crate::mutators::grammar::actions::evaluate_actions;
 use crate::mutators::grammar::ast::AST;
-use crate::mutators::map::AndMapMutator;
+use crate::mutators::grammar::parser::parse_ast;
+use crate::mutators::map::{AndMapMutator, MapMutator};
 use crate::mutators::recursive::{RecurToMutator, RecursiveMutator};
 use crate::mutators::tuples::Tuple1Mutator;
 use crate::mutators::vector::VecMutator;
@@ -230,6 +232,12 @@ impl Mutator<AST> for ASTMutator {
     fn visit_subvalues<'a>(&self, value: &'a AST, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
         self.inner.visit_subvalues(value, &cache.inner, visit);
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.inner.mutation_operators()
+    }
 }
 
 #[coverage(off)]
@@ -237,6 +245,71 @@ pub fn grammar_based_ast_mutator(grammar: Rc<Grammar>) -> ASTMutator {
     ASTMutator::from_grammar(grammar)
 }
 
+/// A `Mutator<String>` created by [`grammar_based_string_mutator`](crate::mutators::grammar::grammar_based_string_mutator)
+pub type GrammarBasedStringMutator = MapMutator<
+    AST,
+    String,
+    ASTMutator,
+    Box<dyn Fn(&String) -> Option<AST>>,
+    fn(&AST) -> String,
+    fn(&String, f64) -> f64,
+>;
+
+/// Create a mutator which only generates strings matching the given grammar.
+///
+/// Unlike [`.with_string()`](ASTMutator::with_string), the syntax tree of the string is hidden, which
+/// makes the mutator usable for a `String` field of a type deriving [`DefaultMutator`](crate::DefaultMutator),
+/// through the `#[field_mutator(grammar = ..)]` attribute.
+///
+/// The strings given to the mutator, such as those from the input corpus, are parsed according to
+/// the grammar. Those which do not match it are rejected.
+#[coverage(off)]
+pub fn grammar_based_string_mutator(grammar: Rc<Grammar>) -> GrammarBasedStringMutator {
+    #[coverage(off)]
+    fn map(ast: &AST) -> String {
+        ast.to_string()
+    }
+    let mutator = ASTMutator::from_grammar(grammar.clone());
+    let parse: Box<dyn Fn(&String) -> Option<AST>> = Box::new(
+        #[coverage(off)]
+        move |string| parse_ast(&grammar, string),
+    );
+    MapMutator::new(mutator, parse, map, crate::mutators::keep_orig_cplx)
+}
+
+/// Create a mutator which generates the values built by the [semantic actions](crate::mutators::grammar::action)
+/// of the given grammar from the syntax trees matching it.
+///
+/// The outermost semantic action of the grammar must build a value of type `T`. It is rebuilt from the syntax
+/// tree after each mutation, such that the test function can use it directly instead of parsing the string.
+/// The syntax tree is kept in the cache of the mutator, alongside the cache of the [`ASTMutator`].
+///
+/// A value is valid if its [`Display`](std::fmt::Display) implementation writes a string matching the grammar
+/// from which the semantic actions build the same value. This is how the values read from the corpus are
+/// turned back into syntax trees.
+///
+/// ## Panics
+/// The mutator panics if the grammar does not build a single value of type `T`.
+#[coverage(off)]
+pub fn grammar_based_typed_mutator<T>(grammar: Rc<Grammar>) -> impl Mutator<T>
+where
+    T: Clone + PartialEq + std::fmt::Display + 'static,
+{
+    let mutator = ASTMutator::from_grammar(grammar.clone());
+    let parse_grammar = grammar.clone();
+    MapMutator::new(
+        mutator,
+        #[coverage(off)]
+        move |value: &T| {
+            let ast = parse_ast(&parse_grammar, &value.to_string())?;
+            (evaluate_actions::<T>(&parse_grammar, &ast) == *value).then_some(ast)
+        },
+        #[coverage(off)]
+        move |ast: &AST| evaluate_actions(&grammar, ast),
+        crate::mutators::keep_orig_cplx,
+    )
+}
+
 impl ASTMutator {
     #[coverage(off)]
     fn token(m: CharacterMutator) -> Self {
@@ -303,6 +376,14 @@ impl ASTMutator {
                     .collect(),
                 0.0,
             )),
+            Grammar::Concatenation(gs) if gs.is_empty() => {
+                // a fixed-length vector mutator needs at least one element, but the empty sequence can
+                // also be generated by an empty repetition
+                Self::repetition(VecMutator::new_without_inherent_complexity(
+                    Self::token(CharacterMutator::new(vec!['\0'..='\0'])),
+                    0..=0,
+                ))
+            }
             Grammar::Concatenation(gs) => {
                 let mut ms = Vec::<ASTMutator>::new();
                 for g in gs {
@@ -330,6 +411,7 @@ impl ASTMutator {
                     Self::from_grammar_rec(g.clone(), others)
                 },
             ),
+            Grammar::Action(g, _) => Self::from_grammar_rec(g.clone(), others),
         }
     }
 }
@@ -0,0 +1,197 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::mutators::grammar::ast::AST;
+use crate::mutators::grammar::grammar::Grammar;
+
+/// Parse the given string into a syntax tree that can be handled by the [`ASTMutator`](crate::mutators::grammar::ASTMutator)
+/// of the grammar. Returns `None` if the string does not match the grammar.
+///
+/// The shape of the returned tree follows the one of the values generated by the mutator:
+/// * a literal is a token
+/// * a concatenation or a repetition is a sequence of its parts
+/// * an alternation is the tree of the matching alternative
+/// * a recursion point is a sequence made of a single tree
+///
+/// The parser backtracks until it finds a syntax tree matching the whole string. The number of nested
+/// recursion points is bounded by the length of the string, which ensures that left-recursive grammars
+/// do not cause infinite loops.
+///
+/// Ambiguous grammars, such as `(a|a)*b`, can require an exponential number of backtracking steps to reject
+/// a string. The parser therefore gives up, and returns `None`, after [`MAX_PARSE_STEPS`] steps.
+#[coverage(off)]
+pub(crate) fn parse_ast(grammar: &Grammar, string: &str) -> Option<AST> {
+    let input = Input {
+        chars: string.chars().collect(),
+        remaining_steps: Cell::new(MAX_PARSE_STEPS),
+    };
+    let chars = &input.chars;
+    let mut result = None;
+    let max_depth = chars.len() + 1;
+    parse_rec(
+        grammar,
+        &input,
+        0,
+        max_depth,
+        &mut #[coverage(off)]
+        |ast, pos| {
+            if pos == chars.len() {
+                result = Some(ast);
+                true
+            } else {
+                false
+            }
+        },
+    );
+    result
+}
+
+/// The maximum number of grammar rules that [`parse_ast`] tries to match before rejecting the string
+const MAX_PARSE_STEPS: usize = 1_000_000;
+
+/// The string being parsed, and the number of steps the parser can still take before giving up
+struct Input {
+    chars: Vec<char>,
+    remaining_steps: Cell<usize>,
+}
+
+/// Try to match `grammar` starting at `pos`, and call `k` with each possible syntax tree and the position
+/// following it, until `k` returns `true`.
+#[coverage(off)]
+fn parse_rec(
+    grammar: &Grammar,
+    input: &Input,
+    pos: usize,
+    depth: usize,
+    k: &mut dyn FnMut(AST, usize) -> bool,
+) -> bool {
+    let remaining_steps = input.remaining_steps.get();
+    if remaining_steps == 0 {
+        return false;
+    }
+    input.remaining_steps.set(remaining_steps - 1);
+    match grammar {
+        Grammar::Literal(ranges) => {
+            if let Some(&c) = input.chars.get(pos)
+                && ranges.iter().any(
+                    #[coverage(off)]
+                    |range| range.contains(&c),
+                )
+            {
+                k(AST::Token(c), pos + 1)
+            } else {
+                false
+            }
+        }
+        Grammar::Alternation(gs) => gs.iter().any(
+            #[coverage(off)]
+            |g| parse_rec(g, input, pos, depth, k),
+        ),
+        Grammar::Concatenation(gs) => parse_concatenation(gs, input, pos, depth, Vec::with_capacity(gs.len()), k),
+        Grammar::Repetition(g, range) => parse_repetition(g, range.start, range.end, input, pos, depth, Vec::new(), k),
+        Grammar::Recurse(g) => {
+            if depth == 0 {
+                return false;
+            }
+            let g = g.upgrade().expect("the recursive grammar rule was dropped");
+            parse_rec(
+                &g,
+                input,
+                pos,
+                depth - 1,
+                &mut #[coverage(off)]
+                |ast, pos| k(AST::Sequence(vec![ast]), pos),
+            )
+        }
+        Grammar::Recursive(g) | Grammar::Action(g, _) => parse_rec(g, input, pos, depth, k),
+    }
+}
+
+#[coverage(off)]
+fn parse_concatenation(
+    gs: &[Rc<Grammar>],
+    input: &Input,
+    pos: usize,
+    depth: usize,
+    asts: Vec<AST>,
+    k: &mut dyn FnMut(AST, usize) -> bool,
+) -> bool {
+    if let Some((first, rest)) = gs.split_first() {
+        parse_rec(
+            first,
+            input,
+            pos,
+            depth,
+            &mut #[coverage(off)]
+            |ast, pos| {
+                let mut asts = asts.clone();
+                asts.push(ast);
+                parse_concatenation(rest, input, pos, depth, asts, k)
+            },
+        )
+    } else {
+        k(AST::Sequence(asts), pos)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[coverage(off)]
+fn parse_repetition(
+    g: &Grammar,
+    min: usize,
+    max: usize,
+    input: &Input,
+    pos: usize,
+    depth: usize,
+    asts: Vec<AST>,
+    k: &mut dyn FnMut(AST, usize) -> bool,
+) -> bool {
+    // `max` is exclusive, like the range of `Grammar::Repetition`
+    if asts.len() + 1 < max {
+        let found = parse_rec(
+            g,
+            input,
+            pos,
+            depth,
+            &mut #[coverage(off)]
+            |ast, next_pos| {
+                // an element matching the empty string cannot help matching more characters
+                if next_pos == pos && asts.len() >= min {
+                    return false;
+                }
+                let mut asts = asts.clone();
+                asts.push(ast);
+                parse_repetition(g, min, max, input, next_pos, depth, asts, k)
+            },
+        );
+        if found {
+            return true;
+        }
+    }
+    asts.len() >= min && k(AST::Sequence(asts), pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::mutators::grammar::{alternation, concatenation, literal, repetition};
+
+    #[test]
+    #[coverage(off)]
+    fn test_ambiguous_repetition() {
+        // every `a` can be matched by both alternatives
+        let grammar = concatenation([
+            repetition(alternation([literal('a'), literal('a')]), 0..usize::MAX),
+            literal('b'),
+        ]);
+        let matching = format!("{}b", "a".repeat(50));
+        assert!(parse_ast(&grammar, &matching).is_some());
+
+        let start = Instant::now();
+        let not_matching = "a".repeat(50);
+        assert!(parse_ast(&grammar, &not_matching).is_none());
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -1,39 +1,122 @@
+use std::error::Error;
+use std::fmt::Display;
 use std::rc::Rc;
 
-use regex_syntax::hir::{Class, HirKind, Literal, RepetitionKind, RepetitionRange};
+use regex_syntax::hir::{Anchor, Class, Hir, HirKind, Literal, RepetitionKind, RepetitionRange, WordBoundary};
 
 use crate::mutators::grammar::{alternation, concatenation, literal, literal_ranges, repetition, Grammar};
 
+/// The reason why a regular expression could not be converted to a [`Grammar`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexGrammarError {
+    /// The regular expression is not valid, the error message of the parser is given
+    Syntax(String),
+    /// The regular expression contains a construct which cannot be expressed by a grammar
+    Unsupported(&'static str),
+}
+
+impl Display for RegexGrammarError {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegexGrammarError::Syntax(message) => write!(f, "invalid regular expression: {}", message),
+            RegexGrammarError::Unsupported(construct) => write!(
+                f,
+                "the regular expression cannot be converted to a grammar because it contains {}",
+                construct
+            ),
+        }
+    }
+}
+
+impl Error for RegexGrammarError {}
+
 #[coverage(off)]
-pub(crate) fn grammar_from_regex(regex: &str) -> Rc<Grammar> {
+pub(crate) fn grammar_from_regex(regex: &str) -> Result<Rc<Grammar>, RegexGrammarError> {
     let mut parser = regex_syntax::Parser::new();
-    let hir = parser.parse(regex).unwrap();
-    grammar_from_regex_hir_kind(hir.kind())
+    let hir = parser.parse(regex).map_err(
+        #[coverage(off)]
+        |e| RegexGrammarError::Syntax(e.to_string()),
+    )?;
+    grammar_from_regex_hir(&hir, true, true)
 }
+
+/// Convert the regular expression to a grammar.
+///
+/// `at_start` and `at_end` tell whether the regular expression is necessarily at the start or the end of the
+/// whole string. Anchors are only accepted there, where they match the empty string.
 #[coverage(off)]
-pub fn grammar_from_regex_hir_kind(hir: &HirKind) -> Rc<Grammar> {
-    match hir {
-        HirKind::Empty => panic!("empty regexes are not supported"),
+fn grammar_from_regex_hir(hir: &Hir, at_start: bool, at_end: bool) -> Result<Rc<Grammar>, RegexGrammarError> {
+    let grammar = match hir.kind() {
+        HirKind::Empty => concatenation([]),
         HirKind::Literal(l) => match l {
             Literal::Unicode(l) => literal(*l),
-            Literal::Byte(_) => panic!("non-unicode regexes are not supported"),
+            // the parser only accepts byte literals which are valid UTF-8, that is, ASCII
+            Literal::Byte(b) if b.is_ascii() => literal(*b as char),
+            Literal::Byte(_) => return Err(RegexGrammarError::Unsupported("a non-ASCII byte literal")),
         },
-        HirKind::Class(class) => match class {
-            Class::Unicode(class) => {
-                let ranges = class
+        HirKind::Class(class) => {
+            let ranges = match class {
+                Class::Unicode(class) => class
                     .ranges()
                     .iter()
                     .map(
                         #[coverage(off)]
                         |r| r.start()..=r.end(),
                     )
-                    .collect::<Vec<_>>();
-                literal_ranges(ranges)
+                    .collect::<Vec<_>>(),
+                Class::Bytes(class) => {
+                    if !class.is_all_ascii() {
+                        return Err(RegexGrammarError::Unsupported("a byte class with non-ASCII bytes"));
+                    }
+                    class
+                        .ranges()
+                        .iter()
+                        .map(
+                            #[coverage(off)]
+                            |r| (r.start() as char)..=(r.end() as char),
+                        )
+                        .collect::<Vec<_>>()
+                }
+            };
+            if ranges.is_empty() {
+                return Err(RegexGrammarError::Unsupported(
+                    "a character class that matches no character",
+                ));
             }
-            Class::Bytes(_) => panic!("non-unicode regexes are not supported"),
-        },
-        HirKind::Anchor(_) => panic!("anchors are not supported"),
-        HirKind::WordBoundary(_) => panic!("word boundaries are not supported"),
+            literal_ranges(ranges)
+        }
+        HirKind::Anchor(anchor) => {
+            let (is_start, name) = match anchor {
+                Anchor::StartLine => (
+                    true,
+                    "a start of line anchor (`(?m)^`) which is not at the start of the string",
+                ),
+                Anchor::EndLine => (
+                    false,
+                    "an end of line anchor (`(?m)$`) which is not at the end of the string",
+                ),
+                Anchor::StartText => (
+                    true,
+                    "a start of text anchor (`^` or `\\A`) which is not at the start of the string",
+                ),
+                Anchor::EndText => (
+                    false,
+                    "an end of text anchor (`$` or `\\z`) which is not at the end of the string",
+                ),
+            };
+            if (is_start && at_start) || (!is_start && at_end) {
+                concatenation([])
+            } else {
+                return Err(RegexGrammarError::Unsupported(name));
+            }
+        }
+        HirKind::WordBoundary(boundary) => {
+            return Err(RegexGrammarError::Unsupported(match boundary {
+                WordBoundary::Unicode | WordBoundary::Ascii => "a word boundary (`\\b`)",
+                WordBoundary::UnicodeNegate | WordBoundary::AsciiNegate => "a negated word boundary (`\\B`)",
+            }));
+        }
         HirKind::Repetition(rep) => {
             let range = match rep.kind.clone() {
                 RepetitionKind::ZeroOrOne => 0..=1u32,
@@ -46,17 +129,87 @@ pub fn grammar_from_regex_hir_kind(hir: &HirKind) -> Rc<Grammar> {
                 },
             };
             let range = (*range.start() as usize)..=(*range.end() as usize);
-            let grammar = grammar_from_regex_hir_kind(rep.hir.kind());
+            // only a single repetition can be at the start or the end of the string
+            let single = *range.end() <= 1;
+            let grammar = grammar_from_regex_hir(&rep.hir, at_start && single, at_end && single)?;
             repetition(grammar, range)
         }
-        HirKind::Group(group) => grammar_from_regex_hir_kind(group.hir.kind()),
-        HirKind::Concat(concat) => concatenation(concat.iter().map(
-            #[coverage(off)]
-            |hir| grammar_from_regex_hir_kind(hir.kind()),
-        )),
-        HirKind::Alternation(alt) => alternation(alt.iter().map(
-            #[coverage(off)]
-            |hir| grammar_from_regex_hir_kind(hir.kind()),
-        )),
+        HirKind::Group(group) => grammar_from_regex_hir(&group.hir, at_start, at_end)?,
+        HirKind::Concat(concat) => {
+            let last = concat.len().saturating_sub(1);
+            concatenation(
+                concat
+                    .iter()
+                    .enumerate()
+                    .map(
+                        #[coverage(off)]
+                        |(i, hir)| grammar_from_regex_hir(hir, at_start && i == 0, at_end && i == last),
+                    )
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        }
+        HirKind::Alternation(alt) => alternation(
+            alt.iter()
+                .map(
+                    #[coverage(off)]
+                    |hir| grammar_from_regex_hir(hir, at_start, at_end),
+                )
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+    };
+    Ok(grammar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutators::grammar::grammar_based_ast_mutator;
+    use crate::mutators::grammar::parser::parse_ast;
+    use crate::Mutator;
+
+    #[coverage(off)]
+    fn accepts(regex: &str, string: &str) -> bool {
+        let grammar = grammar_from_regex(regex).unwrap();
+        parse_ast(&grammar, string).is_some()
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_supported_constructs() {
+        assert!(accepts("^ab+$", "abbb"));
+        assert!(accepts(r"\Aab\z", "ab"));
+        assert!(accepts("(?m)^a|b$", "b"));
+        assert!(accepts("a|", ""));
+        assert!(accepts("a{2,1000}", "aaa"));
+        assert!(!accepts("a{2,1000}", "a"));
+        assert!(accepts("[[:alpha:]][[:digit:]]", "x1"));
+        assert!(!accepts("[[:alpha:]]", "1"));
+        assert!(accepts(r"(?-u:\w)+", "a_9"));
+        assert!(accepts("(^a)?b", "ab"));
+
+        let mutator = grammar_based_ast_mutator(grammar_from_regex("^(a|)$").unwrap());
+        mutator.initialize();
+        for _ in 0..10 {
+            let (ast, _) = mutator.random_arbitrary(10.0);
+            assert!(ast.to_string().len() <= 1);
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_unsupported_constructs() {
+        assert_eq!(
+            grammar_from_regex(r"\bab").unwrap_err(),
+            RegexGrammarError::Unsupported("a word boundary (`\\b`)")
+        );
+        assert!(matches!(
+            grammar_from_regex("a^b"),
+            Err(RegexGrammarError::Unsupported(_))
+        ));
+        assert!(matches!(
+            grammar_from_regex("(a$)*"),
+            Err(RegexGrammarError::Unsupported(_))
+        ));
+        assert!(matches!(grammar_from_regex("(a"), Err(RegexGrammarError::Syntax(_))));
     }
 }
@@ -1,5 +1,6 @@
 use std::any::Any;
 
+use crate::mutators::operators::{choose_operator, record_operator_use, register_operator, OperatorId};
 use crate::{DefaultMutator, Mutator};
 /*
     These mutators try to achieve multiple things:
@@ -69,15 +70,22 @@ binary_search_arbitrary!(binary_search_arbitrary_u8, u8);
 binary_search_arbitrary!(binary_search_arbitrary_u16, u16);
 binary_search_arbitrary!(binary_search_arbitrary_u32, u32);
 binary_search_arbitrary!(binary_search_arbitrary_u64, u64);
+binary_search_arbitrary!(binary_search_arbitrary_u128, u128);
 
 const INITIAL_MUTATION_STEP: u64 = 0;
 
+/// The operators of the random mutations of the integer mutators: replacing the integer with a random
+/// one, or nudging it by a small amount
+const OPERATOR_NAMES: [&str; 2] = ["integer::Random", "integer::Nudge"];
+const OPERATOR_WEIGHTS: [f64; 2] = [3.0, 1.0];
+
 macro_rules! impl_int_mutator {
     ($name:ident, $name_unsigned: ident, $name_mutator:ident) => {
         #[derive(Clone)]
         pub struct $name_mutator {
             shuffled_integers: [u8; 256],
             rng: fastrand::Rng,
+            operators: [OperatorId; 2],
         }
         impl Default for $name_mutator {
             #[coverage(off)]
@@ -91,6 +99,7 @@ macro_rules! impl_int_mutator {
                 $name_mutator {
                     shuffled_integers,
                     rng,
+                    operators: OPERATOR_NAMES.map(register_operator),
                 }
             }
         }
@@ -140,7 +149,10 @@ macro_rules! impl_int_mutator {
                     // and then we XOR it with previous integer picked from shuffled_integers[step_i]
                     // to get the next index into shuffled_integers, which we insert into
                     // the generated integer at the right place
-                    let step_i = (((step >> (i * GRANULARITY)) ^ prev as u64) & STEP_MASK) as usize;
+                    // the step only has 64 bits, so the indices of the lowest bits of 128-bit integers
+                    // only depend on the previous picks
+                    let shifted_step = step.checked_shr((i * GRANULARITY) as u32).unwrap_or(0);
+                    let step_i = ((shifted_step ^ prev as u64) & STEP_MASK) as usize;
                     prev = unsafe { *self.shuffled_integers.get_unchecked(step_i) as $name_unsigned };
                     result |= prev << (size - (i + 1) * GRANULARITY);
                 }
@@ -270,7 +282,19 @@ macro_rules! impl_int_mutator {
                 _cache: &mut Self::Cache,
                 _max_cplx: f64,
             ) -> (Self::UnmutateToken, f64) {
-                (std::mem::replace(value, self.rng.$name(..)), <$name>::BITS as f64)
+                let operator = choose_operator(&self.rng, &self.operators, &OPERATOR_WEIGHTS).unwrap_or(0);
+                record_operator_use(self.operators[operator]);
+                let new_value = if operator == 0 {
+                    self.rng.$name(..)
+                } else {
+                    let nudge = self.rng.u8(1..=4) as $name;
+                    if self.rng.bool() {
+                        value.wrapping_add(nudge)
+                    } else {
+                        value.wrapping_sub(nudge)
+                    }
+                };
+                (std::mem::replace(value, new_value), <$name>::BITS as f64)
             }
             #[doc(hidden)]
             #[coverage(off)]
@@ -287,6 +311,12 @@ macro_rules! impl_int_mutator {
                 _visit: &mut dyn FnMut(&'a dyn Any, f64),
             ) {
             }
+
+            #[doc(hidden)]
+            #[coverage(off)]
+            fn mutation_operators(&self) -> Vec<&'static str> {
+                OPERATOR_NAMES.to_vec()
+            }
         }
 
         impl DefaultMutator for $name {
@@ -303,9 +333,11 @@ impl_int_mutator!(u8, u8, U8Mutator);
 impl_int_mutator!(u16, u16, U16Mutator);
 impl_int_mutator!(u32, u32, U32Mutator);
 impl_int_mutator!(u64, u64, U64Mutator);
+impl_int_mutator!(u128, u128, U128Mutator);
 impl_int_mutator!(usize, usize, USizeMutator);
 impl_int_mutator!(i8, u8, I8Mutator);
 impl_int_mutator!(i16, u16, I16Mutator);
 impl_int_mutator!(i32, u32, I32Mutator);
 impl_int_mutator!(i64, u64, I64Mutator);
+impl_int_mutator!(i128, u128, I128Mutator);
 impl_int_mutator!(isize, isize, ISizeMutator);
@@ -0,0 +1,349 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+use crate::Mutator;
+
+/// The constants added to the dictionary of every [`IntegerDictionaryMutator`] when it is initialised
+static GLOBAL_DICTIONARY: Mutex<Vec<i128>> = Mutex::new(Vec::new());
+
+/// Register constants that the integer dictionary mutators should try, in addition to their own dictionary.
+///
+/// The constants are added to the dictionary of each [`IntegerDictionaryMutator`] when its `initialize`
+/// method is called, which the fuzzer does before fuzzing starts. Therefore, they should be registered
+/// before the fuzz test is launched. The constants that cannot be represented by the type of the
+/// mutated integers are ignored.
+#[coverage(off)]
+pub fn register_interesting_integers(values: impl IntoIterator<Item = i128>) {
+    GLOBAL_DICTIONARY.lock().unwrap().extend(values);
+}
+
+/// An integer type whose values can be taken from the dictionary of an [`IntegerDictionaryMutator`]
+pub trait DictionaryInteger: Copy + PartialEq + TryFrom<i128> + 'static {
+    /// The value plus one, wrapping around at the boundary of the type
+    fn wrapping_succ(self) -> Self;
+    /// The value minus one, wrapping around at the boundary of the type
+    fn wrapping_pred(self) -> Self;
+}
+
+macro_rules! impl_dictionary_integer {
+    ($($name:ty),*) => {
+        $(
+            impl DictionaryInteger for $name {
+                #[coverage(off)]
+                fn wrapping_succ(self) -> Self {
+                    self.wrapping_add(1)
+                }
+                #[coverage(off)]
+                fn wrapping_pred(self) -> Self {
+                    self.wrapping_sub(1)
+                }
+            }
+        )*
+    };
+}
+impl_dictionary_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// The probability that [`random_mutate`](crate::Mutator::random_mutate) uses the dictionary, as `1 / DICTIONARY_RATE`
+const DICTIONARY_RATE: usize = 8;
+
+/// An [`IntegerDictionaryMutator`] wraps an integer mutator and also sets the integer to “interesting” constants.
+///
+/// For each constant `c` of its dictionary, the mutator tries the values `c`, `c + 1`, and `c - 1`, which is
+/// useful to pass comparisons such as `x == c` or `x > c` that are unlikely to be passed by random values.
+/// All the values of the dictionary are tried first by [`ordered_mutate`](crate::Mutator::ordered_mutate),
+/// after which the mutations of the wrapped mutator are used. The values rejected by the wrapped mutator,
+/// for example because they are not within its range, are skipped.
+///
+/// The dictionary consists of the constants given to [`IntegerDictionaryMutator::new`] and of those
+/// registered with [`register_interesting_integers`].
+///
+/// ```
+/// # #![feature(coverage_attribute)]
+/// use fuzzcheck::mutators::integer::U32Mutator;
+/// use fuzzcheck::mutators::integer_dictionary::IntegerDictionaryMutator;
+///
+/// // 0x7f454c46 is the magic number of ELF files
+/// let mutator = IntegerDictionaryMutator::new(U32Mutator::default(), [0x7f454c46, 1024]);
+/// ```
+pub struct IntegerDictionaryMutator<T, M> {
+    mutator: M,
+    /// The values derived from the dictionary, in the order in which they are tried
+    candidates: RefCell<Vec<T>>,
+    rng: fastrand::Rng,
+}
+
+impl<T, M> IntegerDictionaryMutator<T, M>
+where
+    T: DictionaryInteger,
+    M: Mutator<T>,
+{
+    /// Creates a new [`IntegerDictionaryMutator`] with the given dictionary.
+    #[coverage(off)]
+    pub fn new(mutator: M, dictionary: impl IntoIterator<Item = T>) -> Self {
+        let x = Self {
+            mutator,
+            candidates: RefCell::new(vec![]),
+            rng: fastrand::Rng::new(),
+        };
+        x.extend_dictionary(dictionary);
+        x
+    }
+
+    /// Add the values `c`, `c + 1`, and `c - 1` of each constant `c` to the candidates, unless they are already there
+    #[coverage(off)]
+    fn extend_dictionary(&self, dictionary: impl IntoIterator<Item = T>) {
+        let mut candidates = self.candidates.borrow_mut();
+        for constant in dictionary {
+            for candidate in [constant, constant.wrapping_succ(), constant.wrapping_pred()] {
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+    }
+
+    /// Replace the value with the given candidate, if it is different, valid, and not too complex
+    #[coverage(off)]
+    fn replace_with_candidate(
+        &self,
+        value: &mut T,
+        cache: &mut M::Cache,
+        candidate: T,
+        max_cplx: f64,
+    ) -> Option<(IntegerDictionaryUnmutateToken<T, M::Cache, M::UnmutateToken>, f64)> {
+        if candidate == *value {
+            return None;
+        }
+        let candidate_cache = self.mutator.validate_value(&candidate)?;
+        let cplx = self.mutator.complexity(&candidate, &candidate_cache);
+        if cplx > max_cplx {
+            return None;
+        }
+        let value = std::mem::replace(value, candidate);
+        let cache = std::mem::replace(cache, candidate_cache);
+        Some((IntegerDictionaryUnmutateToken::Replace { value, cache }, cplx))
+    }
+}
+
+#[derive(Clone)]
+pub struct IntegerDictionaryMutationStep<S> {
+    inner: S,
+    /// The index of the next candidate of the dictionary to try
+    candidate_idx: usize,
+}
+
+pub enum IntegerDictionaryUnmutateToken<T, C, U> {
+    Inner(U),
+    Replace { value: T, cache: C },
+}
+
+impl<T, M> Mutator<T> for IntegerDictionaryMutator<T, M>
+where
+    T: DictionaryInteger,
+    M: Mutator<T>,
+{
+    #[doc(hidden)]
+    type Cache = M::Cache;
+    #[doc(hidden)]
+    type MutationStep = IntegerDictionaryMutationStep<M::MutationStep>;
+    #[doc(hidden)]
+    type ArbitraryStep = M::ArbitraryStep;
+    #[doc(hidden)]
+    type UnmutateToken = IntegerDictionaryUnmutateToken<T, M::Cache, M::UnmutateToken>;
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn initialize(&self) {
+        self.mutator.initialize();
+        let global_dictionary = GLOBAL_DICTIONARY.lock().unwrap().clone();
+        self.extend_dictionary(global_dictionary.into_iter().filter_map(
+            #[coverage(off)]
+            |constant| T::try_from(constant).ok(),
+        ));
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
+        self.mutator.default_arbitrary_step()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn is_valid(&self, value: &T) -> bool {
+        self.mutator.is_valid(value)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn validate_value(&self, value: &T) -> Option<Self::Cache> {
+        self.mutator.validate_value(value)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_mutation_step(&self, value: &T, cache: &Self::Cache) -> Self::MutationStep {
+        IntegerDictionaryMutationStep {
+            inner: self.mutator.default_mutation_step(value, cache),
+            candidate_idx: 0,
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.mutator.global_search_space_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn max_complexity(&self) -> f64 {
+        self.mutator.max_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn min_complexity(&self) -> f64 {
+        self.mutator.min_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn complexity(&self, value: &T, cache: &Self::Cache) -> f64 {
+        self.mutator.complexity(value, cache)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(T, f64)> {
+        self.mutator.ordered_arbitrary(step, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_arbitrary(&self, max_cplx: f64) -> (T, f64) {
+        self.mutator.random_arbitrary(max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_mutate(
+        &self,
+        value: &mut T,
+        cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        subvalue_provider: &dyn crate::SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)> {
+        let candidates = self.candidates.borrow();
+        while let Some(&candidate) = candidates.get(step.candidate_idx) {
+            step.candidate_idx += 1;
+            if let Some(x) = self.replace_with_candidate(value, cache, candidate, max_cplx) {
+                return Some(x);
+            }
+        }
+        self.mutator
+            .ordered_mutate(value, cache, &mut step.inner, subvalue_provider, max_cplx)
+            .map(
+                #[coverage(off)]
+                |(t, cplx)| (IntegerDictionaryUnmutateToken::Inner(t), cplx),
+            )
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_mutate(&self, value: &mut T, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        let candidates = self.candidates.borrow();
+        if !candidates.is_empty() && self.rng.usize(..DICTIONARY_RATE) == 0 {
+            let candidate = candidates[self.rng.usize(..candidates.len())];
+            if let Some(x) = self.replace_with_candidate(value, cache, candidate, max_cplx) {
+                return x;
+            }
+        }
+        let (t, cplx) = self.mutator.random_mutate(value, cache, max_cplx);
+        (IntegerDictionaryUnmutateToken::Inner(t), cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn unmutate(&self, value: &mut T, cache: &mut Self::Cache, t: Self::UnmutateToken) {
+        match t {
+            IntegerDictionaryUnmutateToken::Inner(t) => self.mutator.unmutate(value, cache, t),
+            IntegerDictionaryUnmutateToken::Replace {
+                value: old_value,
+                cache: old_cache,
+            } => {
+                *value = old_value;
+                *cache = old_cache;
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn visit_subvalues<'a>(&self, value: &'a T, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
+        self.mutator.visit_subvalues(value, cache, visit)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::let_unit_value)]
+mod tests {
+    use super::*;
+    use crate::mutators::integer::U32Mutator;
+    use crate::mutators::integer_within_range::U8WithinRangeMutator;
+    use crate::mutators::testing_utilities::test_mutator;
+    use crate::subvalue_provider::EmptySubValueProvider;
+
+    #[test]
+    #[coverage(off)]
+    fn test_ordered_mutate_tries_dictionary_first() {
+        let mutator = IntegerDictionaryMutator::new(U32Mutator::default(), [100, 0]);
+        mutator.initialize();
+        let mut value = 101;
+        let mut cache = mutator.validate_value(&value).unwrap();
+        let mut step = mutator.default_mutation_step(&value, &cache);
+        let mut mutated = vec![];
+        for _ in 0..5 {
+            let (token, _) = mutator
+                .ordered_mutate(&mut value, &mut cache, &mut step, &EmptySubValueProvider, 100.0)
+                .unwrap();
+            mutated.push(value);
+            mutator.unmutate(&mut value, &mut cache, token);
+            assert_eq!(value, 101);
+        }
+        // 101 itself is skipped, and 0 - 1 wraps around
+        assert_eq!(mutated, vec![100, 99, 0, 1, u32::MAX]);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_invalid_candidates_are_skipped() {
+        register_interesting_integers([11, 1000]);
+        let mutator = IntegerDictionaryMutator::new(U8WithinRangeMutator::new(0..=10), []);
+        mutator.initialize();
+        let mut value = 0;
+        let mut cache = mutator.validate_value(&value).unwrap();
+        let mut step = mutator.default_mutation_step(&value, &cache);
+        let (token, _) = mutator
+            .ordered_mutate(&mut value, &mut cache, &mut step, &EmptySubValueProvider, 100.0)
+            .unwrap();
+        // 11 and 12 are out of range, and 1000 is not a u8
+        assert_eq!(value, 10);
+        mutator.unmutate(&mut value, &mut cache, token);
+
+        for _ in 0..1000 {
+            let (token, _) = mutator.random_mutate(&mut value, &mut cache, 100.0);
+            assert!(value <= 10);
+            mutator.unmutate(&mut value, &mut cache, token);
+        }
+        test_mutator(mutator, 100., 100., false, true, 100, 100);
+    }
+}
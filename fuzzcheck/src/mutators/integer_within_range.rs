@@ -2,11 +2,24 @@ use std::any::Any;
 use std::ops::{Bound, RangeBounds};
 
 use crate::mutators::integer::{
-    binary_search_arbitrary_u16, binary_search_arbitrary_u32, binary_search_arbitrary_u64, binary_search_arbitrary_u8,
+    binary_search_arbitrary_u128, binary_search_arbitrary_u16, binary_search_arbitrary_u32,
+    binary_search_arbitrary_u64, binary_search_arbitrary_u8,
 };
 use crate::Mutator;
 const INITIAL_MUTATION_STEP: u64 = 0;
 
+/// A type whose values can be generated within a given range by a mutator, such as
+/// [`U8WithinRangeMutator`] for `u8` or [`CharWithinRangeMutator`](crate::mutators::char::CharWithinRangeMutator)
+/// for `char`.
+///
+/// It is used by the `#[range(..)]` field attribute of `#[derive(DefaultMutator)]` to choose the
+/// mutator of the field based on its type.
+pub trait WithinRange: Clone + Sized + 'static {
+    type Mutator: Mutator<Self>;
+
+    fn within_range_mutator<RB: RangeBounds<Self>>(range: RB) -> Self::Mutator;
+}
+
 macro_rules! impl_int_mutator_constrained {
     ($name:ident,$name_unsigned:ident, $name_mutator:ident, $name_binary_arbitrary_function: ident) => {
         pub struct $name_mutator {
@@ -42,11 +55,11 @@ macro_rules! impl_int_mutator_constrained {
                         range.end_bound()
                     )
                 }
-                let length = end.wrapping_sub(start);
+                let len_range = end.wrapping_sub(start) as $name_unsigned;
                 Self {
                     start_range: start,
-                    len_range: end.wrapping_sub(start) as $name_unsigned,
-                    search_space_complexity: super::size_to_cplxity(length as usize),
+                    len_range,
+                    search_space_complexity: (<$name_unsigned>::BITS - len_range.leading_zeros()) as f64,
                     rng: fastrand::Rng::default(),
                 }
             }
@@ -122,7 +135,7 @@ macro_rules! impl_int_mutator_constrained {
                 if max_cplx < self.min_complexity() {
                     return None;
                 }
-                if *step > self.len_range as u64 {
+                if *step as u128 > self.len_range as u128 {
                     None
                 } else {
                     let result = $name_binary_arbitrary_function(0, self.len_range, *step);
@@ -156,7 +169,7 @@ macro_rules! impl_int_mutator_constrained {
                 if max_cplx < self.min_complexity() {
                     return None;
                 }
-                if *step > self.len_range as u64 {
+                if *step as u128 > self.len_range as u128 {
                     return None;
                 }
                 let token = *value;
@@ -202,6 +215,15 @@ macro_rules! impl_int_mutator_constrained {
             ) {
             }
         }
+
+        impl WithinRange for $name {
+            type Mutator = $name_mutator;
+
+            #[coverage(off)]
+            fn within_range_mutator<RB: RangeBounds<$name>>(range: RB) -> Self::Mutator {
+                $name_mutator::new(range)
+            }
+        }
     };
 }
 
@@ -209,10 +231,12 @@ impl_int_mutator_constrained!(u8, u8, U8WithinRangeMutator, binary_search_arbitr
 impl_int_mutator_constrained!(u16, u16, U16WithinRangeMutator, binary_search_arbitrary_u16);
 impl_int_mutator_constrained!(u32, u32, U32WithinRangeMutator, binary_search_arbitrary_u32);
 impl_int_mutator_constrained!(u64, u64, U64WithinRangeMutator, binary_search_arbitrary_u64);
+impl_int_mutator_constrained!(u128, u128, U128WithinRangeMutator, binary_search_arbitrary_u128);
 impl_int_mutator_constrained!(i8, u8, I8WithinRangeMutator, binary_search_arbitrary_u8);
 impl_int_mutator_constrained!(i16, u16, I16WithinRangeMutator, binary_search_arbitrary_u16);
 impl_int_mutator_constrained!(i32, u32, I32WithinRangeMutator, binary_search_arbitrary_u32);
 impl_int_mutator_constrained!(i64, u64, I64WithinRangeMutator, binary_search_arbitrary_u64);
+impl_int_mutator_constrained!(i128, u128, I128WithinRangeMutator, binary_search_arbitrary_u128);
 
 #[cfg(test)]
 mod tests {
@@ -211,6 +211,12 @@ where
         self.mutator
             .visit_subvalues(&cache.from_value, &cache.from_cache, visit)
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
 }
 
 pub struct AndMapMutator<From, To, M, Map>
@@ -387,4 +393,10 @@ where
         let (_, from_value) = value;
         self.mutator.visit_subvalues(from_value, cache, visit)
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
 }
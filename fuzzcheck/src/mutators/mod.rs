@@ -5,13 +5,14 @@ This module provides the following mutators:
 
 * mutators for basic types such as
     * `bool` ([here](crate::mutators::bool::BoolMutator))
-    * `char` ([here](crate::mutators::char::CharWithinRangeMutator) and [here](crate::mutators::character_classes::CharacterMutator))
+    * `char` ([here](crate::mutators::char::CharWithinRangeMutator), [here](crate::mutators::character_classes::CharacterMutator), and [here](crate::mutators::unicode_char::UnicodeCharMutator))
     * integers ([here](crate::mutators::integer) and [here](crate::mutators::integer_within_range))
     * `Vec` ([here](crate::mutators::vector::VecMutator) and [here](crate::mutators::fixed_len_vector::FixedLenVecMutator))
+    * arrays ([here](crate::mutators::array::ArrayMutator) and [here](crate::mutators::array::FixedLenArrayMutator))
     * `Option` ([here](crate::mutators::option::OptionMutator))
     * `Result` ([here](crate::mutators::result::ResultMutator))
     * `Box` ([here](crate::mutators::boxed))
-    * tuples of up to 10 elements ([here](crate::mutators::tuples))
+    * tuples of up to 20 elements ([here](crate::mutators::tuples))
 
 * procedural macros to generate mutators for custom types:
     * [`#[derive(DefaultMutator)]`](fuzzcheck_mutators_derive::DefaultMutator) which works on most structs and enums
@@ -28,14 +29,19 @@ This module provides the following mutators:
 /*!
 - basic blocks to build more complex mutators:
     * [`AlternationMutator<_, M>`](crate::mutators::alternation::AlternationMutator) to use multiple different mutators acting on the same test case type
+    * [`AnyMutator<B>`](crate::mutators::dynamic::AnyMutator) to mutate trait objects using the mutators of the concrete types implementing the trait
     * [`Either<M1, M2>`](crate::mutators::either::Either) is the regular `Either` type, which also implements `Mutator<T>` if both `M1` and `M2` implement it too
     * [`RecursiveMutator` and `RecurToMutator`](crate::mutators::recursive) are wrappers allowing mutators to call themselves recursively, which is necessary to mutate recursive types.
     * [`MapMutator<..>`](crate::mutators::map::MapMutator) wraps a mutator and transforms the generated value using a user-provided function.
+    * [`TryMapMutator<..>`](crate::mutators::try_map::TryMapMutator) is like `MapMutator`, but the transformation may fail and the complexity of the transformed value is computed by a user-provided function.
+    * [`ScaledComplexityMutator<M>`](crate::mutators::scaled_complexity::ScaledComplexityMutator) wraps a mutator and multiplies the complexity of the generated values by a constant factor.
+    * [`OpaqueMutator<M>`](crate::mutators::opaque::OpaqueMutator) wraps a mutator and hides the subvalues of the generated values from crossover mutations.
+    * [`IntegerDictionaryMutator<T, M>`](crate::mutators::integer_dictionary::IntegerDictionaryMutator) wraps an integer mutator and also sets the integer to constants taken from a dictionary, or to their neighbours.
 */
 
 pub const CROSSOVER_RATE: u8 = 10;
 
-use std::any::{Any, TypeId};
+use std::any::TypeId;
 use std::marker::PhantomData;
 use std::ops::Range;
 
@@ -43,6 +49,7 @@ use ahash::AHashMap;
 
 use self::filter::FilterMutator;
 use self::map::MapMutator;
+use self::try_map::TryMapMutator;
 use crate::subvalue_provider::Generation;
 use crate::{Mutator, SubValueProvider};
 
@@ -54,6 +61,7 @@ pub mod boxed;
 pub mod char;
 pub mod character_classes;
 pub mod cow;
+pub mod dynamic;
 pub mod either;
 pub mod enums;
 pub mod filter;
@@ -62,19 +70,26 @@ pub mod fixed_len_vector;
 #[doc(cfg(feature = "grammar_mutator"))]
 pub mod grammar;
 pub mod integer;
+pub mod integer_dictionary;
 pub mod integer_within_range;
 pub mod map;
 pub mod mutations;
 pub mod never;
+pub mod opaque;
+pub mod operators;
 pub mod option;
 pub mod range;
 pub mod rc;
 pub mod recursive;
 pub mod result;
+pub mod scaled_complexity;
 pub mod string;
+pub mod try_map;
 pub mod tuples;
+pub mod unicode_char;
 pub mod unique;
 pub mod unit;
+pub mod validate;
 pub mod vector;
 pub mod vose_alias;
 
@@ -156,6 +171,27 @@ where
     {
         MapMutator::new(self, parse, map, keep_orig_cplx)
     }
+    /// Create a mutator which wraps `self` and transforms the values generated by `self`
+    /// using the fallible `map` closure. The values that cannot be mapped are skipped.
+    /// The second closure, `parse`, should apply the opposite transformation, and
+    /// the complexity of the transformed values is given by `cplx`.
+    ///
+    /// See [`TryMapMutator`] for more details.
+    #[coverage(off)]
+    fn try_map<To, Map, Parse, Cplx>(
+        self,
+        map: Map,
+        parse: Parse,
+        cplx: Cplx,
+    ) -> TryMapMutator<T, To, Self, Parse, Map, Cplx>
+    where
+        To: Clone + 'static,
+        Map: Fn(&T) -> Option<To>,
+        Parse: Fn(&To) -> Option<T>,
+        Cplx: Fn(&To, f64) -> f64,
+    {
+        TryMapMutator::new(self, parse, map, cplx)
+    }
 }
 impl<T, M> MutatorExt<T> for M
 where
@@ -164,138 +200,8 @@ where
 {
 }
 
-/**
- A trait for types that are basic wrappers over a mutator, such as `Box<M>`.
-
- Such wrapper types automatically implement the [`Mutator`](Mutator) trait.
-*/
-pub trait MutatorWrapper {
-    type Wrapped;
-
-    fn wrapped_mutator(&self) -> &Self::Wrapped;
-}
-
-impl<T: Clone + 'static, W, M> Mutator<T> for M
-where
-    M: MutatorWrapper<Wrapped = W>,
-    W: Mutator<T>,
-    Self: 'static,
-{
-    #[doc(hidden)]
-    type Cache = W::Cache;
-    #[doc(hidden)]
-    type MutationStep = W::MutationStep;
-    #[doc(hidden)]
-    type ArbitraryStep = W::ArbitraryStep;
-    #[doc(hidden)]
-    type UnmutateToken = W::UnmutateToken;
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn initialize(&self) {
-        self.wrapped_mutator().initialize()
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
-        self.wrapped_mutator().default_arbitrary_step()
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn is_valid(&self, value: &T) -> bool {
-        self.wrapped_mutator().is_valid(value)
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn validate_value(&self, value: &T) -> Option<Self::Cache> {
-        self.wrapped_mutator().validate_value(value)
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn default_mutation_step(&self, value: &T, cache: &Self::Cache) -> Self::MutationStep {
-        self.wrapped_mutator().default_mutation_step(value, cache)
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn global_search_space_complexity(&self) -> f64 {
-        self.wrapped_mutator().global_search_space_complexity()
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn max_complexity(&self) -> f64 {
-        self.wrapped_mutator().max_complexity()
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn min_complexity(&self) -> f64 {
-        self.wrapped_mutator().min_complexity()
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn complexity(&self, value: &T, cache: &Self::Cache) -> f64 {
-        self.wrapped_mutator().complexity(value, cache)
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(T, f64)> {
-        self.wrapped_mutator().ordered_arbitrary(step, max_cplx)
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn random_arbitrary(&self, max_cplx: f64) -> (T, f64) {
-        self.wrapped_mutator().random_arbitrary(max_cplx)
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn ordered_mutate(
-        &self,
-        value: &mut T,
-        cache: &mut Self::Cache,
-        step: &mut Self::MutationStep,
-        subvalue_provider: &dyn crate::SubValueProvider,
-        max_cplx: f64,
-    ) -> Option<(Self::UnmutateToken, f64)> {
-        self.wrapped_mutator()
-            .ordered_mutate(value, cache, step, subvalue_provider, max_cplx)
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn random_mutate(&self, value: &mut T, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
-        self.wrapped_mutator().random_mutate(value, cache, max_cplx)
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn unmutate(&self, value: &mut T, cache: &mut Self::Cache, t: Self::UnmutateToken) {
-        self.wrapped_mutator().unmutate(value, cache, t)
-    }
-
-    #[doc(hidden)]
-    #[coverage(off)]
-    fn visit_subvalues<'a>(&self, value: &'a T, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
-        self.wrapped_mutator().visit_subvalues(value, cache, visit)
-    }
-}
-
-impl<M> MutatorWrapper for Box<M> {
-    type Wrapped = M;
-    #[coverage(off)]
-    fn wrapped_mutator(&self) -> &Self::Wrapped {
-        self.as_ref()
-    }
-}
+#[doc(inline)]
+pub use fuzzcheck_core::MutatorWrapper;
 
 pub struct Wrapper<T>(pub T);
 impl<T> MutatorWrapper for Wrapper<T> {
@@ -0,0 +1,567 @@
+//! Statistics about the mutation operators chosen by the mutators, which are used to adapt their weights
+//! during a fuzzing session.
+//!
+//! A mutator that chooses between several kinds of mutations, such as the [`VecMutator`](crate::mutators::vector::VecMutator),
+//! registers each of them as an operator with [`register_operator`] or [`register_mutator_operator`], lists their
+//! names in [`Mutator::mutation_operators`](crate::Mutator::mutation_operators), and calls [`record_operator_use`]
+//! whenever it applies one of them. After running each test case, the fuzzer reports whether it was added to the
+//! corpus, which counts as a success for every operator applied to obtain it. The mutator then scales the weights
+//! of the operators with [`adapted_weight`], or chooses one with [`choose_operator`], each time it applies the next
+//! ones, which favours the operators whose success rate is higher than average.
+//!
+//! The statistics belong to the fuzzing session running on the current thread, and start from scratch with each
+//! session. Outside of a fuzzing session, the uses of the operators are not recorded and their weights are not
+//! adapted.
+//!
+//! The adaptation can be disabled with the `--no-adaptive-mutations` flag of `cargo fuzzcheck`. The statistics
+//! of each operator are saved to the stats folder as `mutation_operators.csv`.
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use crate::CSVField;
+
+/// The name of the file saved in the stats folder, without its extension
+const OPERATORS_FILE: &str = "mutation_operators";
+
+/// How many uses of an operator it takes for its own success rate to weigh as much as the average success rate
+/// of all operators when its weight is adapted
+const PRIOR_NBR_USES: f64 = 100.0;
+/// The bounds of the factor by which the weight of an operator is multiplied
+const MIN_WEIGHT_FACTOR: f64 = 0.1;
+const MAX_WEIGHT_FACTOR: f64 = 10.0;
+
+/// Identifies a mutation operator registered with [`register_operator`] or [`register_mutator_operator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OperatorId(usize);
+
+/// The number of times a mutation operator was applied during the current fuzzing session, and how many
+/// of the resulting test cases were added to the corpus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorStats {
+    pub name: &'static str,
+    pub nbr_uses: usize,
+    pub nbr_successes: usize,
+}
+
+/// A registered operator
+struct Operator {
+    name: &'static str,
+    /// Whether the operator is shared by all the mutators registering its name
+    shared: bool,
+}
+
+/// The operators registered by all the mutators of the process, indexed by their identifier
+static OPERATORS: Mutex<Vec<Operator>> = Mutex::new(Vec::new());
+
+/// The statistics of an operator during a fuzzing session
+#[derive(Debug, Clone, Copy)]
+struct Counters {
+    nbr_uses: u64,
+    nbr_successes: u64,
+    /// The factor by which the weight of the operator is multiplied, updated after each test case
+    weight_factor: f64,
+}
+
+impl Default for Counters {
+    #[coverage(off)]
+    fn default() -> Self {
+        Self {
+            nbr_uses: 0,
+            nbr_successes: 0,
+            weight_factor: 1.0,
+        }
+    }
+}
+
+/// The statistics of the operators during a fuzzing session
+struct SessionStats {
+    adaptive: bool,
+    /// The operators listed by the mutator of the fuzz test, whose statistics are saved even if they are never used
+    listed: Vec<OperatorId>,
+    /// The statistics of each operator, indexed by its identifier, and which may be shorter than the number of
+    /// registered operators
+    counters: RefCell<Vec<Counters>>,
+    /// The operators applied to obtain the test case that is being tested
+    applied: RefCell<Vec<OperatorId>>,
+}
+
+impl SessionStats {
+    #[coverage(off)]
+    fn new(adaptive: bool, listed: Vec<OperatorId>) -> Self {
+        Self {
+            adaptive,
+            listed,
+            counters: RefCell::new(Vec::new()),
+            applied: RefCell::new(Vec::new()),
+        }
+    }
+
+    #[coverage(off)]
+    fn counters(&self, operator: OperatorId) -> Counters {
+        self.counters.borrow().get(operator.0).copied().unwrap_or_default()
+    }
+
+    #[coverage(off)]
+    fn record_use(&self, operator: OperatorId) {
+        let mut counters = self.counters.borrow_mut();
+        if counters.len() <= operator.0 {
+            counters.resize(operator.0 + 1, Counters::default());
+        }
+        counters[operator.0].nbr_uses += 1;
+        self.applied.borrow_mut().push(operator);
+    }
+
+    /// Record the success of the applied operators, and update the weight factors of all operators
+    #[coverage(off)]
+    fn report(&self, success: bool) {
+        let applied = std::mem::take(&mut *self.applied.borrow_mut());
+        let mut counters = self.counters.borrow_mut();
+        if success {
+            for operator in applied.iter() {
+                counters[operator.0].nbr_successes += 1;
+            }
+        }
+        if !applied.is_empty() && self.adaptive {
+            update_weight_factors(&mut counters);
+        }
+    }
+
+    #[coverage(off)]
+    fn stats(&self, operators: impl IntoIterator<Item = OperatorId>) -> Vec<(OperatorId, OperatorStats)> {
+        let names = OPERATORS.lock().unwrap();
+        operators
+            .into_iter()
+            .map(
+                #[coverage(off)]
+                |operator| {
+                    let counters = self.counters(operator);
+                    let stats = OperatorStats {
+                        name: names[operator.0].name,
+                        nbr_uses: counters.nbr_uses as usize,
+                        nbr_successes: counters.nbr_successes as usize,
+                    };
+                    (operator, stats)
+                },
+            )
+            .collect()
+    }
+
+    /// The operators listed by the mutator, followed by the other operators used during the session
+    #[coverage(off)]
+    fn reported_operators(&self) -> Vec<OperatorId> {
+        let mut operators = self.listed.clone();
+        for (idx, counters) in self.counters.borrow().iter().enumerate() {
+            if counters.nbr_uses > 0 && !operators.contains(&OperatorId(idx)) {
+                operators.push(OperatorId(idx));
+            }
+        }
+        operators
+    }
+}
+
+#[coverage(off)]
+fn update_weight_factors(counters: &mut [Counters]) {
+    let (mut total_uses, mut total_successes) = (0, 0);
+    for operator in counters.iter() {
+        total_uses += operator.nbr_uses;
+        total_successes += operator.nbr_successes;
+    }
+    let average_rate = (total_successes as f64 + 1.0) / (total_uses as f64 + 1.0);
+    for operator in counters.iter_mut() {
+        let rate = (operator.nbr_successes as f64 + average_rate * PRIOR_NBR_USES)
+            / (operator.nbr_uses as f64 + PRIOR_NBR_USES);
+        operator.weight_factor = (rate / average_rate).clamp(MIN_WEIGHT_FACTOR, MAX_WEIGHT_FACTOR);
+    }
+}
+
+thread_local! {
+    /// The statistics of the fuzzing session running on this thread, if any
+    static CURRENT_SESSION: RefCell<Option<Rc<SessionStats>>> = const { RefCell::new(None) };
+}
+
+/// Call `f` with the statistics of the fuzzing session running on this thread, if any
+#[coverage(off)]
+fn with_current_session<R>(f: impl FnOnce(&SessionStats) -> R) -> Option<R> {
+    let session = CURRENT_SESSION.with_borrow(Option::clone)?;
+    Some(f(&session))
+}
+
+#[coverage(off)]
+fn register(name: &'static str, shared: bool) -> OperatorId {
+    let mut operators = OPERATORS.lock().unwrap();
+    if shared
+        && let Some(idx) = operators.iter().position(
+            #[coverage(off)]
+            |operator| operator.shared && operator.name == name,
+        )
+    {
+        return OperatorId(idx);
+    }
+    operators.push(Operator { name, shared });
+    OperatorId(operators.len() - 1)
+}
+
+/// Register the operator with the given name and return its identifier.
+///
+/// Registering the same name multiple times returns the same identifier, such that all the mutators
+/// sharing an operator also share its statistics.
+#[coverage(off)]
+pub fn register_operator(name: &'static str) -> OperatorId {
+    register(name, true)
+}
+
+/// Register a new operator owned by a single mutator and return its identifier.
+///
+/// Unlike [`register_operator`], each call returns a new identifier, such that the statistics of the operator,
+/// for example one of the alternatives of an [`AlternationMutator`](crate::mutators::alternation::AlternationMutator),
+/// are not mixed with those of the operators of the same name that belong to other mutators.
+#[coverage(off)]
+pub fn register_mutator_operator(name: &'static str) -> OperatorId {
+    register(name, false)
+}
+
+/// Record that the operator was applied to obtain the next test case
+#[coverage(off)]
+pub fn record_operator_use(operator: OperatorId) {
+    with_current_session(
+        #[coverage(off)]
+        |session| session.record_use(operator),
+    );
+}
+
+/// The weight of the operator, adapted to its success rate during the current fuzzing session.
+///
+/// The given weight is multiplied by the ratio between the success rate of the operator and the average
+/// success rate of all operators, which is kept between `0.1` and `10`. The weight is not changed if the
+/// adaptation is disabled, or if the mutator is used outside of a fuzzing session.
+///
+/// The ratios are updated after each test case, so this function should be called every time an operator
+/// is chosen, instead of caching the adapted weights.
+#[coverage(off)]
+pub fn adapted_weight(operator: OperatorId, weight: f64) -> f64 {
+    let factor = with_current_session(
+        #[coverage(off)]
+        |session| session.counters(operator).weight_factor,
+    );
+    weight * factor.unwrap_or(1.0)
+}
+
+/// Choose the index of an operator, with a probability proportional to its [adapted weight](adapted_weight).
+///
+/// `operators` and `weights` must have the same length. Returns `None` if they are empty or if all weights
+/// are zero.
+#[coverage(off)]
+pub fn choose_operator(rng: &fastrand::Rng, operators: &[OperatorId], weights: &[f64]) -> Option<usize> {
+    let adapted = with_current_session(
+        #[coverage(off)]
+        |session| {
+            let counters = session.counters.borrow();
+            choose_weighted(
+                rng,
+                operators.iter().zip(weights).map(
+                    #[coverage(off)]
+                    |(operator, &weight)| {
+                        let factor = counters.get(operator.0).map_or(
+                            1.0,
+                            #[coverage(off)]
+                            |counters| counters.weight_factor,
+                        );
+                        weight * factor
+                    },
+                ),
+            )
+        },
+    );
+    match adapted {
+        Some(idx) => idx,
+        None => choose_weighted(rng, weights.iter().copied()),
+    }
+}
+
+/// Choose an index with a probability proportional to its weight, or `None` if all weights are zero
+#[coverage(off)]
+fn choose_weighted(rng: &fastrand::Rng, weights: impl Iterator<Item = f64> + Clone) -> Option<usize> {
+    let total: f64 = weights.clone().sum();
+    if total.is_nan() || total <= 0.0 {
+        return None;
+    }
+    let mut target = rng.f64() * total;
+    let mut last_positive = None;
+    for (idx, weight) in weights.enumerate() {
+        if weight > 0.0 {
+            if target < weight {
+                return Some(idx);
+            }
+            target -= weight;
+            last_positive = Some(idx);
+        }
+    }
+    // rounding errors may leave a small remainder
+    last_positive
+}
+
+/// The statistics of the operators listed by the mutator and of those used during the fuzzing session running
+/// on this thread, or an empty list outside of a fuzzing session
+#[coverage(off)]
+pub fn operator_stats() -> Vec<OperatorStats> {
+    let stats = with_current_session(
+        #[coverage(off)]
+        |session| session.stats(session.reported_operators()),
+    );
+    stats
+        .unwrap_or_default()
+        .into_iter()
+        .map(
+            #[coverage(off)]
+            |(_, stats)| stats,
+        )
+        .collect()
+}
+
+/// The statistics of the operators of a fuzzing session, which are used by the mutators while it is alive
+pub(crate) struct OperatorsSession {
+    stats: Rc<SessionStats>,
+    /// The session that was running on this thread before this one started, which is restored when it ends
+    previous: Option<Rc<SessionStats>>,
+}
+
+impl OperatorsSession {
+    /// Start recording the statistics of the operators from scratch, and register the given ones, which are listed
+    /// in the statistics even if they are never applied
+    #[coverage(off)]
+    pub(crate) fn start(adaptive: bool, names: Vec<&'static str>) -> Self {
+        let listed = {
+            let mut operators = OPERATORS.lock().unwrap();
+            let mut listed = vec![];
+            for name in names {
+                let mut found = false;
+                for (idx, operator) in operators.iter().enumerate() {
+                    if operator.name == name {
+                        found = true;
+                        listed.push(OperatorId(idx));
+                    }
+                }
+                if !found {
+                    operators.push(Operator { name, shared: true });
+                    listed.push(OperatorId(operators.len() - 1));
+                }
+            }
+            listed
+        };
+        let stats = Rc::new(SessionStats::new(adaptive, listed));
+        let previous = CURRENT_SESSION.replace(Some(stats.clone()));
+        Self { stats, previous }
+    }
+
+    /// Forget the operators applied to obtain the previous test case, which must be done before mutating a new one
+    #[coverage(off)]
+    pub(crate) fn clear_applied_operators(&self) {
+        self.stats.applied.borrow_mut().clear();
+    }
+
+    /// The names of the operators recorded since the last call to [`clear_applied_operators`](Self::clear_applied_operators),
+    /// in the order in which they were applied
+    #[coverage(off)]
+    pub(crate) fn applied_operators(&self) -> Vec<&'static str> {
+        let operators = OPERATORS.lock().unwrap();
+        self.stats
+            .applied
+            .borrow()
+            .iter()
+            .map(
+                #[coverage(off)]
+                |operator| operators[operator.0].name,
+            )
+            .collect()
+    }
+
+    /// Report whether the test case obtained with the operators recorded since the last call to
+    /// [`clear_applied_operators`](Self::clear_applied_operators) was added to the corpus
+    #[coverage(off)]
+    pub(crate) fn report_applied_operators(&self, success: bool) {
+        self.stats.report(success);
+    }
+
+    /// The statistics of the operators as a CSV file to be saved in the stats folder, if any operator was listed
+    /// or used.
+    ///
+    /// The operators owned by a single mutator may have the same name as other operators, so their identifier
+    /// is given as well.
+    #[coverage(off)]
+    pub(crate) fn files(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        let stats = self.stats.stats(self.stats.reported_operators());
+        if stats.is_empty() {
+            return vec![];
+        }
+        let headers = ["operator", "uses", "successes", "weight_factor", "id"].map(
+            #[coverage(off)]
+            |header| CSVField::String(header.to_string()),
+        );
+        let mut csv = CSVField::to_bytes(&headers);
+        for (operator, stats) in stats {
+            csv.extend(CSVField::to_bytes(&[
+                CSVField::String(stats.name.to_string()),
+                CSVField::Integer(stats.nbr_uses as isize),
+                CSVField::Integer(stats.nbr_successes as isize),
+                CSVField::Float(self.stats.counters(operator).weight_factor),
+                CSVField::Integer(operator.0 as isize),
+            ]));
+        }
+        vec![(PathBuf::new().join(OPERATORS_FILE).with_extension("csv"), csv)]
+    }
+}
+
+impl Drop for OperatorsSession {
+    #[coverage(off)]
+    fn drop(&mut self) {
+        CURRENT_SESSION.set(self.previous.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_adaptive_operators() {
+        let good = register_operator("test::good");
+        let bad = register_operator("test::bad");
+        assert_eq!(register_operator("test::good"), good);
+
+        // the weights are not adapted outside of a fuzzing session
+        record_operator_use(good);
+        assert_eq!(adapted_weight(good, 2.0), 2.0);
+        assert!(operator_stats().is_empty());
+
+        let session = OperatorsSession::start(true, vec!["test::good", "test::bad"]);
+        assert_eq!(adapted_weight(good, 2.0), 2.0);
+        for i in 0..1000 {
+            record_operator_use(good);
+            session.report_applied_operators(i % 2 == 0);
+            record_operator_use(bad);
+            session.report_applied_operators(i % 100 == 0);
+        }
+        assert_eq!(
+            operator_stats()[0],
+            OperatorStats {
+                name: "test::good",
+                nbr_uses: 1000,
+                nbr_successes: 500
+            }
+        );
+        assert!(adapted_weight(good, 1.0) > 1.5);
+        assert!(adapted_weight(bad, 1.0) < 0.5);
+
+        // all the operators applied to obtain a test case share its success
+        record_operator_use(good);
+        record_operator_use(bad);
+        assert_eq!(session.applied_operators(), ["test::good", "test::bad"]);
+        session.report_applied_operators(true);
+        assert_eq!(operator_stats()[0].nbr_successes, 501);
+        assert_eq!(operator_stats()[1].nbr_successes, 11);
+
+        // a new session starts from scratch, and the previous one is restored when it ends
+        let nested_session = OperatorsSession::start(false, vec![]);
+        assert!(operator_stats().is_empty());
+        assert_eq!(adapted_weight(bad, 1.0), 1.0);
+        drop(nested_session);
+        assert_eq!(operator_stats()[1].nbr_uses, 1001);
+        drop(session);
+        assert!(operator_stats().is_empty());
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_mutator_operators() {
+        let first = register_mutator_operator("test::alternative");
+        let second = register_mutator_operator("test::alternative");
+        assert_ne!(first, second);
+        assert_ne!(register_operator("test::alternative"), first);
+
+        // the sessions of different threads do not share their statistics
+        let session = OperatorsSession::start(true, vec![]);
+        record_operator_use(first);
+        std::thread::spawn(
+            #[coverage(off)]
+            move || {
+                let session = OperatorsSession::start(true, vec![]);
+                record_operator_use(second);
+                session.report_applied_operators(true);
+                assert_eq!(operator_stats().len(), 1);
+                assert_eq!(operator_stats()[0].nbr_successes, 1);
+            },
+        )
+        .join()
+        .unwrap();
+        session.report_applied_operators(false);
+        assert_eq!(
+            operator_stats(),
+            [OperatorStats {
+                name: "test::alternative",
+                nbr_uses: 1,
+                nbr_successes: 0
+            }]
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_choose_operator() {
+        let rng = fastrand::Rng::new();
+        let operators = [
+            register_mutator_operator("test::first"),
+            register_mutator_operator("test::second"),
+        ];
+        assert_eq!(choose_operator(&rng, &[], &[]), None);
+        assert_eq!(choose_operator(&rng, &operators, &[0.0, 0.0]), None);
+        for _ in 0..100 {
+            assert_eq!(choose_operator(&rng, &operators, &[0.0, 1.0]), Some(1));
+        }
+        let nbr_first = (0..10_000)
+            .filter(
+                #[coverage(off)]
+                |_| choose_operator(&rng, &operators, &[3.0, 1.0]) == Some(0),
+            )
+            .count();
+        assert!((7_000..8_000).contains(&nbr_first));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_alternation_operators() {
+        use crate::mutators::alternation::AlternationMutator;
+        use crate::mutators::integer_within_range::U8WithinRangeMutator;
+        use crate::Mutator;
+
+        let m = AlternationMutator::new(
+            vec![U8WithinRangeMutator::new(3..=10), U8WithinRangeMutator::new(78..=200)],
+            0.0,
+        );
+        m.initialize();
+        assert_eq!(
+            m.mutation_operators(),
+            ["alternation::Alternative0", "alternation::Alternative1"]
+        );
+
+        // only the values generated by the second alternative are interesting
+        let session = OperatorsSession::start(true, m.mutation_operators());
+        for _ in 0..1000 {
+            let (value, _) = m.random_arbitrary(100.0);
+            session.report_applied_operators(value >= 78);
+        }
+        let nbr_second = (0..1000)
+            .filter(
+                #[coverage(off)]
+                |_| m.random_arbitrary(100.0).0 >= 78,
+            )
+            .count();
+        // it is about 820 on average, since the first alternative is only slowly discouraged
+        assert!(nbr_second > 700);
+        // the given weights are unchanged
+        assert_eq!(m.weights(), [1.0, 1.0]);
+    }
+}
@@ -1,4 +1,6 @@
 use fuzzcheck_mutators_derive::make_mutator;
+
+use crate::Mutator;
 extern crate self as fuzzcheck;
 
 make_mutator! {
@@ -9,3 +11,33 @@ make_mutator! {
         None,
     }
 }
+
+impl<T, M0> OptionMutator<T, M0>
+where
+    T: Clone + 'static,
+    M0: Mutator<T>,
+{
+    /// Create an `OptionMutator` that generates `None` with the given probability, instead of half of the time.
+    ///
+    /// See [`set_none_probability`](OptionMutator::set_none_probability) for more details.
+    #[coverage(off)]
+    pub fn with_none_probability(mutator: M0, probability: f64) -> Self {
+        let m = Self::new(mutator);
+        m.set_none_probability(probability);
+        m
+    }
+
+    /// Change the probability that a new value generated by the mutator is `None`.
+    ///
+    /// It affects [`random_arbitrary`](Mutator::random_arbitrary) and the mutations that replace the
+    /// value by a new one, but not the order in which [`ordered_arbitrary`](Mutator::ordered_arbitrary)
+    /// generates values. The probability must be between `0.0` and `1.0`.
+    #[coverage(off)]
+    pub fn set_none_probability(&self, probability: f64) {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "the probability of generating None must be between 0.0 and 1.0"
+        );
+        self.mutator.set_weights(vec![1.0 - probability, probability]);
+    }
+}
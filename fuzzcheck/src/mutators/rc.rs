@@ -1,3 +1,66 @@
+//! Mutator for `Rc<T>`.
+//!
+//! The [`RcMutator`] never mutates the value behind the `Rc` in place. Instead, it mutates a copy
+//! of it and replaces the `Rc` by a new one. This is necessary because the fuzzer relies on
+//! `Clone` to store the interesting test cases, and cloning an `Rc` does not clone the value it
+//! points to. If a mutator modified a node through one of its references, it would also modify
+//! every test case of the corpus sharing that node. As a consequence, a node shared by several
+//! `Rc`s inside a test case stops being shared once it is mutated.
+//!
+//! To fuzz data structures that share nodes intentionally, the aliasing should instead be part of
+//! the generated value. Use [`map`](crate::MutatorExt::map) to generate an owned representation of
+//! the data structure, where the shared nodes appear only once, and build the `Rc`s from it. The
+//! `parse` closure is the opposite transformation, which finds the shared nodes with [`Rc::ptr_eq`].
+//! This way:
+//! * mutating a shared node mutates all of its references
+//! * the complexity of a shared node is counted only once
+//! * the data structure is rebuilt after each mutation, such that the test cases never share any
+//!   node with each other
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//!
+//! use fuzzcheck::{DefaultMutator, Mutator, MutatorExt};
+//!
+//! #[derive(Clone)]
+//! struct Pair {
+//!     a: Rc<RefCell<u8>>,
+//!     b: Rc<RefCell<u8>>,
+//! }
+//!
+//! // the value of `a`, and the value of `b` if it is not the same node as `a`
+//! let mutator = <(u8, Option<u8>)>::default_mutator().map(
+//!     |(a, b): &(u8, Option<u8>)| {
+//!         let a = Rc::new(RefCell::new(*a));
+//!         let b = match b {
+//!             Some(b) => Rc::new(RefCell::new(*b)),
+//!             None => a.clone(),
+//!         };
+//!         Pair { a, b }
+//!     },
+//!     |pair: &Pair| {
+//!         let b = if Rc::ptr_eq(&pair.a, &pair.b) {
+//!             None
+//!         } else {
+//!             Some(*pair.b.borrow())
+//!         };
+//!         Some((*pair.a.borrow(), b))
+//!     },
+//! );
+//! mutator.initialize();
+//!
+//! let node = Rc::new(RefCell::new(7));
+//! let shared = Pair { a: node.clone(), b: node };
+//! let separate = Pair {
+//!     a: Rc::new(RefCell::new(7)),
+//!     b: Rc::new(RefCell::new(7)),
+//! };
+//! let shared_cplx = mutator.complexity(&shared, &mutator.validate_value(&shared).unwrap());
+//! let separate_cplx = mutator.complexity(&separate, &mutator.validate_value(&separate).unwrap());
+//! assert!(shared_cplx < separate_cplx);
+//! ```
+
 use std::any::Any;
 use std::rc::Rc;
 
@@ -175,6 +238,12 @@ impl<T: Clone + 'static, M: Mutator<T>> Mutator<Rc<T>> for RcMutator<M> {
     fn visit_subvalues<'a>(&self, value: &'a Rc<T>, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
         self.mutator.visit_subvalues(value, cache, visit)
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
 }
 
 impl<T> DefaultMutator for Rc<T>
@@ -45,8 +45,14 @@
 //! // s_mutator impl Mutator<S>
 //! # }
 //! ```
+//!
+//! The depth of the values produced by a recursive mutator can be capped with [`RecursiveMutator::with_max_depth`].
+//!
+//! The mutators of mutually recursive types, such as a type `A` containing a `B` which contains an `A`, can be
+//! declared together with the [`recursive_group!`](crate::recursive_group) macro.
 
 use std::any::Any;
+use std::cell::Cell;
 use std::fmt::Debug;
 use std::rc::{Rc, Weak};
 
@@ -111,6 +117,7 @@ let s_mutator = RecursiveMutator::new(|mutator| {
 */
 pub struct RecursiveMutator<M> {
     pub mutator: Rc<M>,
+    max_depth: Option<usize>,
     rng: fastrand::Rng,
 }
 impl<M> RecursiveMutator<M> {
@@ -119,9 +126,136 @@ impl<M> RecursiveMutator<M> {
     pub fn new(data_fn: impl FnOnce(&Weak<M>) -> M) -> Self {
         Self {
             mutator: Rc::new_cyclic(data_fn),
+            max_depth: None,
             rng: fastrand::Rng::new(),
         }
     }
+
+    /// Only produce values whose depth of recursion is at most `max_depth`.
+    ///
+    /// The depth of a value is the number of values of the recursive type that are nested inside each other,
+    /// not counting the value itself. For example, with `struct S { content: bool, next: Option<Box<S>> }`,
+    /// the value `S { content: true, next: None }` has a depth of 0 and
+    /// `S { content: true, next: Some(Box::new(S { content: false, next: None })) }` has a depth of 1.
+    ///
+    /// Values that are deeper than `max_depth` are also rejected by [`validate_value`](Mutator::validate_value),
+    /// including those produced by a crossover with the subvalues of other test cases.
+    /// Note that the mutator may need to retry generating or mutating a value a few times to
+    /// find one that is shallow enough, and gives up after a thousand attempts: ordered mutations and
+    /// generations then return `None`, random mutations leave the value unchanged, and random generations
+    /// return the simplest value.
+    #[must_use]
+    #[coverage(off)]
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self {
+            max_depth: Some(max_depth),
+            ..self
+        }
+    }
+
+    #[coverage(off)]
+    fn is_within_max_depth(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(
+            #[coverage(off)]
+            |max_depth| depth <= max_depth,
+        )
+    }
+
+    /// The depth of the whole value, which is measured by visiting all of it
+    #[coverage(off)]
+    fn depth<T>(&self, value: &T) -> usize
+    where
+        M: Mutator<T>,
+        T: Clone + 'static,
+    {
+        with_recursion_scope(
+            self.max_depth,
+            #[coverage(off)]
+            || self.mutator.is_valid(value),
+        )
+        .1
+    }
+
+    /// Generate a random value within the maximum depth, or return `None` if none was found after
+    /// [`MAX_NBR_ATTEMPTS`]
+    #[coverage(off)]
+    fn random_arbitrary_within_max_depth<T>(&self, max_cplx: f64) -> Option<((T, f64), usize)>
+    where
+        M: Mutator<T>,
+        T: Clone + 'static,
+    {
+        let max_cplx = if self.max_depth == Some(0) { 0.0 } else { max_cplx };
+        for _ in 0..MAX_NBR_ATTEMPTS {
+            let (result, depth) = with_recursion_scope(
+                self.max_depth,
+                #[coverage(off)]
+                || self.mutator.random_arbitrary(max_cplx),
+            );
+            if self.is_within_max_depth(depth) {
+                return Some((result, depth));
+            }
+        }
+        None
+    }
+}
+
+/// The number of attempts at generating a value within the maximum depth of a [`RecursiveMutator`]
+/// before giving up
+const MAX_NBR_ATTEMPTS: usize = 1000;
+
+/// The current depth of recursion of the value handled by a [`RecursiveMutator`]
+#[derive(Clone, Copy)]
+struct RecursionDepth {
+    current: usize,
+    deepest: usize,
+    max: Option<usize>,
+}
+
+thread_local! {
+    static RECURSION_DEPTH: Cell<RecursionDepth> = const {
+        Cell::new(RecursionDepth {
+            current: 0,
+            deepest: 0,
+            max: None,
+        })
+    };
+}
+
+/// Call `f` in a new recursion scope, which is started each time a [`RecursiveMutator`] is called.
+///
+/// Returns the result of `f` and the deepest level of recursion that was reached.
+#[coverage(off)]
+fn with_recursion_scope<R>(max_depth: Option<usize>, f: impl FnOnce() -> R) -> (R, usize) {
+    let outer = RECURSION_DEPTH.get();
+    RECURSION_DEPTH.set(RecursionDepth {
+        current: 0,
+        deepest: 0,
+        max: max_depth,
+    });
+    let result = f();
+    let deepest = RECURSION_DEPTH.get().deepest;
+    RECURSION_DEPTH.set(outer);
+    (result, deepest)
+}
+
+/// Call `f` one level of recursion deeper, which is done each time a [`RecurToMutator`] is called.
+///
+/// The argument given to `f` is whether this level is the maximum depth of the current recursion scope.
+#[coverage(off)]
+fn recur<R>(f: impl FnOnce(bool) -> R) -> R {
+    let mut depth = RECURSION_DEPTH.get();
+    depth.current += 1;
+    depth.deepest = depth.deepest.max(depth.current);
+    RECURSION_DEPTH.set(depth);
+    let is_max_depth = depth.max.is_some_and(
+        #[coverage(off)]
+        |max| depth.current >= max,
+    );
+    let result = f(is_max_depth);
+    let mut depth = RECURSION_DEPTH.get();
+    depth.current -= 1;
+    RECURSION_DEPTH.set(depth);
+    result
 }
 
 /// A mutator that defers to a weak reference of a
@@ -165,13 +299,19 @@ where
     #[doc(hidden)]
     #[coverage(off)]
     fn is_valid(&self, value: &T) -> bool {
-        self.reference.upgrade().unwrap().is_valid(value)
+        recur(
+            #[coverage(off)]
+            |_| self.reference.upgrade().unwrap().is_valid(value),
+        )
     }
 
     #[doc(hidden)]
     #[coverage(off)]
     fn validate_value(&self, value: &T) -> Option<Self::Cache> {
-        self.reference.upgrade().unwrap().validate_value(value)
+        recur(
+            #[coverage(off)]
+            |_| self.reference.upgrade().unwrap().validate_value(value),
+        )
     }
 
     #[doc(hidden)]
@@ -216,18 +356,30 @@ where
                 *step = RecursingArbitraryStep::Initialized(inner_step);
                 self.ordered_arbitrary(step, max_cplx)
             }
-            RecursingArbitraryStep::Initialized(inner_step) => self
-                .reference
-                .upgrade()
-                .unwrap()
-                .ordered_arbitrary(inner_step, max_cplx),
+            RecursingArbitraryStep::Initialized(inner_step) => recur(
+                #[coverage(off)]
+                |is_max_depth| {
+                    // at the maximum depth, prefer the simplest values, which should not recurse further
+                    let max_cplx = if is_max_depth { 0.0 } else { max_cplx };
+                    self.reference
+                        .upgrade()
+                        .unwrap()
+                        .ordered_arbitrary(inner_step, max_cplx)
+                },
+            ),
         }
     }
 
     #[doc(hidden)]
     #[coverage(off)]
     fn random_arbitrary(&self, max_cplx: f64) -> (T, f64) {
-        self.reference.upgrade().unwrap().random_arbitrary(max_cplx)
+        recur(
+            #[coverage(off)]
+            |is_max_depth| {
+                let max_cplx = if is_max_depth { 0.0 } else { max_cplx };
+                self.reference.upgrade().unwrap().random_arbitrary(max_cplx)
+            },
+        )
     }
 
     #[doc(hidden)]
@@ -240,16 +392,24 @@ where
         subvalue_provider: &dyn crate::SubValueProvider,
         max_cplx: f64,
     ) -> Option<(Self::UnmutateToken, f64)> {
-        self.reference
-            .upgrade()
-            .unwrap()
-            .ordered_mutate(value, cache, step, subvalue_provider, max_cplx)
+        recur(
+            #[coverage(off)]
+            |_| {
+                self.reference
+                    .upgrade()
+                    .unwrap()
+                    .ordered_mutate(value, cache, step, subvalue_provider, max_cplx)
+            },
+        )
     }
 
     #[doc(hidden)]
     #[coverage(off)]
     fn random_mutate(&self, value: &mut T, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
-        self.reference.upgrade().unwrap().random_mutate(value, cache, max_cplx)
+        recur(
+            #[coverage(off)]
+            |_| self.reference.upgrade().unwrap().random_mutate(value, cache, max_cplx),
+        )
     }
 
     #[doc(hidden)]
@@ -265,11 +425,231 @@ where
     }
 }
 
+/**
+Declare a group of mutators for mutually recursive types, such as a type `A` containing a `B` which contains an `A`.
+
+The macro declares a struct which holds one mutator for each type of the group, and implements
+[`Mutator<T>`](crate::Mutator) for each type `T` of the group by forwarding to the corresponding mutator.
+A [`RecursiveMutator`] of the struct, returned by its `new()` function, can therefore mutate the values of
+every type of the group. Each mutator is created by a closure which receives a weak reference to the group.
+At the points of recursion, the mutators use a [`RecurToMutator`] of the group, created from that reference.
+
+The macro also implements [`DefaultMutator`](crate::DefaultMutator) for each type of the group, whose
+default mutator is then the `RecursiveMutator` of the group. The types must therefore be defined in the
+same crate as the group and must not already implement `DefaultMutator`.
+Since the group mutates values of several types, the type of the values must be annotated where the compiler
+cannot infer it, for example when the mutator is given to a function that is generic over that type.
+
+The mutators of the types are typically created by [`make_mutator!`](crate::make_mutator) with the setting
+`recursive: true` and the `#[field_mutator(..)]` attribute on the fields which contain the other types:
+```
+# #![feature(coverage_attribute)]
+use fuzzcheck::mutators::option::OptionMutator;
+use fuzzcheck::mutators::recursive::RecurToMutator;
+use fuzzcheck::mutators::vector::VecMutator;
+use fuzzcheck::{make_mutator, recursive_group, DefaultMutator};
+
+#[derive(Clone)]
+struct A {
+    b: Vec<B>,
+    data: u8,
+}
+#[derive(Clone)]
+struct B {
+    a: Option<A>,
+    data: bool,
+}
+
+make_mutator! {
+    name: AMutator,
+    recursive: true,
+    default: false,
+    type: struct A {
+        #[field_mutator(VecMutator<B, RecurToMutator<ABMutators>>)]
+        b: Vec<B>,
+        data: u8,
+    }
+}
+make_mutator! {
+    name: BMutator,
+    recursive: true,
+    default: false,
+    type: struct B {
+        #[field_mutator(OptionMutator<A, RecurToMutator<ABMutators>>)]
+        a: Option<A>,
+        data: bool,
+    }
+}
+
+recursive_group! {
+    struct ABMutators {
+        a: A => AMutator<<u8 as DefaultMutator>::Mutator> = |group| {
+            AMutator::new(VecMutator::new(group.into(), 0..=usize::MAX), u8::default_mutator())
+        },
+        b: B => BMutator<<bool as DefaultMutator>::Mutator> = |group| {
+            BMutator::new(OptionMutator::new(group.into()), bool::default_mutator())
+        },
+    }
+}
+
+let a_mutator = A::default_mutator();
+let b_mutator = B::default_mutator();
+```
+*/
+#[macro_export]
+macro_rules! recursive_group {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($field:ident : $ty:ty => $mutator:ty = |$group:ident| $init:expr),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($field: $mutator),+
+        }
+        impl $name {
+            /// Create the mutators of the group, wrapped in a `RecursiveMutator`
+            #[coverage(off)]
+            $vis fn new() -> $crate::mutators::recursive::RecursiveMutator<Self> {
+                $crate::mutators::recursive::RecursiveMutator::new(|group: &::std::rc::Weak<Self>| Self {
+                    $($field: {
+                        let $group = group;
+                        $init
+                    }),+
+                })
+            }
+            #[coverage(off)]
+            fn initialize_group(&self) {
+                $(<$mutator as $crate::Mutator<$ty>>::initialize(&self.$field);)+
+            }
+        }
+        $(
+            impl $crate::Mutator<$ty> for $name {
+                #[doc(hidden)]
+                type Cache = <$mutator as $crate::Mutator<$ty>>::Cache;
+                #[doc(hidden)]
+                type MutationStep = <$mutator as $crate::Mutator<$ty>>::MutationStep;
+                #[doc(hidden)]
+                type ArbitraryStep = <$mutator as $crate::Mutator<$ty>>::ArbitraryStep;
+                #[doc(hidden)]
+                type UnmutateToken = <$mutator as $crate::Mutator<$ty>>::UnmutateToken;
+
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn initialize(&self) {
+                    // the other mutators of the group are only reached through `RecurToMutator`s,
+                    // which do not initialize them
+                    self.initialize_group()
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
+                    <$mutator as $crate::Mutator<$ty>>::default_arbitrary_step(&self.$field)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn is_valid(&self, value: &$ty) -> bool {
+                    <$mutator as $crate::Mutator<$ty>>::is_valid(&self.$field, value)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn validate_value(&self, value: &$ty) -> ::std::option::Option<Self::Cache> {
+                    <$mutator as $crate::Mutator<$ty>>::validate_value(&self.$field, value)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn default_mutation_step(&self, value: &$ty, cache: &Self::Cache) -> Self::MutationStep {
+                    <$mutator as $crate::Mutator<$ty>>::default_mutation_step(&self.$field, value, cache)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn global_search_space_complexity(&self) -> f64 {
+                    <$mutator as $crate::Mutator<$ty>>::global_search_space_complexity(&self.$field)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn max_complexity(&self) -> f64 {
+                    <$mutator as $crate::Mutator<$ty>>::max_complexity(&self.$field)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn min_complexity(&self) -> f64 {
+                    <$mutator as $crate::Mutator<$ty>>::min_complexity(&self.$field)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn complexity(&self, value: &$ty, cache: &Self::Cache) -> f64 {
+                    <$mutator as $crate::Mutator<$ty>>::complexity(&self.$field, value, cache)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> ::std::option::Option<($ty, f64)> {
+                    <$mutator as $crate::Mutator<$ty>>::ordered_arbitrary(&self.$field, step, max_cplx)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn random_arbitrary(&self, max_cplx: f64) -> ($ty, f64) {
+                    <$mutator as $crate::Mutator<$ty>>::random_arbitrary(&self.$field, max_cplx)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn ordered_mutate(
+                    &self,
+                    value: &mut $ty,
+                    cache: &mut Self::Cache,
+                    step: &mut Self::MutationStep,
+                    subvalue_provider: &dyn $crate::SubValueProvider,
+                    max_cplx: f64,
+                ) -> ::std::option::Option<(Self::UnmutateToken, f64)> {
+                    <$mutator as $crate::Mutator<$ty>>::ordered_mutate(&self.$field, value, cache, step, subvalue_provider, max_cplx)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn random_mutate(&self, value: &mut $ty, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+                    <$mutator as $crate::Mutator<$ty>>::random_mutate(&self.$field, value, cache, max_cplx)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn unmutate(&self, value: &mut $ty, cache: &mut Self::Cache, t: Self::UnmutateToken) {
+                    <$mutator as $crate::Mutator<$ty>>::unmutate(&self.$field, value, cache, t)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn visit_subvalues<'a>(&self, value: &'a $ty, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn ::std::any::Any, f64)) {
+                    <$mutator as $crate::Mutator<$ty>>::visit_subvalues(&self.$field, value, cache, visit)
+                }
+                #[doc(hidden)]
+                #[coverage(off)]
+                fn mutation_operators(&self) -> ::std::vec::Vec<&'static str> {
+                    <$mutator as $crate::Mutator<$ty>>::mutation_operators(&self.$field)
+                }
+            }
+            impl $crate::DefaultMutator for $ty {
+                type Mutator = $crate::mutators::recursive::RecursiveMutator<$name>;
+                #[coverage(off)]
+                fn default_mutator() -> Self::Mutator {
+                    $name::new()
+                }
+            }
+        )+
+    };
+}
+
 #[derive(Clone)]
 pub struct RecursiveMutatorCache<T, C> {
     inner: C,
     _cloned_self: Box<(T, C)>,
     sub_self_values: Vec<(*const T, f64)>,
+    depth: usize,
+}
+impl<T, C> RecursiveMutatorCache<T, C> {
+    /// The depth of recursion of the value, as defined by [`RecursiveMutator::with_max_depth`]. It is kept
+    /// up to date when the value is mutated and unmutated.
+    #[coverage(off)]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -278,9 +658,10 @@ pub struct RecursiveMutatorMutationStep<MS> {
     idx_sub_self_values: usize,
 }
 
+/// The token to unmutate a value, along with the depth of the value before the mutation
 pub enum RecursiveMutatorUnmutateToken<T, UnmutateToken> {
-    Replace(T),
-    Token(UnmutateToken),
+    Replace(T, usize),
+    Token(UnmutateToken, usize),
 }
 
 impl<M, T: Clone + 'static> Mutator<T> for RecursiveMutator<M>
@@ -311,13 +692,26 @@ where
     #[doc(hidden)]
     #[coverage(off)]
     fn is_valid(&self, value: &T) -> bool {
-        self.mutator.is_valid(value)
+        let (is_valid, depth) = with_recursion_scope(
+            self.max_depth,
+            #[coverage(off)]
+            || self.mutator.is_valid(value),
+        );
+        is_valid && self.is_within_max_depth(depth)
     }
 
     #[doc(hidden)]
     #[coverage(off)]
     fn validate_value(&self, value: &T) -> Option<Self::Cache> {
-        if let Some(cache) = self.mutator.validate_value(value) {
+        let (cache, depth) = with_recursion_scope(
+            self.max_depth,
+            #[coverage(off)]
+            || self.mutator.validate_value(value),
+        );
+        if !self.is_within_max_depth(depth) {
+            return None;
+        }
+        if let Some(cache) = cache {
             let cloned_self = Box::new((value.clone(), cache.clone()));
             let mut sub_self_values = vec![];
 
@@ -332,12 +726,19 @@ where
                 }
             };
 
-            self.mutator
-                .visit_subvalues(&cloned_self.0, &cloned_self.1, &mut visit_subvalues);
+            with_recursion_scope(
+                self.max_depth,
+                #[coverage(off)]
+                || {
+                    self.mutator
+                        .visit_subvalues(&cloned_self.0, &cloned_self.1, &mut visit_subvalues)
+                },
+            );
             Some(RecursiveMutatorCache {
                 inner: cache,
                 _cloned_self: cloned_self,
                 sub_self_values,
+                depth,
             })
         } else {
             None
@@ -381,13 +782,34 @@ where
     #[doc(hidden)]
     #[coverage(off)]
     fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(T, f64)> {
-        self.mutator.ordered_arbitrary(step, max_cplx)
+        // a maximum depth of 0 means the top-level value must be as simple as possible
+        let max_cplx = if self.max_depth == Some(0) { 0.0 } else { max_cplx };
+        for _ in 0..MAX_NBR_ATTEMPTS {
+            let (result, depth) = with_recursion_scope(
+                self.max_depth,
+                #[coverage(off)]
+                || self.mutator.ordered_arbitrary(step, max_cplx),
+            );
+            // skip the values that are too deep
+            if result.is_none() || self.is_within_max_depth(depth) {
+                return result;
+            }
+        }
+        None
     }
 
     #[doc(hidden)]
     #[coverage(off)]
     fn random_arbitrary(&self, max_cplx: f64) -> (T, f64) {
-        self.mutator.random_arbitrary(max_cplx)
+        if let Some((result, _)) = self.random_arbitrary_within_max_depth(max_cplx) {
+            return result;
+        }
+        // the maximum depth is too small for the random values, so the simplest one is returned instead
+        let mut step = self.default_arbitrary_step();
+        self.ordered_arbitrary(&mut step, 0.0).unwrap_or_else(
+            #[coverage(off)]
+            || self.mutator.random_arbitrary(0.0),
+        )
     }
 
     #[doc(hidden)]
@@ -400,42 +822,80 @@ where
         subvalue_provider: &dyn crate::SubValueProvider,
         max_cplx: f64,
     ) -> Option<(Self::UnmutateToken, f64)> {
+        let old_depth = cache.depth;
         if step.idx_sub_self_values < cache.sub_self_values.len() {
             let (subself, cplx) = cache.sub_self_values[step.idx_sub_self_values];
             let subself = unsafe { subself.as_ref() }.unwrap();
             let mut tmp = subself.clone();
             step.idx_sub_self_values += 1;
             std::mem::swap(value, &mut tmp);
-            Some((RecursiveMutatorUnmutateToken::Replace(tmp), cplx))
+            cache.depth = self.depth(value);
+            Some((RecursiveMutatorUnmutateToken::Replace(tmp, old_depth), cplx))
         } else {
-            if let Some((token, cplx)) = self.mutator.ordered_mutate(
-                value,
-                &mut cache.inner,
-                &mut step.mutation_step,
-                subvalue_provider,
-                max_cplx,
-            ) {
-                Some((RecursiveMutatorUnmutateToken::Token(token), cplx))
-            } else {
-                None
+            for _ in 0..MAX_NBR_ATTEMPTS {
+                let (result, _) = with_recursion_scope(
+                    self.max_depth,
+                    #[coverage(off)]
+                    || {
+                        self.mutator.ordered_mutate(
+                            value,
+                            &mut cache.inner,
+                            &mut step.mutation_step,
+                            subvalue_provider,
+                            max_cplx,
+                        )
+                    },
+                );
+                let (token, cplx) = result?;
+                // the whole value is measured, since the subvalues inserted by a crossover are not visited
+                // by the mutation
+                let depth = self.depth(value);
+                if self.is_within_max_depth(depth) {
+                    cache.depth = depth;
+                    return Some((RecursiveMutatorUnmutateToken::Token(token, old_depth), cplx));
+                } else {
+                    // the mutated value is too deep, try the next mutation step instead
+                    self.mutator.unmutate(value, &mut cache.inner, token);
+                }
             }
+            None
         }
     }
 
     #[doc(hidden)]
     #[coverage(off)]
     fn random_mutate(&self, value: &mut T, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        let old_depth = cache.depth;
         if !cache.sub_self_values.is_empty() && self.rng.usize(..100) == 0 {
             let idx = self.rng.usize(..cache.sub_self_values.len());
             let (subself, cplx) = cache.sub_self_values[idx];
             let subself = unsafe { subself.as_ref() }.unwrap();
             let mut tmp = subself.clone();
             std::mem::swap(value, &mut tmp);
-            (RecursiveMutatorUnmutateToken::Replace(tmp), cplx)
+            cache.depth = self.depth(value);
+            return (RecursiveMutatorUnmutateToken::Replace(tmp, old_depth), cplx);
+        }
+        for _ in 0..MAX_NBR_ATTEMPTS {
+            let ((token, cplx), depth) = with_recursion_scope(
+                self.max_depth,
+                #[coverage(off)]
+                || self.mutator.random_mutate(value, &mut cache.inner, max_cplx),
+            );
+            if self.is_within_max_depth(depth) {
+                cache.depth = self.depth(value);
+                return (RecursiveMutatorUnmutateToken::Token(token, old_depth), cplx);
+            }
+            self.mutator.unmutate(value, &mut cache.inner, token);
+        }
+        // no mutation kept the value within the maximum depth, replace it by a new one instead
+        if let Some(((mut tmp, cplx), depth)) = self.random_arbitrary_within_max_depth(max_cplx) {
+            std::mem::swap(value, &mut tmp);
+            cache.depth = depth;
+            (RecursiveMutatorUnmutateToken::Replace(tmp, old_depth), cplx)
         } else {
-            let (token, cplx) = self.mutator.random_mutate(value, &mut cache.inner, max_cplx);
-            let token = RecursiveMutatorUnmutateToken::Token(token);
-            (token, cplx)
+            // or leave it unchanged
+            let cplx = self.mutator.complexity(value, &cache.inner);
+            (RecursiveMutatorUnmutateToken::Replace(value.clone(), old_depth), cplx)
         }
     }
 
@@ -443,10 +903,14 @@ where
     #[coverage(off)]
     fn unmutate(&self, value: &mut T, cache: &mut Self::Cache, t: Self::UnmutateToken) {
         match t {
-            RecursiveMutatorUnmutateToken::Replace(x) => {
+            RecursiveMutatorUnmutateToken::Replace(x, depth) => {
                 let _ = std::mem::replace(value, x);
+                cache.depth = depth;
+            }
+            RecursiveMutatorUnmutateToken::Token(t, depth) => {
+                self.mutator.unmutate(value, &mut cache.inner, t);
+                cache.depth = depth;
             }
-            RecursiveMutatorUnmutateToken::Token(t) => self.mutator.unmutate(value, &mut cache.inner, t),
         }
     }
 
@@ -455,4 +919,10 @@ where
     fn visit_subvalues<'a>(&self, value: &'a T, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
         self.mutator.visit_subvalues(value, &cache.inner, visit)
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
 }
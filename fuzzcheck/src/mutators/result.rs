@@ -1,6 +1,8 @@
 use std::result::Result;
 
 use fuzzcheck_mutators_derive::make_mutator;
+
+use crate::Mutator;
 extern crate self as fuzzcheck;
 
 make_mutator! {
@@ -11,3 +13,35 @@ make_mutator! {
         Err(E)
     }
 }
+
+impl<T, E, M0, M1> ResultMutator<T, E, M0, M1>
+where
+    T: Clone + 'static,
+    E: Clone + 'static,
+    M0: Mutator<T>,
+    M1: Mutator<E>,
+{
+    /// Create a `ResultMutator` that generates `Err` with the given probability, instead of half of the time.
+    ///
+    /// See [`set_err_probability`](ResultMutator::set_err_probability) for more details.
+    #[coverage(off)]
+    pub fn with_err_probability(ok_mutator: M0, err_mutator: M1, probability: f64) -> Self {
+        let m = Self::new(ok_mutator, err_mutator);
+        m.set_err_probability(probability);
+        m
+    }
+
+    /// Change the probability that a new value generated by the mutator is `Err`.
+    ///
+    /// It affects [`random_arbitrary`](Mutator::random_arbitrary) and the mutations that replace the
+    /// value by a new one, but not the order in which [`ordered_arbitrary`](Mutator::ordered_arbitrary)
+    /// generates values. The probability must be between `0.0` and `1.0`.
+    #[coverage(off)]
+    pub fn set_err_probability(&self, probability: f64) {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "the probability of generating Err must be between 0.0 and 1.0"
+        );
+        self.mutator.set_weights(vec![1.0 - probability, probability]);
+    }
+}
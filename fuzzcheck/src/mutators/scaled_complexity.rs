@@ -0,0 +1,153 @@
+use std::any::Any;
+
+use crate::Mutator;
+
+/// A [`ScaledComplexityMutator`] wraps a mutator and multiplies the complexity of the values
+/// it generates by a constant factor.
+///
+/// It is used by the `#[weight(..)]` attribute of `#[derive(DefaultMutator)]` to change the
+/// contribution of a field to the complexity of the whole value. Since the field of a struct to
+/// mutate is chosen with a probability that increases with its complexity, fields with a higher
+/// weight are mutated more often.
+pub struct ScaledComplexityMutator<M> {
+    mutator: M,
+    factor: f64,
+}
+
+impl<M> ScaledComplexityMutator<M> {
+    /// Creates a new [`ScaledComplexityMutator`].
+    ///
+    /// The factor must be strictly positive.
+    #[coverage(off)]
+    pub fn new(mutator: M, factor: f64) -> Self {
+        assert!(
+            factor > 0.0,
+            "the factor of a ScaledComplexityMutator must be strictly positive"
+        );
+        Self { mutator, factor }
+    }
+}
+
+impl<T, M> Mutator<T> for ScaledComplexityMutator<M>
+where
+    M: Mutator<T>,
+    T: Clone + 'static,
+{
+    #[doc(hidden)]
+    type Cache = <M as Mutator<T>>::Cache;
+    #[doc(hidden)]
+    type MutationStep = <M as Mutator<T>>::MutationStep;
+    #[doc(hidden)]
+    type ArbitraryStep = <M as Mutator<T>>::ArbitraryStep;
+    #[doc(hidden)]
+    type UnmutateToken = <M as Mutator<T>>::UnmutateToken;
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn initialize(&self) {
+        self.mutator.initialize();
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
+        self.mutator.default_arbitrary_step()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn is_valid(&self, value: &T) -> bool {
+        self.mutator.is_valid(value)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn validate_value(&self, value: &T) -> Option<Self::Cache> {
+        self.mutator.validate_value(value)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_mutation_step(&self, value: &T, cache: &Self::Cache) -> Self::MutationStep {
+        self.mutator.default_mutation_step(value, cache)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.mutator.global_search_space_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn max_complexity(&self) -> f64 {
+        self.mutator.max_complexity() * self.factor
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn min_complexity(&self) -> f64 {
+        self.mutator.min_complexity() * self.factor
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn complexity(&self, value: &T, cache: &Self::Cache) -> f64 {
+        self.mutator.complexity(value, cache) * self.factor
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(T, f64)> {
+        let (value, cplx) = self.mutator.ordered_arbitrary(step, max_cplx / self.factor)?;
+        Some((value, cplx * self.factor))
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_arbitrary(&self, max_cplx: f64) -> (T, f64) {
+        let (value, cplx) = self.mutator.random_arbitrary(max_cplx / self.factor);
+        (value, cplx * self.factor)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_mutate(
+        &self,
+        value: &mut T,
+        cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        subvalue_provider: &dyn crate::SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)> {
+        let (t, cplx) = self
+            .mutator
+            .ordered_mutate(value, cache, step, subvalue_provider, max_cplx / self.factor)?;
+        Some((t, cplx * self.factor))
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_mutate(&self, value: &mut T, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        let (t, cplx) = self.mutator.random_mutate(value, cache, max_cplx / self.factor);
+        (t, cplx * self.factor)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn unmutate(&self, value: &mut T, cache: &mut Self::Cache, t: Self::UnmutateToken) {
+        self.mutator.unmutate(value, cache, t)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn visit_subvalues<'a>(&self, value: &'a T, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
+        self.mutator.visit_subvalues(value, cache, visit)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
+}
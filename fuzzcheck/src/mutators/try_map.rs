@@ -0,0 +1,318 @@
+use std::any::Any;
+use std::marker::PhantomData;
+
+use crate::Mutator;
+
+/// [`TryMapMutator`] transforms a mutator outputting values of type `From` into a
+/// mutator outputting values of type `To`, using a conversion function from `From`
+/// to `To` that may fail.
+///
+/// It is similar to [`MapMutator`](crate::mutators::map::MapMutator), with two differences:
+/// 1. the `map` function returns an `Option<To>`. The values of type `From` that cannot
+///    be mapped are skipped, and the inner mutator is asked for another value, in the
+///    same way that a [`FilterMutator`](crate::mutators::filter::FilterMutator) skips
+///    the values rejected by its filter.
+/// 2. the complexity of a value is always given by the `cplx` function, which receives
+///    the mapped value and the complexity of the original value. It is used both when
+///    a value is generated (through `map`) and when it is validated (through `parse`),
+///    such that the complexities reported by the mutator are always consistent.
+///
+/// The `parse` function must apply the opposite transformation: for any value `x`
+/// such that `map(&x)` is `Some(y)`, `parse(&y)` should also return a value equivalent
+/// to `x`. When debug assertions are enabled, every generated value is parsed back and
+/// validated, and the mutator panics if it cannot be parsed or if its complexity
+/// differs from the one computed when it was generated.
+///
+/// # Example
+/// ```
+/// use fuzzcheck::mutators::integer::U8Mutator;
+/// use fuzzcheck::mutators::try_map::TryMapMutator;
+/// use fuzzcheck::Mutator;
+///
+/// // a mutator of ascii digits, whose complexity is the same as the byte they come from
+/// let mutator = TryMapMutator::new(
+///     U8Mutator::default(),
+///     |c: &char| c.is_ascii_digit().then(|| *c as u8),
+///     |x: &u8| x.is_ascii_digit().then(|| *x as char),
+///     |_c: &char, cplx: f64| cplx,
+/// );
+/// let (c, _) = mutator.random_arbitrary(8.0);
+/// assert!(c.is_ascii_digit());
+/// ```
+pub struct TryMapMutator<From, To, M, Parse, Map, Cplx>
+where
+    From: Clone + 'static,
+    To: Clone + 'static,
+    M: Mutator<From>,
+    Parse: Fn(&To) -> Option<From>,
+    Map: Fn(&From) -> Option<To>,
+    Cplx: Fn(&To, f64) -> f64,
+{
+    pub mutator: M,
+    pub parse: Parse,
+    pub map: Map,
+    pub cplx: Cplx,
+    _phantom: PhantomData<(To, From)>,
+}
+impl<From, To, M, Parse, Map, Cplx> TryMapMutator<From, To, M, Parse, Map, Cplx>
+where
+    From: Clone + 'static,
+    To: Clone + 'static,
+    M: Mutator<From>,
+    Parse: Fn(&To) -> Option<From>,
+    Map: Fn(&From) -> Option<To>,
+    Cplx: Fn(&To, f64) -> f64,
+{
+    #[coverage(off)]
+    pub fn new(mutator: M, parse: Parse, map: Map, cplx: Cplx) -> Self {
+        Self {
+            mutator,
+            parse,
+            map,
+            cplx,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Check that the mapped value can be parsed back and that its complexity, as computed
+    /// by [`Mutator::complexity`], is the same as the one reported when it was generated.
+    #[coverage(off)]
+    fn debug_assert_round_trip(&self, to_value: &To, cplx: f64) {
+        if cfg!(debug_assertions) {
+            let from_value = (self.parse)(to_value)
+                .expect("TryMapMutator: a value produced by the map function could not be parsed back");
+            let from_cache = self
+                .mutator
+                .validate_value(&from_value)
+                .expect("TryMapMutator: a parsed value is not valid for the inner mutator");
+            let other_cplx = (self.cplx)(to_value, self.mutator.complexity(&from_value, &from_cache));
+            assert!(
+                (cplx - other_cplx).abs() < 0.01,
+                "TryMapMutator: inconsistent complexity of a mapped value: {:.3} != {:.3}",
+                cplx,
+                other_cplx
+            );
+        }
+    }
+}
+
+pub struct Cache<From, M>
+where
+    From: Clone + 'static,
+    M: Mutator<From>,
+{
+    from_value: From,
+    from_cache: M::Cache,
+}
+impl<From, M> Clone for Cache<From, M>
+where
+    From: Clone + 'static,
+    M: Mutator<From>,
+{
+    #[coverage(off)]
+    fn clone(&self) -> Self {
+        Self {
+            from_value: self.from_value.clone(),
+            from_cache: self.from_cache.clone(),
+        }
+    }
+}
+
+impl<From, To, M, Parse, Map, Cplx> Mutator<To> for TryMapMutator<From, To, M, Parse, Map, Cplx>
+where
+    From: Clone + 'static,
+    To: Clone + 'static,
+    M: Mutator<From>,
+    Parse: Fn(&To) -> Option<From>,
+    Map: Fn(&From) -> Option<To>,
+    Cplx: Fn(&To, f64) -> f64,
+    Self: 'static,
+{
+    #[doc(hidden)]
+    type Cache = Cache<From, M>;
+    #[doc(hidden)]
+    type MutationStep = M::MutationStep;
+    #[doc(hidden)]
+    type ArbitraryStep = M::ArbitraryStep;
+    #[doc(hidden)]
+    type UnmutateToken = M::UnmutateToken;
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn initialize(&self) {
+        self.mutator.initialize();
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
+        self.mutator.default_arbitrary_step()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn is_valid(&self, value: &To) -> bool {
+        if let Some(from_value) = (self.parse)(value) {
+            self.mutator.is_valid(&from_value)
+        } else {
+            false
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn validate_value(&self, to_value: &To) -> Option<Self::Cache> {
+        let from_value = (self.parse)(to_value)?;
+        let from_cache = self.mutator.validate_value(&from_value)?;
+        Some(Cache { from_value, from_cache })
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_mutation_step(&self, _value: &To, cache: &Self::Cache) -> Self::MutationStep {
+        self.mutator.default_mutation_step(&cache.from_value, &cache.from_cache)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.mutator.global_search_space_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn max_complexity(&self) -> f64 {
+        self.mutator.max_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn min_complexity(&self) -> f64 {
+        self.mutator.min_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn complexity(&self, value: &To, cache: &Self::Cache) -> f64 {
+        let orig_cplx = self.mutator.complexity(&cache.from_value, &cache.from_cache);
+        (self.cplx)(value, orig_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(To, f64)> {
+        loop {
+            let (from_value, orig_cplx) = self.mutator.ordered_arbitrary(step, max_cplx)?;
+            if let Some(to_value) = (self.map)(&from_value) {
+                let cplx = (self.cplx)(&to_value, orig_cplx);
+                self.debug_assert_round_trip(&to_value, cplx);
+                return Some((to_value, cplx));
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_arbitrary(&self, max_cplx: f64) -> (To, f64) {
+        loop {
+            let (from_value, orig_cplx) = self.mutator.random_arbitrary(max_cplx);
+            if let Some(to_value) = (self.map)(&from_value) {
+                let cplx = (self.cplx)(&to_value, orig_cplx);
+                self.debug_assert_round_trip(&to_value, cplx);
+                return (to_value, cplx);
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_mutate(
+        &self,
+        value: &mut To,
+        cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        subvalue_provider: &dyn crate::SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)> {
+        loop {
+            let (token, orig_cplx) = self.mutator.ordered_mutate(
+                &mut cache.from_value,
+                &mut cache.from_cache,
+                step,
+                subvalue_provider,
+                max_cplx,
+            )?;
+            if let Some(to_value) = (self.map)(&cache.from_value) {
+                *value = to_value;
+                let cplx = (self.cplx)(value, orig_cplx);
+                self.debug_assert_round_trip(value, cplx);
+                return Some((token, cplx));
+            } else {
+                self.mutator
+                    .unmutate(&mut cache.from_value, &mut cache.from_cache, token);
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_mutate(&self, value: &mut To, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        loop {
+            let (token, orig_cplx) = self
+                .mutator
+                .random_mutate(&mut cache.from_value, &mut cache.from_cache, max_cplx);
+            if let Some(to_value) = (self.map)(&cache.from_value) {
+                *value = to_value;
+                let cplx = (self.cplx)(value, orig_cplx);
+                self.debug_assert_round_trip(value, cplx);
+                return (token, cplx);
+            } else {
+                self.mutator
+                    .unmutate(&mut cache.from_value, &mut cache.from_cache, token);
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn unmutate(&self, value: &mut To, cache: &mut Self::Cache, t: Self::UnmutateToken) {
+        self.mutator.unmutate(&mut cache.from_value, &mut cache.from_cache, t);
+        *value = (self.map)(&cache.from_value).expect("TryMapMutator: the unmutated value could not be mapped again");
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn visit_subvalues<'a>(&self, _value: &'a To, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
+        self.mutator
+            .visit_subvalues(&cache.from_value, &cache.from_cache, visit)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TryMapMutator;
+    use crate::mutators::integer::U8Mutator;
+    use crate::mutators::testing_utilities::test_mutator;
+
+    #[test]
+    #[coverage(off)]
+    fn test_try_map_mutator() {
+        // even bytes mapped to their half, with a complexity that grows with the mapped value
+        let mutator = TryMapMutator::new(
+            U8Mutator::default(),
+            #[coverage(off)]
+            |x: &u8| (*x < 128).then(|| x * 2),
+            #[coverage(off)]
+            |x: &u8| x.is_multiple_of(2).then(|| x / 2),
+            #[coverage(off)]
+            |x: &u8, cplx: f64| cplx + (*x as f64) / 128.0,
+        );
+        test_mutator(mutator, 10.0, 10.0, false, true, 100, 100);
+    }
+}
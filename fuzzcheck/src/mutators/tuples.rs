@@ -5,7 +5,7 @@
 //!
 //! - `TupleN` is a marker type which implements [`RefTypes`] for tuples and structures of N elements.
 //!
-//!    In this module, `Tuple0` to `Tuple10` are defined.
+//!    In this module, `Tuple0` to `Tuple20` are defined.
 //!
 //! - [`TupleStructure`] is a trait that can actually perform the destructuring for tuples and structures.
 //!   For example, the code below shows how to implement `TupleStructure<Tuple2<A, B>>` for a struct `S`.
@@ -39,7 +39,7 @@
 //!
 //! - `TupleNMutator` is a [`TupleMutator`] for types that implememt `TupleStructure<TupleN<..>>`.
 //!   
-//!   In this module, `Tuple1Mutator` to `Tuple10Mutator` are defined.
+//!   In this module, `Tuple1Mutator` to `Tuple20Mutator` are defined.
 //!
 //! ### It seems convoluted, why does all of this exist?”
 //!
@@ -178,6 +178,12 @@ where
         cache: &'a Self::Cache,
         visit: &mut dyn FnMut(&'a dyn Any, f64),
     );
+
+    /// See [`Mutator::mutation_operators`]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 /// A wrapper that transforms a [`TupleMutator`] into a [`Mutator`] of values [with a tuple structure](TupleStructure).
@@ -326,6 +332,12 @@ where
     fn visit_subvalues<'a>(&self, value: &'a T, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
         self.mutator.visit_subvalues(value.get_ref(), cache, visit)
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
 }
 
 pub use tuple0::{Tuple0, Tuple0Mutator};
@@ -684,6 +696,12 @@ mod tuple1 {
             visit(value.0, cplx);
             self.mutator_0.visit_subvalues(value.0, cache, visit);
         }
+
+        #[doc(hidden)]
+        #[coverage(off)]
+        fn mutation_operators(&self) -> Vec<&'static str> {
+            self.mutator_0.mutation_operators()
+        }
     }
     impl<T0> crate::mutators::DefaultMutator for (T0,)
     where
@@ -743,3 +761,53 @@ mod tuple10 {
     extern crate self as fuzzcheck;
     fuzzcheck_mutators_derive::make_basic_tuple_mutator!(10);
 }
+pub use tuple11::{Tuple11, Tuple11Mutator};
+mod tuple11 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(11);
+}
+pub use tuple12::{Tuple12, Tuple12Mutator};
+mod tuple12 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(12);
+}
+pub use tuple13::{Tuple13, Tuple13Mutator};
+mod tuple13 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(13);
+}
+pub use tuple14::{Tuple14, Tuple14Mutator};
+mod tuple14 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(14);
+}
+pub use tuple15::{Tuple15, Tuple15Mutator};
+mod tuple15 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(15);
+}
+pub use tuple16::{Tuple16, Tuple16Mutator};
+mod tuple16 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(16);
+}
+pub use tuple17::{Tuple17, Tuple17Mutator};
+mod tuple17 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(17);
+}
+pub use tuple18::{Tuple18, Tuple18Mutator};
+mod tuple18 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(18);
+}
+pub use tuple19::{Tuple19, Tuple19Mutator};
+mod tuple19 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(19);
+}
+pub use tuple20::{Tuple20, Tuple20Mutator};
+mod tuple20 {
+    extern crate self as fuzzcheck;
+    fuzzcheck_mutators_derive::make_basic_tuple_mutator!(20);
+}
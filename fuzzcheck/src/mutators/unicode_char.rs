@@ -0,0 +1,471 @@
+//! A mutator for `char` covering the whole range of Unicode scalar values, which generates characters
+//! by category so that the unusual ones are as likely as the ASCII ones.
+//!
+//! With the `unicode_char_mutator` feature, it is the default mutator of `char`.
+
+use std::any::Any;
+use std::ops::RangeInclusive;
+
+use fastrand::Rng;
+
+use super::character_classes::CharacterMutator;
+use super::size_to_cplxity;
+use crate::Mutator;
+
+/// A category of characters from which the [`UnicodeCharMutator`] generates values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnicodeCategory {
+    /// The printable ASCII characters
+    Ascii,
+    /// The ASCII and Latin-1 control characters
+    Control,
+    /// The characters with the `White_Space` property, such as `'\u{A0}'` or `'\u{2028}'`
+    Whitespace,
+    /// The most common blocks of combining diacritical marks
+    CombiningMark,
+    /// The characters at the boundaries of the ranges of Unicode scalar values, such as those
+    /// adjacent to the surrogate code points, or those whose UTF-8 encoding is one byte longer
+    /// than the previous character
+    Boundary,
+    /// The non-ASCII characters of the basic multilingual plane
+    BasicMultilingualPlane,
+    /// The characters outside of the basic multilingual plane, which are encoded with four bytes
+    AstralPlane,
+}
+
+impl UnicodeCategory {
+    /// All the categories
+    pub const ALL: [UnicodeCategory; 7] = [
+        UnicodeCategory::Ascii,
+        UnicodeCategory::Control,
+        UnicodeCategory::Whitespace,
+        UnicodeCategory::CombiningMark,
+        UnicodeCategory::Boundary,
+        UnicodeCategory::BasicMultilingualPlane,
+        UnicodeCategory::AstralPlane,
+    ];
+
+    /// The ranges of the characters of the category
+    #[coverage(off)]
+    pub fn ranges(self) -> Vec<RangeInclusive<char>> {
+        match self {
+            UnicodeCategory::Ascii => vec![' '..='~'],
+            UnicodeCategory::Control => vec!['\0'..='\u{1F}', '\u{7F}'..='\u{9F}'],
+            UnicodeCategory::Whitespace => vec![
+                '\t'..='\r',
+                ' '..=' ',
+                '\u{85}'..='\u{85}',
+                '\u{A0}'..='\u{A0}',
+                '\u{1680}'..='\u{1680}',
+                '\u{2000}'..='\u{200A}',
+                '\u{2028}'..='\u{2029}',
+                '\u{202F}'..='\u{202F}',
+                '\u{205F}'..='\u{205F}',
+                '\u{3000}'..='\u{3000}',
+            ],
+            UnicodeCategory::CombiningMark => vec![
+                '\u{300}'..='\u{36F}',
+                '\u{1AB0}'..='\u{1AFF}',
+                '\u{1DC0}'..='\u{1DFF}',
+                '\u{20D0}'..='\u{20FF}',
+                '\u{FE20}'..='\u{FE2F}',
+            ],
+            UnicodeCategory::Boundary => vec![
+                '\0'..='\0',
+                '\u{7F}'..='\u{80}',
+                '\u{7FF}'..='\u{800}',
+                '\u{D7FF}'..='\u{D7FF}',
+                '\u{E000}'..='\u{E000}',
+                '\u{FFFD}'..='\u{FFFF}',
+                '\u{10000}'..='\u{10000}',
+                '\u{10FFFF}'..='\u{10FFFF}',
+            ],
+            UnicodeCategory::BasicMultilingualPlane => vec!['\u{80}'..='\u{D7FF}', '\u{E000}'..='\u{FFFF}'],
+            UnicodeCategory::AstralPlane => vec!['\u{10000}'..='\u{10FFFF}'],
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CategoryMutator {
+    nbr_chars: u64,
+    /// The complexity of the simplest character of the category
+    min_cplx: f64,
+    max_cplx: f64,
+    mutator: CharacterMutator,
+}
+
+/// Mutator for a `char` that first chooses a [category](UnicodeCategory) and then a character within it.
+///
+/// Each category is equally likely to be chosen, which means that whitespace, combining marks, or the
+/// characters at the boundaries of the UTF-8 encoding are generated much more often than if the character
+/// was chosen uniformly among all Unicode scalar values. The complexity of a character is the length
+/// of its UTF-8 encoding, in bits.
+///
+/// ```
+/// use fuzzcheck::mutators::unicode_char::{UnicodeCategory, UnicodeCharMutator};
+///
+/// // all characters
+/// let m = UnicodeCharMutator::default();
+/// // only whitespace and combining marks
+/// let m = UnicodeCharMutator::new([UnicodeCategory::Whitespace, UnicodeCategory::CombiningMark]);
+/// ```
+#[derive(Debug)]
+pub struct UnicodeCharMutator {
+    categories: Vec<CategoryMutator>,
+    search_space_complexity: f64,
+    max_cplx: f64,
+    min_cplx: f64,
+    rng: Rng,
+}
+
+impl UnicodeCharMutator {
+    /// Create a mutator generating characters from the given categories
+    #[coverage(off)]
+    pub fn new(categories: impl IntoIterator<Item = UnicodeCategory>) -> Self {
+        let mut unique_categories = vec![];
+        for category in categories {
+            if !unique_categories.contains(&category) {
+                unique_categories.push(category);
+            }
+        }
+        assert!(
+            !unique_categories.is_empty(),
+            "a UnicodeCharMutator needs at least one category"
+        );
+        let categories = unique_categories
+            .into_iter()
+            .map(
+                #[coverage(off)]
+                |category| {
+                    let ranges = category.ranges();
+                    let (min_cplx, max_cplx) = ranges.iter().fold(
+                        (f64::INFINITY, 0.0f64),
+                        #[coverage(off)]
+                        |(min, max), range| {
+                            (
+                                min.min(Self::complexity_of_value(*range.start())),
+                                max.max(Self::complexity_of_value(*range.end())),
+                            )
+                        },
+                    );
+                    let nbr_chars = ranges
+                        .iter()
+                        .map(
+                            #[coverage(off)]
+                            |range| range.clone().count() as u64,
+                        )
+                        .sum();
+                    CategoryMutator {
+                        mutator: CharacterMutator::new(ranges),
+                        nbr_chars,
+                        min_cplx,
+                        max_cplx,
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+        let nbr_chars = categories
+            .iter()
+            .map(
+                #[coverage(off)]
+                |category| category.nbr_chars,
+            )
+            .sum::<u64>();
+        let max_cplx = categories
+            .iter()
+            .map(
+                #[coverage(off)]
+                |category| category.max_cplx,
+            )
+            .fold(0.0, f64::max);
+        let min_cplx = categories
+            .iter()
+            .map(
+                #[coverage(off)]
+                |category| category.min_cplx,
+            )
+            .fold(f64::INFINITY, f64::min);
+        Self {
+            categories,
+            search_space_complexity: size_to_cplxity(nbr_chars as usize),
+            max_cplx,
+            min_cplx,
+            rng: Rng::new(),
+        }
+    }
+
+    #[coverage(off)]
+    fn complexity_of_value(c: char) -> f64 {
+        (c.len_utf8() * 8) as f64
+    }
+
+    /// A random category whose simplest character is not more complex than `max_cplx`, if possible
+    #[coverage(off)]
+    fn random_category(&self, max_cplx: f64) -> &CharacterMutator {
+        let nbr_eligible = self
+            .categories
+            .iter()
+            .filter(
+                #[coverage(off)]
+                |category| category.min_cplx <= max_cplx,
+            )
+            .count();
+        if nbr_eligible == 0 {
+            return &self.categories[self.rng.usize(..self.categories.len())].mutator;
+        }
+        let pick = self.rng.usize(..nbr_eligible);
+        self.categories
+            .iter()
+            .filter(
+                #[coverage(off)]
+                |category| category.min_cplx <= max_cplx,
+            )
+            .nth(pick)
+            .map(
+                #[coverage(off)]
+                |category| &category.mutator,
+            )
+            .unwrap()
+    }
+
+    /// The character at the given step, cycling through the categories such that the first steps cover
+    /// all of them, or `None` if all the characters have been generated already
+    #[coverage(off)]
+    fn ordered_char(&self, step: &mut u64) -> Option<char> {
+        let nbr_categories = self.categories.len() as u64;
+        let max_nbr_chars = self
+            .categories
+            .iter()
+            .map(
+                #[coverage(off)]
+                |category| category.nbr_chars,
+            )
+            .max()
+            .unwrap();
+        while *step / nbr_categories < max_nbr_chars {
+            let category = &self.categories[(*step % nbr_categories) as usize];
+            let mut category_step = *step / nbr_categories;
+            *step += 1;
+            if category_step < category.nbr_chars
+                && let Some((c, _)) = category.mutator.ordered_arbitrary(&mut category_step, f64::INFINITY)
+            {
+                return Some(c);
+            }
+        }
+        None
+    }
+}
+
+impl Default for UnicodeCharMutator {
+    #[coverage(off)]
+    fn default() -> Self {
+        Self::new(UnicodeCategory::ALL)
+    }
+}
+
+impl Mutator<char> for UnicodeCharMutator {
+    #[doc(hidden)]
+    type Cache = ();
+    #[doc(hidden)]
+    type MutationStep = u64;
+    #[doc(hidden)]
+    type ArbitraryStep = u64;
+    #[doc(hidden)]
+    type UnmutateToken = char;
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn initialize(&self) {}
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
+        0
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn is_valid(&self, value: &char) -> bool {
+        self.categories.iter().any(
+            #[coverage(off)]
+            |category| category.mutator.is_valid(value),
+        )
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn validate_value(&self, value: &char) -> Option<Self::Cache> {
+        if self.is_valid(value) {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_mutation_step(&self, _value: &char, _cache: &Self::Cache) -> Self::MutationStep {
+        0
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.search_space_complexity
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn max_complexity(&self) -> f64 {
+        self.max_cplx
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn min_complexity(&self) -> f64 {
+        self.min_cplx
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn complexity(&self, value: &char, _cache: &Self::Cache) -> f64 {
+        Self::complexity_of_value(*value)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(char, f64)> {
+        if max_cplx < self.min_cplx {
+            return None;
+        }
+        self.ordered_char(step).map(
+            #[coverage(off)]
+            |c| (c, Self::complexity_of_value(c)),
+        )
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_arbitrary(&self, max_cplx: f64) -> (char, f64) {
+        self.random_category(max_cplx).random_arbitrary(max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_mutate(
+        &self,
+        value: &mut char,
+        _cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        _subvalue_provider: &dyn crate::SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)> {
+        if max_cplx < self.min_cplx {
+            return None;
+        }
+        loop {
+            let c = self.ordered_char(step)?;
+            if c != *value {
+                let old_value = std::mem::replace(value, c);
+                return Some((old_value, Self::complexity_of_value(c)));
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_mutate(&self, value: &mut char, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        self.random_category(max_cplx).random_mutate(value, cache, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn unmutate(&self, value: &mut char, _cache: &mut Self::Cache, t: Self::UnmutateToken) {
+        *value = t;
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn visit_subvalues<'a>(&self, _value: &'a char, _cache: &'a Self::Cache, _visit: &mut dyn FnMut(&'a dyn Any, f64)) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_unicode_char_mutator() {
+        let m = UnicodeCharMutator::default();
+        assert_eq!(m.min_complexity(), 8.0);
+        assert_eq!(m.max_complexity(), 32.0);
+
+        // the first steps go through every category
+        let mut step = m.default_arbitrary_step();
+        let first = (0..UnicodeCategory::ALL.len())
+            .map(
+                #[coverage(off)]
+                |_| m.ordered_arbitrary(&mut step, 32.0).unwrap().0,
+            )
+            .collect::<Vec<_>>();
+        for (c, category) in first.iter().zip(UnicodeCategory::ALL) {
+            assert!(category.ranges().iter().any(
+                #[coverage(off)]
+                |range| range.contains(c)
+            ));
+        }
+
+        let mut whitespace = 0;
+        let mut astral = 0;
+        for _ in 0..1000 {
+            let (c, cplx) = m.random_arbitrary(32.0);
+            assert_eq!(cplx, (c.len_utf8() * 8) as f64);
+            whitespace += c.is_whitespace() as usize;
+            astral += (c.len_utf8() == 4) as usize;
+        }
+        assert!(whitespace > 50);
+        assert!(astral > 50);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_unicode_char_mutator_categories() {
+        let m = UnicodeCharMutator::new([UnicodeCategory::Boundary, UnicodeCategory::Whitespace]);
+        assert!(m.is_valid(&'\u{D7FF}'));
+        assert!(!m.is_valid(&'a'));
+
+        // all the characters are eventually generated
+        let mut step = m.default_arbitrary_step();
+        let mut generated = HashSet::new();
+        while let Some((c, _)) = m.ordered_arbitrary(&mut step, 32.0) {
+            generated.insert(c);
+        }
+        let expected = [UnicodeCategory::Boundary, UnicodeCategory::Whitespace]
+            .iter()
+            .flat_map(
+                #[coverage(off)]
+                |category| category.ranges(),
+            )
+            .flatten()
+            .collect::<HashSet<_>>();
+        assert_eq!(generated, expected);
+
+        let mut value = ' ';
+        let mut step = m.default_mutation_step(&value, &());
+        while let Some((token, _)) = m.ordered_mutate(
+            &mut value,
+            &mut (),
+            &mut step,
+            &crate::subvalue_provider::EmptySubValueProvider,
+            32.0,
+        ) {
+            assert!(m.is_valid(&value));
+            m.unmutate(&mut value, &mut (), token);
+            assert_eq!(value, ' ');
+        }
+    }
+}
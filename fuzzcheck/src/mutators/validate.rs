@@ -0,0 +1,516 @@
+//! Systematic checks of the rules that every [`Mutator`] implementation must follow.
+//!
+//! A mutator that breaks these rules does not necessarily crash the fuzzer, but it can silently degrade its
+//! performance: a wrong complexity misleads the pools, and a faulty `unmutate` corrupts the test cases of the corpus.
+//! [`check_mutator`] generates and mutates values with every method of the mutator and verifies that:
+//! * [`min_complexity`](Mutator::min_complexity) and [`max_complexity`](Mutator::max_complexity) are consistent
+//! * the generated and mutated values are accepted by [`validate_value`](Mutator::validate_value)
+//! * the complexity returned alongside these values is equal to the one computed by [`complexity`](Mutator::complexity)
+//!   and lies within the bounds of the mutator
+//! * [`unmutate`](Mutator::unmutate) restores the value exactly as it was before the mutation
+//! * optionally, [`ordered_arbitrary`](Mutator::ordered_arbitrary) and [`ordered_mutate`](Mutator::ordered_mutate)
+//!   never produce the same value twice
+//!
+//! ```
+//! use fuzzcheck::mutators::validate::{check_mutator, check_mutator_with, CheckOptions};
+//! use fuzzcheck::DefaultMutator;
+//! use fuzzcheck::Mutator;
+//!
+//! let mutator = <Vec<Option<u8>>>::default_mutator();
+//! mutator.initialize();
+//! check_mutator(&mutator).unwrap();
+//!
+//! let mutator = bool::default_mutator();
+//! mutator.initialize();
+//! check_mutator_with(&mutator, &CheckOptions::exhaustive(1.0).check_duplicates(true)).unwrap();
+//! ```
+//!
+//! When the fuzzer is compiled with debug assertions, it runs these checks on its mutator before fuzzing and prints
+//! the rules that were broken, if any.
+
+use std::collections::HashSet;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+use crate::subvalue_provider::EmptySubValueProvider;
+use crate::Mutator;
+
+/// The largest difference allowed between two complexities that should be equal
+const COMPLEXITY_TOLERANCE: f64 = 0.01;
+/// The checks stop once that many failures were found
+const MAX_NBR_FAILURES: usize = 10;
+
+/// The configuration of [`check_mutator_with`]
+#[derive(Clone, Debug)]
+pub struct CheckOptions {
+    /// The maximum complexity given to the mutator when generating and mutating values
+    pub max_cplx: f64,
+    /// The number of values generated by `ordered_arbitrary` and by `random_arbitrary`
+    pub nbr_arbitraries: usize,
+    /// The number of mutations applied to each generated value by `ordered_mutate` and by `random_mutate`
+    pub nbr_mutations: usize,
+    /// Whether `ordered_arbitrary` and `ordered_mutate` are called until they cannot produce new values,
+    /// regardless of `nbr_arbitraries` and `nbr_mutations`.
+    ///
+    /// This never ends if the mutator can produce an infinite number of values within `max_cplx`.
+    pub exhaustive: bool,
+    /// Whether `ordered_arbitrary` must never generate the same value twice, and `ordered_mutate` must never
+    /// produce the same value twice from the same starting value.
+    ///
+    /// This is not required by the [`Mutator`] trait and many mutators do not guarantee it.
+    pub check_duplicates: bool,
+}
+
+impl Default for CheckOptions {
+    #[coverage(off)]
+    fn default() -> Self {
+        Self {
+            max_cplx: 100.0,
+            nbr_arbitraries: 100,
+            nbr_mutations: 100,
+            exhaustive: false,
+            check_duplicates: false,
+        }
+    }
+}
+
+impl CheckOptions {
+    /// The options enumerating every value that the mutator can produce within the given complexity
+    #[coverage(off)]
+    pub fn exhaustive(max_cplx: f64) -> Self {
+        Self {
+            max_cplx,
+            exhaustive: true,
+            ..Self::default()
+        }
+    }
+    #[must_use]
+    #[coverage(off)]
+    pub fn max_cplx(self, max_cplx: f64) -> Self {
+        Self { max_cplx, ..self }
+    }
+    #[must_use]
+    #[coverage(off)]
+    pub fn nbr_arbitraries(self, nbr_arbitraries: usize) -> Self {
+        Self {
+            nbr_arbitraries,
+            ..self
+        }
+    }
+    #[must_use]
+    #[coverage(off)]
+    pub fn nbr_mutations(self, nbr_mutations: usize) -> Self {
+        Self { nbr_mutations, ..self }
+    }
+    #[must_use]
+    #[coverage(off)]
+    pub fn check_duplicates(self, check_duplicates: bool) -> Self {
+        Self {
+            check_duplicates,
+            ..self
+        }
+    }
+}
+
+/// A rule of the [`Mutator`] trait that was broken by the checked mutator.
+///
+/// The values are described by the `Debug` representation of their key, as given to [`check_mutator_by_key`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum MutatorCheckFailure {
+    InvalidComplexityBounds {
+        min: f64,
+        max: f64,
+    },
+    InvalidValue {
+        method: &'static str,
+        value: String,
+    },
+    InconsistentComplexity {
+        method: &'static str,
+        value: String,
+        returned: f64,
+        computed: f64,
+    },
+    ComplexityOutOfBounds {
+        method: &'static str,
+        value: String,
+        cplx: f64,
+        min: f64,
+        max: f64,
+    },
+    Duplicate {
+        method: &'static str,
+        value: String,
+    },
+    UnmutateMismatch {
+        method: &'static str,
+        original: String,
+        unmutated: String,
+    },
+}
+
+impl Display for MutatorCheckFailure {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MutatorCheckFailure::InvalidComplexityBounds { min, max } => write!(
+                f,
+                "min_complexity() is {} and max_complexity() is {}, but the minimum complexity must be positive and no \
+                 greater than the maximum complexity",
+                min, max
+            ),
+            MutatorCheckFailure::InvalidValue { method, value } => write!(
+                f,
+                "{} produced {}, which is rejected by validate_value. The mutator must accept every value it produces.",
+                method, value
+            ),
+            MutatorCheckFailure::InconsistentComplexity {
+                method,
+                value,
+                returned,
+                computed,
+            } => write!(
+                f,
+                "{} returned a complexity of {:.3} for {}, but complexity() computes {:.3}. Both must use the same \
+                 formula, including the transformations applied by wrapping mutators such as maps.",
+                method, returned, value, computed
+            ),
+            MutatorCheckFailure::ComplexityOutOfBounds {
+                method,
+                value,
+                cplx,
+                min,
+                max,
+            } => write!(
+                f,
+                "{} produced {} with a complexity of {:.3}, which is outside of the bounds [{:.3}, {:.3}] given by \
+                 min_complexity() and max_complexity()",
+                method, value, cplx, min, max
+            ),
+            MutatorCheckFailure::Duplicate { method, value } => {
+                write!(f, "{} produced {} more than once", method, value)
+            }
+            MutatorCheckFailure::UnmutateMismatch {
+                method,
+                original,
+                unmutated,
+            } => write!(
+                f,
+                "unmutate did not undo a mutation made by {}: the value was {} before the mutation but {} after \
+                 unmutate. The unmutate token must describe how to restore the value exactly.",
+                method, original, unmutated
+            ),
+        }
+    }
+}
+
+/// The rules broken by a mutator, as found by [`check_mutator`]
+#[derive(Clone, PartialEq)]
+pub struct MutatorCheckError {
+    pub failures: Vec<MutatorCheckFailure>,
+}
+
+impl Display for MutatorCheckError {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the mutator broke {} rule(s) of the Mutator trait:",
+            self.failures.len()
+        )?;
+        for failure in self.failures.iter() {
+            write!(f, "\n* {}", failure)?;
+        }
+        Ok(())
+    }
+}
+// the failures are meant to be read when a check is unwrapped
+impl Debug for MutatorCheckError {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+impl std::error::Error for MutatorCheckError {}
+
+/// Check the mutator with the [default options](CheckOptions::default).
+///
+/// The mutator must have been [initialized](Mutator::initialize) beforehand.
+#[coverage(off)]
+pub fn check_mutator<T, M>(mutator: &M) -> Result<(), MutatorCheckError>
+where
+    T: Clone + Debug + Hash + Eq + 'static,
+    M: Mutator<T>,
+{
+    check_mutator_with(mutator, &CheckOptions::default())
+}
+
+/// Check the mutator with the given options.
+///
+/// The mutator must have been [initialized](Mutator::initialize) beforehand.
+#[coverage(off)]
+pub fn check_mutator_with<T, M>(mutator: &M, options: &CheckOptions) -> Result<(), MutatorCheckError>
+where
+    T: Clone + Debug + Hash + Eq + 'static,
+    M: Mutator<T>,
+{
+    check_mutator_by_key(
+        mutator,
+        options,
+        #[coverage(off)]
+        |value: &T| value.clone(),
+    )
+}
+
+/// Check the mutator of a type that cannot be compared or printed, by comparing and printing keys derived
+/// from its values instead, such as their serialization.
+///
+/// The mutator must have been [initialized](Mutator::initialize) beforehand.
+#[coverage(off)]
+pub fn check_mutator_by_key<T, M, K>(
+    mutator: &M,
+    options: &CheckOptions,
+    key: impl Fn(&T) -> K,
+) -> Result<(), MutatorCheckError>
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+    K: Debug + Hash + Eq,
+{
+    let mut checker = Checker {
+        mutator,
+        key,
+        min: mutator.min_complexity(),
+        max: mutator.max_complexity(),
+        failures: vec![],
+        _phantom: std::marker::PhantomData,
+    };
+    checker.check(options);
+    if checker.failures.is_empty() {
+        Ok(())
+    } else {
+        Err(MutatorCheckError {
+            failures: checker.failures,
+        })
+    }
+}
+
+struct Checker<'a, T, M, F> {
+    mutator: &'a M,
+    key: F,
+    min: f64,
+    max: f64,
+    failures: Vec<MutatorCheckFailure>,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T, M, F, K> Checker<'_, T, M, F>
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+    F: Fn(&T) -> K,
+    K: Debug + Hash + Eq,
+{
+    #[coverage(off)]
+    fn is_done(&self) -> bool {
+        self.failures.len() >= MAX_NBR_FAILURES
+    }
+
+    #[coverage(off)]
+    fn describe(&self, value: &T) -> String {
+        format!("{:?}", (self.key)(value))
+    }
+
+    #[coverage(off)]
+    fn check(&mut self, options: &CheckOptions) {
+        if !(self.min >= 0.0 && self.min <= self.max) {
+            self.failures.push(MutatorCheckFailure::InvalidComplexityBounds {
+                min: self.min,
+                max: self.max,
+            });
+            return;
+        }
+        let nbr_arbitraries = if options.exhaustive {
+            usize::MAX
+        } else {
+            options.nbr_arbitraries
+        };
+        let mut arbitraries = HashSet::new();
+        let mut step = self.mutator.default_arbitrary_step();
+        for _ in 0..nbr_arbitraries {
+            if self.is_done() {
+                return;
+            }
+            let Some((value, cplx)) = self.mutator.ordered_arbitrary(&mut step, options.max_cplx) else {
+                break;
+            };
+            if options.check_duplicates && !arbitraries.insert((self.key)(&value)) {
+                self.failures.push(MutatorCheckFailure::Duplicate {
+                    method: "ordered_arbitrary",
+                    value: self.describe(&value),
+                });
+            }
+            if let Some(cache) = self.check_value("ordered_arbitrary", &value, cplx) {
+                self.check_ordered_mutations(value, cache, options);
+            }
+        }
+        for _ in 0..options.nbr_arbitraries {
+            if self.is_done() {
+                return;
+            }
+            let (value, cplx) = self.mutator.random_arbitrary(options.max_cplx);
+            if let Some(cache) = self.check_value("random_arbitrary", &value, cplx) {
+                self.check_random_mutations(value, cache, options);
+            }
+        }
+    }
+
+    /// Check that the value is valid and that its complexity is correct, and return its cache
+    #[coverage(off)]
+    fn check_value(&mut self, method: &'static str, value: &T, cplx: f64) -> Option<M::Cache> {
+        let Some(cache) = self.mutator.validate_value(value) else {
+            self.failures.push(MutatorCheckFailure::InvalidValue {
+                method,
+                value: self.describe(value),
+            });
+            return None;
+        };
+        let computed = self.mutator.complexity(value, &cache);
+        if (cplx - computed).abs() >= COMPLEXITY_TOLERANCE {
+            self.failures.push(MutatorCheckFailure::InconsistentComplexity {
+                method,
+                value: self.describe(value),
+                returned: cplx,
+                computed,
+            });
+        } else if cplx < self.min - COMPLEXITY_TOLERANCE || cplx > self.max + COMPLEXITY_TOLERANCE {
+            self.failures.push(MutatorCheckFailure::ComplexityOutOfBounds {
+                method,
+                value: self.describe(value),
+                cplx,
+                min: self.min,
+                max: self.max,
+            });
+        }
+        Some(cache)
+    }
+
+    #[coverage(off)]
+    fn check_unmutated(&mut self, method: &'static str, original: &K, value: &T) -> bool {
+        let unmutated = (self.key)(value);
+        if unmutated != *original {
+            self.failures.push(MutatorCheckFailure::UnmutateMismatch {
+                method,
+                original: format!("{:?}", original),
+                unmutated: format!("{:?}", unmutated),
+            });
+            false
+        } else {
+            true
+        }
+    }
+
+    #[coverage(off)]
+    fn check_ordered_mutations(&mut self, mut value: T, mut cache: M::Cache, options: &CheckOptions) {
+        let nbr_mutations = if options.exhaustive {
+            usize::MAX
+        } else {
+            options.nbr_mutations
+        };
+        let original = (self.key)(&value);
+        let mut mutated = HashSet::new();
+        let mut step = self.mutator.default_mutation_step(&value, &cache);
+        for _ in 0..nbr_mutations {
+            if self.is_done() {
+                return;
+            }
+            let Some((token, cplx)) = self.mutator.ordered_mutate(
+                &mut value,
+                &mut cache,
+                &mut step,
+                &EmptySubValueProvider,
+                options.max_cplx,
+            ) else {
+                break;
+            };
+            if options.check_duplicates {
+                let key = (self.key)(&value);
+                if key == original || !mutated.insert(key) {
+                    self.failures.push(MutatorCheckFailure::Duplicate {
+                        method: "ordered_mutate",
+                        value: self.describe(&value),
+                    });
+                }
+            }
+            self.check_value("ordered_mutate", &value, cplx);
+            self.mutator.unmutate(&mut value, &mut cache, token);
+            if !self.check_unmutated("ordered_mutate", &original, &value) {
+                // the value and its cache may no longer be consistent
+                return;
+            }
+        }
+    }
+
+    #[coverage(off)]
+    fn check_random_mutations(&mut self, mut value: T, mut cache: M::Cache, options: &CheckOptions) {
+        let original = (self.key)(&value);
+        for _ in 0..options.nbr_mutations {
+            if self.is_done() {
+                return;
+            }
+            let (token, cplx) = self.mutator.random_mutate(&mut value, &mut cache, options.max_cplx);
+            self.check_value("random_mutate", &value, cplx);
+            self.mutator.unmutate(&mut value, &mut cache, token);
+            if !self.check_unmutated("random_mutate", &original, &value) {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutators::char::CharWithinRangeMutator;
+    use crate::mutators::map::MapMutator;
+    use crate::DefaultMutator;
+
+    #[test]
+    #[coverage(off)]
+    fn test_check_default_mutators() {
+        let m = <Vec<Option<u16>>>::default_mutator();
+        m.initialize();
+        check_mutator(&m).unwrap();
+
+        let m = <Option<bool>>::default_mutator();
+        m.initialize();
+        check_mutator_with(&m, &CheckOptions::exhaustive(100.0)).unwrap();
+
+        let m = CharWithinRangeMutator::new('a'..='z');
+        m.initialize();
+        check_mutator_with(&m, &CheckOptions::exhaustive(100.0).check_duplicates(true)).unwrap();
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_check_inconsistent_complexity() {
+        // `parse` is not the inverse of `map`, so the complexity computed from a parsed value is wrong
+        let m = MapMutator::new(
+            <Vec<u8>>::default_mutator(),
+            #[coverage(off)]
+            |x: &Vec<u8>| Some(x[..x.len() / 2].to_vec()),
+            #[coverage(off)]
+            |x: &Vec<u8>| x.clone(),
+            #[coverage(off)]
+            |_: &Vec<u8>, cplx: f64| cplx,
+        );
+        m.initialize();
+        let error = check_mutator(&m).unwrap_err();
+        assert!(error.failures.iter().any(
+            #[coverage(off)]
+            |failure| matches!(failure, MutatorCheckFailure::InconsistentComplexity { .. })
+        ));
+        assert!(error.failures.len() <= MAX_NBR_FAILURES);
+        assert!(error.to_string().contains("complexity() computes"));
+    }
+}
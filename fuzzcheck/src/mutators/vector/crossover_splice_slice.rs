@@ -0,0 +1,186 @@
+use std::any::TypeId;
+use std::ops::Range;
+
+use super::VecMutator;
+use crate::mutators::mutations::{Mutation, RevertMutation};
+use crate::{Mutator, SubValueProvider};
+
+/// Replace a slice of the vector with a slice taken from another test case, such that
+/// `[a, b, c, d]` can become `[a, x, y, z, d]` if `[.., x, y, z, ..]` is in the corpus
+pub struct CrossoverSpliceSlice;
+
+#[derive(Clone)]
+pub struct CrossoverSpliceSliceStep;
+
+pub enum ConcreteCrossoverSpliceSlice<T> {
+    Random(usize),
+    SpliceSlice {
+        removed: Range<usize>,
+        slice: Vec<T>,
+        removed_cplx: f64,
+        added_cplx: f64,
+    },
+}
+pub enum RevertCrossoverSpliceSlice<T, UT> {
+    Random(UT, usize),
+    SpliceSlice { idxs: Range<usize>, removed: Vec<T> },
+}
+
+impl<T, M> RevertMutation<Vec<T>, VecMutator<T, M>> for RevertCrossoverSpliceSlice<T, M::UnmutateToken>
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    #[coverage(off)]
+    fn revert(
+        self,
+        mutator: &VecMutator<T, M>,
+        value: &mut Vec<T>,
+        cache: &mut <VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+    ) {
+        match self {
+            RevertCrossoverSpliceSlice::Random(token, idx) => {
+                mutator.m.unmutate(&mut value[idx], &mut cache.inner[idx], token);
+            }
+            RevertCrossoverSpliceSlice::SpliceSlice { idxs, removed } => {
+                let _ = value.splice(idxs, removed);
+            }
+        }
+    }
+}
+
+impl<T, M> Mutation<Vec<T>, VecMutator<T, M>> for CrossoverSpliceSlice
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    type RandomStep = !;
+    type Step = CrossoverSpliceSliceStep;
+    type Concrete<'a> = ConcreteCrossoverSpliceSlice<T>;
+    type Revert = RevertCrossoverSpliceSlice<T, M::UnmutateToken>;
+
+    #[coverage(off)]
+    fn default_random_step(&self, _mutator: &VecMutator<T, M>, _value: &Vec<T>) -> Option<Self::RandomStep> {
+        None
+    }
+
+    #[coverage(off)]
+    fn random<'a>(
+        _mutator: &VecMutator<T, M>,
+        _value: &Vec<T>,
+        _cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        _random_step: &Self::RandomStep,
+        _max_cplx: f64,
+    ) -> Self::Concrete<'a> {
+        unreachable!()
+    }
+
+    #[coverage(off)]
+    fn default_step(
+        &self,
+        mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        _cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+    ) -> Option<Self::Step> {
+        if mutator.m.global_search_space_complexity() == 0. {
+            return None;
+        }
+        // at least one element must be removable
+        if value.len() <= *mutator.len_range.start() {
+            None
+        } else {
+            Some(CrossoverSpliceSliceStep)
+        }
+    }
+
+    #[coverage(off)]
+    fn from_step<'a>(
+        mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        _step: &'a mut Self::Step,
+        subvalue_provider: &dyn SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<Self::Concrete<'a>> {
+        let choice = mutator.rng.usize(..value.len());
+        let Some((slice, _)) = subvalue_provider.get_random_subvalue(TypeId::of::<Vec<T>>(), f64::INFINITY) else {
+            return Some(ConcreteCrossoverSpliceSlice::Random(choice));
+        };
+        let slice = slice.downcast_ref::<Vec<T>>().unwrap();
+        if slice.is_empty() {
+            return Some(ConcreteCrossoverSpliceSlice::Random(choice));
+        }
+        let start_removed = mutator.rng.usize(..value.len());
+        let max_removed_len = std::cmp::min(value.len() - start_removed, value.len() - *mutator.len_range.start());
+        let removed = start_removed..start_removed + mutator.rng.usize(1..=max_removed_len);
+        let removed_cplx = mutator.complexity_of_slice(&value[removed.clone()], &cache.inner[removed.clone()]);
+
+        let remaining_len = value.len() - removed.len();
+        let spare_cplx = max_cplx - mutator.complexity_from_inner(cache.sum_cplx - removed_cplx, remaining_len);
+        let max_added_len = std::cmp::min(slice.len(), *mutator.len_range.end() - remaining_len);
+        let start_copied_slice = mutator.rng.usize(..slice.len());
+        let mut copied_slice = vec![];
+        let mut added_cplx = 0.0;
+        // TODO: take into consideration the inherent complexity of the vector due to its length
+        for el in slice[start_copied_slice..].iter() {
+            if copied_slice.len() == max_added_len {
+                break;
+            }
+            if let Some(el_cache) = mutator.m.validate_value(el) {
+                let el_cplx = mutator.m.complexity(el, &el_cache);
+                if added_cplx + el_cplx < spare_cplx {
+                    copied_slice.push(el.clone());
+                    added_cplx += el_cplx;
+                }
+            }
+        }
+        if remaining_len + copied_slice.len() < *mutator.len_range.start() {
+            return Some(ConcreteCrossoverSpliceSlice::Random(choice));
+        }
+        Some(ConcreteCrossoverSpliceSlice::SpliceSlice {
+            removed,
+            slice: copied_slice,
+            removed_cplx,
+            added_cplx,
+        })
+    }
+
+    #[coverage(off)]
+    fn apply<'a>(
+        mutation: Self::Concrete<'a>,
+        mutator: &VecMutator<T, M>,
+        value: &mut Vec<T>,
+        cache: &mut <VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        _subvalue_provider: &dyn SubValueProvider,
+        max_cplx: f64,
+    ) -> (Self::Revert, f64) {
+        match mutation {
+            ConcreteCrossoverSpliceSlice::Random(idx) => {
+                let old_cplx = mutator.complexity(value, cache);
+                let old_el_cplx = mutator.m.complexity(&value[idx], &cache.inner[idx]);
+                let spare_cplx = max_cplx - (old_cplx - old_el_cplx);
+                let (token, new_el_cplx) = mutator
+                    .m
+                    .random_mutate(&mut value[idx], &mut cache.inner[idx], spare_cplx);
+                (
+                    RevertCrossoverSpliceSlice::Random(token, idx),
+                    mutator.complexity_from_inner(cache.sum_cplx - old_el_cplx + new_el_cplx, value.len()),
+                )
+            }
+            ConcreteCrossoverSpliceSlice::SpliceSlice {
+                removed,
+                slice,
+                removed_cplx,
+                added_cplx,
+            } => {
+                let idxs = removed.start..removed.start + slice.len();
+                let removed = value.splice(removed, slice).collect();
+                assert!(mutator.len_range.contains(&value.len()));
+                (
+                    RevertCrossoverSpliceSlice::SpliceSlice { idxs, removed },
+                    mutator.complexity_from_inner(cache.sum_cplx - removed_cplx + added_cplx, value.len()),
+                )
+            }
+        }
+    }
+}
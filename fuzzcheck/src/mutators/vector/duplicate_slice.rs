@@ -0,0 +1,154 @@
+use std::ops::Range;
+
+use super::insert_many_elements::insert_many;
+use super::VecMutator;
+use crate::mutators::mutations::{Mutation, RevertMutation};
+use crate::{Mutator, SubValueProvider};
+
+/// Insert a copy of a slice of two elements or more right after it, such that
+/// `[a, b, c, d]` can become `[a, b, c, b, c, d]`
+pub struct DuplicateSlice;
+
+#[derive(Clone)]
+pub struct DuplicateSliceRandomStep;
+
+#[derive(Clone)]
+pub struct DuplicateSliceStep {
+    start: usize,
+    len: usize,
+}
+pub struct ConcreteDuplicateSlice {
+    range: Range<usize>,
+    added_cplx: f64,
+}
+pub struct RevertDuplicateSlice {
+    idxs: Range<usize>,
+}
+
+impl<T, M> RevertMutation<Vec<T>, VecMutator<T, M>> for RevertDuplicateSlice
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    #[coverage(off)]
+    fn revert(
+        self,
+        _mutator: &VecMutator<T, M>,
+        value: &mut Vec<T>,
+        _cache: &mut <VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+    ) {
+        let _ = value.drain(self.idxs);
+    }
+}
+
+impl<T, M> Mutation<Vec<T>, VecMutator<T, M>> for DuplicateSlice
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    type RandomStep = DuplicateSliceRandomStep;
+    type Step = DuplicateSliceStep;
+    type Concrete<'a> = ConcreteDuplicateSlice;
+    type Revert = RevertDuplicateSlice;
+
+    #[coverage(off)]
+    fn default_random_step(&self, mutator: &VecMutator<T, M>, value: &Vec<T>) -> Option<Self::RandomStep> {
+        if mutator.m.max_complexity() == 0. {
+            return None;
+        }
+        if value.len() < 2 || value.len() + 2 > *mutator.len_range.end() {
+            None
+        } else {
+            Some(DuplicateSliceRandomStep)
+        }
+    }
+
+    #[coverage(off)]
+    fn random<'a>(
+        mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        _random_step: &Self::RandomStep,
+        max_cplx: f64,
+    ) -> Self::Concrete<'a> {
+        let max_len = std::cmp::min(value.len(), *mutator.len_range.end() - value.len());
+        let len = mutator.rng.usize(2..=max_len);
+        let start = mutator.rng.usize(..=value.len() - len);
+        let mut range = start..start + len;
+        let mut added_cplx = mutator.complexity_of_slice(&value[range.clone()], &cache.inner[range.clone()]);
+        // shorten the slice until it fits within the maximum complexity, but always duplicate at least one element
+        while range.len() > 1
+            && mutator.complexity_from_inner(cache.sum_cplx + added_cplx, value.len() + range.len()) > max_cplx
+        {
+            range.end -= 1;
+            added_cplx -= mutator.m.complexity(&value[range.end], &cache.inner[range.end]);
+        }
+        ConcreteDuplicateSlice { range, added_cplx }
+    }
+
+    #[coverage(off)]
+    fn default_step(
+        &self,
+        mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        _cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+    ) -> Option<Self::Step> {
+        if mutator.m.max_complexity() == 0. {
+            return None;
+        }
+        if value.len() < 2 || value.len() + 2 > *mutator.len_range.end() {
+            None
+        } else {
+            Some(DuplicateSliceStep { start: 0, len: 2 })
+        }
+    }
+
+    #[coverage(off)]
+    fn from_step<'a>(
+        mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        step: &'a mut Self::Step,
+        _subvalue_provider: &dyn SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<Self::Concrete<'a>> {
+        // the slices are tried from the shortest to the longest, and from left to right
+        loop {
+            if step.len > value.len() || value.len() + step.len > *mutator.len_range.end() {
+                return None;
+            }
+            if step.start + step.len > value.len() {
+                step.start = 0;
+                step.len += 1;
+                continue;
+            }
+            let range = step.start..step.start + step.len;
+            step.start += 1;
+            let added_cplx = mutator.complexity_of_slice(&value[range.clone()], &cache.inner[range.clone()]);
+            if mutator.complexity_from_inner(cache.sum_cplx + added_cplx, value.len() + range.len()) <= max_cplx {
+                return Some(ConcreteDuplicateSlice { range, added_cplx });
+            }
+        }
+    }
+
+    #[coverage(off)]
+    fn apply<'a>(
+        mutation: Self::Concrete<'a>,
+        mutator: &VecMutator<T, M>,
+        value: &mut Vec<T>,
+        cache: &mut <VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        _subvalue_provider: &dyn SubValueProvider,
+        _max_cplx: f64,
+    ) -> (Self::Revert, f64) {
+        let ConcreteDuplicateSlice { range, added_cplx } = mutation;
+        let copied_slice = value[range.clone()].to_vec();
+        insert_many(value, range.end, copied_slice.into_iter());
+        let new_cplx = mutator.complexity_from_inner(cache.sum_cplx + added_cplx, value.len());
+        (
+            RevertDuplicateSlice {
+                idxs: range.end..range.end + range.len(),
+            },
+            new_cplx,
+        )
+    }
+}
@@ -0,0 +1,368 @@
+use std::any::Any;
+use std::marker::PhantomData;
+
+use super::VecMutator;
+use crate::Mutator;
+
+/// A mutator for vectors that must satisfy an invariant, such as being sorted or not containing
+/// duplicate elements.
+///
+/// It wraps a mutator for `Vec<T>` and a `normalize` function, which transforms any vector into
+/// one that satisfies the invariant. Each value generated by the wrapped mutator is normalized
+/// before being given to the test function. The complexity of a value is the complexity of its
+/// normalized version, and [`validate_value`](Mutator::validate_value) rejects the values that are
+/// not already normalized. Therefore, the invariant is also preserved when the fuzzer minifies the
+/// test cases.
+///
+/// The `normalize` function should be idempotent, and the vectors it returns should be accepted by
+/// the wrapped mutator. Otherwise, the mutator will keep generating new values until it finds one
+/// whose normalization is valid.
+///
+/// The most common invariants can be created directly from a [`VecMutator`]:
+/// ```
+/// use fuzzcheck::mutators::integer::U8Mutator;
+/// use fuzzcheck::mutators::vector::VecMutator;
+/// use fuzzcheck::DefaultMutator;
+///
+/// // sorted vectors of unique bytes
+/// let mutator = VecMutator::new(U8Mutator::default(), 0..=usize::MAX).sorted_and_deduplicated();
+/// // vectors of pairs sorted by the first element of the pair
+/// let mutator = VecMutator::new(<(u8, bool)>::default_mutator(), 0..=usize::MAX).sorted_by_key(|x: &(u8, bool)| x.0);
+/// ```
+pub struct InvariantPreservingVecMutator<T, M, N>
+where
+    T: Clone + PartialEq + 'static,
+    M: Mutator<Vec<T>>,
+    N: Fn(&mut Vec<T>),
+{
+    pub mutator: M,
+    pub normalize: N,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, M, N> InvariantPreservingVecMutator<T, M, N>
+where
+    T: Clone + PartialEq + 'static,
+    M: Mutator<Vec<T>>,
+    N: Fn(&mut Vec<T>),
+{
+    #[coverage(off)]
+    pub fn new(mutator: M, normalize: N) -> Self {
+        Self {
+            mutator,
+            normalize,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Normalize the given value, and validate the result with the wrapped mutator
+    #[coverage(off)]
+    fn normalized(&self, value: &[T]) -> Option<(Vec<T>, M::Cache)> {
+        let mut normalized = value.to_vec();
+        (self.normalize)(&mut normalized);
+        let cache = self.mutator.validate_value(&normalized)?;
+        Some((normalized, cache))
+    }
+
+    #[coverage(off)]
+    fn is_normalized(&self, value: &[T]) -> bool {
+        let mut normalized = value.to_vec();
+        (self.normalize)(&mut normalized);
+        normalized == value
+    }
+}
+
+// these functions must take a `&mut Vec<T>` to be used as the `normalize` function
+#[allow(clippy::ptr_arg)]
+#[coverage(off)]
+fn sort<T: Ord>(value: &mut Vec<T>) {
+    value.sort();
+}
+#[allow(clippy::ptr_arg)]
+#[coverage(off)]
+fn dedup<T: PartialEq>(value: &mut Vec<T>) {
+    value.dedup();
+}
+#[allow(clippy::ptr_arg)]
+#[coverage(off)]
+fn sort_and_dedup<T: Ord>(value: &mut Vec<T>) {
+    value.sort();
+    value.dedup();
+}
+
+impl<T, M> VecMutator<T, M>
+where
+    T: Clone + PartialEq + 'static,
+    M: Mutator<T>,
+{
+    /// Only generate vectors that are normalized by the given function.
+    ///
+    /// See [`InvariantPreservingVecMutator`] for more details.
+    #[must_use]
+    #[coverage(off)]
+    pub fn with_invariant<N>(self, normalize: N) -> InvariantPreservingVecMutator<T, Self, N>
+    where
+        N: Fn(&mut Vec<T>),
+    {
+        InvariantPreservingVecMutator::new(self, normalize)
+    }
+
+    /// Only generate sorted vectors
+    #[must_use]
+    #[coverage(off)]
+    pub fn sorted(self) -> InvariantPreservingVecMutator<T, Self, fn(&mut Vec<T>)>
+    where
+        T: Ord,
+    {
+        self.with_invariant(sort)
+    }
+
+    /// Only generate vectors sorted by the given key
+    #[must_use]
+    #[coverage(off)]
+    pub fn sorted_by_key<K, F>(self, f: F) -> InvariantPreservingVecMutator<T, Self, impl Fn(&mut Vec<T>)>
+    where
+        K: Ord,
+        F: Fn(&T) -> K,
+    {
+        self.with_invariant(
+            #[coverage(off)]
+            move |value: &mut Vec<T>| value.sort_by_key(&f),
+        )
+    }
+
+    /// Only generate vectors that do not contain consecutive repeated elements
+    #[must_use]
+    #[coverage(off)]
+    pub fn deduplicated(self) -> InvariantPreservingVecMutator<T, Self, fn(&mut Vec<T>)> {
+        self.with_invariant(dedup)
+    }
+
+    /// Only generate sorted vectors whose elements are all different
+    #[must_use]
+    #[coverage(off)]
+    pub fn sorted_and_deduplicated(self) -> InvariantPreservingVecMutator<T, Self, fn(&mut Vec<T>)>
+    where
+        T: Ord,
+    {
+        self.with_invariant(sort_and_dedup)
+    }
+}
+
+pub struct InvariantPreservingVecMutatorCache<T, M>
+where
+    T: Clone + 'static,
+    M: Mutator<Vec<T>>,
+{
+    /// The value generated by the wrapped mutator, before it was normalized
+    inner_value: Vec<T>,
+    inner_cache: M::Cache,
+    /// The cache of the normalized value
+    cache: M::Cache,
+}
+impl<T, M> Clone for InvariantPreservingVecMutatorCache<T, M>
+where
+    T: Clone + 'static,
+    M: Mutator<Vec<T>>,
+{
+    #[coverage(off)]
+    fn clone(&self) -> Self {
+        Self {
+            inner_value: self.inner_value.clone(),
+            inner_cache: self.inner_cache.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+pub struct UnmutateInvariantPreservingVecToken<T, M>
+where
+    T: Clone + 'static,
+    M: Mutator<Vec<T>>,
+{
+    inner: M::UnmutateToken,
+    value: Vec<T>,
+    cache: M::Cache,
+}
+
+impl<T, M, N> Mutator<Vec<T>> for InvariantPreservingVecMutator<T, M, N>
+where
+    T: Clone + PartialEq + 'static,
+    M: Mutator<Vec<T>>,
+    N: Fn(&mut Vec<T>),
+    Self: 'static,
+{
+    #[doc(hidden)]
+    type Cache = InvariantPreservingVecMutatorCache<T, M>;
+    #[doc(hidden)]
+    type MutationStep = M::MutationStep;
+    #[doc(hidden)]
+    type ArbitraryStep = M::ArbitraryStep;
+    #[doc(hidden)]
+    type UnmutateToken = UnmutateInvariantPreservingVecToken<T, M>;
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn initialize(&self) {
+        self.mutator.initialize();
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
+        self.mutator.default_arbitrary_step()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn is_valid(&self, value: &Vec<T>) -> bool {
+        self.is_normalized(value) && self.mutator.is_valid(value)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn validate_value(&self, value: &Vec<T>) -> Option<Self::Cache> {
+        if !self.is_normalized(value) {
+            return None;
+        }
+        let cache = self.mutator.validate_value(value)?;
+        Some(InvariantPreservingVecMutatorCache {
+            inner_value: value.clone(),
+            inner_cache: cache.clone(),
+            cache,
+        })
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn default_mutation_step(&self, _value: &Vec<T>, cache: &Self::Cache) -> Self::MutationStep {
+        self.mutator
+            .default_mutation_step(&cache.inner_value, &cache.inner_cache)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.mutator.global_search_space_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn max_complexity(&self) -> f64 {
+        self.mutator.max_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn min_complexity(&self) -> f64 {
+        self.mutator.min_complexity()
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn complexity(&self, value: &Vec<T>, cache: &Self::Cache) -> f64 {
+        self.mutator.complexity(value, &cache.cache)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(Vec<T>, f64)> {
+        loop {
+            let (value, _) = self.mutator.ordered_arbitrary(step, max_cplx)?;
+            if let Some((value, cache)) = self.normalized(&value) {
+                let cplx = self.mutator.complexity(&value, &cache);
+                if cplx <= max_cplx {
+                    return Some((value, cplx));
+                }
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_arbitrary(&self, max_cplx: f64) -> (Vec<T>, f64) {
+        loop {
+            let (value, _) = self.mutator.random_arbitrary(max_cplx);
+            if let Some((value, cache)) = self.normalized(&value) {
+                let cplx = self.mutator.complexity(&value, &cache);
+                return (value, cplx);
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn ordered_mutate(
+        &self,
+        value: &mut Vec<T>,
+        cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        subvalue_provider: &dyn crate::SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)> {
+        loop {
+            let (inner, _) = self.mutator.ordered_mutate(
+                &mut cache.inner_value,
+                &mut cache.inner_cache,
+                step,
+                subvalue_provider,
+                max_cplx,
+            )?;
+            if let Some((new_value, new_cache)) = self.normalized(&cache.inner_value) {
+                let cplx = self.mutator.complexity(&new_value, &new_cache);
+                if cplx <= max_cplx {
+                    let token = UnmutateInvariantPreservingVecToken {
+                        inner,
+                        value: std::mem::replace(value, new_value),
+                        cache: std::mem::replace(&mut cache.cache, new_cache),
+                    };
+                    return Some((token, cplx));
+                }
+            }
+            self.mutator
+                .unmutate(&mut cache.inner_value, &mut cache.inner_cache, inner);
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn random_mutate(&self, value: &mut Vec<T>, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        loop {
+            let (inner, _) = self
+                .mutator
+                .random_mutate(&mut cache.inner_value, &mut cache.inner_cache, max_cplx);
+            if let Some((new_value, new_cache)) = self.normalized(&cache.inner_value) {
+                let cplx = self.mutator.complexity(&new_value, &new_cache);
+                let token = UnmutateInvariantPreservingVecToken {
+                    inner,
+                    value: std::mem::replace(value, new_value),
+                    cache: std::mem::replace(&mut cache.cache, new_cache),
+                };
+                return (token, cplx);
+            }
+            self.mutator
+                .unmutate(&mut cache.inner_value, &mut cache.inner_cache, inner);
+        }
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn unmutate(&self, value: &mut Vec<T>, cache: &mut Self::Cache, t: Self::UnmutateToken) {
+        self.mutator
+            .unmutate(&mut cache.inner_value, &mut cache.inner_cache, t.inner);
+        *value = t.value;
+        cache.cache = t.cache;
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn visit_subvalues<'a>(&self, value: &'a Vec<T>, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
+        self.mutator.visit_subvalues(value, &cache.cache, visit)
+    }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.mutator.mutation_operators()
+    }
+}
@@ -12,12 +12,17 @@ pub mod arbitrary;
 pub mod copy_element;
 pub mod crossover_insert_slice;
 pub mod crossover_replace_element;
+pub mod crossover_splice_slice;
+pub mod duplicate_slice;
 pub mod insert_element;
 pub mod insert_many_elements;
+pub mod invariant_preserving;
 pub mod mutate_element;
 pub mod only_choose_length;
 pub mod remove;
 pub mod remove_and_insert_element;
+pub mod reverse_slice;
+pub mod rotate;
 pub mod swap_elements;
 pub mod vec_mutation;
 
@@ -104,6 +109,16 @@ where
         }
     }
 
+    /// The sum of the complexities of the given elements
+    #[coverage(off)]
+    fn complexity_of_slice(&self, elements: &[T], caches: &[M::Cache]) -> f64 {
+        elements.iter().zip(caches.iter()).fold(
+            0.0,
+            #[coverage(off)]
+            |cplx, (el, el_cache)| cplx + self.m.complexity(el, el_cache),
+        )
+    }
+
     #[coverage(off)]
     fn complexity_from_inner(&self, cplx: f64, len: usize) -> f64 {
         if self.inherent_complexity {
@@ -351,6 +366,18 @@ where
             }
         }
     }
+
+    #[doc(hidden)]
+    #[coverage(off)]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        let mut operators = self.mutations.operator_names();
+        for operator in self.m.mutation_operators() {
+            if !operators.contains(&operator) {
+                operators.push(operator);
+            }
+        }
+        operators
+    }
 }
 
 impl<T, M> VecMutator<T, M>
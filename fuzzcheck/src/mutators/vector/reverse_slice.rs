@@ -0,0 +1,133 @@
+use std::ops::Range;
+
+use super::VecMutator;
+use crate::mutators::mutations::{Mutation, RevertMutation};
+use crate::{Mutator, SubValueProvider};
+
+/// Reverse the order of the elements of a slice of three elements or more, such that
+/// `[a, b, c, d]` can become `[a, d, c, b]`
+pub struct ReverseSlice;
+
+#[derive(Clone)]
+pub struct ReverseSliceRandomStep;
+
+#[derive(Clone)]
+pub struct ReverseSliceStep {
+    start: usize,
+    len: usize,
+}
+pub struct ConcreteReverseSlice {
+    range: Range<usize>,
+}
+pub struct RevertReverseSlice {
+    range: Range<usize>,
+}
+
+impl<T, M> RevertMutation<Vec<T>, VecMutator<T, M>> for RevertReverseSlice
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    #[coverage(off)]
+    fn revert(
+        self,
+        _mutator: &VecMutator<T, M>,
+        value: &mut Vec<T>,
+        _cache: &mut <VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+    ) {
+        value[self.range].reverse();
+    }
+}
+
+impl<T, M> Mutation<Vec<T>, VecMutator<T, M>> for ReverseSlice
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    type RandomStep = ReverseSliceRandomStep;
+    type Step = ReverseSliceStep;
+    type Concrete<'a> = ConcreteReverseSlice;
+    type Revert = RevertReverseSlice;
+
+    #[coverage(off)]
+    fn default_random_step(&self, mutator: &VecMutator<T, M>, value: &Vec<T>) -> Option<Self::RandomStep> {
+        if mutator.m.max_complexity() == 0. {
+            return None;
+        }
+        if value.len() <= 2 {
+            // reversing a vector of two elements is the same as swapping them
+            None
+        } else {
+            Some(ReverseSliceRandomStep)
+        }
+    }
+
+    #[coverage(off)]
+    fn random<'a>(
+        mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        _cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        _random_step: &Self::RandomStep,
+        _max_cplx: f64,
+    ) -> Self::Concrete<'a> {
+        let len = mutator.rng.usize(3..=value.len());
+        let start = mutator.rng.usize(..=value.len() - len);
+        ConcreteReverseSlice {
+            range: start..start + len,
+        }
+    }
+
+    #[coverage(off)]
+    fn default_step(
+        &self,
+        mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        _cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+    ) -> Option<Self::Step> {
+        if mutator.m.max_complexity() == 0. {
+            return None;
+        }
+        if value.len() <= 2 {
+            None
+        } else {
+            Some(ReverseSliceStep { start: 0, len: 3 })
+        }
+    }
+
+    #[coverage(off)]
+    fn from_step<'a>(
+        _mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        _cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        step: &'a mut Self::Step,
+        _subvalue_provider: &dyn SubValueProvider,
+        _max_cplx: f64,
+    ) -> Option<Self::Concrete<'a>> {
+        // the slices are tried from the shortest to the longest, and from left to right
+        // slices of two elements are skipped since reversing them is the same as swapping them
+        if step.start + step.len > value.len() {
+            step.start = 0;
+            step.len += 1;
+        }
+        if step.len > value.len() {
+            return None;
+        }
+        let range = step.start..step.start + step.len;
+        step.start += 1;
+        Some(ConcreteReverseSlice { range })
+    }
+
+    #[coverage(off)]
+    fn apply<'a>(
+        mutation: Self::Concrete<'a>,
+        mutator: &VecMutator<T, M>,
+        value: &mut Vec<T>,
+        cache: &mut <VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        _subvalue_provider: &dyn SubValueProvider,
+        _max_cplx: f64,
+    ) -> (Self::Revert, f64) {
+        let cplx = mutator.complexity(value, cache);
+        value[mutation.range.clone()].reverse();
+        (RevertReverseSlice { range: mutation.range }, cplx)
+    }
+}
@@ -0,0 +1,122 @@
+use super::VecMutator;
+use crate::mutators::mutations::{Mutation, RevertMutation};
+use crate::{Mutator, SubValueProvider};
+
+/// Rotate the elements of the vector to the left, such that `[a, b, c, d]` can become `[c, d, a, b]`
+pub struct Rotate;
+
+#[derive(Clone)]
+pub struct RotateRandomStep;
+
+#[derive(Clone)]
+pub struct RotateStep {
+    mid: usize,
+}
+pub struct ConcreteRotate {
+    mid: usize,
+}
+pub struct RevertRotate {
+    mid: usize,
+}
+
+impl<T, M> RevertMutation<Vec<T>, VecMutator<T, M>> for RevertRotate
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    #[coverage(off)]
+    fn revert(
+        self,
+        _mutator: &VecMutator<T, M>,
+        value: &mut Vec<T>,
+        _cache: &mut <VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+    ) {
+        value.rotate_right(self.mid);
+    }
+}
+
+impl<T, M> Mutation<Vec<T>, VecMutator<T, M>> for Rotate
+where
+    T: Clone + 'static,
+    M: Mutator<T>,
+{
+    type RandomStep = RotateRandomStep;
+    type Step = RotateStep;
+    type Concrete<'a> = ConcreteRotate;
+    type Revert = RevertRotate;
+
+    #[coverage(off)]
+    fn default_random_step(&self, mutator: &VecMutator<T, M>, value: &Vec<T>) -> Option<Self::RandomStep> {
+        if mutator.m.max_complexity() == 0. {
+            return None;
+        }
+        if value.len() <= 2 {
+            // rotating a vector of two elements is the same as swapping them
+            None
+        } else {
+            Some(RotateRandomStep)
+        }
+    }
+
+    #[coverage(off)]
+    fn random<'a>(
+        mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        _cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        _random_step: &Self::RandomStep,
+        _max_cplx: f64,
+    ) -> Self::Concrete<'a> {
+        ConcreteRotate {
+            mid: mutator.rng.usize(1..value.len()),
+        }
+    }
+
+    #[coverage(off)]
+    fn default_step(
+        &self,
+        mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        _cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+    ) -> Option<Self::Step> {
+        if mutator.m.max_complexity() == 0. {
+            return None;
+        }
+        if value.len() <= 2 {
+            None
+        } else {
+            Some(RotateStep { mid: 1 })
+        }
+    }
+
+    #[coverage(off)]
+    fn from_step<'a>(
+        _mutator: &VecMutator<T, M>,
+        value: &Vec<T>,
+        _cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        step: &'a mut Self::Step,
+        _subvalue_provider: &dyn SubValueProvider,
+        _max_cplx: f64,
+    ) -> Option<Self::Concrete<'a>> {
+        if step.mid >= value.len() {
+            None
+        } else {
+            let x = ConcreteRotate { mid: step.mid };
+            step.mid += 1;
+            Some(x)
+        }
+    }
+
+    #[coverage(off)]
+    fn apply<'a>(
+        mutation: Self::Concrete<'a>,
+        mutator: &VecMutator<T, M>,
+        value: &mut Vec<T>,
+        cache: &mut <VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
+        _subvalue_provider: &dyn SubValueProvider,
+        _max_cplx: f64,
+    ) -> (Self::Revert, f64) {
+        let cplx = mutator.complexity(value, cache);
+        value.rotate_left(mutation.mid);
+        (RevertRotate { mid: mutation.mid }, cplx)
+    }
+}
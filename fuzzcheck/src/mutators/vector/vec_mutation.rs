@@ -1,11 +1,13 @@
 use super::crossover_insert_slice::CrossoverInsertSlice;
 use super::crossover_replace_element::CrossoverReplaceElement;
+use super::crossover_splice_slice::CrossoverSpliceSlice;
 use super::{
-    arbitrary, copy_element, crossover_insert_slice, crossover_replace_element, insert_element, insert_many_elements,
-    mutate_element, only_choose_length, remove, remove_and_insert_element, swap_elements, VecMutator,
+    arbitrary, copy_element, crossover_insert_slice, crossover_replace_element, crossover_splice_slice,
+    duplicate_slice, insert_element, insert_many_elements, mutate_element, only_choose_length, remove,
+    remove_and_insert_element, reverse_slice, rotate, swap_elements, VecMutator,
 };
 use crate::mutators::mutations::{Mutation, NoMutation, RevertMutation};
-use crate::mutators::vose_alias::VoseAlias;
+use crate::mutators::operators::{choose_operator, record_operator_use, register_operator, OperatorId};
 use crate::Mutator;
 
 pub struct WeightedMutation<M> {
@@ -18,8 +20,21 @@ macro_rules! impl_vec_mutation {
         pub enum InnerVectorMutation {
             $($i($t),)*
         }
+        impl InnerVectorMutation {
+            /// The name of the mutation operator, which is shared by the mutations of the same kind
+            #[coverage(off)]
+            fn operator_name(&self) -> &'static str {
+                match self {
+                    $(
+                        Self::$i(_) => concat!("vector::", stringify!($i))
+                    ),*
+                }
+            }
+        }
         pub struct VectorMutation {
             mutations: Vec<WeightedMutation<InnerVectorMutation>>,
+            /// The operator of each mutation, whose weight is adapted to its success
+            operators: Vec<OperatorId>,
         }
         pub enum VectorMutationInnerStep<T, M>
         where
@@ -34,8 +49,9 @@ macro_rules! impl_vec_mutation {
             M: Mutator<T>,
         {
             inner_steps: Vec<VectorMutationInnerStep<T, M>>,
+            operators: Vec<OperatorId>,
+            /// The weight of each step, before it is adapted to the success of its operator
             weights: Vec<f64>,
-            sampling: VoseAlias,
         }
         pub enum VectorMutationInnerRandomStep<T, M>
         where
@@ -50,7 +66,9 @@ macro_rules! impl_vec_mutation {
             M: Mutator<T>
         {
             inner_steps: Vec<VectorMutationInnerRandomStep<T, M>>,
-            sampling: VoseAlias,
+            operators: Vec<OperatorId>,
+            /// The weight of each step, before it is adapted to the success of its operator
+            weights: Vec<f64>,
         }
         pub enum ConcreteVectorMutation<'a, T, M>
         where
@@ -91,8 +109,8 @@ macro_rules! impl_vec_mutation {
             fn clone(&self) -> Self {
                 Self {
                     inner_steps: self.inner_steps.clone(),
+                    operators: self.operators.clone(),
                     weights: self.weights.clone(),
-                    sampling: self.sampling.clone(),
                 }
             }
         }
@@ -123,7 +141,8 @@ macro_rules! impl_vec_mutation {
             fn clone(&self) -> Self {
                 Self {
                     inner_steps: self.inner_steps.clone(),
-                    sampling: self.sampling.clone(),
+                    operators: self.operators.clone(),
+                    weights: self.weights.clone(),
                 }
             }
         }
@@ -159,10 +178,11 @@ macro_rules! impl_vec_mutation {
             type Revert = RevertVectorMutation<T, M>;
             #[coverage(off)]
             fn default_random_step(&self, mutator: &VecMutator<T, M>, value: &Vec<T>) -> Option<Self::RandomStep> {
-                let inner_steps_and_weights: Vec<(_, f64)> = self
+                let inner_steps_and_weights: Vec<(_, OperatorId, f64)> = self
                     .mutations
                     .iter()
-                    .filter_map(#[coverage(off)] |mutation| {
+                    .zip(self.operators.iter())
+                    .filter_map(#[coverage(off)] |(mutation, &operator)| {
                         match &mutation.mutation {
                             $(
                                 InnerVectorMutation::$i(r) => r
@@ -170,18 +190,18 @@ macro_rules! impl_vec_mutation {
                                     .map(VectorMutationInnerRandomStep::$i)
                             ),*
                         }
-                        .map(#[coverage(off)] |inner| (inner, mutation.random_weight))
+                        .map(#[coverage(off)] |inner| (inner, operator, mutation.random_weight))
                     })
                     .collect::<Vec<_>>();
 
                 if inner_steps_and_weights.is_empty() {
                     return None;
                 }
-                let weights = inner_steps_and_weights.iter().map(#[coverage(off)] |x| x.1).collect();
-                let sampling = VoseAlias::new(weights);
+                let weights = inner_steps_and_weights.iter().map(#[coverage(off)] |x| x.2).collect();
+                let operators = inner_steps_and_weights.iter().map(#[coverage(off)] |x| x.1).collect();
                 let inner_steps = inner_steps_and_weights.into_iter().map(#[coverage(off)] |x| x.0).collect();
 
-                Some(VectorMutationRandomStep { inner_steps, sampling })
+                Some(VectorMutationRandomStep { inner_steps, operators, weights })
             }
             #[coverage(off)]
             fn random<'a>(
@@ -191,7 +211,12 @@ macro_rules! impl_vec_mutation {
                 step: &Self::RandomStep,
                 max_cplx: f64,
             ) -> Self::Concrete<'a> {
-                let inner_step_idx = step.sampling.sample();
+                // the weights are adapted when the operator is chosen, since they change during the fuzzing session
+                let inner_step_idx = choose_operator(&mutator.rng, &step.operators, &step.weights).unwrap_or_else(
+                    #[coverage(off)]
+                    || mutator.rng.usize(..step.inner_steps.len()),
+                );
+                record_operator_use(step.operators[inner_step_idx]);
                 let step = &step.inner_steps[inner_step_idx];
                 match step {
                     $(
@@ -207,10 +232,11 @@ macro_rules! impl_vec_mutation {
                 value: &Vec<T>,
                 cache: &<VecMutator<T, M> as Mutator<Vec<T>>>::Cache,
             ) -> Option<Self::Step> {
-                let inner_steps_and_weights: Vec<(VectorMutationInnerStep<_, _>, f64)> = self
+                let inner_steps_and_weights: Vec<(VectorMutationInnerStep<_, _>, OperatorId, f64)> = self
                     .mutations
                     .iter()
-                    .filter_map(#[coverage(off)] |mutation| {
+                    .zip(self.operators.iter())
+                    .filter_map(#[coverage(off)] |(mutation, &operator)| {
                         match &mutation.mutation {
                             $(
                                 InnerVectorMutation::$i(r) =>
@@ -218,7 +244,7 @@ macro_rules! impl_vec_mutation {
                                     .map(VectorMutationInnerStep::$i)
                             ),*
                         }
-                        .map(#[coverage(off)] |inner| (inner, mutation.ordered_weight))
+                        .map(#[coverage(off)] |inner| (inner, operator, mutation.ordered_weight))
                     })
                     .collect::<Vec<_>>();
 
@@ -227,19 +253,18 @@ macro_rules! impl_vec_mutation {
                 }
 
                 let mut inner_steps = Vec::with_capacity(inner_steps_and_weights.len());
+                let mut operators = Vec::with_capacity(inner_steps_and_weights.len());
                 let mut weights = Vec::with_capacity(inner_steps_and_weights.len());
-                let mut probabilities = Vec::with_capacity(inner_steps_and_weights.len());
-                for (inner_step, weight) in inner_steps_and_weights {
+                for (inner_step, operator, weight) in inner_steps_and_weights {
                     inner_steps.push(inner_step);
-                    probabilities.push(weight);
+                    operators.push(operator);
                     weights.push(weight);
                 }
-                let sampling = VoseAlias::new(probabilities);
 
                 Some(VectorMutationStep {
                     inner_steps,
+                    operators,
                     weights,
-                    sampling,
                 })
             }
             #[coverage(off)]
@@ -254,7 +279,10 @@ macro_rules! impl_vec_mutation {
                 if step.inner_steps.is_empty() {
                     return None;
                 }
-                let inner_step_idx = step.sampling.sample();
+                let inner_step_idx = choose_operator(&mutator.rng, &step.operators, &step.weights).unwrap_or_else(
+                    #[coverage(off)]
+                    || mutator.rng.usize(..step.inner_steps.len()),
+                );
                 let step_raw = step as *mut Self::Step;
                 {
                     let inner_step = &mut step.inner_steps[inner_step_idx];
@@ -268,6 +296,7 @@ macro_rules! impl_vec_mutation {
                             ),*
                         };
                     if let Some(concrete) = concrete {
+                        record_operator_use(step.operators[inner_step_idx]);
                         return Some(concrete);
                     }
                 }
@@ -275,11 +304,11 @@ macro_rules! impl_vec_mutation {
                 let step = unsafe { &mut *step_raw };
                 // remove the step from the array
                 step.weights.remove(inner_step_idx);
+                step.operators.remove(inner_step_idx);
                 step.inner_steps.remove(inner_step_idx);
                 if step.weights.is_empty() {
                     None
                 } else {
-                    step.sampling = VoseAlias::new(step.weights.clone());
                     Self::from_step(mutator, value, cache, step, subvalue_provider, max_cplx)
                 }
             }
@@ -317,7 +346,11 @@ impl_vec_mutation! {
     (OnlyChooseLength, only_choose_length::OnlyChooseLength),
     (Arbitrary, arbitrary::Arbitrary),
     (CrossoverReplaceElement, crossover_replace_element::CrossoverReplaceElement),
-    (CrossoverInsertSlice, crossover_insert_slice::CrossoverInsertSlice)
+    (CrossoverInsertSlice, crossover_insert_slice::CrossoverInsertSlice),
+    (DuplicateSlice, duplicate_slice::DuplicateSlice),
+    (ReverseSlice, reverse_slice::ReverseSlice),
+    (Rotate, rotate::Rotate),
+    (CrossoverSpliceSlice, crossover_splice_slice::CrossoverSpliceSlice)
 }
 
 impl<'a, T, M> std::fmt::Debug for ConcreteVectorMutation<'a, T, M>
@@ -363,135 +396,189 @@ where
             ConcreteVectorMutation::CrossoverInsertSlice(_) => {
                 write!(f, "CrossoverInsertSlice")
             }
+            ConcreteVectorMutation::DuplicateSlice(_) => {
+                write!(f, "DuplicateSlice")
+            }
+            ConcreteVectorMutation::ReverseSlice(_) => {
+                write!(f, "ReverseSlice")
+            }
+            ConcreteVectorMutation::Rotate(_) => {
+                write!(f, "Rotate")
+            }
+            ConcreteVectorMutation::CrossoverSpliceSlice(_) => {
+                write!(f, "CrossoverSpliceSlice")
+            }
         }
     }
 }
 
 // ====== Default Vector Mutations =====
 
+impl VectorMutation {
+    /// The names of the operators of the mutations, without duplicates
+    #[coverage(off)]
+    pub(crate) fn operator_names(&self) -> Vec<&'static str> {
+        let mut names = vec![];
+        for mutation in self.mutations.iter() {
+            let name = mutation.mutation.operator_name();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+}
+
 impl Default for VectorMutation {
     #[coverage(off)]
     fn default() -> Self {
         // use the same standard for all of them
-        Self {
-            mutations: vec![
-                WeightedMutation {
-                    mutation: InnerVectorMutation::CopyElement(copy_element::CopyElement),
-                    random_weight: 50.,
-                    ordered_weight: 500.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::OnlyChooseLength(only_choose_length::OnlyChooseLength),
-                    random_weight: 1., // doesn't matter, it's the only mutation when relevant!
-                    ordered_weight: 1.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::Arbitrary(arbitrary::Arbitrary),
-                    random_weight: 1.,
-                    ordered_weight: 1.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::Remove(remove::Remove),
-                    random_weight: 50.,
-                    ordered_weight: 50_000.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::MutateElement(mutate_element::MutateElement),
-                    random_weight: 1000.,
-                    ordered_weight: 1000.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::InsertElement(insert_element::InsertElement),
-                    random_weight: 50.,
-                    ordered_weight: 50.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::RemoveAndInsertElement(
-                        remove_and_insert_element::RemoveAndInsertElement,
-                    ),
-                    random_weight: 50.,
-                    ordered_weight: 30.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::SwapElements(swap_elements::SwapElements),
-                    random_weight: 20.,
-                    ordered_weight: 500.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
-                        nbr_added_elements: 2,
-                        repeated: false,
-                    }),
-                    random_weight: 10.,
-                    ordered_weight: 5.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
-                        nbr_added_elements: 3,
-                        repeated: false,
-                    }),
-                    random_weight: 8.,
-                    ordered_weight: 4.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
-                        nbr_added_elements: 4,
-                        repeated: false,
-                    }),
-                    random_weight: 6.,
-                    ordered_weight: 3.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
-                        nbr_added_elements: 5,
-                        repeated: false,
-                    }),
-                    random_weight: 4.,
-                    ordered_weight: 2.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
-                        nbr_added_elements: 2,
-                        repeated: true,
-                    }),
-                    random_weight: 10.,
-                    ordered_weight: 5.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
-                        nbr_added_elements: 3,
-                        repeated: true,
-                    }),
-                    random_weight: 8.,
-                    ordered_weight: 4.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::CrossoverReplaceElement(CrossoverReplaceElement),
-                    random_weight: 0.,
-                    ordered_weight: 100.,
-                },
-                WeightedMutation {
-                    mutation: InnerVectorMutation::CrossoverInsertSlice(CrossoverInsertSlice),
-                    random_weight: 0.,
-                    ordered_weight: 50.,
-                },
-                // WeightedMutation {
-                //     mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
-                //         nbr_added_elements: 4,
-                //         repeated: true,
-                //     }),
-                //     random_weight: 6.,
-                //     ordered_weight: 3.,
-                // },
-                // WeightedMutation {
-                //     mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
-                //         nbr_added_elements: 5,
-                //         repeated: true,
-                //     }),
-                //     random_weight: 4.,
-                //     ordered_weight: 2.,
-                // },
-            ],
-        }
+        let mutations = vec![
+            WeightedMutation {
+                mutation: InnerVectorMutation::CopyElement(copy_element::CopyElement),
+                random_weight: 50.,
+                ordered_weight: 500.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::OnlyChooseLength(only_choose_length::OnlyChooseLength),
+                random_weight: 1., // doesn't matter, it's the only mutation when relevant!
+                ordered_weight: 1.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::Arbitrary(arbitrary::Arbitrary),
+                random_weight: 1.,
+                ordered_weight: 1.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::Remove(remove::Remove),
+                random_weight: 50.,
+                ordered_weight: 50_000.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::MutateElement(mutate_element::MutateElement),
+                random_weight: 1000.,
+                ordered_weight: 1000.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::InsertElement(insert_element::InsertElement),
+                random_weight: 50.,
+                ordered_weight: 50.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::RemoveAndInsertElement(
+                    remove_and_insert_element::RemoveAndInsertElement,
+                ),
+                random_weight: 50.,
+                ordered_weight: 30.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::SwapElements(swap_elements::SwapElements),
+                random_weight: 20.,
+                ordered_weight: 500.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
+                    nbr_added_elements: 2,
+                    repeated: false,
+                }),
+                random_weight: 10.,
+                ordered_weight: 5.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
+                    nbr_added_elements: 3,
+                    repeated: false,
+                }),
+                random_weight: 8.,
+                ordered_weight: 4.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
+                    nbr_added_elements: 4,
+                    repeated: false,
+                }),
+                random_weight: 6.,
+                ordered_weight: 3.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
+                    nbr_added_elements: 5,
+                    repeated: false,
+                }),
+                random_weight: 4.,
+                ordered_weight: 2.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
+                    nbr_added_elements: 2,
+                    repeated: true,
+                }),
+                random_weight: 10.,
+                ordered_weight: 5.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
+                    nbr_added_elements: 3,
+                    repeated: true,
+                }),
+                random_weight: 8.,
+                ordered_weight: 4.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::CrossoverReplaceElement(CrossoverReplaceElement),
+                random_weight: 0.,
+                ordered_weight: 100.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::CrossoverInsertSlice(CrossoverInsertSlice),
+                random_weight: 0.,
+                ordered_weight: 50.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::CrossoverSpliceSlice(CrossoverSpliceSlice),
+                random_weight: 0.,
+                ordered_weight: 50.,
+            },
+            // the chunk-level mutations help with formats made of repeated records
+            WeightedMutation {
+                mutation: InnerVectorMutation::DuplicateSlice(duplicate_slice::DuplicateSlice),
+                random_weight: 10.,
+                ordered_weight: 20.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::ReverseSlice(reverse_slice::ReverseSlice),
+                random_weight: 10.,
+                ordered_weight: 20.,
+            },
+            WeightedMutation {
+                mutation: InnerVectorMutation::Rotate(rotate::Rotate),
+                random_weight: 5.,
+                ordered_weight: 10.,
+            },
+            // WeightedMutation {
+            //     mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
+            //         nbr_added_elements: 4,
+            //         repeated: true,
+            //     }),
+            //     random_weight: 6.,
+            //     ordered_weight: 3.,
+            // },
+            // WeightedMutation {
+            //     mutation: InnerVectorMutation::InsertManyElements(insert_many_elements::InsertManyElements {
+            //         nbr_added_elements: 5,
+            //         repeated: true,
+            //     }),
+            //     random_weight: 4.,
+            //     ordered_weight: 2.,
+            // },
+        ];
+        let operators = mutations
+            .iter()
+            .map(
+                #[coverage(off)]
+                |mutation| register_operator(mutation.mutation.operator_name()),
+            )
+            .collect();
+        Self { mutations, operators }
     }
 }
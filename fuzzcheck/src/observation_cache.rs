@@ -0,0 +1,89 @@
+//! A cache of the hashes of the observations that were already analyzed by the pools, which lets the
+//! fuzzer skip the analysis of the observations that cannot improve the pools.
+
+use std::collections::BTreeMap;
+
+use ahash::AHashMap;
+
+/// The hashes of the most recently seen observations, each with the lowest complexity of the test cases
+/// that produced them.
+///
+/// To bound the memory used by the cache, only the `capacity` most recently seen observations are
+/// remembered. The least recently seen ones are forgotten first.
+pub(crate) struct ObservationCache {
+    capacity: usize,
+    // the hash of each cached observations, with the lowest complexity it was seen with
+    // and the last time it was seen
+    cache: AHashMap<u64, (f64, u64)>,
+    // the hashes of the cached observations, ordered by the last time they were seen
+    recently_seen: BTreeMap<u64, u64>,
+    time: u64,
+}
+
+impl ObservationCache {
+    #[coverage(off)]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            cache: AHashMap::new(),
+            recently_seen: BTreeMap::new(),
+            time: 0,
+        }
+    }
+
+    /// Record that the observations with the given hash were seen with the given complexity.
+    ///
+    /// Returns true if they were already seen with an equal or lower complexity, or with any complexity
+    /// if `ignore_complexity` is true.
+    #[coverage(off)]
+    pub fn record(&mut self, hash: u64, complexity: f64, ignore_complexity: bool) -> bool {
+        self.time += 1;
+        if let Some((lowest_cplx, last_seen)) = self.cache.get_mut(&hash) {
+            self.recently_seen.remove(last_seen);
+            self.recently_seen.insert(self.time, hash);
+            *last_seen = self.time;
+            if ignore_complexity || *lowest_cplx <= complexity {
+                return true;
+            }
+            *lowest_cplx = complexity;
+            return false;
+        }
+        if self.cache.len() >= self.capacity {
+            if let Some((_, least_recent)) = self.recently_seen.pop_first() {
+                self.cache.remove(&least_recent);
+            } else {
+                // the capacity is 0
+                return false;
+            }
+        }
+        self.cache.insert(hash, (complexity, self.time));
+        self.recently_seen.insert(self.time, hash);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_observation_cache() {
+        let mut cache = ObservationCache::new(2);
+
+        assert!(!cache.record(0, 5.0, false));
+        assert!(cache.record(0, 5.0, false));
+        // a simpler test case producing the same observations is analysed again
+        assert!(!cache.record(0, 4.0, false));
+        assert!(cache.record(0, 4.5, false));
+        assert!(cache.record(0, 1.0, true));
+
+        // the least recently seen observations are forgotten
+        assert!(!cache.record(1, 1.0, false));
+        assert!(!cache.record(2, 1.0, false));
+        assert!(!cache.record(0, 4.0, false));
+        assert!(cache.record(2, 1.0, false));
+        assert_eq!(cache.cache.len(), 2);
+        assert_eq!(cache.recently_seen.len(), 2);
+    }
+}
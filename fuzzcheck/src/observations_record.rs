@@ -0,0 +1,215 @@
+//! A record of the observations of the sensor for every test case executed by the fuzzer, and the `repool`
+//! command, which replays it into the pool of the fuzz test in order to compare its configurations without
+//! re-executing the fuzz test.
+//!
+//! The record is kept when the fuzzer is launched with `--record-observations` and written, as the fuzzer runs,
+//! to the `observations.bin` file of the stats folder. It contains an entry for each executed test case, made of
+//! its iteration number, its complexity, and the length of its observations, each encoded as 8 bytes in little
+//! endian, followed by the observations as [encoded by the sensor](crate::Sensor::get_and_record_observations).
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::traits::SensorAndPool;
+use crate::{CSVField, PoolStorageIndex};
+
+/// The name of the file, in the stats folder, containing the observations recorded during a fuzzing session
+pub(crate) const OBSERVATIONS_RECORD_FILE: &str = "observations.bin";
+/// The name of the file, in the stats folder, to which the `repool` command writes the evolution of the pool
+pub(crate) const REPOOL_STATS_FILE: &str = "repool.csv";
+
+/// The file to which the observations of the test cases executed by the fuzzer are written, in the format of
+/// [`OBSERVATIONS_RECORD_FILE`].
+pub(crate) struct ObservationsRecord {
+    file: BufWriter<File>,
+    /// The observations of the last test case, as encoded by the sensor
+    observations: Vec<u8>,
+}
+
+/// The observations of a single test case
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RecordedObservations<'a> {
+    /// The iteration at which the test case was executed, which identifies it in the record
+    pub id: usize,
+    pub complexity: f64,
+    /// The observations, as encoded by the sensor
+    pub observations: &'a [u8],
+}
+
+#[coverage(off)]
+fn next_u64(bytes: &mut &[u8]) -> Option<u64> {
+    let (number, rest) = bytes.split_first_chunk::<8>()?;
+    *bytes = rest;
+    Some(u64::from_le_bytes(*number))
+}
+
+impl ObservationsRecord {
+    /// Create the record in the given stats folder
+    #[coverage(off)]
+    pub fn new(stats_folder: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(stats_folder)?;
+        let file = File::create(stats_folder.join(OBSERVATIONS_RECORD_FILE))?;
+        Ok(Self {
+            file: BufWriter::new(file),
+            observations: vec![],
+        })
+    }
+
+    /// The buffer in which the sensor encodes the observations of the next test case
+    #[coverage(off)]
+    pub fn buffer(&mut self) -> &mut Vec<u8> {
+        self.observations.clear();
+        &mut self.observations
+    }
+
+    /// Append an entry for the test case whose observations were encoded in the [buffer](Self::buffer)
+    #[coverage(off)]
+    pub fn push(&mut self, id: usize, complexity: f64) -> std::io::Result<()> {
+        self.file.write_all(&(id as u64).to_le_bytes())?;
+        self.file.write_all(&complexity.to_le_bytes())?;
+        self.file.write_all(&(self.observations.len() as u64).to_le_bytes())?;
+        self.file.write_all(&self.observations)
+    }
+
+    #[coverage(off)]
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+
+    /// Decode the entries of the record saved by a previous fuzzing session.
+    ///
+    /// Returns `None` if the content of the file is not valid.
+    #[coverage(off)]
+    pub fn decode(mut bytes: &[u8]) -> Option<Vec<RecordedObservations<'_>>> {
+        let mut entries = vec![];
+        while !bytes.is_empty() {
+            let id = next_u64(&mut bytes)? as usize;
+            let complexity = f64::from_bits(next_u64(&mut bytes)?);
+            let len = next_u64(&mut bytes)?;
+            if len > bytes.len() as u64 {
+                return None;
+            }
+            let (observations, rest) = bytes.split_at(len as usize);
+            bytes = rest;
+            entries.push(RecordedObservations {
+                id,
+                complexity,
+                observations,
+            });
+        }
+        Some(entries)
+    }
+}
+
+/// Append the `(counter index, counter value)` pairs observed by a sensor of counters to the record,
+/// preceded by their number
+#[coverage(off)]
+pub(crate) fn encode_counters(observations: &[(usize, u64)], record: &mut Vec<u8>) {
+    record.extend((observations.len() as u64).to_le_bytes());
+    for &(index, value) in observations {
+        record.extend((index as u64).to_le_bytes());
+        record.extend(value.to_le_bytes());
+    }
+}
+
+/// Decode the counters encoded by [`encode_counters`] at the start of the record.
+///
+/// Returns `None` if they are not valid, or if the index of a counter is not lower than `nbr_counters`.
+#[coverage(off)]
+pub(crate) fn decode_counters(record: &mut &[u8], nbr_counters: usize) -> Option<Vec<(usize, u64)>> {
+    let len = next_u64(record)?;
+    if len > (record.len() / 16) as u64 {
+        return None;
+    }
+    let mut observations = Vec::with_capacity(len as usize);
+    for _ in 0..len {
+        let index = next_u64(record)? as usize;
+        if index >= nbr_counters {
+            return None;
+        }
+        observations.push((index, next_u64(record)?));
+    }
+    Some(observations)
+}
+
+/// Feed the recorded observations to the pool, as if they were observed by the fuzzer.
+///
+/// Returns the statistics of the pool, as a CSV file with a row for every test case that changed
+/// the pool’s content, or `None` if the sensor could not replay the observations.
+#[coverage(off)]
+pub(crate) fn repool(entries: &[RecordedObservations], sensor_and_pool: &mut dyn SensorAndPool) -> Option<Vec<u8>> {
+    let mut headers = vec![CSVField::String("iteration".to_string())];
+    headers.extend(sensor_and_pool.stats().csv_headers());
+    let mut csv = CSVField::to_bytes(&headers);
+    for entry in entries {
+        let mut observations = entry.observations;
+        let deltas = sensor_and_pool.replay(PoolStorageIndex::mock(entry.id), entry.complexity, &mut observations)?;
+        if !observations.is_empty() {
+            return None;
+        }
+        if !deltas.is_empty() {
+            let mut record = vec![CSVField::Integer(entry.id as isize)];
+            record.extend(sensor_and_pool.stats().to_csv_record());
+            csv.extend(CSVField::to_bytes(&record));
+        }
+    }
+    Some(csv)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU64;
+
+    use super::*;
+    use crate::sensors_and_pools::{DynamicValueSensor, SimplestToActivateCounterPool};
+    use crate::traits::Pool;
+
+    #[test]
+    #[coverage(off)]
+    fn test_observations_record() {
+        let folder = std::env::temp_dir().join(format!("fuzzcheck_observations_record_{}", std::process::id()));
+        let mut record = ObservationsRecord::new(&folder).unwrap();
+        let entries = [(1, 2.0, vec![(0, 1), (3, 8)]), (2, 1.5, vec![]), (3, 1.0, vec![(0, 2)])];
+        for (id, complexity, observations) in &entries {
+            encode_counters(observations, record.buffer());
+            record.push(*id, *complexity).unwrap();
+        }
+        record.flush().unwrap();
+        let bytes = std::fs::read(folder.join(OBSERVATIONS_RECORD_FILE)).unwrap();
+        std::fs::remove_dir_all(&folder).unwrap();
+
+        let decoded = ObservationsRecord::decode(&bytes).unwrap();
+        assert_eq!(decoded.len(), 3);
+        for (entry, (id, complexity, observations)) in decoded.iter().zip(&entries) {
+            assert_eq!(entry.id, *id);
+            assert_eq!(entry.complexity, *complexity);
+            let mut encoded = entry.observations;
+            assert_eq!(decode_counters(&mut encoded, 4).as_ref(), Some(observations));
+            assert!(encoded.is_empty());
+        }
+        // the index of a counter is too large
+        let mut encoded = decoded[0].observations;
+        assert!(decode_counters(&mut encoded, 3).is_none());
+        assert!(ObservationsRecord::decode(&bytes[..bytes.len() - 1]).is_none());
+        assert_eq!(ObservationsRecord::decode(&[]), Some(vec![]));
+
+        // the first and third test cases are added to the pool, the second one does not activate any counter
+        static COUNTERS: [AtomicU64; 4] = [const { AtomicU64::new(0) }; 4];
+        let sensor = DynamicValueSensor::new(&COUNTERS);
+        let mut sensor_and_pool = (sensor, SimplestToActivateCounterPool::new("cov", COUNTERS.len()));
+        let csv = repool(&decoded, &mut sensor_and_pool).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let rows = csv.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("\"iteration\","));
+        assert!(rows[1].starts_with("1,"));
+        assert!(rows[2].starts_with("3,"));
+        assert_eq!(sensor_and_pool.1.stats().pool_size, 2);
+
+        // the observations were recorded by a sensor with more counters
+        let sensor = DynamicValueSensor::new(&COUNTERS[..3]);
+        let mut sensor_and_pool = (sensor, SimplestToActivateCounterPool::new("cov", 3));
+        assert!(repool(&decoded, &mut sensor_and_pool).is_none());
+    }
+}
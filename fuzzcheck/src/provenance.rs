@@ -0,0 +1,252 @@
+//! The provenance of the test cases of the corpus, which is recorded with the `--track-provenance` flag.
+//!
+//! For each test case added to the corpus, the fuzzer records the test case it was derived from, how it was
+//! derived, and its generation, which is the number of mutations separating it from a test case that was not
+//! derived from another one. The records can be used to find out which derivations are productive, or
+//! where a pathological test case comes from, by following its parents.
+
+use std::path::PathBuf;
+
+use crate::CSVField;
+
+/// The name of the file saved in the stats folder, without its extension
+pub(crate) const PROVENANCE_FILE: &str = "provenance";
+
+/// How a test case was obtained by the fuzzer
+#[cfg_attr(feature = "serde_json_serializer", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde_json_serializer", serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Derivation {
+    /// Read from the input corpus
+    Corpus,
+    /// Given to [`FuzzerBuilder5::initial_inputs`](crate::builder::FuzzerBuilder5::initial_inputs)
+    InitialInput,
+    /// Read from the incoming corpus while fuzzing
+    Incoming,
+    /// Generated randomly by the mutator
+    Arbitrary,
+    /// Obtained by mutating its parent
+    Mutation,
+    /// Obtained by mutating its parent with the subvalues of another test case of the pool
+    Crossover,
+}
+
+impl Derivation {
+    #[coverage(off)]
+    fn as_str(self) -> &'static str {
+        match self {
+            Derivation::Corpus => "corpus",
+            Derivation::InitialInput => "initial_input",
+            Derivation::Incoming => "incoming",
+            Derivation::Arbitrary => "arbitrary",
+            Derivation::Mutation => "mutation",
+            Derivation::Crossover => "crossover",
+        }
+    }
+}
+
+/// The identity of a test case stored by the fuzzer, which is given to the records of the test cases derived from it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Lineage {
+    /// The hash of the serialized test case, as used in the names of the files of the corpus
+    ///
+    /// It is empty if the provenance of the test case was not recorded.
+    pub hash: String,
+    pub generation: usize,
+}
+
+/// The provenance of a test case added to the corpus
+#[cfg_attr(feature = "serde_json_serializer", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ProvenanceRecord {
+    pub hash: String,
+    /// The hash of the test case from which it was derived, if any
+    pub parent: Option<String>,
+    pub derivation: Derivation,
+    pub generation: usize,
+    /// The number of iterations of the fuzzer when the test case was added to the corpus
+    pub iteration: usize,
+    pub complexity: f64,
+}
+
+/// How a failing test case was obtained, which is saved alongside its artifact to help find out which mutation
+/// introduced the failure
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ArtifactOrigin {
+    pub derivation: Derivation,
+    /// The lineage of the test case of the corpus from which it was derived, if any
+    ///
+    /// Its hash is empty if the provenance of the corpus is not tracked.
+    pub parent: Option<Lineage>,
+    /// The names of the mutation operators applied to the parent to obtain the failing test case, in order
+    pub operators: Vec<&'static str>,
+}
+
+impl ArtifactOrigin {
+    /// `lineage` is the lineage of the test case that was mutated to obtain the failing one, which is only
+    /// relevant if it was derived from another test case.
+    #[coverage(off)]
+    pub(crate) fn new(derivation: Derivation, lineage: &Lineage, operators: Vec<&'static str>) -> Self {
+        let parent = match derivation {
+            Derivation::Mutation | Derivation::Crossover => Some(lineage.clone()),
+            Derivation::Corpus | Derivation::InitialInput | Derivation::Incoming | Derivation::Arbitrary => None,
+        };
+        Self {
+            derivation,
+            parent,
+            operators,
+        }
+    }
+
+    /// A human-readable description of the origin, with one property per line
+    #[coverage(off)]
+    pub(crate) fn describe(&self) -> String {
+        let mut description = format!("derivation: {}\n", self.derivation.as_str());
+        match &self.parent {
+            Some(parent) if parent.hash.is_empty() => {
+                description.push_str("parent: unknown, launch the fuzzer with --track-provenance to record it\n");
+            }
+            Some(parent) => {
+                description.push_str(&format!("parent: {} (generation {})\n", parent.hash, parent.generation));
+            }
+            None => {}
+        }
+        if !self.operators.is_empty() {
+            description.push_str(&format!("mutation operators: {}\n", self.operators.join(", ")));
+        } else if self.parent.is_some() {
+            description.push_str("mutation operators: none reported by the mutator\n");
+        }
+        description
+    }
+}
+
+/// The provenance of all the test cases added to the corpus during the fuzzing session, including those
+/// that were later removed from it
+#[derive(Default)]
+pub(crate) struct Provenance {
+    records: Vec<ProvenanceRecord>,
+}
+
+impl Provenance {
+    /// Record that a test case was added to the corpus and return its lineage
+    ///
+    /// `parent` is the lineage of the test case it was derived from, if any.
+    #[coverage(off)]
+    pub(crate) fn record(
+        &mut self,
+        hash: String,
+        parent: Option<&Lineage>,
+        derivation: Derivation,
+        iteration: usize,
+        complexity: f64,
+    ) -> Lineage {
+        let generation = parent.map_or(
+            0,
+            #[coverage(off)]
+            |parent| parent.generation + 1,
+        );
+        self.records.push(ProvenanceRecord {
+            hash: hash.clone(),
+            parent: parent.map(
+                #[coverage(off)]
+                |parent| parent.hash.clone(),
+            ),
+            derivation,
+            generation,
+            iteration,
+            complexity,
+        });
+        Lineage { hash, generation }
+    }
+
+    /// The records as a CSV file
+    #[coverage(off)]
+    fn to_csv(&self) -> Vec<u8> {
+        let headers = ["hash", "parent", "derivation", "generation", "iteration", "complexity"].map(
+            #[coverage(off)]
+            |header| CSVField::String(header.to_string()),
+        );
+        let mut csv = CSVField::to_bytes(&headers);
+        for record in self.records.iter() {
+            csv.extend(CSVField::to_bytes(&[
+                CSVField::String(record.hash.clone()),
+                CSVField::String(record.parent.clone().unwrap_or_default()),
+                CSVField::String(record.derivation.as_str().to_string()),
+                CSVField::Integer(record.generation as isize),
+                CSVField::Integer(record.iteration as isize),
+                CSVField::Float(record.complexity),
+            ]));
+        }
+        csv
+    }
+
+    /// The files of the records to be saved in the stats folder
+    #[coverage(off)]
+    pub(crate) fn files(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        #[allow(unused_mut)]
+        let mut files = vec![(
+            PathBuf::new().join(PROVENANCE_FILE).with_extension("csv"),
+            self.to_csv(),
+        )];
+        #[cfg(feature = "serde_json_serializer")]
+        files.push((
+            PathBuf::new().join(PROVENANCE_FILE).with_extension("json"),
+            serde_json::to_vec_pretty(&self.records).unwrap(),
+        ));
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_provenance() {
+        let mut provenance = Provenance::default();
+        let a = provenance.record("a".to_owned(), None, Derivation::Corpus, 0, 1.0);
+        let b = provenance.record("b".to_owned(), Some(&a), Derivation::Mutation, 10, 2.0);
+        let c = provenance.record("c".to_owned(), Some(&b), Derivation::Crossover, 25, 3.5);
+        assert_eq!(a.generation, 0);
+        assert_eq!(b.generation, 1);
+        assert_eq!(
+            c,
+            Lineage {
+                hash: "c".to_owned(),
+                generation: 2
+            }
+        );
+        assert_eq!(provenance.records[2].parent.as_deref(), Some("b"));
+
+        let csv = String::from_utf8(provenance.to_csv()).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with(r#""hash","parent","derivation","generation""#));
+        assert!(lines[1].starts_with(r#""a","","corpus",0,0,"#));
+        assert!(lines[3].starts_with(r#""c","b","crossover",2,25,"#));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_artifact_origin() {
+        let lineage = Lineage {
+            hash: "b".to_owned(),
+            generation: 1,
+        };
+        let origin = ArtifactOrigin::new(Derivation::Mutation, &lineage, vec!["insert_element", "remove_element"]);
+        assert_eq!(
+            origin.describe(),
+            "derivation: mutation\nparent: b (generation 1)\nmutation operators: insert_element, remove_element\n"
+        );
+
+        let origin = ArtifactOrigin::new(Derivation::Crossover, &Lineage::default(), vec![]);
+        assert!(origin.describe().contains("--track-provenance"));
+        assert!(origin.describe().contains("none reported"));
+
+        // an arbitrary test case has no parent, even if it is tested in place of a test case of the corpus
+        let origin = ArtifactOrigin::new(Derivation::Arbitrary, &lineage, vec![]);
+        assert_eq!(origin.parent, None);
+        assert_eq!(origin.describe(), "derivation: arbitrary\n");
+    }
+}
@@ -0,0 +1,17 @@
+#include <stddef.h>
+
+// Defined by the runtime of the sanitizers, such as AddressSanitizer and LeakSanitizer, if the
+// program is compiled with one of them. Otherwise, the symbol is null.
+void __sanitizer_set_death_callback(void (*callback)(void)) __attribute__((weak));
+
+// Register the function called by the sanitizers right before they terminate the process.
+// Returns 1 if it was registered and 0 if the program does not use a sanitizer.
+int fuzzcheck_set_sanitizer_death_callback(void (*callback)(void))
+{
+    if (__sanitizer_set_death_callback == NULL)
+    {
+        return 0;
+    }
+    __sanitizer_set_death_callback(callback);
+    return 1;
+}
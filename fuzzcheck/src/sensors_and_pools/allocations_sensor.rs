@@ -13,6 +13,9 @@ use crate::{SaveToStatsFolder, Sensor};
 /// where the first element is the number of allocations performed and the
 /// second element is the amount of bytes that were allocated.
 ///
+/// The simplest way to use it is with [`FuzzerBuilder3::allocation_sensor_and_pool`](crate::builder::FuzzerBuilder3::allocation_sensor_and_pool),
+/// which pairs it with a [`MaximiseAllocationsPool`](crate::sensors_and_pools::MaximiseAllocationsPool).
+///
 /// # Example
 ///
 /// ```rust
@@ -37,9 +37,13 @@
 //! // then (s, p) is a valid combination of sensor and pool
 //! ```
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::path::PathBuf;
 
+use ahash::AHasher;
+
+use super::CodeCoverageSensor;
 use crate::traits::{CompatibleWithObservations, CorpusDelta, Pool, SaveToStatsFolder, Sensor, SensorAndPool, Stats};
 use crate::{CSVField, PoolStorageIndex, ToCSV};
 /// Marker type used by [`AndPool`] to signal that all sub-pools are compatible with the same observations.
@@ -150,6 +154,12 @@ where
     fn weight(&self) -> f64 {
         self.p1_weight() + self.p2_weight()
     }
+
+    #[coverage(off)]
+    fn record_interesting_child(&mut self, parent: PoolStorageIndex) {
+        self.p1.record_interesting_child(parent);
+        self.p2.record_interesting_child(parent);
+    }
 }
 
 impl<P1, P2, ObservationsMarker> SaveToStatsFolder for AndPool<P1, P2, ObservationsMarker>
@@ -199,6 +209,33 @@ where
     fn get_observations(&mut self) -> Self::Observations {
         (self.0.get_observations(), self.1.get_observations())
     }
+    #[coverage(off)]
+    fn get_and_record_observations(&mut self, record: &mut Vec<u8>) -> Self::Observations {
+        (
+            self.0.get_and_record_observations(record),
+            self.1.get_and_record_observations(record),
+        )
+    }
+    #[coverage(off)]
+    fn replay_observations(&self, record: &mut &[u8]) -> Option<Self::Observations> {
+        Some((self.0.replay_observations(record)?, self.1.replay_observations(record)?))
+    }
+    /// The code coverage sensor of the first sensor, or else of the second one
+    #[coverage(off)]
+    fn code_coverage_sensor(&mut self) -> Option<&mut CodeCoverageSensor> {
+        if self.0.code_coverage_sensor().is_some() {
+            self.0.code_coverage_sensor()
+        } else {
+            self.1.code_coverage_sensor()
+        }
+    }
+    #[coverage(off)]
+    fn hash_observations(&self, observations: &Self::Observations) -> Option<u64> {
+        let mut hasher = AHasher::default();
+        self.0.hash_observations(&observations.0)?.hash(&mut hasher);
+        self.1.hash_observations(&observations.1)?.hash(&mut hasher);
+        Some(hasher.finish())
+    }
 }
 
 impl<S1, S2> SaveToStatsFolder for AndSensor<S1, S2>
@@ -340,6 +377,20 @@ impl AndSensorAndPool {
             rng: fastrand::Rng::new(),
         }
     }
+
+    /// Remember which of the two pools made progress, and concatenate their deltas
+    #[coverage(off)]
+    fn combine_deltas(&mut self, deltas_1: Vec<CorpusDelta>, deltas_2: Vec<CorpusDelta>) -> Vec<CorpusDelta> {
+        if !deltas_1.is_empty() {
+            self.sap1_number_times_chosen_since_last_progress = 1;
+        }
+        if !deltas_2.is_empty() {
+            self.sap2_number_times_chosen_since_last_progress = 1;
+        }
+        let mut deltas = deltas_1;
+        deltas.extend(deltas_2);
+        deltas
+    }
 }
 impl SaveToStatsFolder for AndSensorAndPool {
     #[coverage(off)]
@@ -369,6 +420,18 @@ impl SensorAndPool for AndSensorAndPool {
 
     #[coverage(off)]
     fn process(&mut self, input_id: PoolStorageIndex, cplx: f64) -> Vec<CorpusDelta> {
+        let deltas_1 = self.sap1.process(input_id, cplx);
+        let deltas_2 = self.sap2.process(input_id, cplx);
+        self.combine_deltas(deltas_1, deltas_2)
+    }
+
+    #[coverage(off)]
+    fn process_unless_known(
+        &mut self,
+        input_id: PoolStorageIndex,
+        cplx: f64,
+        is_known: &mut dyn FnMut(u64) -> bool,
+    ) -> Option<Vec<CorpusDelta>> {
         let AndSensorAndPool {
             sap1,
             sap2,
@@ -376,17 +439,55 @@ impl SensorAndPool for AndSensorAndPool {
             sap2_number_times_chosen_since_last_progress,
             ..
         } = self;
-        let deltas_1 = sap1.process(input_id, cplx);
-        if !deltas_1.is_empty() {
+        // each pool skips the observations that it has already seen, which are told apart from the
+        // observations of the other pool by salting their hash, once per level of nested pools
+        let deltas_1 = sap1.process_unless_known(
+            input_id,
+            cplx,
+            &mut #[coverage(off)]
+            |hash| is_known(salted_hash(hash, 0)),
+        );
+        if deltas_1.as_ref().is_some_and(
+            #[coverage(off)]
+            |deltas| !deltas.is_empty(),
+        ) {
             *sap1_number_times_chosen_since_last_progress = 1;
         }
-        let deltas_2 = sap2.process(input_id, cplx);
-        if !deltas_2.is_empty() {
+        let deltas_2 = sap2.process_unless_known(
+            input_id,
+            cplx,
+            &mut #[coverage(off)]
+            |hash| is_known(salted_hash(hash, 1)),
+        );
+        if deltas_2.as_ref().is_some_and(
+            #[coverage(off)]
+            |deltas| !deltas.is_empty(),
+        ) {
             *sap2_number_times_chosen_since_last_progress = 1;
         }
-        let mut deltas = deltas_1;
-        deltas.extend(deltas_2);
-        deltas
+        match (deltas_1, deltas_2) {
+            (None, None) => None,
+            (deltas_1, deltas_2) => {
+                let mut deltas = deltas_1.unwrap_or_default();
+                deltas.extend(deltas_2.unwrap_or_default());
+                Some(deltas)
+            }
+        }
+    }
+
+    /// The observations of the second sensor are recorded after those of the first one
+    #[coverage(off)]
+    fn process_and_record(&mut self, input_id: PoolStorageIndex, cplx: f64, record: &mut Vec<u8>) -> Vec<CorpusDelta> {
+        let deltas_1 = self.sap1.process_and_record(input_id, cplx, record);
+        let deltas_2 = self.sap2.process_and_record(input_id, cplx, record);
+        self.combine_deltas(deltas_1, deltas_2)
+    }
+
+    #[coverage(off)]
+    fn replay(&mut self, input_id: PoolStorageIndex, cplx: f64, record: &mut &[u8]) -> Option<Vec<CorpusDelta>> {
+        let deltas_1 = self.sap1.replay(input_id, cplx, record)?;
+        let deltas_2 = self.sap2.replay(input_id, cplx, record)?;
+        Some(self.combine_deltas(deltas_1, deltas_2))
     }
 
     #[coverage(off)]
@@ -408,4 +509,28 @@ impl SensorAndPool for AndSensorAndPool {
             self.sap1.get_random_index()
         }
     }
+
+    #[coverage(off)]
+    fn record_interesting_child(&mut self, parent: PoolStorageIndex) {
+        self.sap1.record_interesting_child(parent);
+        self.sap2.record_interesting_child(parent);
+    }
+
+    #[coverage(off)]
+    fn code_coverage_sensor(&mut self) -> Option<&mut CodeCoverageSensor> {
+        if self.sap1.code_coverage_sensor().is_some() {
+            self.sap1.code_coverage_sensor()
+        } else {
+            self.sap2.code_coverage_sensor()
+        }
+    }
+}
+
+/// Mix the salt into the hash of some observations
+#[coverage(off)]
+fn salted_hash(hash: u64, salt: u8) -> u64 {
+    let mut hasher = AHasher::default();
+    hash.hash(&mut hasher);
+    salt.hash(&mut hasher);
+    hasher.finish()
 }
@@ -0,0 +1,223 @@
+use std::path::PathBuf;
+
+use crate::traits::{CorpusDelta, Pool, SaveToStatsFolder};
+use crate::{CompatibleWithObservations, PoolStorageIndex};
+
+/// A simpler interface for the pools that handle the observations of a
+/// [`CodeCoverageSensor`](crate::sensors_and_pools::CodeCoverageSensor), or of any other sensor whose
+/// observations are a list of counters `(index, value)`.
+///
+/// Wrap the pool in a [`CounterPoolAdapter`] to use it with such a sensor. The adapter implements
+/// [`CompatibleWithObservations`] and gives the observations to
+/// [`process_counters`](CounterPool::process_counters) in a normalised form, such that the pool does not
+/// need to handle unsorted or duplicated counters.
+///
+/// The meaning of each index is given by
+/// [`CodeCoverageSensor::counter_locations`](crate::sensors_and_pools::CodeCoverageSensor::counter_locations).
+///
+/// ```no_run
+/// use std::path::PathBuf;
+///
+/// use fuzzcheck::sensors_and_pools::stats::EmptyStats;
+/// use fuzzcheck::sensors_and_pools::{CodeCoverageSensor, CounterPool, CounterPoolAdapter};
+/// use fuzzcheck::{CorpusDelta, Pool, PoolStorageIndex, SaveToStatsFolder};
+///
+/// /// Keeps the first test case that reaches each function
+/// struct FunctionPool {
+///     function_of_counter: Vec<usize>,
+///     test_case_of_function: Vec<Option<PoolStorageIndex>>,
+///     test_cases: Vec<PoolStorageIndex>,
+///     next: usize,
+/// }
+/// impl Pool for FunctionPool {
+///     type Stats = EmptyStats;
+///     fn stats(&self) -> EmptyStats {
+///         EmptyStats
+///     }
+///     fn get_random_index(&mut self) -> Option<PoolStorageIndex> {
+///         self.next = self.next.wrapping_add(1);
+///         self.test_cases.get(self.next % self.test_cases.len().max(1)).copied()
+///     }
+/// }
+/// impl SaveToStatsFolder for FunctionPool {
+///     fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+///         vec![]
+///     }
+/// }
+/// impl CounterPool for FunctionPool {
+///     fn process_counters(&mut self, input_id: PoolStorageIndex, counters: &[(usize, u64)], _complexity: f64) -> Vec<CorpusDelta> {
+///         let mut is_interesting = false;
+///         for &(index, _) in counters {
+///             let function = self.function_of_counter[index];
+///             if self.test_case_of_function[function].is_none() {
+///                 self.test_case_of_function[function] = Some(input_id);
+///                 is_interesting = true;
+///             }
+///         }
+///         if !is_interesting {
+///             return vec![];
+///         }
+///         self.test_cases.push(input_id);
+///         vec![CorpusDelta::new(PathBuf::from("functions"), true, vec![])]
+///     }
+/// }
+///
+/// let sensor = CodeCoverageSensor::observing_only_files_from_current_dir();
+/// let mut functions = Vec::<String>::new();
+/// let function_of_counter = sensor
+///     .counter_locations()
+///     .into_iter()
+///     .map(|location| {
+///         if functions.last() != Some(&location.function) {
+///             functions.push(location.function);
+///         }
+///         functions.len() - 1
+///     })
+///     .collect();
+/// let pool = CounterPoolAdapter::new(FunctionPool {
+///     function_of_counter,
+///     test_case_of_function: vec![None; functions.len()],
+///     test_cases: vec![],
+///     next: 0,
+/// });
+/// ```
+pub trait CounterPool: Pool {
+    /// Process the counters activated by a test case.
+    ///
+    /// The counters are sorted by index, each index appears at most once, and no value is zero.
+    fn process_counters(
+        &mut self,
+        input_id: PoolStorageIndex,
+        counters: &[(usize, u64)],
+        complexity: f64,
+    ) -> Vec<CorpusDelta>;
+}
+
+/// Wraps a [`CounterPool`] to make it compatible with the observations of a
+/// [`CodeCoverageSensor`](crate::sensors_and_pools::CodeCoverageSensor), or of any sensor whose observations
+/// can be iterated as `&(usize, u64)`.
+///
+/// Before they are given to the pool, the counters whose value is zero are removed and the others are sorted by
+/// index. If an index appears several times, only its greatest value is kept.
+pub struct CounterPoolAdapter<P> {
+    pub pool: P,
+    counters: Vec<(usize, u64)>,
+}
+
+impl<P> CounterPoolAdapter<P>
+where
+    P: CounterPool,
+{
+    #[coverage(off)]
+    pub fn new(pool: P) -> Self {
+        Self { pool, counters: vec![] }
+    }
+}
+
+impl<P> Pool for CounterPoolAdapter<P>
+where
+    P: CounterPool,
+{
+    type Stats = P::Stats;
+
+    #[coverage(off)]
+    fn stats(&self) -> Self::Stats {
+        self.pool.stats()
+    }
+    #[coverage(off)]
+    fn get_random_index(&mut self) -> Option<PoolStorageIndex> {
+        self.pool.get_random_index()
+    }
+    #[coverage(off)]
+    fn weight(&self) -> f64 {
+        self.pool.weight()
+    }
+    #[coverage(off)]
+    fn record_interesting_child(&mut self, parent: PoolStorageIndex) {
+        self.pool.record_interesting_child(parent)
+    }
+}
+
+impl<P> SaveToStatsFolder for CounterPoolAdapter<P>
+where
+    P: CounterPool,
+{
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        self.pool.save_to_stats_folder()
+    }
+}
+
+impl<P, O> CompatibleWithObservations<O> for CounterPoolAdapter<P>
+where
+    P: CounterPool,
+    for<'a> &'a O: IntoIterator<Item = &'a (usize, u64)>,
+{
+    #[coverage(off)]
+    fn process(&mut self, input_id: PoolStorageIndex, observations: &O, complexity: f64) -> Vec<CorpusDelta> {
+        self.counters.clear();
+        self.counters.extend(observations.into_iter().filter(
+            #[coverage(off)]
+            |(_, value)| *value != 0,
+        ));
+        // the observations of the code coverage sensor are already sorted, which makes this cheap
+        self.counters.sort_unstable_by(
+            #[coverage(off)]
+            |x, y| x.0.cmp(&y.0).then(y.1.cmp(&x.1)),
+        );
+        self.counters.dedup_by_key(
+            #[coverage(off)]
+            |x| x.0,
+        );
+        self.pool.process_counters(input_id, &self.counters, complexity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sensors_and_pools::stats::EmptyStats;
+
+    struct RecordingPool {
+        processed: Vec<Vec<(usize, u64)>>,
+    }
+    impl Pool for RecordingPool {
+        type Stats = EmptyStats;
+        #[coverage(off)]
+        fn stats(&self) -> Self::Stats {
+            EmptyStats
+        }
+        #[coverage(off)]
+        fn get_random_index(&mut self) -> Option<PoolStorageIndex> {
+            None
+        }
+    }
+    impl SaveToStatsFolder for RecordingPool {
+        #[coverage(off)]
+        fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+            vec![]
+        }
+    }
+    impl CounterPool for RecordingPool {
+        #[coverage(off)]
+        fn process_counters(
+            &mut self,
+            _input_id: PoolStorageIndex,
+            counters: &[(usize, u64)],
+            _complexity: f64,
+        ) -> Vec<CorpusDelta> {
+            self.processed.push(counters.to_vec());
+            vec![]
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_counters_are_normalised() {
+        let mut pool = CounterPoolAdapter::new(RecordingPool { processed: vec![] });
+        let observations: Vec<(usize, u64)> = vec![(7, 1), (2, 3), (7, 4), (5, 0), (2, 1)];
+        pool.process(PoolStorageIndex::mock(0), &observations, 1.0);
+        pool.process(PoolStorageIndex::mock(1), &Vec::<(usize, u64)>::new(), 1.0);
+        assert_eq!(pool.pool.processed, vec![vec![(2, 3), (7, 4)], vec![]]);
+    }
+}
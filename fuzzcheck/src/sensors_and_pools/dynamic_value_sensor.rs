@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::observations_record::{decode_counters, encode_counters};
+use crate::{SaveToStatsFolder, Sensor};
+
+/// A custom sensor whose observations are given by an array of counters whose size is only known at runtime.
+///
+/// It is similar to the [`CodeCoverageSensor`](crate::sensors_and_pools::CodeCoverageSensor), but the counters
+/// are maintained by some other code, such as a C library with its own instrumentation. Its observations are the
+/// index and value of each counter that is not zero. They can therefore be handled by the same pools as the
+/// code coverage sensor, such as [`SimplestToActivateCounterPool`](crate::sensors_and_pools::SimplestToActivateCounterPool).
+///
+/// All counters are reset to zero before each run of the test function.
+///
+/// ```
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use fuzzcheck::sensors_and_pools::{DynamicValueSensor, SimplestToActivateCounterPool};
+///
+/// // the counters must live for the whole fuzzing session
+/// let nbr_counters = 1024;
+/// let counters: &'static [AtomicU64] = Box::leak((0..nbr_counters).map(|_| AtomicU64::new(0)).collect());
+///
+/// // then give `counters` to the code that updates them, for example:
+/// counters[28].fetch_add(1, Ordering::Relaxed);
+///
+/// let sensor = DynamicValueSensor::new(counters);
+/// let pool = SimplestToActivateCounterPool::new("counters", sensor.len());
+/// ```
+pub struct DynamicValueSensor {
+    counters: &'static [AtomicU64],
+}
+
+impl DynamicValueSensor {
+    #[coverage(off)]
+    pub fn new(counters: &'static [AtomicU64]) -> Self {
+        Self { counters }
+    }
+
+    /// Create a sensor observing the `len` counters starting at `counters`, for example
+    /// an array of `uint64_t` exported by a C library.
+    ///
+    /// ## Safety
+    /// `counters` must be valid for reads and writes of `len` consecutive `u64` values, aligned to
+    /// 8 bytes, for the rest of the program. The counters may be modified by other code while the
+    /// sensor exists, but only through atomic operations if it happens on a different thread.
+    #[coverage(off)]
+    pub unsafe fn from_raw_parts(counters: *mut u64, len: usize) -> Self {
+        // AtomicU64 has the same in-memory representation as u64
+        Self::new(std::slice::from_raw_parts(counters as *const AtomicU64, len))
+    }
+
+    /// The number of counters observed by the sensor
+    #[coverage(off)]
+    pub fn len(&self) -> usize {
+        self.counters.len()
+    }
+
+    #[coverage(off)]
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+}
+
+impl SaveToStatsFolder for DynamicValueSensor {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        vec![]
+    }
+}
+
+impl Sensor for DynamicValueSensor {
+    type Observations = Vec<(usize, u64)>;
+
+    #[coverage(off)]
+    fn start_recording(&mut self) {
+        for counter in self.counters {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    #[coverage(off)]
+    fn stop_recording(&mut self) {}
+
+    #[coverage(off)]
+    fn get_observations(&mut self) -> Self::Observations {
+        let mut observations = vec![];
+        for (i, counter) in self.counters.iter().enumerate() {
+            let value = counter.load(Ordering::Relaxed);
+            if value != 0 {
+                observations.push((i, value));
+            }
+        }
+        observations
+    }
+
+    #[coverage(off)]
+    fn get_and_record_observations(&mut self, record: &mut Vec<u8>) -> Self::Observations {
+        let observations = self.get_observations();
+        encode_counters(&observations, record);
+        observations
+    }
+
+    #[coverage(off)]
+    fn replay_observations(&self, record: &mut &[u8]) -> Option<Self::Observations> {
+        decode_counters(record, self.counters.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_dynamic_value_sensor() {
+        let counters: &'static mut [u64] = Box::leak(vec![0; 10].into_boxed_slice());
+        let ptr = counters.as_mut_ptr();
+        let mut sensor = unsafe { DynamicValueSensor::from_raw_parts(ptr, counters.len()) };
+        assert_eq!(sensor.len(), 10);
+
+        unsafe {
+            *ptr.add(3) = 7;
+        }
+        sensor.start_recording();
+        unsafe {
+            *ptr.add(2) = 1;
+            *ptr.add(9) = 4;
+        }
+        sensor.stop_recording();
+        assert_eq!(sensor.get_observations(), vec![(2, 1), (9, 4)]);
+    }
+}
@@ -1,5 +1,6 @@
 use std::marker::PhantomData;
 
+use crate::sensors_and_pools::CodeCoverageSensor;
 use crate::{SaveToStatsFolder, Sensor};
 
 /// The result of [`sensor.map(..)`](crate::SensorExt::map)
@@ -10,6 +11,8 @@ where
 {
     sensor: S,
     map_f: F,
+    /// The hash of the observations of the wrapped sensor from which the last observations were mapped
+    last_hash: Option<u64>,
     _phantom: PhantomData<ToObservations>,
 }
 
@@ -23,6 +26,7 @@ where
         Self {
             sensor,
             map_f,
+            last_hash: None,
             _phantom: PhantomData,
         }
     }
@@ -58,8 +62,35 @@ where
     #[coverage(off)]
     fn get_observations(&mut self) -> Self::Observations {
         let observations = self.sensor.get_observations();
+        self.last_hash = self.sensor.hash_observations(&observations);
         (self.map_f)(observations)
     }
+
+    /// The hash of the observations of the wrapped sensor from which the given observations were mapped.
+    ///
+    /// Since the mapping function is deterministic, identical observations of the wrapped sensor are mapped to
+    /// identical observations. It must only be called on the last observations returned by `get_observations`.
+    #[coverage(off)]
+    fn hash_observations(&self, _observations: &Self::Observations) -> Option<u64> {
+        self.last_hash
+    }
+
+    #[coverage(off)]
+    fn get_and_record_observations(&mut self, record: &mut Vec<u8>) -> Self::Observations {
+        let observations = self.sensor.get_and_record_observations(record);
+        self.last_hash = self.sensor.hash_observations(&observations);
+        (self.map_f)(observations)
+    }
+
+    #[coverage(off)]
+    fn replay_observations(&self, record: &mut &[u8]) -> Option<Self::Observations> {
+        self.sensor.replay_observations(record).map(&self.map_f)
+    }
+
+    #[coverage(off)]
+    fn code_coverage_sensor(&mut self) -> Option<&mut CodeCoverageSensor> {
+        self.sensor.code_coverage_sensor()
+    }
 }
 pub trait WrapperSensor: Sensor {
     type Wrapped: Sensor;
@@ -0,0 +1,187 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use crate::traits::{CorpusDelta, Pool, SaveToStatsFolder, Stats};
+use crate::{CSVField, CompatibleWithObservations, PoolStorageIndex, ToCSV};
+
+#[derive(Clone, Copy)]
+struct Input {
+    input_id: PoolStorageIndex,
+    value: u64,
+    complexity: f64,
+}
+
+/// A pool that keeps the test case performing the most allocations and the one allocating the most bytes.
+///
+/// It is compatible with the observations of an [`AllocationSensor`](crate::sensors_and_pools::AllocationSensor),
+/// i.e. `(number_of_allocations, number_of_allocated_bytes)`. The two test cases are saved in the
+/// `<name>/blocks` and `<name>/bytes` subfolders of the output corpus, respectively.
+///
+/// Use [`FuzzerBuilder3::allocation_sensor_and_pool`](crate::builder::FuzzerBuilder3::allocation_sensor_and_pool)
+/// to pair it with an `AllocationSensor` in a fuzz test. Note that the global allocator must be a
+/// [`CountingAllocator`](crate::sensors_and_pools::CountingAllocator) for the sensor to work.
+pub struct MaximiseAllocationsPool {
+    name: String,
+    most_blocks: Option<Input>,
+    most_bytes: Option<Input>,
+    rng: fastrand::Rng,
+}
+
+impl MaximiseAllocationsPool {
+    #[coverage(off)]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            most_blocks: None,
+            most_bytes: None,
+            rng: fastrand::Rng::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MaximiseAllocationsPoolStats {
+    name: String,
+    most_blocks: u64,
+    most_bytes: u64,
+}
+impl Display for MaximiseAllocationsPoolStats {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}(blocks: {}, bytes: {})",
+            self.name, self.most_blocks, self.most_bytes
+        )
+    }
+}
+impl ToCSV for MaximiseAllocationsPoolStats {
+    #[coverage(off)]
+    fn csv_headers(&self) -> Vec<CSVField> {
+        vec![
+            CSVField::String(format!("{}-blocks", self.name)),
+            CSVField::String(format!("{}-bytes", self.name)),
+        ]
+    }
+    #[coverage(off)]
+    fn to_csv_record(&self) -> Vec<CSVField> {
+        vec![
+            CSVField::Integer(self.most_blocks as isize),
+            CSVField::Integer(self.most_bytes as isize),
+        ]
+    }
+}
+impl Stats for MaximiseAllocationsPoolStats {}
+
+impl Pool for MaximiseAllocationsPool {
+    type Stats = MaximiseAllocationsPoolStats;
+
+    #[coverage(off)]
+    fn stats(&self) -> Self::Stats {
+        let value = #[coverage(off)]
+        |input: &Option<Input>| {
+            input
+                .map(
+                    #[coverage(off)]
+                    |input| input.value,
+                )
+                .unwrap_or_default()
+        };
+        MaximiseAllocationsPoolStats {
+            name: self.name.clone(),
+            most_blocks: value(&self.most_blocks),
+            most_bytes: value(&self.most_bytes),
+        }
+    }
+    #[coverage(off)]
+    fn get_random_index(&mut self) -> Option<PoolStorageIndex> {
+        let (first, second) = if self.rng.bool() {
+            (self.most_blocks, self.most_bytes)
+        } else {
+            (self.most_bytes, self.most_blocks)
+        };
+        first.or(second).map(
+            #[coverage(off)]
+            |input| input.input_id,
+        )
+    }
+}
+
+impl SaveToStatsFolder for MaximiseAllocationsPool {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        vec![]
+    }
+}
+
+/// Replace `current_best` by the new input if it is better, and return the corresponding corpus delta
+#[coverage(off)]
+fn process_value(current_best: &mut Option<Input>, new: Input, path: PathBuf) -> Option<CorpusDelta> {
+    let is_interesting = if let Some(best) = current_best {
+        new.value > best.value || (new.value == best.value && new.complexity < best.complexity)
+    } else {
+        true
+    };
+    if !is_interesting {
+        return None;
+    }
+    let remove = current_best
+        .map(
+            #[coverage(off)]
+            |best| vec![best.input_id],
+        )
+        .unwrap_or_default();
+    *current_best = Some(new);
+    Some(CorpusDelta {
+        path,
+        add: true,
+        remove,
+        tags: vec![],
+    })
+}
+
+impl CompatibleWithObservations<(u64, u64)> for MaximiseAllocationsPool {
+    #[coverage(off)]
+    fn process(&mut self, input_id: PoolStorageIndex, observations: &(u64, u64), complexity: f64) -> Vec<CorpusDelta> {
+        let &(blocks, bytes) = observations;
+        let path = PathBuf::new().join(&self.name);
+        let blocks = Input {
+            input_id,
+            value: blocks,
+            complexity,
+        };
+        let bytes = Input {
+            input_id,
+            value: bytes,
+            complexity,
+        };
+        process_value(&mut self.most_blocks, blocks, path.join("blocks"))
+            .into_iter()
+            .chain(process_value(&mut self.most_bytes, bytes, path.join("bytes")))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_maximise_allocations_pool() {
+        let mut pool = MaximiseAllocationsPool::new("alloc");
+        assert_eq!(pool.process(PoolStorageIndex::mock(0), &(10, 100), 1.0).len(), 2);
+        // more blocks, fewer bytes
+        let deltas = pool.process(PoolStorageIndex::mock(1), &(20, 50), 1.0);
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].path, PathBuf::new().join("alloc").join("blocks"));
+        assert_eq!(deltas[0].remove, vec![PoolStorageIndex::mock(0)]);
+        // same number of bytes, but simpler
+        let deltas = pool.process(PoolStorageIndex::mock(2), &(1, 100), 0.5);
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].path, PathBuf::new().join("alloc").join("bytes"));
+
+        let stats = pool.stats();
+        assert_eq!((stats.most_blocks, stats.most_bytes), (20, 100));
+    }
+}
@@ -1,5 +1,5 @@
 use std::fmt::{Debug, Display};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use ahash::AHashSet;
 use nu_ansi_term::Color;
@@ -60,6 +60,12 @@ struct Input {
 /// It is [compatible with](crate::CompatibleWithObservations) the following sensors:
 /// * [`CodeCoverageSensor`](crate::sensors_and_pools::CodeCoverageSensor)
 /// * any other sensor whose [observations](crate::Sensor::Observations) are given by an iterator of `(usize, u64)`
+///
+/// The test case maximising each counter is given by [`best_input_for_counter`](MaximiseEachCounterPool::best_input_for_counter).
+/// At the end of the fuzz test, the same information is written to `<name>.json` in the stats folder, as a list of
+/// objects with the fields `counter`, `value`, `complexity`, and `input`, the [`PoolStorageIndex`] of the test case.
+/// These indices can be matched with the names of the files in the output corpus using the `world.json` file of the
+/// same stats folder.
 pub struct MaximiseEachCounterPool {
     name: String,
     complexities: Vec<f64>,
@@ -103,6 +109,36 @@ impl MaximiseEachCounterPool {
     }
 }
 
+impl MaximiseEachCounterPool {
+    /// The test case with the highest value for the given counter, along with this value, if any test case
+    /// activated the counter
+    #[coverage(off)]
+    pub fn best_input_for_counter(&self, counter: usize) -> Option<(PoolStorageIndex, u64)> {
+        let key = (*self.best_input_for_counter.get(counter)?)?;
+        Some((self.inputs[key].idx, self.highest_counts[counter]))
+    }
+
+    /// Iterate over the counters activated by a test case of the pool, in increasing order, along with the test
+    /// case with the highest value for each of them and this value
+    #[coverage(off)]
+    pub fn best_inputs(&self) -> impl Iterator<Item = (usize, PoolStorageIndex, u64)> + '_ {
+        self.best_input_for_counter.iter().enumerate().filter_map(
+            #[coverage(off)]
+            |(counter, key)| Some((counter, self.inputs[(*key)?].idx, self.highest_counts[counter])),
+        )
+    }
+}
+
+/// The test case with the highest value for a counter, as saved to the stats folder
+#[cfg(feature = "serde_json_serializer")]
+#[derive(serde::Serialize)]
+struct BestInputForCounter {
+    counter: usize,
+    value: u64,
+    complexity: f64,
+    input: PoolStorageIndex,
+}
+
 impl Pool for MaximiseEachCounterPool {
     type Stats = MaximiseEachCounterPoolStats;
 
@@ -130,8 +166,27 @@ impl Pool for MaximiseEachCounterPool {
 
 impl SaveToStatsFolder for MaximiseEachCounterPool {
     #[coverage(off)]
-    fn save_to_stats_folder(&self) -> Vec<(std::path::PathBuf, Vec<u8>)> {
-        vec![]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "serde_json_serializer")] {
+                let best_inputs = self
+                    .best_inputs()
+                    .map(
+                        #[coverage(off)]
+                        |(counter, input, value)| BestInputForCounter {
+                            counter,
+                            value,
+                            complexity: self.complexities[counter],
+                            input,
+                        },
+                    )
+                    .collect::<Vec<_>>();
+                let content = serde_json::to_vec(&best_inputs).unwrap();
+                vec![(PathBuf::new().join(&self.name).with_extension("json"), content)]
+            } else {
+                vec![]
+            }
+        }
     }
 }
 
@@ -230,6 +285,7 @@ where
             path: Path::new(&self.name).to_path_buf(),
             add: true,
             remove: removed_idxs,
+            tags: vec![],
         }]
     }
 }
@@ -239,7 +295,7 @@ mod tests {
     use std::collections::HashMap;
 
     use super::MaximiseEachCounterPool;
-    use crate::traits::{CompatibleWithObservations, Pool};
+    use crate::traits::{CompatibleWithObservations, Pool, SaveToStatsFolder};
     use crate::PoolStorageIndex;
 
     #[test]
@@ -330,4 +386,39 @@ mod tests {
         }
         println!("{:?}", map);
     }
+
+    #[test]
+    fn test_best_input_for_counter() {
+        let mut pool = MaximiseEachCounterPool::new("c", 5);
+        assert_eq!(pool.best_input_for_counter(1), None);
+
+        let _ = pool.process(PoolStorageIndex::mock(0), &[(1, 4), (2, 1)], 1.0);
+        let _ = pool.process(PoolStorageIndex::mock(1), &[(2, 3)], 2.0);
+        assert_eq!(pool.best_input_for_counter(1), Some((PoolStorageIndex::mock(0), 4)));
+        assert_eq!(pool.best_input_for_counter(2), Some((PoolStorageIndex::mock(1), 3)));
+        assert_eq!(pool.best_input_for_counter(3), None);
+        assert_eq!(pool.best_input_for_counter(10), None);
+
+        // the same value with a lower complexity replaces the previous test case
+        let _ = pool.process(PoolStorageIndex::mock(2), &[(1, 4)], 0.5);
+        assert_eq!(
+            pool.best_inputs().collect::<Vec<_>>(),
+            vec![(1, PoolStorageIndex::mock(2), 4), (2, PoolStorageIndex::mock(1), 3)]
+        );
+
+        #[cfg(feature = "serde_json_serializer")]
+        {
+            let saved = pool.save_to_stats_folder();
+            assert_eq!(saved.len(), 1);
+            assert_eq!(saved[0].0, std::path::Path::new("c.json"));
+            let content: serde_json::Value = serde_json::from_slice(&saved[0].1).unwrap();
+            assert_eq!(
+                content,
+                serde_json::json!([
+                    { "counter": 1, "value": 4, "complexity": 0.5, "input": 2 },
+                    { "counter": 2, "value": 3, "complexity": 2.0, "input": 1 },
+                ])
+            );
+        }
+    }
 }
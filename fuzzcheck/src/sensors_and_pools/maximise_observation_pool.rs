@@ -114,6 +114,7 @@ where
             } else {
                 vec![]
             },
+            tags: vec![],
         };
         let new = Input { input_id, complexity };
         self.current_best = Some((observations, new));
@@ -4,26 +4,41 @@ Types implementing the [Sensor](crate::Sensor) and [Pool](crate::Pool) traits.
 
 mod allocations_sensor;
 mod and_sensor_and_pool;
+mod counter_pool;
+mod dynamic_value_sensor;
 mod map_sensor;
+mod maximise_allocations_pool;
 mod maximise_each_counter_pool;
 mod maximise_observation_pool;
 mod most_n_diverse_pool;
 mod noop_sensor;
+mod output_diversity_pool;
+mod sensor_suite;
 mod simplest_to_activate_counter_pool;
+mod stack_depth_sensor;
 mod static_value_sensor;
 mod test_failure_pool;
+mod time_sensor;
 mod unique_values_pool;
 mod unit_pool;
+mod user_event_sensor;
 
 #[doc(inline)]
 pub use allocations_sensor::{AllocationSensor, CountingAllocator};
 #[doc(inline)]
 pub use and_sensor_and_pool::{AndPool, AndSensor, AndSensorAndPool, DifferentObservations, SameObservations};
 #[doc(inline)]
+#[doc(inline)]
+pub use counter_pool::{CounterPool, CounterPoolAdapter};
+#[doc(inline)]
+pub use dynamic_value_sensor::DynamicValueSensor;
+#[doc(inline)]
 pub use map_sensor::MapSensor;
 #[doc(inline)]
 pub use map_sensor::WrapperSensor;
 #[doc(inline)]
+pub use maximise_allocations_pool::MaximiseAllocationsPool;
+#[doc(inline)]
 pub use maximise_each_counter_pool::MaximiseEachCounterPool;
 #[doc(inline)]
 pub use maximise_observation_pool::MaximiseObservationPool;
@@ -32,7 +47,15 @@ pub use most_n_diverse_pool::MostNDiversePool;
 #[doc(inline)]
 pub use noop_sensor::NoopSensor;
 #[doc(inline)]
-pub use simplest_to_activate_counter_pool::SimplestToActivateCounterPool;
+pub use output_diversity_pool::{record_output, OutputDiversityPool, OutputSensor};
+#[doc(inline)]
+pub use sensor_suite::{SensorSuite, SensorSuiteKey, SensorSuiteObservations, SensorSuitePool};
+#[doc(inline)]
+pub use simplest_to_activate_counter_pool::{
+    EnergyInfo, EnergySchedule, EvictionPolicy, SimplestToActivateCounterPool,
+};
+#[doc(inline)]
+pub use stack_depth_sensor::{record_stack_depth, StackDepthPool, StackDepthSensor};
 #[doc(inline)]
 pub use static_value_sensor::StaticValueSensor;
 #[doc(inline)]
@@ -41,14 +64,22 @@ pub use test_failure_pool::TestFailure;
 pub use test_failure_pool::TestFailurePool;
 #[doc(inline)]
 pub use test_failure_pool::TestFailureSensor;
-pub(crate) use test_failure_pool::TEST_FAILURE;
+pub(crate) use test_failure_pool::{TEST_FAILURE, TIMEOUT_DISPLAY};
 #[doc(inline)]
-pub use unique_values_pool::UniqueValuesPool;
+pub use time_sensor::{MaximiseTimePool, TimeSensor};
+#[doc(inline)]
+pub use unique_values_pool::{log2_bucket, UniqueValuesPool};
 #[doc(inline)]
 pub use unit_pool::UnitPool;
+#[doc(inline)]
+pub use user_event_sensor::{record_assertion_violation, record_event, UserEventSensor};
 
 #[doc(inline)]
 pub use crate::code_coverage_sensor::CodeCoverageSensor;
+#[doc(inline)]
+pub use crate::code_coverage_sensor::CoverageFilter;
+#[doc(inline)]
+pub use crate::code_coverage_sensor::{CodeRegion, CounterLocation};
 use crate::{Pool, Sensor};
 
 /// A trait for convenience methods automatically implemented for all types that conform to Pool.
@@ -110,14 +141,20 @@ pub mod stats {
     #[doc(inline)]
     pub use super::and_sensor_and_pool::AndPoolStats;
     #[doc(inline)]
+    pub use super::maximise_allocations_pool::MaximiseAllocationsPoolStats;
+    #[doc(inline)]
     pub use super::maximise_each_counter_pool::MaximiseEachCounterPoolStats;
     #[doc(inline)]
     pub use super::most_n_diverse_pool::MostNDiversePoolStats;
     #[doc(inline)]
+    pub use super::output_diversity_pool::OutputDiversityPoolStats;
+    #[doc(inline)]
     pub use super::simplest_to_activate_counter_pool::UniqueCoveragePoolStats;
     #[doc(inline)]
     pub use super::test_failure_pool::TestFailurePoolStats;
     #[doc(inline)]
+    pub use super::time_sensor::MaximiseTimePoolStats;
+    #[doc(inline)]
     pub use super::unique_values_pool::UniqueValuesPoolStats;
     use crate::traits::Stats;
     use crate::{CSVField, ToCSV};
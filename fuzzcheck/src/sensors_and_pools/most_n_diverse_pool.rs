@@ -2,7 +2,6 @@ use std::cmp::Ordering;
 use std::fmt::{self, Display, Formatter};
 use std::path::PathBuf;
 
-use crate::bitset::FixedBitSet;
 use crate::fenwick_tree::FenwickTree;
 use crate::traits::{CorpusDelta, Pool, SaveToStatsFolder, Stats};
 use crate::{CSVField, CompatibleWithObservations, PoolStorageIndex, ToCSV};
@@ -10,8 +9,8 @@ use crate::{CSVField, CompatibleWithObservations, PoolStorageIndex, ToCSV};
 #[derive(Clone)]
 struct Input {
     nbr_unique_counters: usize,
-    unique_counters: FixedBitSet,
-    counters: FixedBitSet,
+    /// The sorted indices of the counters activated by the input
+    counters: Vec<usize>,
     pool_idx: PoolStorageIndex,
     cplx: f64,
 }
@@ -19,16 +18,30 @@ struct Input {
 /// A pool that tries to find N test cases which, combined, activate the most counters of a sensor
 ///
 /// A counter is a tuple `(index: usize, value: u64)`. It is “activated” when its value is != 0.
+///
+/// The pool keeps track of how many of its test cases activate each counter. The counters that are
+/// activated by a single test case are “unique” to it, and a new test case replaces an existing one
+/// if, after the swap, the unique counters it would bring are more numerous (or equally numerous but simpler).
+///
+/// This bookkeeping is updated incrementally: processing an observation costs time proportional to
+/// the number of counters it activates, and swapping a test case costs time proportional to the number
+/// of counters activated by the two test cases involved. It never depends on the total number of
+/// counters of the sensor.
 pub struct MostNDiversePool {
     name: String,
     max_len: usize,
-    nbr_counters: usize,
     inputs: Vec<Input>,
-    all_counters: FixedBitSet,
+    /// For each counter, the number of inputs of the pool that activate it
+    counter_occurrences: Vec<u32>,
+    /// For each counter activated by exactly one input, the index of that input in `inputs`
+    counter_owner: Vec<usize>,
+    nbr_activated_counters: usize,
     worst_input_idx: Option<usize>,
     fenwick_tree: FenwickTree,
     rng: fastrand::Rng,
-    cache: FixedBitSet,
+    /// For each input, the number of its unique counters that are also activated by the observations
+    /// being processed. Kept as a field to reuse its allocation.
+    marginal_gains: Vec<usize>,
 }
 
 #[derive(Clone)]
@@ -42,13 +55,14 @@ impl MostNDiversePool {
         Self {
             name: name.to_owned(),
             max_len,
-            nbr_counters,
             inputs: vec![],
-            all_counters: FixedBitSet::new(),
+            counter_occurrences: vec![0; nbr_counters],
+            counter_owner: vec![0; nbr_counters],
+            nbr_activated_counters: 0,
             worst_input_idx: None,
             rng: fastrand::Rng::new(),
             fenwick_tree: FenwickTree::new(vec![]),
-            cache: FixedBitSet::new(),
+            marginal_gains: vec![],
         }
     }
 }
@@ -60,7 +74,7 @@ impl Pool for MostNDiversePool {
     fn stats(&self) -> Self::Stats {
         MostNDiversePoolStats {
             name: self.name.clone(),
-            counters: self.all_counters.count_ones(),
+            counters: self.nbr_activated_counters,
         }
     }
     #[coverage(off)]
@@ -77,47 +91,43 @@ impl SaveToStatsFolder for MostNDiversePool {
     }
 }
 
-pub struct ObservationState {
-    counters: FixedBitSet,
-    nbr_new_counters: usize,
+/// Where a new input should be placed in the pool
+enum Slot {
+    Push,
+    Replace(usize),
 }
 
 impl MostNDiversePool {
+    /// Decide where to place an input activating `nbr_new_counters` counters that no other input of the pool
+    /// activates, given that `self.marginal_gains` was computed for its observations.
+    ///
+    /// Returns `None` if the input is not interesting.
     #[coverage(off)]
-    fn is_interesting(&self, state: &ObservationState, input_complexity: f64) -> bool {
-        let ObservationState {
-            counters,
-            nbr_new_counters,
-        } = state;
-        if self.inputs.len() < self.max_len && *nbr_new_counters > 0 {
-            return true;
+    fn choose_slot(&self, nbr_new_counters: usize, input_complexity: f64) -> Option<Slot> {
+        if self.inputs.len() < self.max_len && nbr_new_counters > 0 {
+            return Some(Slot::Push);
         }
-        if let Some(worst_input) = self.worst_input_idx.map(
-            #[coverage(off)]
-            |idx| &self.inputs[idx],
-        ) && ((*nbr_new_counters > worst_input.nbr_unique_counters)
-            || (*nbr_new_counters == worst_input.nbr_unique_counters && worst_input.cplx > input_complexity))
-        {
-            return true;
+        if let Some(worst_input_idx) = self.worst_input_idx {
+            let worst_input = &self.inputs[worst_input_idx];
+            if (nbr_new_counters > worst_input.nbr_unique_counters)
+                || (nbr_new_counters == worst_input.nbr_unique_counters && worst_input.cplx > input_complexity)
+            {
+                return Some(Slot::Replace(worst_input_idx));
+            }
         }
+        for (idx, input) in self.inputs.iter().enumerate() {
+            if nbr_new_counters > 0 || input.cplx > input_complexity {
+                // the unique counters of the new input if it replaced this one
+                let nbr_unique_counters = self.marginal_gains[idx] + nbr_new_counters;
 
-        let mut common_unique_counters = FixedBitSet::with_capacity(counters.len());
-        for input in &self.inputs {
-            if *nbr_new_counters > 0 || input.cplx > input_complexity {
-                common_unique_counters.clone_from(counters);
-                common_unique_counters.intersect_with(&input.unique_counters);
-                let common_uniq_counters = common_unique_counters.count_ones();
-                let nbr_new_counters = common_uniq_counters + nbr_new_counters;
-
-                if (nbr_new_counters > input.nbr_unique_counters)
-                    || (nbr_new_counters == input.nbr_unique_counters && input.cplx > input_complexity)
+                if (nbr_unique_counters > input.nbr_unique_counters)
+                    || (nbr_unique_counters == input.nbr_unique_counters && input.cplx > input_complexity)
                 {
-                    return true;
+                    return Some(Slot::Replace(idx));
                 }
             }
         }
-
-        false
+        None
     }
 }
 
@@ -127,120 +137,128 @@ where
 {
     #[coverage(off)]
     fn process(&mut self, input_id: PoolStorageIndex, observations: &O, complexity: f64) -> Vec<CorpusDelta> {
-        let mut state = ObservationState {
-            counters: FixedBitSet::with_capacity(self.nbr_counters + 1),
-            nbr_new_counters: 0,
-        };
-
-        for (index, _counter) in observations.into_iter() {
-            state.counters.insert(*index);
+        let mut counters = observations
+            .into_iter()
+            .map(
+                #[coverage(off)]
+                |(index, _counter)| *index,
+            )
+            .collect::<Vec<_>>();
+        counters.sort_unstable();
+        counters.dedup();
+        if let Some(&max_index) = counters.last()
+            && max_index >= self.counter_occurrences.len()
+        {
+            self.counter_occurrences.resize(max_index + 1, 0);
+            self.counter_owner.resize(max_index + 1, 0);
         }
 
-        self.cache.clone_from(&state.counters);
-        // let mut unique_counters = counters.clone();
-        self.cache.difference_with(&self.all_counters);
-
-        state.nbr_new_counters = self.cache.count_ones();
-        self.cache.clear();
+        self.marginal_gains.clear();
+        self.marginal_gains.resize(self.inputs.len(), 0);
+        let mut nbr_new_counters = 0;
+        for &counter in &counters {
+            match self.counter_occurrences[counter] {
+                0 => nbr_new_counters += 1,
+                1 => self.marginal_gains[self.counter_owner[counter]] += 1,
+                _ => {}
+            }
+        }
 
-        if !self.is_interesting(&state, complexity) {
+        let Some(slot) = self.choose_slot(nbr_new_counters, complexity) else {
             return vec![];
-        }
-        let ObservationState {
-            counters,
-            nbr_new_counters,
-        } = state;
-        let unique_counters = {
-            let mut x = counters.clone();
-            x.difference_with(&self.all_counters);
-            x
         };
-        assert!(unique_counters.count_ones() == nbr_new_counters);
 
         let new_input = Input {
-            nbr_unique_counters: nbr_new_counters,
-            unique_counters,
-            counters: counters.clone(),
+            nbr_unique_counters: 0,
+            counters,
             pool_idx: input_id,
             cplx: complexity,
         };
-
-        if self.inputs.len() < self.max_len && nbr_new_counters > 0 {
-            self.inputs.push(new_input);
-            self.recompute_state_from_inputs_vec();
-            return vec![CorpusDelta {
-                path: PathBuf::new().join(&self.name),
-                add: true,
-                remove: vec![],
-            }];
-        }
-
-        if let Some(worst_input) = self.worst_input_idx.map(
-            #[coverage(off)]
-            |idx| &mut self.inputs[idx],
-        ) && ((nbr_new_counters > worst_input.nbr_unique_counters)
-            || (nbr_new_counters == worst_input.nbr_unique_counters && worst_input.cplx > complexity))
-        {
-            let worst_input_data = worst_input.pool_idx;
-            *worst_input = new_input;
-            self.recompute_state_from_inputs_vec();
-            return vec![CorpusDelta {
-                path: PathBuf::new().join(&self.name),
-                add: true,
-                remove: vec![worst_input_data],
-            }];
-        }
-
-        let mut common_unique_counters = FixedBitSet::with_capacity(counters.len());
-        for input in &mut self.inputs {
-            if nbr_new_counters > 0 || input.cplx > complexity {
-                common_unique_counters.clone_from(&counters);
-                common_unique_counters.intersect_with(&input.unique_counters);
-                let common_uniq_counters = common_unique_counters.count_ones();
-                let nbr_new_counters = common_uniq_counters + nbr_new_counters;
-
-                if (nbr_new_counters > input.nbr_unique_counters)
-                    || (nbr_new_counters == input.nbr_unique_counters && input.cplx > complexity)
-                {
-                    let input_data = input.pool_idx;
-                    *input = new_input;
-                    self.recompute_state_from_inputs_vec();
-                    return vec![CorpusDelta {
-                        path: PathBuf::new().join(&self.name),
-                        add: true,
-                        remove: vec![input_data],
-                    }];
-                }
+        let remove = match slot {
+            Slot::Push => {
+                self.inputs.push(new_input);
+                self.add_counters_of_input(self.inputs.len() - 1);
+                vec![]
             }
-        }
-
-        unreachable!()
+            Slot::Replace(idx) => {
+                self.remove_counters_of_input(idx);
+                let old_input = std::mem::replace(&mut self.inputs[idx], new_input);
+                self.add_counters_of_input(idx);
+                vec![old_input.pool_idx]
+            }
+        };
+        self.update_worst_input_and_weights();
+
+        vec![CorpusDelta {
+            path: PathBuf::new().join(&self.name),
+            add: true,
+            remove,
+            tags: vec![],
+        }]
     }
 }
 
 impl MostNDiversePool {
+    /// Account for the counters of the input at `idx`, which was just placed in the pool
     #[coverage(off)]
-    fn recompute_state_from_inputs_vec(&mut self) {
-        let mut all_counters = FixedBitSet::new();
-        let mut unique_counters = FixedBitSet::new();
-        for input in &self.inputs {
-            for bit in input.counters.ones() {
-                if !all_counters.contains(bit) {
-                    unique_counters.grow(bit * 2 + 1);
-                    unique_counters.insert(bit);
-                } else if unique_counters.contains(bit) {
-                    unique_counters.toggle(bit);
+    fn add_counters_of_input(&mut self, idx: usize) {
+        let counters = std::mem::take(&mut self.inputs[idx].counters);
+        let mut nbr_unique_counters = 0;
+        for &counter in &counters {
+            let occurrences = &mut self.counter_occurrences[counter];
+            *occurrences += 1;
+            match *occurrences {
+                1 => {
+                    self.counter_owner[counter] = idx;
+                    self.nbr_activated_counters += 1;
+                    nbr_unique_counters += 1;
+                }
+                2 => {
+                    // the counter is no longer unique to its previous owner
+                    self.inputs[self.counter_owner[counter]].nbr_unique_counters -= 1;
                 }
+                _ => {}
             }
-            all_counters.union_with(&input.counters);
         }
-        self.all_counters = all_counters;
+        self.inputs[idx].counters = counters;
+        self.inputs[idx].nbr_unique_counters = nbr_unique_counters;
+    }
 
-        for input in &mut self.inputs {
-            input.unique_counters.clone_from(&input.counters);
-            input.unique_counters.intersect_with(&unique_counters);
-            input.nbr_unique_counters = input.unique_counters.count_ones();
+    /// Stop accounting for the counters of the input at `idx`, which is about to be replaced
+    #[coverage(off)]
+    fn remove_counters_of_input(&mut self, idx: usize) {
+        let counters = std::mem::take(&mut self.inputs[idx].counters);
+        for &counter in &counters {
+            let occurrences = &mut self.counter_occurrences[counter];
+            *occurrences -= 1;
+            match *occurrences {
+                0 => {
+                    self.nbr_activated_counters -= 1;
+                }
+                1 => {
+                    // the counter becomes unique to the only other input that activates it
+                    let owner = self
+                        .inputs
+                        .iter()
+                        .enumerate()
+                        .position(
+                            #[coverage(off)]
+                            |(other_idx, other)| other_idx != idx && other.counters.binary_search(&counter).is_ok(),
+                        )
+                        .unwrap();
+                    self.counter_owner[counter] = owner;
+                    self.inputs[owner].nbr_unique_counters += 1;
+                }
+                _ => {}
+            }
         }
+        self.inputs[idx].counters = counters;
+        self.inputs[idx].nbr_unique_counters = 0;
+    }
+
+    /// The pool holds at most `max_len` inputs, so this is cheap compared to updating the counters
+    #[coverage(off)]
+    fn update_worst_input_and_weights(&mut self) {
         self.worst_input_idx = self
             .inputs
             .iter()
@@ -289,8 +307,14 @@ impl Stats for MostNDiversePoolStats {}
 
 #[cfg(test)]
 mod tests {
+    extern crate test;
+
+    use test::Bencher;
+
     use super::*;
 
+    const NBR_COUNTERS: usize = 200_000;
+
     #[test]
     #[coverage(off)]
     fn test_most_n_diverse_pool() {
@@ -309,6 +333,138 @@ mod tests {
         run(&mut pool, vec![1, 2, 3], 10.0);
     }
 
+    /// Compare the incrementally updated state of the pool with the one computed from scratch
+    #[coverage(off)]
+    fn assert_state_is_consistent(pool: &MostNDiversePool) {
+        let mut occurrences = vec![0u32; pool.counter_occurrences.len()];
+        for input in &pool.inputs {
+            for &counter in &input.counters {
+                occurrences[counter] += 1;
+            }
+        }
+        assert_eq!(occurrences, pool.counter_occurrences);
+        assert_eq!(
+            occurrences
+                .iter()
+                .filter(
+                    #[coverage(off)]
+                    |&&x| x > 0
+                )
+                .count(),
+            pool.nbr_activated_counters
+        );
+        for (idx, input) in pool.inputs.iter().enumerate() {
+            let unique_counters = input
+                .counters
+                .iter()
+                .filter(
+                    #[coverage(off)]
+                    |&&counter| occurrences[counter] == 1,
+                )
+                .collect::<Vec<_>>();
+            assert_eq!(unique_counters.len(), input.nbr_unique_counters);
+            for &counter in unique_counters {
+                assert_eq!(pool.counter_owner[counter], idx);
+            }
+        }
+        if let Some(worst_input_idx) = pool.worst_input_idx {
+            let worst_input = &pool.inputs[worst_input_idx];
+            assert!(pool.inputs.iter().all(
+                #[coverage(off)]
+                |input| (input.nbr_unique_counters, -input.cplx)
+                    >= (worst_input.nbr_unique_counters, -worst_input.cplx)
+            ));
+        } else {
+            assert!(pool.inputs.is_empty());
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_incremental_state_is_consistent() {
+        let rng = fastrand::Rng::with_seed(0);
+        let mut pool = MostNDiversePool::new("diverse8", 8, 100);
+        for i in 0..10_000 {
+            let len = rng.usize(..20);
+            let observations = (0..len)
+                .map(
+                    #[coverage(off)]
+                    |_| (rng.usize(..120), 1u64),
+                )
+                .collect::<Vec<_>>();
+            let deltas = pool.process(PoolStorageIndex::mock(i), &observations, rng.f64() * 100.0);
+            for delta in deltas {
+                assert!(delta.remove.len() <= 1);
+            }
+            assert!(pool.inputs.len() <= 8);
+            assert_state_is_consistent(&pool);
+        }
+    }
+
+    /// Fill a pool with `max_len` inputs activating disjoint ranges of `NBR_COUNTERS / max_len` counters
+    #[coverage(off)]
+    fn full_pool(max_len: usize) -> (MostNDiversePool, Vec<Vec<(usize, u64)>>) {
+        let mut pool = MostNDiversePool::new("diverse", max_len, NBR_COUNTERS);
+        let chunk_len = NBR_COUNTERS / max_len;
+        let observations = (0..max_len)
+            .map(
+                #[coverage(off)]
+                |i| {
+                    (i * chunk_len..(i + 1) * chunk_len)
+                        .map(
+                            #[coverage(off)]
+                            |index| (index, 1u64),
+                        )
+                        .collect::<Vec<_>>()
+                },
+            )
+            .collect::<Vec<_>>();
+        for (i, observations) in observations.iter().enumerate() {
+            assert!(!pool.process(PoolStorageIndex::mock(i), observations, 1000.0).is_empty());
+        }
+        (pool, observations)
+    }
+
+    #[bench]
+    #[coverage(off)]
+    fn bench_process_uninteresting_input(b: &mut Bencher) {
+        let (mut pool, _) = full_pool(20);
+        // a few counters spread over the whole range, all of them already activated
+        let observations = (0..NBR_COUNTERS)
+            .step_by(100)
+            .map(
+                #[coverage(off)]
+                |index| (index, 1u64),
+            )
+            .collect::<Vec<_>>();
+        b.iter(
+            #[coverage(off)]
+            || pool.process(PoolStorageIndex::mock(0), test::black_box(&observations), 1000.0),
+        );
+    }
+
+    #[bench]
+    #[coverage(off)]
+    fn bench_replace_input(b: &mut Bencher) {
+        let (mut pool, observations) = full_pool(20);
+        let mut cplx = 1000.0;
+        let mut i = 0;
+        b.iter(
+            #[coverage(off)]
+            || {
+                // a simpler version of an existing input always replaces it
+                cplx -= 0.001;
+                i += 1;
+                let deltas = pool.process(
+                    PoolStorageIndex::mock(i),
+                    test::black_box(&observations[i % observations.len()]),
+                    cplx,
+                );
+                assert_eq!(deltas.len(), 1);
+            },
+        );
+    }
+
     #[coverage(off)]
     fn run(pool: &mut MostNDiversePool, observations: Vec<usize>, cplx: f64) {
         let observations = observations
@@ -325,14 +481,10 @@ mod tests {
                 "input_count: {} worst_idx: {:?}, all_counters: {}",
                 pool.inputs.len(),
                 pool.worst_input_idx,
-                pool.all_counters.count_ones()
+                pool.nbr_activated_counters
             );
             for input in &pool.inputs {
-                println!(
-                    "\tuniq_counters: {:?}",
-                    input.unique_counters.ones().collect::<Vec<_>>()
-                );
-                // println!("\tall_counters: {:?}", input.counters.ones().collect::<Vec<_>>());
+                println!("\tnbr_uniq_counters: {}", input.nbr_unique_counters);
             }
         } else {
             println!("not interesting: {:?}", observations);
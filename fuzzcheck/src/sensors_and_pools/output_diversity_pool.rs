@@ -0,0 +1,262 @@
+use std::fmt::Display;
+use std::hash::{BuildHasher, Hash};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use ahash::AHashMap;
+
+use crate::fenwick_tree::FenwickTree;
+use crate::traits::{CorpusDelta, Pool, SaveToStatsFolder, Stats};
+use crate::{CSVField, CompatibleWithObservations, PoolStorageIndex, Sensor, ToCSV};
+
+static IS_RECORDING: AtomicBool = AtomicBool::new(false);
+static RECORDED_OUTPUT: Mutex<Option<u64>> = Mutex::new(None);
+
+/// Record the output of the test function, to be observed by an [`OutputSensor`].
+///
+/// Only the hash of the output is recorded. If it is called multiple times during a run of the test function,
+/// only the last output is kept. It does nothing when no `OutputSensor` is recording.
+///
+/// Instead of calling this function, the output of the test function can also be recorded automatically
+/// with [`observe_output`](crate::builder::TestFunctionExt::observe_output).
+#[coverage(off)]
+pub fn record_output<O>(output: &O)
+where
+    O: Hash + ?Sized,
+{
+    if IS_RECORDING.load(Ordering::Relaxed) {
+        let hash = ahash::RandomState::with_seeds(0, 0, 0, 0).hash_one(output);
+        *RECORDED_OUTPUT.lock().expect("the output could not be recorded") = Some(hash);
+    }
+}
+
+/// A [`Sensor`] that observes the output of the test function, as given to [`record_output`].
+///
+/// Its [observations](crate::Sensor::Observations) are the hash of the output, or `None` if no output
+/// was recorded. It is meant to be used with an [`OutputDiversityPool`].
+#[derive(Default)]
+pub struct OutputSensor {
+    observation: Option<u64>,
+}
+
+impl SaveToStatsFolder for OutputSensor {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        vec![]
+    }
+}
+
+impl Sensor for OutputSensor {
+    type Observations = Option<u64>;
+
+    #[coverage(off)]
+    fn start_recording(&mut self) {
+        *RECORDED_OUTPUT.lock().expect("the output could not be cleared") = None;
+        IS_RECORDING.store(true, Ordering::Relaxed);
+    }
+
+    #[coverage(off)]
+    fn stop_recording(&mut self) {
+        IS_RECORDING.store(false, Ordering::Relaxed);
+        self.observation = RECORDED_OUTPUT.lock().expect("the output could not be read").take();
+    }
+
+    #[coverage(off)]
+    fn get_observations(&mut self) -> Self::Observations {
+        self.observation.take()
+    }
+}
+
+#[derive(Clone)]
+pub struct OutputDiversityPoolStats {
+    name: String,
+    nbr_outputs: usize,
+}
+impl Display for OutputDiversityPoolStats {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", self.name, self.nbr_outputs)
+    }
+}
+impl ToCSV for OutputDiversityPoolStats {
+    #[coverage(off)]
+    fn csv_headers(&self) -> Vec<CSVField> {
+        vec![CSVField::String(format!("{}-outputs", self.name))]
+    }
+    #[coverage(off)]
+    fn to_csv_record(&self) -> Vec<CSVField> {
+        vec![CSVField::Integer(self.nbr_outputs as isize)]
+    }
+}
+impl Stats for OutputDiversityPoolStats {}
+
+struct Output {
+    input_id: PoolStorageIndex,
+    complexity: f64,
+    /// The number of test cases that produced this output
+    nbr_observations: u64,
+}
+
+/// A pool that keeps the simplest test case for each distinct output of the test function, as observed
+/// by an [`OutputSensor`].
+///
+/// It is useful when the variety of the outputs matters more than the code coverage, for example to test
+/// an encoder. Each output is chosen with a probability inversely proportional to the number of test
+/// cases that produced it, so that the test cases producing rare outputs are mutated more often.
+///
+/// ```
+/// use fuzzcheck::builder::TestFunctionExt;
+/// use fuzzcheck::sensors_and_pools::{OutputDiversityPool, OutputSensor};
+/// use fuzzcheck::Arguments;
+///
+/// fn encode(xs: &[u8]) -> Vec<u8> {
+///     xs.iter().map(|x| x & 0b111).collect()
+/// }
+///
+/// let result = fuzzcheck::fuzz_test(encode.observe_output())
+///     .default_mutator()
+///     .serde_serializer()
+///     .sensor_and_pool(OutputSensor::default(), OutputDiversityPool::new("outputs"))
+///     .arguments(Arguments::for_property_test(1000))
+///     .launch();
+/// ```
+pub struct OutputDiversityPool {
+    name: String,
+    outputs: Vec<Output>,
+    index_of_output: AHashMap<u64, usize>,
+    ranked_outputs: FenwickTree,
+    rng: fastrand::Rng,
+}
+
+impl OutputDiversityPool {
+    #[coverage(off)]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            outputs: vec![],
+            index_of_output: AHashMap::new(),
+            ranked_outputs: FenwickTree::new(vec![]),
+            rng: fastrand::Rng::new(),
+        }
+    }
+}
+
+impl Pool for OutputDiversityPool {
+    type Stats = OutputDiversityPoolStats;
+
+    #[coverage(off)]
+    fn stats(&self) -> Self::Stats {
+        OutputDiversityPoolStats {
+            name: self.name.clone(),
+            nbr_outputs: self.outputs.len(),
+        }
+    }
+
+    #[coverage(off)]
+    fn get_random_index(&mut self) -> Option<PoolStorageIndex> {
+        let choice = self.ranked_outputs.sample(&self.rng)?;
+        Some(self.outputs[choice].input_id)
+    }
+}
+
+impl SaveToStatsFolder for OutputDiversityPool {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        vec![]
+    }
+}
+
+impl CompatibleWithObservations<Option<u64>> for OutputDiversityPool {
+    #[coverage(off)]
+    fn process(&mut self, input_id: PoolStorageIndex, observations: &Option<u64>, complexity: f64) -> Vec<CorpusDelta> {
+        let Some(output) = observations else {
+            return vec![];
+        };
+        if let Some(&index) = self.index_of_output.get(output) {
+            let existing = &mut self.outputs[index];
+            existing.nbr_observations += 1;
+            let n = existing.nbr_observations as f64;
+            self.ranked_outputs.update(index, 1.0 / n - 1.0 / (n - 1.0));
+            if complexity < existing.complexity {
+                let removed = existing.input_id;
+                existing.input_id = input_id;
+                existing.complexity = complexity;
+                vec![CorpusDelta {
+                    path: PathBuf::new().join(&self.name),
+                    add: true,
+                    remove: vec![removed],
+                    tags: vec![],
+                }]
+            } else {
+                vec![]
+            }
+        } else {
+            self.index_of_output.insert(*output, self.outputs.len());
+            self.outputs.push(Output {
+                input_id,
+                complexity,
+                nbr_observations: 1,
+            });
+            let ranks = self
+                .outputs
+                .iter()
+                .map(
+                    #[coverage(off)]
+                    |output| 1.0 / output.nbr_observations as f64,
+                )
+                .collect();
+            self.ranked_outputs = FenwickTree::new(ranks);
+            vec![CorpusDelta {
+                path: PathBuf::new().join(&self.name),
+                add: true,
+                remove: vec![],
+                tags: vec![],
+            }]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_output_diversity_pool() {
+        let mut sensor = OutputSensor::default();
+        sensor.start_recording();
+        record_output("a");
+        sensor.stop_recording();
+        let a = sensor.get_observations();
+        assert!(a.is_some());
+
+        sensor.start_recording();
+        sensor.stop_recording();
+        assert_eq!(sensor.get_observations(), None);
+
+        sensor.start_recording();
+        record_output("b");
+        sensor.stop_recording();
+        let b = sensor.get_observations();
+        assert_ne!(a, b);
+
+        let mut pool = OutputDiversityPool::new("outputs");
+        assert_eq!(pool.process(PoolStorageIndex::mock(0), &a, 2.0).len(), 1);
+        assert!(pool.process(PoolStorageIndex::mock(1), &None, 1.0).is_empty());
+        assert!(pool.process(PoolStorageIndex::mock(2), &a, 3.0).is_empty());
+        let deltas = pool.process(PoolStorageIndex::mock(3), &a, 1.0);
+        assert_eq!(deltas[0].remove, vec![PoolStorageIndex::mock(0)]);
+        assert_eq!(pool.process(PoolStorageIndex::mock(4), &b, 5.0).len(), 1);
+        assert_eq!(pool.stats().nbr_outputs, 2);
+
+        // `a` was produced three times and `b` once
+        let mut nbr_b = 0;
+        for _ in 0..1000 {
+            if pool.get_random_index() == Some(PoolStorageIndex::mock(4)) {
+                nbr_b += 1;
+            }
+        }
+        assert!((650..=850).contains(&nbr_b), "{}", nbr_b);
+    }
+}
@@ -0,0 +1,327 @@
+use std::any::Any;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use ahash::AHasher;
+
+use crate::traits::CorpusDelta;
+use crate::{CompatibleWithObservations, Pool, PoolStorageIndex, SaveToStatsFolder, Sensor};
+
+/// A sensor holding a list of other sensors, whose observations are type-erased.
+///
+/// It is an alternative to nesting many [`AndSensor`](crate::sensors_and_pools::AndSensor),
+/// which is useful when the list of sensors is only known at runtime. Each sensor is added to the suite
+/// with [`suite.add(..)`](SensorSuite::add), which returns a [`SensorSuiteKey`]. This key can then
+/// be used to retrieve the observations of that specific sensor from the suite’s [`SensorSuiteObservations`].
+///
+/// The sensors start recording in the order in which they were added to the suite, and stop
+/// recording in the reverse order. So the first sensor that was added to the suite observes the
+/// execution of the other ones.
+///
+/// To handle the observations of a sensor in the suite, wrap a pool compatible with them in a
+/// [`SensorSuitePool`]. All such pools are compatible with [`SensorSuiteObservations`], and they can be combined
+/// together with [`PoolExt::and`](crate::PoolExt::and) and [`SameObservations`](crate::sensors_and_pools::SameObservations).
+///
+/// # Example
+/// ```
+/// use fuzzcheck::sensors_and_pools::{
+///     MaximiseObservationPool, SameObservations, SensorSuite, SensorSuitePool, StaticValueSensor,
+/// };
+/// use fuzzcheck::{Arguments, PoolExt, ReasonForStopping};
+///
+/// static mut COUNT_EVEN: usize = 0;
+/// static mut COUNT_ODD: usize = 0;
+///
+/// fn test_function(xs: &[u8]) -> bool {
+///     if xs.len() == 4 {
+///         unsafe {
+///             if xs[0] == 12 { COUNT_EVEN += 1 }
+///             if xs[1] == 91 { COUNT_ODD += 1 }
+///             if xs[2] == 54 { COUNT_EVEN += 1 }
+///             if xs[3] == 7  { COUNT_ODD += 1 }
+///             COUNT_EVEN + COUNT_ODD != 4
+///         }
+///     } else {
+///         true
+///     }
+/// }
+///
+/// let mut sensor = SensorSuite::new();
+/// let even = sensor.add(unsafe { StaticValueSensor::new(&mut COUNT_EVEN, 0) });
+/// let odd = sensor.add(unsafe { StaticValueSensor::new(&mut COUNT_ODD, 0) });
+///
+/// let pool = SensorSuitePool::new(even, MaximiseObservationPool::<usize>::new("even")).and(
+///     SensorSuitePool::new(odd, MaximiseObservationPool::<usize>::new("odd")),
+///     None,
+///     SameObservations,
+/// );
+///
+/// let result = fuzzcheck::fuzz_test(test_function)
+///     .default_mutator()
+///     .serde_serializer()
+///     .sensor_and_pool(sensor, pool)
+///     .arguments(Arguments::for_internal_documentation_test())
+///     .stop_after_first_test_failure(true)
+///     .launch();
+///
+/// assert!(matches!(
+///     result.reason_for_stopping,
+///     ReasonForStopping::TestFailure(x) if matches!(x.as_slice(), [12, 91, 54, 7])
+/// ));
+/// ```
+#[derive(Default)]
+pub struct SensorSuite {
+    sensors: Vec<Box<dyn AnySensor>>,
+}
+
+/// The [observations](crate::Sensor::Observations) of a [`SensorSuite`].
+///
+/// Use [`observations.get(key)`](SensorSuiteObservations::get) to access the observations of one of its sensors.
+pub struct SensorSuiteObservations {
+    observations: Vec<Box<dyn Any>>,
+}
+
+/// A key identifying a sensor within a [`SensorSuite`], whose observations have type `O`.
+pub struct SensorSuiteKey<O> {
+    index: usize,
+    _phantom: PhantomData<O>,
+}
+impl<O> Clone for SensorSuiteKey<O> {
+    #[coverage(off)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<O> Copy for SensorSuiteKey<O> {}
+
+/// An object safe version of the [`Sensor`] trait, whose observations are boxed into a `dyn Any`
+trait AnySensor: SaveToStatsFolder {
+    fn start_recording(&mut self);
+    fn stop_recording(&mut self);
+    fn get_observations(&mut self) -> Box<dyn Any>;
+    fn hash_observations(&self, observations: &dyn Any) -> Option<u64>;
+    fn get_and_record_observations(&mut self, record: &mut Vec<u8>) -> Box<dyn Any>;
+    fn replay_observations(&self, record: &mut &[u8]) -> Option<Box<dyn Any>>;
+}
+impl<S> AnySensor for S
+where
+    S: Sensor,
+    S::Observations: 'static,
+{
+    #[coverage(off)]
+    fn start_recording(&mut self) {
+        <Self as Sensor>::start_recording(self)
+    }
+    #[coverage(off)]
+    fn stop_recording(&mut self) {
+        <Self as Sensor>::stop_recording(self)
+    }
+    #[coverage(off)]
+    fn get_observations(&mut self) -> Box<dyn Any> {
+        Box::new(<Self as Sensor>::get_observations(self))
+    }
+    #[coverage(off)]
+    fn hash_observations(&self, observations: &dyn Any) -> Option<u64> {
+        <Self as Sensor>::hash_observations(self, observations.downcast_ref()?)
+    }
+    #[coverage(off)]
+    fn get_and_record_observations(&mut self, record: &mut Vec<u8>) -> Box<dyn Any> {
+        Box::new(<Self as Sensor>::get_and_record_observations(self, record))
+    }
+    #[coverage(off)]
+    fn replay_observations(&self, record: &mut &[u8]) -> Option<Box<dyn Any>> {
+        let observations = <Self as Sensor>::replay_observations(self, record)?;
+        Some(Box::new(observations))
+    }
+}
+
+impl SensorSuite {
+    #[coverage(off)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a sensor to the suite and return the key that can be used to retrieve its observations.
+    #[coverage(off)]
+    pub fn add<S>(&mut self, sensor: S) -> SensorSuiteKey<S::Observations>
+    where
+        S: Sensor,
+        S::Observations: 'static,
+    {
+        let index = self.sensors.len();
+        self.sensors.push(Box::new(sensor));
+        SensorSuiteKey {
+            index,
+            _phantom: PhantomData,
+        }
+    }
+    /// The number of sensors in the suite
+    #[coverage(off)]
+    pub fn len(&self) -> usize {
+        self.sensors.len()
+    }
+    #[coverage(off)]
+    pub fn is_empty(&self) -> bool {
+        self.sensors.is_empty()
+    }
+}
+
+impl SensorSuiteObservations {
+    /// Get the observations of the sensor identified by `key`.
+    ///
+    /// ## Panics
+    /// Panics if `key` was returned by a different [`SensorSuite`] with incompatible sensors.
+    #[coverage(off)]
+    pub fn get<O: 'static>(&self, key: SensorSuiteKey<O>) -> &O {
+        self.observations[key.index]
+            .downcast_ref::<O>()
+            .expect("the SensorSuiteKey does not belong to the SensorSuite that produced these observations")
+    }
+}
+
+impl SaveToStatsFolder for SensorSuite {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        self.sensors
+            .iter()
+            .flat_map(
+                #[coverage(off)]
+                |sensor| sensor.save_to_stats_folder(),
+            )
+            .collect()
+    }
+}
+
+impl Sensor for SensorSuite {
+    type Observations = SensorSuiteObservations;
+
+    #[coverage(off)]
+    fn start_recording(&mut self) {
+        for sensor in self.sensors.iter_mut() {
+            sensor.start_recording();
+        }
+    }
+    #[coverage(off)]
+    fn stop_recording(&mut self) {
+        for sensor in self.sensors.iter_mut().rev() {
+            sensor.stop_recording();
+        }
+    }
+    #[coverage(off)]
+    fn get_observations(&mut self) -> Self::Observations {
+        SensorSuiteObservations {
+            observations: self
+                .sensors
+                .iter_mut()
+                .map(
+                    #[coverage(off)]
+                    |sensor| sensor.get_observations(),
+                )
+                .collect(),
+        }
+    }
+    /// Combine the hashes of the observations of each sensor, or return `None` if one of them cannot be hashed
+    #[coverage(off)]
+    fn hash_observations(&self, observations: &Self::Observations) -> Option<u64> {
+        let mut hasher = AHasher::default();
+        for (sensor, observations) in self.sensors.iter().zip(observations.observations.iter()) {
+            sensor.hash_observations(observations.as_ref())?.hash(&mut hasher);
+        }
+        Some(hasher.finish())
+    }
+    /// The observations of each sensor are recorded one after the other
+    #[coverage(off)]
+    fn get_and_record_observations(&mut self, record: &mut Vec<u8>) -> Self::Observations {
+        SensorSuiteObservations {
+            observations: self
+                .sensors
+                .iter_mut()
+                .map(
+                    #[coverage(off)]
+                    |sensor| sensor.get_and_record_observations(record),
+                )
+                .collect(),
+        }
+    }
+    #[coverage(off)]
+    fn replay_observations(&self, record: &mut &[u8]) -> Option<Self::Observations> {
+        let observations = self
+            .sensors
+            .iter()
+            .map(
+                #[coverage(off)]
+                |sensor| sensor.replay_observations(record),
+            )
+            .collect::<Option<Vec<_>>>()?;
+        Some(SensorSuiteObservations { observations })
+    }
+}
+
+/// A pool handling the observations of a single sensor in a [`SensorSuite`]
+///
+/// It wraps a pool compatible with the observations of that sensor and makes it compatible
+/// with [`SensorSuiteObservations`] instead.
+pub struct SensorSuitePool<P, O> {
+    key: SensorSuiteKey<O>,
+    pool: P,
+}
+
+impl<P, O> SensorSuitePool<P, O>
+where
+    P: CompatibleWithObservations<O>,
+{
+    #[coverage(off)]
+    pub fn new(key: SensorSuiteKey<O>, pool: P) -> Self {
+        Self { key, pool }
+    }
+}
+
+impl<P, O> SaveToStatsFolder for SensorSuitePool<P, O>
+where
+    P: Pool,
+{
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        self.pool.save_to_stats_folder()
+    }
+}
+
+impl<P, O> Pool for SensorSuitePool<P, O>
+where
+    P: Pool,
+{
+    type Stats = P::Stats;
+
+    #[coverage(off)]
+    fn stats(&self) -> Self::Stats {
+        self.pool.stats()
+    }
+    #[coverage(off)]
+    fn get_random_index(&mut self) -> Option<PoolStorageIndex> {
+        self.pool.get_random_index()
+    }
+    #[coverage(off)]
+    fn weight(&self) -> f64 {
+        self.pool.weight()
+    }
+    #[coverage(off)]
+    fn record_interesting_child(&mut self, parent: PoolStorageIndex) {
+        self.pool.record_interesting_child(parent)
+    }
+}
+
+impl<P, O> CompatibleWithObservations<SensorSuiteObservations> for SensorSuitePool<P, O>
+where
+    P: CompatibleWithObservations<O>,
+    O: 'static,
+{
+    #[coverage(off)]
+    fn process(
+        &mut self,
+        input_id: PoolStorageIndex,
+        observations: &SensorSuiteObservations,
+        complexity: f64,
+    ) -> Vec<CorpusDelta> {
+        self.pool.process(input_id, observations.get(self.key), complexity)
+    }
+}
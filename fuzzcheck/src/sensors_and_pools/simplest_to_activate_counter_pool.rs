@@ -25,6 +25,20 @@
 //! In short, an input’s final score is the sum of the score of each of its
 //! activated counters divided by their frequencies.
 //!
+//! # Choosing an input
+//!
+//! The probability that an input is chosen to be mutated is proportional to its energy, which
+//! is derived from its score by an [`EnergySchedule`]. By default, the energy of an input is its score divided
+//! by the number of times it was already chosen.
+//!
+//! # Limiting the size of the pool
+//!
+//! The pool keeps one input for each counter in the worst case, which can amount to a very large corpus
+//! after a long fuzzing session. With [`with_max_size(..)`](SimplestToActivateCounterPool::with_max_size),
+//! the inputs exceeding the maximum size are evicted according to an [`EvictionPolicy`]. The pool then forgets
+//! the counters that were only activated by the evicted inputs, such that new inputs activating them can be
+//! added again later.
+//!
 
 use std::fmt::Display;
 use std::hash::Hash;
@@ -39,6 +53,10 @@ use crate::fenwick_tree::FenwickTree;
 use crate::traits::{CorpusDelta, Pool, SaveToStatsFolder, Stats};
 use crate::{CSVField, CompatibleWithObservations, PoolStorageIndex, ToCSV};
 
+/// The ranking of the inputs is rebuilt from scratch after a number of additions equal to
+/// `pool.len() / REBUILD_RANKING_AFTER_FRACTION_OF_ADDITIONS`.
+const REBUILD_RANKING_AFTER_FRACTION_OF_ADDITIONS: usize = 8;
+
 #[derive(Debug)]
 #[repr(transparent)]
 struct CounterIdx(pub usize);
@@ -100,6 +118,88 @@ struct Input {
     ///
     /// This is used to prioritise new inputs over old ones.
     number_times_chosen: usize,
+    /// The number of inputs that had been added to the pool before this one
+    added_at: usize,
+    /// The number of inputs that had been added to the pool before this one, or before the last time
+    /// that one of its mutations was added to the corpus
+    last_interesting_at: usize,
+    /// The energy of the input as it is currently recorded in `ranked_inputs`
+    energy: f64,
+}
+
+/// Determines how often each test case of a [`SimplestToActivateCounterPool`] is chosen
+/// to be mutated, based on its score and history.
+///
+/// The pool picks a test case with a probability proportional to its *energy*, which is
+/// computed from an [`EnergyInfo`] as follows:
+#[derive(Clone, Copy, Debug, Default)]
+pub enum EnergySchedule {
+    /// `score / nbr_times_chosen`
+    ///
+    /// Each test case gets less energy as it is mutated, so that the fuzzer spreads its effort
+    /// over the whole pool. This is the default schedule.
+    #[default]
+    Explore,
+    /// `score`
+    ///
+    /// The fuzzer keeps focusing on the highest scoring test cases, regardless of how many times
+    /// they were already mutated.
+    Exploit,
+    /// `score * (1 + 1 / (1 + age)) / nbr_times_chosen²`
+    ///
+    /// The energy of a test case drops quickly as it is mutated, and the test cases that were added or whose
+    /// mutations produced new coverage most recently get up to twice as much energy. This may find new coverage faster than
+    /// `Explore` when the pool contains many test cases that were already thoroughly mutated.
+    Fast,
+    /// A custom function computing the energy of a test case. It must return a non-negative number.
+    Custom(fn(EnergyInfo) -> f64),
+}
+
+/// The information about a test case that is used to compute its energy. See [`EnergySchedule`].
+#[derive(Clone, Copy, Debug)]
+pub struct EnergyInfo {
+    /// The score of the test case, which is the sum, for each of its counters, of 1 divided by the
+    /// number of test cases activating that counter
+    pub score: f64,
+    /// The number of times that the test case was chosen to be mutated (starting at 1)
+    pub nbr_times_chosen: usize,
+    /// The number of test cases that were added to the pool since this one was added, or since one of its
+    /// mutations was last added to the corpus, whichever is later. Since test cases are only added when they
+    /// activate new counters or activate existing counters more simply, a low age means that the test case, or
+    /// one of its mutations, was recently interesting.
+    pub age: usize,
+}
+
+impl EnergySchedule {
+    /// Compute the energy of a test case
+    #[coverage(off)]
+    pub fn energy(&self, info: EnergyInfo) -> f64 {
+        let nbr_times_chosen = info.nbr_times_chosen as f64;
+        match self {
+            EnergySchedule::Explore => info.score / nbr_times_chosen,
+            EnergySchedule::Exploit => info.score,
+            EnergySchedule::Fast => {
+                let recency = 1.0 + 1.0 / (1.0 + info.age as f64);
+                info.score * recency / (nbr_times_chosen * nbr_times_chosen)
+            }
+            EnergySchedule::Custom(f) => f(info),
+        }
+    }
+}
+
+/// Determines which test case is removed from a [`SimplestToActivateCounterPool`] when it exceeds its
+/// maximum size. See [`with_max_size(..)`](SimplestToActivateCounterPool::with_max_size).
+///
+/// The test case that was just added to the pool is never evicted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Evict the test case with the lowest score, which activates the fewest counters relative to the
+    /// number of other test cases activating them
+    LowestScore,
+    /// Evict the test case that was added to the pool first
+    Oldest,
+    /// Evict the test case with the highest complexity
+    HighestComplexity,
 }
 
 /**
@@ -123,8 +223,8 @@ impl AnalysedCounter {
         inputs: Vec<SlabKey<Input>>,
         least_complex_input: SlabKey<Input>,
         least_complexity: f64,
+        score: f64,
     ) -> Self {
-        let score = SimplestToActivateCounterPool::score_of_counter(inputs.len());
         Self {
             key,
             inputs,
@@ -140,16 +240,37 @@ impl AnalysedCounter {
 /// It is compatible with any sensor whose [observations](crate::Sensor::Observations)
 /// are given by an iterator of `(usize, u64)`. In particular, it is recommended to use it
 /// with the [`CodeCoverageSensor`](crate::sensors_and_pools::CodeCoverageSensor).
+///
+/// The test cases of the pool are chosen according to an [`EnergySchedule`], which can be
+/// changed with [`self.with_energy_schedule(..)`](SimplestToActivateCounterPool::with_energy_schedule).
+///
+/// By default, every counter is equally important. Use
+/// [`self.with_counter_weights(..)`](SimplestToActivateCounterPool::with_counter_weights)
+/// to favour the progress made in some parts of the code.
+///
+/// Adding a test case to the pool only updates the ranking of the test cases whose score changed.
+/// The energy of every other test case, which may depend on its age, is refreshed once every few
+/// additions, such that the cost of maintaining the ranking stays constant on average even for
+/// very large pools.
 pub struct SimplestToActivateCounterPool {
     pub name: String,
 
     least_complexity_for_counter: Vec<f64>,
+    counter_weights: Vec<f64>,
     analysed_counters: AHashMap<CounterIdx, AnalysedCounter>,
     slab_inputs: Slab<Input>,
 
     pub average_complexity: f64,
     pub total_score: f64,
+    /// The energy of each input, indexed by its key in `slab_inputs`. Empty slots have an energy of 0.
     pub ranked_inputs: FenwickTree,
+    sum_complexity: f64,
+
+    energy_schedule: EnergySchedule,
+    /// The maximum number of inputs of the pool and how to choose the ones to evict, if any
+    max_size: Option<(usize, EvictionPolicy)>,
+    nbr_added_inputs: usize,
+    nbr_added_inputs_since_rebuild: usize,
 
     rng: Rng,
 }
@@ -160,6 +281,7 @@ impl SimplestToActivateCounterPool {
         SimplestToActivateCounterPool {
             name: name.to_string(),
             least_complexity_for_counter: vec![f64::INFINITY; nbr_counters],
+            counter_weights: vec![1.0; nbr_counters],
             analysed_counters: AHashMap::with_hasher(ahash::RandomState::with_seeds(0, 0, 0, 0)),
 
             slab_inputs: Slab::new(),
@@ -167,11 +289,84 @@ impl SimplestToActivateCounterPool {
             average_complexity: 0.0,
             total_score: 0.0,
             ranked_inputs: FenwickTree::new(vec![]),
+            sum_complexity: 0.0,
+
+            energy_schedule: EnergySchedule::default(),
+            max_size: None,
+            nbr_added_inputs: 0,
+            nbr_added_inputs_since_rebuild: 0,
 
             rng: fastrand::Rng::new(),
         }
     }
 
+    /// Use the given [`EnergySchedule`] to decide which test cases of the pool are chosen to be mutated.
+    #[must_use]
+    #[coverage(off)]
+    pub fn with_energy_schedule(self, energy_schedule: EnergySchedule) -> Self {
+        Self {
+            energy_schedule,
+            ..self
+        }
+    }
+
+    /// Keep at most `max_size` test cases in the pool, evicting the ones chosen by the given policy.
+    ///
+    /// The counters that were only activated by an evicted test case are forgotten by the pool, and are thus
+    /// no longer counted in its coverage, until a new test case activates them again.
+    ///
+    /// ## Panics
+    /// Panics if `max_size` is 0.
+    #[must_use]
+    #[coverage(off)]
+    pub fn with_max_size(self, max_size: usize, policy: EvictionPolicy) -> Self {
+        assert!(max_size > 0, "the maximum size of the pool must be strictly positive");
+        Self {
+            max_size: Some((max_size, policy)),
+            ..self
+        }
+    }
+
+    /// Multiply the score of each counter by the weight at its index, such that the test cases activating
+    /// the counters with a higher weight are considered more interesting.
+    ///
+    /// The weights of a [`CodeCoverageSensor`](crate::sensors_and_pools::CodeCoverageSensor) can be computed
+    /// from the file and name of each instrumented function with
+    /// [`sensor.counter_weights(..)`](crate::sensors_and_pools::CodeCoverageSensor::counter_weights).
+    ///
+    /// ## Panics
+    /// Panics if the number of weights is different from the number of counters of the pool or if a weight is
+    /// not strictly positive.
+    #[must_use]
+    #[coverage(off)]
+    pub fn with_counter_weights(self, counter_weights: Vec<f64>) -> Self {
+        assert_eq!(
+            counter_weights.len(),
+            self.counter_weights.len(),
+            "there must be exactly one weight per counter"
+        );
+        assert!(
+            counter_weights.iter().all(
+                #[coverage(off)]
+                |&w| w > 0.0 && w.is_finite()
+            ),
+            "the weight of a counter must be strictly positive"
+        );
+        Self {
+            counter_weights,
+            ..self
+        }
+    }
+
+    #[coverage(off)]
+    fn energy(&self, input: &Input) -> f64 {
+        self.energy_schedule.energy(EnergyInfo {
+            score: input.score,
+            nbr_times_chosen: input.number_times_chosen,
+            age: self.nbr_added_inputs - input.last_interesting_at - 1,
+        })
+    }
+
     #[coverage(off)]
     pub fn score(&self) -> f64 {
         self.total_score
@@ -201,7 +396,12 @@ impl SimplestToActivateCounterPool {
             data,
             complexity,
             number_times_chosen: 1,
+            added_at: self.nbr_added_inputs,
+            last_interesting_at: self.nbr_added_inputs,
+            energy: 0.0,
         };
+        self.nbr_added_inputs += 1;
+        self.sum_complexity += complexity;
         let element_key = self.slab_inputs.insert(element);
 
         let mut to_delete: AHashSet<SlabKey<Input>> = AHashSet::with_hasher(ahash::RandomState::with_seeds(0, 0, 0, 0));
@@ -246,7 +446,10 @@ impl SimplestToActivateCounterPool {
             let new_counter_for_iter = complexity;
             self.least_complexity_for_counter[f.0] = new_counter_for_iter;
 
-            let analyzed_f = AnalysedCounter::new(f, vec![element_key], element_key, complexity);
+            let score = self.counter_weights[f.0] * Self::score_of_counter(1);
+            // the weight of a counter is shared between all the inputs activating it
+            self.total_score += self.counter_weights[f.0];
+            let analyzed_f = AnalysedCounter::new(f, vec![element_key], element_key, complexity, score);
             self.analysed_counters.insert(f, analyzed_f);
 
             element.all_counters.push(f);
@@ -256,7 +459,7 @@ impl SimplestToActivateCounterPool {
         let mut affected_counters = AHashSet::<CounterIdx>::new();
 
         let deleted_values: Vec<_> = to_delete.iter().copied().collect();
-        let deleted_pool_storage_indices = deleted_values
+        let mut deleted_pool_storage_indices = deleted_values
             .iter()
             .map(
                 #[coverage(off)]
@@ -266,21 +469,34 @@ impl SimplestToActivateCounterPool {
 
         self.delete_elements(to_delete, &mut affected_counters);
 
+        if let Some((max_size, policy)) = self.max_size {
+            while self.slab_inputs.len() > max_size {
+                let Some(evicted) = self.eviction_candidate(policy, element_key) else {
+                    break;
+                };
+                deleted_pool_storage_indices.push(self.slab_inputs[evicted].data);
+                self.evict(evicted, &mut affected_counters);
+            }
+        }
+
         // now track the counters whose scores are affected by the existing counters
         for counter_key in existing_counters.iter() {
             affected_counters.insert(*counter_key);
         }
         // and update the score of every affected input
+        let mut affected_inputs = AHashSet::<SlabKey<Input>>::with_hasher(ahash::RandomState::with_seeds(0, 0, 0, 0));
+        affected_inputs.insert(element_key);
         for counter_key in affected_counters.into_iter() {
             let counter = self.analysed_counters.get_mut(&counter_key).unwrap();
 
             let old_score = counter.score;
-            counter.score = Self::score_of_counter(counter.inputs.len());
+            counter.score = self.counter_weights[counter_key.0] * Self::score_of_counter(counter.inputs.len());
             let change_in_score = counter.score - old_score;
 
             for &input_key in &counter.inputs {
                 let element_with_counter = &mut self.slab_inputs[input_key];
                 element_with_counter.score += change_in_score;
+                affected_inputs.insert(input_key);
             }
         }
 
@@ -288,11 +504,11 @@ impl SimplestToActivateCounterPool {
         element.score = 0.0;
         for f_key in &element.all_counters {
             let analyzed_counter = self.analysed_counters.get_mut(f_key).unwrap();
-            let counter_score = Self::score_of_counter(analyzed_counter.inputs.len());
+            let counter_score = self.counter_weights[f_key.0] * Self::score_of_counter(analyzed_counter.inputs.len());
             element.score += counter_score;
         }
 
-        self.update_self_stats();
+        self.update_self_stats(affected_inputs, element_key);
 
         // self.sanity_check();
         let stats = self.stats();
@@ -301,6 +517,7 @@ impl SimplestToActivateCounterPool {
                 path: Path::new(&self.name).to_path_buf(),
                 add: true,
                 remove: deleted_pool_storage_indices,
+                tags: vec![],
             },
             stats,
         ))
@@ -329,55 +546,149 @@ impl SimplestToActivateCounterPool {
                 analyzed_f.inputs.swap_remove(idx_to_delete_key);
             }
 
+            let to_delete_el = &mut self.slab_inputs[to_delete_key];
+            self.sum_complexity -= to_delete_el.complexity;
+            self.ranked_inputs.update(to_delete_key.key, -to_delete_el.energy);
+            to_delete_el.energy = 0.0;
             self.slab_inputs.remove(to_delete_key);
         }
     }
 
+    /// The input that should be evicted according to the policy, other than the one that was just added
+    #[coverage(off)]
+    fn eviction_candidate(&self, policy: EvictionPolicy, new_input: SlabKey<Input>) -> Option<SlabKey<Input>> {
+        let candidates = self.slab_inputs.keys().filter(
+            #[coverage(off)]
+            |&key| key != new_input,
+        );
+        let ordering = #[coverage(off)]
+        |a: &f64, b: &f64| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal);
+        match policy {
+            EvictionPolicy::LowestScore => candidates.min_by(
+                #[coverage(off)]
+                |&a, &b| ordering(&self.slab_inputs[a].score, &self.slab_inputs[b].score),
+            ),
+            EvictionPolicy::Oldest => candidates.min_by_key(
+                #[coverage(off)]
+                |&key| self.slab_inputs[key].added_at,
+            ),
+            EvictionPolicy::HighestComplexity => candidates.max_by(
+                #[coverage(off)]
+                |&a, &b| ordering(&self.slab_inputs[a].complexity, &self.slab_inputs[b].complexity),
+            ),
+        }
+    }
+
+    /// Remove an input from the pool even though it may still be the least complex input activating some counters.
+    ///
+    /// The least complex input of each of these counters is chosen again among the remaining ones, and the counters
+    /// that are no longer activated by any input are forgotten.
+    #[coverage(off)]
+    fn evict(&mut self, key: SlabKey<Input>, affected_counters: &mut AHashSet<CounterIdx>) {
+        let counters = self.slab_inputs[key].all_counters.clone();
+        let mut to_delete = AHashSet::with_hasher(ahash::RandomState::with_seeds(0, 0, 0, 0));
+        to_delete.insert(key);
+        self.delete_elements(to_delete, affected_counters);
+
+        for counter_key in counters {
+            let counter = self.analysed_counters.get_mut(&counter_key).unwrap();
+            if counter.inputs.is_empty() {
+                self.analysed_counters.remove(&counter_key);
+                self.least_complexity_for_counter[counter_key.0] = f64::INFINITY;
+                self.total_score -= self.counter_weights[counter_key.0];
+                affected_counters.remove(&counter_key);
+            } else if counter.least_complex_input == key {
+                let slab_inputs = &self.slab_inputs;
+                let least_complex_input = *counter
+                    .inputs
+                    .iter()
+                    .min_by(
+                        #[coverage(off)]
+                        |&&a, &&b| {
+                            slab_inputs[a]
+                                .complexity
+                                .partial_cmp(&slab_inputs[b].complexity)
+                                .unwrap_or(std::cmp::Ordering::Equal)
+                        },
+                    )
+                    .unwrap();
+                let least_complexity = self.slab_inputs[least_complex_input].complexity;
+                counter.least_complex_input = least_complex_input;
+                counter.least_complexity = least_complexity;
+                self.least_complexity_for_counter[counter_key.0] = least_complexity;
+                self.slab_inputs[least_complex_input]
+                    .least_complex_for_counters
+                    .insert(counter_key);
+            }
+        }
+    }
+
     #[coverage(off)]
     pub fn score_of_counter(exact_counter_multiplicity: usize) -> f64 {
         1.0 / (exact_counter_multiplicity as f64)
     }
 
-    /// Update global statistics of the pool following a change in its content
+    /// Update global statistics of the pool following the addition of `new_input`
+    ///
+    /// Only the energies of the `affected_inputs`, whose score changed, are updated, unless
+    /// the ranking of the inputs needs to be rebuilt.
     #[coverage(off)]
-    fn update_self_stats(&mut self) {
-        let slab = &self.slab_inputs;
-
-        let ranked_inputs = self
-            .slab_inputs
-            .keys()
-            .map(
-                #[coverage(off)]
-                |key| {
-                    let input = &slab[key];
-                    input.score / (input.number_times_chosen as f64)
-                },
-            )
-            .collect();
-        self.ranked_inputs = FenwickTree::new(ranked_inputs);
+    fn update_self_stats(&mut self, affected_inputs: AHashSet<SlabKey<Input>>, new_input: SlabKey<Input>) {
+        self.nbr_added_inputs_since_rebuild += 1;
+        if new_input.key >= self.ranked_inputs.len()
+            || self.nbr_added_inputs_since_rebuild * REBUILD_RANKING_AFTER_FRACTION_OF_ADDITIONS
+                >= self.slab_inputs.len()
+        {
+            self.rebuild_ranked_inputs();
+        } else {
+            for key in affected_inputs {
+                self.update_energy(key);
+            }
+        }
+        self.average_complexity = self.sum_complexity / self.slab_inputs.len() as f64;
+    }
 
-        self.total_score = self
-            .slab_inputs
-            .keys()
-            .map(
-                #[coverage(off)]
-                |key| slab[key].score,
-            )
-            .sum();
+    /// Recompute the energy of the input and update its rank accordingly
+    #[coverage(off)]
+    fn update_energy(&mut self, key: SlabKey<Input>) {
+        let new_energy = self.energy(&self.slab_inputs[key]);
+        let input = &mut self.slab_inputs[key];
+        self.ranked_inputs.update(key.key, new_energy - input.energy);
+        input.energy = new_energy;
+    }
 
-        self.average_complexity = self
-            .slab_inputs
-            .keys()
+    /// Recompute the energy of every input of the pool, which may have changed
+    /// as the inputs got older.
+    ///
+    /// The capacity of the ranking is doubled when it cannot hold every key of the pool,
+    /// such that it is only rebuilt a constant number of times per addition, on average.
+    #[coverage(off)]
+    fn rebuild_ranked_inputs(&mut self) {
+        let keys = self.slab_inputs.keys().collect::<Vec<_>>();
+        let max_key = keys
+            .iter()
             .map(
                 #[coverage(off)]
-                |key| &slab[key],
-            )
-            .fold(
-                0.0,
-                #[coverage(off)]
-                |c, x| c + x.complexity,
+                |key| key.key + 1,
             )
-            / self.slab_inputs.len() as f64;
+            .max()
+            .unwrap_or(0);
+        let mut len = self.ranked_inputs.len().max(1);
+        while len < max_key {
+            len *= 2;
+        }
+        let mut energies = vec![0.0; len];
+        let mut sum_complexity = 0.0;
+        for key in keys {
+            let energy = self.energy(&self.slab_inputs[key]);
+            let input = &mut self.slab_inputs[key];
+            input.energy = energy;
+            energies[key.key] = energy;
+            sum_complexity += input.complexity;
+        }
+        self.ranked_inputs = FenwickTree::new(energies);
+        self.sum_complexity = sum_complexity;
+        self.nbr_added_inputs_since_rebuild = 0;
     }
 
     #[cfg(test)]
@@ -418,7 +729,7 @@ impl SimplestToActivateCounterPool {
             assert!(input.score > 0.0);
             let expected_input_score = input.all_counters.iter().fold(0.0, |c, fk| {
                 let f = &slab[fk];
-                c + Self::score_of_counter(f.inputs.len())
+                c + self.counter_weights[fk.0] * Self::score_of_counter(f.inputs.len())
             });
             assert!(
                 (input.score - expected_input_score).abs() < 0.01,
@@ -445,6 +756,27 @@ impl SimplestToActivateCounterPool {
             }
         }
 
+        // the ranking agrees with the energies recorded in the inputs, and empty slots are never chosen
+        let mut recorded_energies = vec![0.0; self.ranked_inputs.len()];
+        for input_key in self.slab_inputs.keys() {
+            recorded_energies[input_key.key] = self.slab_inputs[input_key].energy;
+        }
+        for (idx, energy) in recorded_energies.into_iter().enumerate() {
+            let prev_prefix_sum = if idx == 0 {
+                0.0
+            } else {
+                self.ranked_inputs.prefix_sum(idx - 1)
+            };
+            let rank = self.ranked_inputs.prefix_sum(idx) - prev_prefix_sum;
+            assert!((rank - energy).abs() < 0.01, "{:.2} != {:.2}", rank, energy);
+        }
+        let expected_total_score = self
+            .slab_inputs
+            .keys()
+            .map(|key| self.slab_inputs[key].score)
+            .sum::<f64>();
+        assert!((self.total_score - expected_total_score).abs() < 0.01);
+
         let mut dedupped_inputs = self.slab_inputs.keys().collect::<Vec<_>>();
         dedupped_inputs.sort();
         dedupped_inputs.dedup();
@@ -474,16 +806,35 @@ impl Pool for SimplestToActivateCounterPool {
     #[coverage(off)]
     fn get_random_index(&mut self) -> Option<PoolStorageIndex> {
         let choice = self.ranked_inputs.sample(&self.rng)?;
-        let key = self.slab_inputs.get_nth_key(choice);
+        let mut key = SlabKey::new(choice);
+        if !self.slab_inputs.contains(key) || self.slab_inputs[key].energy <= 0.0 {
+            // the rounding errors accumulated by the updates of the ranking left some
+            // energy on an empty slot, which may be past the end of the slab since the
+            // ranking is larger than it, so we start again from a clean ranking
+            self.rebuild_ranked_inputs();
+            key = SlabKey::new(self.ranked_inputs.sample(&self.rng)?);
+            if !self.slab_inputs.contains(key) {
+                return None;
+            }
+        }
 
-        let input = &mut self.slab_inputs[key];
-        let old_rank = input.score / (input.number_times_chosen as f64);
-        input.number_times_chosen += 1;
-        let new_rank = input.score / (input.number_times_chosen as f64);
+        self.slab_inputs[key].number_times_chosen += 1;
+        self.update_energy(key);
+        Some(self.slab_inputs[key].data)
+    }
 
-        let delta = new_rank - old_rank;
-        self.ranked_inputs.update(choice, delta);
-        Some(input.data)
+    #[coverage(off)]
+    fn record_interesting_child(&mut self, parent: PoolStorageIndex) {
+        let slab_inputs = &self.slab_inputs;
+        let Some(key) = slab_inputs.keys().find(
+            #[coverage(off)]
+            |&key| slab_inputs[key].data == parent,
+        ) else {
+            return;
+        };
+        // the parent is now as recent as the last input added to the pool, which is usually its child
+        self.slab_inputs[key].last_interesting_at = self.nbr_added_inputs - 1;
+        self.update_energy(key);
     }
 }
 
@@ -672,6 +1023,206 @@ mod tests {
     use super::*;
     use crate::Mutator;
 
+    #[test]
+    #[coverage(off)]
+    fn test_energy_schedules() {
+        let info = #[coverage(off)]
+        |nbr_times_chosen, age| EnergyInfo {
+            score: 2.0,
+            nbr_times_chosen,
+            age,
+        };
+        assert_eq!(EnergySchedule::Explore.energy(info(4, 0)), 0.5);
+        assert_eq!(EnergySchedule::Exploit.energy(info(4, 0)), 2.0);
+        assert_eq!(EnergySchedule::Fast.energy(info(2, 0)), 1.0);
+        assert_eq!(EnergySchedule::Fast.energy(info(2, 3)), 0.625);
+        assert_eq!(
+            EnergySchedule::Custom(
+                #[coverage(off)]
+                |info| info.age as f64
+            )
+            .energy(info(1, 7)),
+            7.0
+        );
+
+        let mut pool = SimplestToActivateCounterPool::new("cov", 4).with_energy_schedule(EnergySchedule::Exploit);
+        pool.process(PoolStorageIndex::mock(0), &vec![(0, 1)], 1.0);
+        pool.process(PoolStorageIndex::mock(1), &vec![(1, 1), (2, 1)], 1.0);
+        let energies = pool
+            .slab_inputs
+            .keys()
+            .map(
+                #[coverage(off)]
+                |key| pool.energy(&pool.slab_inputs[key]),
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(energies, vec![1.0, 2.0]);
+        for _ in 0..10 {
+            pool.get_random_index();
+        }
+        // the exploit schedule does not depend on the number of times that the inputs were chosen
+        assert!((pool.ranked_inputs.prefix_sum(1) - 3.0).abs() < 1e-9);
+
+        let mut pool = SimplestToActivateCounterPool::new("cov", 4).with_energy_schedule(EnergySchedule::Fast);
+        pool.process(PoolStorageIndex::mock(0), &vec![(0, 1)], 1.0);
+        pool.process(PoolStorageIndex::mock(1), &vec![(1, 1)], 1.0);
+        let energies = pool
+            .slab_inputs
+            .keys()
+            .map(
+                #[coverage(off)]
+                |key| pool.energy(&pool.slab_inputs[key]),
+            )
+            .collect::<Vec<_>>();
+        // the most recent input has more energy
+        assert_eq!(energies, vec![1.5, 2.0]);
+
+        // until a mutation of the first input is added to the corpus
+        pool.record_interesting_child(PoolStorageIndex::mock(0));
+        assert_eq!(pool.energy(&pool.slab_inputs[SlabKey::new(0)]), 2.0);
+        assert!((pool.ranked_inputs.prefix_sum(1) - 4.0).abs() < 1e-9);
+        // the indices that are not in the pool are ignored
+        pool.record_interesting_child(PoolStorageIndex::mock(7));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_random_index_of_empty_slot() {
+        let mut pool = SimplestToActivateCounterPool::new("cov", 4);
+        pool.process(PoolStorageIndex::mock(0), &vec![(0, 1)], 1.0);
+        pool.process(PoolStorageIndex::mock(1), &vec![(1, 1)], 1.0);
+        // some energy is left on slots past the end of the slab, which must not be chosen
+        let mut energies = vec![0.0; 8];
+        energies[7] = 1.0;
+        pool.ranked_inputs = FenwickTree::new(energies);
+        for _ in 0..10 {
+            let idx = pool.get_random_index().unwrap();
+            assert!(idx == PoolStorageIndex::mock(0) || idx == PoolStorageIndex::mock(1));
+        }
+        pool.sanity_check();
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_counter_weights() {
+        let mut pool = SimplestToActivateCounterPool::new("cov", 4)
+            .with_energy_schedule(EnergySchedule::Exploit)
+            .with_counter_weights(vec![10.0, 1.0, 1.0, 1.0]);
+        pool.process(PoolStorageIndex::mock(0), &vec![(0, 1)], 1.0);
+        pool.process(PoolStorageIndex::mock(1), &vec![(1, 1), (2, 1)], 1.0);
+        pool.sanity_check();
+        let scores = pool
+            .slab_inputs
+            .keys()
+            .map(
+                #[coverage(off)]
+                |key| pool.slab_inputs[key].score,
+            )
+            .collect::<Vec<_>>();
+        // the input activating the heavy counter is more interesting despite activating fewer counters
+        assert_eq!(scores, vec![10.0, 2.0]);
+
+        // the weighted scores are shared between the inputs activating the same counter
+        pool.process(PoolStorageIndex::mock(2), &vec![(0, 1), (3, 1)], 1.0);
+        pool.sanity_check();
+        assert!((pool.score() - 13.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_incremental_ranking() {
+        let mut pool = SimplestToActivateCounterPool::new("cov", 64);
+        for i in 0..500 {
+            let mut counters = (0..fastrand::usize(1..4))
+                .map(
+                    #[coverage(off)]
+                    |_| (fastrand::usize(0..64), 1),
+                )
+                .collect::<Vec<_>>();
+            counters.sort_unstable();
+            counters.dedup();
+            pool.process(PoolStorageIndex::mock(i), &counters, fastrand::f64() * 100.0);
+            pool.sanity_check();
+            // the energy of the explore schedule does not depend on the age of the inputs,
+            // so the ranking is always up to date
+            for key in pool.slab_inputs.keys() {
+                assert!((pool.slab_inputs[key].energy - pool.energy(&pool.slab_inputs[key])).abs() < 1e-9);
+            }
+            let live_inputs = pool
+                .slab_inputs
+                .keys()
+                .map(
+                    #[coverage(off)]
+                    |key| pool.slab_inputs[key].data,
+                )
+                .collect::<Vec<_>>();
+            for _ in 0..10 {
+                let chosen = pool.get_random_index().unwrap();
+                assert!(live_inputs.contains(&chosen));
+            }
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_max_size() {
+        let mut pool = SimplestToActivateCounterPool::new("cov", 8).with_max_size(2, EvictionPolicy::Oldest);
+        pool.process(PoolStorageIndex::mock(0), &vec![(0, 1), (1, 1)], 1.0);
+        pool.process(PoolStorageIndex::mock(1), &vec![(1, 1), (2, 1)], 2.0);
+        let deltas = pool.process(PoolStorageIndex::mock(2), &vec![(3, 1)], 1.0);
+        pool.sanity_check();
+        // the oldest input is evicted, and the counter it was the only one to activate is forgotten
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].remove, vec![PoolStorageIndex::mock(0)]);
+        assert_eq!(pool.stats().pool_size, 2);
+        assert_eq!(pool.stats().coverage.0, 3);
+        // the remaining input is now the least complex one activating counter 1
+        assert_eq!(pool.least_complexity_for_counter[1], 2.0);
+        assert_eq!(pool.least_complexity_for_counter[0], f64::INFINITY);
+        // and a new input activating the forgotten counter is interesting again
+        assert!(!pool.process(PoolStorageIndex::mock(3), &vec![(0, 1)], 5.0).is_empty());
+        pool.sanity_check();
+
+        let mut pool = SimplestToActivateCounterPool::new("cov", 8).with_max_size(2, EvictionPolicy::HighestComplexity);
+        pool.process(PoolStorageIndex::mock(0), &vec![(0, 1)], 3.0);
+        pool.process(PoolStorageIndex::mock(1), &vec![(1, 1)], 1.0);
+        let deltas = pool.process(PoolStorageIndex::mock(2), &vec![(2, 1)], 5.0);
+        assert_eq!(deltas[0].remove, vec![PoolStorageIndex::mock(0)]);
+
+        let mut pool = SimplestToActivateCounterPool::new("cov", 8).with_max_size(2, EvictionPolicy::LowestScore);
+        pool.process(PoolStorageIndex::mock(0), &vec![(0, 1), (1, 1), (2, 1)], 1.0);
+        pool.process(PoolStorageIndex::mock(1), &vec![(3, 1)], 1.0);
+        let deltas = pool.process(PoolStorageIndex::mock(2), &vec![(4, 1)], 1.0);
+        assert_eq!(deltas[0].remove, vec![PoolStorageIndex::mock(1)]);
+        pool.sanity_check();
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_max_size_random() {
+        let mut pool = SimplestToActivateCounterPool::new("cov", 64).with_max_size(10, EvictionPolicy::LowestScore);
+        for i in 0..500 {
+            let mut counters = (0..fastrand::usize(1..4))
+                .map(
+                    #[coverage(off)]
+                    |_| (fastrand::usize(0..64), 1),
+                )
+                .collect::<Vec<_>>();
+            counters.sort_unstable();
+            counters.dedup();
+            pool.process(PoolStorageIndex::mock(i), &counters, fastrand::f64() * 100.0);
+            pool.sanity_check();
+            assert!(pool.slab_inputs.len() <= 10);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    #[coverage(off)]
+    fn test_counter_weights_wrong_len() {
+        let _ = SimplestToActivateCounterPool::new("cov", 4).with_counter_weights(vec![1.0; 3]);
+    }
+
     #[coverage(off)]
     fn edge_f(index: usize, intensity: u16) -> CounterIdx {
         CounterIdx(index * 64 + intensity as usize)
@@ -0,0 +1,335 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::traits::{CorpusDelta, Pool, Stats};
+use crate::{CSVField, CompatibleWithObservations, PoolStorageIndex, SaveToStatsFolder, Sensor, ToCSV};
+
+extern "C" {
+    fn get_sancov_lowest_stack() -> usize;
+    fn reset_sancov_lowest_stack();
+}
+
+static LOWEST_STACK_ADDRESS: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Get the address of the current stack frame
+#[inline(never)]
+#[coverage(off)]
+fn stack_address() -> usize {
+    let local = 0u8;
+    std::hint::black_box(std::ptr::addr_of!(local) as usize)
+}
+
+/// Record the current depth of the stack, to be observed by a [`StackDepthSensor`].
+///
+/// This function should be called from the test function, typically at the start of
+/// each recursive function whose stack usage should be measured. It is very cheap
+/// and does nothing noticeable when no `StackDepthSensor` is used.
+#[inline(never)]
+#[coverage(off)]
+pub fn record_stack_depth() {
+    LOWEST_STACK_ADDRESS.fetch_min(stack_address(), Ordering::Relaxed);
+}
+
+/// A [`Sensor`](crate::Sensor) that records the maximum depth of the stack reached by the test function.
+///
+/// When the fuzz test is compiled with `cargo fuzzcheck --coverage-backend sancov`, every instrumented function
+/// records the address of its stack frame when it is called, so the stack depth is measured automatically.
+/// Otherwise, it is only measured at the points where the test function calls
+/// [`record_stack_depth()`](crate::sensors_and_pools::record_stack_depth).
+///
+/// Its [observations](crate::Sensor::Observations) are the maximum number of bytes between the
+/// stack frame of the fuzzer and one of those points, or 0 if the stack depth was never measured.
+/// This assumes that the stack grows downwards, which is the case on all the platforms supported by fuzzcheck.
+/// It can be paired with a [`StackDepthPool`] or a [`MaximiseObservationPool`](crate::sensors_and_pools::MaximiseObservationPool).
+///
+/// # Example
+///
+/// ```rust
+/// use fuzzcheck::sensors_and_pools::{record_stack_depth, StackDepthPool, StackDepthSensor};
+/// use fuzzcheck::{Arguments, ReasonForStopping};
+///
+/// fn count_nested_parentheses(xs: &[u8]) -> usize {
+///     record_stack_depth();
+///     match xs {
+///         [b'(', rest @ ..] => 1 + count_nested_parentheses(rest),
+///         _ => 0,
+///     }
+/// }
+///
+/// // This function fails when the input starts with 8 opening parentheses.
+/// // By trying to maximise the depth of the stack, the fuzzer can incrementally find the failing input.
+/// fn test_function(xs: &[u8]) -> bool {
+///     count_nested_parentheses(xs) < 8
+/// }
+///
+/// // The sensor can be paired with any pool which is compatible with
+/// // observations of type `u64`. For example, we can use the following
+/// // pool to keep the test cases with the deepest and the shallowest stack.
+/// let sensor = StackDepthSensor::default();
+/// let pool = StackDepthPool::new("stack_depth");
+///
+/// let result = fuzzcheck::fuzz_test(test_function)
+///     .default_mutator()
+///     .serde_serializer()
+///     .sensor_and_pool(sensor, pool)
+///     .arguments(Arguments::for_internal_documentation_test())
+///     .stop_after_first_test_failure(true)
+///     .launch();
+///
+/// assert!(matches!(
+///     result.reason_for_stopping,
+///     ReasonForStopping::TestFailure(x) if x.starts_with(b"((((((((")
+/// ));
+/// ```
+#[derive(Default)]
+pub struct StackDepthSensor {
+    start_address: usize,
+    lowest_address: usize,
+}
+
+impl SaveToStatsFolder for StackDepthSensor {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(std::path::PathBuf, Vec<u8>)> {
+        vec![]
+    }
+}
+impl Sensor for StackDepthSensor {
+    type Observations = u64;
+
+    #[coverage(off)]
+    fn start_recording(&mut self) {
+        self.start_address = stack_address();
+        LOWEST_STACK_ADDRESS.store(usize::MAX, Ordering::Relaxed);
+        unsafe { reset_sancov_lowest_stack() };
+    }
+
+    #[coverage(off)]
+    fn stop_recording(&mut self) {
+        // the lowest address recorded by the SanitizerCoverage instrumentation, if there is any
+        let sancov_lowest_address = unsafe { get_sancov_lowest_stack() };
+        self.lowest_address = LOWEST_STACK_ADDRESS.load(Ordering::Relaxed).min(sancov_lowest_address);
+    }
+
+    #[coverage(off)]
+    fn get_observations(&mut self) -> Self::Observations {
+        self.start_address.saturating_sub(self.lowest_address) as u64
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Input {
+    input_id: PoolStorageIndex,
+    depth: u64,
+    complexity: f64,
+}
+
+/// A pool that keeps the test case reaching the deepest stack and the one reaching the shallowest stack.
+///
+/// It is compatible with the observations of a [`StackDepthSensor`]. The test cases whose stack depth was
+/// not measured, i.e. is 0, are ignored. The two test cases are saved in the `<name>/deepest` and
+/// `<name>/shallowest` subfolders of the output corpus, respectively.
+///
+/// The deepest stack helps finding the test cases that cause a stack overflow, for example through unbounded
+/// recursion, while the shallowest stack helps finding the test cases that skip most of the test function.
+pub struct StackDepthPool {
+    name: String,
+    deepest: Option<Input>,
+    shallowest: Option<Input>,
+    rng: fastrand::Rng,
+}
+
+impl StackDepthPool {
+    #[coverage(off)]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            deepest: None,
+            shallowest: None,
+            rng: fastrand::Rng::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct StackDepthPoolStats {
+    name: String,
+    deepest: u64,
+    shallowest: u64,
+}
+impl Display for StackDepthPoolStats {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({}..={})", self.name, self.shallowest, self.deepest)
+    }
+}
+impl ToCSV for StackDepthPoolStats {
+    #[coverage(off)]
+    fn csv_headers(&self) -> Vec<CSVField> {
+        vec![
+            CSVField::String(format!("{}-deepest", self.name)),
+            CSVField::String(format!("{}-shallowest", self.name)),
+        ]
+    }
+    #[coverage(off)]
+    fn to_csv_record(&self) -> Vec<CSVField> {
+        vec![
+            CSVField::Integer(self.deepest as isize),
+            CSVField::Integer(self.shallowest as isize),
+        ]
+    }
+}
+impl Stats for StackDepthPoolStats {}
+
+impl Pool for StackDepthPool {
+    type Stats = StackDepthPoolStats;
+
+    #[coverage(off)]
+    fn stats(&self) -> Self::Stats {
+        let depth = #[coverage(off)]
+        |input: &Option<Input>| {
+            input
+                .map(
+                    #[coverage(off)]
+                    |input| input.depth,
+                )
+                .unwrap_or_default()
+        };
+        StackDepthPoolStats {
+            name: self.name.clone(),
+            deepest: depth(&self.deepest),
+            shallowest: depth(&self.shallowest),
+        }
+    }
+    #[coverage(off)]
+    fn get_random_index(&mut self) -> Option<PoolStorageIndex> {
+        let (first, second) = if self.rng.bool() {
+            (self.deepest, self.shallowest)
+        } else {
+            (self.shallowest, self.deepest)
+        };
+        first.or(second).map(
+            #[coverage(off)]
+            |input| input.input_id,
+        )
+    }
+}
+
+impl SaveToStatsFolder for StackDepthPool {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        vec![]
+    }
+}
+
+/// Replace `current` by the new input if `is_better` or if it is as good and simpler, and return the
+/// corresponding corpus delta
+#[coverage(off)]
+fn process_depth(
+    current: &mut Option<Input>,
+    new: Input,
+    is_better: impl Fn(u64, u64) -> bool,
+    path: PathBuf,
+) -> Option<CorpusDelta> {
+    let is_interesting = if let Some(current) = current {
+        is_better(new.depth, current.depth) || (new.depth == current.depth && new.complexity < current.complexity)
+    } else {
+        true
+    };
+    if !is_interesting {
+        return None;
+    }
+    let remove = current
+        .map(
+            #[coverage(off)]
+            |current| vec![current.input_id],
+        )
+        .unwrap_or_default();
+    *current = Some(new);
+    Some(CorpusDelta::new(path, true, remove))
+}
+
+impl CompatibleWithObservations<u64> for StackDepthPool {
+    #[coverage(off)]
+    fn process(&mut self, input_id: PoolStorageIndex, observations: &u64, complexity: f64) -> Vec<CorpusDelta> {
+        let depth = *observations;
+        if depth == 0 {
+            return vec![];
+        }
+        let path = PathBuf::new().join(&self.name);
+        let new = Input {
+            input_id,
+            depth,
+            complexity,
+        };
+        process_depth(
+            &mut self.deepest,
+            new,
+            #[coverage(off)]
+            |new, current| new > current,
+            path.join("deepest"),
+        )
+        .into_iter()
+        .chain(process_depth(
+            &mut self.shallowest,
+            new,
+            #[coverage(off)]
+            |new, current| new < current,
+            path.join("shallowest"),
+        ))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[inline(never)]
+    #[coverage(off)]
+    fn recurse(depth: usize) -> usize {
+        record_stack_depth();
+        if depth == 0 {
+            0
+        } else {
+            1 + std::hint::black_box(recurse(depth - 1))
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_stack_depth_sensor() {
+        let mut sensor = StackDepthSensor::default();
+        let mut depth = #[coverage(off)]
+        |n: usize| {
+            sensor.start_recording();
+            std::hint::black_box(recurse(n));
+            sensor.stop_recording();
+            sensor.get_observations()
+        };
+        let shallow = depth(1);
+        assert!(shallow > 0);
+        assert!(depth(100) > shallow);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_stack_depth_pool() {
+        let mut pool = StackDepthPool::new("stack");
+        assert_eq!(pool.process(PoolStorageIndex::mock(0), &100, 1.0).len(), 2);
+        // the stack depth was not measured
+        assert!(pool.process(PoolStorageIndex::mock(1), &0, 0.5).is_empty());
+        let deltas = pool.process(PoolStorageIndex::mock(2), &200, 1.0);
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].path, PathBuf::new().join("stack").join("deepest"));
+        assert_eq!(deltas[0].remove, vec![PoolStorageIndex::mock(0)]);
+        // same depth as the shallowest, but simpler
+        let deltas = pool.process(PoolStorageIndex::mock(3), &100, 0.5);
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].path, PathBuf::new().join("stack").join("shallowest"));
+        assert!(pool.process(PoolStorageIndex::mock(4), &150, 1.0).is_empty());
+
+        let stats = pool.stats();
+        assert_eq!((stats.deepest, stats.shallowest), (200, 100));
+    }
+}
@@ -10,6 +10,9 @@ const NBR_ARTIFACTS_PER_ERROR_AND_CPLX: usize = 8;
 
 pub(crate) static mut TEST_FAILURE: Option<TestFailure> = None;
 
+/// The description of the test failures caused by a test function that timed out
+pub(crate) const TIMEOUT_DISPLAY: &str = "the test function timed out";
+
 /// A type describing a test failure.
 ///
 /// It is uniquely identifiable through `self.id` and displayable through `self.display`.
@@ -94,6 +97,8 @@ struct TestFailureListForError {
 /// A pool that saves failing test cases.
 ///
 /// It categorizes the test cases by their failure information and sort them by complexity.
+/// The saved test cases are tagged as `test_failure` in the output corpus, and those that timed out are also
+/// tagged as `timeout`.
 pub struct TestFailurePool {
     name: String,
     inputs: Vec<TestFailureList>,
@@ -228,10 +233,15 @@ impl CompatibleWithObservations<Option<TestFailure>> for TestFailurePool {
                     }
                 };
 
+                let mut tags = vec!["test_failure".to_string()];
+                if error.display == TIMEOUT_DISPLAY {
+                    tags.push("timeout".to_string());
+                }
                 let delta = CorpusDelta {
                     path,
                     add: true,
                     remove: vec![],
+                    tags,
                 };
                 return vec![delta];
             }
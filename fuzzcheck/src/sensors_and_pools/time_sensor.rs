@@ -0,0 +1,317 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::traits::{CorpusDelta, Pool, SaveToStatsFolder, Stats};
+use crate::{CSVField, CompatibleWithObservations, PoolStorageIndex, Sensor, ToCSV};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Clock {
+    WallClock,
+    CpuTime,
+}
+
+/// A [`Sensor`](crate::Sensor) that records the time taken by each run of the test function.
+///
+/// Its [observations](crate::Sensor::Observations) are a [`Duration`]. It can measure either:
+/// * the elapsed wall-clock time, with [`TimeSensor::wall_clock()`] (also the default)
+/// * the CPU time spent by the current thread, with [`TimeSensor::cpu_time()`], which is less
+///   sensitive to the other processes running on the machine
+///
+/// It is typically paired with a [`MaximiseTimePool`] in order to find inputs that make the
+/// test function unusually slow, such as those triggering the worst-case complexity of an algorithm.
+///
+/// # Example
+/// ```rust
+/// use fuzzcheck::sensors_and_pools::{MaximiseTimePool, TimeSensor};
+/// use fuzzcheck::Arguments;
+///
+/// // This function is quadratic in the number of zeros at the start of `xs`.
+/// fn test_function(xs: &[u8]) -> bool {
+///     let zeros = xs.iter().take_while(|&&x| x == 0).count();
+///     let mut count = 0;
+///     for i in 0..zeros {
+///         for j in 0..zeros {
+///             count += std::hint::black_box(i ^ j) % 2;
+///         }
+///     }
+///     count <= zeros * zeros
+/// }
+///
+/// let result = fuzzcheck::fuzz_test(test_function)
+///     .default_mutator()
+///     .serde_serializer()
+///     .sensor_and_pool(TimeSensor::cpu_time(), MaximiseTimePool::new("slowest", 10))
+///     .arguments(Arguments::for_internal_documentation_test())
+///     .stop_after_iterations(1000)
+///     .launch();
+///
+/// assert!(!result.found_test_failure);
+/// ```
+pub struct TimeSensor {
+    clock: Clock,
+    start: Duration,
+    end: Duration,
+}
+
+impl Default for TimeSensor {
+    #[coverage(off)]
+    fn default() -> Self {
+        Self::wall_clock()
+    }
+}
+
+impl TimeSensor {
+    /// A sensor measuring the elapsed wall-clock time of each test run
+    #[coverage(off)]
+    pub fn wall_clock() -> Self {
+        Self {
+            clock: Clock::WallClock,
+            start: Duration::ZERO,
+            end: Duration::ZERO,
+        }
+    }
+    /// A sensor measuring the CPU time spent by the current thread during each test run
+    ///
+    /// On non-unix platforms, it measures the elapsed wall-clock time instead.
+    #[coverage(off)]
+    pub fn cpu_time() -> Self {
+        Self {
+            clock: Clock::CpuTime,
+            start: Duration::ZERO,
+            end: Duration::ZERO,
+        }
+    }
+    #[coverage(off)]
+    fn now(&self) -> Duration {
+        match self.clock {
+            Clock::WallClock => {
+                thread_local! {
+                    static ORIGIN: Instant = Instant::now();
+                }
+                ORIGIN.with(
+                    #[coverage(off)]
+                    |origin| origin.elapsed(),
+                )
+            }
+            #[cfg(unix)]
+            Clock::CpuTime => {
+                let mut time = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+                unsafe {
+                    libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time);
+                }
+                Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+            }
+            #[cfg(not(unix))]
+            Clock::CpuTime => Self::wall_clock().now(),
+        }
+    }
+}
+
+impl SaveToStatsFolder for TimeSensor {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        vec![]
+    }
+}
+impl Sensor for TimeSensor {
+    type Observations = Duration;
+
+    #[coverage(off)]
+    fn start_recording(&mut self) {
+        self.start = self.now();
+    }
+
+    #[coverage(off)]
+    fn stop_recording(&mut self) {
+        self.end = self.now();
+    }
+
+    #[coverage(off)]
+    fn get_observations(&mut self) -> Self::Observations {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+struct Input {
+    input_id: PoolStorageIndex,
+    duration: Duration,
+    complexity: f64,
+}
+
+/// A pool that keeps the `N` test cases which took the longest time to run.
+///
+/// It is compatible with the observations of a [`TimeSensor`], or any other sensor whose observations are a [`Duration`].
+///
+/// At the end of the fuzz test, the durations of the retained test cases are written to `<name>.json` in the stats folder,
+/// indexed by their [`PoolStorageIndex`]. These indices can be matched with the names of the files in the output corpus
+/// using the `world.json` file of the same stats folder. The retained test cases are tagged as `slow` in the
+/// output corpus.
+pub struct MaximiseTimePool {
+    name: String,
+    size: usize,
+    /// The retained test cases, sorted from the slowest to the fastest
+    inputs: Vec<Input>,
+    rng: fastrand::Rng,
+}
+
+impl MaximiseTimePool {
+    /// Create a pool that keeps the `size` slowest test cases.
+    ///
+    /// The `name` of the pool is used for its statistics and its folder in the output corpus.
+    #[coverage(off)]
+    pub fn new(name: &str, size: usize) -> Self {
+        assert!(size > 0, "the size of a MaximiseTimePool must be greater than 0");
+        Self {
+            name: name.to_string(),
+            size,
+            inputs: vec![],
+            rng: fastrand::Rng::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MaximiseTimePoolStats {
+    name: String,
+    slowest: Duration,
+}
+impl Display for MaximiseTimePoolStats {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({:.2?})", self.name, self.slowest)
+    }
+}
+impl ToCSV for MaximiseTimePoolStats {
+    #[coverage(off)]
+    fn csv_headers(&self) -> Vec<CSVField> {
+        vec![CSVField::String(format!("{}-slowest-ns", self.name))]
+    }
+    #[coverage(off)]
+    fn to_csv_record(&self) -> Vec<CSVField> {
+        vec![CSVField::Integer(self.slowest.as_nanos() as isize)]
+    }
+}
+impl Stats for MaximiseTimePoolStats {}
+
+impl Pool for MaximiseTimePool {
+    type Stats = MaximiseTimePoolStats;
+
+    #[coverage(off)]
+    fn stats(&self) -> Self::Stats {
+        MaximiseTimePoolStats {
+            name: self.name.clone(),
+            slowest: self
+                .inputs
+                .first()
+                .map(
+                    #[coverage(off)]
+                    |input| input.duration,
+                )
+                .unwrap_or_default(),
+        }
+    }
+    #[coverage(off)]
+    fn get_random_index(&mut self) -> Option<PoolStorageIndex> {
+        if self.inputs.is_empty() {
+            None
+        } else {
+            Some(self.inputs[self.rng.usize(..self.inputs.len())].input_id)
+        }
+    }
+}
+
+impl SaveToStatsFolder for MaximiseTimePool {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "serde_json_serializer")] {
+                let durations = self
+                    .inputs
+                    .iter()
+                    .map(
+                        #[coverage(off)]
+                        |input| (input.input_id, input.duration.as_nanos() as u64),
+                    )
+                    .collect::<Vec<_>>();
+                let content = serde_json::to_vec(&durations).unwrap();
+                vec![(PathBuf::new().join(&self.name).with_extension("json"), content)]
+            } else {
+                vec![]
+            }
+        }
+    }
+}
+
+impl CompatibleWithObservations<Duration> for MaximiseTimePool {
+    #[coverage(off)]
+    fn process(&mut self, input_id: PoolStorageIndex, observations: &Duration, complexity: f64) -> Vec<CorpusDelta> {
+        let duration = *observations;
+        let is_interesting = self.inputs.len() < self.size
+            || self.inputs.last().is_some_and(
+                #[coverage(off)]
+                |fastest| {
+                    duration > fastest.duration || (duration == fastest.duration && complexity < fastest.complexity)
+                },
+            );
+        if !is_interesting {
+            return vec![];
+        }
+        let remove = if self.inputs.len() == self.size {
+            vec![self.inputs.pop().unwrap().input_id]
+        } else {
+            vec![]
+        };
+        let position = self.inputs.partition_point(
+            #[coverage(off)]
+            |input| input.duration >= duration,
+        );
+        self.inputs.insert(
+            position,
+            Input {
+                input_id,
+                duration,
+                complexity,
+            },
+        );
+        vec![CorpusDelta {
+            path: PathBuf::new().join(&self.name),
+            add: true,
+            remove,
+            tags: vec!["slow".to_string()],
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_maximise_time_pool_keeps_slowest_inputs() {
+        let mut pool = MaximiseTimePool::new("slowest", 2);
+        let durations = [3, 1, 2, 5, 4];
+        let mut removed = vec![];
+        for (i, &ms) in durations.iter().enumerate() {
+            let deltas = pool.process(PoolStorageIndex::mock(i), &Duration::from_millis(ms), 1.0);
+            for delta in deltas {
+                removed.extend(delta.remove);
+            }
+        }
+        let kept = pool
+            .inputs
+            .iter()
+            .map(
+                #[coverage(off)]
+                |input| input.input_id,
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(kept, vec![PoolStorageIndex::mock(3), PoolStorageIndex::mock(4)]);
+        assert_eq!(
+            removed,
+            vec![PoolStorageIndex::mock(1), PoolStorageIndex::mock(2), PoolStorageIndex::mock(0)]
+        );
+        assert_eq!(pool.stats().slowest, Duration::from_millis(5));
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
+use std::mem::size_of;
 use std::path::Path;
 
 use ahash::{AHashMap, AHashSet};
@@ -13,16 +14,28 @@ use crate::{CompatibleWithObservations, PoolStorageIndex, ToCSV};
 pub struct UniqueValuesPoolStats {
     pub name: String,
     pub size: usize,
+    /// The number of distinct `(counter, value)` pairs remembered by the pool
+    pub nbr_values: usize,
+    /// An estimate of the memory used by the pool to remember the values and the test cases, in bytes
+    pub estimated_bytes: usize,
 }
 impl ToCSV for UniqueValuesPoolStats {
     #[coverage(off)]
     fn csv_headers(&self) -> Vec<crate::CSVField> {
-        vec![]
+        vec![
+            crate::CSVField::String(format!("{}-size", self.name)),
+            crate::CSVField::String(format!("{}-nbr-values", self.name)),
+            crate::CSVField::String(format!("{}-estimated-bytes", self.name)),
+        ]
     }
 
     #[coverage(off)]
     fn to_csv_record(&self) -> Vec<crate::CSVField> {
-        vec![]
+        vec![
+            crate::CSVField::Integer(self.size as isize),
+            crate::CSVField::Integer(self.nbr_values as isize),
+            crate::CSVField::Integer(self.estimated_bytes as isize),
+        ]
     }
 }
 
@@ -32,7 +45,13 @@ impl Display for UniqueValuesPoolStats {
         write!(
             f,
             "{}",
-            nu_ansi_term::Color::Blue.paint(format!("{}({})", self.name, self.size))
+            nu_ansi_term::Color::Blue.paint(format!(
+                "{}({} values: {} mem: {}KiB)",
+                self.name,
+                self.size,
+                self.nbr_values,
+                self.estimated_bytes / 1024
+            ))
         )
     }
 }
@@ -50,6 +69,18 @@ where
 }
 
 /// A pool that stores an input for each different value of each sensor counter
+///
+/// By default, the pool remembers every value that was ever observed, which can use a lot of
+/// memory when the observations have a high cardinality, such as hit counts. This can be controlled with:
+/// * [`self.with_bucketing(..)`](UniqueValuesPool::with_bucketing), which maps each value to a bucket
+///   before it is compared with the other values, for example with [`log2_bucket`]
+/// * [`self.with_max_values_per_counter(..)`](UniqueValuesPool::with_max_values_per_counter), which
+///   ignores the new values of a counter once it has reached the given number of distinct values
+/// * [`self.with_max_inputs(..)`](UniqueValuesPool::with_max_inputs), which evicts the test cases
+///   that are the best for the fewest values once the pool exceeds the given size, and forgets these values
+///
+/// The number of values remembered by the pool and an estimate of its memory usage are reported in its
+/// [statistics](UniqueValuesPoolStats).
 pub struct UniqueValuesPool<T>
 where
     T: Hash + Eq + Clone,
@@ -59,6 +90,9 @@ where
     inputs: Slab<Input<T>>,
     best_input_for_value: Vec<AHashMap<T, SlabKey<Input<T>>>>,
     ranked_inputs: FenwickTree,
+    bucketing: Option<fn(T) -> T>,
+    max_values_per_counter: usize,
+    max_inputs: usize,
     stats: UniqueValuesPoolStats,
     rng: fastrand::Rng,
 }
@@ -90,13 +124,73 @@ where
             inputs: Slab::new(),
             best_input_for_value: vec![AHashMap::new(); size],
             ranked_inputs: FenwickTree::new(vec![]),
+            bucketing: None,
+            max_values_per_counter: usize::MAX,
+            max_inputs: usize::MAX,
             stats: UniqueValuesPoolStats {
                 name: name.to_string(),
                 size: 0,
+                nbr_values: 0,
+                estimated_bytes: 0,
             },
             rng: fastrand::Rng::new(),
         }
     }
+
+    /// Map each observed value to a bucket before processing it.
+    ///
+    /// Two values in the same bucket are considered equal by the pool. For example, using
+    /// [`log2_bucket`] for hit counts means that the pool only keeps test cases reaching a new order of magnitude
+    /// for one of the counters.
+    #[must_use]
+    #[coverage(off)]
+    pub fn with_bucketing(self, bucketing: fn(T) -> T) -> Self {
+        Self {
+            bucketing: Some(bucketing),
+            ..self
+        }
+    }
+
+    /// Set the maximum number of distinct values that the pool remembers for each counter.
+    ///
+    /// Once a counter has reached that number, its new values are ignored. The test cases that
+    /// are simpler than the current ones for an already known value are still kept.
+    #[must_use]
+    #[coverage(off)]
+    pub fn with_max_values_per_counter(self, max_values_per_counter: usize) -> Self {
+        Self {
+            max_values_per_counter,
+            ..self
+        }
+    }
+
+    /// Set the maximum number of test cases stored by the pool.
+    ///
+    /// When there are too many of them, the test case that is the best for the fewest values is evicted.
+    /// The values it was the best for are forgotten as well, such that the memory used by the pool stays
+    /// bounded. They are considered new again if a later test case reaches them.
+    #[must_use]
+    #[coverage(off)]
+    pub fn with_max_inputs(self, max_inputs: usize) -> Self {
+        assert!(
+            max_inputs > 0,
+            "a UniqueValuesPool must be able to store at least one input"
+        );
+        Self { max_inputs, ..self }
+    }
+}
+
+/// Map a value to the largest power of two that is smaller than or equal to it, or 0 if it is 0.
+///
+/// It can be used as the bucketing function of a [`UniqueValuesPool`] with
+/// [`with_bucketing`](UniqueValuesPool::with_bucketing).
+#[coverage(off)]
+pub fn log2_bucket(x: u64) -> u64 {
+    if x == 0 {
+        0
+    } else {
+        1 << x.ilog2()
+    }
 }
 
 impl<T> Pool for UniqueValuesPool<T>
@@ -158,6 +252,70 @@ where
         self.ranked_inputs = FenwickTree::new(ranked_inputs);
 
         self.stats.size = self.inputs.len();
+        self.stats.nbr_values = self
+            .complexities
+            .iter()
+            .map(
+                #[coverage(off)]
+                |values| values.len(),
+            )
+            .sum();
+        self.stats.estimated_bytes = self.estimated_bytes();
+    }
+
+    /// An estimate of the memory used by the tables of the pool, which ignores the overhead of the hash maps
+    #[coverage(off)]
+    fn estimated_bytes(&self) -> usize {
+        let values: usize = self
+            .complexities
+            .iter()
+            .map(
+                #[coverage(off)]
+                |values| values.capacity() * size_of::<(T, f64)>(),
+            )
+            .sum();
+        let best_inputs: usize = self
+            .best_input_for_value
+            .iter()
+            .map(
+                #[coverage(off)]
+                |inputs| inputs.capacity() * size_of::<(T, SlabKey<Input<T>>)>(),
+            )
+            .sum();
+        let inputs: usize = self
+            .inputs
+            .keys()
+            .map(
+                #[coverage(off)]
+                |key| size_of::<Input<T>>() + self.inputs[key].best_for_values.capacity() * size_of::<(usize, T)>(),
+            )
+            .sum();
+        values + best_inputs + inputs
+    }
+
+    /// Remove the input that is the best for the fewest values, except for `except`
+    #[coverage(off)]
+    fn evict_least_valuable_input(&mut self, except: SlabKey<Input<T>>) -> Option<PoolStorageIndex> {
+        let inputs = &self.inputs;
+        let key = self
+            .inputs
+            .keys()
+            .filter(
+                #[coverage(off)]
+                |&key| key != except,
+            )
+            .min_by(
+                #[coverage(off)]
+                |&k1, &k2| inputs[k1].score.total_cmp(&inputs[k2].score),
+            )?;
+        let input = &mut self.inputs[key];
+        for (counter, value) in input.best_for_values.drain() {
+            self.best_input_for_value[counter].remove(&value);
+            self.complexities[counter].remove(&value);
+        }
+        let data = input.data;
+        self.inputs.remove(key);
+        Some(data)
     }
 }
 
@@ -168,15 +326,20 @@ where
 {
     #[coverage(off)]
     fn process(&mut self, input_id: PoolStorageIndex, observations: &O, complexity: f64) -> Vec<CorpusDelta> {
-        let mut state = vec![];
+        let mut state = AHashSet::new();
         for &(index, v) in observations.into_iter() {
+            let v = if let Some(bucketing) = self.bucketing {
+                bucketing(v)
+            } else {
+                v
+            };
             if let Some(&previous_cplx) = self.complexities[index].get(&v) {
                 if previous_cplx > complexity {
                     // already exists but this one is better
-                    state.push((index, v));
+                    state.insert((index, v));
                 }
-            } else {
-                state.push((index, v));
+            } else if self.complexities[index].len() < self.max_values_per_counter {
+                state.insert((index, v));
             }
         }
         if state.is_empty() {
@@ -186,10 +349,9 @@ where
         let new_observations = state;
         let score = new_observations.len() as f64;
         let cplx = complexity;
-        let input = input_id;
         let input = Input {
-            best_for_values: AHashSet::new(), // fill in later! with new_observations.into_iter().collect(),
-            data: input,
+            best_for_values: new_observations.clone(),
+            data: input_id,
             score,
             number_times_chosen: 1,
         };
@@ -198,38 +360,115 @@ where
 
         let mut removed_keys = vec![];
 
-        for (counter, id) in &new_observations {
-            self.complexities[*counter].insert(*id, cplx);
+        for &(counter, id) in &new_observations {
+            self.complexities[counter].insert(id, cplx);
 
-            let previous_best_key = self.best_input_for_value[*counter].get_mut(id);
-            if let Some(previous_best_key) = previous_best_key {
-                let previous_best = &mut self.inputs[*previous_best_key];
-                let was_present_in_set = previous_best.best_for_values.remove(&(*counter, *id));
+            if let Some(previous_best_key) = self.best_input_for_value[counter].insert(id, input_key) {
+                let previous_best = &mut self.inputs[previous_best_key];
+                let was_present_in_set = previous_best.best_for_values.remove(&(counter, id));
                 assert!(was_present_in_set);
                 previous_best.score = previous_best.best_for_values.len() as f64;
                 if previous_best.best_for_values.is_empty() {
-                    removed_keys.push(*previous_best_key);
+                    removed_keys.push(previous_best_key);
                 }
-                *previous_best_key = input_key;
-            } else {
-                self.best_input_for_value[*counter].insert(*id, input_key);
             }
         }
-        for &removed_key in &removed_keys {
-            self.inputs.remove(removed_key);
-        }
-        let removed_keys = removed_keys
+        let mut removed_keys: Vec<PoolStorageIndex> = removed_keys
             .into_iter()
             .map(
                 #[coverage(off)]
-                |k| self.inputs[k].data,
+                |k| {
+                    let data = self.inputs[k].data;
+                    self.inputs.remove(k);
+                    data
+                },
             )
             .collect();
+        while self.inputs.len() > self.max_inputs {
+            let Some(removed) = self.evict_least_valuable_input(input_key) else {
+                break;
+            };
+            removed_keys.push(removed);
+        }
         self.update_stats();
         vec![CorpusDelta {
             path: Path::new(&self.name).to_path_buf(),
             add: true,
             remove: removed_keys,
+            tags: vec![],
         }]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[coverage(off)]
+    fn removed_inputs(deltas: Vec<CorpusDelta>) -> Vec<PoolStorageIndex> {
+        deltas
+            .into_iter()
+            .flat_map(
+                #[coverage(off)]
+                |delta| delta.remove,
+            )
+            .collect()
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_log2_bucket() {
+        let buckets = [0, 1, 2, 3, 4, 7, 8, 1000].map(log2_bucket);
+        assert_eq!(buckets, [0, 1, 2, 2, 4, 4, 8, 512]);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_bucketing_and_max_values_per_counter() {
+        let mut pool = UniqueValuesPool::<u64>::new("values", 1)
+            .with_bucketing(log2_bucket)
+            .with_max_values_per_counter(2);
+
+        assert_eq!(pool.process(PoolStorageIndex::mock(0), &vec![(0, 4)], 1.0).len(), 1);
+        // same bucket as 4
+        assert!(pool.process(PoolStorageIndex::mock(1), &vec![(0, 5)], 1.0).is_empty());
+        assert_eq!(pool.process(PoolStorageIndex::mock(2), &vec![(0, 8)], 1.0).len(), 1);
+        // the counter already has two distinct values
+        assert!(pool.process(PoolStorageIndex::mock(3), &vec![(0, 100)], 1.0).is_empty());
+        // but a simpler input for a known value is still interesting
+        let deltas = pool.process(PoolStorageIndex::mock(4), &vec![(0, 6)], 0.5);
+        assert_eq!(removed_inputs(deltas), vec![PoolStorageIndex::mock(0)]);
+        assert_eq!(pool.stats().size, 2);
+        assert_eq!(pool.stats().nbr_values, 2);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_max_inputs() {
+        let mut pool = UniqueValuesPool::<u64>::new("values", 3).with_max_inputs(2);
+
+        pool.process(PoolStorageIndex::mock(0), &vec![(0, 1), (1, 1)], 1.0);
+        pool.process(PoolStorageIndex::mock(1), &vec![(2, 1)], 1.0);
+        // the pool is full, the input that is the best for the fewest values is evicted
+        let deltas = pool.process(PoolStorageIndex::mock(2), &vec![(2, 2)], 1.0);
+        assert_eq!(removed_inputs(deltas), vec![PoolStorageIndex::mock(1)]);
+        assert_eq!(pool.stats().size, 2);
+        assert_eq!(pool.stats().nbr_values, 3);
+        // the values of the evicted input are forgotten, so they are new again
+        let deltas = pool.process(PoolStorageIndex::mock(3), &vec![(2, 1)], 1.0);
+        assert_eq!(removed_inputs(deltas), vec![PoolStorageIndex::mock(2)]);
+        assert_eq!(pool.stats().nbr_values, 3);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_stats() {
+        let mut pool = UniqueValuesPool::<u64>::new("values", 2);
+        assert_eq!(pool.stats().estimated_bytes, 0);
+        pool.process(PoolStorageIndex::mock(0), &vec![(0, 1), (1, 1)], 1.0);
+        let stats = pool.stats();
+        assert_eq!(stats.nbr_values, 2);
+        assert!(stats.estimated_bytes > 0);
+        assert_eq!(stats.to_csv_record().len(), stats.csv_headers().len());
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use ahash::AHashMap;
+
+use crate::{SaveToStatsFolder, Sensor};
+
+static IS_RECORDING: AtomicBool = AtomicBool::new(false);
+static USER_EVENTS: Mutex<Vec<(u64, u64)>> = Mutex::new(Vec::new());
+/// The labels of the assertions that were violated while a `UserEventSensor` was recording, by the key of their event
+static ASSERTION_LABELS: Mutex<BTreeMap<u64, &'static str>> = Mutex::new(BTreeMap::new());
+
+/// Record that the event identified by `key` happened with the given `value`, to be observed by a [`UserEventSensor`].
+///
+/// This function should be called from the test function, for example to indicate that a parser
+/// reached a specific state. It does nothing when no `UserEventSensor` is recording.
+///
+/// If the same event is recorded multiple times during a run of the test function, only its highest value is kept.
+#[coverage(off)]
+pub fn record_event(key: u64, value: u64) {
+    if IS_RECORDING.load(Ordering::Relaxed) {
+        USER_EVENTS
+            .lock()
+            .expect("the user events could not be recorded")
+            .push((key, value));
+    }
+}
+
+/// Check that the condition holds. Otherwise, record a violation of the assertion identified by the given label,
+/// as an event observed by a [`UserEventSensor`](crate::sensors_and_pools::UserEventSensor).
+///
+/// Unlike `assert!`, a violated `fuzz_assert!` does not make the test function fail. Instead, the fuzzer
+/// keeps the simplest test cases violating each label in its corpus, which makes it possible to explore
+/// many different violations in a single fuzz test before hardening the assertions into real test failures.
+///
+/// ```
+/// use fuzzcheck::fuzz_assert;
+///
+/// fn test_function(xs: &[u8]) {
+///     fuzz_assert!(xs.len() < 8, "short input");
+///     fuzz_assert!(!xs.contains(&0), "no zero byte");
+/// }
+/// # test_function(&[1, 2, 3]);
+/// ```
+#[macro_export]
+macro_rules! fuzz_assert {
+    ($cond:expr, $label:expr $(,)?) => {
+        if !$cond {
+            $crate::sensors_and_pools::record_assertion_violation($label);
+        }
+    };
+}
+
+/// Record a violation of the assertion identified by `label`, as an event observed by a [`UserEventSensor`].
+///
+/// It is called by the [`fuzz_assert!`](crate::fuzz_assert) macro. The key of the event is a hash of the label,
+/// and its value is 1. Like [`record_event`], it does nothing when no `UserEventSensor` is recording.
+#[coverage(off)]
+pub fn record_assertion_violation(label: &'static str) {
+    if IS_RECORDING.load(Ordering::Relaxed) {
+        let mut hasher = DefaultHasher::new();
+        label.hash(&mut hasher);
+        let key = hasher.finish();
+        ASSERTION_LABELS
+            .lock()
+            .expect("the assertion violation could not be recorded")
+            .insert(key, label);
+        record_event(key, 1);
+    }
+}
+
+/// A [`Sensor`](crate::Sensor) that records the events passed to [`record_event(key, value)`](crate::record_event)
+/// by the test function.
+///
+/// Its [observations](crate::Sensor::Observations) are a list of `(index, value)` pairs, sorted by index,
+/// where each index uniquely identifies the `key` of an event, and `value` is the highest value recorded
+/// for that event. The indices are assigned in the order in which the keys are first seen, and
+/// are always lower than the `max_nbr_keys` given to [`UserEventSensor::new`]. The events whose
+/// key cannot be assigned an index anymore are ignored. The mapping from index to key is saved in the
+/// `user_events.json` file of the stats folder.
+///
+/// The sensor also records the violations of the assertions checked with [`fuzz_assert!`](crate::fuzz_assert),
+/// whose labels are saved in the `assertions.json` file of the stats folder as a list of `(index, label)` pairs.
+///
+/// The sensor can therefore be paired with any pool that is compatible with the observations of the
+/// [`CodeCoverageSensor`](crate::sensors_and_pools::CodeCoverageSensor), such as the
+/// [`SimplestToActivateCounterPool`](crate::sensors_and_pools::SimplestToActivateCounterPool) or the
+/// [`UniqueValuesPool`](crate::sensors_and_pools::UniqueValuesPool), created with `max_nbr_keys` counters.
+///
+/// # Example
+/// ```
+/// use fuzzcheck::sensors_and_pools::{SimplestToActivateCounterPool, UserEventSensor};
+/// use fuzzcheck::{record_event, Arguments, ReasonForStopping};
+///
+/// // A small state machine, which fails when it reaches its last state.
+/// fn test_function(xs: &[u8]) -> bool {
+///     let expected = b"fuzz!";
+///     let mut state = 0;
+///     for &x in xs {
+///         if x == expected[state] {
+///             state += 1;
+///             // tell the fuzzer that this state was reached
+///             record_event(state as u64, 1);
+///             if state == expected.len() {
+///                 return false;
+///             }
+///         } else {
+///             state = 0;
+///         }
+///     }
+///     true
+/// }
+///
+/// let sensor = UserEventSensor::new(8);
+/// let pool = SimplestToActivateCounterPool::new("user_events", 8);
+///
+/// let result = fuzzcheck::fuzz_test(test_function)
+///     .default_mutator()
+///     .serde_serializer()
+///     .sensor_and_pool(sensor, pool)
+///     .arguments(Arguments::for_internal_documentation_test())
+///     .stop_after_first_test_failure(true)
+///     .launch();
+///
+/// assert!(matches!(result.reason_for_stopping, ReasonForStopping::TestFailure(_)));
+/// ```
+///
+/// With [`fuzz_assert!`](crate::fuzz_assert), the corpus keeps the simplest test case violating each assertion,
+/// and violated assertions are not test failures:
+/// ```
+/// use fuzzcheck::sensors_and_pools::{SimplestToActivateCounterPool, UserEventSensor};
+/// use fuzzcheck::{fuzz_assert, Arguments};
+///
+/// fn test_function(xs: &[u8]) -> bool {
+///     fuzz_assert!(xs.len() < 4, "long input");
+///     fuzz_assert!(xs.first() != Some(&42), "starts with 42");
+///     true
+/// }
+///
+/// let sensor = UserEventSensor::new(8);
+/// let pool = SimplestToActivateCounterPool::new("assertions", 8);
+///
+/// let result = fuzzcheck::fuzz_test(test_function)
+///     .default_mutator()
+///     .serde_serializer()
+///     .sensor_and_pool(sensor, pool)
+///     .arguments(Arguments::for_internal_documentation_test())
+///     .stop_after_iterations(10_000)
+///     .launch();
+///
+/// assert!(!result.found_test_failure);
+/// ```
+pub struct UserEventSensor {
+    max_nbr_keys: usize,
+    index_for_key: AHashMap<u64, usize>,
+    keys: Vec<u64>,
+    observations: Vec<(usize, u64)>,
+}
+
+impl UserEventSensor {
+    /// Create a sensor which can distinguish between at most `max_nbr_keys` different events.
+    #[coverage(off)]
+    pub fn new(max_nbr_keys: usize) -> Self {
+        Self {
+            max_nbr_keys,
+            index_for_key: AHashMap::new(),
+            keys: vec![],
+            observations: vec![],
+        }
+    }
+    #[coverage(off)]
+    fn index_of_key(&mut self, key: u64) -> Option<usize> {
+        if let Some(&index) = self.index_for_key.get(&key) {
+            Some(index)
+        } else if self.keys.len() < self.max_nbr_keys {
+            let index = self.keys.len();
+            self.keys.push(key);
+            self.index_for_key.insert(key, index);
+            Some(index)
+        } else {
+            None
+        }
+    }
+}
+
+impl SaveToStatsFolder for UserEventSensor {
+    #[coverage(off)]
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "serde_json_serializer")] {
+                let content = serde_json::to_vec(&self.keys).unwrap();
+                let labels = ASSERTION_LABELS.lock().expect("the assertion labels could not be read");
+                let assertions = self
+                    .keys
+                    .iter()
+                    .enumerate()
+                    .filter_map(
+                        #[coverage(off)]
+                        |(index, key)| Some((index, *labels.get(key)?)),
+                    )
+                    .collect::<Vec<_>>();
+                let assertions = serde_json::to_vec(&assertions).unwrap();
+                vec![
+                    (PathBuf::new().join("user_events.json"), content),
+                    (PathBuf::new().join("assertions.json"), assertions),
+                ]
+            } else {
+                vec![]
+            }
+        }
+    }
+}
+
+impl Sensor for UserEventSensor {
+    type Observations = Vec<(usize, u64)>;
+
+    #[coverage(off)]
+    fn start_recording(&mut self) {
+        USER_EVENTS
+            .lock()
+            .expect("the user events could not be cleared")
+            .clear();
+        IS_RECORDING.store(true, Ordering::Relaxed);
+    }
+
+    #[coverage(off)]
+    fn stop_recording(&mut self) {
+        IS_RECORDING.store(false, Ordering::Relaxed);
+        let events = std::mem::take(&mut *USER_EVENTS.lock().expect("the user events could not be read"));
+        let mut observations = Vec::<(usize, u64)>::with_capacity(events.len());
+        for (key, value) in events {
+            if let Some(index) = self.index_of_key(key) {
+                observations.push((index, value));
+            }
+        }
+        // keep only the highest value of each event
+        observations.sort_unstable_by(
+            #[coverage(off)]
+            |a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)),
+        );
+        observations.dedup_by_key(
+            #[coverage(off)]
+            |observation| observation.0,
+        );
+        self.observations = observations;
+    }
+
+    #[coverage(off)]
+    fn get_observations(&mut self) -> Self::Observations {
+        std::mem::take(&mut self.observations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_user_event_sensor() {
+        let mut sensor = UserEventSensor::new(2);
+        record_event(7, 1);
+        sensor.start_recording();
+        record_event(42, 3);
+        record_event(7, 2);
+        record_event(42, 5);
+        record_event(13, 1);
+        sensor.stop_recording();
+        assert_eq!(sensor.get_observations(), vec![(0, 5), (1, 2)]);
+
+        sensor.start_recording();
+        record_event(7, 4);
+        sensor.stop_recording();
+        assert_eq!(sensor.get_observations(), vec![(1, 4)]);
+        assert_eq!(sensor.keys, vec![42, 7]);
+
+        // the violations of the assertions are events too, which are recorded in the same test because
+        // the events are global
+        let mut sensor = UserEventSensor::new(2);
+        fuzz_assert!(false, "not recording");
+        sensor.start_recording();
+        fuzz_assert!(1 + 1 == 2, "holds");
+        fuzz_assert!(false, "a");
+        fuzz_assert!(false, "b");
+        fuzz_assert!(false, "a");
+        fuzz_assert!(false, "c");
+        sensor.stop_recording();
+        assert_eq!(sensor.get_observations(), vec![(0, 1), (1, 1)]);
+
+        sensor.start_recording();
+        fuzz_assert!(false, "b");
+        sensor.stop_recording();
+        assert_eq!(sensor.get_observations(), vec![(1, 1)]);
+        let labels = ASSERTION_LABELS.lock().unwrap();
+        let sensor_labels: Vec<_> = sensor
+            .keys
+            .iter()
+            .map(
+                #[coverage(off)]
+                |key| labels[key],
+            )
+            .collect();
+        assert_eq!(sensor_labels, vec!["a", "b"]);
+    }
+}
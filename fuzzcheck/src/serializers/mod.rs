@@ -3,22 +3,35 @@
 //! There are currently three implementations:
 //!
 //! * SerdeSerializer uses the `serde` and `serde_json` crate to serialize
-//! the test inputs (of arbitrary Serializable type) to a `.json` file.
+//!   the test inputs (of arbitrary Serializable type) to a `.json` file.
+//!   With the `serde_ron_serializer` and `serde_bincode_serializer` features,
+//!   SerdeRonSerializer and SerdeBincodeSerializer do the same with the `ron`
+//!   and compact binary `bincode` formats.
 //!
 //! * [ByteSerializer] encodes and decodes values of type `Vec<u8>` by simply
-//! copy/pasting the bytes from/to the files. The extension is customizable.
+//!   copy/pasting the bytes from/to the files. The extension is customizable.
 //!
 //! * [StringSerializer] encodes and decodes values of any type implementing
-//! `FromStr` and `ToString` into utf-8 encoded text files.
+//!   `FromStr` and `ToString` into utf-8 encoded text files.
+//!
+//! Additionally, a [MultiSerializer] combines several serializers to read
+//! files in different formats, dispatching on their extension, and a
+//! [MapSerializer] reuses a serializer for another type, such as a newtype
+//! wrapping the serializer's value.
 
+#[cfg(feature = "serde_bincode_serializer")]
+mod serde_bincode_serializer;
 #[cfg(feature = "serde_ron_serializer")]
 mod serde_ron_serializer;
 #[cfg(feature = "serde_json_serializer")]
 mod serde_serializer;
 
+use std::io::{Read, Result, Write};
 use std::marker::PhantomData;
 use std::str::FromStr;
 
+#[cfg(feature = "serde_bincode_serializer")]
+pub use serde_bincode_serializer::SerdeBincodeSerializer;
 #[cfg(feature = "serde_ron_serializer")]
 pub use serde_ron_serializer::SerdeRonSerializer;
 #[cfg(feature = "serde_json_serializer")]
@@ -62,6 +75,16 @@ impl crate::traits::Serializer for ByteSerializer {
     fn to_data(&self, value: &Self::Value) -> Vec<u8> {
         value.clone()
     }
+    #[coverage(off)]
+    fn to_writer(&self, value: &Self::Value, writer: &mut dyn Write) -> Result<()> {
+        writer.write_all(value)
+    }
+    #[coverage(off)]
+    fn from_reader(&self, reader: &mut dyn Read) -> Result<Option<Self::Value>> {
+        let mut value = vec![];
+        reader.read_to_end(&mut value)?;
+        Ok(Some(value))
+    }
 }
 
 /**
@@ -115,3 +138,194 @@ where
         value.to_string().into_bytes()
     }
 }
+
+/**
+A serializer that writes test cases with a primary serializer, but can also read the files
+whose extension is supported by one of its secondary serializers.
+
+This is useful to migrate a long-lived corpus from one format to another. For example, the following
+serializer encodes the new test cases in `.ron` files, but still reads the legacy `.json` ones:
+```
+# #[cfg(all(feature = "serde_json_serializer", feature = "serde_ron_serializer"))] {
+use fuzzcheck::serializers::MultiSerializer;
+use fuzzcheck::{SerdeRonSerializer, SerdeSerializer, Serializer};
+
+let serializer = MultiSerializer::new(SerdeRonSerializer::<Vec<u8>>::default())
+    .also_read(SerdeSerializer::<Vec<u8>>::default());
+
+assert_eq!(serializer.extension(), "ron");
+assert!(serializer.reads_extension("json"));
+assert_eq!(serializer.from_data_with_extension(b"[1,2]", "json"), Some(vec![1, 2]));
+# }
+```
+When a file can be read by several serializers, the primary serializer is preferred, followed by the
+secondary serializers in the order in which they were added.
+*/
+pub struct MultiSerializer<V> {
+    primary: Box<dyn Serializer<Value = V>>,
+    secondary: Vec<Box<dyn Serializer<Value = V>>>,
+}
+
+impl<V> MultiSerializer<V> {
+    /// Create a serializer which reads and writes files with the given primary serializer
+    #[coverage(off)]
+    pub fn new<S>(primary: S) -> Self
+    where
+        S: Serializer<Value = V> + 'static,
+    {
+        Self {
+            primary: Box::new(primary),
+            secondary: vec![],
+        }
+    }
+
+    /// Also read the files supported by the given serializer
+    #[must_use]
+    #[coverage(off)]
+    pub fn also_read<S>(self, serializer: S) -> Self
+    where
+        S: Serializer<Value = V> + 'static,
+    {
+        let mut x = self;
+        x.secondary.push(Box::new(serializer));
+        x
+    }
+}
+
+impl<V> Serializer for MultiSerializer<V> {
+    type Value = V;
+
+    #[coverage(off)]
+    fn extension(&self) -> &str {
+        self.primary.extension()
+    }
+    #[coverage(off)]
+    fn from_data(&self, data: &[u8]) -> Option<Self::Value> {
+        self.primary.from_data(data)
+    }
+    #[coverage(off)]
+    fn to_data(&self, value: &Self::Value) -> Vec<u8> {
+        self.primary.to_data(value)
+    }
+    #[coverage(off)]
+    fn to_writer(&self, value: &Self::Value, writer: &mut dyn Write) -> Result<()> {
+        self.primary.to_writer(value, writer)
+    }
+    #[coverage(off)]
+    fn from_reader(&self, reader: &mut dyn Read) -> Result<Option<Self::Value>> {
+        self.primary.from_reader(reader)
+    }
+    #[coverage(off)]
+    fn reads_extension(&self, extension: &str) -> bool {
+        std::iter::once(&self.primary).chain(self.secondary.iter()).any(
+            #[coverage(off)]
+            |serializer| serializer.reads_extension(extension),
+        )
+    }
+    #[coverage(off)]
+    fn from_data_with_extension(&self, data: &[u8], extension: &str) -> Option<Self::Value> {
+        std::iter::once(&self.primary)
+            .chain(self.secondary.iter())
+            .find(
+                #[coverage(off)]
+                |serializer| serializer.reads_extension(extension),
+            )?
+            .from_data_with_extension(data, extension)
+    }
+}
+
+/**
+A serializer that wraps another serializer and converts its values to and from another type.
+
+This avoids writing a bespoke serializer for newtypes wrapping a value that can already be serialized.
+For example, the following serializer saves the test cases of type `Packet` as raw bytes:
+```
+use fuzzcheck::serializers::MapSerializer;
+use fuzzcheck::{ByteSerializer, Serializer};
+
+#[derive(Debug, PartialEq)]
+struct Packet(Vec<u8>);
+
+let serializer = MapSerializer::new(
+    ByteSerializer::new("bin"),
+    |packet: &Packet| packet.0.clone(),
+    |bytes| Some(Packet(bytes)),
+);
+
+assert_eq!(serializer.extension(), "bin");
+assert_eq!(serializer.to_data(&Packet(vec![1, 2])), vec![1, 2]);
+assert_eq!(serializer.from_data(&[3, 4]), Some(Packet(vec![3, 4])));
+```
+The conversion from the inner value can fail by returning `None`, in which case the file is
+considered invalid, like any file that cannot be decoded by the inner serializer.
+*/
+pub struct MapSerializer<S, V, To, From>
+where
+    S: Serializer,
+    To: Fn(&V) -> S::Value,
+    From: Fn(S::Value) -> Option<V>,
+{
+    serializer: S,
+    to: To,
+    from: From,
+    _phantom: PhantomData<V>,
+}
+
+impl<S, V, To, From> MapSerializer<S, V, To, From>
+where
+    S: Serializer,
+    To: Fn(&V) -> S::Value,
+    From: Fn(S::Value) -> Option<V>,
+{
+    /// Create a serializer of values of type `V` from a serializer of `S::Value`, a function
+    /// converting a `V` to a `S::Value` and a function converting it back
+    #[coverage(off)]
+    pub fn new(serializer: S, to: To, from: From) -> Self {
+        Self {
+            serializer,
+            to,
+            from,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S, V, To, From> Serializer for MapSerializer<S, V, To, From>
+where
+    S: Serializer,
+    To: Fn(&V) -> S::Value,
+    From: Fn(S::Value) -> Option<V>,
+{
+    type Value = V;
+
+    #[coverage(off)]
+    fn extension(&self) -> &str {
+        self.serializer.extension()
+    }
+    #[coverage(off)]
+    fn from_data(&self, data: &[u8]) -> Option<Self::Value> {
+        self.serializer.from_data(data).and_then(&self.from)
+    }
+    #[coverage(off)]
+    fn to_data(&self, value: &Self::Value) -> Vec<u8> {
+        self.serializer.to_data(&(self.to)(value))
+    }
+    #[coverage(off)]
+    fn to_writer(&self, value: &Self::Value, writer: &mut dyn Write) -> Result<()> {
+        self.serializer.to_writer(&(self.to)(value), writer)
+    }
+    #[coverage(off)]
+    fn from_reader(&self, reader: &mut dyn Read) -> Result<Option<Self::Value>> {
+        Ok(self.serializer.from_reader(reader)?.and_then(&self.from))
+    }
+    #[coverage(off)]
+    fn reads_extension(&self, extension: &str) -> bool {
+        self.serializer.reads_extension(extension)
+    }
+    #[coverage(off)]
+    fn from_data_with_extension(&self, data: &[u8], extension: &str) -> Option<Self::Value> {
+        self.serializer
+            .from_data_with_extension(data, extension)
+            .and_then(&self.from)
+    }
+}
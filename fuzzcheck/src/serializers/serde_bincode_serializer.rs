@@ -0,0 +1,109 @@
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+
+use bincode::Options;
+
+/// The maximum number of bytes read by [`SerdeBincodeSerializer`] when deserializing a value from a reader.
+///
+/// A corrupted file can encode the length of a collection as a very large number, which bincode would
+/// otherwise try to allocate before noticing that the file is too short.
+const MAX_DESERIALIZED_SIZE: u64 = 1 << 30;
+
+/// The options used by `bincode::serialize` and `bincode::deserialize`, such that the files keep the same format
+#[coverage(off)]
+fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+}
+
+/// A serializer that uses [`serde`] and [`bincode`] to serialize the test
+/// inputs (of arbitrary type `T: Serializable + for<'e> Deserializable<'e>`)
+/// to a compact binary file.
+///
+/// It is a good choice for inputs containing large amounts of raw data, such as
+/// `Vec<u8>`, which take much more space when encoded in a text format like JSON.
+/// However, the resulting files cannot be read or edited by hand.
+#[doc(cfg(feature = "serde_bincode_serializer"))]
+pub struct SerdeBincodeSerializer<S> {
+    phantom: PhantomData<S>,
+}
+
+impl<S> Default for SerdeBincodeSerializer<S> {
+    #[coverage(off)]
+    fn default() -> Self {
+        Self { phantom: PhantomData }
+    }
+}
+
+impl<S> crate::traits::Serializer for SerdeBincodeSerializer<S>
+where
+    S: serde::Serialize + for<'e> serde::Deserialize<'e>,
+{
+    type Value = S;
+
+    #[coverage(off)]
+    fn extension(&self) -> &str {
+        "bin"
+    }
+    #[coverage(off)]
+    fn from_data(&self, data: &[u8]) -> Option<S> {
+        // the value cannot be larger than its encoding
+        options().with_limit(data.len() as u64).deserialize(data).ok()
+    }
+    #[coverage(off)]
+    fn to_data(&self, value: &Self::Value) -> Vec<u8> {
+        options().serialize(value).unwrap()
+    }
+    #[coverage(off)]
+    fn to_writer(&self, value: &Self::Value, writer: &mut dyn Write) -> io::Result<()> {
+        options().serialize_into(writer, value).map_err(
+            #[coverage(off)]
+            |e| match *e {
+                bincode::ErrorKind::Io(e) => e,
+                e => io::Error::other(e),
+            },
+        )
+    }
+    #[coverage(off)]
+    fn from_reader(&self, reader: &mut dyn Read) -> io::Result<Option<S>> {
+        match options().with_limit(MAX_DESERIALIZED_SIZE).deserialize_from(reader) {
+            Ok(value) => Ok(Some(value)),
+            // a truncated or oversized file is invalid, like it is for from_data
+            Err(e) => match *e {
+                bincode::ErrorKind::Io(e) if e.kind() != io::ErrorKind::UnexpectedEof => Err(e),
+                _ => Ok(None),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Serializer;
+
+    #[test]
+    #[coverage(off)]
+    fn test_serde_bincode_serializer() {
+        let serializer = SerdeBincodeSerializer::<(Vec<u8>, String)>::default();
+        let value = (vec![0, 1, 2, 255], "fuzz".to_string());
+        let data = serializer.to_data(&value);
+        // 8 bytes for each length, followed by the raw bytes
+        assert_eq!(data.len(), 8 + 4 + 8 + 4);
+        assert_eq!(serializer.from_data(&data), Some(value.clone()));
+        assert_eq!(serializer.from_data(&data[..10]), None);
+
+        let mut streamed = vec![];
+        serializer.to_writer(&value, &mut streamed).unwrap();
+        assert_eq!(streamed, data);
+        assert_eq!(serializer.from_reader(&mut data.as_slice()).unwrap(), Some(value));
+        assert_eq!(serializer.from_reader(&mut &data[..10]).unwrap(), None);
+
+        // the length of the vector is much larger than the file, it is not allocated
+        let mut oversized = (1u64 << 40).to_le_bytes().to_vec();
+        oversized.extend([0; 16]);
+        assert_eq!(serializer.from_data(&oversized), None);
+        assert_eq!(serializer.from_reader(&mut oversized.as_slice()).unwrap(), None);
+    }
+}
@@ -1,17 +1,43 @@
+use std::io::{self, Read, Write};
 use std::marker::PhantomData;
 
 /// A serializer that uses `serde` and `serde_json` to serialize the test
 /// inputs (of arbitrary type `T: Serializable + for<'e> Deserializable<'e>`)
 /// to a json file.
+///
+/// By default, the json is written compactly. Use [`SerdeSerializer::pretty`] to
+/// write human-editable files instead.
 #[doc(cfg(feature = "serde_json_serializer"))]
 pub struct SerdeSerializer<S> {
+    pretty: bool,
     phantom: PhantomData<S>,
 }
 
 impl<S> Default for SerdeSerializer<S> {
     #[coverage(off)]
     fn default() -> Self {
-        Self { phantom: PhantomData }
+        Self {
+            pretty: false,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<S> SerdeSerializer<S> {
+    /// A serializer that pretty-prints the json, with the keys of each map sorted
+    /// alphabetically and a trailing newline, so that the files of the corpus are
+    /// easy to edit by hand and produce readable diffs.
+    ///
+    /// The keys are left in their original order if the value contains a number that
+    /// does not fit in a `serde_json::Value`, such as a `u128` larger than `u64::MAX`.
+    ///
+    /// It reads both pretty-printed and compact json files.
+    #[coverage(off)]
+    pub fn pretty() -> Self {
+        Self {
+            pretty: true,
+            phantom: PhantomData,
+        }
     }
 }
 
@@ -31,6 +57,94 @@ where
     }
     #[coverage(off)]
     fn to_data(&self, value: &Self::Value) -> Vec<u8> {
-        serde_json::to_vec(value).unwrap()
+        if self.pretty {
+            let mut data = vec![];
+            write_pretty(value, &mut data).unwrap();
+            data.push(b'\n');
+            data
+        } else {
+            serde_json::to_vec(value).unwrap()
+        }
+    }
+    #[coverage(off)]
+    fn to_writer(&self, value: &Self::Value, writer: &mut dyn Write) -> io::Result<()> {
+        if self.pretty {
+            write_pretty(value, &mut *writer)?;
+            writer.write_all(b"\n")
+        } else {
+            Ok(serde_json::to_writer(writer, value)?)
+        }
+    }
+    #[coverage(off)]
+    fn from_reader(&self, reader: &mut dyn Read) -> io::Result<Option<S>> {
+        // serde_json reads one byte at a time, so the reader should be buffered
+        match serde_json::from_reader(reader) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.is_io() => Err(e.into()),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+/// Pretty-print the value, with the keys of its maps sorted if it can be converted to a `serde_json::Value`
+#[coverage(off)]
+fn write_pretty<S: serde::Serialize>(value: &S, writer: &mut dyn Write) -> serde_json::Result<()> {
+    // the maps of a serde_json::Value are sorted by key
+    match serde_json::to_value(value) {
+        Ok(value) => serde_json::to_writer_pretty(writer, &value),
+        Err(_) => serde_json::to_writer_pretty(writer, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::Serializer;
+
+    #[test]
+    #[coverage(off)]
+    fn test_pretty_serde_serializer() {
+        let serializer = SerdeSerializer::<HashMap<String, Vec<u8>>>::pretty();
+        let value = HashMap::from([("b".to_string(), vec![1]), ("a".to_string(), vec![])]);
+        let data = serializer.to_data(&value);
+        assert_eq!(
+            String::from_utf8(data.clone()).unwrap(),
+            "{\n  \"a\": [],\n  \"b\": [\n    1\n  ]\n}\n"
+        );
+        assert_eq!(serializer.from_data(&data), Some(value.clone()));
+
+        let compact = SerdeSerializer::default().to_data(&value);
+        assert_eq!(serializer.from_data(&compact), Some(value));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_pretty_serde_serializer_large_integers() {
+        let serializer = SerdeSerializer::<Vec<u128>>::pretty();
+        let value = vec![u128::MAX, 1];
+        let data = serializer.to_data(&value);
+        assert_eq!(
+            String::from_utf8(data.clone()).unwrap(),
+            format!("[\n  {},\n  1\n]\n", u128::MAX)
+        );
+        assert_eq!(serializer.from_data(&data), Some(value.clone()));
+        let mut written = vec![];
+        serializer.to_writer(&value, &mut written).unwrap();
+        assert_eq!(written, data);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_serde_serializer_streaming() {
+        for serializer in [SerdeSerializer::<Vec<String>>::default(), SerdeSerializer::pretty()] {
+            let value = vec!["a".to_string(), "b".to_string()];
+            let mut data = vec![];
+            serializer.to_writer(&value, &mut data).unwrap();
+            assert_eq!(data, serializer.to_data(&value));
+            assert_eq!(serializer.from_reader(&mut data.as_slice()).unwrap(), Some(value));
+            assert_eq!(serializer.from_reader(&mut &data[..data.len() / 2]).unwrap(), None);
+        }
     }
 }
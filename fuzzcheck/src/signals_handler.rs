@@ -1,82 +1,228 @@
 // ! A small, naive implementation of signal handlers in order to detect and
 // ! recover from crashes.
 
-use std::ptr;
-
-use libc::{
-    sigaction, sigemptyset, SA_NODEFER, SA_ONSTACK, SA_SIGINFO, SIGABRT, SIGALRM, SIGBUS, SIGFPE, SIGINT, SIGSEGV,
-    SIGTERM, SIGTRAP, SIG_DFL,
-};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 static mut SIGNAL_HANDLER: Option<Box<dyn Fn(libc::c_int) -> !>> = None;
 
+/// The signals asking the fuzzer to stop, such as the one sent by CTRL+C
+pub(crate) const INTERRUPT_SIGNALS: &[libc::c_int] = &[libc::SIGINT, libc::SIGTERM];
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Returns true if the fuzzer received an interrupt signal since the signal handlers were set.
+///
+/// The first interrupt signal is not passed to the signal handler. Instead, the fuzzer is expected
+/// to check this function regularly and stop cleanly. The next interrupt signals are passed to the
+/// signal handler, in case the test function never returns.
 #[coverage(off)]
-extern "C" fn os_handler(signal: libc::c_int, _: *mut libc::siginfo_t, _: *mut libc::c_void) {
-    // Assuming this always succeeds. Can't really handle errors in any meaningful way.
-    unsafe {
-        reset_signal_handlers();
-        if let Some(h) = SIGNAL_HANDLER.as_mut() {
-            (*h)(signal);
-        } else {
-            std::process::exit(1);
-        }
-    }
+pub(crate) fn was_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
 }
 
-/// Set signal handlers to the given function and return the pointer and layout
-/// of the alternative stack used by the signal handlers.
+/// Record the interrupt signal and return true if it is the first one
 #[coverage(off)]
-pub unsafe fn set_signal_handlers<F: 'static>(f: F) -> (*mut u8, std::alloc::Layout)
+fn record_first_interrupt(signal: libc::c_int) -> bool {
+    INTERRUPT_SIGNALS.contains(&signal) && !INTERRUPTED.swap(true, Ordering::SeqCst)
+}
+
+static mut SANITIZER_DEATH_HANDLER: Option<Box<dyn Fn()>> = None;
+
+/// Set the function called when a sanitizer, such as AddressSanitizer or LeakSanitizer, reports an
+/// error and is about to terminate the process.
+///
+/// It has no effect if the program is not compiled with a sanitizer, or on platforms other than Linux.
+#[coverage(off)]
+pub(crate) unsafe fn set_sanitizer_death_handler<F: 'static>(f: F)
 where
-    F: Fn(libc::c_int) -> !,
+    F: Fn(),
 {
-    SIGNAL_HANDLER = Some(Box::new(f));
-
-    // Make sure the alternative stack is big enough. ~65_000 bytes should be okay.
-    let stack_size = std::cmp::max(libc::SIGSTKSZ, 0b1 << 16);
-    let stack_layout = std::alloc::Layout::array::<u8>(stack_size).unwrap();
-    let stack_pointer = std::alloc::alloc_zeroed(stack_layout);
-
-    let signal_stack = libc::stack_t {
-        ss_sp: stack_pointer as *mut std::ffi::c_void,
-        ss_size: stack_size,
-        ss_flags: 0,
-    };
-
-    let stack = libc::sigaltstack(&signal_stack, std::ptr::null_mut());
-    if stack < 0 {
-        panic!("could not set alternate stack for handling signals");
+    SANITIZER_DEATH_HANDLER = Some(Box::new(f));
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "linux")] {
+            extern "C" {
+                fn fuzzcheck_set_sanitizer_death_callback(callback: extern "C" fn()) -> libc::c_int;
+            }
+            #[coverage(off)]
+            extern "C" fn sanitizer_death_callback() {
+                unsafe {
+                    if let Some(h) = SANITIZER_DEATH_HANDLER.take() {
+                        (*h)();
+                    }
+                }
+            }
+            fuzzcheck_set_sanitizer_death_callback(sanitizer_death_callback);
+        }
     }
+}
+
+/// Remove the function called when a sanitizer terminates the process
+#[coverage(off)]
+pub(crate) unsafe fn reset_sanitizer_death_handler() {
+    SANITIZER_DEATH_HANDLER = None;
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(unix)] {
+        use std::ptr;
 
-    let mut sa: sigaction = std::mem::zeroed();
-    sigemptyset(&mut sa.sa_mask as *mut libc::sigset_t);
+        use libc::{
+            sigaction, sigemptyset, SA_NODEFER, SA_ONSTACK, SA_SIGINFO, SIGABRT, SIGALRM, SIGBUS, SIGFPE, SIGINT, SIGSEGV,
+            SIGTERM, SIGTRAP, SIG_DFL,
+        };
 
-    sa.sa_flags = SA_NODEFER | SA_SIGINFO | SA_ONSTACK;
-    sa.sa_sigaction = os_handler as usize;
+        /// The signals indicating that the test function crashed or timed out
+        pub(crate) const CRASH_SIGNALS: &[libc::c_int] = &[SIGABRT, SIGBUS, SIGSEGV, SIGFPE, SIGALRM, SIGTRAP];
 
-    let signals = [
-        SIGALRM, SIGINT, SIGTERM, SIGSEGV, SIGBUS, SIGABRT, SIGFPE, SIGABRT, SIGTRAP,
-    ];
-    for sig in signals {
-        if sigaction(sig as i32, &mut sa as *mut sigaction, ptr::null_mut()) < 0 {
-            panic!("Could not set up signal handler");
+        const HANDLED_SIGNALS: &[libc::c_int] = &[
+            SIGALRM, SIGINT, SIGTERM, SIGSEGV, SIGBUS, SIGABRT, SIGFPE, SIGABRT, SIGTRAP,
+        ];
+
+        #[coverage(off)]
+        extern "C" fn os_handler(signal: libc::c_int, _: *mut libc::siginfo_t, _: *mut libc::c_void) {
+            if record_first_interrupt(signal) {
+                return;
+            }
+            // Assuming this always succeeds. Can't really handle errors in any meaningful way.
+            unsafe {
+                reset_signal_handlers();
+                if let Some(h) = SIGNAL_HANDLER.as_mut() {
+                    (*h)(signal);
+                } else {
+                    std::process::exit(1);
+                }
+            }
         }
-    }
 
-    (stack_pointer, stack_layout)
-}
+        /// Set signal handlers to the given function and return the pointer and layout
+        /// of the alternative stack used by the signal handlers.
+        #[coverage(off)]
+        pub unsafe fn set_signal_handlers<F: 'static>(f: F) -> Option<(*mut u8, std::alloc::Layout)>
+        where
+            F: Fn(libc::c_int) -> !,
+        {
+            SIGNAL_HANDLER = Some(Box::new(f));
+            INTERRUPTED.store(false, Ordering::SeqCst);
 
-#[coverage(off)]
-pub(crate) unsafe fn reset_signal_handlers() {
-    let mut sa: sigaction = std::mem::zeroed();
-    sigemptyset(&mut sa.sa_mask as *mut libc::sigset_t);
-    sa.sa_sigaction = SIG_DFL;
-
-    for &signal in &[
-        SIGALRM, SIGINT, SIGTERM, SIGSEGV, SIGBUS, SIGABRT, SIGFPE, SIGABRT, SIGTRAP,
-    ] {
-        if sigaction(signal, &mut sa as *mut sigaction, ptr::null_mut()) < 0 {
-            panic!("Could not set up signal handler");
+            // Make sure the alternative stack is big enough. ~65_000 bytes should be okay.
+            let stack_size = std::cmp::max(libc::SIGSTKSZ, 0b1 << 16);
+            let stack_layout = std::alloc::Layout::array::<u8>(stack_size).unwrap();
+            let stack_pointer = std::alloc::alloc_zeroed(stack_layout);
+
+            let signal_stack = libc::stack_t {
+                ss_sp: stack_pointer as *mut std::ffi::c_void,
+                ss_size: stack_size,
+                ss_flags: 0,
+            };
+
+            let stack = libc::sigaltstack(&signal_stack, std::ptr::null_mut());
+            if stack < 0 {
+                panic!("could not set alternate stack for handling signals");
+            }
+
+            let mut sa: sigaction = std::mem::zeroed();
+            sigemptyset(&mut sa.sa_mask as *mut libc::sigset_t);
+
+            sa.sa_flags = SA_NODEFER | SA_SIGINFO | SA_ONSTACK;
+            sa.sa_sigaction = os_handler as usize;
+
+            for &sig in HANDLED_SIGNALS {
+                if sigaction(sig as i32, &mut sa as *mut sigaction, ptr::null_mut()) < 0 {
+                    panic!("Could not set up signal handler");
+                }
+            }
+
+            Some((stack_pointer, stack_layout))
+        }
+
+        #[coverage(off)]
+        pub(crate) unsafe fn reset_signal_handlers() {
+            let mut sa: sigaction = std::mem::zeroed();
+            sigemptyset(&mut sa.sa_mask as *mut libc::sigset_t);
+            sa.sa_sigaction = SIG_DFL;
+
+            for &signal in HANDLED_SIGNALS {
+                if sigaction(signal, &mut sa as *mut sigaction, ptr::null_mut()) < 0 {
+                    panic!("Could not set up signal handler");
+                }
+            }
+        }
+
+        /// Raise `SIGALRM` after one second, which is handled as a crash, unless the alarm
+        /// is reset before then
+        #[coverage(off)]
+        pub(crate) fn set_infinite_loop_alarm() {
+            let _old_time_left = unsafe { libc::alarm(1) };
+            // TODO: I think setitimer should be prefered, but libc
+            // doesn't support it on linux, see:
+            // https://github.com/rust-lang/libc/issues/1347#event-3879031340
+
+            // let success = unsafe {
+            // let t = itimerval {
+            //     it_interval: libc::timeval { tv_sec: 0, tv_usec: 0 },
+            //     it_value: libc::timeval { tv_sec: 1, tv_usec: 0 },
+            // };
+            // libc::setitimer(ITIMER_REAL, &t, std::ptr::null_mut())
+            // };
+            // assert!(success == 0);
+        }
+    } else {
+        // Without POSIX signals, only the signals of the C runtime are handled, using `signal`.
+        // They are handled on the stack of the thread that raised them, which means that a stack
+        // overflow cannot be recovered from.
+        use libc::{SIGABRT, SIGFPE, SIGILL, SIGINT, SIGSEGV, SIGTERM, SIG_DFL};
+
+        /// The signals indicating that the test function crashed
+        pub(crate) const CRASH_SIGNALS: &[libc::c_int] = &[SIGABRT, SIGSEGV, SIGFPE, SIGILL];
+
+        const HANDLED_SIGNALS: &[libc::c_int] = &[SIGINT, SIGTERM, SIGSEGV, SIGABRT, SIGFPE, SIGILL];
+
+        #[coverage(off)]
+        extern "C" fn os_handler(signal: libc::c_int) {
+            if record_first_interrupt(signal) {
+                // the handler is reset to the default one before being called, so it is set again
+                unsafe { libc::signal(signal, os_handler as libc::sighandler_t) };
+                return;
+            }
+            unsafe {
+                reset_signal_handlers();
+                if let Some(h) = SIGNAL_HANDLER.as_mut() {
+                    (*h)(signal);
+                } else {
+                    std::process::exit(1);
+                }
+            }
         }
+
+        /// Set signal handlers to the given function.
+        ///
+        /// No alternative stack is used by the signal handlers, so `None` is returned.
+        #[coverage(off)]
+        pub unsafe fn set_signal_handlers<F: 'static>(f: F) -> Option<(*mut u8, std::alloc::Layout)>
+        where
+            F: Fn(libc::c_int) -> !,
+        {
+            SIGNAL_HANDLER = Some(Box::new(f));
+            INTERRUPTED.store(false, Ordering::SeqCst);
+            for &sig in HANDLED_SIGNALS {
+                if libc::signal(sig, os_handler as libc::sighandler_t) == libc::SIG_ERR as libc::sighandler_t {
+                    panic!("Could not set up signal handler");
+                }
+            }
+            None
+        }
+
+        #[coverage(off)]
+        pub(crate) unsafe fn reset_signal_handlers() {
+            for &sig in HANDLED_SIGNALS {
+                if libc::signal(sig, SIG_DFL) == libc::SIG_ERR as libc::sighandler_t {
+                    panic!("Could not set up signal handler");
+                }
+            }
+        }
+
+        /// Infinite loops can only be detected on unix platforms, where `SIGALRM` exists
+        #[coverage(off)]
+        pub(crate) fn set_infinite_loop_alarm() {}
     }
 }
@@ -0,0 +1,137 @@
+//! Detection of the code coverage counters that are not determined by the test case, enabled by `--check-stability`.
+//!
+//! When a test case activates counters that were never activated before, the fuzzer executes it
+//! [`NBR_STABILITY_RERUNS`] more times. The counters whose activation differs between these executions are
+//! nondeterministic: they are ignored by every code coverage sensor from then on, such that the pools do not
+//! mistake noise for progress. Like in AFL, the stability is the percentage of the counters activated so far
+//! that were never found to be nondeterministic.
+
+/// The number of times a test case with new code coverage is executed again to check that its coverage is reproducible
+pub(crate) const NBR_STABILITY_RERUNS: usize = 3;
+
+/// The counters activated so far and the ones that were found to be nondeterministic
+pub(crate) struct CounterStability {
+    seen: Vec<bool>,
+    nbr_seen: usize,
+    unstable: Vec<bool>,
+    nbr_unstable: usize,
+}
+
+impl CounterStability {
+    #[coverage(off)]
+    pub fn new(nbr_counters: usize) -> Self {
+        Self {
+            seen: vec![false; nbr_counters],
+            nbr_seen: 0,
+            unstable: vec![false; nbr_counters],
+            nbr_unstable: 0,
+        }
+    }
+
+    /// Returns the indices of the activated counters if at least one of them was never activated before,
+    /// in which case the test case should be executed again to check its stability.
+    #[coverage(off)]
+    pub fn new_coverage(&self, observations: &[(usize, u64)]) -> Option<Vec<usize>> {
+        if observations.iter().any(
+            #[coverage(off)]
+            |&(index, _)| !self.seen[index],
+        ) {
+            Some(
+                observations
+                    .iter()
+                    .map(
+                        #[coverage(off)]
+                        |&(index, _)| index,
+                    )
+                    .collect(),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Compare the counters activated by a new execution of the test case with those activated by its first execution.
+    ///
+    /// Returns the counters that were found to be nondeterministic, which were not known to be before.
+    #[coverage(off)]
+    pub fn compare(&mut self, first: &[usize], observations: &[(usize, u64)]) -> Vec<usize> {
+        let mut activated = vec![false; self.seen.len()];
+        for &(index, _) in observations {
+            activated[index] = true;
+        }
+        let mut newly_unstable = vec![];
+        for &index in first {
+            if !activated[index] {
+                newly_unstable.push(index);
+            }
+            activated[index] = false;
+        }
+        // the counters that are still marked as activated were not activated by the first execution
+        newly_unstable.extend(activated.iter().enumerate().filter_map(
+            #[coverage(off)]
+            |(index, &activated)| if activated { Some(index) } else { None },
+        ));
+        newly_unstable.retain(
+            #[coverage(off)]
+            |&index| !self.unstable[index],
+        );
+        for &index in &newly_unstable {
+            self.unstable[index] = true;
+            self.nbr_unstable += 1;
+            if !self.seen[index] {
+                self.seen[index] = true;
+                self.nbr_seen += 1;
+            }
+        }
+        newly_unstable
+    }
+
+    /// Mark the counters activated by the first execution of a test case as seen, once its stability was checked
+    #[coverage(off)]
+    pub fn finish(&mut self, first: &[usize]) {
+        for &index in first {
+            if !self.seen[index] {
+                self.seen[index] = true;
+                self.nbr_seen += 1;
+            }
+        }
+    }
+
+    /// The percentage of the counters activated so far that behave deterministically
+    #[coverage(off)]
+    pub fn stability(&self) -> f64 {
+        if self.nbr_seen == 0 {
+            100.0
+        } else {
+            100.0 * (1.0 - self.nbr_unstable as f64 / self.nbr_seen as f64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_counter_stability() {
+        let mut stability = CounterStability::new(10);
+        assert_eq!(stability.stability(), 100.0);
+
+        let first = stability.new_coverage(&[(0, 1), (2, 1), (3, 4)]).unwrap();
+        assert_eq!(first, vec![0, 2, 3]);
+        // the hit counts may differ, only the activation of the counters matters
+        assert!(stability.compare(&first, &[(0, 2), (2, 1), (3, 4)]).is_empty());
+        // counter 3 is not activated anymore and counter 5 is activated instead
+        assert_eq!(stability.compare(&first, &[(0, 1), (2, 1), (5, 1)]), vec![3, 5]);
+        // they are only reported once
+        assert!(stability.compare(&first, &[(0, 1), (2, 1)]).is_empty());
+        stability.finish(&first);
+        assert_eq!(stability.stability(), 50.0);
+
+        assert!(stability.new_coverage(&[(0, 1), (2, 1)]).is_none());
+        assert!(stability.new_coverage(&[(0, 1), (7, 1)]).is_some());
+        stability.finish(&[0, 7]);
+        assert_eq!(stability.stability(), 60.0);
+    }
+}
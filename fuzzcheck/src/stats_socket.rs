@@ -0,0 +1,190 @@
+//! Live telemetry: the events reported by the fuzzer can be streamed to a TCP socket, given by the
+//! `--stats-socket ADDR` argument, as newline-delimited JSON objects.
+
+use std::fmt::Write as _;
+use std::io::{Result, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::CSVField;
+
+/// A JSON value, serialized by hand so that the telemetry does not depend on the `serde_json_serializer` feature
+#[derive(Clone)]
+pub(crate) enum JsonValue {
+    Null,
+    Integer(isize),
+    Float(f64),
+    String(String),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl From<&CSVField> for JsonValue {
+    #[coverage(off)]
+    fn from(field: &CSVField) -> Self {
+        match field {
+            CSVField::Integer(n) => JsonValue::Integer(*n),
+            CSVField::Float(f) => JsonValue::Float(*f),
+            CSVField::String(s) => JsonValue::String(s.clone()),
+        }
+    }
+}
+
+impl JsonValue {
+    #[coverage(off)]
+    fn write_to(&self, out: &mut String) {
+        match self {
+            JsonValue::Null => out.push_str("null"),
+            JsonValue::Integer(n) => {
+                let _ = write!(out, "{}", n);
+            }
+            JsonValue::Float(f) if f.is_finite() => {
+                let _ = write!(out, "{}", f);
+            }
+            // NaN and infinities cannot be represented in JSON
+            JsonValue::Float(_) => out.push_str("null"),
+            JsonValue::String(s) => write_json_string(s, out),
+            JsonValue::Object(fields) => {
+                out.push('{');
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_json_string(key, out);
+                    out.push(':');
+                    value.write_to(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+}
+
+#[coverage(off)]
+fn write_json_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// The event with the given name and fields as a JSON object, followed by a newline.
+///
+/// The name of the event is given by the `"event"` field of the JSON object.
+#[coverage(off)]
+pub(crate) fn json_line(event: &str, fields: Vec<(String, JsonValue)>) -> String {
+    let mut all_fields = vec![("event".to_string(), JsonValue::String(event.to_string()))];
+    all_fields.extend(fields);
+    let mut line = String::new();
+    JsonValue::Object(all_fields).write_to(&mut line);
+    line.push('\n');
+    line
+}
+
+/// The maximum time spent writing an event to the socket, such that a receiver that stops reading the events
+/// cannot stall the fuzzer
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// A connection to the socket receiving the events of the fuzzer
+pub(crate) struct StatsSocket {
+    /// The connection is dropped after the first failed write, which may have written only part of a line
+    stream: Option<TcpStream>,
+}
+
+impl StatsSocket {
+    #[coverage(off)]
+    pub fn connect(addr: &str) -> Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        Ok(Self { stream: Some(stream) })
+    }
+
+    /// Send the event with the given name and fields as a single line of JSON.
+    ///
+    /// The name of the event is given by the `"event"` field of the JSON object. If the event cannot be written
+    /// within [`WRITE_TIMEOUT`], or if the write fails for any other reason, the connection is dropped and the
+    /// following events are discarded.
+    #[coverage(off)]
+    pub fn send(&mut self, event: &str, fields: Vec<(String, JsonValue)>) -> Result<()> {
+        let Some(stream) = &mut self.stream else {
+            return Ok(());
+        };
+        let result = stream.write_all(json_line(event, fields).as_bytes());
+        if result.is_err() {
+            self.stream = None;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_stats_socket() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let mut socket = StatsSocket::connect(&addr).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+
+        socket
+            .send(
+                "pulse",
+                vec![
+                    ("iterations".to_string(), JsonValue::Integer(12)),
+                    ("score".to_string(), JsonValue::Float(f64::NAN)),
+                    (
+                        "pool".to_string(),
+                        JsonValue::Object(vec![("name".to_string(), JsonValue::String("a \"b\"\n".to_string()))]),
+                    ),
+                ],
+            )
+            .unwrap();
+        socket
+            .send("done", vec![("path".to_string(), JsonValue::Null)])
+            .unwrap();
+
+        let mut lines = BufReader::new(stream).lines();
+        assert_eq!(
+            lines.next().unwrap().unwrap(),
+            r#"{"event":"pulse","iterations":12,"score":null,"pool":{"name":"a \"b\"\n"}}"#
+        );
+        assert_eq!(lines.next().unwrap().unwrap(), r#"{"event":"done","path":null}"#);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_stats_socket_stalled_receiver() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let mut socket = StatsSocket::connect(&addr).unwrap();
+        // the connection is accepted, but the events are never read
+        let (_stream, _) = listener.accept().unwrap();
+
+        let fields = || vec![("path".to_string(), JsonValue::String("x".repeat(1 << 16)))];
+        let mut nbr_sent_events = 0;
+        while socket.send("artifact", fields()).is_ok() {
+            nbr_sent_events += 1;
+            assert!(nbr_sent_events < 100_000, "the socket never timed out");
+        }
+        // the connection was dropped, so the following events are discarded without blocking
+        assert!(socket.stream.is_none());
+        socket.send("artifact", fields()).unwrap();
+    }
+}
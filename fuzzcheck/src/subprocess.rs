@@ -0,0 +1,352 @@
+/*!
+Fuzz programs that must run in a separate process.
+
+This is useful to test code that cannot run inside the fuzzer, for example because it aborts the
+process when it fails. A [`SubprocessExecutor`] launches the program once per test case, writes
+the bytes given to [`run`](SubprocessExecutor::run) to its standard input, and considers that
+the test failed if the program does not exit successfully, or does not exit before its
+[timeout](SubprocessExecutor::with_timeout). The program reports its code coverage to the fuzzer through
+counters located in shared memory, which are observed by a
+[`DynamicValueSensor`](crate::sensors_and_pools::DynamicValueSensor) and can thus be used with the
+same pools as the default code coverage sensor.
+
+The fuzz test could look like this:
+```no_run
+use fuzzcheck::sensors_and_pools::SimplestToActivateCounterPool;
+use fuzzcheck::subprocess::SubprocessExecutor;
+
+let nbr_counters = 1 << 16;
+let executor = SubprocessExecutor::new("path/to/program", &["--some-argument"], nbr_counters).unwrap();
+let sensor = executor.sensor();
+let pool = SimplestToActivateCounterPool::new("subprocess", nbr_counters);
+
+let result = fuzzcheck::fuzz_test(move |bytes: &[u8]| executor.run(bytes))
+    .default_mutator()
+    .serde_serializer()
+    .sensor_and_pool(sensor, pool)
+    .arguments_from_cargo_fuzzcheck()
+    .launch();
+```
+and the program can update the counters returned by [`shared_counters`]:
+```no_run
+use std::io::Read;
+use std::sync::atomic::Ordering;
+
+let counters = fuzzcheck::subprocess::shared_counters().expect("not launched by a SubprocessExecutor");
+let mut input = vec![];
+std::io::stdin().read_to_end(&mut input).unwrap();
+if let Some(&first_byte) = input.first() {
+    counters[first_byte as usize].fetch_add(1, Ordering::Relaxed);
+}
+```
+*/
+
+use std::ffi::CString;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::sensors_and_pools::DynamicValueSensor;
+
+/// The environment variable containing the name of the shared memory object where the counters are located
+pub const SHARED_COUNTERS_ENV: &str = "FUZZCHECK_SHARED_COUNTERS";
+
+/// A region of `u64` counters in shared memory, which can be mapped by other processes using its name
+pub(crate) struct SharedCounters {
+    pub name: String,
+    pub counters: &'static [AtomicU64],
+}
+
+impl SharedCounters {
+    /// Create a new shared memory object containing `len` counters, initialised to zero.
+    ///
+    /// The counters stay mapped until the end of the program, but the shared memory object
+    /// is removed when `self` is dropped.
+    #[coverage(off)]
+    pub fn new(len: usize) -> std::io::Result<Self> {
+        static NBR_CREATED: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "/fuzzcheck_{}_{}",
+            std::process::id(),
+            NBR_CREATED.fetch_add(1, Ordering::Relaxed)
+        );
+        let c_name = CString::new(name.clone()).unwrap();
+        unsafe {
+            let fd = libc::shm_open(c_name.as_ptr(), libc::O_CREAT | libc::O_EXCL | libc::O_RDWR, 0o600);
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            let size = len * std::mem::size_of::<u64>();
+            if libc::ftruncate(fd, size as libc::off_t) < 0 {
+                let error = std::io::Error::last_os_error();
+                libc::close(fd);
+                libc::shm_unlink(c_name.as_ptr());
+                return Err(error);
+            }
+            match Self::map(fd, len) {
+                Ok(counters) => Ok(Self { name, counters }),
+                Err(error) => {
+                    libc::shm_unlink(c_name.as_ptr());
+                    Err(error)
+                }
+            }
+        }
+    }
+
+    /// Map the counters of the shared memory object with the given name, created by another process
+    #[coverage(off)]
+    pub fn open(name: &str) -> std::io::Result<&'static [AtomicU64]> {
+        let c_name = CString::new(name).map_err(
+            #[coverage(off)]
+            |_| std::io::Error::other("invalid name of shared memory object"),
+        )?;
+        unsafe {
+            let fd = libc::shm_open(c_name.as_ptr(), libc::O_RDWR, 0);
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            let mut stat: libc::stat = std::mem::zeroed();
+            if libc::fstat(fd, &mut stat) < 0 {
+                let error = std::io::Error::last_os_error();
+                libc::close(fd);
+                return Err(error);
+            }
+            Self::map(fd, stat.st_size as usize / std::mem::size_of::<u64>())
+        }
+    }
+
+    /// Map `len` counters from the file descriptor and close it
+    #[coverage(off)]
+    unsafe fn map(fd: libc::c_int, len: usize) -> std::io::Result<&'static [AtomicU64]> {
+        if len == 0 {
+            libc::close(fd);
+            return Ok(&[]);
+        }
+        let ptr = libc::mmap(
+            std::ptr::null_mut(),
+            len * std::mem::size_of::<u64>(),
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd,
+            0,
+        );
+        let error = std::io::Error::last_os_error();
+        libc::close(fd);
+        if ptr == libc::MAP_FAILED {
+            return Err(error);
+        }
+        Ok(std::slice::from_raw_parts(ptr as *const AtomicU64, len))
+    }
+}
+
+impl Drop for SharedCounters {
+    #[coverage(off)]
+    fn drop(&mut self) {
+        let c_name = CString::new(self.name.clone()).unwrap();
+        unsafe {
+            libc::shm_unlink(c_name.as_ptr());
+        }
+    }
+}
+
+/// Returns the counters shared with the fuzzer, if the current process was launched by a [`SubprocessExecutor`].
+///
+/// The counters are reset by the fuzzer before each test case. Their indices and values are the
+/// observations of the sensor returned by [`SubprocessExecutor::sensor`]. They are mapped by the first
+/// call to this function, and the following calls return the same counters.
+#[coverage(off)]
+pub fn shared_counters() -> Option<&'static [AtomicU64]> {
+    static COUNTERS: OnceLock<Option<&'static [AtomicU64]>> = OnceLock::new();
+    *COUNTERS.get_or_init(
+        #[coverage(off)]
+        || {
+            let name = std::env::var(SHARED_COUNTERS_ENV).ok()?;
+            SharedCounters::open(&name).ok()
+        },
+    )
+}
+
+/// Allocate `len` counters, initialised to zero, in anonymous shared memory.
+///
+/// The counters are shared with the child processes created by `fork` afterwards. In particular, they
+/// can be used by a [`DynamicValueSensor`] to observe the test cases run with the fork mode of the
+/// fuzzer (see [`FuzzerBuilder5::fork`](crate::builder::FuzzerBuilder5::fork)), whose other sensors only
+/// transmit the code coverage counters. The counters stay mapped until the end of the program.
+#[coverage(off)]
+pub fn anonymous_shared_counters(len: usize) -> std::io::Result<&'static [AtomicU64]> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    unsafe {
+        let ptr = libc::mmap(
+            std::ptr::null_mut(),
+            len * std::mem::size_of::<u64>(),
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        );
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(std::slice::from_raw_parts(ptr as *const AtomicU64, len))
+    }
+}
+
+/// Runs a program for each test case, in a new process. See the [module documentation](crate::subprocess).
+///
+/// The input given to [`run`](Self::run) is written to the standard input of the program, whose
+/// standard output and standard error are discarded. The test case fails if the program does not exit
+/// successfully, for example because it returned a non-zero exit code or was killed by a signal, or if it
+/// is still running after the [timeout](Self::with_timeout), in which case it is killed.
+pub struct SubprocessExecutor {
+    program: PathBuf,
+    args: Vec<String>,
+    counters: SharedCounters,
+    timeout: Option<Duration>,
+}
+
+impl SubprocessExecutor {
+    /// Create an executor for the given program and arguments, with `nbr_counters` counters in shared memory
+    #[coverage(off)]
+    pub fn new(program: impl Into<PathBuf>, args: &[&str], nbr_counters: usize) -> std::io::Result<Self> {
+        Ok(Self {
+            program: program.into(),
+            args: args
+                .iter()
+                .map(
+                    #[coverage(off)]
+                    |arg| arg.to_string(),
+                )
+                .collect(),
+            counters: SharedCounters::new(nbr_counters)?,
+            timeout: None,
+        })
+    }
+
+    /// Kill the program, and consider that the test case failed, if it runs for longer than the given
+    /// duration. By default, there is no timeout.
+    #[coverage(off)]
+    #[must_use]
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// The counters shared with the program
+    #[coverage(off)]
+    pub fn counters(&self) -> &'static [AtomicU64] {
+        self.counters.counters
+    }
+
+    /// A sensor observing the counters shared with the program
+    #[coverage(off)]
+    pub fn sensor(&self) -> DynamicValueSensor {
+        DynamicValueSensor::new(self.counters.counters)
+    }
+
+    /// Run the program with the given input and return true if it exited successfully.
+    ///
+    /// ## Panics
+    /// Panics if the program cannot be launched.
+    #[coverage(off)]
+    pub fn run(&self, input: &[u8]) -> bool {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .env(SHARED_COUNTERS_ENV, &self.counters.name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap_or_else(
+                #[coverage(off)]
+                |e| panic!("could not launch {}: {}", self.program.display(), e),
+            );
+        let mut stdin = child.stdin.take().unwrap();
+        let Some(timeout) = self.timeout else {
+            // the program may exit without reading its whole input, so writing can fail
+            let _ = stdin.write_all(input);
+            drop(stdin);
+            let status = child.wait().expect("could not wait for the subprocess to exit");
+            return status.success();
+        };
+        let deadline = Instant::now() + timeout;
+        // the input is written by another thread, such that the timeout also applies to a program
+        // that never reads it
+        std::thread::scope(
+            #[coverage(off)]
+            |scope| {
+                scope.spawn(
+                    #[coverage(off)]
+                    move || {
+                        let _ = stdin.write_all(input);
+                    },
+                );
+                wait_until(&mut child, deadline)
+            },
+        )
+    }
+}
+
+/// Wait for the child to exit, and return true if it exited successfully before the deadline.
+/// Otherwise, it is killed.
+#[coverage(off)]
+fn wait_until(child: &mut Child, deadline: Instant) -> bool {
+    // the status of the child is polled at increasing intervals, such that short-lived programs are not
+    // delayed much and long-lived ones do not keep the fuzzer busy
+    let mut interval = Duration::from_micros(10);
+    loop {
+        if let Some(status) = child.try_wait().expect("could not wait for the subprocess to exit") {
+            return status.success();
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            // the child may have exited in the meantime, in which case killing it fails
+            let _ = child.kill();
+            let _ = child.wait();
+            return false;
+        }
+        std::thread::sleep(interval.min(deadline - now));
+        interval = (interval * 2).min(Duration::from_millis(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn test_shared_counters() {
+        let shared = SharedCounters::new(10).unwrap();
+        let other = SharedCounters::open(&shared.name).unwrap();
+        assert_eq!(other.len(), 10);
+        other[3].store(5, Ordering::Relaxed);
+        assert_eq!(shared.counters[3].load(Ordering::Relaxed), 5);
+
+        let name = shared.name.clone();
+        drop(shared);
+        assert!(SharedCounters::open(&name).is_err());
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_anonymous_shared_counters() {
+        let counters = anonymous_shared_counters(4).unwrap();
+        unsafe {
+            let pid = libc::fork();
+            if pid == 0 {
+                counters[2].store(3, Ordering::Relaxed);
+                libc::_exit(0);
+            }
+            let mut status = 0;
+            assert_eq!(libc::waitpid(pid, &mut status, 0), pid);
+        }
+        assert_eq!(counters[2].load(Ordering::Relaxed), 3);
+    }
+}
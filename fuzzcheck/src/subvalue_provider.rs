@@ -9,68 +9,10 @@ informatiom.
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 
-use crate::Mutator;
+#[doc(inline)]
+pub use fuzzcheck_core::{EmptySubValueProvider, Generation, SubValueProvider, SubValueProviderId};
 
-/// Uniquely identifies a [`SubValueProvider`](crate::SubValueProvider)
-///
-/// The identifier is composed of two fields: `idx` and `generation`. At any
-/// point in time, only one subvalue provider should have a given `idx`.
-///
-/// If two subvalue providers have the same `idx` but different `generation`,
-/// then only the one with the larger generation is valid.
-#[derive(Clone, Copy)]
-pub struct SubValueProviderId {
-    pub idx: usize,
-    pub generation: Generation,
-}
-
-/// See: [`SubValueProviderId`](crate::SubValueProviderId)
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Generation(pub usize);
-
-/// An object-safe trait which can give values of arbitrary types.
-///
-/// See the documentation of the [`Mutator`](crate::Mutator) trait for more information
-/// about its purpose.
-pub trait SubValueProvider {
-    /// A globally unique identifier for the subvalue provider
-    fn identifier(&self) -> SubValueProviderId;
-    /// Get a subvalue of the given type and under a certain maximum complexity.
-    ///
-    /// Returns `Some((subvalue, cplx))` or `None` if no subvalue matches the
-    /// type id or maximum complexity.
-    fn get_random_subvalue(&self, typeid: TypeId, max_cplx: f64) -> Option<(&dyn Any, f64)>;
-    /// Get a subvalue of the given type and under a certain maximum complexity.
-    ///
-    /// Each `index` points to a different subvalue. The function will automatically
-    /// increment `index` to the next valid value.
-    ///
-    /// Returns `Some((subvalue, cplx))` or `None` if no more unique subvalues
-    /// match thhe type id or maximum complexity.
-    fn get_subvalue(&self, typeid: TypeId, max_cplx: f64, index: &mut usize) -> Option<(&dyn Any, f64)>;
-}
-
-/// A [`SubValueProvider`](crate::SubValueProvider) that always return `None`
-pub struct EmptySubValueProvider;
-impl SubValueProvider for EmptySubValueProvider {
-    #[coverage(off)]
-    fn identifier(&self) -> SubValueProviderId {
-        SubValueProviderId {
-            idx: 0,
-            generation: Generation(0),
-        }
-    }
-
-    #[coverage(off)]
-    fn get_random_subvalue(&self, _typeid: TypeId, _max_cplx: f64) -> Option<(&dyn Any, f64)> {
-        None
-    }
-
-    #[coverage(off)]
-    fn get_subvalue(&self, _typeid: TypeId, _max_cplx: f64, _index: &mut usize) -> Option<(&dyn Any, f64)> {
-        None
-    }
-}
+use crate::Mutator;
 
 /// A [`SubValueProvider`](crate::SubValueProvider) created from the subvalues
 /// of a particular test case.
@@ -189,3 +131,234 @@ where
         }
     }
 }
+
+/// A subvalue of a test case of the corpus, stored by [`CorpusSubValueProvider`]
+struct CorpusSubValue {
+    /// The index of the test case in the fuzzer’s storage
+    source: usize,
+    subvalue: *const dyn Any,
+    complexity: f64,
+}
+
+/// A [`SubValueProvider`](crate::SubValueProvider) whose subvalues are sampled from all the test
+/// cases of the corpus.
+///
+/// For each type, it keeps at most `max_subvalues_per_type` subvalues, chosen by reservoir sampling
+/// among the subvalues of the test cases added to it. It is updated incrementally, when test cases
+/// are added to or removed from the corpus.
+///
+/// It does not own the subvalues. Instead, it refers to the subvalues stored by the
+/// [`CrossoverSubValueProvider`] of each test case, which must be kept alive until the test case is
+/// removed from the `CorpusSubValueProvider`.
+pub(crate) struct CorpusSubValueProvider {
+    identifier: SubValueProviderId,
+    max_subvalues_per_type: usize,
+    /// The sampled subvalues of each type, sorted by complexity
+    subvalues: HashMap<TypeId, Vec<CorpusSubValue>>,
+    /// The number of subvalues of each type that were considered by the reservoir sampling
+    nbr_seen_subvalues: HashMap<TypeId, usize>,
+    rng: fastrand::Rng,
+}
+
+impl CorpusSubValueProvider {
+    /// Create an empty subvalue provider, whose identifier has the given index.
+    ///
+    /// The index should be different from the identifiers of the subvalue providers of the
+    /// test cases of the corpus.
+    #[coverage(off)]
+    pub(crate) fn new(idx: usize, max_subvalues_per_type: usize) -> Self {
+        Self {
+            identifier: SubValueProviderId {
+                idx,
+                generation: Generation(0),
+            },
+            max_subvalues_per_type,
+            subvalues: HashMap::new(),
+            nbr_seen_subvalues: HashMap::new(),
+            rng: fastrand::Rng::new(),
+        }
+    }
+
+    /// Sample the subvalues of the test case at the index `source` of the fuzzer’s storage.
+    ///
+    /// # Safety
+    /// `subvalues` must not be dropped until the test case is removed with
+    /// [`remove_test_case`](CorpusSubValueProvider::remove_test_case) or [`clear`](CorpusSubValueProvider::clear).
+    #[coverage(off)]
+    pub(crate) unsafe fn add_test_case<T, M>(&mut self, source: usize, subvalues: &CrossoverSubValueProvider<T, M>)
+    where
+        T: Clone + 'static,
+        M: Mutator<T>,
+    {
+        let whole_value = (
+            TypeId::of::<T>(),
+            &subvalues.immutable_data.0 as &dyn Any as *const dyn Any,
+            subvalues.whole_complexity,
+        );
+        let all_subvalues = subvalues
+            .subvalues
+            .iter()
+            .flat_map(
+                #[coverage(off)]
+                |(typeid, subvalues)| {
+                    subvalues.iter().map(
+                        #[coverage(off)]
+                        |(subvalue, complexity)| (*typeid, *subvalue, *complexity),
+                    )
+                },
+            )
+            .chain(std::iter::once(whole_value));
+
+        let mut changed_types = vec![];
+        for (typeid, subvalue, complexity) in all_subvalues {
+            let nbr_seen = self.nbr_seen_subvalues.entry(typeid).or_default();
+            *nbr_seen += 1;
+            let sampled = self.subvalues.entry(typeid).or_default();
+            let new_subvalue = CorpusSubValue {
+                source,
+                subvalue,
+                complexity,
+            };
+            if sampled.len() < self.max_subvalues_per_type {
+                sampled.push(new_subvalue);
+            } else {
+                let idx = self.rng.usize(..*nbr_seen);
+                if idx < sampled.len() {
+                    sampled[idx] = new_subvalue;
+                } else {
+                    continue;
+                }
+            }
+            changed_types.push(typeid);
+        }
+        changed_types.sort_unstable();
+        changed_types.dedup();
+        for typeid in changed_types {
+            self.subvalues.get_mut(&typeid).unwrap().sort_by(
+                #[coverage(off)]
+                |x, y| {
+                    x.complexity
+                        .partial_cmp(&y.complexity)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                },
+            );
+        }
+        self.identifier.generation.0 += 1;
+    }
+
+    /// Remove the subvalues of the test case at the index `source` of the fuzzer’s storage
+    #[coverage(off)]
+    pub(crate) fn remove_test_case(&mut self, source: usize) {
+        self.subvalues.retain(
+            #[coverage(off)]
+            |_, subvalues| {
+                subvalues.retain(
+                    #[coverage(off)]
+                    |subvalue| subvalue.source != source,
+                );
+                !subvalues.is_empty()
+            },
+        );
+        self.identifier.generation.0 += 1;
+    }
+
+    /// Remove the subvalues of all test cases
+    #[coverage(off)]
+    pub(crate) fn clear(&mut self) {
+        self.subvalues.clear();
+        self.nbr_seen_subvalues.clear();
+        self.identifier.generation.0 += 1;
+    }
+}
+
+impl SubValueProvider for CorpusSubValueProvider {
+    #[coverage(off)]
+    fn identifier(&self) -> SubValueProviderId {
+        self.identifier
+    }
+
+    #[coverage(off)]
+    fn get_random_subvalue(&self, typeid: TypeId, max_cplx: f64) -> Option<(&dyn Any, f64)> {
+        let subvalues = self.subvalues.get(&typeid)?;
+        let end_index_for_complexity = subvalues
+            .iter()
+            .position(
+                #[coverage(off)]
+                |x| x.complexity >= max_cplx,
+            )
+            .unwrap_or(subvalues.len());
+        if end_index_for_complexity == 0 {
+            return None;
+        }
+        let subvalue = &subvalues[self.rng.usize(..end_index_for_complexity)];
+        Some((unsafe { subvalue.subvalue.as_ref() }.unwrap(), subvalue.complexity))
+    }
+
+    #[coverage(off)]
+    fn get_subvalue(&self, typeid: TypeId, max_cplx: f64, index: &mut usize) -> Option<(&dyn Any, f64)> {
+        let subvalue = self.subvalues.get(&typeid)?.get(*index)?;
+        if subvalue.complexity < max_cplx {
+            *index += 1;
+            Some((unsafe { subvalue.subvalue.as_ref() }.unwrap(), subvalue.complexity))
+        } else {
+            // the values are sorted by complexity
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use super::{CorpusSubValueProvider, CrossoverSubValueProvider, Generation, SubValueProviderId};
+    use crate::{DefaultMutator, Mutator, SubValueProvider};
+
+    #[test]
+    fn test_corpus_subvalue_provider() {
+        let mutator = <Vec<u8>>::default_mutator();
+        let providers = [vec![1, 2, 3], vec![4, 5]].map(|value| {
+            let cache = mutator.validate_value(&value).unwrap();
+            CrossoverSubValueProvider::new(
+                SubValueProviderId {
+                    idx: 0,
+                    generation: Generation(0),
+                },
+                &value,
+                &cache,
+                &mutator,
+            )
+        });
+        let mut corpus = CorpusSubValueProvider::new(usize::MAX, 4);
+        unsafe {
+            corpus.add_test_case(0, &providers[0]);
+            corpus.add_test_case(1, &providers[1]);
+        }
+        let get_all = |corpus: &CorpusSubValueProvider| {
+            let mut index = 0;
+            let mut all = vec![];
+            while let Some((x, _)) = corpus.get_subvalue(TypeId::of::<u8>(), 100.0, &mut index) {
+                all.push(*x.downcast_ref::<u8>().unwrap());
+            }
+            all
+        };
+        // at most 4 subvalues are kept, sampled from both test cases
+        let all = get_all(&corpus);
+        assert_eq!(all.len(), 4);
+        assert!(all.iter().all(|x| (1..=5).contains(x)));
+        let mut index = 0;
+        let mut nbr_whole_values = 0;
+        while let Some((x, _)) = corpus.get_subvalue(TypeId::of::<Vec<u8>>(), 100.0, &mut index) {
+            assert!(providers
+                .iter()
+                .any(|p| p.immutable_data.0 == *x.downcast_ref::<Vec<u8>>().unwrap()));
+            nbr_whole_values += 1;
+        }
+        assert_eq!(nbr_whole_values, 2);
+
+        corpus.remove_test_case(0);
+        assert!(get_all(&corpus).iter().all(|x| (4..=5).contains(x)));
+        corpus.remove_test_case(1);
+        assert!(corpus.get_random_subvalue(TypeId::of::<u8>(), 100.0).is_none());
+    }
+}
@@ -3,50 +3,245 @@ use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::fs;
 use std::fs::{File, OpenOptions};
-use std::hash::{Hash, Hasher};
-use std::io::{self, Result, Write};
+use std::hash::Hasher;
+use std::io::{self, BufReader, BufWriter, Result, Write};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant, SystemTime};
 
-use fuzzcheck_common::arg::{Arguments, FuzzerCommand};
-use fuzzcheck_common::{FuzzerEvent, FuzzerStats};
+use fuzzcheck_common::arg::{Arguments, CorpusNamingPolicy, FuzzerCommand};
+use fuzzcheck_common::{FuzzerEvent, FuzzerStats, TerminationStatus, STATS_JSONL_FILE};
 use nu_ansi_term::Color;
 
-use crate::fuzzer::{PoolStorageIndex, TerminationStatus};
-use crate::traits::{CorpusDelta, SaveToStatsFolder, Stats};
+#[cfg(feature = "serde_json_serializer")]
+use crate::fuzzer::RESUME_STATE_FILE;
+use crate::fuzzer::{terminate, PoolStorageIndex};
+use crate::provenance::ArtifactOrigin;
+use crate::stats_socket::{json_line, JsonValue, StatsSocket};
+use crate::traits::{CorpusDelta, SaveToStatsFolder, Serializer, Stats};
 use crate::{CSVField, ToCSV};
 
 impl ToCSV for FuzzerStats {
     #[coverage(off)]
     fn csv_headers(&self) -> Vec<CSVField> {
-        vec![
+        let mut headers = vec![
             CSVField::String("nbr_iter".to_string()),
             CSVField::String("iter/s".to_string()),
-        ]
+        ];
+        if self.stability.is_some() {
+            headers.push(CSVField::String("stability".to_string()));
+        }
+        if self.skipped_observations.is_some() {
+            headers.push(CSVField::String("skipped_observations".to_string()));
+        }
+        headers
     }
     #[coverage(off)]
     fn to_csv_record(&self) -> Vec<CSVField> {
-        vec![
+        let mut record = vec![
             CSVField::Integer(self.total_number_of_runs as isize),
             CSVField::Integer(self.exec_per_s as isize),
+        ];
+        if let Some(stability) = self.stability {
+            record.push(CSVField::Float(stability));
+        }
+        if let Some(skipped_observations) = self.skipped_observations {
+            record.push(CSVField::Integer(skipped_observations as isize));
+        }
+        record
+    }
+}
+
+/// The resources used by the fuzzer process so far, as reported by `getrusage`
+///
+/// On platforms without `getrusage`, all resources are reported as zero.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ResourceUsage {
+    /// The peak resident set size, in kilobytes
+    pub max_rss_kb: usize,
+    pub user_time: Duration,
+    pub system_time: Duration,
+}
+
+impl ResourceUsage {
+    #[cfg(not(unix))]
+    #[coverage(off)]
+    pub fn current() -> Self {
+        Self::default()
+    }
+
+    #[cfg(unix)]
+    #[coverage(off)]
+    pub fn current() -> Self {
+        #[coverage(off)]
+        fn duration(time: libc::timeval) -> Duration {
+            Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000)
+        }
+        let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+        if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+            return Self::default();
+        }
+        // ru_maxrss is given in bytes on macOS, and in kilobytes elsewhere
+        let max_rss_kb = if cfg!(target_os = "macos") {
+            usage.ru_maxrss as usize / 1024
+        } else {
+            usage.ru_maxrss as usize
+        };
+        Self {
+            max_rss_kb,
+            user_time: duration(usage.ru_utime),
+            system_time: duration(usage.ru_stime),
+        }
+    }
+}
+
+impl ToCSV for ResourceUsage {
+    #[coverage(off)]
+    fn csv_headers(&self) -> Vec<CSVField> {
+        vec![
+            CSVField::String("peak_rss_kb".to_string()),
+            CSVField::String("user_time_ms".to_string()),
+            CSVField::String("system_time_ms".to_string()),
+        ]
+    }
+    #[coverage(off)]
+    fn to_csv_record(&self) -> Vec<CSVField> {
+        vec![
+            CSVField::Integer(self.max_rss_kb as isize),
+            CSVField::Integer(self.user_time.as_millis() as isize),
+            CSVField::Integer(self.system_time.as_millis() as isize),
         ]
     }
 }
 
+/// The name of the file, in the stats folder, listing the files of the output corpus kept by each pool
+#[cfg(feature = "serde_json_serializer")]
+const CORPUS_CONTENT_FILE: &str = "corpus.json";
+
+/// The name of the subfolder of the artifacts folder containing the origin of each artifact, as a text file
+/// of the same name
+///
+/// The origins are kept in a subfolder such that `cargo fuzzcheck minify` does not mistake them for artifacts.
+const ARTIFACT_ORIGINS_FOLDER: &str = "origins";
+
+/// The extension of the files containing the tags of the test cases of the corpus, one per line
+///
+/// They are saved next to the test case they describe, with the same name. They are never read as test cases.
+const CORPUS_TAGS_EXTENSION: &str = "tags";
+
+/// The extension of the hidden temporary files in which test cases are written before being renamed to their
+/// final path
+///
+/// A temporary file is only left behind if the fuzzer is killed while writing it. It is never read as a test case.
+const TEMPORARY_FILE_EXTENSION: &str = "tmp";
+
+/// Write the content to a temporary file in the same folder, and then rename it to the given path.
+///
+/// The rename is atomic, so the file at the given path is either missing or complete, even if the fuzzer is killed
+/// while writing it. Otherwise, a truncated test case could be saved in the corpus and fail to be decoded at every
+/// subsequent fuzzing session.
+#[coverage(off)]
+fn write_atomically(path: &Path, write: impl FnOnce(&mut dyn Write) -> Result<()>) -> Result<()> {
+    let mut temporary_name = std::ffi::OsString::from(".");
+    temporary_name.push(path.file_name().unwrap_or_default());
+    temporary_name.push(".");
+    temporary_name.push(TEMPORARY_FILE_EXTENSION);
+    let temporary_path = path.with_file_name(temporary_name);
+    let mut file = BufWriter::new(File::create(&temporary_path)?);
+    write(&mut file)?;
+    file.into_inner().map_err(io::IntoInnerError::into_error)?;
+    fs::rename(&temporary_path, path)
+}
+
+/// A writer that discards the bytes written to it, and only counts them
+#[derive(Default)]
+struct CountingWriter {
+    nbr_bytes: usize,
+}
+
+impl Write for CountingWriter {
+    #[coverage(off)]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.nbr_bytes += buf.len();
+        Ok(buf.len())
+    }
+    #[coverage(off)]
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A writer that discards the bytes written to it, and only hashes them.
+///
+/// The hash of `n` bytes is the same as the hash of the slice of these bytes, which starts with its length,
+/// so that the names of the test cases do not depend on whether they are streamed.
+struct HashingWriter {
+    hasher: DefaultHasher,
+}
+
+impl HashingWriter {
+    #[coverage(off)]
+    fn new(nbr_bytes: usize) -> Self {
+        let mut hasher = DefaultHasher::new();
+        hasher.write_usize(nbr_bytes);
+        Self { hasher }
+    }
+}
+
+impl Write for HashingWriter {
+    #[coverage(off)]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.hasher.write(buf);
+        Ok(buf.len())
+    }
+    #[coverage(off)]
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The statistics as the fields of a JSON object, whose keys are the headers of the CSV columns
+#[coverage(off)]
+fn csv_json_fields<S: ToCSV + ?Sized>(stats: &S) -> Vec<(String, JsonValue)> {
+    stats
+        .csv_headers()
+        .iter()
+        .zip(stats.to_csv_record().iter())
+        .map(
+            #[coverage(off)]
+            |(header, field)| {
+                let key = match header {
+                    CSVField::Integer(n) => n.to_string(),
+                    CSVField::Float(f) => f.to_string(),
+                    CSVField::String(s) => s.clone(),
+                };
+                (key, JsonValue::from(field))
+            },
+        )
+        .collect()
+}
+
 pub struct World {
     settings: Arguments,
     initial_instant: Instant,
     checkpoint_instant: Instant,
-    /// keeps track of the hash of each input in the corpus, indexed by the Pool key
-    pub corpus: HashMap<(PathBuf, PoolStorageIndex), String>,
+    /// keeps track of the file name of each input in the corpus, without its extension, indexed by the Pool key,
+    /// along with the number of pools keeping it in that folder
+    pub corpus: HashMap<(PathBuf, PoolStorageIndex), (String, usize)>,
+    /// The tags of the test cases of the corpus that have any, with the same keys as `corpus`, along with
+    /// the number of pools that gave each tag
+    corpus_tags: HashMap<(PathBuf, PoolStorageIndex), Vec<(String, usize)>>,
     pub stats: Option<RefCell<File>>,
+    /// The same statistics as `stats`, along with the event that caused them to be reported and the wall-clock
+    /// time, as JSON lines
+    stats_jsonl: Option<RefCell<File>>,
     pub stats_folder: Option<PathBuf>,
+    stats_socket: Option<RefCell<StatsSocket>>,
 }
 
 impl World {
     #[coverage(off)]
     pub fn new(settings: Arguments) -> Result<Self> {
-        let (stats, stats_folder) = if let Some(stats_folder) = &settings.stats_folder {
+        let (stats, stats_jsonl, stats_folder) = if let Some(stats_folder) = &settings.stats_folder {
             let now = SystemTime::now();
             let duration_since_epoch = now.duration_since(SystemTime::UNIX_EPOCH).unwrap();
             let name = format!("{}", duration_since_epoch.as_millis());
@@ -54,27 +249,140 @@ impl World {
             std::fs::create_dir_all(&stats_folder)?;
             let path = stats_folder.join("events").with_extension("csv");
             let file = OpenOptions::new().create_new(true).append(true).open(path)?;
-            (Some(RefCell::new(file)), Some(stats_folder))
+            let path = stats_folder.join(STATS_JSONL_FILE);
+            let jsonl_file = OpenOptions::new().create_new(true).append(true).open(path)?;
+            (
+                Some(RefCell::new(file)),
+                Some(RefCell::new(jsonl_file)),
+                Some(stats_folder),
+            )
         } else {
-            (None, None)
+            (None, None, None)
+        };
+        let stats_socket = if let Some(addr) = &settings.stats_socket {
+            Some(RefCell::new(StatsSocket::connect(addr)?))
+        } else {
+            None
         };
         Ok(Self {
             settings,
             initial_instant: std::time::Instant::now(),
             checkpoint_instant: std::time::Instant::now(),
             corpus: HashMap::new(),
+            corpus_tags: HashMap::new(),
             stats,
+            stats_jsonl,
             stats_folder,
+            stats_socket,
         })
     }
 
+    /// The fields of the JSON object describing an event: the given fields specific to the event, preceded by
+    /// the time since the start of the fuzzer and followed by the statistics of the fuzzer and the pool
     #[coverage(off)]
-    fn hash(&self, input: &[u8]) -> String {
-        let mut hasher = DefaultHasher::new();
-        input.hash(&mut hasher);
-        let hash = hasher.finish();
-        let hash = format!("{:x}", hash);
-        hash
+    fn json_fields(
+        &self,
+        mut fields: Vec<(String, JsonValue)>,
+        stats: Option<(&FuzzerStats, &dyn Stats)>,
+    ) -> Vec<(String, JsonValue)> {
+        fields.insert(
+            0,
+            (
+                "time_ms".to_string(),
+                JsonValue::Integer(self.initial_instant.elapsed().as_millis() as isize),
+            ),
+        );
+        if let Some((fuzzer_stats, pool_stats)) = stats {
+            let pool_fields = csv_json_fields(pool_stats);
+            fields.extend([
+                (
+                    "iterations".to_string(),
+                    JsonValue::Integer(fuzzer_stats.total_number_of_runs as isize),
+                ),
+                (
+                    "iter_per_s".to_string(),
+                    JsonValue::Integer(fuzzer_stats.exec_per_s as isize),
+                ),
+                ("pool".to_string(), JsonValue::Object(pool_fields)),
+            ]);
+            if let Some(stability) = fuzzer_stats.stability {
+                fields.push(("stability".to_string(), JsonValue::Float(stability)));
+            }
+            if let Some(skipped_observations) = fuzzer_stats.skipped_observations {
+                fields.push((
+                    "skipped_observations".to_string(),
+                    JsonValue::Integer(skipped_observations as isize),
+                ));
+            }
+        }
+        fields
+    }
+
+    /// Send the event to the stats socket, if there is one.
+    ///
+    /// Errors are ignored, such that the fuzzer keeps running if the receiving end of the socket is closed or
+    /// stops reading the events.
+    #[coverage(off)]
+    fn send_to_stats_socket(
+        &self,
+        event: &str,
+        fields: Vec<(String, JsonValue)>,
+        stats: Option<(&FuzzerStats, &dyn Stats)>,
+    ) {
+        if let Some(socket) = &self.stats_socket {
+            let _ = socket.borrow_mut().send(event, self.json_fields(fields, stats));
+        }
+    }
+
+    /// Append the event, with the wall-clock time in milliseconds since the Unix epoch and the resource usage
+    /// of the fuzzer, to the JSON lines statistics file
+    #[coverage(off)]
+    fn append_stats_jsonl(&self, event: &str, mut fields: Vec<(String, JsonValue)>) -> Result<()> {
+        if let Some(stats_jsonl) = &self.stats_jsonl {
+            let timestamp = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default();
+            fields.insert(
+                0,
+                (
+                    "timestamp_ms".to_string(),
+                    JsonValue::Integer(timestamp.as_millis() as isize),
+                ),
+            );
+            fields.extend(csv_json_fields(&ResourceUsage::current()));
+            let line = json_line(event, fields);
+            stats_jsonl.try_borrow_mut().unwrap().write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// The hash of the serialized value, which names the file where it is saved, computed by
+    /// [streaming](Serializer::to_writer) it twice instead of holding it in memory: first to count its bytes,
+    /// then to hash them.
+    #[coverage(off)]
+    pub(crate) fn hash_value<T>(&self, value: &T, serializer: &dyn Serializer<Value = T>) -> String {
+        let mut counter = CountingWriter::default();
+        serializer.to_writer(value, &mut counter).unwrap();
+        let mut hasher = HashingWriter::new(counter.nbr_bytes);
+        serializer.to_writer(value, &mut hasher).unwrap();
+        format!("{:x}", hasher.hasher.finish())
+    }
+
+    /// The name of the file where the test case with the given hash is saved, without its extension
+    #[coverage(off)]
+    fn file_name(&self, hash: &str, cplx: f64, naming: CorpusNamingPolicy) -> String {
+        match naming {
+            CorpusNamingPolicy::ContentHash => hash.to_owned(),
+            CorpusNamingPolicy::ComplexityAndHash => format!("{:.0}--{}", cplx * 100.0, hash),
+        }
+    }
+
+    /// The hash of the content of a test case, given the name of the file where it is saved,
+    /// with any naming policy
+    #[cfg(feature = "serde_json_serializer")]
+    #[coverage(off)]
+    pub(crate) fn hash_from_file_name(name: &str) -> &str {
+        name.rsplit("--").next().unwrap_or(name)
     }
 
     #[coverage(off)]
@@ -86,33 +394,137 @@ impl World {
         Ok(())
     }
 
+    /// Add the test case to, or remove the test cases from, the folders of the output corpus, as described
+    /// by the deltas of the pools.
+    ///
+    /// The test case is [streamed](Serializer::to_writer) to its file, whose name is derived from the
+    /// [hash of its value](Self::hash_value), which is only used if it is added.
     #[coverage(off)]
-    pub(crate) fn update_corpus(
+    pub(crate) fn update_corpus<T>(
         &mut self,
         idx: PoolStorageIndex,
-        content: Vec<u8>,
+        value: &T,
+        serializer: &dyn Serializer<Value = T>,
+        hash: &str,
+        cplx: f64,
         deltas: &[CorpusDelta],
-        extension: &str,
     ) -> Result<()> {
+        let extension = serializer.extension();
         for delta in deltas {
-            let CorpusDelta { path, add, remove } = delta;
+            let CorpusDelta {
+                path,
+                add,
+                remove,
+                tags,
+            } = delta;
             for to_remove_key in remove {
-                let hash = self.corpus.remove(&(path.to_path_buf(), *to_remove_key)).unwrap();
-                self.remove_from_output_corpus(path, hash.clone(), extension)?;
+                let key = (path.to_path_buf(), *to_remove_key);
+                let (name, nbr_pools) = self.corpus.get_mut(&key).unwrap();
+                *nbr_pools -= 1;
+                // several pools with the same name share a folder, the file is kept until none of them keep it
+                if *nbr_pools == 0 {
+                    let (name, _) = self.corpus.remove(&key).unwrap();
+                    self.corpus_tags.remove(&key);
+                    self.remove_from_output_corpus(path, name.clone(), CORPUS_TAGS_EXTENSION)?;
+                    self.remove_from_output_corpus(path, name, extension)?;
+                } else if !tags.is_empty() {
+                    // the test case loses the tags given by the pool that removed it, unless another pool
+                    // keeping it gave them too
+                    let name = name.clone();
+                    let all_tags = self.corpus_tags.entry(key).or_default();
+                    for tag in tags {
+                        if let Some(position) = all_tags.iter().position(
+                            #[coverage(off)]
+                            |(t, _)| t == tag,
+                        ) {
+                            all_tags[position].1 -= 1;
+                            if all_tags[position].1 == 0 {
+                                all_tags.remove(position);
+                            }
+                        }
+                    }
+                    let all_tags = all_tags.clone();
+                    self.save_tags(path, name, &all_tags)?;
+                }
             }
 
             if *add {
-                let hash = self.hash(&content);
-                let _old = self.corpus.insert((path.to_path_buf(), idx), hash.clone());
-                self.add_to_output_corpus(path, hash.clone(), content.clone(), extension)?;
+                let key = (path.to_path_buf(), idx);
+                let name = if let Some((name, nbr_pools)) = self.corpus.get_mut(&key) {
+                    *nbr_pools += 1;
+                    name.clone()
+                } else {
+                    let name = self.file_name(hash, cplx, self.settings.corpus_naming);
+                    self.corpus.insert(key.clone(), (name.clone(), 1));
+                    self.add_to_output_corpus(
+                        path,
+                        name.clone(),
+                        extension,
+                        #[coverage(off)]
+                        |writer| serializer.to_writer(value, writer),
+                    )?;
+                    if tags.is_empty() {
+                        // the tags saved by a previous fuzzing session do not describe the test case anymore
+                        self.remove_from_output_corpus(path, name.clone(), CORPUS_TAGS_EXTENSION)?;
+                    }
+                    name
+                };
+                // the test case has the tags of all the pools that keep it in that folder, each counted once
+                // for every pool that gave it
+                if !tags.is_empty() {
+                    let all_tags = self.corpus_tags.entry(key).or_default();
+                    for tag in tags {
+                        if let Some((_, count)) = all_tags.iter_mut().find(
+                            #[coverage(off)]
+                            |(t, _)| t == tag,
+                        ) {
+                            *count += 1;
+                        } else {
+                            all_tags.push((tag.clone(), 1));
+                        }
+                    }
+                    let all_tags = all_tags.clone();
+                    self.save_tags(path, name, &all_tags)?;
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Save the tags of the test case of the given name, one per line, next to it in the output corpus,
+    /// or remove the file of its tags if it has none
+    #[coverage(off)]
+    fn save_tags(&self, path: &Path, name: String, tags: &[(String, usize)]) -> Result<()> {
+        if tags.is_empty() {
+            return self.remove_from_output_corpus(path, name, CORPUS_TAGS_EXTENSION);
+        }
+        let content = tags
+            .iter()
+            .map(
+                #[coverage(off)]
+                |(tag, _)| tag.as_str(),
+            )
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.add_to_output_corpus(
+            path,
+            name,
+            CORPUS_TAGS_EXTENSION,
+            #[coverage(off)]
+            |writer| writer.write_all(content.as_bytes()),
+        )
+    }
+
+    /// Save a file in the given folder of the output corpus, whose content is written by `write`
     #[coverage(off)]
-    pub fn add_to_output_corpus(&self, path: &Path, name: String, content: Vec<u8>, extension: &str) -> Result<()> {
+    pub fn add_to_output_corpus(
+        &self,
+        path: &Path,
+        name: String,
+        extension: &str,
+        write: impl FnOnce(&mut dyn Write) -> Result<()>,
+    ) -> Result<()> {
         if self.settings.corpus_out.is_none() {
             return Ok(());
         }
@@ -123,7 +535,7 @@ impl World {
         }
 
         let path = folder.join(name).with_extension(extension);
-        fs::write(path, content)?;
+        write_atomically(&path, write)?;
 
         Ok(())
     }
@@ -143,6 +555,61 @@ impl World {
 
     #[coverage(off)]
     pub(crate) fn report_event(&self, event: FuzzerEvent, stats: Option<(&FuzzerStats, &dyn Stats)>) {
+        let telemetry = match event {
+            FuzzerEvent::Start => Some(("start", vec![])),
+            FuzzerEvent::Stop => Some(("stop", vec![])),
+            FuzzerEvent::End => Some(("end", vec![])),
+            FuzzerEvent::CrashNoInput => Some(("crash", vec![])),
+            FuzzerEvent::Pulse => Some(("pulse", vec![])),
+            FuzzerEvent::Done => Some(("done", vec![])),
+            FuzzerEvent::Replace(added, removed) => Some((
+                "new_coverage",
+                vec![
+                    ("added".to_string(), JsonValue::Integer(added as isize)),
+                    ("removed".to_string(), JsonValue::Integer(removed as isize)),
+                ],
+            )),
+            FuzzerEvent::DidReadCorpus => Some(("did_read_corpus", vec![])),
+            FuzzerEvent::SkippedCorpusFiles(nbr_files) => Some((
+                "skipped_corpus_files",
+                vec![("nbr_files".to_string(), JsonValue::Integer(nbr_files as isize))],
+            )),
+            FuzzerEvent::SkippedInvalidCorpusFiles(nbr_files) => Some((
+                "skipped_invalid_corpus_files",
+                vec![("nbr_files".to_string(), JsonValue::Integer(nbr_files as isize))],
+            )),
+            FuzzerEvent::ReadIncomingCorpus(nbr_files) => Some((
+                "read_incoming_corpus",
+                vec![("nbr_files".to_string(), JsonValue::Integer(nbr_files as isize))],
+            )),
+            FuzzerEvent::SkippedInitialInputs(nbr_inputs) => Some((
+                "skipped_initial_inputs",
+                vec![("nbr_inputs".to_string(), JsonValue::Integer(nbr_inputs as isize))],
+            )),
+            FuzzerEvent::CaughtSignal(signal) => Some((
+                "signal",
+                vec![("signal".to_string(), JsonValue::Integer(signal as isize))],
+            )),
+            FuzzerEvent::SanitizerReport => Some(("sanitizer_report", vec![])),
+            FuzzerEvent::TestFailure => Some(("test_failure", vec![])),
+            FuzzerEvent::HighFilterRejectionRate(rejected, total) => Some((
+                "high_filter_rejection_rate",
+                vec![
+                    ("rejected".to_string(), JsonValue::Integer(rejected as isize)),
+                    ("total".to_string(), JsonValue::Integer(total as isize)),
+                ],
+            )),
+            FuzzerEvent::None => None,
+        };
+        if let Some((name, fields)) = telemetry {
+            // only the events reported along with statistics are part of the time series
+            if self.stats_jsonl.is_some() && stats.is_some() {
+                self.append_stats_jsonl(name, self.json_fields(fields.clone(), stats))
+                    .expect("cannot write to stats file");
+            }
+            self.send_to_stats_socket(name, fields, stats);
+        }
+
         // println uses a lock, which may mess up the signal handling
         let time_since_start = self.initial_instant.elapsed();
         let time_since_start_display = {
@@ -166,6 +633,10 @@ impl World {
             FuzzerEvent::Stop => {
                 println!("\n======================== STOPPED ========================");
                 println!(r#"The fuzzer was stopped."#);
+                if let Some((fuzzer_stats, pool_stats)) = stats {
+                    println!("{}", pool_stats);
+                    self.print_summary(fuzzer_stats);
+                }
                 return;
             }
             FuzzerEvent::End => {
@@ -186,18 +657,69 @@ This should never happen, and is probably a bug in fuzzcheck. Sorry :("#
                 return;
             }
             FuzzerEvent::Done => {
-                println!("{}", Color::Yellow.paint("DONE"));
-                return;
+                print!("{} ", Color::Yellow.paint("DONE"));
             }
             FuzzerEvent::DidReadCorpus => {
                 println!("{}", Color::Yellow.paint("FINISHED READING CORPUS"));
                 return;
             }
+            FuzzerEvent::SkippedCorpusFiles(nbr_files) => {
+                println!(
+                    "{}",
+                    Color::Yellow.paint(format!(
+                        "SKIPPED {} CORPUS FILE(S) WITH AN UNKNOWN EXTENSION",
+                        nbr_files
+                    ))
+                );
+                return;
+            }
+            FuzzerEvent::SkippedInvalidCorpusFiles(nbr_files) => {
+                println!(
+                    "{}",
+                    Color::Yellow.paint(format!(
+                        "SKIPPED {} CORPUS FILE(S) THAT CANNOT BE READ, DECODED, OR VALIDATED",
+                        nbr_files
+                    ))
+                );
+                return;
+            }
+            FuzzerEvent::ReadIncomingCorpus(nbr_files) => {
+                print!("{} ", Color::Yellow.paint(format!("INCOMING {}", nbr_files)));
+            }
+            FuzzerEvent::SkippedInitialInputs(nbr_inputs) => {
+                println!(
+                    "{}",
+                    Color::Yellow.paint(format!(
+                        "SKIPPED {} INITIAL INPUT(S) REJECTED BY THE MUTATOR",
+                        nbr_inputs
+                    ))
+                );
+                return;
+            }
             FuzzerEvent::CaughtSignal(signal) => println!("\n================ SIGNAL {} ================", signal),
+            FuzzerEvent::SanitizerReport => {
+                println!("\n================ SANITIZER REPORT ================");
+                println!(
+                    r#"The sanitizer detected an error while running a test case, as described above.
+The test case is saved to the artifacts folder, if there is one, before the process is terminated."#
+                );
+            }
 
             FuzzerEvent::TestFailure => {
                 println!("\n================ TEST FAILED ================");
             }
+            FuzzerEvent::HighFilterRejectionRate(rejected, total) => {
+                println!(
+                    "{}",
+                    Color::Yellow.paint(format!(
+                        "WARNING: THE FILTER MUTATORS REJECTED {} OF THE {} VALUES THEY GENERATED ({:.0}%)",
+                        rejected,
+                        total,
+                        rejected as f64 / total as f64 * 100.0
+                    ))
+                );
+                return;
+            }
             FuzzerEvent::Replace(_, _) => {}
             FuzzerEvent::None => return,
         };
@@ -211,16 +733,43 @@ This should never happen, and is probably a bug in fuzzcheck. Sorry :("#
                 "{} ",
                 Color::Yellow.paint(format!("iter/s {}", fuzzer_stats.exec_per_s))
             );
+            if let Some(stability) = fuzzer_stats.stability {
+                print!("{} ", Color::Yellow.paint(format!("stab {:.1}%", stability)));
+            }
+            if let Some(skipped_observations) = fuzzer_stats.skipped_observations {
+                print!("{} ", Color::Yellow.paint(format!("skip {}", skipped_observations)));
+            }
 
             println!();
             let mut stats_fields = vec![CSVField::Integer(time_since_start.as_millis() as isize)];
             stats_fields.extend(fuzzer_stats.to_csv_record());
+            stats_fields.extend(ResourceUsage::current().to_csv_record());
             stats_fields.extend(pool_stats.to_csv_record());
             self.append_stats_file(&stats_fields)
                 .expect("cannot write to stats file");
+
+            if let FuzzerEvent::Done = event {
+                self.print_summary(fuzzer_stats);
+            }
         }
     }
 
+    /// Print the number of executions and the resources used by the fuzzer since the beginning of the fuzz test
+    #[coverage(off)]
+    fn print_summary(&self, fuzzer_stats: &FuzzerStats) {
+        let time_since_start = self.initial_instant.elapsed();
+        let average_exec_per_s = fuzzer_stats.total_number_of_runs as f64 / time_since_start.as_secs_f64();
+        let usage = ResourceUsage::current();
+        println!(
+            "executions: {}, average iter/s: {:.0}, peak RSS: {:.1} MB, CPU time: {:.2?} user, {:.2?} system",
+            fuzzer_stats.total_number_of_runs,
+            average_exec_per_s,
+            usage.max_rss_kb as f64 / 1024.0,
+            usage.user_time,
+            usage.system_time,
+        );
+    }
+
     // #[coverage(off)]
     // pub fn set_start_instant(&mut self) {
     //     self.initial_instant = Instant::now();
@@ -239,17 +788,26 @@ This should never happen, and is probably a bug in fuzzcheck. Sorry :("#
     }
 
     #[coverage(off)]
-    pub fn read_input_corpus(&self) -> Result<Vec<Vec<u8>>> {
-        if self.settings.corpus_in.is_none() {
-            return Result::Ok(vec![]);
-        }
-        let corpus = self.settings.corpus_in.as_ref().unwrap().as_path();
+    /// Read every file of the input corpus, along with its path
+    ///
+    /// With the `merge` command, the files of each merged corpus are read as well.
+    ///
+    /// A file that cannot be read is returned with its error, instead of stopping the whole reading, such that a
+    /// single corrupt file does not prevent the fuzzer from starting.
+    pub fn read_input_corpus(&self) -> Result<Vec<(PathBuf, Result<Vec<u8>>)>> {
+        let merged_corpora = if let FuzzerCommand::Merge { corpora } = &self.settings.command {
+            corpora.as_slice()
+        } else {
+            &[]
+        };
         let mut values = vec![];
-        self.read_input_corpus_rec(corpus, &mut values)?;
+        for corpus in self.settings.corpus_in.iter().chain(merged_corpora) {
+            self.read_input_corpus_rec(corpus, &mut values)?;
+        }
         Ok(values)
     }
     #[coverage(off)]
-    fn read_input_corpus_rec(&self, corpus: &Path, values: &mut Vec<Vec<u8>>) -> Result<()> {
+    fn read_input_corpus_rec(&self, corpus: &Path, values: &mut Vec<(PathBuf, Result<Vec<u8>>)>) -> Result<()> {
         if !corpus.exists() {
             return Ok(());
         }
@@ -264,24 +822,205 @@ This should never happen, and is probably a bug in fuzzcheck. Sorry :("#
             let path = entry.path();
             if path.is_dir() {
                 self.read_input_corpus_rec(&path, values)?;
-            } else {
-                let data = fs::read(path)?;
-                values.push(data);
+            } else if path.extension().is_none_or(
+                #[coverage(off)]
+                |extension| extension != CORPUS_TAGS_EXTENSION && extension != TEMPORARY_FILE_EXTENSION,
+            ) && self.has_selected_tags(&path)?
+            {
+                let data = fs::read(&path);
+                values.push((path, data));
             }
         }
         Ok(())
     }
 
+    /// Whether the tags of the test case at the given path, saved in the file with the same name next to it,
+    /// are selected by the `corpus_with_tags` and `corpus_without_tags` arguments
+    #[coverage(off)]
+    fn has_selected_tags(&self, path: &Path) -> Result<bool> {
+        let Arguments {
+            corpus_with_tags,
+            corpus_without_tags,
+            ..
+        } = &self.settings;
+        if corpus_with_tags.is_empty() && corpus_without_tags.is_empty() {
+            return Ok(true);
+        }
+        let tags_path = path.with_extension(CORPUS_TAGS_EXTENSION);
+        let tags = if tags_path.is_file() {
+            fs::read_to_string(tags_path)?
+        } else {
+            String::new()
+        };
+        let has_tag = #[coverage(off)]
+        |tag: &String| {
+            tags.lines().any(
+                #[coverage(off)]
+                |line| line == tag,
+            )
+        };
+        Ok((corpus_with_tags.is_empty() || corpus_with_tags.iter().any(has_tag))
+            && !corpus_without_tags.iter().any(has_tag))
+    }
+
+    /// Read the state saved by the most recent fuzzing session in the stats folder, if there is one
+    #[cfg(feature = "serde_json_serializer")]
+    #[coverage(off)]
+    pub(crate) fn read_resume_state(&self) -> Result<Option<Vec<u8>>> {
+        if let Some((path, content)) = self.read_from_latest_session(RESUME_STATE_FILE)? {
+            println!("Resuming the fuzzing session saved at {:?}", path);
+            Ok(Some(content))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Read the file with the given name saved by the most recent fuzzing session in the stats folder
+    /// which contains it, and return its path and content
+    #[coverage(off)]
+    pub(crate) fn read_from_latest_session(&self, file_name: &str) -> Result<Option<(PathBuf, Vec<u8>)>> {
+        let Some(stats_folder) = &self.settings.stats_folder else {
+            return Ok(None);
+        };
+        if !stats_folder.is_dir() {
+            return Ok(None);
+        }
+        // each fuzzing session has its own subfolder, named after the time at which it started
+        let mut latest_session: Option<(u128, PathBuf)> = None;
+        for entry in fs::read_dir(stats_folder)? {
+            let path = entry?.path().join(file_name);
+            let time = path
+                .parent()
+                .and_then(Path::file_name)
+                .and_then(std::ffi::OsStr::to_str)
+                .and_then(
+                    #[coverage(off)]
+                    |name| name.parse::<u128>().ok(),
+                );
+            if let Some(time) = time
+                && path.is_file()
+                && latest_session.as_ref().is_none_or(
+                    #[coverage(off)]
+                    |(latest_time, _)| time > *latest_time,
+                )
+            {
+                latest_session = Some((time, path));
+            }
+        }
+        if let Some((_, path)) = latest_session {
+            let content = fs::read(&path)?;
+            Ok(Some((path, content)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// The files of the incoming corpus folder, along with their modification time
+    ///
+    /// The subfolders and the hidden files, such as the temporary files of text editors, are ignored.
+    #[coverage(off)]
+    pub(crate) fn incoming_corpus_files(&self) -> Result<Vec<(PathBuf, SystemTime)>> {
+        let Some(incoming_corpus) = &self.settings.incoming_corpus else {
+            return Ok(vec![]);
+        };
+        if !incoming_corpus.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut files = vec![];
+        for entry in fs::read_dir(incoming_corpus)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() && !entry.file_name().to_string_lossy().starts_with('.') {
+                files.push((entry.path(), metadata.modified()?));
+            }
+        }
+        // the files are read in a deterministic order
+        files.sort();
+        Ok(files)
+    }
+
+    /// Move a file of the incoming corpus to the input corpus folder, with the same name
+    ///
+    /// It returns `false`, and leaves the file where it is, if there is no input corpus.
+    #[coverage(off)]
+    pub(crate) fn move_to_input_corpus(&self, path: &Path) -> Result<bool> {
+        let Some(corpus_in) = &self.settings.corpus_in else {
+            return Ok(false);
+        };
+        fs::create_dir_all(corpus_in)?;
+        let destination = corpus_in.join(path.file_name().unwrap_or_default());
+        if fs::rename(path, &destination).is_err() {
+            // the folders may be on different file systems
+            let content = fs::read(path)?;
+            write_atomically(
+                &destination,
+                #[coverage(off)]
+                |writer| writer.write_all(&content),
+            )?;
+            fs::remove_file(path)?;
+        }
+        Ok(true)
+    }
+
+    /// Tell the user that a file of the input corpus is skipped because it is invalid
+    #[coverage(off)]
+    pub(crate) fn report_invalid_corpus_file(&self, path: &Path, reason: &dyn std::fmt::Display) {
+        println!(
+            "{}",
+            Color::Yellow.paint(format!(
+                "Skipping the invalid corpus file {}: {}",
+                path.display(),
+                reason
+            ))
+        );
+    }
+
     #[coverage(off)]
     pub fn read_input_file(&self, file: &Path) -> Result<Vec<u8>> {
         let data = fs::read(file)?;
         Ok(data)
     }
 
+    /// Decode the test case saved in the given file, without reading the whole file in memory first
+    /// if the serializer [streams it](Serializer::from_reader).
+    ///
+    /// It returns `Ok(None)` if the file cannot be decoded by the serializer.
     #[coverage(off)]
-    pub fn save_artifact(&mut self, content: Vec<u8>, cplx: f64, extension: &str) -> Result<()> {
+    pub fn read_input_value<T>(&self, file: &Path, serializer: &dyn Serializer<Value = T>) -> Result<Option<T>> {
+        let mut reader = BufReader::new(File::open(file)?);
+        serializer.from_reader(&mut reader)
+    }
+
+    /// Save a failing test case to the artifacts folder, by [streaming](Serializer::to_writer) it to its file.
+    ///
+    /// Its origin, if it is known, is printed and saved to the `origins` subfolder of the artifacts folder.
+    #[coverage(off)]
+    pub fn save_artifact<T>(
+        &mut self,
+        value: &T,
+        serializer: &dyn Serializer<Value = T>,
+        cplx: f64,
+        origin: Option<&ArtifactOrigin>,
+    ) -> Result<()> {
+        let origin = origin.map(
+            #[coverage(off)]
+            |origin| origin.describe(),
+        );
+        let origin_field = (
+            "origin".to_string(),
+            origin.clone().map_or(JsonValue::Null, JsonValue::String),
+        );
         let artifacts_folder = self.settings.artifacts_folder.as_ref();
         if artifacts_folder.is_none() {
+            self.send_to_stats_socket(
+                "artifact",
+                vec![
+                    ("path".to_string(), JsonValue::Null),
+                    ("complexity".to_string(), JsonValue::Float(cplx)),
+                    origin_field,
+                ],
+                None,
+            );
             return Ok(());
         }
         let artifacts_folder = artifacts_folder.unwrap().as_path();
@@ -290,27 +1029,44 @@ This should never happen, and is probably a bug in fuzzcheck. Sorry :("#
             std::fs::create_dir_all(artifacts_folder)?;
         }
 
-        let mut hasher = DefaultHasher::new();
-        content.hash(&mut hasher);
-        let hash = hasher.finish();
-
-        let name = if let FuzzerCommand::MinifyInput { .. } | FuzzerCommand::Read { .. } = self.settings.command {
-            format!("{:.0}--{:x}", cplx * 100.0, hash)
+        // the minify command of cargo-fuzzcheck relies on the complexity of the artifacts being part of their name
+        let naming = if let FuzzerCommand::MinifyInput { .. } | FuzzerCommand::Read { .. } = self.settings.command {
+            CorpusNamingPolicy::ComplexityAndHash
         } else {
-            format!("{:x}", hash)
+            self.settings.corpus_naming
         };
+        let name = self.file_name(&self.hash_value(value, serializer), cplx, naming);
 
-        let path = artifacts_folder.join(&name).with_extension(extension);
-        fs::write(&path, &content)?;
+        let path = artifacts_folder.join(&name).with_extension(serializer.extension());
+        write_atomically(
+            &path,
+            #[coverage(off)]
+            |writer| serializer.to_writer(value, writer),
+        )?;
         println!("Failing test case found. Saving at {:?}", path);
+        if let Some(origin) = &origin {
+            let origins_folder = artifacts_folder.join(ARTIFACT_ORIGINS_FOLDER);
+            fs::create_dir_all(&origins_folder)?;
+            fs::write(origins_folder.join(&name).with_extension("txt"), origin)?;
+            print!("{}", origin);
+        }
+        self.send_to_stats_socket(
+            "artifact",
+            vec![
+                ("path".to_string(), JsonValue::String(path.display().to_string())),
+                ("complexity".to_string(), JsonValue::Float(cplx)),
+                origin_field,
+            ],
+            None,
+        );
 
         Result::Ok(())
     }
 
     #[coverage(off)]
-    pub fn stop(&mut self) -> ! {
-        self.report_event(FuzzerEvent::Stop, None);
-        std::process::exit(TerminationStatus::Success as i32);
+    pub fn stop(&mut self, fuzzer_stats: &FuzzerStats, pool_stats: &dyn Stats) -> ! {
+        self.report_event(FuzzerEvent::Stop, Some((fuzzer_stats, pool_stats)));
+        terminate(TerminationStatus::Success)
     }
 
     #[coverage(off)]
@@ -330,10 +1086,120 @@ impl SaveToStatsFolder for World {
         cfg_if::cfg_if! {
             if #[cfg(feature = "serde_json_serializer")] {
                 let content = serde_json::to_vec(&self.corpus.iter().collect::<Vec<_>>()).unwrap();
-                vec![(PathBuf::new().join("world.json"), content)]
+                // the names of the files kept by each pool, indexed by the subfolder of the pool
+                let mut pools = std::collections::BTreeMap::<String, Vec<String>>::new();
+                for ((path, _), (name, _)) in self.corpus.iter() {
+                    pools.entry(path.display().to_string()).or_default().push(name.clone());
+                }
+                for names in pools.values_mut() {
+                    names.sort();
+                }
+                let pools = serde_json::to_vec_pretty(&pools).unwrap();
+                vec![
+                    (PathBuf::new().join("world.json"), content),
+                    (PathBuf::new().join(CORPUS_CONTENT_FILE), pools),
+                ]
             } else {
                 vec![]
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hash;
+
+    use super::*;
+    use crate::ByteSerializer;
+
+    #[test]
+    #[coverage(off)]
+    fn test_hash_value() {
+        let mut hasher = HashingWriter::new(7);
+        hasher.write_all(b"fuzz").unwrap();
+        hasher.write_all(b"ing").unwrap();
+        let mut expected = DefaultHasher::new();
+        b"fuzzing".as_slice().hash(&mut expected);
+        assert_eq!(hasher.hasher.finish(), expected.finish());
+
+        let folder = std::env::temp_dir().join(format!("fuzzcheck_world_hash_value_{}", std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+        let path = folder.join("test_case.bin");
+        let serializer = ByteSerializer::new("bin");
+        let value = vec![0, 1, 2, 255];
+        write_atomically(
+            &path,
+            #[coverage(off)]
+            |writer| serializer.to_writer(&value, writer),
+        )
+        .unwrap();
+        let content = fs::read(&path).unwrap();
+        // the temporary file was renamed
+        let nbr_files = fs::read_dir(&folder).unwrap().count();
+        fs::remove_dir_all(&folder).unwrap();
+        assert_eq!(content, value);
+        assert_eq!(nbr_files, 1);
+
+        let mut counter = CountingWriter::default();
+        serializer.to_writer(&value, &mut counter).unwrap();
+        assert_eq!(counter.nbr_bytes, 4);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_corpus_tags() {
+        let folder = std::env::temp_dir().join(format!("fuzzcheck_world_corpus_tags_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        let mut settings = Arguments::for_property_test(0);
+        settings.corpus_out = Some(folder.clone());
+        let mut world = World::new(settings).unwrap();
+        let serializer = ByteSerializer::new("bin");
+        let tags_file = folder.join("shared").join("0").with_extension(CORPUS_TAGS_EXTENSION);
+        let tags = #[coverage(off)]
+        |tags: &[&str]| {
+            tags.iter()
+                .map(
+                    #[coverage(off)]
+                    |tag| tag.to_string(),
+                )
+                .collect::<Vec<_>>()
+        };
+        let mut update = #[coverage(off)]
+        |idx: usize, add: bool, remove: &[usize], delta_tags: &[&str]| {
+            let remove = remove.iter().copied().map(PoolStorageIndex::mock).collect();
+            let delta = CorpusDelta::new(PathBuf::from("shared"), add, remove).with_tags(tags(delta_tags));
+            world
+                .update_corpus(
+                    PoolStorageIndex::mock(idx),
+                    &vec![0],
+                    &serializer,
+                    &idx.to_string(),
+                    1.0,
+                    &[delta],
+                )
+                .unwrap();
+        };
+        let read_tags = #[coverage(off)]
+        || fs::read_to_string(&tags_file).ok();
+
+        // two pools sharing a folder keep the same test case, with different tags
+        update(0, true, &[], &["slow"]);
+        assert_eq!(read_tags().as_deref(), Some("slow"));
+        update(0, true, &[], &["slow", "large"]);
+        assert_eq!(read_tags().as_deref(), Some("slow\nlarge"));
+        // the second pool removes it, the first one still keeps it with its tag
+        update(1, false, &[0], &["slow", "large"]);
+        assert_eq!(read_tags().as_deref(), Some("slow"));
+        // the first pool removes it too
+        update(2, false, &[0], &["slow"]);
+        assert_eq!(read_tags(), None);
+
+        // a test case added without tags does not keep the tags saved by a previous fuzzing session
+        fs::write(&tags_file, "slow").unwrap();
+        update(0, true, &[], &[]);
+        assert_eq!(read_tags(), None);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+use fuzzcheck::mutators::vector::VecMutator;
+use fuzzcheck::{Arguments, DefaultMutator, Mutator};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{events_pool, events_sensor, latest_session, record_byte_events, TempFolder};
+
+fn test_function(xs: &[u8]) -> bool {
+    record_byte_events(xs, 8);
+    true
+}
+
+#[test]
+fn test_adaptive_mutations() {
+    let mutator = VecMutator::new(u8::default_mutator(), 0..=16);
+    let operators = mutator.mutation_operators();
+    assert!(operators.contains(&"vector::MutateElement"));
+    assert!(operators.contains(&"vector::Remove"));
+
+    let folder = TempFolder::new("adaptive_mutations");
+    let mut arguments = Arguments::for_property_test(2000);
+    arguments.stats_folder = Some(folder.join("stats"));
+    arguments.adaptive_mutations = true;
+    let _ = fuzzcheck::fuzz_test(test_function)
+        .mutator(mutator)
+        .serde_serializer()
+        .sensor_and_pool(events_sensor(8), events_pool("events", 8))
+        .arguments(arguments)
+        .launch();
+
+    let csv = std::fs::read_to_string(latest_session(&folder.join("stats")).join("mutation_operators.csv")).unwrap();
+
+    let records = csv
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').map(|field| field.trim_matches('"')).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    // every operator of the mutator is listed, even if it was never applied
+    for operator in operators {
+        assert!(records.iter().any(|record| record[0] == operator), "{operator}");
+    }
+    let mutate_element = records
+        .iter()
+        .find(|record| record[0] == "vector::MutateElement")
+        .unwrap();
+    let (uses, successes) = (
+        mutate_element[1].parse::<usize>().unwrap(),
+        mutate_element[2].parse::<usize>().unwrap(),
+    );
+    assert!(uses > 0 && successes > 0 && successes <= uses);
+}
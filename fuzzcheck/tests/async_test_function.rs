@@ -0,0 +1,52 @@
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use fuzzcheck::builder::TestFuture;
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+use fuzzcheck::{Arguments, DefaultMutator};
+
+/// A minimal executor for futures that never wait on anything but each other
+fn block_on<O>(future: TestFuture<O>) -> O {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+async fn length(xs: &[u8]) -> usize {
+    xs.len()
+}
+
+async fn short_vectors_only(xs: &[u8]) -> Result<(), String> {
+    if length(xs).await < 4 {
+        Ok(())
+    } else {
+        Err(format!("the vector has {} elements", xs.len()))
+    }
+}
+
+#[test]
+fn test_async_test_function() {
+    let counterexample = fuzzcheck::fuzz_test_async(short_vectors_only, block_on)
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(10_000))
+        .launch_property_test();
+
+    let counterexample = counterexample.expect("the property test should have found a counterexample");
+    assert_eq!(counterexample.len(), 4);
+
+    let counterexample = fuzzcheck::fuzz_test_async(async |x: &bool| *x || !*x, block_on)
+        .mutator(bool::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(1_000))
+        .launch_property_test();
+
+    assert!(counterexample.is_none());
+}
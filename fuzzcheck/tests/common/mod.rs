@@ -0,0 +1,78 @@
+//! The setup shared by the tests which run the fuzzer on a test function recording one user event for each
+//! byte of its input, such that the fuzzer finds new test cases without relying on code coverage.
+//!
+//! It is included with `#[path = "common/mod.rs"] mod common;`, because the test files are compiled both as
+//! their own test executable and as modules of `lib.rs`.
+#![allow(dead_code)]
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use fuzzcheck::sensors_and_pools::{SimplestToActivateCounterPool, UserEventSensor};
+use fuzzcheck::{record_event, Arguments, FuzzingResult};
+
+/// Record one event for each of the first `nbr_bytes` bytes of `xs`, identified by the index and the value of the byte
+pub fn record_byte_events(xs: &[u8], nbr_bytes: usize) {
+    for (i, &x) in xs.iter().enumerate().take(nbr_bytes) {
+        record_event(i as u64 * 256 + x as u64, 1);
+    }
+}
+
+/// The sensor observing the events recorded by [`record_byte_events`]
+pub fn events_sensor(nbr_bytes: usize) -> UserEventSensor {
+    UserEventSensor::new(nbr_bytes * 256)
+}
+
+/// A pool called `name` keeping the simplest test case recording each event of [`record_byte_events`]
+pub fn events_pool(name: &str, nbr_bytes: usize) -> SimplestToActivateCounterPool {
+    SimplestToActivateCounterPool::new(name, nbr_bytes * 256)
+}
+
+/// Fuzz the test function with the default mutator of `Vec<u8>`, the serde serializer, and the events sensor
+/// and pool of the first `nbr_bytes` bytes of the input
+pub fn fuzz(test_function: fn(&[u8]) -> bool, nbr_bytes: usize, arguments: Arguments) -> FuzzingResult<Vec<u8>> {
+    fuzzcheck::fuzz_test(test_function)
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(events_sensor(nbr_bytes), events_pool("events", nbr_bytes))
+        .arguments(arguments)
+        .launch()
+}
+
+/// A folder in the temporary directory, which is empty when it is created and removed when it is dropped
+pub struct TempFolder {
+    path: PathBuf,
+}
+impl TempFolder {
+    /// Create the folder `fuzzcheck_<name>_<process id>`, such that concurrent runs of the tests do not share it
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("fuzzcheck_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        Self { path }
+    }
+}
+impl Deref for TempFolder {
+    type Target = Path;
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+impl AsRef<Path> for TempFolder {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+impl Drop for TempFolder {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// The folder of the most recent fuzzing session in the stats folder
+pub fn latest_session(stats_folder: &Path) -> PathBuf {
+    std::fs::read_dir(stats_folder)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .max()
+        .unwrap()
+}
@@ -2,7 +2,8 @@
 use std::collections::HashSet;
 use std::ops::RangeBounds;
 
-use fuzzcheck::mutators::integer_within_range::I8WithinRangeMutator;
+use fuzzcheck::mutators::integer::{I128Mutator, U128Mutator};
+use fuzzcheck::mutators::integer_within_range::{I128WithinRangeMutator, I8WithinRangeMutator, U128WithinRangeMutator};
 use fuzzcheck::mutators::testing_utilities::test_mutator;
 use fuzzcheck::Mutator;
 
@@ -80,3 +81,47 @@ fn test_mutate_constrained_signed_integer_8() {
     set.sort_unstable();
     println!("{} {set:?}", set.len());
 }
+
+#[test]
+fn test_constrained_integer_128() {
+    let m = I128WithinRangeMutator::new(-10..10);
+    let mut step = 0;
+    let mut all_generated = HashSet::new();
+    while let Some((x, cplx)) = m.ordered_arbitrary(&mut step, 1000.0) {
+        assert_eq!(cplx, 128.0);
+        let is_new = all_generated.insert(x);
+        assert!(is_new);
+    }
+    assert_eq!(all_generated, (-10..10).collect());
+
+    let range = u128::MAX - 1000..=u128::MAX;
+    let m = U128WithinRangeMutator::new(range.clone());
+    for _ in 0..1000 {
+        let x = m.random_arbitrary(1000.0).0;
+        assert!(range.contains(&x), "{}", x);
+    }
+    let mut step = 0;
+    for _ in 0..1000 {
+        let (x, _) = m.ordered_arbitrary(&mut step, 1000.0).unwrap();
+        assert!(range.contains(&x), "{}", x);
+    }
+    test_mutator(m, 1000., 1000., false, true, 100, 100);
+    test_mutator(I128WithinRangeMutator::new(..), 1000., 1000., false, true, 100, 100);
+}
+
+#[test]
+fn test_integer_128() {
+    test_mutator(U128Mutator::default(), 1000., 1000., false, true, 100, 100);
+    test_mutator(I128Mutator::default(), 1000., 1000., false, true, 100, 100);
+
+    let m = U128Mutator::default();
+    let mut step = 0;
+    let mut all_generated = HashSet::new();
+    for _ in 0..10_000 {
+        let (x, cplx) = m.ordered_arbitrary(&mut step, 1000.0).unwrap();
+        assert_eq!(cplx, 128.0);
+        let is_new = all_generated.insert(x);
+        assert!(is_new);
+    }
+    assert!(all_generated.iter().any(|x| *x > u64::MAX as u128));
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+use fuzzcheck::serializers::MultiSerializer;
+use fuzzcheck::{Arguments, DefaultMutator, SerdeSerializer, StringSerializer};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::TempFolder;
+
+const MAGIC: &str = "a string that is unlikely to be generated randomly";
+
+fn is_not_magic(s: &str) -> bool {
+    s != MAGIC
+}
+
+fn find_magic_string_in_corpus(corpus: &Path, also_read_txt_files: bool) -> Option<String> {
+    let builder = fuzzcheck::fuzz_test(is_not_magic)
+        .mutator(String::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(100))
+        .in_corpus(Some(corpus));
+    if also_read_txt_files {
+        builder
+            .also_read_corpus_with(StringSerializer::<String>::new("txt"))
+            .launch_property_test()
+    } else {
+        builder.launch_property_test()
+    }
+}
+
+fn find_magic_string_with_multi_serializer(corpus: &Path) -> Option<String> {
+    let serializer =
+        MultiSerializer::new(SerdeSerializer::<String>::default()).also_read(StringSerializer::<String>::new("txt"));
+    fuzzcheck::fuzz_test(is_not_magic)
+        .mutator(String::default_mutator())
+        .serializer(serializer)
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(100))
+        .in_corpus(Some(corpus))
+        .launch_property_test()
+}
+
+#[test]
+fn test_corpus_extensions() {
+    let corpus = TempFolder::new("corpus_extensions");
+    std::fs::create_dir_all(&corpus).unwrap();
+
+    // files with an unknown extension are skipped instead of being parsed as json
+    std::fs::write(corpus.join("magic.txt"), MAGIC).unwrap();
+    std::fs::write(corpus.join("garbage.bin"), [0xff, 0x00, 0x12]).unwrap();
+    assert_eq!(find_magic_string_in_corpus(&corpus, false), None);
+
+    // unless a serializer for that extension was registered
+    assert_eq!(find_magic_string_in_corpus(&corpus, true).as_deref(), Some(MAGIC));
+    assert_eq!(find_magic_string_with_multi_serializer(&corpus).as_deref(), Some(MAGIC));
+
+    // files with the extension of the main serializer are always read
+    std::fs::remove_file(corpus.join("magic.txt")).unwrap();
+    std::fs::write(corpus.join("magic.json"), serde_json::to_vec(MAGIC).unwrap()).unwrap();
+    assert_eq!(find_magic_string_in_corpus(&corpus, false).as_deref(), Some(MAGIC));
+}
@@ -0,0 +1,65 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use fuzzcheck::{Arguments, CorpusNamingPolicy};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{fuzz, record_byte_events, TempFolder};
+
+fn test_function(xs: &[u8]) -> bool {
+    record_byte_events(xs, 2);
+    true
+}
+
+fn fuzz_with_naming_policy(corpus_in: &Path, corpus_out: &Path, corpus_naming: CorpusNamingPolicy) {
+    let mut arguments = Arguments::for_property_test(1000);
+    arguments.corpus_in = Some(corpus_in.to_path_buf());
+    arguments.corpus_out = Some(corpus_out.to_path_buf());
+    arguments.corpus_naming = corpus_naming;
+    let _ = fuzz(test_function, 2, arguments);
+}
+
+/// The names of the files of the corpus of the `events` pool, without their extension
+fn file_names(corpus: &Path) -> HashSet<String> {
+    std::fs::read_dir(corpus.join("events"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path().file_stem().unwrap().to_str().unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn test_corpus_naming() {
+    let folder = TempFolder::new("corpus_naming");
+
+    fuzz_with_naming_policy(
+        &folder.join("none"),
+        &folder.join("cplx_hash"),
+        CorpusNamingPolicy::ComplexityAndHash,
+    );
+    let cplx_hash_names = file_names(&folder.join("cplx_hash"));
+    assert!(!cplx_hash_names.is_empty());
+    for name in &cplx_hash_names {
+        let (cplx, hash) = name.split_once("--").unwrap();
+        assert!(cplx.parse::<usize>().is_ok(), "{}", name);
+        assert!(u64::from_str_radix(hash, 16).is_ok(), "{}", name);
+    }
+
+    // a corpus named with one policy can be read and saved with the other one
+    fuzz_with_naming_policy(
+        &folder.join("cplx_hash"),
+        &folder.join("hash"),
+        CorpusNamingPolicy::ContentHash,
+    );
+    let hash_names = file_names(&folder.join("hash"));
+    assert!(!hash_names.is_empty());
+    for name in &hash_names {
+        assert!(u64::from_str_radix(name, 16).is_ok(), "{}", name);
+    }
+    // the names are stable: the test cases that were kept have the same content hash
+    let cplx_hashes = cplx_hash_names
+        .iter()
+        .map(|name| name.split_once("--").unwrap().1.to_owned())
+        .collect::<HashSet<_>>();
+    assert!(!cplx_hashes.is_disjoint(&hash_names));
+}
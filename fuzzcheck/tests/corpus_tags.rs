@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, MaximiseTimePool, NoopSensor, TimeSensor};
+use fuzzcheck::{Arguments, DefaultMutator};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::TempFolder;
+
+fn test_function(xs: &[u8]) -> bool {
+    xs.iter().map(|&x| x as usize).sum::<usize>() < usize::MAX
+}
+
+/// The number of test cases run by the first step of the fuzzer, with the given tag filters
+fn nbr_initial_runs(corpus: &Path, with_tags: &[&str], without_tags: &[&str]) -> usize {
+    let mut arguments = Arguments::for_property_test(1000);
+    arguments.corpus_in = Some(corpus.to_path_buf());
+    arguments.corpus_with_tags = with_tags.iter().map(|tag| tag.to_string()).collect();
+    arguments.corpus_without_tags = without_tags.iter().map(|tag| tag.to_string()).collect();
+    let mut fuzzer = fuzzcheck::fuzz_test(test_function)
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(arguments)
+        .build();
+    // the first step runs the test cases of the input corpus, followed by a fixed number of arbitrary ones
+    fuzzer.step().unwrap().nbr_runs
+}
+
+#[test]
+fn test_corpus_tags() {
+    let folder = TempFolder::new("corpus_tags");
+    let corpus = folder.join("corpus");
+
+    let mut arguments = Arguments::for_property_test(1000);
+    arguments.corpus_out = Some(corpus.clone());
+    let _ = fuzzcheck::fuzz_test(test_function)
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(TimeSensor::default(), MaximiseTimePool::new("slowest", 3))
+        .arguments(arguments)
+        .launch();
+
+    // every test case kept by the pool is tagged
+    let mut nbr_slow = 0;
+    for entry in std::fs::read_dir(corpus.join("slowest")).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().unwrap() == "json" {
+            nbr_slow += 1;
+            let tags = std::fs::read_to_string(path.with_extension("tags")).unwrap();
+            assert_eq!(tags, "slow");
+        }
+    }
+    assert_eq!(nbr_slow, 3);
+
+    std::fs::create_dir_all(corpus.join("manual")).unwrap();
+    std::fs::write(corpus.join("manual/untagged.json"), "[1, 2, 3]").unwrap();
+
+    let nbr_arbitrary_runs = nbr_initial_runs(&folder.join("empty_corpus"), &[], &[]);
+    let nbr_read_test_cases =
+        |with_tags, without_tags| nbr_initial_runs(&corpus, with_tags, without_tags) - nbr_arbitrary_runs;
+    assert_eq!(nbr_read_test_cases(&[], &[]), 4);
+    assert_eq!(nbr_read_test_cases(&["slow"], &[]), 3);
+    assert_eq!(nbr_read_test_cases(&[], &["slow"]), 1);
+    assert_eq!(nbr_read_test_cases(&["slow"], &["slow"]), 0);
+}
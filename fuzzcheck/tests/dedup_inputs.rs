@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+
+use fuzzcheck::Arguments;
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{fuzz, record_byte_events, TempFolder};
+
+static EXECUTED_INPUTS: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+fn test_function(xs: &[u8]) -> bool {
+    EXECUTED_INPUTS.lock().unwrap().push(xs.to_vec());
+    record_byte_events(xs, 4);
+    true
+}
+
+/// Run a fuzzing session and return the inputs that were executed
+fn fuzz_and_collect_inputs(folder: &Path, maximum_iterations: usize, deduplicate_inputs: bool) -> Vec<Vec<u8>> {
+    EXECUTED_INPUTS.lock().unwrap().clear();
+    let mut arguments = Arguments::for_property_test(maximum_iterations);
+    arguments.corpus_in = Some(folder.join("corpus"));
+    arguments.corpus_out = Some(folder.join("corpus"));
+    arguments.stats_folder = Some(folder.join("stats"));
+    arguments.deduplicate_inputs = deduplicate_inputs;
+    let _ = fuzz(test_function, 4, arguments);
+    std::mem::take(&mut *EXECUTED_INPUTS.lock().unwrap())
+}
+
+fn nbr_files(folder: &Path) -> usize {
+    std::fs::read_dir(folder)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                nbr_files(&path)
+            } else {
+                1
+            }
+        })
+        .sum()
+}
+
+/// The number of inputs read from the corpus and generated from scratch when the fuzzer starts,
+/// which are always executed
+fn max_nbr_initial_inputs(folder: &Path) -> usize {
+    nbr_files(&folder.join("corpus")) + 100
+}
+
+#[test]
+fn test_dedup_inputs() {
+    let folder = TempFolder::new("dedup_inputs");
+
+    let executed = fuzz_and_collect_inputs(&folder, 3000, true);
+    let mut seen = HashSet::new();
+    let nbr_repeated = executed.iter().filter(|x| !seen.insert(x.to_vec())).count();
+    assert!(nbr_repeated <= 100, "{}", nbr_repeated);
+
+    // the inputs executed by the previous session are not executed again, except the initial ones
+    let max_nbr_initial_inputs = max_nbr_initial_inputs(&folder);
+    let executed = fuzz_and_collect_inputs(&folder, 3000, true);
+    let nbr_repeated = executed.iter().filter(|x| !seen.insert(x.to_vec())).count();
+    assert!(nbr_repeated <= max_nbr_initial_inputs, "{}", nbr_repeated);
+
+    // without deduplication, many inputs are executed multiple times
+    let executed = fuzz_and_collect_inputs(&folder, 3000, false);
+    let nbr_repeated = executed.iter().filter(|x| !seen.insert(x.to_vec())).count();
+    assert!(nbr_repeated > max_nbr_initial_inputs, "{}", nbr_repeated);
+}
@@ -0,0 +1,80 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::DefaultMutator;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+enum BasicWithDiscriminants {
+    A = 1,
+    B = 5,
+    C = 10,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+#[repr(u8)]
+enum DataWithDiscriminants {
+    A(u8) = 1,
+    B = 3,
+    C { x: bool } = 7,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+#[non_exhaustive]
+enum NonExhaustiveBasic {
+    A,
+    B(),
+    #[ignore_variant]
+    C {},
+    D,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+#[non_exhaustive]
+enum NonExhaustiveData {
+    A(u8),
+    #[non_exhaustive]
+    B {
+        x: bool,
+    },
+    C,
+}
+
+#[test]
+fn test_derived_enums_with_discriminants() {
+    test_mutator::<BasicWithDiscriminants, _>(
+        BasicWithDiscriminants::default_mutator(),
+        1000.,
+        1000.,
+        false,
+        true,
+        100,
+        100,
+    );
+    test_mutator(
+        DataWithDiscriminants::default_mutator(),
+        1000.,
+        1000.,
+        false,
+        true,
+        100,
+        100,
+    );
+    test_mutator::<NonExhaustiveBasic, _>(
+        NonExhaustiveBasic::default_mutator(),
+        1000.,
+        1000.,
+        false,
+        true,
+        100,
+        100,
+    );
+    test_mutator(
+        NonExhaustiveData::default_mutator(),
+        1000.,
+        1000.,
+        false,
+        true,
+        100,
+        100,
+    );
+}
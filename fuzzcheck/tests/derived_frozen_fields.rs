@@ -0,0 +1,45 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::{DefaultMutator, Mutator};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+struct FrozenStruct {
+    x: u8,
+    #[frozen]
+    counter: usize,
+    #[frozen(String::from("v1"))]
+    version: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+enum FrozenEnum {
+    A(#[frozen(7)] u16, bool),
+    B {
+        #[frozen]
+        x: Vec<u8>,
+        y: u32,
+    },
+}
+
+#[test]
+fn test_derived_frozen_fields() {
+    let mutator = FrozenStruct::default_mutator();
+    let (mut value, _) = mutator.random_arbitrary(100.0);
+    let mut cache = mutator.validate_value(&value).unwrap();
+    for _ in 0..1000 {
+        assert_eq!(value.counter, 0);
+        assert_eq!(value.version, "v1");
+        let _ = mutator.random_mutate(&mut value, &mut cache, 100.0);
+    }
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+
+    let mutator = FrozenEnum::default_mutator();
+    for _ in 0..1000 {
+        match mutator.random_arbitrary(100.0).0 {
+            FrozenEnum::A(x, _) => assert_eq!(x, 7),
+            FrozenEnum::B { x, .. } => assert!(x.is_empty()),
+        }
+    }
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+}
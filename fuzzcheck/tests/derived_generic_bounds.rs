@@ -0,0 +1,44 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::DefaultMutator;
+
+pub trait Trait: Clone + Debug + Eq + std::hash::Hash {}
+impl Trait for u8 {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+struct WithLifetime<'a, T: Trait>
+where
+    T: PartialEq,
+{
+    name: Cow<'a, str>,
+    x: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+enum EnumWithLifetime<'a, T>
+where
+    T: Trait + PartialEq,
+{
+    A(Cow<'a, str>),
+    B { x: T, y: bool },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+struct StaticCow {
+    name: Cow<'static, str>,
+    bytes: Cow<'static, [u8]>,
+}
+
+#[test]
+fn test_derived_with_generic_bounds() {
+    let mutator = WithLifetime::<'static, u8>::default_mutator();
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+    let mutator = EnumWithLifetime::<'static, u8>::default_mutator();
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+    let mutator = StaticCow::default_mutator();
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+}
@@ -0,0 +1,57 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+
+use std::rc::Rc;
+
+use fuzzcheck::mutators::grammar::{alternation, concatenation, literal, recurse, recursive, Grammar};
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::{DefaultMutator, Mutator};
+
+#[coverage(off)]
+fn parentheses() -> Rc<Grammar> {
+    recursive(
+        #[coverage(off)]
+        |rule| alternation([concatenation([literal('('), recurse(rule), literal(')')]), literal('x')]),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+struct Request {
+    #[field_mutator(grammar = "GET|POST|PUT")]
+    method: String,
+    #[field_mutator(grammar = { parentheses() })]
+    body: String,
+    id: u8,
+}
+
+#[test]
+fn test_derived_grammar_field() {
+    let mutator = Request::default_mutator();
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+
+    let mutator = Request::default_mutator();
+    mutator.initialize();
+    for _ in 0..100 {
+        let (value, _) = mutator.random_arbitrary(100.0);
+        assert!(["GET", "POST", "PUT"].contains(&value.method.as_str()));
+        let depth = value.body.len() / 2;
+        assert_eq!(value.body, format!("{}x{}", "(".repeat(depth), ")".repeat(depth)));
+    }
+
+    let valid = Request {
+        method: "POST".to_owned(),
+        body: "((x))".to_owned(),
+        id: 3,
+    };
+    assert!(mutator.validate_value(&valid).is_some());
+    let invalid = Request {
+        method: "DELETE".to_owned(),
+        ..valid.clone()
+    };
+    assert!(mutator.validate_value(&invalid).is_none());
+    let invalid = Request {
+        body: "((x)".to_owned(),
+        ..valid
+    };
+    assert!(mutator.validate_value(&invalid).is_none());
+}
@@ -0,0 +1,47 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+use std::any::Any;
+
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::{DefaultMutator, Mutator};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+struct OpaqueStruct {
+    #[opaque]
+    x: Vec<u8>,
+    y: Vec<u16>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+enum OpaqueEnum {
+    A(#[opaque] Vec<u8>, bool),
+    B { x: u8 },
+}
+
+#[test]
+fn test_derived_opaque_fields() {
+    let mutator = OpaqueStruct::default_mutator();
+    let value = OpaqueStruct {
+        x: vec![1, 2],
+        y: vec![3, 4],
+    };
+    let cache = mutator.validate_value(&value).unwrap();
+    let mut vectors = 0;
+    let mut bytes = 0;
+    let mut shorts = 0;
+    mutator.visit_subvalues(&value, &cache, &mut |subvalue: &dyn Any, _cplx| {
+        if subvalue.is::<Vec<u8>>() {
+            vectors += 1;
+        } else if subvalue.is::<u8>() {
+            bytes += 1;
+        } else if subvalue.is::<u16>() {
+            shorts += 1;
+        }
+    });
+    // the opaque field is visited as a whole, but not its elements
+    assert_eq!((vectors, bytes, shorts), (1, 0, 2));
+
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+    let mutator = OpaqueEnum::default_mutator();
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+}
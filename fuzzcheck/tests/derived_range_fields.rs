@@ -0,0 +1,51 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::{DefaultMutator, Mutator};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+struct RangeStruct {
+    #[range(0..=10)]
+    x: u8,
+    #[range(-5..5)]
+    y: i64,
+    #[range('a'..='f')]
+    c: char,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+enum RangeEnum {
+    A(#[range(100..)] u16),
+    B {
+        #[range(..=0)]
+        x: i8,
+        #[weight(2.0)]
+        #[range(3..=4)]
+        y: u32,
+    },
+}
+
+#[test]
+fn test_derived_range_fields() {
+    let mutator = RangeStruct::default_mutator();
+    for _ in 0..1000 {
+        let (value, _) = mutator.random_arbitrary(100.0);
+        assert!(value.x <= 10);
+        assert!((-5..5).contains(&value.y));
+        assert!(('a'..='f').contains(&value.c));
+    }
+    assert!(!mutator.is_valid(&RangeStruct { x: 11, y: 0, c: 'a' }));
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+
+    let mutator = RangeEnum::default_mutator();
+    for _ in 0..1000 {
+        match mutator.random_arbitrary(100.0).0 {
+            RangeEnum::A(x) => assert!(x >= 100),
+            RangeEnum::B { x, y } => {
+                assert!(x <= 0);
+                assert!((3..=4).contains(&y));
+            }
+        }
+    }
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+}
@@ -2,6 +2,7 @@
 #![allow(clippy::type_complexity)]
 #![feature(coverage_attribute)]
 
+use std::any::{Any, TypeId};
 use std::fmt::Debug;
 
 use fuzzcheck::mutators::boxed::BoxMutator;
@@ -11,7 +12,8 @@ use fuzzcheck::mutators::recursive::RecurToMutator;
 use fuzzcheck::mutators::testing_utilities::test_mutator;
 use fuzzcheck::mutators::tuples::{Tuple2, Tuple2Mutator, TupleMutatorWrapper};
 use fuzzcheck::mutators::vector::VecMutator;
-use fuzzcheck::{make_mutator, DefaultMutator, Mutator};
+use fuzzcheck::subvalue_provider::Generation;
+use fuzzcheck::{make_mutator, DefaultMutator, Mutator, SubValueProvider, SubValueProviderId};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 struct SampleStruct<T, U> {
@@ -96,3 +98,96 @@ fn test_derived_struct() {
     let mutator = <Vec<SampleStruct<u8, u8>>>::default_mutator();
     test_mutator(mutator, 500., 500., false, true, 50, 100);
 }
+
+fn depth(x: &SampleStruct2) -> usize {
+    x.w.iter().map(|x| depth(x) + 1).max().unwrap_or(0)
+}
+
+/// A subvalue provider whose subvalues are a deep `SampleStruct2`, to be inserted by crossover mutations,
+/// in a box and in a vector
+// the vector has the type of the field of `SampleStruct2`
+#[allow(clippy::vec_box)]
+struct DeepSubValueProvider {
+    deep: Vec<Box<SampleStruct2>>,
+}
+impl SubValueProvider for DeepSubValueProvider {
+    fn identifier(&self) -> SubValueProviderId {
+        SubValueProviderId {
+            idx: 1,
+            generation: Generation(0),
+        }
+    }
+    fn get_random_subvalue(&self, typeid: TypeId, _max_cplx: f64) -> Option<(&dyn Any, f64)> {
+        if typeid == TypeId::of::<Vec<Box<SampleStruct2>>>() {
+            Some((&self.deep, 1.0))
+        } else if typeid == TypeId::of::<Box<SampleStruct2>>() {
+            Some((&self.deep[0], 1.0))
+        } else if typeid == TypeId::of::<SampleStruct2>() {
+            Some((self.deep[0].as_ref(), 1.0))
+        } else {
+            None
+        }
+    }
+    fn get_subvalue(&self, typeid: TypeId, max_cplx: f64, index: &mut usize) -> Option<(&dyn Any, f64)> {
+        *index += 1;
+        if *index > 1 {
+            return None;
+        }
+        self.get_random_subvalue(typeid, max_cplx)
+    }
+}
+
+#[test]
+fn test_derived_struct_max_depth() {
+    let mutator = SampleStruct2::default_mutator().with_max_depth(2);
+    for _ in 0..1000 {
+        let (value, _) = mutator.random_arbitrary(1000.);
+        assert!(depth(&value) <= 2);
+    }
+    let shallow = SampleStruct2 {
+        w: vec![Box::new(SampleStruct2 { w: vec![] })],
+    };
+    let cache = mutator.validate_value(&shallow).unwrap();
+    assert_eq!(cache.depth(), 1);
+    let deep = SampleStruct2 {
+        w: vec![Box::new(SampleStruct2 {
+            w: vec![Box::new(shallow.clone())],
+        })],
+    };
+    assert!(mutator.validate_value(&deep).is_none());
+    assert!(!mutator.is_valid(&deep));
+
+    let mut value = shallow;
+    let mut cache = mutator.validate_value(&value).unwrap();
+    for _ in 0..1000 {
+        let (token, cplx) = mutator.random_mutate(&mut value, &mut cache, 1000.);
+        assert!(depth(&value) <= 2);
+        assert_eq!(cache.depth(), depth(&value));
+        let validated = mutator.validate_value(&value).unwrap();
+        assert_eq!(mutator.complexity(&value, &validated), cplx);
+        mutator.unmutate(&mut value, &mut cache, token);
+        assert_eq!(cache.depth(), 1);
+    }
+
+    // the deep subvalues of other test cases are not inserted by crossover mutations
+    let subvalue_provider = DeepSubValueProvider {
+        deep: vec![Box::new(SampleStruct2 {
+            w: vec![Box::new(SampleStruct2 {
+                w: vec![Box::new(SampleStruct2 { w: vec![] })],
+            })],
+        })],
+    };
+    let mut step = mutator.default_mutation_step(&value, &cache);
+    for _ in 0..1000 {
+        let Some((token, _)) = mutator.ordered_mutate(&mut value, &mut cache, &mut step, &subvalue_provider, 1000.)
+        else {
+            break;
+        };
+        assert!(depth(&value) <= 2);
+        assert_eq!(cache.depth(), depth(&value));
+        mutator.unmutate(&mut value, &mut cache, token);
+    }
+
+    let mutator = SampleStruct::<u8, u8>::default_mutator().with_max_depth(3);
+    test_mutator(mutator, 500., 500., false, true, 50, 100);
+}
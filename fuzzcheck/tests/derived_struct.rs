@@ -16,3 +16,61 @@ fn test_derived_struct() {
     let mutator = <Vec<SampleStruct<u8, u8>>>::default_mutator();
     test_mutator(mutator, 1000., 1000., false, true, 100, 100);
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+struct Row {
+    c0: u8,
+    c1: bool,
+    c2: u16,
+    c3: Option<u8>,
+    c4: u8,
+    c5: bool,
+    c6: u16,
+    c7: Option<u8>,
+    c8: u8,
+    c9: bool,
+    c10: u16,
+    c11: Option<u8>,
+    c12: u8,
+    c13: bool,
+    c14: u16,
+    c15: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+enum LargeVariant {
+    Row(
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+        u8,
+    ),
+    Empty,
+}
+
+#[test]
+fn test_derived_struct_with_many_fields() {
+    let mutator = Row::default_mutator();
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+    let mutator = LargeVariant::default_mutator();
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+    type Tuple12 = (u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8);
+    let mutator = Tuple12::default_mutator();
+    test_mutator::<Tuple12, _>(mutator, 1000., 1000., false, true, 100, 100);
+}
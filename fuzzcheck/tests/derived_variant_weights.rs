@@ -0,0 +1,31 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::{DefaultMutator, Mutator};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+enum Weighted {
+    #[variant_weight(9)]
+    Common(u8),
+    Rare {
+        x: bool,
+    },
+    #[ignore_variant]
+    Never(u16),
+}
+
+#[test]
+fn test_derived_variant_weights() {
+    let mutator = Weighted::default_mutator();
+    let mut nbr_common = 0;
+    for _ in 0..10_000 {
+        match mutator.random_arbitrary(100.0).0 {
+            Weighted::Common(_) => nbr_common += 1,
+            Weighted::Rare { .. } => {}
+            Weighted::Never(_) => panic!("an ignored variant was generated"),
+        }
+    }
+    // expected to be 9000
+    assert!((8500..9500).contains(&nbr_common), "{}", nbr_common);
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+}
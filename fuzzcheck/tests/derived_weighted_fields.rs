@@ -0,0 +1,37 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::{DefaultMutator, Mutator};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+struct WeightedStruct {
+    #[weight(4.0)]
+    x: Vec<u8>,
+    y: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+enum WeightedEnum {
+    A(#[weight(2)] Vec<u8>, bool),
+    B { x: u8 },
+}
+
+#[test]
+fn test_derived_weighted_fields() {
+    let mutator = WeightedStruct::default_mutator();
+    let value = WeightedStruct {
+        x: vec![1, 2],
+        y: vec![1, 2],
+    };
+    let cache = mutator.validate_value(&value).unwrap();
+    let cplx = mutator.complexity(&value, &cache);
+    let x_cplx = <Vec<u8>>::default_mutator().complexity(
+        &value.x,
+        &<Vec<u8>>::default_mutator().validate_value(&value.x).unwrap(),
+    );
+    assert!((cplx - 5. * x_cplx).abs() < 0.01, "{cplx} {x_cplx}");
+
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+    let mutator = WeightedEnum::default_mutator();
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+}
@@ -0,0 +1,28 @@
+use fuzzcheck::{Arguments, ReasonForStopping};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{events_pool, events_sensor, record_byte_events};
+
+fn sum(xs: &[u8]) -> u64 {
+    xs.iter().take(8).map(|&x| x as u64).sum()
+}
+
+fn fast_sum(xs: &[u8]) -> u64 {
+    record_byte_events(xs, 8);
+    xs.iter().take(8).fold(0u8, |sum, &x| sum.wrapping_add(x)) as u64
+}
+
+#[test]
+fn test_fuzz_differential() {
+    let result = fuzzcheck::fuzz_differential(sum, fast_sum, |a, b| a == b)
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(events_sensor(8), events_pool("events", 8))
+        .arguments(Arguments::for_property_test(100_000))
+        .launch();
+    assert!(matches!(
+        result.reason_for_stopping,
+        ReasonForStopping::TestFailure(xs) if sum(&xs) != fast_sum(&xs)
+    ));
+}
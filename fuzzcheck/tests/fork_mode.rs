@@ -0,0 +1,48 @@
+#![cfg(unix)]
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use fuzzcheck::sensors_and_pools::{DynamicValueSensor, SimplestToActivateCounterPool};
+use fuzzcheck::subprocess::anonymous_shared_counters;
+use fuzzcheck::{Arguments, ReasonForStopping};
+
+fn fuzz(counters: &'static [AtomicU64], test: impl Fn(&[u8]) + 'static) -> ReasonForStopping<Vec<u8>> {
+    let sensor = DynamicValueSensor::new(counters);
+    let pool = SimplestToActivateCounterPool::new("fork", counters.len());
+    fuzzcheck::fuzz_test(move |xs: &[u8]| test(xs))
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(sensor, pool)
+        .arguments(Arguments::for_property_test(20_000))
+        .fork(true)
+        .launch()
+        .reason_for_stopping
+}
+
+#[test]
+fn test_fork_mode_discards_global_state() {
+    static WAS_CALLED: AtomicBool = AtomicBool::new(false);
+    let counters = anonymous_shared_counters(256).unwrap();
+    let result = fuzz(counters, move |xs| {
+        assert!(!WAS_CALLED.swap(true, Ordering::Relaxed));
+        if let Some(&x) = xs.first() {
+            counters[x as usize].fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    assert!(matches!(result, ReasonForStopping::MaxIterationsReached));
+    assert!(!WAS_CALLED.load(Ordering::Relaxed));
+}
+
+#[test]
+fn test_fork_mode_reports_crashes() {
+    let counters = anonymous_shared_counters(256).unwrap();
+    let result = fuzz(counters, move |xs| {
+        if let Some(&x) = xs.first() {
+            counters[x as usize].fetch_add(1, Ordering::Relaxed);
+            if x == 42 {
+                std::process::abort();
+            }
+        }
+    });
+    assert!(matches!(result, ReasonForStopping::TestFailure(xs) if xs.first() == Some(&42)));
+}
@@ -2,11 +2,12 @@
 #![allow(unused_attributes)]
 #![feature(coverage_attribute)]
 
+use std::fmt::Display;
 use std::rc::{Rc, Weak};
 
 use fuzzcheck::mutators::grammar::*;
 use fuzzcheck::mutators::testing_utilities::test_mutator;
-// use fuzzcheck::{DefaultMutator, Mutator};
+use fuzzcheck::Mutator;
 
 #[coverage(off)]
 fn text() -> Rc<Grammar> {
@@ -182,3 +183,102 @@ fn test_grammar_based_ast_mutator() {
     let mutator = grammar_based_ast_mutator(markdown());
     test_mutator(mutator, 500., 500., false, true, 60, 100);
 }
+
+/// A sum of numbers, built from the strings matching the grammar returned by `sum`
+#[derive(Clone, PartialEq, Debug)]
+struct Sum {
+    first: u64,
+    terms: Vec<i64>,
+}
+
+impl Display for Sum {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.first)?;
+        for term in &self.terms {
+            if *term < 0 {
+                write!(f, "{term}")?;
+            } else {
+                write!(f, "+{term}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[coverage(off)]
+fn sum() -> Rc<Grammar> {
+    let number = action(
+        repetition(literal_range('0'..='9'), 1..=3),
+        #[coverage(off)]
+        |text, _| text.parse::<u64>().unwrap(),
+    );
+    let term = action(
+        concatenation([alternation([literal('+'), literal('-')]), number.clone()]),
+        #[coverage(off)]
+        |text, values| {
+            let number = *values.get::<u64>(0) as i64;
+            if text.starts_with('-') {
+                -number
+            } else {
+                number
+            }
+        },
+    );
+    action(
+        concatenation([number, repetition(term, 0..10)]),
+        #[coverage(off)]
+        |_, values| Sum {
+            first: *values.get::<u64>(0),
+            terms: values.iter::<i64>().copied().collect(),
+        },
+    )
+}
+
+#[coverage(off)]
+fn evaluate_sum(s: &str) -> i64 {
+    let mut result = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices().skip(1) {
+        if c == '+' || c == '-' {
+            result += s[start..i].parse::<i64>().unwrap();
+            start = i;
+        }
+    }
+    result + s[start..].parse::<i64>().unwrap()
+}
+
+#[test]
+fn test_grammar_based_typed_mutator() {
+    let mutator = grammar_based_typed_mutator::<Sum>(sum());
+    mutator.initialize();
+    for _ in 0..100 {
+        let (mut value, _) = mutator.random_arbitrary(100.);
+        let mut cache = mutator.validate_value(&value).unwrap();
+        let total = value.first as i64 + value.terms.iter().sum::<i64>();
+        assert_eq!(total, evaluate_sum(&value.to_string()));
+        for _ in 0..20 {
+            let (token, _) = mutator.random_mutate(&mut value, &mut cache, 100.);
+            assert!(mutator.validate_value(&value).is_some());
+            mutator.unmutate(&mut value, &mut cache, token);
+            assert!(mutator.validate_value(&value).is_some());
+        }
+    }
+    // the string of the value does not match the grammar
+    let too_large = Sum {
+        first: 1000,
+        terms: vec![],
+    };
+    assert!(mutator.validate_value(&too_large).is_none());
+
+    // the string of the value matches the grammar, but it builds a different value
+    let double = action(
+        literal_range('0'..='9'),
+        #[coverage(off)]
+        |text, _| text.parse::<u64>().unwrap() * 2,
+    );
+    let mutator = grammar_based_typed_mutator::<u64>(double);
+    mutator.initialize();
+    assert!(mutator.validate_value(&0).is_some());
+    assert!(mutator.validate_value(&4).is_none());
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+use fuzzcheck::{Arguments, DefaultMutator};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::TempFolder;
+
+const MAGIC: &str = "a string that is unlikely to be generated randomly";
+
+fn is_not_magic(s: &str) -> bool {
+    s != MAGIC
+}
+
+#[test]
+fn test_incoming_corpus() {
+    let folder = TempFolder::new("incoming_corpus");
+    let incoming = folder.join("incoming");
+    let corpus = folder.join("corpus");
+    std::fs::create_dir_all(&incoming).unwrap();
+    std::fs::write(incoming.join("magic.json"), serde_json::to_vec(MAGIC).unwrap()).unwrap();
+    // files that cannot be decoded are ignored
+    std::fs::write(incoming.join("truncated.json"), "\"a string").unwrap();
+
+    // the incoming corpus is first scanned one second after the fuzzer starts
+    let mut arguments = Arguments::for_property_test(usize::MAX);
+    arguments.maximum_duration = Duration::from_secs(30);
+    let failure = fuzzcheck::fuzz_test(is_not_magic)
+        .mutator(String::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(arguments)
+        .in_corpus(Some(&corpus))
+        .incoming_corpus(Some(&incoming))
+        .launch_property_test();
+    assert_eq!(failure.as_deref(), Some(MAGIC));
+
+    // the test case is moved to the input corpus, but the invalid file is left in place
+    assert!(corpus.join("magic.json").is_file());
+    assert!(!incoming.join("magic.json").exists());
+    assert!(incoming.join("truncated.json").is_file());
+}
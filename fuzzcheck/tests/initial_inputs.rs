@@ -0,0 +1,31 @@
+use fuzzcheck::mutators::integer_within_range::U64WithinRangeMutator;
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+use fuzzcheck::Arguments;
+
+const MAGIC: u64 = 999_983;
+
+fn is_not_magic(x: &u64) -> bool {
+    *x != MAGIC && *x < 1_000_000
+}
+
+fn find_counterexample(initial_inputs: Vec<u64>) -> Option<u64> {
+    fuzzcheck::fuzz_test(is_not_magic)
+        .mutator(U64WithinRangeMutator::new(0..1_000_000))
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(100))
+        .initial_inputs(initial_inputs)
+        .launch_property_test()
+}
+
+#[test]
+fn test_initial_inputs() {
+    // the magic value is unlikely to be generated randomly
+    assert_eq!(find_counterexample(vec![]), None);
+
+    // but it is tested before fuzzing starts if it is given as an initial input
+    assert_eq!(find_counterexample(vec![1, MAGIC, 2]), Some(MAGIC));
+
+    // the initial inputs that are rejected by the mutator are skipped
+    assert_eq!(find_counterexample(vec![2_000_000]), None);
+}
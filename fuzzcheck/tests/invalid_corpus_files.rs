@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+use fuzzcheck::{Arguments, DefaultMutator};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::TempFolder;
+
+const MAGIC: &str = "a string that is unlikely to be generated randomly";
+
+fn is_not_magic(s: &str) -> bool {
+    s != MAGIC
+}
+
+fn find_magic_string_in_corpus(corpus: &Path) -> Option<String> {
+    fuzzcheck::fuzz_test(is_not_magic)
+        .mutator(String::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(100))
+        .in_corpus(Some(corpus))
+        .launch_property_test()
+}
+
+#[test]
+fn test_invalid_corpus_files() {
+    let corpus = TempFolder::new("invalid_corpus_files");
+    std::fs::create_dir_all(&corpus).unwrap();
+
+    let magic = serde_json::to_vec(MAGIC).unwrap();
+    // a truncated file is skipped instead of stopping the fuzzer
+    std::fs::write(corpus.join("truncated.json"), &magic[..magic.len() / 2]).unwrap();
+    // and so is the temporary file left behind by a fuzzer killed while writing a test case
+    std::fs::write(corpus.join(".magic.json.tmp"), &magic).unwrap();
+    assert_eq!(find_magic_string_in_corpus(&corpus), None);
+
+    // the valid files of the corpus are still read
+    std::fs::write(corpus.join("magic.json"), &magic).unwrap();
+    assert_eq!(find_magic_string_in_corpus(&corpus).as_deref(), Some(MAGIC));
+}
@@ -0,0 +1,117 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+
+use fuzzcheck::legacy::{LegacyMutator, LegacyMutatorAdapter, LegacySerializer, LegacySerializerAdapter};
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::{Mutator, Serializer};
+
+/// A legacy mutator for the integers between 0 and `max`
+struct LegacyU8Mutator {
+    max: u8,
+}
+
+impl LegacyMutator<u8> for LegacyU8Mutator {
+    type Cache = ();
+    type MutationStep = u8;
+    type ArbitraryStep = u8;
+    type UnmutateToken = u8;
+
+    #[coverage(off)]
+    fn default_arbitrary_step(&self) -> u8 {
+        0
+    }
+    #[coverage(off)]
+    fn validate_value(&self, value: &u8) -> Option<((), u8)> {
+        if *value <= self.max {
+            Some(((), 0))
+        } else {
+            None
+        }
+    }
+    #[coverage(off)]
+    fn max_complexity(&self) -> f64 {
+        8.0
+    }
+    #[coverage(off)]
+    fn min_complexity(&self) -> f64 {
+        8.0
+    }
+    #[coverage(off)]
+    fn complexity(&self, _value: &u8, _cache: &()) -> f64 {
+        8.0
+    }
+    #[coverage(off)]
+    fn ordered_arbitrary(&self, step: &mut u8, _max_cplx: f64) -> Option<(u8, f64)> {
+        if *step > self.max {
+            None
+        } else {
+            *step += 1;
+            Some((*step - 1, 8.0))
+        }
+    }
+    #[coverage(off)]
+    fn random_arbitrary(&self, _max_cplx: f64) -> (u8, f64) {
+        (fuzzcheck::fastrand::u8(..=self.max), 8.0)
+    }
+    #[coverage(off)]
+    fn ordered_mutate(&self, value: &mut u8, _cache: &mut (), step: &mut u8, _max_cplx: f64) -> Option<(u8, f64)> {
+        // try every other value
+        while *step <= self.max {
+            let new_value = *step;
+            *step += 1;
+            if new_value != *value {
+                let old_value = std::mem::replace(value, new_value);
+                return Some((old_value, 8.0));
+            }
+        }
+        None
+    }
+    #[coverage(off)]
+    fn random_mutate(&self, value: &mut u8, _cache: &mut (), _max_cplx: f64) -> (u8, f64) {
+        let old_value = std::mem::replace(value, fuzzcheck::fastrand::u8(..=self.max));
+        (old_value, 8.0)
+    }
+    #[coverage(off)]
+    fn unmutate(&self, value: &mut u8, _cache: &mut (), t: u8) {
+        *value = t;
+    }
+}
+
+struct LegacyStringSerializer;
+
+impl LegacySerializer for LegacyStringSerializer {
+    type Value = String;
+
+    #[coverage(off)]
+    fn is_utf8(&self) -> bool {
+        true
+    }
+    #[coverage(off)]
+    fn extension(&self) -> &str {
+        "txt"
+    }
+    #[coverage(off)]
+    fn from_data(&self, data: &[u8]) -> Option<String> {
+        String::from_utf8(data.to_vec()).ok()
+    }
+    #[coverage(off)]
+    fn to_data(&self, value: &String) -> Vec<u8> {
+        value.as_bytes().to_vec()
+    }
+}
+
+#[test]
+fn test_legacy_mutator_adapter() {
+    let mutator = LegacyMutatorAdapter::new(LegacyU8Mutator { max: 20 });
+    assert!(mutator.is_valid(&20));
+    assert!(!mutator.is_valid(&21));
+    test_mutator(mutator, 8.0, 8.0, false, true, 21, 20);
+}
+
+#[test]
+fn test_legacy_serializer_adapter() {
+    let serializer = LegacySerializerAdapter::new(LegacyStringSerializer);
+    assert_eq!(serializer.extension(), "txt");
+    let data = serializer.to_data(&"hello".to_owned());
+    assert_eq!(serializer.from_data(&data).as_deref(), Some("hello"));
+}
@@ -1,16 +1,54 @@
 #![feature(coverage_attribute)]
 // #![feature(trivial_bounds)]
+mod adaptive_mutations;
 mod alternation_char_mutators;
+mod async_test_function;
 mod char_mutators;
 mod const_generics;
 mod constrained_integer;
+mod corpus_extensions;
+mod corpus_naming;
+mod corpus_tags;
+mod dedup_inputs;
+mod derived_enum_discriminants;
+mod derived_frozen_fields;
+mod derived_generic_bounds;
+#[cfg(feature = "regex_grammar")]
+mod derived_grammar_field;
 mod derived_mutually_recursive_structs;
+mod derived_opaque_fields;
+mod derived_range_fields;
 mod derived_recursive_struct;
 mod derived_recursive_struct_fully_custom;
 mod derived_struct;
+mod derived_variant_weights;
+mod derived_weighted_fields;
+mod differential;
 mod enum_with_ignored_variant;
 mod expansions;
+mod fork_mode;
 #[cfg(feature = "regex_grammar")]
 mod grammar_based_mutators;
+mod incoming_corpus;
+mod initial_inputs;
+mod invalid_corpus_files;
+mod legacy_adapters;
+mod make_mutator_remote;
+mod merge_corpora;
+mod observation_sampling;
 mod option;
+mod output_diversity;
+mod pool_subfolders;
+mod preserve_failure;
+mod property_test;
+mod provenance;
+mod random_generation;
+mod recursive_group;
+mod repool;
+mod resume;
+mod step_api;
+mod stop_after_no_progress;
+mod subprocess;
+mod test_function_kinds;
+mod test_hooks;
 mod vector;
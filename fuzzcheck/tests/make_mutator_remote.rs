@@ -0,0 +1,90 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+
+use fuzzcheck::mutators::integer::U8Mutator;
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::{make_mutator, Mutator};
+
+/// Stands in for a crate whose types do not implement `DefaultMutator`
+mod other {
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Point {
+        pub x: u8,
+        pub y: u8,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Pair<T>(pub T, pub bool);
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[non_exhaustive]
+    pub enum Shape<T> {
+        Empty,
+        Dot(Point),
+        Line { from: Point, to: T },
+    }
+}
+
+make_mutator! {
+    name: PointMutator,
+    remote: other::Point,
+    default: true,
+    type:
+        struct PointShape {
+            #[range(0..=10)]
+            x: u8,
+            y: u8,
+        }
+}
+
+make_mutator! {
+    name: PairMutator,
+    remote: other::Pair,
+    default: true,
+    type:
+        struct PairShape<T>(T, bool);
+}
+
+make_mutator! {
+    name: ShapeMutator,
+    remote: other::Shape,
+    default: true,
+    type:
+        enum ShapeKind<T> {
+            Empty,
+            Dot(#[field_mutator(PointMutator<U8Mutator>)] other::Point),
+            Line {
+                #[field_mutator(PointMutator<U8Mutator>)]
+                from: other::Point,
+                to: T,
+            },
+        }
+}
+
+#[test]
+fn test_make_mutator_remote_struct() {
+    let mutator = PointMutator::default();
+    for _ in 0..1000 {
+        let (value, _) = mutator.random_arbitrary(100.0);
+        assert!(value.x <= 10);
+    }
+    assert!(!mutator.is_valid(&other::Point { x: 11, y: 0 }));
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+
+    let mutator = PairMutator::<u16, _, _>::default();
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+}
+
+#[test]
+fn test_make_mutator_remote_enum() {
+    let mutator = ShapeMutator::<u8, _>::default();
+    let mut found_line = false;
+    for _ in 0..1000 {
+        if let (other::Shape::Line { from, .. }, _) = mutator.random_arbitrary(100.0) {
+            assert!(from.x <= 10);
+            found_line = true;
+        }
+    }
+    assert!(found_line);
+    test_mutator(mutator, 1000., 1000., false, true, 100, 100);
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use fuzzcheck::sensors_and_pools::{DynamicValueSensor, SimplestToActivateCounterPool};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::TempFolder;
+
+static COUNTERS: [AtomicU64; 256] = [const { AtomicU64::new(0) }; 256];
+
+fn test(xs: &[u8]) {
+    for &x in xs {
+        COUNTERS[x as usize].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The fuzz test launched with the `merge` command by `test_merge_corpora`, which does nothing otherwise
+#[test]
+fn merge_corpora_child() {
+    if std::env::var("FUZZCHECK_ARGS").is_err() {
+        return;
+    }
+    let sensor = DynamicValueSensor::new(&COUNTERS);
+    let pool = SimplestToActivateCounterPool::new("merged", COUNTERS.len());
+    fuzzcheck::fuzz_test(test)
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(sensor, pool)
+        .arguments_from_cargo_fuzzcheck()
+        .launch();
+}
+
+/// The test cases of the corpus, sorted
+fn read_corpus(corpus: &Path) -> Vec<Vec<u8>> {
+    let mut values = vec![];
+    for entry in std::fs::read_dir(corpus.join("merged")).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|extension| extension == "json") {
+            values.push(serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap());
+        }
+    }
+    values.sort();
+    values
+}
+
+#[test]
+fn test_merge_corpora() {
+    let folder = TempFolder::new("merge_corpora");
+    let corpora = [folder.join("a"), folder.join("b")];
+    let out_corpus = folder.join("out");
+    let values: [&[&[u8]]; 2] = [&[&[1], &[1, 2]], &[&[2], &[2, 3], &[1]]];
+    for (corpus, values) in corpora.iter().zip(values) {
+        std::fs::create_dir_all(corpus).unwrap();
+        for (i, value) in values.iter().enumerate() {
+            std::fs::write(corpus.join(format!("{i}.json")), serde_json::to_vec(value).unwrap()).unwrap();
+        }
+    }
+
+    let args = format!(
+        "--command merge --merge-corpus {} --merge-corpus {} --out-corpus {} --no-in-corpus --no-artifacts --no-stats",
+        corpora[0].display(),
+        corpora[1].display(),
+        out_corpus.display()
+    );
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["merge_corpora_child", "--test-threads=1"])
+        .env("FUZZCHECK_ARGS", &args)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    // the duplicated test case and those activating no new counter are not kept
+    assert_eq!(read_corpus(&out_corpus), vec![vec![1], vec![2], vec![2, 3]]);
+
+    // the output corpus must be empty
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["merge_corpora_child", "--test-threads=1"])
+        .env("FUZZCHECK_ARGS", &args)
+        .status()
+        .unwrap();
+    assert!(!status.success());
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use fuzzcheck::sensors_and_pools::MaximiseObservationPool;
+use fuzzcheck::{Arguments, DefaultMutator, SaveToStatsFolder, Sensor};
+
+static LENGTH: AtomicU64 = AtomicU64::new(0);
+
+/// Observes the length of the tested vector, up to 16
+struct LengthSensor;
+impl SaveToStatsFolder for LengthSensor {
+    fn save_to_stats_folder(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        vec![]
+    }
+}
+impl Sensor for LengthSensor {
+    type Observations = u64;
+
+    fn start_recording(&mut self) {
+        LENGTH.store(0, Ordering::SeqCst);
+    }
+    fn stop_recording(&mut self) {}
+    fn get_observations(&mut self) -> u64 {
+        LENGTH.load(Ordering::SeqCst)
+    }
+    fn hash_observations(&self, observations: &u64) -> Option<u64> {
+        Some(*observations)
+    }
+}
+
+fn test(xs: &[u8]) -> bool {
+    LENGTH.store(xs.len().min(16) as u64, Ordering::SeqCst);
+    true
+}
+
+#[test]
+fn test_observation_sampling() {
+    let mut fuzzer = fuzzcheck::fuzz_test(test)
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(LengthSensor, MaximiseObservationPool::<u64>::new("length"))
+        .arguments(Arguments::for_property_test(20_000))
+        .observation_sampling(8)
+        .build();
+    while fuzzer.step().is_ok() {}
+
+    let skipped_observations = fuzzer.stats().skipped_observations.unwrap();
+    assert!(skipped_observations > 0);
+    // the new observations are never skipped
+    assert!(fuzzer.corpus().any(|xs| xs.len() >= 16));
+    drop(fuzzer);
+
+    // without sampling, the observations that were already seen with a lower complexity are still skipped
+    let mut fuzzer = fuzzcheck::fuzz_test(test)
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(LengthSensor, MaximiseObservationPool::<u64>::new("length"))
+        .arguments(Arguments::for_property_test(20_000))
+        .build();
+    while fuzzer.step().is_ok() {}
+    let skipped_observations_without_sampling = fuzzer.stats().skipped_observations.unwrap();
+    assert!(skipped_observations_without_sampling > 0);
+    assert!(fuzzer.corpus().any(|xs| xs.len() >= 16));
+    drop(fuzzer);
+
+    // the default sensor, which maps the observations of the code coverage sensor, hashes its observations too
+    #[cfg(fuzzing)]
+    {
+        let mut fuzzer = fuzzcheck::fuzz_test(test)
+            .mutator(Vec::<u8>::default_mutator())
+            .serde_serializer()
+            .default_sensor_and_pool()
+            .arguments(Arguments::for_property_test(5_000))
+            .build();
+        while fuzzer.step().is_ok() {}
+        assert!(fuzzer.stats().skipped_observations.unwrap() > 0);
+    }
+}
@@ -1,7 +1,10 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
 use std::any::TypeId;
 
 use fuzzcheck::mutators::integer::U8Mutator;
 use fuzzcheck::mutators::option::OptionMutator;
+use fuzzcheck::mutators::result::ResultMutator;
 use fuzzcheck::subvalue_provider::{CrossoverSubValueProvider, Generation, SubValueProviderId};
 use fuzzcheck::{DefaultMutator, Mutator, SubValueProvider};
 
@@ -60,3 +63,39 @@ fn test_option() {
     let m = OptionMutator::new(U8Mutator::default());
     fuzzcheck::mutators::testing_utilities::test_mutator(m, 100.0, 100.0, false, true, 500, 500);
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DefaultMutator)]
+struct RarelyNone {
+    #[field_mutator(OptionMutator<u8, U8Mutator> = { OptionMutator::with_none_probability(U8Mutator::default(), 0.1) })]
+    x: Option<u8>,
+}
+
+#[test]
+fn test_option_none_probability() {
+    let m = OptionMutator::with_none_probability(U8Mutator::default(), 0.1);
+    m.initialize();
+    let nbr_none = (0..10_000).filter(|_| m.random_arbitrary(100.0).0.is_none()).count();
+    // expected to be 1000
+    assert!((700..1300).contains(&nbr_none), "{}", nbr_none);
+    fuzzcheck::mutators::testing_utilities::test_mutator(m, 100.0, 100.0, false, true, 500, 500);
+
+    let m = OptionMutator::new(U8Mutator::default());
+    m.initialize();
+    m.set_none_probability(0.0);
+    assert!((0..1000).all(|_| m.random_arbitrary(100.0).0.is_some()));
+
+    let m = RarelyNone::default_mutator();
+    m.initialize();
+    let nbr_none = (0..10_000).filter(|_| m.random_arbitrary(100.0).0.x.is_none()).count();
+    assert!((700..1300).contains(&nbr_none), "{}", nbr_none);
+}
+
+#[test]
+fn test_result_err_probability() {
+    let m = ResultMutator::<u8, (), _, _>::with_err_probability(U8Mutator::default(), <()>::default_mutator(), 0.9);
+    m.initialize();
+    let nbr_err = (0..10_000).filter(|_| m.random_arbitrary(100.0).0.is_err()).count();
+    // expected to be 9000
+    assert!((8500..9500).contains(&nbr_err), "{}", nbr_err);
+    fuzzcheck::mutators::testing_utilities::test_mutator(m, 100.0, 100.0, false, true, 500, 500);
+}
@@ -0,0 +1,36 @@
+use fuzzcheck::builder::TestFunctionExt;
+use fuzzcheck::sensors_and_pools::{OutputDiversityPool, OutputSensor};
+use fuzzcheck::Arguments;
+
+#[path = "common/mod.rs"]
+mod common;
+use common::TempFolder;
+
+/// An encoder with 17 different outputs
+fn encode(xs: &[u8]) -> Option<u8> {
+    xs.first().map(|x| x % 16)
+}
+
+#[test]
+fn test_output_diversity_pool() {
+    let folder = TempFolder::new("output_diversity");
+    let mut arguments = Arguments::for_property_test(20_000);
+    arguments.corpus_out = Some(folder.join("corpus"));
+    let _ = fuzzcheck::fuzz_test(encode.observe_output())
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(OutputSensor::default(), OutputDiversityPool::new("outputs"))
+        .arguments(arguments)
+        .launch();
+
+    // one test case is kept for each output
+    let corpus: Vec<Vec<u8>> = std::fs::read_dir(folder.join("corpus/outputs"))
+        .unwrap()
+        .map(|entry| serde_json::from_slice(&std::fs::read(entry.unwrap().path()).unwrap()).unwrap())
+        .collect();
+    assert_eq!(corpus.len(), 17);
+    let mut outputs = corpus.iter().map(|xs| encode(xs)).collect::<Vec<_>>();
+    outputs.sort();
+    outputs.dedup();
+    assert_eq!(outputs.len(), 17);
+}
@@ -0,0 +1,68 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use fuzzcheck::sensors_and_pools::SameObservations;
+use fuzzcheck::{Arguments, PoolExt};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{events_pool, events_sensor, latest_session, record_byte_events, TempFolder};
+
+fn test_function(xs: &[u8]) -> bool {
+    record_byte_events(xs, 2);
+    true
+}
+
+fn fuzz(folder: &Path, name_1: &str, name_2: &str) {
+    let mut arguments = Arguments::for_property_test(1000);
+    arguments.corpus_out = Some(folder.join("corpus"));
+    arguments.stats_folder = Some(folder.join("stats"));
+    let pool = events_pool(name_1, 2).and(events_pool(name_2, 2), None, SameObservations);
+    let _ = fuzzcheck::fuzz_test(test_function)
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(events_sensor(2), pool)
+        .arguments(arguments)
+        .launch();
+}
+
+/// The names of the files of each subfolder of the corpus, without their extension
+fn corpus_content(corpus: &Path) -> BTreeMap<String, BTreeSet<String>> {
+    std::fs::read_dir(corpus)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let names = std::fs::read_dir(&path)
+                .unwrap()
+                .map(|entry| entry.unwrap().path().file_stem().unwrap().to_str().unwrap().to_owned())
+                .collect();
+            (path.file_name().unwrap().to_str().unwrap().to_owned(), names)
+        })
+        .collect()
+}
+
+/// The content of the corpus, as saved in the stats folder
+fn saved_corpus_content(stats: &Path) -> BTreeMap<String, BTreeSet<String>> {
+    serde_json::from_slice(&std::fs::read(latest_session(stats).join("corpus.json")).unwrap()).unwrap()
+}
+
+#[test]
+fn test_pool_subfolders() {
+    let folder = TempFolder::new("pool_subfolders");
+
+    // each pool saves its test cases in its own subfolder
+    fuzz(&folder.join("different_names"), "first", "second");
+    let content = corpus_content(&folder.join("different_names/corpus"));
+    assert_eq!(
+        content.keys().map(String::as_str).collect::<Vec<_>>(),
+        ["first", "second"]
+    );
+    assert!(content.values().all(|names| !names.is_empty()));
+    assert_eq!(saved_corpus_content(&folder.join("different_names/stats")), content);
+
+    // pools with the same name share a subfolder
+    fuzz(&folder.join("same_names"), "shared", "shared");
+    let content = corpus_content(&folder.join("same_names/corpus"));
+    assert_eq!(content.keys().map(String::as_str).collect::<Vec<_>>(), ["shared"]);
+    assert_eq!(saved_corpus_content(&folder.join("same_names/stats")), content);
+}
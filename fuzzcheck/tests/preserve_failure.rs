@@ -0,0 +1,79 @@
+use std::path::Path;
+use std::process::Command;
+
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::TempFolder;
+
+/// Panics at a different location for the non-empty test cases of even and odd lengths
+fn test(xs: &[u8]) {
+    if xs.is_empty() {
+        return;
+    }
+    if xs.len().is_multiple_of(2) {
+        panic!("the length of the test case is even");
+    } else {
+        panic!("the length of the test case is odd");
+    }
+}
+
+/// The fuzz test launched with the `minify` command by `test_preserve_failure`, which does nothing otherwise
+#[test]
+fn preserve_failure_child() {
+    if std::env::var("FUZZCHECK_ARGS").is_err() {
+        return;
+    }
+    fuzzcheck::fuzz_test(test)
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments_from_cargo_fuzzcheck()
+        .launch();
+}
+
+/// Minify the test case of the input file and return the artifacts that were saved
+fn minify(input_file: &Path, artifacts: &Path, preserve_failure: bool) -> Vec<Vec<u8>> {
+    let _ = std::fs::remove_dir_all(artifacts);
+    let mut args = format!(
+        "--command minify --input-file {} --artifacts {} --stop-after-iterations 5000 --stop-after-first-failure --no-in-corpus --no-out-corpus --no-stats",
+        input_file.display(),
+        artifacts.display()
+    );
+    if preserve_failure {
+        args.push_str(" --preserve-failure");
+    }
+    Command::new(std::env::current_exe().unwrap())
+        .args(["preserve_failure_child", "--test-threads=1"])
+        .env("FUZZCHECK_ARGS", &args)
+        .status()
+        .unwrap();
+    let mut values = vec![];
+    for entry in std::fs::read_dir(artifacts).into_iter().flatten() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|extension| extension == "json") {
+            values.push(serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap());
+        }
+    }
+    values
+}
+
+#[test]
+fn test_preserve_failure() {
+    let folder = TempFolder::new("preserve_failure");
+    std::fs::create_dir_all(&folder).unwrap();
+    let input_file = folder.join("input.json");
+    std::fs::write(&input_file, serde_json::to_vec(&[7u8; 8]).unwrap()).unwrap();
+    let artifacts = folder.join("artifacts");
+    let has_even_len = |xs: &Vec<u8>| xs.len().is_multiple_of(2);
+
+    // a simpler test case panicking at a different location is kept
+    let values = minify(&input_file, &artifacts, false);
+    assert!(values.iter().any(|xs| !has_even_len(xs)));
+
+    // it is rejected when the failure is preserved
+    let values = minify(&input_file, &artifacts, true);
+    assert!(!values.is_empty());
+    assert!(values.iter().all(has_even_len));
+}
@@ -0,0 +1,28 @@
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+use fuzzcheck::{Arguments, DefaultMutator};
+
+fn short_vectors_only(xs: &[u8]) -> bool {
+    xs.len() < 4
+}
+
+#[test]
+fn test_property_test() {
+    let counterexample = fuzzcheck::fuzz_test(short_vectors_only)
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(10_000))
+        .launch_property_test();
+
+    let counterexample = counterexample.expect("the property test should have found a counterexample");
+    assert_eq!(counterexample.len(), 4);
+
+    let counterexample = fuzzcheck::fuzz_test(|_: &bool| true)
+        .mutator(bool::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(1_000))
+        .launch_property_test();
+
+    assert!(counterexample.is_none());
+}
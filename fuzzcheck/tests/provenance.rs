@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use fuzzcheck::Arguments;
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{fuzz, latest_session, record_byte_events, TempFolder};
+
+fn test_function(xs: &[u8]) -> bool {
+    record_byte_events(xs, 8);
+    true
+}
+
+fn fuzz_with_provenance(folder: &Path, track_provenance: bool) {
+    let mut arguments = Arguments::for_property_test(2000);
+    arguments.stats_folder = Some(folder.join("stats"));
+    arguments.track_provenance = track_provenance;
+    let _ = fuzz(test_function, 8, arguments);
+}
+
+/// The content of the provenance file saved by the most recent fuzzing session, if any
+fn saved_provenance(folder: &Path) -> Option<String> {
+    std::fs::read_to_string(latest_session(&folder.join("stats")).join("provenance.csv")).ok()
+}
+
+#[test]
+fn test_provenance() {
+    let folder = TempFolder::new("provenance");
+
+    fuzz_with_provenance(&folder, false);
+    assert!(saved_provenance(&folder).is_none());
+    std::fs::remove_dir_all(&folder).unwrap();
+
+    fuzz_with_provenance(&folder, true);
+    let provenance = saved_provenance(&folder).unwrap();
+    let records = provenance
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').map(|field| field.trim_matches('"')).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert!(!records.is_empty());
+
+    for record in records.iter() {
+        let (parent, derivation, generation) = (record[1], record[2], record[3].parse::<usize>().unwrap());
+        match derivation {
+            "arbitrary" => {
+                assert!(parent.is_empty());
+                assert_eq!(generation, 0);
+            }
+            "mutation" | "crossover" => {
+                // the parent was added to the corpus before its children
+                assert!(records
+                    .iter()
+                    .any(|r| r[0] == parent && r[3].parse::<usize>().unwrap() + 1 == generation));
+            }
+            _ => panic!("unexpected derivation {derivation}"),
+        }
+    }
+    // the fuzzer cannot find new events without mutating the test cases of the pool
+    assert!(records.iter().any(|record| record[2] == "mutation"));
+}
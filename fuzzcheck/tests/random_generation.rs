@@ -0,0 +1,28 @@
+use fuzzcheck::DefaultMutator;
+
+#[test]
+fn test_random_generation_only() {
+    let failure = fuzzcheck::fuzz_test(|xs: &[u8]| xs.len() < 4)
+        .default_mutator()
+        .random_generation_only(1_000)
+        .launch();
+    assert!(
+        failure
+            .expect("a vector of length 4 or more should have been generated")
+            .len()
+            >= 4
+    );
+
+    let failure = fuzzcheck::fuzz_test(|x: &u8| assert!(*x != 7))
+        .mutator(u8::default_mutator())
+        .random_generation_only(10_000)
+        .launch();
+    assert_eq!(failure, Some(7));
+
+    let failure = fuzzcheck::fuzz_test(|_: &Option<bool>| true)
+        .default_mutator()
+        .random_generation_only(100)
+        .maximum_complexity(2.0)
+        .launch();
+    assert!(failure.is_none());
+}
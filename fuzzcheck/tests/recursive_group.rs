@@ -0,0 +1,73 @@
+#![allow(unused_attributes)]
+#![feature(coverage_attribute)]
+
+use fuzzcheck::mutators::option::OptionMutator;
+use fuzzcheck::mutators::recursive::RecurToMutator;
+use fuzzcheck::mutators::testing_utilities::test_mutator;
+use fuzzcheck::mutators::vector::VecMutator;
+use fuzzcheck::{make_mutator, recursive_group, DefaultMutator, Mutator};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct GroupA {
+    b: Vec<GroupB>,
+    data: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct GroupB {
+    a: Option<GroupA>,
+    data: bool,
+}
+
+make_mutator! {
+    name: GroupAMutator,
+    recursive: true,
+    default: false,
+    type:
+        struct GroupA {
+            #[field_mutator(VecMutator<GroupB, RecurToMutator<GroupMutators>>)]
+            b: Vec<GroupB>,
+            data: Vec<u64>,
+        }
+}
+
+make_mutator! {
+    name: GroupBMutator,
+    recursive: true,
+    default: false,
+    type:
+        struct GroupB {
+            #[field_mutator(OptionMutator<GroupA, RecurToMutator<GroupMutators>>)]
+            a: Option<GroupA>,
+            data: bool,
+        }
+}
+
+recursive_group! {
+    struct GroupMutators {
+        a: GroupA => GroupAMutator<<Vec<u64> as DefaultMutator>::Mutator> = |group| {
+            GroupAMutator::new(VecMutator::new(group.into(), 0..=usize::MAX), <Vec<u64>>::default_mutator())
+        },
+        b: GroupB => GroupBMutator<<bool as DefaultMutator>::Mutator> = |group| {
+            GroupBMutator::new(OptionMutator::new(group.into()), bool::default_mutator())
+        },
+    }
+}
+
+#[test]
+fn test_recursive_group() {
+    let value = GroupB {
+        a: Some(GroupA {
+            b: vec![GroupB { a: None, data: true }],
+            data: vec![1, 2],
+        }),
+        data: false,
+    };
+    let mutator = GroupB::default_mutator();
+    assert!(mutator.validate_value(&value).is_some());
+    test_mutator::<GroupB, _>(mutator, 1000., 1000., false, true, 50, 50);
+
+    let mutator = GroupA::default_mutator();
+    assert!(mutator.validate_value(value.a.as_ref().unwrap()).is_some());
+    test_mutator::<GroupA, _>(mutator, 1000., 1000., false, true, 50, 50);
+}
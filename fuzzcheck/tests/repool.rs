@@ -0,0 +1,84 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use fuzzcheck::sensors_and_pools::{DynamicValueSensor, SimplestToActivateCounterPool};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{latest_session, TempFolder};
+
+static COUNTERS: [AtomicU64; 256] = [const { AtomicU64::new(0) }; 256];
+
+fn test(xs: &[u8]) {
+    for &x in xs {
+        COUNTERS[x as usize].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The fuzz test launched by `test_repool`, which does nothing otherwise
+#[test]
+fn repool_child() {
+    if std::env::var("FUZZCHECK_ARGS").is_err() {
+        return;
+    }
+    // the sensor may observe fewer counters than the one that recorded the observations
+    let nbr_counters = std::env::var("REPOOL_NBR_COUNTERS").map_or(COUNTERS.len(), |n| n.parse().unwrap());
+    let sensor = DynamicValueSensor::new(&COUNTERS[..nbr_counters]);
+    let pool = SimplestToActivateCounterPool::new("counters", nbr_counters);
+    fuzzcheck::fuzz_test(test)
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(sensor, pool)
+        .arguments_from_cargo_fuzzcheck()
+        .launch();
+}
+
+/// The content of the file with the given name in the most recent fuzzing session of the stats folder
+fn read_latest_session(stats: &Path, file_name: &str) -> Option<Vec<u8>> {
+    std::fs::read(latest_session(stats).join(file_name)).ok()
+}
+
+#[test]
+fn test_repool() {
+    let stats = TempFolder::new("repool");
+    let run = |args: &str, nbr_counters: Option<usize>| {
+        let mut command = Command::new(std::env::current_exe().unwrap());
+        command
+            .args(["repool_child", "--test-threads=1"])
+            .env(
+                "FUZZCHECK_ARGS",
+                format!(
+                    "{args} --stats {} --no-in-corpus --no-out-corpus --no-artifacts",
+                    stats.display()
+                ),
+            )
+            .env_remove("REPOOL_NBR_COUNTERS");
+        if let Some(nbr_counters) = nbr_counters {
+            command.env("REPOOL_NBR_COUNTERS", nbr_counters.to_string());
+        }
+        let status = command.status().unwrap();
+        // the stats of each run are saved to a different subfolder, named after the time at which it started
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        status
+    };
+
+    assert!(run(
+        "--command fuzz --record-observations --stop-after-iterations 1000",
+        None
+    )
+    .success());
+    let record = read_latest_session(&stats, "observations.bin").unwrap();
+    assert!(!record.is_empty());
+
+    assert!(run("--command repool", None).success());
+    let csv = String::from_utf8(read_latest_session(&stats, "repool.csv").unwrap()).unwrap();
+    let rows = csv.lines().collect::<Vec<_>>();
+    assert!(rows[0].starts_with("\"iteration\","));
+    // the observations of the test cases that activated new counters were replayed into the pool
+    assert!(rows.len() > 1);
+
+    // the observations cannot be replayed by a sensor with fewer counters, since the test cases
+    // activated counters beyond them
+    assert!(!run("--command repool", Some(16)).success());
+}
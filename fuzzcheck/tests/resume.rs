@@ -0,0 +1,53 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use fuzzcheck::Arguments;
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{fuzz, latest_session, record_byte_events, TempFolder};
+
+static NBR_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+fn test_function(xs: &[u8]) -> bool {
+    NBR_RUNS.fetch_add(1, Ordering::Relaxed);
+    record_byte_events(xs, 8);
+    true
+}
+
+/// Run a fuzzing session and return the number of times the test function was run
+fn fuzz_and_count_runs(folder: &Path, maximum_iterations: usize, resume: bool) -> usize {
+    NBR_RUNS.store(0, Ordering::Relaxed);
+    let mut arguments = Arguments::for_property_test(maximum_iterations);
+    arguments.corpus_in = Some(folder.join("corpus"));
+    arguments.corpus_out = Some(folder.join("corpus"));
+    arguments.stats_folder = Some(folder.join("stats"));
+    arguments.resume = resume;
+    let _ = fuzz(test_function, 8, arguments);
+    NBR_RUNS.load(Ordering::Relaxed)
+}
+
+/// The total number of iterations saved by the most recent fuzzing session
+fn saved_number_of_runs(folder: &Path) -> usize {
+    let latest_session = latest_session(&folder.join("stats"));
+    let resume_state: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(latest_session.join("resume.json")).unwrap()).unwrap();
+    resume_state["total_number_of_runs"].as_u64().unwrap() as usize
+}
+
+#[test]
+fn test_resume() {
+    let folder = TempFolder::new("resume");
+
+    assert!(fuzz_and_count_runs(&folder, 2000, false) >= 2000);
+    assert_eq!(saved_number_of_runs(&folder), 2000);
+
+    // the resumed session continues from the number of iterations of the previous one,
+    // so the test function is run fewer than 2500 times, including when the corpus is read again
+    let nbr_runs = fuzz_and_count_runs(&folder, 2500, true);
+    assert!(nbr_runs < 2500, "{}", nbr_runs);
+    assert!(saved_number_of_runs(&folder) >= 2500);
+
+    // without --resume, a new session is started
+    assert!(fuzz_and_count_runs(&folder, 2500, false) >= 2500);
+}
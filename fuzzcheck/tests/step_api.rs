@@ -0,0 +1,45 @@
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+use fuzzcheck::{Arguments, DefaultMutator, ReasonForStopping};
+
+#[test]
+fn test_step_api() {
+    let mut fuzzer = fuzzcheck::fuzz_test(|_: &Vec<u8>| true)
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(1_000))
+        .build();
+
+    let mut nbr_steps = 0;
+    let mut corpus_size = 0;
+    let reason_for_stopping = loop {
+        match fuzzer.step() {
+            Ok(step) => {
+                nbr_steps += 1;
+                corpus_size = corpus_size + step.nbr_added - step.nbr_removed;
+                assert_eq!(fuzzer.corpus().count(), corpus_size);
+            }
+            Err(reason) => break reason,
+        }
+    };
+    assert!(matches!(reason_for_stopping, ReasonForStopping::MaxIterationsReached));
+    assert_eq!(fuzzer.stats().total_number_of_runs, 1_000);
+    assert!(nbr_steps > 1);
+    drop(fuzzer);
+
+    let mut fuzzer = fuzzcheck::fuzz_test(|xs: &Vec<u8>| xs.len() < 4)
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(100_000))
+        .build();
+    let reason_for_stopping = loop {
+        if let Err(reason) = fuzzer.step() {
+            break reason;
+        }
+    };
+    match reason_for_stopping {
+        ReasonForStopping::TestFailure(xs) => assert!(xs.len() >= 4),
+        _ => panic!("the fuzzer should have found a test failure"),
+    }
+}
@@ -0,0 +1,16 @@
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+use fuzzcheck::{Arguments, DefaultMutator, NoProgressBudget, ReasonForStopping};
+
+#[test]
+fn test_stop_after_no_progress() {
+    // the pool never finds any new observation after the first test case, so the fuzzer stops
+    // long before the maximum number of iterations
+    let result = fuzzcheck::fuzz_test(|_: &Vec<u8>| true)
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(1_000_000))
+        .stop_after_no_progress(NoProgressBudget::Iterations(500))
+        .launch();
+    assert!(matches!(result.reason_for_stopping, ReasonForStopping::NoProgress));
+}
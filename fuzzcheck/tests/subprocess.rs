@@ -0,0 +1,65 @@
+#![cfg(unix)]
+
+use std::io::Read;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use fuzzcheck::sensors_and_pools::SimplestToActivateCounterPool;
+use fuzzcheck::subprocess::{shared_counters, SubprocessExecutor};
+use fuzzcheck::{Arguments, ReasonForStopping, Sensor};
+
+/// The program run by the executor, which is this test when it is launched by the executor
+/// and does nothing otherwise
+#[test]
+fn subprocess_child() {
+    let Some(counters) = shared_counters() else {
+        return;
+    };
+    let mut input = vec![];
+    std::io::stdin().read_to_end(&mut input).unwrap();
+    for (i, &x) in input.iter().enumerate().take(2) {
+        counters[i * 256 + x as usize].fetch_add(1, Ordering::Relaxed);
+    }
+    match input.first() {
+        Some(42) => std::process::abort(),
+        Some(43) => std::thread::sleep(Duration::from_secs(60)),
+        _ => {}
+    }
+}
+
+fn executor() -> SubprocessExecutor {
+    let test_executable = std::env::current_exe().unwrap();
+    SubprocessExecutor::new(test_executable, &["subprocess_child", "--test-threads=1"], 2 * 256).unwrap()
+}
+
+#[test]
+fn test_subprocess_executor() {
+    let executor = executor();
+    let mut sensor = executor.sensor();
+    sensor.start_recording();
+    assert!(executor.run(&[5, 6, 7]));
+    sensor.stop_recording();
+    assert_eq!(sensor.get_observations(), vec![(5, 1), (256 + 6, 1)]);
+    assert!(!executor.run(&[42]));
+    // the program is killed if it is still running after the timeout
+    let executor = self::executor().with_timeout(Duration::from_secs(5));
+    let start = Instant::now();
+    assert!(!executor.run(&[43]));
+    assert!(start.elapsed() < Duration::from_secs(30));
+    assert!(executor.run(&[5]));
+
+    let executor = self::executor();
+    let sensor = executor.sensor();
+    let pool = SimplestToActivateCounterPool::new("subprocess", sensor.len());
+    let result = fuzzcheck::fuzz_test(move |xs: &[u8]| executor.run(xs))
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(sensor, pool)
+        .arguments(Arguments::for_property_test(20_000))
+        .stop_after_first_test_failure(true)
+        .launch();
+    assert!(matches!(
+        result.reason_for_stopping,
+        ReasonForStopping::TestFailure(xs) if xs.first() == Some(&42)
+    ));
+}
@@ -0,0 +1,79 @@
+use std::path::{Path, PathBuf};
+
+use fuzzcheck::builder::TestFunctionExt;
+use fuzzcheck::{record_event, Arguments, ReasonForStopping};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{events_pool, events_sensor, TempFolder};
+
+fn parse(xs: &[u8]) -> Result<u8, String> {
+    match xs {
+        [] => Ok(0),
+        [x, ..] if *x >= 250 => Err(format!("invalid first byte: {}", x)),
+        [x, ..] => {
+            record_event(*x as u64, 1);
+            Ok(*x)
+        }
+    }
+}
+
+fn encode(xs: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![];
+    for &x in xs.iter().take(8) {
+        record_event(x as u64, 1);
+        // bug: the byte 42 is dropped
+        if x != 42 {
+            encoded.push(x);
+        }
+        encoded.push(x);
+    }
+    encoded
+}
+
+fn find_file(folder: &Path, name: &str) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(folder).ok()? {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            if let Some(path) = find_file(&path, name) {
+                return Some(path);
+            }
+        } else if path.file_name().unwrap() == name {
+            return Some(path);
+        }
+    }
+    None
+}
+
+#[test]
+fn test_result_error_describes_test_failure() {
+    let folder = TempFolder::new("test_function_kinds");
+    let mut arguments = Arguments::for_property_test(20_000);
+    arguments.stats_folder = Some(folder.to_path_buf());
+    let result = fuzzcheck::fuzz_test(parse)
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(events_sensor(1), events_pool("events", 1))
+        .arguments(arguments)
+        .stop_after_first_test_failure(false)
+        .launch();
+    assert!(result.found_test_failure);
+
+    let test_failures = std::fs::read_to_string(find_file(&folder, "test_failures.json").unwrap()).unwrap();
+    assert!(test_failures.contains("invalid first byte: 25"), "{}", test_failures);
+}
+
+#[test]
+fn test_failure_predicate() {
+    let is_invalid = |output: &Vec<u8>| output.chunks(2).any(|pair| pair.len() != 2 || pair[0] != pair[1]);
+    let result = fuzzcheck::fuzz_test(encode.failure_predicate(is_invalid))
+        .default_mutator()
+        .serde_serializer()
+        .sensor_and_pool(events_sensor(1), events_pool("events", 1))
+        .arguments(Arguments::for_property_test(20_000))
+        .launch();
+    assert!(matches!(
+        result.reason_for_stopping,
+        ReasonForStopping::TestFailure(xs) if xs.iter().take(8).any(|&x| x == 42)
+    ));
+}
@@ -0,0 +1,48 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use fuzzcheck::sensors_and_pools::{MaximiseObservationPool, NoopSensor};
+use fuzzcheck::{Arguments, DefaultMutator};
+
+#[test]
+fn test_before_and_after_each() {
+    let nbr_before = Rc::new(Cell::new(0usize));
+    let nbr_after = Rc::new(Cell::new(0usize));
+    let nbr_failures = Rc::new(Cell::new(0usize));
+    let is_running = Rc::new(Cell::new(false));
+
+    let counterexample = {
+        let is_running_in_test = is_running.clone();
+        let (nbr_before, is_running_before) = (nbr_before.clone(), is_running.clone());
+        let (nbr_after, nbr_failures, is_running_after) = (nbr_after.clone(), nbr_failures.clone(), is_running.clone());
+        fuzzcheck::fuzz_test(move |xs: &Vec<u8>| {
+            assert!(is_running_in_test.get());
+            xs.len() < 4
+        })
+        .mutator(Vec::<u8>::default_mutator())
+        .serde_serializer()
+        .sensor_and_pool(NoopSensor, MaximiseObservationPool::<()>::new("unit"))
+        .arguments(Arguments::for_property_test(10_000))
+        .before_each(move |_| {
+            assert!(!is_running_before.get());
+            is_running_before.set(true);
+            nbr_before.set(nbr_before.get() + 1);
+        })
+        .after_each(move |xs, success| {
+            assert!(is_running_after.get());
+            is_running_after.set(false);
+            assert_eq!(success, xs.len() < 4);
+            nbr_after.set(nbr_after.get() + 1);
+            if !success {
+                nbr_failures.set(nbr_failures.get() + 1);
+            }
+        })
+        .launch_property_test()
+    };
+
+    assert!(counterexample.is_some());
+    assert!(!is_running.get());
+    assert!(nbr_before.get() > 1);
+    assert_eq!(nbr_before.get(), nbr_after.get());
+    assert!(nbr_failures.get() >= 1);
+}
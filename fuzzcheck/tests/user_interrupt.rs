@@ -0,0 +1,38 @@
+// This test is not part of `lib.rs`, because signals are received by the whole process, which would
+// interrupt the other fuzz tests running at the same time.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use fuzzcheck::{Arguments, ReasonForStopping};
+
+#[path = "common/mod.rs"]
+mod common;
+use common::{fuzz, record_byte_events, TempFolder};
+
+static NBR_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+fn test_function(xs: &[u8]) -> bool {
+    if NBR_RUNS.fetch_add(1, Ordering::Relaxed) == 500 {
+        unsafe {
+            libc::raise(libc::SIGINT);
+        }
+    }
+    record_byte_events(xs, 4);
+    true
+}
+
+#[test]
+fn test_user_interrupt() {
+    let folder = TempFolder::new("user_interrupt");
+
+    let mut arguments = Arguments::for_property_test(usize::MAX);
+    arguments.stats_folder = Some(folder.join("stats"));
+    let result = fuzz(test_function, 4, arguments);
+
+    assert!(matches!(result.reason_for_stopping, ReasonForStopping::UserInterrupt));
+    assert!(!result.found_test_failure);
+    // the fuzzer stops right after the test function that received the signal returns
+    assert_eq!(NBR_RUNS.load(Ordering::Relaxed), 501);
+    // and the statistics of the session are saved
+    assert!(std::fs::read_dir(folder.join("stats")).unwrap().count() > 0);
+}
@@ -1,11 +1,118 @@
+use std::any::{Any, TypeId};
+use std::collections::HashSet;
+
 use fuzzcheck::mutators::integer::U8Mutator;
 use fuzzcheck::mutators::vector::VecMutator;
+use fuzzcheck::subvalue_provider::{EmptySubValueProvider, Generation, SubValueProviderId};
+use fuzzcheck::{Mutator, SubValueProvider};
 #[test]
 fn test_vector_mutator() {
     let m = VecMutator::new(VecMutator::new(U8Mutator::default(), 0..=usize::MAX), 0..=usize::MAX);
     fuzzcheck::mutators::testing_utilities::test_mutator(m, 500.0, 500.0, false, true, 100, 150);
 }
 
+#[test]
+fn test_vector_invariants() {
+    let m = VecMutator::new(U8Mutator::default(), 0..=usize::MAX).sorted_and_deduplicated();
+    fuzzcheck::mutators::testing_utilities::test_mutator(m, 500.0, 500.0, false, true, 100, 150);
+
+    let m = VecMutator::new(U8Mutator::default(), 2..=10).sorted_and_deduplicated();
+    assert!(m.validate_value(&vec![1, 2, 3]).is_some());
+    assert!(m.validate_value(&vec![1, 3, 2]).is_none());
+    assert!(m.validate_value(&vec![1, 1, 2]).is_none());
+    let is_valid = |x: &Vec<u8>| (2..=10).contains(&x.len()) && x.windows(2).all(|w| w[0] < w[1]);
+    for _ in 0..100 {
+        let (mut value, _) = m.random_arbitrary(100.0);
+        assert!(is_valid(&value));
+        let mut cache = m.validate_value(&value).unwrap();
+        let original = value.clone();
+        for _ in 0..100 {
+            let (token, cplx) = m.random_mutate(&mut value, &mut cache, 100.0);
+            assert!(is_valid(&value), "{value:?}");
+            assert_eq!(cplx, m.complexity(&value, &m.validate_value(&value).unwrap()));
+            m.unmutate(&mut value, &mut cache, token);
+            assert_eq!(value, original);
+        }
+    }
+
+    let m = VecMutator::new(U8Mutator::default(), 0..=usize::MAX).sorted_by_key(|x| std::cmp::Reverse(*x));
+    let mut step = m.default_arbitrary_step();
+    for _ in 0..100 {
+        let (value, _) = m.ordered_arbitrary(&mut step, 100.0).unwrap();
+        assert!(value.windows(2).all(|w| w[0] >= w[1]), "{value:?}");
+    }
+}
+
+#[test]
+fn test_vector_chunk_mutations() {
+    let m = VecMutator::new(U8Mutator::default(), 0..=usize::MAX);
+    let original = vec![1, 2, 3, 4, 5];
+    let mut value = original.clone();
+    let mut cache = m.validate_value(&value).unwrap();
+    let mut step = m.default_mutation_step(&value, &cache);
+    let mut mutated = HashSet::new();
+    for _ in 0..100_000 {
+        if let Some((token, cplx)) = m.ordered_mutate(&mut value, &mut cache, &mut step, &EmptySubValueProvider, 1000.0)
+        {
+            assert_eq!(cplx, m.complexity(&value, &m.validate_value(&value).unwrap()));
+            mutated.insert(value.clone());
+            m.unmutate(&mut value, &mut cache, token);
+            assert_eq!(value, original);
+        } else {
+            break;
+        }
+    }
+    // duplicate a slice
+    assert!(mutated.contains(&vec![1, 2, 3, 2, 3, 4, 5]));
+    // reverse a slice
+    assert!(mutated.contains(&vec![1, 4, 3, 2, 5]));
+    // rotate the vector
+    assert!(mutated.contains(&vec![3, 4, 5, 1, 2]));
+}
+
+/// Always provides the same slice
+struct SliceProvider(Vec<u8>);
+impl SubValueProvider for SliceProvider {
+    fn identifier(&self) -> SubValueProviderId {
+        SubValueProviderId {
+            idx: 1,
+            generation: Generation(0),
+        }
+    }
+    fn get_random_subvalue(&self, typeid: TypeId, _max_cplx: f64) -> Option<(&dyn Any, f64)> {
+        (typeid == TypeId::of::<Vec<u8>>()).then_some((&self.0 as &dyn Any, 0.0))
+    }
+    fn get_subvalue(&self, _typeid: TypeId, _max_cplx: f64, _index: &mut usize) -> Option<(&dyn Any, f64)> {
+        None
+    }
+}
+
+#[test]
+fn test_vector_crossover_splice_slice() {
+    let m = VecMutator::new(U8Mutator::default(), 2..=6);
+    let provider = SliceProvider(vec![9, 9, 9]);
+    let original = vec![1, 2, 3, 4];
+    let mut value = original.clone();
+    let mut cache = m.validate_value(&value).unwrap();
+    let mut step = m.default_mutation_step(&value, &cache);
+    let mut spliced = false;
+    for _ in 0..10_000 {
+        let Some((token, cplx)) = m.ordered_mutate(&mut value, &mut cache, &mut step, &provider, 1000.0) else {
+            break;
+        };
+        assert!((2..=6).contains(&value.len()));
+        assert_eq!(cplx, m.complexity(&value, &m.validate_value(&value).unwrap()));
+        // some elements were replaced by a part of the provided slice
+        let nbr_nines = value.iter().filter(|&&x| x == 9).count();
+        if nbr_nines > 0 && value.len() - nbr_nines < original.len() {
+            spliced = true;
+        }
+        m.unmutate(&mut value, &mut cache, token);
+        assert_eq!(value, original);
+    }
+    assert!(spliced);
+}
+
 // #[test]
 // fn test_vector_explore() {
 //     // let m = VecMutator::new(VecMutator::new(U8Mutator::default(), 0..=5), 0..=5);
@@ -6,26 +6,59 @@ use std::time::Duration;
 use getopts::{Fail, Matches, Options};
 
 pub const MAX_INPUT_CPLX_FLAG: &str = "max-cplx";
+pub const MAX_ARBITRARY_CPLX_FLAG: &str = "max-arbitrary-cplx";
+pub const MAX_MUTATE_CPLX_FLAG: &str = "max-mutate-cplx";
 pub const INPUT_FILE_FLAG: &str = "input-file";
 pub const IN_CORPUS_FLAG: &str = "in-corpus";
 pub const NO_IN_CORPUS_FLAG: &str = "no-in-corpus";
 pub const OUT_CORPUS_FLAG: &str = "out-corpus";
 pub const NO_OUT_CORPUS_FLAG: &str = "no-out-corpus";
+pub const INCOMING_CORPUS_FLAG: &str = "incoming-corpus";
+pub const MERGE_CORPUS_FLAG: &str = "merge-corpus";
 pub const ARTIFACTS_FLAG: &str = "artifacts";
 pub const NO_ARTIFACTS_FLAG: &str = "no-artifacts";
 pub const STATS_FLAG: &str = "stats";
 pub const NO_STATS_FLAG: &str = "no-stats";
+pub const STATS_SOCKET_FLAG: &str = "stats-socket";
 pub const COMMAND_FLAG: &str = "command";
 
 pub const MAX_DURATION_FLAG: &str = "stop-after-duration";
 pub const MAX_ITERATIONS_FLAG: &str = "stop-after-iterations";
 pub const STOP_AFTER_FIRST_FAILURE_FLAG: &str = "stop-after-first-failure";
+pub const STOP_AFTER_NO_PROGRESS_FLAG: &str = "stop-after-no-progress";
+pub const PRESERVE_FAILURE_FLAG: &str = "preserve-failure";
+pub const RESUME_FLAG: &str = "resume";
+pub const DEDUP_INPUTS_FLAG: &str = "dedup-inputs";
+pub const NO_ADAPTIVE_MUTATIONS_FLAG: &str = "no-adaptive-mutations";
+pub const FORK_FLAG: &str = "fork";
+pub const TRACK_PROVENANCE_FLAG: &str = "track-provenance";
+pub const RECORD_OBSERVATIONS_FLAG: &str = "record-observations";
+pub const CHECK_STABILITY_FLAG: &str = "check-stability";
+pub const OBSERVATION_SAMPLING_FLAG: &str = "observation-sampling";
+pub const COVERAGE_INCLUDE_FLAG: &str = "coverage-include";
+pub const COVERAGE_EXCLUDE_FLAG: &str = "coverage-exclude";
+pub const PRINT_COVERAGE_FUNCTIONS_FLAG: &str = "print-coverage-functions";
+pub const CORPUS_WITH_TAG_FLAG: &str = "corpus-with-tag";
+pub const CORPUS_WITHOUT_TAG_FLAG: &str = "corpus-without-tag";
 
 pub const DETECT_INFINITE_LOOP_FLAG: &str = "detect-infinite-loop";
 
+pub const EXIT_CODE_POLICY_FLAG: &str = "exit-code-policy";
+pub const EXIT_CODE_POLICY_RETURN: &str = "return";
+pub const EXIT_CODE_POLICY_EXIT: &str = "exit";
+
+pub const CORPUS_NAMING_FLAG: &str = "corpus-naming";
+pub const CORPUS_NAMING_HASH: &str = "hash";
+pub const CORPUS_NAMING_CPLX_HASH: &str = "cplx-hash";
+
 pub const COMMAND_FUZZ: &str = "fuzz";
 pub const COMMAND_MINIFY_INPUT: &str = "minify";
 pub const COMMAND_READ: &str = "read";
+pub const COMMAND_ANALYZE: &str = "analyze";
+pub const COMMAND_REPOOL: &str = "repool";
+pub const COMMAND_BENCH: &str = "bench";
+pub const COMMAND_COVDIFF: &str = "covdiff";
+pub const COMMAND_MERGE: &str = "merge";
 
 #[derive(Clone)]
 pub struct DefaultArguments {
@@ -42,8 +75,34 @@ impl Default for DefaultArguments {
 #[derive(Debug, Clone)]
 pub enum FuzzerCommand {
     Fuzz,
-    Read { input_file: PathBuf },
-    MinifyInput { input_file: PathBuf },
+    Read {
+        input_file: PathBuf,
+    },
+    MinifyInput {
+        input_file: PathBuf,
+    },
+    /// Run each test case of the input corpus once and save a report of the code coverage it contributes
+    /// to the statistics folder
+    Analyze,
+    /// Replay the observations recorded by the most recent fuzzing session with `--record-observations`
+    /// into the pool of the fuzz test, and save the evolution of its statistics to the statistics folder.
+    /// The fuzz test is not executed.
+    Repool,
+    /// Repeatedly mutate and run the test cases of the input corpus, without any pool, and report the number
+    /// of executions per second along with the time spent in the mutator, the sensor, and the test function
+    Bench,
+    /// Run the two test cases once each and print the code regions covered by only one of them.
+    ///
+    /// The input files are given by two occurrences of `--input-file`.
+    CoverageDiff {
+        input_files: [PathBuf; 2],
+    },
+    /// Run each test case of the given corpora once and save those that the pool keeps to the output corpus.
+    ///
+    /// The corpora are given by one or more occurrences of `--merge-corpus`.
+    Merge {
+        corpora: Vec<PathBuf>,
+    },
 }
 impl Default for FuzzerCommand {
     fn default() -> Self {
@@ -51,33 +110,276 @@ impl Default for FuzzerCommand {
     }
 }
 
+/// What the fuzzer does once it stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExitCodePolicy {
+    /// The result of the fuzz test is returned to the caller of `launch()`, which decides whether the
+    /// test passes. `cargo fuzzcheck` exits with the exit code of `cargo test`.
+    #[default]
+    Return,
+    /// The process exits as soon as the fuzzer stops, with an exit code given by
+    /// [`TerminationStatus`](crate::TerminationStatus): 0 if no test failure was found, 2 if a test
+    /// failure was found, 1 if the test function crashed, and 4 if the fuzzer was not configured correctly.
+    /// `cargo fuzzcheck` exits with the same exit code.
+    Exit,
+}
+
+/// How long the fuzzer keeps running without adding any test case to the corpus, see
+/// [`Arguments::stop_after_no_progress`].
+///
+/// On the command line, it is written either as a number of iterations, e.g. `100000`, or as a duration
+/// followed by its unit, which is one of `s`, `m`, or `h`, e.g. `30s` or `10m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoProgressBudget {
+    Iterations(usize),
+    Duration(Duration),
+}
+
+impl NoProgressBudget {
+    #[coverage(off)]
+    pub fn parse(s: &str) -> Option<Self> {
+        let (number, seconds_per_unit) = match s.as_bytes().last()? {
+            b's' => (&s[..s.len() - 1], 1),
+            b'm' => (&s[..s.len() - 1], 60),
+            b'h' => (&s[..s.len() - 1], 60 * 60),
+            _ => return s.parse::<usize>().ok().map(NoProgressBudget::Iterations),
+        };
+        let seconds = number.parse::<u64>().ok()?.checked_mul(seconds_per_unit)?;
+        Some(NoProgressBudget::Duration(Duration::from_secs(seconds)))
+    }
+}
+
+impl Display for NoProgressBudget {
+    #[coverage(off)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoProgressBudget::Iterations(iterations) => write!(f, "{}", iterations),
+            NoProgressBudget::Duration(duration) => write!(f, "{}s", duration.as_secs()),
+        }
+    }
+}
+
+/// How the files of the output corpus and the artifacts folder are named.
+///
+/// Both kinds of names can be read back by the fuzzer, which does not look at the name of the files
+/// of the input corpus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CorpusNamingPolicy {
+    /// Each file is named after the hash of its content, e.g. `8cd7777109b57b8c.json`.
+    ///
+    /// The same test case always has the same name, which makes it easy to synchronise corpora
+    /// between machines.
+    #[default]
+    ContentHash,
+    /// Each file is named after the complexity of the test case, multiplied by 100, followed by the hash
+    /// of its content, e.g. `4213--8cd7777109b57b8c.json` for a test case of complexity 42.13.
+    ///
+    /// This is the naming scheme always used by the `minify` command.
+    ComplexityAndHash,
+}
+
 /// Various arguments given to the fuzzer, typically provided by the `cargo fuzzcheck` command line tool.
 #[derive(Debug, Clone)]
 pub struct Arguments {
     pub command: FuzzerCommand,
+    /// The maximum complexity of the tested inputs, including those obtained through mutations
     pub max_input_cplx: f64,
+    /// The maximum complexity of the inputs generated from scratch, which should be at most `max_input_cplx`
+    pub max_arbitrary_cplx: f64,
     pub detect_infinite_loop: bool,
     pub maximum_duration: Duration,
     pub maximum_iterations: usize,
     pub stop_after_first_failure: bool,
+    /// Stop the fuzzer once no pool has reported a change to the corpus for the given number of iterations
+    /// or duration, counted from the start of the fuzzing session or from the last change.
+    ///
+    /// The reason for stopping given in the result of the fuzzer is then `ReasonForStopping::NoProgress`.
+    /// This is a natural budget for fuzz tests run in CI, as the fuzzer stops when its code coverage plateaus.
+    pub stop_after_no_progress: Option<NoProgressBudget>,
+    /// When minifying a failing test case, only keep the simpler test cases that fail in the same way.
+    ///
+    /// Two panics are the same failure if they happen at the same location in the source code, whatever their
+    /// message. Otherwise, the minifier keeps any simpler failing test case, which may be a different bug.
+    pub preserve_failure: bool,
+    pub exit_code_policy: ExitCodePolicy,
+    /// Resume the most recent fuzzing session saved in the stats folder.
+    ///
+    /// The number of iterations is restored, and the progress of the mutations of each test case of the corpus
+    /// is approximated from the number of mutations performed on it, so that the fuzzer mostly avoids
+    /// re-exploring the same mutations. The state of the pools is not restored, only their content, which is
+    /// recomputed from the corpus.
+    pub resume: bool,
+    /// Skip the test cases that were already executed, in the current fuzzing session or in the previous ones.
+    ///
+    /// The executed test cases are recorded in a bloom filter, identified by their serialized content, which
+    /// is saved to the stats folder and reloaded from the most recent fuzzing session when the fuzzer starts.
+    /// It has no effect if there is no stats folder, and the inputs of the corpus are always executed.
+    /// It is disabled by default because every test case must then be serialized before being executed.
+    /// The skipped test cases still count as iterations of the fuzzer.
+    pub deduplicate_inputs: bool,
+    /// Adapt the weights of the mutation operators to how often they lead to new test cases of the corpus.
+    ///
+    /// It only affects the mutators that report their operators, such as the vector mutator. The statistics of
+    /// each operator are saved to the stats folder as `mutation_operators.csv`.
+    pub adaptive_mutations: bool,
+    /// How the files of the output corpus and the artifacts are named
+    pub corpus_naming: CorpusNamingPolicy,
+    /// Run each test case in a child process created by `fork`, which is only supported on Unix.
+    ///
+    /// The global state modified by a test case is then discarded before the next one is run, and crashes
+    /// are reported as test failures. Only the code coverage counters are sent back to the fuzzer.
+    pub fork: bool,
+    /// Record, for each test case added to the corpus, the test case it was derived from, how it was derived,
+    /// and its generation, which is the number of mutations separating it from a test case of the input corpus
+    /// or from a randomly generated one. The records are saved to the stats folder as `provenance.csv` (and
+    /// `provenance.json` with the `serde_json_serializer` feature).
+    pub track_provenance: bool,
+    /// Record the observations of the sensor for every executed test case, along with its complexity, and write them
+    /// to the stats folder as `observations.bin`, such that the `repool` command can later replay them into a pool
+    /// configured differently, to compare how it would have evolved during the fuzzing session.
+    ///
+    /// The observations are written to the file as the fuzzer runs, and all of them are analyzed by the pool, even
+    /// those already seen. The file grows with each iteration, so it is only meant for experiments on short fuzzing
+    /// sessions.
+    pub record_observations: bool,
+    /// Execute each test case that activates new code coverage counters a few more times, and ignore the counters
+    /// whose activation differs between these executions.
+    ///
+    /// This prevents nondeterministic test functions, e.g. those using a `HashMap` or multiple threads, from filling
+    /// the corpus with test cases whose only specificity is noise. The percentage of the observed counters that
+    /// behave deterministically is reported as the stability of the fuzzer.
+    pub check_stability: bool,
+    /// Only fully analyze the observations of the sensors every `observation_sampling` iterations.
+    ///
+    /// The observations of the test cases that were generated or mutated by the fuzzer are hashed, and they are
+    /// never given to the pools if the same observations were recently seen with an equal or lower complexity.
+    /// In between every `observation_sampling` iterations, they are not given to the pools either if they were
+    /// seen with a higher complexity. This trades some feedback precision, e.g. a simpler test case with the same
+    /// observations as a test case of the pool is then ignored, for a higher throughput with sensors whose
+    /// observations are large and costly to analyze. It only affects the sensors that can hash their observations
+    /// with `Sensor::hash_observations`, such as the code coverage sensor. The default value of 1 only skips the
+    /// observations that cannot improve the pools.
+    pub observation_sampling: usize,
+    /// Glob patterns selecting the files or functions observed by the default code coverage sensor.
+    ///
+    /// A pattern matches either the path of a file or the demangled name of a function, such as
+    /// `src/parser/**` or `my_crate::parser::*`. If it is not empty, the functions matching none of the patterns
+    /// are not observed. Otherwise, only the files whose path is relative to the current directory are observed.
+    pub coverage_include: Vec<String>,
+    /// Glob patterns selecting the files or functions that are not observed by the default code coverage sensor,
+    /// even if they are matched by `coverage_include`
+    pub coverage_exclude: Vec<String>,
+    /// Print the files and functions observed by the default code coverage sensor, then exit
+    pub print_coverage_functions: bool,
+    /// If it is not empty, only the test cases of the input corpus with at least one of these tags are read.
+    ///
+    /// The tags of a test case are attached by the pools that keep it and saved alongside it in the output corpus.
+    pub corpus_with_tags: Vec<String>,
+    /// The test cases of the input corpus with any of these tags are not read
+    pub corpus_without_tags: Vec<String>,
     pub corpus_in: Option<PathBuf>,
     pub corpus_out: Option<PathBuf>,
+    /// A folder that is scanned for new test cases every second while fuzzing.
+    ///
+    /// The files dropped in this folder are decoded like those of the input corpus, run, and given to the pools,
+    /// which may add them to the corpus. They are then moved to the input corpus folder, so that they are
+    /// read again by the next fuzzing sessions, or left there but ignored if there is no input corpus. The files
+    /// that cannot be decoded are reported and ignored until they are modified.
+    pub incoming_corpus: Option<PathBuf>,
     pub artifacts_folder: Option<PathBuf>,
     pub stats_folder: Option<PathBuf>,
+    /// The address of a TCP socket to which the events of the fuzzer are streamed as newline-delimited JSON.
+    ///
+    /// Each line is a JSON object whose `"event"` field is one of `"start"`, `"pulse"`, `"new_coverage"`,
+    /// `"artifact"`, `"test_failure"`, `"done"`, `"stop"`, `"end"`, `"crash"`, `"signal"`, `"sanitizer_report"`,
+    /// `"did_read_corpus"`, `"skipped_corpus_files"`, or `"read_incoming_corpus"`. Every event has a `"time_ms"`
+    /// field giving the number of milliseconds since the fuzzer started. When available, the `"iterations"` and
+    /// `"iter_per_s"` fields give the number of iterations and the current speed of the fuzzer, and `"pool"` is an
+    /// object containing the statistics of the pool, with the same keys as the columns of its CSV statistics file.
+    /// Additionally:
+    /// * `"new_coverage"` has the fields `"added"` and `"removed"`, the number of test cases added to and removed
+    ///   from the corpus
+    /// * `"artifact"` has the fields `"path"`, the path of the saved artifact (or `null`), and `"complexity"`
+    /// * `"signal"` has the field `"signal"`, the number of the signal that was caught
+    /// * `"skipped_corpus_files"` has the field `"nbr_files"`
+    /// * `"read_incoming_corpus"` has the field `"nbr_files"`, the number of test cases read from the
+    ///   [incoming corpus](Arguments::incoming_corpus)
+    ///
+    /// If the receiver does not read an event within 100 milliseconds, the connection is closed, such that it
+    /// does not slow down the fuzzer.
+    pub stats_socket: Option<String>,
 }
 impl Arguments {
     pub fn for_internal_documentation_test() -> Self {
         Self {
             command: FuzzerCommand::Fuzz,
             max_input_cplx: 256.,
+            max_arbitrary_cplx: 256.,
             detect_infinite_loop: false,
             maximum_duration: Duration::MAX,
             maximum_iterations: usize::MAX,
             stop_after_first_failure: true,
+            stop_after_no_progress: None,
+            preserve_failure: false,
+            exit_code_policy: ExitCodePolicy::Return,
+            resume: false,
+            deduplicate_inputs: false,
+            adaptive_mutations: false,
+            corpus_naming: CorpusNamingPolicy::ContentHash,
+            fork: false,
+            track_provenance: false,
+            record_observations: false,
+            check_stability: false,
+            observation_sampling: 1,
+            coverage_include: vec![],
+            coverage_exclude: vec![],
+            print_coverage_functions: false,
+            corpus_with_tags: vec![],
+            corpus_without_tags: vec![],
+            corpus_in: None,
+            corpus_out: None,
+            incoming_corpus: None,
+            artifacts_folder: None,
+            stats_folder: None,
+            stats_socket: None,
+        }
+    }
+    /// Arguments for a bounded, in-memory fuzz test, as used by `fuzzcheck::test_once`.
+    ///
+    /// No corpus, artifact, or statistics folders are used. The fuzzer is stopped after
+    /// `maximum_iterations` iterations if it hasn't found any test failure.
+    pub fn for_property_test(maximum_iterations: usize) -> Self {
+        Self {
+            command: FuzzerCommand::Fuzz,
+            max_input_cplx: DefaultArguments::default().max_input_cplx,
+            max_arbitrary_cplx: DefaultArguments::default().max_input_cplx,
+            detect_infinite_loop: false,
+            maximum_duration: Duration::MAX,
+            maximum_iterations,
+            stop_after_first_failure: true,
+            stop_after_no_progress: None,
+            preserve_failure: false,
+            exit_code_policy: ExitCodePolicy::Return,
+            resume: false,
+            deduplicate_inputs: false,
+            adaptive_mutations: false,
+            corpus_naming: CorpusNamingPolicy::ContentHash,
+            fork: false,
+            track_provenance: false,
+            record_observations: false,
+            check_stability: false,
+            observation_sampling: 1,
+            coverage_include: vec![],
+            coverage_exclude: vec![],
+            print_coverage_functions: false,
+            corpus_with_tags: vec![],
+            corpus_without_tags: vec![],
             corpus_in: None,
             corpus_out: None,
+            incoming_corpus: None,
             artifacts_folder: None,
             stats_folder: None,
+            stats_socket: None,
         }
     }
 }
@@ -96,7 +398,10 @@ pub fn options_parser() -> Options {
             "the action to be performed (default: fuzz). --{} is required when using `{}`",
             INPUT_FILE_FLAG, COMMAND_MINIFY_INPUT
         ),
-        &format!("<{} | {}>", COMMAND_FUZZ, COMMAND_MINIFY_INPUT),
+        &format!(
+            "<{} | {} | {} | {} | {}>",
+            COMMAND_FUZZ, COMMAND_MINIFY_INPUT, COMMAND_ANALYZE, COMMAND_REPOOL, COMMAND_BENCH
+        ),
     );
     options.optopt(
         "",
@@ -106,10 +411,17 @@ pub fn options_parser() -> Options {
     );
     options.optopt("", MAX_ITERATIONS_FLAG, "maximum number of iterations", "N");
 
+    options.optopt(
+        "",
+        STOP_AFTER_NO_PROGRESS_FLAG,
+        "stop the fuzzer once no test case was added to the corpus for N iterations, or for a duration such as 30s, 10m, or 2h",
+        "N | DURATION",
+    );
+
     options.optflag(
         "",
         DETECT_INFINITE_LOOP_FLAG,
-        "fail on tests running for more than one second",
+        "fail on tests running for more than one second (unix only)",
     );
 
     options.optflag(
@@ -118,6 +430,120 @@ pub fn options_parser() -> Options {
         "stop the fuzzer after the first test failure is found",
     );
 
+    options.optflag(
+        "",
+        PRESERVE_FAILURE_FLAG,
+        "when minifying a test case, only keep the simpler test cases that fail at the same location",
+    );
+
+    options.optflag(
+        "",
+        RESUME_FLAG,
+        "resume the most recent fuzzing session saved in the statistics folder, approximately restoring the progress of the mutations",
+    );
+
+    options.optflag(
+        "",
+        DEDUP_INPUTS_FLAG,
+        "skip the test cases that were executed by this or previous fuzzing sessions",
+    );
+
+    options.optflag(
+        "",
+        NO_ADAPTIVE_MUTATIONS_FLAG,
+        "do not adapt the weights of the mutation operators to how often they lead to new test cases",
+    );
+
+    options.optflag(
+        "",
+        FORK_FLAG,
+        "run each test case in a child process, such that the global state it modifies is discarded (unix only)",
+    );
+
+    options.optflag(
+        "",
+        TRACK_PROVENANCE_FLAG,
+        "record the parent, derivation, and generation of each test case of the corpus in the statistics folder",
+    );
+
+    options.optflag(
+        "",
+        RECORD_OBSERVATIONS_FLAG,
+        &format!(
+            "record the observations of every executed test case in the statistics folder, to be replayed by `{}`",
+            COMMAND_REPOOL
+        ),
+    );
+
+    options.optflag(
+        "",
+        CHECK_STABILITY_FLAG,
+        "re-execute the test cases with new code coverage and ignore the counters that do not behave deterministically",
+    );
+
+    options.optopt(
+        "",
+        OBSERVATION_SAMPLING_FLAG,
+        "only fully analyze the observations every N iterations, and skip the observations seen before in between",
+        "N",
+    );
+
+    options.optmulti(
+        "",
+        COVERAGE_INCLUDE_FLAG,
+        "only observe the code coverage of the files or functions matching the glob pattern, can be repeated",
+        "GLOB",
+    );
+    options.optmulti(
+        "",
+        COVERAGE_EXCLUDE_FLAG,
+        "do not observe the code coverage of the files or functions matching the glob pattern, can be repeated",
+        "GLOB",
+    );
+    options.optflag(
+        "",
+        PRINT_COVERAGE_FUNCTIONS_FLAG,
+        "print the files and functions whose code coverage is observed, then exit",
+    );
+
+    options.optmulti(
+        "",
+        CORPUS_WITH_TAG_FLAG,
+        "only read the test cases of the input corpus that have one of the given tags, can be repeated",
+        "TAG",
+    );
+    options.optmulti(
+        "",
+        CORPUS_WITHOUT_TAG_FLAG,
+        "do not read the test cases of the input corpus that have the given tag, can be repeated",
+        "TAG",
+    );
+
+    options.optopt(
+        "",
+        EXIT_CODE_POLICY_FLAG,
+        &format!(
+            "what to do when the fuzzer stops (default: {ret}). With `{exit}`, the process exits with code 0 \
+            if no test failure was found, 2 if a test failure was found, and 4 if the fuzzer was misconfigured",
+            ret = EXIT_CODE_POLICY_RETURN,
+            exit = EXIT_CODE_POLICY_EXIT
+        ),
+        &format!("<{} | {}>", EXIT_CODE_POLICY_RETURN, EXIT_CODE_POLICY_EXIT),
+    );
+
+    options.optopt(
+        "",
+        CORPUS_NAMING_FLAG,
+        &format!(
+            "how to name the files of the output corpus and the artifacts (default: {hash}). With `{hash}`, \
+            they are named after the hash of their content. With `{cplx_hash}`, the hash is prefixed by the \
+            complexity of the test case",
+            hash = CORPUS_NAMING_HASH,
+            cplx_hash = CORPUS_NAMING_CPLX_HASH
+        ),
+        &format!("<{} | {}>", CORPUS_NAMING_HASH, CORPUS_NAMING_CPLX_HASH),
+    );
+
     options.optopt("", IN_CORPUS_FLAG, "folder for the input corpus", "PATH");
     options.optflag(
         "",
@@ -138,6 +564,21 @@ pub fn options_parser() -> Options {
         )
         .as_str(),
     );
+    options.optopt(
+        "",
+        INCOMING_CORPUS_FLAG,
+        "folder scanned while fuzzing for new test cases, which are then moved to the input corpus",
+        "PATH",
+    );
+    options.optmulti(
+        "",
+        MERGE_CORPUS_FLAG,
+        &format!(
+            "corpus merged into the output corpus by `{}`, which can be repeated",
+            COMMAND_MERGE
+        ),
+        "PATH",
+    );
     options.optopt("", ARTIFACTS_FLAG, "folder where the artifacts will be written", "PATH");
     options.optflag(
         "",
@@ -154,7 +595,18 @@ pub fn options_parser() -> Options {
         NO_STATS_FLAG,
         format!("do not save statistics, overrides --{stats}", stats = STATS_FLAG).as_str(),
     );
-    options.optopt("", INPUT_FILE_FLAG, "file containing a test case", "PATH");
+    options.optopt(
+        "",
+        STATS_SOCKET_FLAG,
+        "TCP address to which the events of the fuzzer are streamed as newline-delimited JSON",
+        "ADDR",
+    );
+    options.optmulti(
+        "",
+        INPUT_FILE_FLAG,
+        &format!("file containing a test case, given twice for `{}`", COMMAND_COVDIFF),
+        "PATH",
+    );
     options.optopt(
         "",
         MAX_INPUT_CPLX_FLAG,
@@ -165,6 +617,26 @@ pub fn options_parser() -> Options {
         .as_str(),
         "N",
     );
+    options.optopt(
+        "",
+        MAX_ARBITRARY_CPLX_FLAG,
+        format!(
+            "maximum allowed complexity of inputs generated from scratch, overrides --{max_cplx}",
+            max_cplx = MAX_INPUT_CPLX_FLAG
+        )
+        .as_str(),
+        "N",
+    );
+    options.optopt(
+        "",
+        MAX_MUTATE_CPLX_FLAG,
+        format!(
+            "maximum allowed complexity of mutated inputs, overrides --{max_cplx}",
+            max_cplx = MAX_INPUT_CPLX_FLAG
+        )
+        .as_str(),
+        "N",
+    );
     options.optflag("h", "help", "print this help menu");
 
     options
@@ -196,25 +668,46 @@ impl Arguments {
 
         let command = command.as_str();
 
-        if !matches!(command, COMMAND_FUZZ | COMMAND_READ | COMMAND_MINIFY_INPUT) {
+        if !matches!(
+            command,
+            COMMAND_FUZZ
+                | COMMAND_READ
+                | COMMAND_MINIFY_INPUT
+                | COMMAND_ANALYZE
+                | COMMAND_REPOOL
+                | COMMAND_BENCH
+                | COMMAND_COVDIFF
+                | COMMAND_MERGE
+        ) {
             return Err(ArgumentsError::Validation(format!(
-                r#"The command {c} is not supported. It can either be ‘{fuzz}’ or ‘{minify}’."#,
+                r#"The command {c} is not supported. It can either be ‘{fuzz}’, ‘{minify}’, ‘{analyze}’, ‘{repool}’, ‘{bench}’, ‘{covdiff}’, or ‘{merge}’."#,
                 c = &matches.free[0],
                 fuzz = COMMAND_FUZZ,
                 minify = COMMAND_MINIFY_INPUT,
+                analyze = COMMAND_ANALYZE,
+                repool = COMMAND_REPOOL,
+                bench = COMMAND_BENCH,
+                covdiff = COMMAND_COVDIFF,
+                merge = COMMAND_MERGE,
             )));
         }
 
-        let max_input_cplx: Option<f64> = matches
-            .opt_str(MAX_INPUT_CPLX_FLAG)
-            .and_then(
-                #[coverage(off)]
-                |x| x.parse::<usize>().ok(),
-            )
-            .map(
-                #[coverage(off)]
-                |x| x as f64,
-            );
+        #[coverage(off)]
+        fn parse_cplx(matches: &Matches, flag: &str) -> Option<f64> {
+            matches
+                .opt_str(flag)
+                .and_then(
+                    #[coverage(off)]
+                    |x| x.parse::<usize>().ok(),
+                )
+                .map(
+                    #[coverage(off)]
+                    |x| x as f64,
+                )
+        }
+        let max_input_cplx = parse_cplx(matches, MAX_INPUT_CPLX_FLAG);
+        let max_arbitrary_cplx = parse_cplx(matches, MAX_ARBITRARY_CPLX_FLAG);
+        let max_mutate_cplx = parse_cplx(matches, MAX_MUTATE_CPLX_FLAG);
 
         let detect_infinite_loop = matches.opt_present(DETECT_INFINITE_LOOP_FLAG);
 
@@ -240,6 +733,11 @@ impl Arguments {
             None
         };
 
+        let incoming_corpus: Option<PathBuf> = matches.opt_str(INCOMING_CORPUS_FLAG).and_then(
+            #[coverage(off)]
+            |x| x.parse::<PathBuf>().ok(),
+        );
+
         let artifacts_folder: Option<PathBuf> = matches.opt_str(ARTIFACTS_FLAG).and_then(
             #[coverage(off)]
             |x| x.parse::<PathBuf>().ok(),
@@ -262,6 +760,8 @@ impl Arguments {
             None
         };
 
+        let stats_socket: Option<String> = matches.opt_str(STATS_SOCKET_FLAG);
+
         let input_file: Option<PathBuf> = matches.opt_str(INPUT_FILE_FLAG).and_then(
             #[coverage(off)]
             |x| x.parse::<PathBuf>().ok(),
@@ -295,6 +795,32 @@ impl Arguments {
                 );
                 FuzzerCommand::MinifyInput { input_file }
             }
+            COMMAND_ANALYZE => FuzzerCommand::Analyze,
+            COMMAND_REPOOL => FuzzerCommand::Repool,
+            COMMAND_BENCH => FuzzerCommand::Bench,
+            COMMAND_COVDIFF => match matches.opt_strs(INPUT_FILE_FLAG).as_slice() {
+                [first, second] => FuzzerCommand::CoverageDiff {
+                    input_files: [PathBuf::from(first), PathBuf::from(second)],
+                },
+                _ => {
+                    return Err(ArgumentsError::Validation(format!(
+                        "Two input files must be provided when comparing the code coverage of test cases. Use --{} twice.",
+                        INPUT_FILE_FLAG
+                    )));
+                }
+            },
+            COMMAND_MERGE => {
+                let corpora = matches.opt_strs(MERGE_CORPUS_FLAG);
+                if corpora.is_empty() {
+                    return Err(ArgumentsError::Validation(format!(
+                        "At least one corpus must be provided when merging corpora. Use --{}.",
+                        MERGE_CORPUS_FLAG
+                    )));
+                }
+                FuzzerCommand::Merge {
+                    corpora: corpora.into_iter().map(PathBuf::from).collect(),
+                }
+            }
             _ => unreachable!(),
         };
 
@@ -316,9 +842,72 @@ impl Arguments {
             )
             .unwrap_or(usize::MAX);
         let stop_after_first_failure = matches.opt_present(STOP_AFTER_FIRST_FAILURE_FLAG);
+        let preserve_failure = matches.opt_present(PRESERVE_FAILURE_FLAG);
+        let stop_after_no_progress = match matches.opt_str(STOP_AFTER_NO_PROGRESS_FLAG) {
+            None => None,
+            Some(budget) => Some(NoProgressBudget::parse(&budget).ok_or_else(
+                #[coverage(off)]
+                || {
+                    ArgumentsError::Validation(format!(
+                        "The budget {} given to --{} is invalid. It can either be a number of iterations, such as 100000, or a duration, such as 30s, 10m, or 2h.",
+                        budget, STOP_AFTER_NO_PROGRESS_FLAG
+                    ))
+                },
+            )?),
+        };
+        let resume = matches.opt_present(RESUME_FLAG);
+        let deduplicate_inputs = matches.opt_present(DEDUP_INPUTS_FLAG);
+        let adaptive_mutations = !matches.opt_present(NO_ADAPTIVE_MUTATIONS_FLAG);
+        let fork = matches.opt_present(FORK_FLAG);
+        let track_provenance = matches.opt_present(TRACK_PROVENANCE_FLAG);
+        let record_observations = matches.opt_present(RECORD_OBSERVATIONS_FLAG);
+        let check_stability = matches.opt_present(CHECK_STABILITY_FLAG);
+        let observation_sampling = match matches.opt_str(OBSERVATION_SAMPLING_FLAG) {
+            None => 1,
+            Some(n) => match n.parse::<usize>() {
+                Ok(n) if n > 0 => n,
+                _ => {
+                    return Err(ArgumentsError::Validation(format!(
+                        "The value {} given to --{} is invalid. It must be a strictly positive number of iterations.",
+                        n, OBSERVATION_SAMPLING_FLAG
+                    )));
+                }
+            },
+        };
+        let coverage_include = matches.opt_strs(COVERAGE_INCLUDE_FLAG);
+        let coverage_exclude = matches.opt_strs(COVERAGE_EXCLUDE_FLAG);
+        let print_coverage_functions = matches.opt_present(PRINT_COVERAGE_FUNCTIONS_FLAG);
+        let corpus_with_tags = matches.opt_strs(CORPUS_WITH_TAG_FLAG);
+        let corpus_without_tags = matches.opt_strs(CORPUS_WITHOUT_TAG_FLAG);
+        let exit_code_policy = match matches.opt_str(EXIT_CODE_POLICY_FLAG).as_deref() {
+            None | Some(EXIT_CODE_POLICY_RETURN) => ExitCodePolicy::Return,
+            Some(EXIT_CODE_POLICY_EXIT) => ExitCodePolicy::Exit,
+            Some(policy) => {
+                return Err(ArgumentsError::Validation(format!(
+                    r#"The exit code policy {p} is not supported. It can either be ‘{ret}’ or ‘{exit}’."#,
+                    p = policy,
+                    ret = EXIT_CODE_POLICY_RETURN,
+                    exit = EXIT_CODE_POLICY_EXIT,
+                )));
+            }
+        };
+        let corpus_naming = match matches.opt_str(CORPUS_NAMING_FLAG).as_deref() {
+            None | Some(CORPUS_NAMING_HASH) => CorpusNamingPolicy::ContentHash,
+            Some(CORPUS_NAMING_CPLX_HASH) => CorpusNamingPolicy::ComplexityAndHash,
+            Some(policy) => {
+                return Err(ArgumentsError::Validation(format!(
+                    r#"The corpus naming policy {p} is not supported. It can either be ‘{hash}’ or ‘{cplx_hash}’."#,
+                    p = policy,
+                    hash = CORPUS_NAMING_HASH,
+                    cplx_hash = CORPUS_NAMING_CPLX_HASH,
+                )));
+            }
+        };
 
         let defaults = DefaultArguments::default();
         let max_input_cplx: f64 = max_input_cplx.unwrap_or(defaults.max_input_cplx as f64);
+        let max_arbitrary_cplx: f64 = max_arbitrary_cplx.unwrap_or(max_input_cplx);
+        let max_input_cplx: f64 = max_mutate_cplx.unwrap_or(max_input_cplx);
         let corpus_in: Option<PathBuf> = if no_in_corpus.is_some() { None } else { corpus_in };
         let corpus_out: Option<PathBuf> = if no_out_corpus.is_some() { None } else { corpus_out };
 
@@ -331,11 +920,31 @@ impl Arguments {
             maximum_duration,
             maximum_iterations,
             stop_after_first_failure,
+            stop_after_no_progress,
+            preserve_failure,
+            exit_code_policy,
+            resume,
+            deduplicate_inputs,
+            adaptive_mutations,
+            corpus_naming,
+            fork,
+            track_provenance,
+            record_observations,
+            check_stability,
+            observation_sampling,
+            coverage_include,
+            coverage_exclude,
+            print_coverage_functions,
+            corpus_with_tags,
+            corpus_without_tags,
             max_input_cplx,
+            max_arbitrary_cplx,
             corpus_in,
             corpus_out,
+            incoming_corpus,
             artifacts_folder,
             stats_folder,
+            stats_socket,
         })
     }
 }
@@ -346,6 +955,7 @@ pub fn help(parser: &Options) -> String {
     let mut help = r##"
 USAGE:
     cargo-fuzzcheck <FUZZ_TEST> [OPTIONS]
+    cargo-fuzzcheck list [--lib | --bin <NAME> | --test <NAME>]
 
 FUZZ_TEST:
     The fuzz test is the exact path to the #[test] function that launches
@@ -361,6 +971,8 @@ FUZZ_TEST:
                 .launch();
         }}
     }}
+
+    The fuzz tests of a target can be listed with `cargo-fuzzcheck list`.
 "##
     .to_owned();
     help += parser.usage("").as_str();
@@ -378,22 +990,89 @@ cargo-fuzzcheck tests::fuzz_bin --bin my_program
 cargo-fuzzcheck fuzz_test2 --test my_integration_test
     Launch the fuzzer on "fuzz_test2", located in the "my_integration_test" test target, with default options.
 
+cargo-fuzzcheck list --test my_integration_test
+    Print the path of each fuzz test located in the "my_integration_test" test target, one per line.
+
 cargo-fuzzcheck tests::fuzzit --{max_cplx} 4000 --{out_corpus} fuzz_results/out/
     Fuzz "tests::fuzzit", generating inputs of complexity no greater than 4000, 
     and write the output corpus (i.e. the folder of most interesting test cases) 
     to fuzz_results/out/.
 
+cargo-fuzzcheck tests::fuzz --{max_duration} 600 --{exit_code_policy} {exit}
+    Fuzz "tests::fuzz" for ten minutes, then exit with code 0 if no test failure
+    was found, 2 if a test failure was found, or 4 if the fuzz test could not be
+    launched. This is useful to run fuzzcheck in CI.
+
+cargo-fuzzcheck tests::fuzz --{no_progress} 10m --{exit_code_policy} {exit}
+    Fuzz "tests::fuzz" until no test case was added to its corpus for ten
+    minutes, which happens once its code coverage stops growing.
+
+cargo-fuzzcheck tests::fuzz --{incoming} fuzz/tests::fuzz/incoming
+    Fuzz "tests::fuzz" while checking every second for new test cases saved in
+    fuzz/tests::fuzz/incoming/. They are run, kept in the corpus if they are
+    interesting, and moved to the input corpus folder.
+
 cargo-fuzzcheck tests::fuzz --command {minify} --{input_file} "artifacts/crash.json"
     Using the fuzz test located at "tests::fuzz_test", minify the test input defined 
     in the file "artifacts/crash.json". It will put minified inputs in the folder 
     artifacts/crash.minified/ and name them {{complexity}}-{{hash}}.json. 
     For example, artifacts/crash.minified/4213--8cd7777109b57b8c.json
     is a minified input of complexity 42.13.
+
+cargo-fuzzcheck tests::fuzz --command {minify} --{input_file} "artifacts/crash.json" --{preserve_failure}
+    Minify the test input defined in the file "artifacts/crash.json", but only
+    keep the simpler test cases that panic at the same location in the source
+    code, instead of switching to a different failure.
+
+cargo-fuzzcheck tests::fuzz --command {analyze}
+    Run each test case of the corpus of "tests::fuzz" once and save a report to
+    fuzz/tests::fuzz/stats/ listing, for each test case, its complexity, its age,
+    and the number of code coverage counters that no other test case activates.
+
+cargo-fuzzcheck tests::fuzz --{record_observations} --{max_iterations} 100000
+cargo-fuzzcheck tests::fuzz --command {repool}
+    Fuzz "tests::fuzz" for 100000 iterations while recording the observations
+    of every test case, then replay them into the pool of "tests::fuzz", which
+    may have been reconfigured in between, and save the evolution of the pool
+    to fuzz/tests::fuzz/stats/.
+
+cargo-fuzzcheck bench tests::fuzz --{max_duration} 30
+    Mutate and run the test cases of the corpus of "tests::fuzz" for 30 seconds,
+    without keeping any new test case, then print the number of executions per
+    second and the time spent in the mutator, the sensor, and the test function.
+
+cargo-fuzzcheck covdiff tests::fuzz fuzz/tests::fuzz/corpus/a.json fuzz/tests::fuzz/corpus/b.json
+    Run both test cases once with the fuzz test "tests::fuzz" and print the
+    code regions that are covered by only one of them, grouped by file and
+    function.
+
+cargo-fuzzcheck merge tests::fuzz machine1/corpus machine2/corpus
+    Run the test cases of both corpora once with the fuzz test "tests::fuzz"
+    and save those that the pool keeps to fuzz/tests::fuzz/merged_corpus/,
+    which must be empty.
+
+cargo-fuzzcheck tests::fuzz --{cov_include} "src/parser/**" --{cov_exclude} "**/tests.rs" --{print_cov}
+    Print the files and functions whose code coverage would be observed when
+    fuzzing "tests::fuzz" with only the files of src/parser/ that are not named
+    tests.rs, then exit. Remove --{print_cov} to start fuzzing.
 "#,
         minify = COMMAND_MINIFY_INPUT,
+        analyze = COMMAND_ANALYZE,
+        repool = COMMAND_REPOOL,
+        record_observations = RECORD_OBSERVATIONS_FLAG,
+        max_iterations = MAX_ITERATIONS_FLAG,
         input_file = INPUT_FILE_FLAG,
         max_cplx = MAX_INPUT_CPLX_FLAG,
         out_corpus = OUT_CORPUS_FLAG,
+        max_duration = MAX_DURATION_FLAG,
+        no_progress = STOP_AFTER_NO_PROGRESS_FLAG,
+        incoming = INCOMING_CORPUS_FLAG,
+        preserve_failure = PRESERVE_FAILURE_FLAG,
+        exit_code_policy = EXIT_CODE_POLICY_FLAG,
+        exit = EXIT_CODE_POLICY_EXIT,
+        cov_include = COVERAGE_INCLUDE_FLAG,
+        cov_exclude = COVERAGE_EXCLUDE_FLAG,
+        print_cov = PRINT_COVERAGE_FUNCTIONS_FLAG,
     )
     .as_str();
     help
@@ -452,3 +1131,291 @@ impl From<Fail> for ArgumentsError {
         Self::Parsing(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[coverage(off)]
+    fn parse(args: &[&str]) -> Arguments {
+        let matches = options_parser().parse(args).unwrap();
+        Arguments::from_matches(&matches, false).unwrap()
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_max_cplx_flags() {
+        let args = parse(&[]);
+        assert_eq!(args.max_input_cplx, DefaultArguments::default().max_input_cplx);
+        assert_eq!(args.max_arbitrary_cplx, args.max_input_cplx);
+
+        let args = parse(&["--max-cplx", "100"]);
+        assert_eq!(args.max_input_cplx, 100.);
+        assert_eq!(args.max_arbitrary_cplx, 100.);
+
+        let args = parse(&["--max-cplx", "100", "--max-arbitrary-cplx", "10"]);
+        assert_eq!(args.max_input_cplx, 100.);
+        assert_eq!(args.max_arbitrary_cplx, 10.);
+
+        let args = parse(&["--max-arbitrary-cplx", "10", "--max-mutate-cplx", "1000"]);
+        assert_eq!(args.max_input_cplx, 1000.);
+        assert_eq!(args.max_arbitrary_cplx, 10.);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_exit_code_policy_flag() {
+        assert_eq!(parse(&[]).exit_code_policy, ExitCodePolicy::Return);
+        assert_eq!(
+            parse(&["--exit-code-policy", "return"]).exit_code_policy,
+            ExitCodePolicy::Return
+        );
+        assert_eq!(
+            parse(&["--exit-code-policy", "exit"]).exit_code_policy,
+            ExitCodePolicy::Exit
+        );
+
+        let matches = options_parser().parse(["--exit-code-policy", "explode"]).unwrap();
+        assert!(matches!(
+            Arguments::from_matches(&matches, false),
+            Err(ArgumentsError::Validation(_))
+        ));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_incoming_corpus_flag() {
+        assert_eq!(parse(&[]).incoming_corpus, None);
+        assert_eq!(
+            parse(&["--incoming-corpus", "fuzz/incoming"]).incoming_corpus,
+            Some(PathBuf::from("fuzz/incoming"))
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_stats_socket_flag() {
+        assert_eq!(parse(&[]).stats_socket, None);
+        assert_eq!(
+            parse(&["--stats-socket", "127.0.0.1:9000"]).stats_socket.as_deref(),
+            Some("127.0.0.1:9000")
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_resume_flag() {
+        assert!(!parse(&[]).resume);
+        assert!(parse(&["--resume"]).resume);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_dedup_inputs_flag() {
+        assert!(!parse(&[]).deduplicate_inputs);
+        assert!(parse(&["--dedup-inputs"]).deduplicate_inputs);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_no_adaptive_mutations_flag() {
+        assert!(parse(&[]).adaptive_mutations);
+        assert!(!parse(&["--no-adaptive-mutations"]).adaptive_mutations);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_fork_flag() {
+        assert!(!parse(&[]).fork);
+        assert!(parse(&["--fork"]).fork);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_track_provenance_flag() {
+        assert!(!parse(&[]).track_provenance);
+        assert!(parse(&["--track-provenance"]).track_provenance);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_record_observations_flag() {
+        assert!(!parse(&[]).record_observations);
+        assert!(parse(&["--record-observations"]).record_observations);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_check_stability_flag() {
+        assert!(!parse(&[]).check_stability);
+        assert!(parse(&["--check-stability"]).check_stability);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_observation_sampling_flag() {
+        assert_eq!(parse(&[]).observation_sampling, 1);
+        assert_eq!(parse(&["--observation-sampling", "16"]).observation_sampling, 16);
+
+        for invalid in ["0", "often"] {
+            let matches = options_parser().parse(["--observation-sampling", invalid]).unwrap();
+            assert!(matches!(
+                Arguments::from_matches(&matches, false),
+                Err(ArgumentsError::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_coverage_filter_flags() {
+        let args = parse(&[]);
+        assert!(args.coverage_include.is_empty());
+        assert!(args.coverage_exclude.is_empty());
+        assert!(!args.print_coverage_functions);
+
+        let args = parse(&[
+            "--coverage-include",
+            "src/parser/**",
+            "--coverage-include",
+            "my_crate::lexer::*",
+            "--coverage-exclude",
+            "**/tests.rs",
+            "--print-coverage-functions",
+        ]);
+        assert_eq!(args.coverage_include, vec!["src/parser/**", "my_crate::lexer::*"]);
+        assert_eq!(args.coverage_exclude, vec!["**/tests.rs"]);
+        assert!(args.print_coverage_functions);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_corpus_tag_flags() {
+        let args = parse(&[]);
+        assert!(args.corpus_with_tags.is_empty());
+        assert!(args.corpus_without_tags.is_empty());
+
+        let args = parse(&[
+            "--corpus-with-tag",
+            "slow",
+            "--corpus-without-tag",
+            "test_failure",
+            "--corpus-without-tag",
+            "timeout",
+        ]);
+        assert_eq!(args.corpus_with_tags, vec!["slow"]);
+        assert_eq!(args.corpus_without_tags, vec!["test_failure", "timeout"]);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_preserve_failure_flag() {
+        assert!(!parse(&[]).preserve_failure);
+        assert!(parse(&["--preserve-failure"]).preserve_failure);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_stop_after_no_progress_flag() {
+        assert_eq!(parse(&[]).stop_after_no_progress, None);
+        assert_eq!(
+            parse(&["--stop-after-no-progress", "5000"]).stop_after_no_progress,
+            Some(NoProgressBudget::Iterations(5000))
+        );
+        assert_eq!(
+            parse(&["--stop-after-no-progress", "10m"]).stop_after_no_progress,
+            Some(NoProgressBudget::Duration(Duration::from_secs(600)))
+        );
+        assert_eq!(
+            NoProgressBudget::parse("30s"),
+            Some(NoProgressBudget::Duration(Duration::from_secs(30)))
+        );
+        assert_eq!(NoProgressBudget::parse("2h").unwrap().to_string(), "7200s");
+        assert_eq!(NoProgressBudget::parse("ten"), None);
+        assert_eq!(NoProgressBudget::parse("m"), None);
+        assert_eq!(NoProgressBudget::parse(""), None);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_analyze_command() {
+        assert!(matches!(parse(&[]).command, FuzzerCommand::Fuzz));
+        assert!(matches!(
+            parse(&["--command", "analyze"]).command,
+            FuzzerCommand::Analyze
+        ));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_repool_command() {
+        assert!(matches!(parse(&["--command", "repool"]).command, FuzzerCommand::Repool));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_bench_command() {
+        assert!(matches!(parse(&["--command", "bench"]).command, FuzzerCommand::Bench));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_covdiff_command() {
+        match parse(&[
+            "--command",
+            "covdiff",
+            "--input-file",
+            "a.json",
+            "--input-file",
+            "b.json",
+        ])
+        .command
+        {
+            FuzzerCommand::CoverageDiff { input_files } => {
+                assert_eq!(input_files, [PathBuf::from("a.json"), PathBuf::from("b.json")])
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+        let matches = options_parser()
+            .parse(["--command", "covdiff", "--input-file", "a.json"])
+            .unwrap();
+        assert!(matches!(
+            Arguments::from_matches(&matches, false),
+            Err(ArgumentsError::Validation(_))
+        ));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_merge_command() {
+        match parse(&["--command", "merge", "--merge-corpus", "a", "--merge-corpus", "b"]).command {
+            FuzzerCommand::Merge { corpora } => assert_eq!(corpora, [PathBuf::from("a"), PathBuf::from("b")]),
+            command => panic!("unexpected command {:?}", command),
+        }
+        let matches = options_parser().parse(["--command", "merge"]).unwrap();
+        assert!(matches!(
+            Arguments::from_matches(&matches, false),
+            Err(ArgumentsError::Validation(_))
+        ));
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn test_corpus_naming_flag() {
+        assert_eq!(parse(&[]).corpus_naming, CorpusNamingPolicy::ContentHash);
+        assert_eq!(
+            parse(&["--corpus-naming", "hash"]).corpus_naming,
+            CorpusNamingPolicy::ContentHash
+        );
+        assert_eq!(
+            parse(&["--corpus-naming", "cplx-hash"]).corpus_naming,
+            CorpusNamingPolicy::ComplexityAndHash
+        );
+
+        let matches = options_parser().parse(["--corpus-naming", "random"]).unwrap();
+        assert!(matches!(
+            Arguments::from_matches(&matches, false),
+            Err(ArgumentsError::Validation(_))
+        ));
+    }
+}
@@ -7,6 +7,11 @@ pub struct FuzzerStats {
     pub total_number_of_runs: usize,
     pub number_of_runs_since_last_reset_time: usize,
     pub exec_per_s: usize,
+    /// The percentage of the code coverage counters that behave deterministically, if it is checked
+    pub stability: Option<f64>,
+    /// The number of test cases whose observations were not analyzed by the pools, because they were identical to
+    /// previous observations, while fuzzing
+    pub skipped_observations: Option<usize>,
 }
 
 #[derive(Clone, Copy)]
@@ -19,7 +24,48 @@ pub enum FuzzerEvent {
     Done,
     Replace(usize, usize),
     DidReadCorpus,
+    SkippedCorpusFiles(usize),
+    /// Some files of the input corpus cannot be read, cannot be decoded by the serializer, or are invalid
+    /// according to the mutator
+    SkippedInvalidCorpusFiles(usize),
+    /// Some test cases were read from the incoming corpus while fuzzing
+    ReadIncomingCorpus(usize),
+    /// Some of the initial inputs given to the fuzzer builder are invalid according to the mutator
+    SkippedInitialInputs(usize),
     CaughtSignal(i32),
+    /// A sanitizer, such as AddressSanitizer, reported an error and is about to terminate the process
+    SanitizerReport,
     TestFailure,
+    /// The filter mutators rejected most of the values they generated: (number of rejected values, total number of values)
+    HighFilterRejectionRate(u64, u64),
     None,
 }
+
+/// The exit code of the fuzzer process.
+///
+/// When the [exit code policy](crate::arg::ExitCodePolicy) is `Exit`, it is also the exit code of `cargo fuzzcheck`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationStatus {
+    /// The fuzzer ran to completion without finding any test failure
+    Success = 0,
+    /// The test function crashed the process, for example because of a stack overflow
+    Crash = 1,
+    /// The fuzzer found a failing test case
+    TestFailure = 2,
+    Unknown = 3,
+    /// The arguments given to the fuzzer are invalid, or the fuzz test could not be found
+    ConfigurationError = 4,
+}
+
+/// The name of the environment variable holding the path of the file where the fuzzer writes
+/// its [`TerminationStatus`] before exiting.
+///
+/// It is set by `cargo fuzzcheck`, which cannot otherwise know the exit code of the fuzzer,
+/// since the fuzz test is launched by `cargo test`.
+pub const TERMINATION_STATUS_FILE_ENV: &str = "FUZZCHECK_TERMINATION_STATUS_FILE";
+
+/// The name of the file, in the folder of each fuzzing session, containing the statistics reported by the fuzzer
+/// as JSON lines.
+///
+/// It is read by the `plot` subcommand of `cargo fuzzcheck`.
+pub const STATS_JSONL_FILE: &str = "events.jsonl";
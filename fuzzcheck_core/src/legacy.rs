@@ -0,0 +1,277 @@
+/*!
+Adapters for mutators and serializers written for the legacy `fuzzcheck_traits`,
+`fuzzcheck_mutators`, and `fuzzcheck_serializer` crates.
+
+Before they were merged into this crate, the [`Mutator`] and [`Serializer`] traits
+were defined in separate crates, with slightly different signatures. In particular, the legacy
+`validate_value` method returned both the cache and the mutation step of the value, and the legacy
+`Serializer` trait had an additional `is_utf8` method.
+
+This module is the only definition of the legacy traits, and it is re-exported as `fuzzcheck::legacy`.
+To migrate a crate which depends on the legacy traits incrementally:
+1. depend on `fuzzcheck_core` and replace the `fuzzcheck_traits::Mutator` trait by [`LegacyMutator`] and `fuzzcheck_serializer::Serializer`
+   by [`LegacySerializer`]. Their signatures are the same as the legacy ones.
+2. replace the `#[no_coverage]` attributes by `#[coverage(off)]`, which requires a nightly compiler, or remove them
+3. wrap the mutators and serializers in [`LegacyMutatorAdapter`] and [`LegacySerializerAdapter`]
+   to use them with fuzzcheck.
+
+```
+use fuzzcheck_core::legacy::{LegacyMutator, LegacyMutatorAdapter};
+use fuzzcheck_core::Mutator;
+
+// A mutator written for the legacy trait
+struct FlipMutator;
+
+impl LegacyMutator<bool> for FlipMutator {
+    type Cache = ();
+    type MutationStep = bool;
+    type ArbitraryStep = bool;
+    type UnmutateToken = ();
+
+    fn default_arbitrary_step(&self) -> bool { false }
+    fn validate_value(&self, _value: &bool) -> Option<((), bool)> { Some(((), false)) }
+    fn max_complexity(&self) -> f64 { 1.0 }
+    fn min_complexity(&self) -> f64 { 1.0 }
+    fn complexity(&self, _value: &bool, _cache: &()) -> f64 { 1.0 }
+    fn ordered_arbitrary(&self, step: &mut bool, _max_cplx: f64) -> Option<(bool, f64)> {
+        if *step { None } else { *step = true; Some((false, 1.0)) }
+    }
+    fn random_arbitrary(&self, _max_cplx: f64) -> (bool, f64) { (true, 1.0) }
+    fn ordered_mutate(&self, value: &mut bool, _cache: &mut (), step: &mut bool, _max_cplx: f64) -> Option<((), f64)> {
+        if *step { None } else { *step = true; *value = !*value; Some(((), 1.0)) }
+    }
+    fn random_mutate(&self, value: &mut bool, _cache: &mut (), _max_cplx: f64) -> ((), f64) {
+        *value = !*value;
+        ((), 1.0)
+    }
+    fn unmutate(&self, value: &mut bool, _cache: &mut (), _t: ()) { *value = !*value }
+}
+
+// it can then be used wherever a `Mutator<bool>` is expected
+let mutator = LegacyMutatorAdapter::new(FlipMutator);
+assert!(mutator.is_valid(&true));
+```
+*/
+
+use alloc::vec::Vec;
+use core::any::Any;
+
+use crate::{Mutator, Serializer, SubValueProvider};
+
+/// The `Mutator` trait of the legacy `fuzzcheck_traits` crate.
+///
+/// Wrap an implementation of this trait in a [`LegacyMutatorAdapter`] to use it as a [`Mutator`].
+pub trait LegacyMutator<Value: Clone> {
+    type Cache: Clone;
+    type MutationStep: Clone;
+    type ArbitraryStep: Clone;
+    type UnmutateToken;
+
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep;
+
+    fn validate_value(&self, value: &Value) -> Option<(Self::Cache, Self::MutationStep)>;
+
+    fn max_complexity(&self) -> f64;
+
+    fn min_complexity(&self) -> f64;
+
+    fn complexity(&self, value: &Value, cache: &Self::Cache) -> f64;
+
+    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(Value, f64)>;
+
+    fn random_arbitrary(&self, max_cplx: f64) -> (Value, f64);
+
+    fn ordered_mutate(
+        &self,
+        value: &mut Value,
+        cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)>;
+
+    fn random_mutate(&self, value: &mut Value, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64);
+
+    fn unmutate(&self, value: &mut Value, cache: &mut Self::Cache, t: Self::UnmutateToken);
+}
+
+/// A [`Mutator`] wrapping a [`LegacyMutator`].
+///
+/// Since legacy mutators do not know about subvalues, the adapter does not use the subvalue provider
+/// given to [`ordered_mutate`](Mutator::ordered_mutate) and does not visit any subvalue in
+/// [`visit_subvalues`](Mutator::visit_subvalues). Its [global search space complexity](Mutator::global_search_space_complexity)
+/// is assumed to be the maximum complexity of the legacy mutator.
+pub struct LegacyMutatorAdapter<M> {
+    pub mutator: M,
+}
+
+impl<M> LegacyMutatorAdapter<M> {
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    pub fn new(mutator: M) -> Self {
+        Self { mutator }
+    }
+}
+
+impl<T, M> Mutator<T> for LegacyMutatorAdapter<M>
+where
+    T: Clone + 'static,
+    M: LegacyMutator<T> + 'static,
+{
+    #[doc(hidden)]
+    type Cache = M::Cache;
+    #[doc(hidden)]
+    type MutationStep = M::MutationStep;
+    #[doc(hidden)]
+    type ArbitraryStep = M::ArbitraryStep;
+    #[doc(hidden)]
+    type UnmutateToken = M::UnmutateToken;
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn initialize(&self) {}
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
+        self.mutator.default_arbitrary_step()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn is_valid(&self, value: &T) -> bool {
+        self.mutator.validate_value(value).is_some()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn validate_value(&self, value: &T) -> Option<Self::Cache> {
+        self.mutator.validate_value(value).map(
+            #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+            |(cache, _)| cache,
+        )
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn default_mutation_step(&self, value: &T, _cache: &Self::Cache) -> Self::MutationStep {
+        let (_, step) = self
+            .mutator
+            .validate_value(value)
+            .expect("the value given to default_mutation_step was not validated by the legacy mutator");
+        step
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.mutator.max_complexity()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn max_complexity(&self) -> f64 {
+        self.mutator.max_complexity()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn min_complexity(&self) -> f64 {
+        self.mutator.min_complexity()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn complexity(&self, value: &T, cache: &Self::Cache) -> f64 {
+        self.mutator.complexity(value, cache)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(T, f64)> {
+        self.mutator.ordered_arbitrary(step, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn random_arbitrary(&self, max_cplx: f64) -> (T, f64) {
+        self.mutator.random_arbitrary(max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn ordered_mutate(
+        &self,
+        value: &mut T,
+        cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        _subvalue_provider: &dyn SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)> {
+        self.mutator.ordered_mutate(value, cache, step, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn random_mutate(&self, value: &mut T, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        self.mutator.random_mutate(value, cache, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn unmutate(&self, value: &mut T, cache: &mut Self::Cache, t: Self::UnmutateToken) {
+        self.mutator.unmutate(value, cache, t)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn visit_subvalues<'a>(&self, _value: &'a T, _cache: &'a Self::Cache, _visit: &mut dyn FnMut(&'a dyn Any, f64)) {}
+}
+
+/// The `Serializer` trait of the legacy `fuzzcheck_serializer` crate.
+///
+/// Wrap an implementation of this trait in a [`LegacySerializerAdapter`] to use it as a [`Serializer`].
+pub trait LegacySerializer {
+    type Value;
+
+    fn is_utf8(&self) -> bool;
+
+    fn extension(&self) -> &str;
+
+    #[allow(clippy::wrong_self_convention)]
+    fn from_data(&self, data: &[u8]) -> Option<Self::Value>;
+
+    fn to_data(&self, value: &Self::Value) -> Vec<u8>;
+}
+
+/// A [`Serializer`] wrapping a [`LegacySerializer`]
+pub struct LegacySerializerAdapter<S> {
+    pub serializer: S,
+}
+
+impl<S> LegacySerializerAdapter<S> {
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    pub fn new(serializer: S) -> Self {
+        Self { serializer }
+    }
+}
+
+impl<S> Serializer for LegacySerializerAdapter<S>
+where
+    S: LegacySerializer,
+{
+    type Value = S::Value;
+
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn extension(&self) -> &str {
+        self.serializer.extension()
+    }
+
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn from_data(&self, data: &[u8]) -> Option<Self::Value> {
+        self.serializer.from_data(data)
+    }
+
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn to_data(&self, value: &Self::Value) -> Vec<u8> {
+        self.serializer.to_data(value)
+    }
+}
@@ -0,0 +1,31 @@
+//! The core traits of [fuzzcheck](https://docs.rs/fuzzcheck): [`Mutator`], [`MutatorWrapper`], [`Serializer`], and [`SubValueProvider`].
+//!
+//! They are re-exported by fuzzcheck, which should be used to write and launch fuzz tests. This crate is
+//! useful to implement mutators or serializers for your types without depending on the fuzzing engine.
+//! Unlike fuzzcheck, it builds on stable Rust and does not depend on the standard library, only on `alloc`.
+//!
+//! The `coverage_attribute` feature, which requires a nightly compiler, excludes the default methods
+//! of the traits from code coverage instrumentation. The `std` feature adds the methods of [`Serializer`] that
+//! stream test cases through `std::io`. Both are enabled by fuzzcheck.
+
+#![no_std]
+#![cfg_attr(feature = "coverage_attribute", feature(coverage_attribute))]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod legacy;
+mod mutator;
+mod mutator_wrapper;
+mod serializer;
+mod subvalue_provider;
+
+#[doc(inline)]
+pub use mutator::Mutator;
+#[doc(inline)]
+pub use mutator_wrapper::MutatorWrapper;
+#[doc(inline)]
+pub use serializer::Serializer;
+#[doc(inline)]
+pub use subvalue_provider::{EmptySubValueProvider, Generation, SubValueProvider, SubValueProviderId};
@@ -0,0 +1,144 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::Any;
+
+use crate::{Mutator, SubValueProvider};
+
+/**
+ A trait for types that are basic wrappers over a mutator, such as `Box<M>`.
+
+ Such wrapper types automatically implement the [`Mutator`] trait.
+*/
+pub trait MutatorWrapper {
+    type Wrapped;
+
+    fn wrapped_mutator(&self) -> &Self::Wrapped;
+}
+
+impl<T: Clone + 'static, W, M> Mutator<T> for M
+where
+    M: MutatorWrapper<Wrapped = W>,
+    W: Mutator<T>,
+    Self: 'static,
+{
+    #[doc(hidden)]
+    type Cache = W::Cache;
+    #[doc(hidden)]
+    type MutationStep = W::MutationStep;
+    #[doc(hidden)]
+    type ArbitraryStep = W::ArbitraryStep;
+    #[doc(hidden)]
+    type UnmutateToken = W::UnmutateToken;
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn initialize(&self) {
+        self.wrapped_mutator().initialize()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn default_arbitrary_step(&self) -> Self::ArbitraryStep {
+        self.wrapped_mutator().default_arbitrary_step()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn is_valid(&self, value: &T) -> bool {
+        self.wrapped_mutator().is_valid(value)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn validate_value(&self, value: &T) -> Option<Self::Cache> {
+        self.wrapped_mutator().validate_value(value)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn default_mutation_step(&self, value: &T, cache: &Self::Cache) -> Self::MutationStep {
+        self.wrapped_mutator().default_mutation_step(value, cache)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn global_search_space_complexity(&self) -> f64 {
+        self.wrapped_mutator().global_search_space_complexity()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn max_complexity(&self) -> f64 {
+        self.wrapped_mutator().max_complexity()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn min_complexity(&self) -> f64 {
+        self.wrapped_mutator().min_complexity()
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn complexity(&self, value: &T, cache: &Self::Cache) -> f64 {
+        self.wrapped_mutator().complexity(value, cache)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn ordered_arbitrary(&self, step: &mut Self::ArbitraryStep, max_cplx: f64) -> Option<(T, f64)> {
+        self.wrapped_mutator().ordered_arbitrary(step, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn random_arbitrary(&self, max_cplx: f64) -> (T, f64) {
+        self.wrapped_mutator().random_arbitrary(max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn ordered_mutate(
+        &self,
+        value: &mut T,
+        cache: &mut Self::Cache,
+        step: &mut Self::MutationStep,
+        subvalue_provider: &dyn SubValueProvider,
+        max_cplx: f64,
+    ) -> Option<(Self::UnmutateToken, f64)> {
+        self.wrapped_mutator()
+            .ordered_mutate(value, cache, step, subvalue_provider, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn random_mutate(&self, value: &mut T, cache: &mut Self::Cache, max_cplx: f64) -> (Self::UnmutateToken, f64) {
+        self.wrapped_mutator().random_mutate(value, cache, max_cplx)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn unmutate(&self, value: &mut T, cache: &mut Self::Cache, t: Self::UnmutateToken) {
+        self.wrapped_mutator().unmutate(value, cache, t)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn visit_subvalues<'a>(&self, value: &'a T, cache: &'a Self::Cache, visit: &mut dyn FnMut(&'a dyn Any, f64)) {
+        self.wrapped_mutator().visit_subvalues(value, cache, visit)
+    }
+
+    #[doc(hidden)]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn mutation_operators(&self) -> Vec<&'static str> {
+        self.wrapped_mutator().mutation_operators()
+    }
+}
+
+impl<M> MutatorWrapper for Box<M> {
+    type Wrapped = M;
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn wrapped_mutator(&self) -> &Self::Wrapped {
+        self.as_ref()
+    }
+}
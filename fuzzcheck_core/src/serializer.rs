@@ -0,0 +1,71 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{Read, Result, Write};
+
+/// A [Serializer] is used to encode and decode test cases into bytes.
+///
+/// It is used to transfer test cases between the corpus on the file system and the fuzzer’s storage.
+pub trait Serializer {
+    /// The type of the value to be serialized
+    type Value;
+
+    /// The extension of the file containing the serialized value
+    fn extension(&self) -> &str;
+
+    #[allow(clippy::wrong_self_convention)]
+    /// Deserialize the bytes into the value.
+    ///
+    /// This method can fail by returning `None`
+    fn from_data(&self, data: &[u8]) -> Option<Self::Value>;
+
+    /// Serialize the value into bytes
+    ///
+    /// This method should never fail.
+    fn to_data(&self, value: &Self::Value) -> Vec<u8>;
+
+    /// Returns true if the serializer can decode the files of the input corpus with the given extension.
+    ///
+    /// By default, only the files with the extension [`self.extension()`](Serializer::extension) can be decoded.
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn reads_extension(&self, extension: &str) -> bool {
+        extension == self.extension()
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    /// Deserialize the content of a file with the given extension into the value.
+    ///
+    /// It is only called for the extensions that the serializer [can read](Serializer::reads_extension).
+    /// By default, it is the same as [`self.from_data(data)`](Serializer::from_data).
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn from_data_with_extension(&self, data: &[u8], extension: &str) -> Option<Self::Value> {
+        let _ = extension;
+        self.from_data(data)
+    }
+
+    /// Serialize the value into the writer.
+    ///
+    /// By default, the bytes returned by [`self.to_data(value)`](Serializer::to_data) are written all at
+    /// once. Override it to avoid holding the whole serialized value in memory, which matters for values
+    /// that are several megabytes large.
+    ///
+    /// It only fails if the writer does.
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn to_writer(&self, value: &Self::Value, writer: &mut dyn Write) -> Result<()> {
+        writer.write_all(&self.to_data(value))
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    /// Deserialize the value from the reader.
+    ///
+    /// It returns an error if the reader fails, and `Ok(None)` if the bytes cannot be decoded into a value.
+    /// By default, the whole content of the reader is read before being given to
+    /// [`self.from_data(data)`](Serializer::from_data).
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn from_reader(&self, reader: &mut dyn Read) -> Result<Option<Self::Value>> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Ok(self.from_data(&data))
+    }
+}
@@ -0,0 +1,62 @@
+use core::any::{Any, TypeId};
+
+/// Uniquely identifies a [`SubValueProvider`](crate::SubValueProvider)
+///
+/// The identifier is composed of two fields: `idx` and `generation`. At any
+/// point in time, only one subvalue provider should have a given `idx`.
+///
+/// If two subvalue providers have the same `idx` but different `generation`,
+/// then only the one with the larger generation is valid.
+#[derive(Clone, Copy)]
+pub struct SubValueProviderId {
+    pub idx: usize,
+    pub generation: Generation,
+}
+
+/// See: [`SubValueProviderId`](crate::SubValueProviderId)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Generation(pub usize);
+
+/// An object-safe trait which can give values of arbitrary types.
+///
+/// See the documentation of the [`Mutator`](crate::Mutator) trait for more information
+/// about its purpose.
+pub trait SubValueProvider {
+    /// A globally unique identifier for the subvalue provider
+    fn identifier(&self) -> SubValueProviderId;
+    /// Get a subvalue of the given type and under a certain maximum complexity.
+    ///
+    /// Returns `Some((subvalue, cplx))` or `None` if no subvalue matches the
+    /// type id or maximum complexity.
+    fn get_random_subvalue(&self, typeid: TypeId, max_cplx: f64) -> Option<(&dyn Any, f64)>;
+    /// Get a subvalue of the given type and under a certain maximum complexity.
+    ///
+    /// Each `index` points to a different subvalue. The function will automatically
+    /// increment `index` to the next valid value.
+    ///
+    /// Returns `Some((subvalue, cplx))` or `None` if no more unique subvalues
+    /// match thhe type id or maximum complexity.
+    fn get_subvalue(&self, typeid: TypeId, max_cplx: f64, index: &mut usize) -> Option<(&dyn Any, f64)>;
+}
+
+/// A [`SubValueProvider`](crate::SubValueProvider) that always return `None`
+pub struct EmptySubValueProvider;
+impl SubValueProvider for EmptySubValueProvider {
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn identifier(&self) -> SubValueProviderId {
+        SubValueProviderId {
+            idx: 0,
+            generation: Generation(0),
+        }
+    }
+
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn get_random_subvalue(&self, _typeid: TypeId, _max_cplx: f64) -> Option<(&dyn Any, f64)> {
+        None
+    }
+
+    #[cfg_attr(feature = "coverage_attribute", coverage(off))]
+    fn get_subvalue(&self, _typeid: TypeId, _max_cplx: f64, _index: &mut usize) -> Option<(&dyn Any, f64)> {
+        None
+    }
+}
@@ -1,7 +1,9 @@
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Literal, TokenStream, TokenTree};
 use syn::{DataEnum, Generics, Visibility};
 
-use crate::structs_and_enums::{CreateWrapperMutatorParams, FieldMutator, FieldMutatorKind};
+use crate::structs_and_enums::{
+    wrap_inner_mutator_for_remote, CreateWrapperMutatorParams, FieldMutator, FieldMutatorKind,
+};
 use crate::token_builder::{access_field, extend_ts, ident, join_ts, ts, TokenBuilder};
 use crate::{q, Common, MakeMutatorSettings};
 
@@ -43,26 +45,19 @@ pub(crate) fn impl_default_mutator_for_enum(
                                 j: Some(j),
                                 field: field.clone(),
                                 kind: FieldMutatorKind::Ignore,
+                                weight: None,
+                                opaque: false,
                             };
                         }
-                        let mut mutator = None;
-                        for attribute in field.attrs.iter() {
-                            match super::read_field_default_mutator_attribute(attribute) {
-                                Ok(Some(field_mutator_attribute)) => {
-                                    mutator = Some((field_mutator_attribute.ty, field_mutator_attribute.equal));
-                                }
-                                Ok(None) => {}
-                                Err(e) => {
-                                    tb.stream(e.to_compile_error());
-                                }
-                            }
-                        }
+                        let (mutator, weight, opaque) = super::read_field_attributes(tb, field);
                         if let Some(m) = mutator {
                             FieldMutator {
                                 i,
                                 j: Some(j),
                                 field: field.clone(),
                                 kind: FieldMutatorKind::Prescribed(m.0, m.1),
+                                weight,
+                                opaque,
                             }
                         } else {
                             FieldMutator {
@@ -70,6 +65,8 @@ pub(crate) fn impl_default_mutator_for_enum(
                                 j: Some(j),
                                 field: field.clone(),
                                 kind: FieldMutatorKind::Generic,
+                                weight,
+                                opaque,
                             }
                         }
                     })
@@ -103,7 +100,7 @@ pub(crate) fn impl_default_mutator_for_enum(
                                 join_ts!(variant_field_mutators.iter().filter(|mutator| {
                                     !mutator.kind.is_ignore()
                                 }), fm,
-                                    fm.mutator_stream(&cm)
+                                    fm.wrapped_mutator_stream(&cm)
                                 , separator: ",")
                             ">"
                         )
@@ -113,6 +110,89 @@ pub(crate) fn impl_default_mutator_for_enum(
         ">"
     );
 
+    let non_ignored_variants = enu
+        .variants
+        .iter()
+        .filter(|variant| !variant.attrs.iter().any(super::has_ignore_variant_attribute));
+    let variant_weights = non_ignored_variants
+        .map(|variant| super::read_variant_weight_attribute(tb, variant))
+        .collect::<Vec<_>>();
+    let is_weighted = variant_weights.iter().any(|weight| weight.is_some());
+
+    let mutator_init = ts!(
+        cm.AlternationMutator if is_weighted { "::with_weights(vec![" } else { "::new(vec![" }
+            join_ts!(enu.variants.iter().enumerate().filter(|(_, variant)| {
+                        variant.attrs.iter().all(|attr| {
+                            !super::has_ignore_variant_attribute(attr)
+                        })
+                    }), (i, variant),
+            EnumSingleVariant "::" variant.ident "("
+            if variant.fields.is_empty() {
+                TupleNMutator(0)
+            } else {
+                    ts!(
+                        TupleNMutator(variant.fields.len()) "::new("
+                            join_ts!(variant.fields.iter().enumerate(), (idx, field),
+                                field_mutators[i][idx].wrapped_mutator_expr(&cm, ts!(ident!("mutator_" enu.variants[i].ident "_" access_field(field, idx))))
+                            , separator: ",")
+                        ")"
+                   )
+            }
+            ")"
+            , separator: ",")
+        "],"
+        if is_weighted {
+            ts!("vec!["
+                join_ts!(variant_weights.iter(), weight,
+                    TokenTree::Literal(Literal::f64_suffixed(weight.unwrap_or(1.0)))
+                , separator: ",")
+            "],")
+        } else {
+            ts!()
+        }
+        format!("{:.2}", size_to_cplxity(enu.variants.len())) ")"
+    );
+
+    let (InnerMutator, mutator_init) = if let Some(remote) = &settings.remote {
+        // convert the values of the local shape into values of the remote type, variant by variant
+        let field_binding = |field: &syn::Field, idx: usize| ident!("field_" access_field(field, idx));
+        let convert = |source: &TokenStream, target: &TokenStream, wrap: &dyn Fn(TokenStream) -> TokenStream| {
+            join_ts!(&enu.variants, variant,
+                source "::" variant.ident "{"
+                    join_ts!(variant.fields.iter().enumerate(), (idx, field),
+                        access_field(field, idx) ":" field_binding(field, idx)
+                    , separator: ",")
+                "} =>" wrap(ts!(target "::" variant.ident "{"
+                    join_ts!(variant.fields.iter().enumerate(), (idx, field),
+                        access_field(field, idx) ":" cm.Clone "::clone(" field_binding(field, idx) ")"
+                    , separator: ",")
+                "}")) ","
+            )
+        };
+        let shape = ts!(enum_ident);
+        let remote_path = ts!(q!(remote));
+        wrap_inner_mutator_for_remote(
+            &cm,
+            remote,
+            enum_ident,
+            generics,
+            enu.variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter().map(|field| &field.ty)),
+            InnerMutator,
+            mutator_init,
+            ts!("match shape {" convert(&shape, &remote_path, &|value| value) "}"),
+            ts!("match remote {"
+                convert(&remote_path, &shape, &|value| ts!(cm.Some "(" value ")"))
+                // the remote enum may be non-exhaustive
+                "#[allow(unreachable_patterns)]
+                _ => " cm.None "
+            }"),
+        )
+    } else {
+        (InnerMutator, mutator_init)
+    };
+
     let params = CreateWrapperMutatorParams {
         cm: &cm,
         visibility: vis,
@@ -121,6 +201,7 @@ pub(crate) fn impl_default_mutator_for_enum(
         field_mutators: &field_mutators,
         InnerMutator: &InnerMutator,
         new_impl: &ts!("
+            #[allow(clippy::too_many_arguments)]
             #[coverage(off)]
             pub fn new("
             join_ts!(field_mutators.iter().filter(|fields|
@@ -129,27 +210,7 @@ pub(crate) fn impl_default_mutator_for_enum(
                 ident!("mutator_" enu.variants[field_mutator.i].ident "_" access_field(&field_mutator.field, field_mutator.j.unwrap())) ":" field_mutator.mutator_stream(&cm)
             , separator: ",") ") -> Self {
                 Self {
-                    mutator: " cm.AlternationMutator "::new(vec!["
-                        join_ts!(enu.variants.iter().enumerate().filter(|(_, variant)| {
-                                    variant.attrs.iter().all(|attr| {
-                                        !super::has_ignore_variant_attribute(attr)
-                                    })
-                                }), (i, variant),
-                        EnumSingleVariant "::" variant.ident "("
-                        if variant.fields.is_empty() {
-                            TupleNMutator(0)
-                        } else {
-                                ts!(
-                                    TupleNMutator(variant.fields.len()) "::new("
-                                        join_ts!(variant.fields.iter().enumerate(), (idx, field),
-                                            ident!("mutator_" enu.variants[i].ident "_" access_field(field, idx))
-                                        , separator: ",")
-                                    ")"
-                               )
-                        }
-                        ")"
-                        , separator: ",")
-                    "], " format!("{:.2}", size_to_cplxity(enu.variants.len())) ")
+                    mutator: " mutator_init "
                 }
             }"
         ),
@@ -167,31 +228,28 @@ pub(crate) fn impl_basic_enum_structure(tb: &mut TokenBuilder, enum_ident: &Iden
 
     let BasicEnumStructure = ts!(cm.mutators "::enums::BasicEnumStructure");
 
-    let variants_init = enu
-        .variants
-        .iter()
-        .map(|variant| match variant.fields {
-            syn::Fields::Named(_) => ts!("{ }"),
-            syn::Fields::Unnamed(_) => ts!("( )"),
-            syn::Fields::Unit => ts!(),
-        })
-        .collect::<Box<_>>();
+    // the variants may be declared as `A`, `A()`, or `A {}`
+    let variant_init = |variant: &syn::Variant| match variant.fields {
+        syn::Fields::Named(_) => ts!("{ }"),
+        syn::Fields::Unnamed(_) => ts!("( )"),
+        syn::Fields::Unit => ts!(),
+    };
 
+    // the variant indices are independent of the explicit discriminants of the enum, if any:
+    // the non-ignored variants come first, followed by the ignored ones
     let (ignored, not_ignored): (Vec<_>, Vec<_>) = enu
         .variants
         .iter()
         .partition(|variant| variant.attrs.iter().any(super::has_ignore_variant_attribute));
+    let variants = not_ignored.iter().chain(ignored.iter()).collect::<Vec<_>>();
 
     extend_ts!(tb,
         "impl" BasicEnumStructure "for" enum_ident "{
             #[coverage(off)]
             fn from_variant_index(variant_index: usize) -> Self {
                 match variant_index {"
-                join_ts!(not_ignored.iter().enumerate(), (i, variant),
-                    i "=>" enum_ident "::" variant.ident variants_init[i] ","
-                )
-                join_ts!(ignored.iter().enumerate(), (i, variant),
-                    (i + not_ignored.len()) "=>" enum_ident "::" variant.ident variants_init[i] ","
+                join_ts!(variants.iter().enumerate(), (i, variant),
+                    i "=>" enum_ident "::" variant.ident variant_init(variant) ","
                 )
                 "
                     _ => unreachable!()
@@ -200,11 +258,8 @@ pub(crate) fn impl_basic_enum_structure(tb: &mut TokenBuilder, enum_ident: &Iden
             #[coverage(off)]
             fn get_variant_index(&self) -> usize {
                 match self {"
-                join_ts!(not_ignored.iter().enumerate(), (i, variant),
-                    enum_ident "::" variant.ident variants_init[i] "=>" i ","
-                )
-                join_ts!(ignored.iter().enumerate(), (i, variant),
-                    enum_ident "::" variant.ident variants_init[i + not_ignored.len()] "=>" i + not_ignored.len() ","
+                join_ts!(variants.iter().enumerate(), (i, variant),
+                    enum_ident "::" variant.ident variant_init(variant) "=>" i ","
                 )
                 "}
             }
@@ -218,6 +273,22 @@ pub(crate) fn impl_default_mutator_for_basic_enum(tb: &mut TokenBuilder, enum_id
 
     let cm = Common::new(0);
 
+    for variant in enu.variants.iter() {
+        if let Some(attribute) = variant
+            .attrs
+            .iter()
+            .find(|attribute| attribute.path.is_ident("variant_weight"))
+        {
+            tb.stream(
+                syn::Error::new_spanned(
+                    attribute,
+                    "The `variant_weight` attribute is not supported for enums whose variants have no fields",
+                )
+                .to_compile_error(),
+            );
+        }
+    }
+
     let BasicEnumMutator = ts!(cm.mutators "::enums::BasicEnumMutator");
 
     let count_non_ignored = enu
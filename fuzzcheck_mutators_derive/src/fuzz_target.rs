@@ -0,0 +1,79 @@
+use proc_macro2::{Delimiter, Ident, TokenStream, TokenTree};
+use syn::parse::{Parse, ParseStream};
+use syn::{Attribute, Error, Token, Visibility};
+
+use crate::q;
+use crate::token_builder::{extend_ts, TokenBuilder};
+
+/// A test function annotated with `#[fuzz_target]`.
+///
+/// The `full` feature of `syn` is not enabled, so the function is only split into its attributes,
+/// its name, its signature, and its body.
+pub struct FuzzTarget {
+    attrs: Vec<Attribute>,
+    ident: Ident,
+    /// The parameters and return type of the function
+    signature: TokenStream,
+    body: TokenTree,
+}
+
+impl Parse for FuzzTarget {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let _ = input.parse::<Visibility>()?;
+        let _ = input.parse::<Token![fn]>()?;
+        let ident = input.parse::<Ident>()?;
+        if input.peek(Token![<]) {
+            return Err(Error::new(
+                input.span(),
+                "fuzz_target cannot be used on generic functions",
+            ));
+        }
+        let mut signature = TokenStream::new();
+        loop {
+            let tt = input.parse::<TokenTree>()?;
+            match &tt {
+                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace && input.is_empty() => {
+                    return Ok(FuzzTarget {
+                        attrs,
+                        ident,
+                        signature,
+                        body: tt,
+                    });
+                }
+                _ => signature.extend(Some(tt)),
+            }
+        }
+    }
+}
+
+/// Replace the test function by a `#[test]` of the same name that fuzzes it with the default options.
+pub fn make_fuzz_target(tb: &mut TokenBuilder, target: FuzzTarget) {
+    let FuzzTarget {
+        attrs,
+        ident,
+        signature,
+        body,
+    } = target;
+    let mut inner_fn = TokenBuilder::default();
+    for attr in &attrs {
+        extend_ts!(&mut inner_fn, q!(attr));
+    }
+    extend_ts!(&mut inner_fn, "fn" ident signature body);
+    let inner_fn = inner_fn.finish();
+
+    extend_ts!(tb,
+        "#[cfg(all(fuzzing, test))]
+        #[test]
+        fn" ident "() {"
+            inner_fn
+            "let result = fuzzcheck::fuzz_test(" ident ")
+                .default_mutator()
+                .serde_serializer()
+                .default_sensor_and_pool()
+                .arguments_from_cargo_fuzzcheck()
+                .launch();
+            assert!(!result.found_test_failure, " q!(format!("the fuzzer found a failing test case for `{ident}`")) ");
+        }"
+    );
+}
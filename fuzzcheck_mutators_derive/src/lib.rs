@@ -7,10 +7,13 @@
 use proc_macro2::{Ident, Literal, TokenStream, TokenTree};
 use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
-use syn::{parenthesized, parse2, parse_macro_input, token, Attribute, DeriveInput, Error, LitBool, Token};
+use syn::{
+    parenthesized, parse2, parse_macro_input, token, Attribute, DeriveInput, Error, Field, Generics, LitBool, Token,
+};
 use token_builder::{extend_ts, ident, ts, TokenBuilder};
 
 mod enums;
+mod fuzz_target;
 mod single_variant;
 mod structs_and_enums;
 
@@ -27,7 +30,8 @@ pub(crate) use q;
 
 /// Create a tuple-mutatpr for of the given arity.
 ///
-/// This function can only be used within fuzzcheck itself.
+/// This function can only be used within fuzzcheck itself, which defines the tuple mutators of
+/// up to 20 elements.
 ///
 /// ```ignore
 /// make_basic_tuple_mutator!(2);
@@ -82,18 +86,36 @@ pub fn make_mutator(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     derive_default_mutator_(settings).into()
 }
 
-#[proc_macro_derive(DefaultMutator, attributes(field_mutator, ignore_variant))]
+#[proc_macro_derive(
+    DefaultMutator,
+    attributes(field_mutator, frozen, ignore_variant, opaque, range, variant_weight, weight)
+)]
 pub fn derive_default_mutator(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let settings = MakeMutatorSettings {
         name: None,
         recursive: false,
         default: true,
+        remote: None,
         ty: parse_macro_input!(item as DeriveInput),
     };
     derive_default_mutator_(settings).into()
 }
 
 #[doc(hidden)]
+#[proc_macro_attribute]
+pub fn fuzz_target(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    if !attr.is_empty() {
+        let attr = proc_macro2::TokenStream::from(attr);
+        return Error::new_spanned(attr, "fuzz_target does not take any argument")
+            .to_compile_error()
+            .into();
+    }
+    let target = parse_macro_input!(item as fuzz_target::FuzzTarget);
+    let mut tb = TokenBuilder::default();
+    fuzz_target::make_fuzz_target(&mut tb, target);
+    tb.finish().into()
+}
+
 #[proc_macro]
 pub fn make_single_variant_mutator(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = proc_macro2::TokenStream::from(item);
@@ -104,13 +126,33 @@ pub fn make_single_variant_mutator(item: proc_macro::TokenStream) -> proc_macro:
 Actual implementations
 */
 
-fn derive_default_mutator_(settings: MakeMutatorSettings) -> proc_macro2::TokenStream {
+/// The largest number of fields of a struct or enum variant supported by `#[derive(DefaultMutator)]`,
+/// which is the arity of the largest tuple mutator defined in `fuzzcheck::mutators::tuples`
+const MAX_NBR_FIELDS: usize = 20;
+
+fn derive_default_mutator_(mut settings: MakeMutatorSettings) -> proc_macro2::TokenStream {
     let mut tb = TokenBuilder::default();
+    require_static_lifetimes(&mut settings.ty.generics);
     let item = settings.ty.clone();
+    if settings.remote.is_some() {
+        if let Err(e) = check_remote_settings(&settings) {
+            return e.to_compile_error();
+        }
+        // the shape of the remote type is declared locally, so that a mutator can be derived for it
+        extend_ts!(&mut tb,
+            "#[derive(" Common::new(0).Clone ")]"
+            q!(without_fuzzcheck_attributes(&item))
+        );
+    }
     match item.data {
         syn::Data::Struct(s) => {
             let nbr_fields = s.fields.len();
-            if nbr_fields == 0 {
+            if nbr_fields > MAX_NBR_FIELDS {
+                extend_ts!(
+                    &mut tb,
+                    "compile_error!(" q!(format!("The DefaultMutator derive proc_macro does not work on structs with more than {MAX_NBR_FIELDS} fields.")) ");"
+                );
+            } else if nbr_fields == 0 {
                 tuples::impl_default_mutator_for_struct_with_0_field(&mut tb, &item.ident, &s);
             } else {
                 tuples::impl_tuple_structure_trait(&mut tb, &item.ident, &item.generics, &s);
@@ -118,7 +160,12 @@ fn derive_default_mutator_(settings: MakeMutatorSettings) -> proc_macro2::TokenS
             }
         }
         syn::Data::Enum(e) => {
-            if e.variants.iter().any(|variant| match &variant.fields {
+            if e.variants.iter().any(|variant| variant.fields.len() > MAX_NBR_FIELDS) {
+                extend_ts!(
+                    &mut tb,
+                    "compile_error!(" q!(format!("The DefaultMutator derive proc_macro does not work on enums whose variants have more than {MAX_NBR_FIELDS} fields.")) ");"
+                );
+            } else if e.variants.iter().any(|variant| match &variant.fields {
                 syn::Fields::Named(fs) => !fs.named.is_empty(),
                 syn::Fields::Unnamed(fs) => !fs.unnamed.is_empty(),
                 syn::Fields::Unit => false,
@@ -148,8 +195,9 @@ fn derive_default_mutator_(settings: MakeMutatorSettings) -> proc_macro2::TokenS
 
 fn make_single_variant_mutator_(item: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
     match parse2::<DeriveInput>(item) {
-        Ok(e) => match e.data {
+        Ok(mut e) => match e.data {
             syn::Data::Enum(enum_data) => {
+                require_static_lifetimes(&mut e.generics);
                 let mut tb = TokenBuilder::default();
                 single_variant::make_single_variant_mutator(&mut tb, &e.ident, &e.generics, &e.vis, &enum_data);
                 tb.finish()
@@ -166,18 +214,94 @@ fn make_single_variant_mutator_(item: proc_macro2::TokenStream) -> proc_macro2::
 
 /* common */
 
+/// Add a `'a: 'static` predicate to the where clause of the generics for each of its lifetime parameters.
+///
+/// Mutators can only be implemented for `'static` types, so a mutator for `S<'a>` is only
+/// available for `S<'static>`.
+fn require_static_lifetimes(generics: &mut Generics) {
+    let lifetimes = generics.lifetimes().map(|lp| lp.lifetime.clone()).collect::<Vec<_>>();
+    if lifetimes.is_empty() {
+        return;
+    }
+    let where_clause = generics.make_where_clause();
+    for lifetime in lifetimes {
+        where_clause
+            .predicates
+            .push(parse2(ts!(q!(lifetime) ": 'static")).unwrap());
+    }
+}
+
 struct MakeMutatorSettings {
     name: Option<proc_macro2::Ident>,
     recursive: bool,
     default: bool,
+    /// The path to a type defined in another crate, whose shape is given by `ty`
+    remote: Option<syn::Path>,
     ty: DeriveInput,
 }
 
+/// Return an error if the declaration given to `make_mutator!` cannot be used with the `remote` setting
+fn check_remote_settings(settings: &MakeMutatorSettings) -> syn::Result<()> {
+    if settings.recursive {
+        return Err(Error::new_spanned(
+            &settings.ty.ident,
+            "The `remote` setting of make_mutator cannot be used for recursive types",
+        ));
+    }
+    let has_data = match &settings.ty.data {
+        syn::Data::Struct(s) => !s.fields.is_empty(),
+        syn::Data::Enum(e) => e.variants.iter().any(|variant| !variant.fields.is_empty()),
+        syn::Data::Union(_) => false,
+    };
+    if !has_data {
+        return Err(Error::new_spanned(
+            &settings.ty.ident,
+            "The `remote` setting of make_mutator can only be used for structs with fields and enums with associated data",
+        ));
+    }
+    Ok(())
+}
+
+/// The declaration of the type, without the attributes that are only meaningful to fuzzcheck’s procedural macros
+fn without_fuzzcheck_attributes(item: &DeriveInput) -> DeriveInput {
+    let is_fuzzcheck_attribute = |attribute: &Attribute| {
+        [
+            "field_mutator",
+            "frozen",
+            "ignore_variant",
+            "opaque",
+            "range",
+            "variant_weight",
+            "weight",
+        ]
+        .iter()
+        .any(|name| attribute.path.is_ident(name))
+    };
+    let strip_fields = |fields: &mut syn::Fields| {
+        for field in fields.iter_mut() {
+            field.attrs.retain(|attribute| !is_fuzzcheck_attribute(attribute));
+        }
+    };
+    let mut item = item.clone();
+    match &mut item.data {
+        syn::Data::Struct(s) => strip_fields(&mut s.fields),
+        syn::Data::Enum(e) => {
+            for variant in e.variants.iter_mut() {
+                variant.attrs.retain(|attribute| !is_fuzzcheck_attribute(attribute));
+                strip_fields(&mut variant.fields);
+            }
+        }
+        syn::Data::Union(_) => {}
+    }
+    item
+}
+
 impl Parse for MakeMutatorSettings {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut name = None;
         let mut recursive = None;
         let mut default = None;
+        let mut remote = None;
 
         while !input.is_empty() {
             let ident = input.call(Ident::parse_any)?;
@@ -196,6 +320,10 @@ impl Parse for MakeMutatorSettings {
                     let value = input.parse::<LitBool>()?;
                     default = Some(value.value);
                 }
+                "remote" => {
+                    let _ = input.parse::<Token![:]>()?;
+                    remote = Some(input.parse::<syn::Path>()?);
+                }
                 "type" => {
                     let _ = input.parse::<Token![:]>()?;
                     let ty = input.parse::<DeriveInput>()?;
@@ -204,6 +332,7 @@ impl Parse for MakeMutatorSettings {
                         name,
                         recursive: recursive.unwrap_or(false),
                         default: default.unwrap_or(true),
+                        remote,
                         ty,
                     });
                 }
@@ -264,6 +393,8 @@ pub(crate) struct Common {
     Box: TokenStream,
     SubValueProvider: TokenStream,
     NeverMutator: TokenStream,
+    ScaledComplexityMutator: TokenStream,
+    OpaqueMutator: TokenStream,
 }
 impl Common {
     #[allow(non_snake_case)]
@@ -329,6 +460,8 @@ impl Common {
             Box: ts!("::std::boxed::Box"),
             NeverMutator: ts!("::fuzzcheck::mutators::never::NeverMutator"),
             SubValueProvider: ts!("fuzzcheck::SubValueProvider"),
+            ScaledComplexityMutator: ts!(mutators "::scaled_complexity::ScaledComplexityMutator"),
+            OpaqueMutator: ts!(mutators "::opaque::OpaqueMutator"),
         }
     }
 }
@@ -353,6 +486,15 @@ impl syn::parse::Parse for FieldMutatorAttribute {
         let _ = parenthesized!(content in input);
         let input = content;
 
+        if input.peek(syn::Ident) && input.peek2(Token![=]) {
+            let ident = input.fork().parse::<Ident>()?;
+            if ident == "grammar" {
+                let _ = input.parse::<Ident>()?;
+                let _ = input.parse::<Token![=]>()?;
+                return Self::parse_grammar(&input);
+            }
+        }
+
         let ty = input.parse::<syn::Type>()?;
         if input.is_empty() {
             return Ok(Self { ty, equal: None });
@@ -382,6 +524,39 @@ impl syn::parse::Parse for FieldMutatorAttribute {
     }
 }
 
+impl FieldMutatorAttribute {
+    /// Parse the right hand side of `#[field_mutator(grammar = ..)]`, which is either a string literal
+    /// containing a regular expression or a block evaluating to an `Rc<Grammar>`
+    fn parse_grammar(input: ParseStream) -> syn::Result<Self> {
+        let grammar = ts!("fuzzcheck::mutators::grammar");
+        let rule = if input.peek(syn::LitStr) {
+            let regex = input.parse::<syn::LitStr>()?;
+            ts!(grammar "::regex(" q!(regex) ")")
+        } else if input.peek(token::Brace) {
+            let block = input.parse::<TokenTree>()?;
+            ts!(block)
+        } else {
+            return Err(syn::Error::new(
+                input.span(),
+                "Expected a string literal containing a regular expression or a block delimited by braces containing an expression of type Rc<Grammar>",
+            ));
+        };
+        if !input.is_empty() {
+            return Err(syn::Error::new(
+                input.span(),
+                "Unexpected tokens after the grammar of field_mutator",
+            ));
+        }
+        Ok(Self {
+            ty: parse2(ts!(grammar "::GrammarBasedStringMutator"))?,
+            equal: Some(ts!(
+                "let grammar: ::std::rc::Rc<" grammar "::Grammar> = " rule ";"
+                grammar "::grammar_based_string_mutator(grammar)"
+            )),
+        })
+    }
+}
+
 fn read_field_default_mutator_attribute(attribute: &Attribute) -> Result<Option<FieldMutatorAttribute>, syn::Error> {
     if let Some(ident) = attribute.path.get_ident() {
         if ident != "field_mutator" {
@@ -393,6 +568,176 @@ fn read_field_default_mutator_attribute(attribute: &Attribute) -> Result<Option<
     }
 }
 
+/// Read the `#[range(..)]` attribute of the field, which uses the `WithinRange` implementation of
+/// its type to create a mutator generating values within the given range
+fn read_field_range_attribute(
+    attribute: &Attribute,
+    field_ty: &syn::Type,
+) -> Result<Option<FieldMutatorAttribute>, syn::Error> {
+    if let Some(ident) = attribute.path.get_ident() {
+        if ident != "range" {
+            return Ok(None);
+        }
+        // syn is not compiled with the "full" feature, which is necessary to parse range expressions
+        let range = attribute.parse_args::<TokenStream>()?;
+        if range.is_empty() {
+            return Err(Error::new_spanned(
+                attribute,
+                "Expected a range expression, such as `0..=10`",
+            ));
+        }
+        let WithinRange = ts!("fuzzcheck::mutators::integer_within_range::WithinRange");
+        Ok(Some(FieldMutatorAttribute {
+            ty: parse2(ts!("<" q!(field_ty) "as" WithinRange ">::Mutator"))?,
+            equal: Some(ts!("<" q!(field_ty) "as" WithinRange ">::within_range_mutator(" range ")")),
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Read the `#[frozen]` or `#[frozen(..)]` attribute of the field, which uses a `UnitMutator` that always
+/// produces the given value, or `Default::default()` if no value is given
+fn read_field_frozen_attribute(
+    attribute: &Attribute,
+    field_ty: &syn::Type,
+) -> Result<Option<FieldMutatorAttribute>, syn::Error> {
+    if let Some(ident) = attribute.path.get_ident() {
+        if ident != "frozen" {
+            return Ok(None);
+        }
+        let value = if attribute.tokens.is_empty() {
+            ts!("<" q!(field_ty) "as ::std::default::Default>::default()")
+        } else {
+            // syn is not compiled with the "full" feature, which is necessary to parse arbitrary expressions
+            let value = attribute.parse_args::<TokenStream>()?;
+            if value.is_empty() {
+                return Err(Error::new_spanned(
+                    attribute,
+                    "Expected an expression giving the value of the frozen field, or no parentheses at all",
+                ));
+            }
+            value
+        };
+        let UnitMutator = ts!("fuzzcheck::mutators::unit::UnitMutator");
+        Ok(Some(FieldMutatorAttribute {
+            ty: parse2(ts!(UnitMutator "<" q!(field_ty) ">"))?,
+            equal: Some(ts!(UnitMutator "::new(" value ", 0.0)")),
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Read the attributes of a field that customise its mutator.
+///
+/// Returns the mutator prescribed by the `#[field_mutator(..)]`, `#[range(..)]`, or `#[frozen]` attribute, if any,
+/// the factor of the `#[weight(..)]` attribute, and whether the field has the `#[opaque]` attribute. Invalid
+/// attributes are reported as compile errors.
+pub(crate) fn read_field_attributes(
+    tb: &mut TokenBuilder,
+    field: &Field,
+) -> (Option<(syn::Type, Option<TokenStream>)>, Option<f64>, bool) {
+    let mut mutator = None;
+    let mut weight = None;
+    let mut opaque = false;
+    for attribute in field.attrs.iter() {
+        if attribute.path.is_ident("opaque") {
+            if !attribute.tokens.is_empty() {
+                tb.stream(
+                    Error::new_spanned(attribute, "The `opaque` attribute does not take any arguments")
+                        .to_compile_error(),
+                );
+            }
+            opaque = true;
+            continue;
+        }
+        let prescribed = read_field_default_mutator_attribute(attribute)
+            .transpose()
+            .or_else(|| read_field_range_attribute(attribute, &field.ty).transpose())
+            .or_else(|| read_field_frozen_attribute(attribute, &field.ty).transpose());
+        match prescribed {
+            Some(Ok(field_mutator_attribute)) => {
+                if mutator.is_some() {
+                    tb.stream(
+                        Error::new_spanned(
+                            attribute,
+                            "A field can only have one `field_mutator`, `range`, or `frozen` attribute",
+                        )
+                        .to_compile_error(),
+                    );
+                }
+                mutator = Some((field_mutator_attribute.ty, field_mutator_attribute.equal));
+            }
+            Some(Err(e)) => {
+                tb.stream(e.to_compile_error());
+            }
+            None => {}
+        }
+        match read_weight_attribute(attribute, "weight", "field") {
+            Ok(Some(w)) => {
+                weight = Some(w);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tb.stream(e.to_compile_error());
+            }
+        }
+    }
+    (mutator, weight, opaque)
+}
+
+/// Read the weight of the variant of an enum given by its `#[variant_weight(..)]` attribute, if any.
+///
+/// Invalid attributes are reported as compile errors.
+pub(crate) fn read_variant_weight_attribute(tb: &mut TokenBuilder, variant: &syn::Variant) -> Option<f64> {
+    let mut weight = None;
+    for attribute in variant.attrs.iter() {
+        match read_weight_attribute(attribute, "variant_weight", "variant") {
+            Ok(Some(w)) => {
+                weight = Some(w);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tb.stream(e.to_compile_error());
+            }
+        }
+    }
+    weight
+}
+
+/// Read the number given to the `#[<name>(..)]` attribute, which must be a strictly positive number.
+///
+/// `what` is the item that is weighted, used in the error messages.
+fn read_weight_attribute(attribute: &Attribute, name: &str, what: &str) -> Result<Option<f64>, syn::Error> {
+    if let Some(ident) = attribute.path.get_ident() {
+        if ident != name {
+            return Ok(None);
+        }
+        let weight = attribute.parse_args_with(|input: ParseStream| {
+            let lit = input.parse::<syn::Lit>()?;
+            match &lit {
+                syn::Lit::Float(f) => f.base10_parse::<f64>(),
+                syn::Lit::Int(i) => i.base10_parse::<f64>(),
+                _ => Err(syn::Error::new(
+                    lit.span(),
+                    format!("Expected a number as the weight of the {what}"),
+                )),
+            }
+        })?;
+        if weight > 0.0 && weight.is_finite() {
+            Ok(Some(weight))
+        } else {
+            Err(syn::Error::new_spanned(
+                attribute,
+                format!("The weight of a {what} must be a strictly positive number"),
+            ))
+        }
+    } else {
+        Ok(None)
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use syn::{parse2, DeriveInput};
@@ -100,10 +100,7 @@ pub fn make_single_variant_mutator(
 
     let impl_mutator_generics = {
         let mut g = Generics::default();
-        for param in generics.type_params() {
-            let tp: TypeParam = param.clone();
-            g.params.push(tp.into());
-        }
+        g.params.extend(generics.params.iter().cloned());
         for variant in enu.variants.iter() {
             // same ident as the single-variant generics
             let mut param: TypeParam = ident!("M" variant.ident).into();
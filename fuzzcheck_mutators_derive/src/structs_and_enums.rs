@@ -1,4 +1,4 @@
-use proc_macro2::{Ident, Punct, Span, TokenStream, TokenTree};
+use proc_macro2::{Ident, Literal, Span, TokenStream, TokenTree};
 use syn::punctuated::Punctuated;
 use syn::token::Where;
 use syn::{parse2, Field, Generics, Visibility, WhereClause};
@@ -14,6 +14,10 @@ pub struct FieldMutator {
     pub j: Option<usize>,
     pub field: Field,
     pub kind: FieldMutatorKind,
+    /// The factor given by the `#[weight(..)]` attribute of the field, if any
+    pub weight: Option<f64>,
+    /// Whether the field has the `#[opaque]` attribute
+    pub opaque: bool,
 }
 
 #[derive(Clone)]
@@ -46,6 +50,87 @@ impl FieldMutator {
             FieldMutatorKind::Ignore => ts!(),
         }
     }
+
+    /// The type of the mutator used for the field within the inner tuple mutator, which is the field
+    /// mutator wrapped in an `OpaqueMutator` if the field is opaque, and in a `ScaledComplexityMutator`
+    /// if the field has a weight.
+    pub(crate) fn wrapped_mutator_stream(&self, cm: &Common) -> TokenStream {
+        let mut mutator = self.mutator_stream(cm);
+        if self.opaque {
+            mutator = ts!(cm.OpaqueMutator "<" mutator ">");
+        }
+        if self.weight.is_some() {
+            mutator = ts!(cm.ScaledComplexityMutator "<" mutator ">");
+        }
+        mutator
+    }
+
+    /// Wrap the given expression, which evaluates to the field mutator, in an `OpaqueMutator` if the
+    /// field is opaque, and in a `ScaledComplexityMutator` if the field has a weight.
+    pub(crate) fn wrapped_mutator_expr(&self, cm: &Common, mut mutator: TokenStream) -> TokenStream {
+        if self.opaque {
+            mutator = ts!(cm.OpaqueMutator "::new(" mutator ")");
+        }
+        if let Some(weight) = self.weight {
+            mutator = ts!(cm.ScaledComplexityMutator "::new(" mutator "," TokenTree::Literal(Literal::f64_suffixed(weight)) ")");
+        }
+        mutator
+    }
+}
+
+/// Wrap the inner mutator of the local shape of a remote type in a `MapMutator` that converts the values
+/// of the shape into values of the remote type, and back.
+///
+/// `to_remote` is an expression converting `shape: &Shape` into a value of the remote type, and `from_remote`
+/// is an expression converting `remote: &Remote` into an `Option<Shape>`. Both can clone the fields of the
+/// values.
+///
+/// Returns the type of the wrapped mutator and the expression initialising it.
+#[allow(non_snake_case)]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn wrap_inner_mutator_for_remote<'a>(
+    cm: &Common,
+    remote: &syn::Path,
+    shape_ident: &Ident,
+    generics: &Generics,
+    field_types: impl Iterator<Item = &'a syn::Type>,
+    InnerMutator: TokenStream,
+    inner_init: TokenStream,
+    to_remote: TokenStream,
+    from_remote: TokenStream,
+) -> (TokenStream, TokenStream) {
+    let mut conversion_generics = generics.clone();
+    let where_clause = conversion_generics.make_where_clause();
+    for ty in field_types {
+        where_clause.predicates.push(parse2(ts!(q!(ty) ":" cm.Clone)).unwrap());
+    }
+    let (impl_generics, ty_generics, where_clause) = conversion_generics.split_for_impl();
+    let Shape = ts!(shape_ident q!(ty_generics));
+    let Remote = ts!(q!(remote) q!(ty_generics));
+
+    let Parse = ts!("fn(&" Remote ") -> " cm.Option "<" Shape ">");
+    let Map = ts!("fn(&" Shape ") -> " Remote);
+    let Cplx = ts!("fn(&" Remote ", f64) -> f64");
+
+    let mutator_type = ts!(
+        cm.mutators "::map::MapMutator<" Shape "," Remote "," InnerMutator "," Parse "," Map "," Cplx ">"
+    );
+    let mutator_init = ts!("{
+        #[coverage(off)]
+        fn to_remote" q!(impl_generics) "(shape: &" Shape ") -> " Remote q!(where_clause) "{"
+            to_remote
+        "}
+        #[coverage(off)]
+        fn from_remote" q!(impl_generics) "(remote: &" Remote ") -> " cm.Option "<" Shape ">" q!(where_clause) "{"
+            from_remote
+        "}
+        #[coverage(off)]
+        fn keep_cplx" q!(impl_generics) "(_remote: &" Remote ", cplx: f64) -> f64" q!(where_clause) "{
+            cplx
+        }"
+        cm.mutators "::map::MapMutator::new(" inner_init ", from_remote as " Parse ", to_remote as " Map ", keep_cplx as " Cplx ")
+    }");
+    (mutator_type, mutator_init)
 }
 
 #[allow(non_snake_case)]
@@ -145,7 +230,7 @@ pub(crate) fn make_mutator_type_and_impl(params: CreateWrapperMutatorParams) ->
                         ts!(tp.ident)
                     },
                     syn::GenericParam::Lifetime(lp) => {
-                        ts!(TokenTree::Punct(Punct::new('\'', proc_macro2::Spacing::Alone)) lp.lifetime.ident)
+                        ts!(q!(lp.lifetime))
                     },
                     syn::GenericParam::Const(cp) => {
                         ts!(cp.ident)
@@ -182,10 +267,17 @@ pub(crate) fn make_mutator_type_and_impl(params: CreateWrapperMutatorParams) ->
     let NameMutator_generics_split = NameMutator_generics.split_for_impl();
     let type_generics_split = type_generics.split_for_impl();
 
+    // the type of the values generated by the mutator
+    let selfty = if let Some(remote) = &settings.remote {
+        ts!(q!(remote) q!(type_generics_split.1))
+    } else {
+        ts!(type_ident q!(type_generics_split.1))
+    };
+
     let helper_type = |helper_type: &str, conformances: bool| {
         let helper_ty_ident = ident!(NameMutator helper_type);
         let InnerType = ts!(
-            "<" InnerMutator " as " cm.fuzzcheck_traits_Mutator "<" type_ident q!(type_generics_split.1) "> >::" helper_type
+            "<" InnerMutator " as " cm.fuzzcheck_traits_Mutator "<" selfty "> >::" helper_type
         );
 
         ts!(
@@ -219,16 +311,51 @@ pub(crate) fn make_mutator_type_and_impl(params: CreateWrapperMutatorParams) ->
         )
     };
 
-    let selfty = ts!(type_ident q!(type_generics_split.1));
-
-    let InnerMutator_as_Mutator =
-        ts!("<" InnerMutator "as" cm.fuzzcheck_traits_Mutator "<" type_ident q!(type_generics_split.1) "> >" );
+    let InnerMutator_as_Mutator = ts!("<" InnerMutator "as" cm.fuzzcheck_traits_Mutator "<" selfty "> >" );
 
     let documentation = format!(
         "A mutator for [`{}`] 
 
 Generated by a procedural macro of [`fuzzcheck`]",
-        type_ident
+        if let Some(remote) = &settings.remote {
+            ts!(q!(remote)).to_string().replace(' ', "")
+        } else {
+            type_ident.to_string()
+        }
+    );
+
+    let default_mutator_expr = ts!(
+        if settings.recursive {
+            format!("{}::new(|self_| {{", cm.RecursiveMutator)
+        } else {
+            "".to_string()
+        }
+        NameMutator "::new("
+            join_ts!(field_mutators.iter().flatten().filter(|variant| {
+                !variant.kind.is_ignore()
+            }), field_mutator,
+                match &field_mutator.kind {
+                    FieldMutatorKind::Generic => {
+                        ts!("<" q!(field_mutator.field.ty) "as" cm.DefaultMutator ">::default_mutator()")
+                    }
+                    FieldMutatorKind::Prescribed(_, Some(init)) => {
+                        ts!("{" init "}")
+                    }
+                    FieldMutatorKind::Prescribed(mutator, None) => {
+                        ts!("<" q!(mutator) "as" cm.Default ">::default()")
+                    }
+                    // do not generate ignored variants
+                    FieldMutatorKind::Ignore => {
+                        unreachable!()
+                    }
+                }
+            , separator: ",")
+        ")"
+        if settings.recursive {
+            "})"
+        } else {
+            ""
+        }
     );
     ts!(
     "#[doc = " q!(documentation) " ]"
@@ -377,7 +504,16 @@ Generated by a procedural macro of [`fuzzcheck`]",
                 " InnerMutator_as_Mutator "::visit_subvalues(&self.mutator, value, &cache.inner, visit);
             }
         }"
-        if settings.default {
+        if settings.default && settings.remote.is_some() {
+            // DefaultMutator cannot be implemented for a type defined in another crate, so
+            // the default mutator is given by the Default implementation of the mutator instead
+            ts!("impl" q!(type_generics_split.0) cm.Default "for" NameMutator q!(DefaultMutator_generic_args) q!(DefaultMutator_Mutator_generics.where_clause) "{
+                #[coverage(off)]
+                fn default() -> Self {"
+                    default_mutator_expr
+                "}
+            }")
+        } else if settings.default {
             ts!("impl" q!(type_generics_split.0) cm.DefaultMutator "for" selfty q!(DefaultMutator_Mutator_generics.where_clause) "{"
             if settings.recursive {
                 ts!("type Mutator = " cm.RecursiveMutator "<" NameMutator q!(DefaultMutator_generic_args) ">;")
@@ -386,38 +522,8 @@ Generated by a procedural macro of [`fuzzcheck`]",
             }
             "#[coverage(off)]
             fn default_mutator() -> Self::Mutator {"
-                if settings.recursive {
-                    format!("{}::new(|self_| {{", cm.RecursiveMutator)
-                } else {
-                    "".to_string()
-                }
-                NameMutator "::new("
-                    join_ts!(field_mutators.iter().flatten().filter(|variant| {
-                        !variant.kind.is_ignore()
-                    }), field_mutator,
-                        match &field_mutator.kind {
-                            FieldMutatorKind::Generic => {
-                                ts!("<" q!(field_mutator.field.ty) "as" cm.DefaultMutator ">::default_mutator()")
-                            }
-                            FieldMutatorKind::Prescribed(_, Some(init)) => {
-                                ts!("{" init "}")
-                            }
-                            FieldMutatorKind::Prescribed(mutator, None) => {
-                                ts!("<" q!(mutator) "as" cm.Default ">::default()")
-                            }
-                            // do not generate ignored variants
-                            FieldMutatorKind::Ignore => {
-                                unreachable!()
-                            }
-                        }
-                    , separator: ",")
-                ")"
-                if settings.recursive {
-                    "})"
-                } else {
-                    ""
-                }
-                "}
+                default_mutator_expr
+            "}
             }")
         } else {
             ts!()
@@ -1,7 +1,10 @@
 use proc_macro2::Ident;
 use syn::{parse2, DataStruct, Generics, Visibility, WhereClause};
 
-use crate::structs_and_enums::{FieldMutator, FieldMutatorKind};
+use crate::structs_and_enums::{
+    make_mutator_type_and_impl, wrap_inner_mutator_for_remote, CreateWrapperMutatorParams, FieldMutator,
+    FieldMutatorKind,
+};
 use crate::token_builder::*;
 use crate::{q, Common, MakeMutatorSettings};
 
@@ -172,24 +175,15 @@ pub(crate) fn impl_default_mutator_for_struct(
         .iter()
         .enumerate()
         .map(|(i, field)| {
-            let mut mutator = None;
-            for attribute in field.attrs.iter() {
-                match super::read_field_default_mutator_attribute(attribute) {
-                    Ok(Some(field_mutator_attribute)) => {
-                        mutator = Some((field_mutator_attribute.ty, field_mutator_attribute.equal));
-                    }
-                    Ok(None) => {}
-                    Err(e) => {
-                        tb.stream(e.to_compile_error());
-                    }
-                }
-            }
+            let (mutator, weight, opaque) = super::read_field_attributes(tb, field);
             if let Some(m) = mutator {
                 FieldMutator {
                     i,
                     j: None,
                     field: field.clone(),
                     kind: FieldMutatorKind::Prescribed(m.0.clone(), m.1),
+                    weight,
+                    opaque,
                 }
             } else {
                 FieldMutator {
@@ -197,6 +191,8 @@ pub(crate) fn impl_default_mutator_for_struct(
                     j: None,
                     field: field.clone(),
                     kind: FieldMutatorKind::Generic,
+                    weight,
+                    opaque,
                 }
             }
         })
@@ -210,14 +206,42 @@ pub(crate) fn impl_default_mutator_for_struct(
         cm.TupleMutatorWrapper "<"
             TupleNMutator "<"
                 join_ts!(field_mutators.iter().flatten(), m,
-                    m.mutator_stream(&cm)
+                    m.wrapped_mutator_stream(&cm)
                 , separator: ",")
             ">,"
             TupleN_and_generics
         ">"
     );
 
-    use crate::structs_and_enums::{make_mutator_type_and_impl, CreateWrapperMutatorParams};
+    let mutator_init = ts!(
+        cm.TupleMutatorWrapper "::new(" TupleNMutator "::new("
+            join_ts!(struc.fields.iter().zip(field_mutators.iter().flatten()).enumerate(), (idx, (field, mutator)),
+                mutator.wrapped_mutator_expr(&cm, ts!(ident!("mutator_" access_field(field, idx))))
+            , separator: ",")
+        "))"
+    );
+
+    let (InnerMutator, mutator_init) = if let Some(remote) = &settings.remote {
+        // convert the values of the local shape into values of the remote type, field by field
+        let fields = |source: &str| {
+            join_ts!(struc.fields.iter().enumerate(), (idx, field),
+                access_field(field, idx) ":" cm.Clone "::clone(&" source "." access_field(field, idx) ")"
+            , separator: ",")
+        };
+        wrap_inner_mutator_for_remote(
+            &cm,
+            remote,
+            struct_ident,
+            generics,
+            struc.fields.iter().map(|field| &field.ty),
+            TupleMutatorWrapper,
+            mutator_init,
+            ts!(q!(remote) "{" fields("shape") "}"),
+            ts!(cm.Some "(" struct_ident "{" fields("remote") "})"),
+        )
+    } else {
+        (TupleMutatorWrapper, mutator_init)
+    };
 
     let params = CreateWrapperMutatorParams {
         cm: &cm,
@@ -225,9 +249,10 @@ pub(crate) fn impl_default_mutator_for_struct(
         type_ident: struct_ident,
         type_generics: generics,
         field_mutators: &field_mutators,
-        InnerMutator: &TupleMutatorWrapper,
+        InnerMutator: &InnerMutator,
         new_impl: &ts!(
             "
+            #[allow(clippy::too_many_arguments)]
             #[coverage(off)]
             pub fn new("
             join_ts!(struc.fields.iter().zip(field_mutators.iter().flatten()).enumerate(), (idx, (field, mutator)),
@@ -235,11 +260,7 @@ pub(crate) fn impl_default_mutator_for_struct(
             , separator: ",")
             ") -> Self {
             Self {
-                mutator : " cm.TupleMutatorWrapper "::new(" TupleNMutator "::new("
-                    join_ts!(struc.fields.iter().enumerate(), (idx, field),
-                        ident!("mutator_" access_field(field, idx))
-                    , separator: ",")
-                    "))
+                mutator : " mutator_init "
             }
             }"
         ),
@@ -257,16 +278,18 @@ fn declare_tuple_mutator(tb: &mut TokenBuilder, nbr_elements: usize) {
     let type_params = ts!(mutator_type_params);
     extend_ts!(tb,
         format!("/// A `TupleMutator` for types that have a {n}-tuple structure", n=nbr_elements)
-        "#[derive(" cm.Default ")]"
         "pub struct" cm.TupleNMutator_ident "<" type_params ">"
         "{"
             join_ts!(0..nbr_elements, i,
                 ident!("mutator_" i) ":" ident!("M" i) ","
             )
             "rng :" cm.fastrand_Rng ",
+            /// The operator of the random mutations of each field, whose weight is adapted to its success
+            operators : " cm.Vec "<" cm.mutators "::operators::OperatorId>,
         }
 
         impl < " type_params " >" cm.TupleNMutator_ident "<" type_params "> {
+            #[allow(clippy::too_many_arguments)]
             #[coverage(off)]
             pub fn new(" join_ts!(0..nbr_elements, i, ident!("mutator_" i) ":" ident!("M" i), separator: ",") ") -> Self {
                 Self {"
@@ -274,10 +297,21 @@ fn declare_tuple_mutator(tb: &mut TokenBuilder, nbr_elements: usize) {
                         ident!("mutator_" i) ","
                     )
                     "rng: <_>::default() ,
+                    operators: vec![" join_ts!(0..nbr_elements, i,
+                        cm.mutators "::operators::register_mutator_operator(" q!(format!("tuple::Field{}", i)) ")"
+                    , separator: ",") "],
                     "
                 "}
             }"
-        "}"
+        "}
+        impl < " type_params " >" cm.Default "for" cm.TupleNMutator_ident "<" type_params ">
+            where" join_ts!(0..nbr_elements, i, ident!("M" i) ":" cm.Default, separator: ",")
+        "{
+            #[coverage(off)]
+            fn default() -> Self {
+                Self::new(" join_ts!(0..nbr_elements, i, "<" ident!("M" i) "as" cm.Default ">::default()", separator: ",") ")
+            }
+        }"
     )
 }
 
@@ -661,7 +695,11 @@ fn impl_mutator_trait(tb: &mut TokenBuilder, nbr_elements: usize) {
         #[coverage(off)]
         fn random_mutate<'__fuzzcheck_derive_lt>(&self, value: " tuple_mut ", cache: &'__fuzzcheck_derive_lt mut Self::Cache, max_cplx: f64, ) -> (Self::UnmutateToken, f64) {
             let current_cplx = " SelfAsTupleMutator "::complexity(self, " TupleNAsRefTypes "::get_ref_from_mut(&value), cache);
-            match cache.vose_alias.sample() {"
+            // the weights are adapted when the field is chosen, since they change during the fuzzing session
+            let idx = " cm.mutators "::operators::choose_operator(&self.rng, &self.operators, &cache.vose_alias.original_probabilities)
+                .unwrap_or_else(#[coverage(off)] || cache.vose_alias.sample());
+            " cm.mutators "::operators::record_operator_use(self.operators[idx]);
+            match idx {"
                 join_ts!(0..nbr_elements, i,
                     i "=> {
                         let old_field_cplx = self." mutator_i(i) ".complexity(value." i ", &cache." ti(i) ");
@@ -707,6 +745,19 @@ fn impl_mutator_trait(tb: &mut TokenBuilder, nbr_elements: usize) {
             )
             "
         }
+        #[doc(hidden)]
+        #[coverage(off)]
+        fn mutation_operators(&self) -> " cm.Vec "<&'static str> {
+            let mut operators = vec![" join_ts!(0..nbr_elements, i, q!(format!("tuple::Field{}", i)), separator: ",") "];"
+            join_ts!(0..nbr_elements, i,
+                "for operator in self." mutator_i(i) ".mutation_operators() {
+                    if !operators.contains(&operator) {
+                        operators.push(operator);
+                    }
+                }"
+            )
+            "operators
+        }
     }"
     )
 }
@@ -31,18 +31,11 @@ fn should_not_crash(xs: &[SampleStruct<u8, SampleEnum>]) {
     }
 }
 
-// fuzz tests reside along your other tests and have the #[test] attribute
+// fuzz tests reside along your other tests
 #[cfg(all(fuzzing, test))]
 mod tests {
-    #[test]
-    fn fuzz() {
-        let result = fuzzcheck::fuzz_test(super::should_not_crash) // the test function to fuzz
-            .default_mutator() // the mutator to generate values of &[SampleStruct<u8, SampleEnum>]
-            .serde_serializer() // save the test cases to the file system using serde
-            .default_sensor_and_pool() // gather observations using the default sensor (i.e. recording code coverage)
-            .arguments_from_cargo_fuzzcheck() // take arguments from the cargo-fuzzcheck command line tool
-            .stop_after_first_test_failure(true) // stop the fuzzer as soon as a test failure is found
-            .launch();
-        assert!(result.found_test_failure);
+    #[fuzzcheck::fuzz_target]
+    fn fuzz(xs: &[super::SampleStruct<u8, super::SampleEnum>]) {
+        super::should_not_crash(xs)
     }
 }
@@ -1,5 +1,6 @@
+# the fuzz test stops once it finds the crash, and cargo fuzzcheck exits with TerminationStatus::TestFailure (2)
 cargo install --path cargo-fuzzcheck --force && \
 cd usage_tests/basic_crate && \
-cargo fuzzcheck tests::fuzz && \
+{ cargo fuzzcheck tests::fuzz --stop-after-first-failure --exit-code-policy exit ; test $? -eq 2 ; } && \
 test  $(cat fuzz/tests::fuzz/artifacts/*.json) = '[{"x":100,"y":{"C":{"x":false,"y":true}}},{"x":55,"y":{"C":{"x":true,"y":false}}},{"x":87,"y":{"C":{"x":false,"y":false}}}]'  && \
 rm -r fuzz